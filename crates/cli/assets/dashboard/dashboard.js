@@ -0,0 +1,152 @@
+// Dashboard of `taskai serve`: a board of the tasks by state and their dependency graph, kept up
+// to date by polling the REST API.
+
+const STATES = ["Todo", "InProgress", "Blocked", "Done"];
+const ACTIONS = {
+  Todo: [["Start", "InProgress"], ["Block", "Blocked"], ["Done", "Done"]],
+  InProgress: [["Done", "Done"], ["Block", "Blocked"], ["Stop", "Todo"]],
+  Blocked: [["Unblock", "Todo"], ["Start", "InProgress"]],
+  Done: [["Reopen", "Todo"]],
+};
+const POLL_MS = 5000;
+
+let version = null;
+let tasks = [];
+let ready = new Set();
+
+const status = document.getElementById("status");
+const search = document.getElementById("search");
+
+// Calls the API, asking for the bearer token once when the server requires one.
+async function api(method, path, body) {
+  for (let attempt = 0; ; attempt++) {
+    const headers = { "Content-Type": "application/json" };
+    const token = localStorage.getItem("taskai-token");
+    if (token) headers["Authorization"] = "Bearer " + token;
+    const response = await fetch(path, {
+      method,
+      headers,
+      body: body === undefined ? undefined : JSON.stringify(body),
+    });
+    if (response.status === 401 && attempt === 0) {
+      const entered = prompt("Token of the taskai server:");
+      if (entered) {
+        localStorage.setItem("taskai-token", entered);
+        continue;
+      }
+    }
+    const isJson = (response.headers.get("Content-Type") || "").includes("json");
+    const data = isJson ? await response.json() : await response.text();
+    if (!response.ok) throw new Error((data && data.error) || response.statusText);
+    return data;
+  }
+}
+
+function showError(err) {
+  status.textContent = err.message;
+  status.className = "error";
+}
+
+async function refresh(force) {
+  try {
+    const list = await api("GET", "/tasks");
+    if (!force && list.version === version) return;
+    const next = await api("GET", "/next");
+    version = list.version;
+    tasks = list.tasks;
+    ready = new Set(next.tasks.map((t) => t.id));
+    renderBoard();
+    if (!document.getElementById("graph").hidden) await renderGraph();
+    status.textContent = "Version " + version;
+    status.className = "";
+  } catch (err) {
+    showError(err);
+  }
+}
+
+function element(tag, className, text) {
+  const el = document.createElement(tag);
+  if (className) el.className = className;
+  if (text !== undefined) el.textContent = text;
+  return el;
+}
+
+function card(task) {
+  const div = element("div", "card");
+  div.dataset.id = task.id;
+  if (ready.has(task.id)) div.classList.add("ready");
+  div.append(element("span", "id", task.id), element("span", "title", task.title));
+
+  const meta = [];
+  if (task.epic) meta.push(task.epic);
+  if (task.assignee) meta.push("@" + task.assignee);
+  if (task.priority !== undefined && task.priority !== null) meta.push("P" + task.priority);
+  if (task.depends && task.depends.length) meta.push("after " + task.depends.join(", "));
+  if (meta.length) div.append(element("div", "meta", meta.join(" · ")));
+
+  const actions = element("div", "actions");
+  for (const [label, state] of ACTIONS[task.state] || []) {
+    const button = element("button", "", label);
+    button.addEventListener("click", () => setState(task.id, state));
+    actions.append(button);
+  }
+  div.append(actions);
+  return div;
+}
+
+function renderBoard() {
+  const text = search.value.toLowerCase();
+  for (const state of STATES) {
+    const column = document.querySelector(`.column[data-state="${state}"]`);
+    const cards = column.querySelector(".cards");
+    const matching = tasks.filter(
+      (t) =>
+        t.state === state &&
+        (text === "" || `${t.id} ${t.title} ${t.assignee || ""}`.toLowerCase().includes(text))
+    );
+    cards.replaceChildren(...matching.map(card));
+    column.querySelector(".count").textContent = matching.length;
+  }
+}
+
+async function renderGraph() {
+  const graph = document.getElementById("graph");
+  graph.innerHTML = await api("GET", "/graph.svg");
+  for (const node of graph.querySelectorAll(".node")) {
+    node.addEventListener("click", () => showCard(node.dataset.id));
+  }
+}
+
+// Switches to the board and points out the card of a task.
+function showCard(id) {
+  select("board");
+  const card = document.querySelector(`.card[data-id="${CSS.escape(id)}"]`);
+  if (!card) return;
+  card.scrollIntoView({ block: "center" });
+  card.classList.add("highlight");
+  setTimeout(() => card.classList.remove("highlight"), 2000);
+}
+
+async function setState(id, state) {
+  try {
+    await api("PATCH", `/tasks/${encodeURIComponent(id)}/state`, { state });
+    await refresh(true);
+  } catch (err) {
+    showError(err);
+  }
+}
+
+function select(view) {
+  document.getElementById("board").hidden = view !== "board";
+  document.getElementById("graph").hidden = view !== "graph";
+  document.getElementById("show-board").classList.toggle("selected", view === "board");
+  document.getElementById("show-graph").classList.toggle("selected", view === "graph");
+  if (view === "graph") renderGraph().catch(showError);
+}
+
+document.getElementById("show-board").addEventListener("click", () => select("board"));
+document.getElementById("show-graph").addEventListener("click", () => select("graph"));
+search.addEventListener("input", renderBoard);
+
+refresh(true);
+setInterval(() => refresh(false), POLL_MS);
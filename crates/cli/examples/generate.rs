@@ -0,0 +1,26 @@
+//! Generates a backlog from a specification given on the command line and prints it as YAML.
+//!
+//! The program is not async: `generate_blocking` runs the call to the LLM on a runtime of its own.
+//!
+//! ```sh
+//! OPENAI_API_KEY=... cargo run --example generate -- "A CLI weather app with caching"
+//! ```
+
+use taskai::generate::BacklogGenerator;
+
+fn main() {
+    let spec = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    if spec.is_empty() {
+        eprintln!("Usage: generate <specification>");
+        std::process::exit(1);
+    }
+
+    let generator = BacklogGenerator::new();
+    match generator.generate_blocking(&spec) {
+        Ok(backlog) => print!("{}", taskai::fmt::format_backlog(&backlog).unwrap()),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
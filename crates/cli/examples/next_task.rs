@@ -0,0 +1,37 @@
+//! Prints the tasks of a backlog that are ready to be worked on, and the one to start with.
+//!
+//! ```sh
+//! cargo run --example next_task -- tasks.yml
+//! ```
+
+use std::process;
+use taskai::Backlog;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: next_task <backlog.yml>");
+        process::exit(1);
+    };
+    let yaml = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Error reading {}: {}", path, err);
+        process::exit(1);
+    });
+
+    let report = taskai::lint::validate_source(&yaml, false);
+    if !report.valid {
+        for diagnostic in &report.errors {
+            eprintln!("{}", diagnostic.message);
+        }
+        process::exit(1);
+    }
+    let backlog: Backlog = serde_yaml::from_str(&yaml).expect("validated above");
+
+    let ready = taskai::ready::get_ready_tasks(&backlog);
+    for task in &ready {
+        println!("{}: {}", task.id, task.title);
+    }
+    match taskai::ready::best_ready_task(&backlog, &ready) {
+        Some(task) => println!("Start with {}.", task.id),
+        None => println!("No task is ready."),
+    }
+}
@@ -0,0 +1,142 @@
+use clap::Command;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+/// Expands a user-defined alias on the command line: when the command name, after the global
+/// options, is a key of `aliases`, it is replaced by the words of its alias.
+///
+/// Built-in commands always take precedence over aliases of the same name, and aliases are not
+/// expanded recursively. Returns an error if the alias is empty or has an unclosed quote.
+pub fn expand(
+    command: &Command,
+    mut args: Vec<OsString>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<OsString>, String> {
+    let Some(index) = command_index(command, &args) else {
+        return Ok(args);
+    };
+    let Some(name) = args[index].to_str() else {
+        return Ok(args);
+    };
+    if command.find_subcommand(name).is_some() {
+        return Ok(args);
+    }
+    let Some(line) = aliases.get(name) else {
+        return Ok(args);
+    };
+
+    let words = split_words(line).map_err(|err| format!("Invalid alias '{}': {}", name, err))?;
+    if words.is_empty() {
+        return Err(format!("Alias '{}' is empty.", name));
+    }
+    tracing::debug!(alias = name, expansion = line.as_str(), "expanding alias");
+    args.splice(index..=index, words.into_iter().map(OsString::from));
+    Ok(args)
+}
+
+/// Returns the index of the command name in the arguments, skipping the program name and the global
+/// options given before it, along with their values.
+fn command_index(command: &Command, args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while index < args.len() {
+        let arg = args[index].to_str()?;
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some(index);
+        }
+        index += if takes_value(command, arg) { 2 } else { 1 };
+    }
+    None
+}
+
+/// Returns true if the option is given its value in the next argument (`--format json`, `-b
+/// tasks.yml`), rather than attached to it (`--format=json`) or not at all (`--quiet`, `-vv`).
+fn takes_value(command: &Command, option: &str) -> bool {
+    let argument = match option.strip_prefix("--") {
+        Some(long) if !long.contains('=') => {
+            command.get_arguments().find(|a| a.get_long() == Some(long))
+        }
+        Some(_) => None,
+        None => {
+            let mut shorts = option.chars().skip(1);
+            match (shorts.next(), shorts.next()) {
+                (Some(short), None) => command
+                    .get_arguments()
+                    .find(|a| a.get_short() == Some(short)),
+                _ => None,
+            }
+        }
+    };
+    argument.is_some_and(|a| a.get_action().takes_values())
+}
+
+/// Splits an alias into words at whitespace. Single or double quotes keep whitespace within a
+/// word.
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("unclosed quote".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+
+    /// Tests that aliases expand after global options, and never shadow built-in commands.
+    #[test]
+    fn test_expand() {
+        let aliases = BTreeMap::from([
+            ("n".to_string(), "next --limit 1".to_string()),
+            ("d".to_string(), "mark-done --task".to_string()),
+            ("wip".to_string(), "search 'in progress'".to_string()),
+            ("next".to_string(), "status".to_string()),
+        ]);
+        let command = Cli::command();
+        let expand = |line: &str| {
+            let args = line.split(' ').map(OsString::from).collect();
+            expand(&command, args, &aliases)
+                .unwrap()
+                .into_iter()
+                .map(|arg| arg.into_string().unwrap())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        assert_eq!(expand("taskai n"), "taskai next --limit 1");
+        assert_eq!(
+            expand("taskai --format json -b n.yml d T-1"),
+            "taskai --format json -b n.yml mark-done --task T-1"
+        );
+        assert_eq!(expand("taskai -v wip"), "taskai -v search in progress");
+        assert_eq!(expand("taskai next"), "taskai next");
+        assert_eq!(expand("taskai status n"), "taskai status n");
+
+        assert_eq!(
+            split_words("search \"a b\" 'c'").unwrap(),
+            vec!["search", "a b", "c"]
+        );
+        assert!(split_words("search \"a b").is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::store;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the project root, where previous versions of backlog files are kept.
+pub const BACKUP_DIR: &str = ".taskai/backups";
+
+/// Number of backups kept per backlog file; older ones are removed.
+const MAX_BACKUPS: usize = 50;
+
+/// Timestamp format of backup file names, sorting in chronological order.
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%6fZ";
+
+/// File of the backup directory recording who made each change, and with which command.
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// A previous version of a backlog file.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the version was replaced by a change.
+    pub taken_at: DateTime<Utc>,
+    /// User who made the change, if recorded.
+    pub user: Option<String>,
+    /// Command line of the change, if recorded.
+    pub command: Option<String>,
+}
+
+/// Line of the journal, describing the change that replaced a backed up version.
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    /// File name of the backup.
+    backup: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    command: String,
+}
+
+/// Returns the directory holding the backups of the backlog file.
+///
+/// Backlogs stored in `.taskai/` keep their backups right next to them, in `.taskai/backups/`.
+pub fn backup_dir(backlog_file: &Path) -> PathBuf {
+    let root = store::project_root(backlog_file);
+    if root.file_name().is_some_and(|name| name == ".taskai") {
+        root.join("backups")
+    } else {
+        root.join(BACKUP_DIR)
+    }
+}
+
+/// Saves the given content as the latest backup of the backlog file and records the change in the
+/// journal, then removes the oldest backups beyond `MAX_BACKUPS`.
+pub fn snapshot(backlog_file: &Path, content: &str) -> Result<Backup, String> {
+    let dir = backup_dir(backlog_file);
+    fs::create_dir_all(&dir).map_err(|err| format!("Error creating backup directory: {}", err))?;
+
+    // Saves in quick succession must not overwrite each other's backup
+    let mut taken_at = Utc::now();
+    let mut path = backup_path(&dir, backlog_file, taken_at);
+    while path.exists() {
+        taken_at += chrono::Duration::microseconds(1);
+        path = backup_path(&dir, backlog_file, taken_at);
+    }
+    fs::write(&path, content).map_err(|err| format!("Error writing backup: {}", err))?;
+    tracing::debug!(path = %path.display(), "backed up backlog");
+
+    let entry = JournalEntry {
+        backup: path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        user: current_user(),
+        command: current_command(),
+    };
+    append_journal(&dir, &entry)?;
+
+    let backups = list(backlog_file);
+    if backups.len() > MAX_BACKUPS {
+        remove_backups(&backups[MAX_BACKUPS..]);
+    }
+
+    Ok(Backup {
+        path,
+        taken_at,
+        user: entry.user,
+        command: Some(entry.command),
+    })
+}
+
+/// Returns the backups of the backlog file, most recent first.
+pub fn list(backlog_file: &Path) -> Vec<Backup> {
+    let dir = backup_dir(backlog_file);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", file_name(backlog_file));
+    let mut journal = read_journal(&dir);
+
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stamp = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(&prefix)?
+                .strip_suffix(".yaml")?;
+            let taken_at = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+                .ok()?
+                .and_utc();
+            let entry = journal.remove(path.file_name()?.to_str()?);
+            Some(Backup {
+                taken_at,
+                user: entry.as_ref().and_then(|e| e.user.clone()),
+                command: entry.map(|e| e.command),
+                path,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| Reverse(backup.taken_at));
+    backups
+}
+
+/// Restores the backlog file to its version from `steps` changes ago, and drops the backups of the
+/// undone changes.
+///
+/// Returns the backup that was restored.
+pub fn restore(backlog_file: &Path, steps: usize) -> Result<Backup, String> {
+    let backups = list(backlog_file);
+    if steps == 0 || steps > backups.len() {
+        return Err(format!(
+            "Cannot undo {} change(s): {} backup(s) of {} available.",
+            steps,
+            backups.len(),
+            backlog_file.display()
+        ));
+    }
+
+    let backup = backups[steps - 1].clone();
+    let content =
+        fs::read_to_string(&backup.path).map_err(|err| format!("Error reading backup: {}", err))?;
+    if store::is_backlog_dir(backlog_file) {
+        let backlog = serde_yaml::from_str(&content)
+            .map_err(|err| format!("Error parsing backup: {}", err))?;
+        store::write_dir(backlog_file, &backlog)?;
+    } else {
+        store::write_atomic(backlog_file, &content)
+            .map_err(|err| format!("Error writing to backlog file: {}", err))?;
+    }
+    tracing::info!(path = %backlog_file.display(), backup = %backup.path.display(), "restored backup");
+
+    remove_backups(&backups[..steps]);
+    Ok(backup)
+}
+
+/// Removes the given backups, and their entries from the journal.
+fn remove_backups(backups: &[Backup]) {
+    for backup in backups {
+        if let Err(err) = fs::remove_file(&backup.path) {
+            tracing::warn!(path = %backup.path.display(), error = %err, "could not remove backup");
+        }
+    }
+
+    let Some(dir) = backups.first().and_then(|b| b.path.parent()) else {
+        return;
+    };
+    let mut entries: Vec<JournalEntry> = read_journal(dir).into_values().collect();
+    entries.retain(|entry| dir.join(&entry.backup).exists());
+    entries.sort_by(|a, b| a.backup.cmp(&b.backup));
+    let lines: String = entries
+        .iter()
+        .filter_map(|entry| serde_json::to_string(entry).ok())
+        .map(|line| line + "\n")
+        .collect();
+    // An interrupted rewrite must never lose the whole journal
+    if let Err(err) = store::write_atomic(&dir.join(JOURNAL_FILE), &lines) {
+        tracing::warn!(error = %err, "could not rewrite the backup journal");
+    }
+}
+
+/// Reads the journal of the backup directory, by backup file name.
+fn read_journal(dir: &Path) -> HashMap<String, JournalEntry> {
+    let Ok(content) = fs::read_to_string(dir.join(JOURNAL_FILE)) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<JournalEntry>(line).ok())
+        .map(|entry| (entry.backup.clone(), entry))
+        .collect()
+}
+
+/// Appends an entry to the journal of the backup directory.
+fn append_journal(dir: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry)
+        .map_err(|err| format!("Error serializing journal entry: {}", err))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(JOURNAL_FILE))
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|err| format!("Error writing backup journal: {}", err))
+}
+
+/// Returns the name of the user running the command.
+pub fn current_user() -> Option<String> {
+    ["USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|user| !user.is_empty()))
+}
+
+/// Returns the command line being run, as recorded in the journal.
+fn current_command() -> String {
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .map(|arg| {
+            if arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg
+            }
+        })
+        .collect();
+    format!("taskai {}", args.join(" "))
+}
+
+/// Returns the path of the backup of the backlog file taken at the given time.
+fn backup_path(dir: &Path, backlog_file: &Path, taken_at: DateTime<Utc>) -> PathBuf {
+    dir.join(format!(
+        "{}.{}.yaml",
+        file_name(backlog_file),
+        taken_at.format(STAMP_FORMAT)
+    ))
+}
+
+/// Returns the file name of the backlog, which prefixes the names of its backups.
+fn file_name(backlog_file: &Path) -> String {
+    backlog_file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "backlog".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::Backlog;
+
+    /// Tests that every change is backed up and can be undone several steps back.
+    #[test]
+    fn test_snapshot_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.yml");
+        let mut backlog: Backlog = serde_yaml::from_str("project: v1\n").unwrap();
+        store::save(&path, &backlog);
+        assert!(list(&path).is_empty());
+
+        for version in ["v2", "v3", "v4"] {
+            backlog.project = version.to_string();
+            store::save(&path, &backlog);
+        }
+        // Saving an unchanged backlog takes no backup
+        store::save(&path, &backlog);
+        let backups = list(&path);
+        assert_eq!(backups.len(), 3);
+        assert!(backups[0].path.starts_with(dir.path().join(BACKUP_DIR)));
+
+        restore(&path, 2).unwrap();
+        assert_eq!(store::load(&path).project, "v2");
+        assert_eq!(list(&path).len(), 1);
+
+        assert!(restore(&path, 2).is_err());
+        restore(&path, 1).unwrap();
+        assert_eq!(store::load(&path).project, "v1");
+        assert!(list(&path).is_empty());
+
+        let nested = dir.path().join(".taskai").join("backlog.yaml");
+        assert_eq!(backup_dir(&nested), dir.path().join(".taskai/backups"));
+    }
+}
@@ -0,0 +1,60 @@
+//! `cargo taskai`: runs taskai as a Cargo subcommand.
+//!
+//! Cargo runs `cargo-taskai taskai <args>` for `cargo taskai <args>`. The arguments are passed on to
+//! the `taskai` binary, with the backlog and `.taskai.toml` looked for from the root of the Cargo
+//! workspace, so that commands work the same from any member crate or subdirectory.
+
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+fn main() {
+    let mut args: Vec<OsString> = env::args_os().skip(1).collect();
+    // Cargo passes the name of the subcommand first; it is missing when run directly
+    if args.first().is_some_and(|arg| arg == "taskai") {
+        args.remove(0);
+    }
+
+    let mut command = Command::new(taskai_path());
+    command.args(&args);
+    if env::var_os("TASKAI_ROOT").is_none() {
+        if let Some(root) = workspace_root() {
+            command.env("TASKAI_ROOT", root);
+        }
+    }
+
+    match command.status() {
+        Ok(status) => process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            eprintln!("Error running taskai: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Returns the `taskai` binary installed next to this one, or else the one found on the `PATH`.
+fn taskai_path() -> PathBuf {
+    let name = format!("taskai{}", env::consts::EXE_SUFFIX);
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(&name)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// Returns the root directory of the Cargo workspace containing the current directory, if any.
+fn workspace_root() -> Option<PathBuf> {
+    // Cargo tells its subcommands which cargo binary runs them
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let output = Command::new(cargo)
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let manifest = String::from_utf8(output.stdout).ok()?;
+    Path::new(manifest.trim()).parent().map(Path::to_path_buf)
+}
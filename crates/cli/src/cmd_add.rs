@@ -0,0 +1,170 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, TaskUpdate};
+use crate::store;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use taskai_schema::{Backlog, DeliverableSpec, Task, TaskState};
+
+/// Options describing the task to add.
+pub struct NewTask {
+    /// Title of the task.
+    pub title: String,
+    /// Explicit ID for the task; minted from the backlog numbering when absent.
+    pub id: Option<String>,
+    /// Epic to add the task to; the task is standalone when absent.
+    pub epic: Option<String>,
+    /// IDs of the tasks the new task depends on.
+    pub depends: Vec<String>,
+    /// Deliverable paths of the task.
+    pub deliverables: Vec<String>,
+    /// Optional description of the task.
+    pub description: Option<String>,
+}
+
+/// Executes the "add" command: appends a new Todo task to the backlog file.
+///
+/// The task ID is minted from the numbering already used in the backlog (or in the target epic) unless
+/// one is given explicitly. Dependencies must refer to existing tasks, and the resulting backlog is
+/// validated before being written back.
+pub fn execute(backlog_file: &Path, new_task: NewTask, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let id = match insert_task(&mut backlog, new_task, chrono::Utc::now()) {
+        Ok(id) => id,
+        Err((kind, message)) => error::fail(kind, message),
+    };
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &TaskUpdate {
+            id: &id,
+            state: &TaskState::Todo,
+            message: format!("Task {} added.", id),
+        },
+    );
+}
+
+/// Adds a new Todo task to the backlog as the "add" command does, returning its ID, or the kind of
+/// failure and its message. The backlog is left unchanged on failure.
+pub fn insert_task(
+    backlog: &mut Backlog,
+    new_task: NewTask,
+    now: DateTime<Utc>,
+) -> Result<String, (ErrorKind, String)> {
+    if let Some(epic_id) = &new_task.epic {
+        if !backlog.epics.iter().any(|e| &e.id == epic_id) {
+            return Err((
+                ErrorKind::NotFound,
+                format!("Epic with ID '{}' not found in the backlog.", epic_id),
+            ));
+        }
+    }
+
+    let id = match new_task.id {
+        Some(id) => {
+            if backlog.has_task(&id) {
+                return Err((
+                    ErrorKind::Validation,
+                    format!("A task with ID '{}' already exists.", id),
+                ));
+            }
+            id
+        }
+        None => taskai_core::next_task_id(backlog, new_task.epic.as_deref()),
+    };
+
+    for dep_id in &new_task.depends {
+        if !backlog.has_task(dep_id) {
+            return Err((
+                ErrorKind::NotFound,
+                format!("Dependency '{}' not found in the backlog.", dep_id),
+            ));
+        }
+    }
+
+    let deliverable = match new_task.deliverables.len() {
+        0 => None,
+        1 => Some(DeliverableSpec::Single(new_task.deliverables[0].clone())),
+        _ => Some(DeliverableSpec::Multiple(new_task.deliverables)),
+    };
+
+    let task = Task {
+        id: id.clone(),
+        title: new_task.title,
+        depends: new_task.depends,
+        state: TaskState::Todo,
+        description: new_task.description,
+        deliverable,
+        created_at: Some(now),
+        updated_at: Some(now),
+        ..Default::default()
+    };
+
+    let mut updated = backlog.clone();
+    match &new_task.epic {
+        Some(epic_id) => {
+            if let Some(epic) = updated.epics.iter_mut().find(|e| &e.id == epic_id) {
+                epic.tasks.push(task);
+            }
+        }
+        None => updated.tasks.push(task),
+    }
+
+    if let Err(err) = updated.validate() {
+        return Err((
+            ErrorKind::Validation,
+            format!("Invalid backlog after adding task: {}", err),
+        ));
+    }
+    *backlog = updated;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a task is added to an epic with a minted ID.
+    #[test]
+    fn test_add_to_epic() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+epics:
+  - id: E-1
+    title: Epic
+    tasks:
+      - id: API-1
+        title: First
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(
+            file.path(),
+            NewTask {
+                title: "Second".to_string(),
+                id: None,
+                epic: Some("E-1".to_string()),
+                depends: vec!["API-1".to_string()],
+                deliverables: vec!["src/api.rs".to_string()],
+                description: None,
+            },
+            Format::Table,
+        );
+
+        let content = fs::read_to_string(file.path()).unwrap();
+        let backlog: Backlog = serde_yaml::from_str(&content).unwrap();
+        let task = &backlog.epics[0].tasks[1];
+
+        assert_eq!(task.id, "API-2");
+        assert_eq!(task.depends, vec!["API-1"]);
+        assert!(task.created_at.is_some());
+    }
+}
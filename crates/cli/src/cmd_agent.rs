@@ -0,0 +1,322 @@
+use crate::aliases;
+use crate::cmd_next;
+use crate::error::{self, ErrorKind};
+use crate::notifications;
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use taskai_core::{NotifyEvent, ReadyFilter};
+use taskai_schema::{Backlog, Blocker, Task, TaskState};
+
+/// Options of the agent loop.
+pub struct AgentOptions {
+    /// Command run for each task; defaults to `agent.command` of the configuration.
+    pub command: Option<String>,
+    /// Criteria the tasks handed to the agent must match.
+    pub filter: ReadyFilter,
+    /// Stop after this many tasks.
+    pub max_tasks: Option<usize>,
+    /// Print the prompt and the command of the next task instead of running it.
+    pub dry_run: bool,
+    /// Run the `done_when` criteria marked as commands (`$ cmd`) when checking the agent's work.
+    pub run_checks: bool,
+}
+
+/// The tasks completed by the agent, and the one it failed on, if any.
+#[derive(Serialize, Default)]
+struct AgentRun {
+    completed: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed: Option<AgentFailure>,
+}
+
+#[derive(Serialize)]
+struct AgentFailure {
+    task_id: String,
+    reason: String,
+}
+
+/// The prompt and the command the next task would be handed to the agent with.
+#[derive(Serialize)]
+struct AgentPlan {
+    task_id: String,
+    command: Vec<String>,
+    prompt: String,
+}
+
+/// Executes the "agent" command: repeatedly hands the best ready task to an external coding agent,
+/// checks its work and marks the task as done, until no task is ready.
+///
+/// Each task is claimed as InProgress, then the command is run in the project root with the task
+/// rendered as a prompt, both in place of the `{prompt}` placeholder and on the command's stdin.
+/// Once the command succeeds, the deliverables and the `done_when` criteria of the task are checked
+/// and the task is marked as done. When the command fails or a check fails, the task is blocked
+/// with the reason and the loop stops with an error, leaving the work for a person to review.
+pub async fn execute(backlog_file: &Path, options: AgentOptions, format: Format) {
+    let config = store::config(backlog_file).agent;
+    let Some(template) = options.command.or(config.command) else {
+        error::fail(
+            ErrorKind::Usage,
+            "No agent command given; pass --command or set agent.command in .taskai.toml.",
+        );
+    };
+    let root = store::project_root(backlog_file);
+
+    let mut run = AgentRun::default();
+    while options
+        .max_tasks
+        .is_none_or(|max| run.completed.len() < max)
+    {
+        let lock = store::lock(backlog_file);
+        let mut backlog = store::load(backlog_file);
+        let ready = taskai_core::filter_ready_tasks(&backlog, &options.filter);
+        let Some(task) = taskai_core::best_ready_task(&backlog, &ready) else {
+            break;
+        };
+        let task_id = task.id.clone();
+        let prompt = taskai_core::agent_prompt(&backlog, task, config.instructions.as_deref());
+        let command =
+            command(&template, backlog_file, &backlog, task, &prompt).unwrap_or_else(|err| {
+                error::fail(ErrorKind::Usage, format!("Invalid agent command: {}", err))
+            });
+        if options.dry_run {
+            let plan = AgentPlan {
+                task_id,
+                command,
+                prompt,
+            };
+            output::print(format, &plan);
+            return;
+        }
+
+        let now = chrono::Utc::now();
+        if let Some(task) = backlog.find_task_mut(&task_id) {
+            task.set_state(TaskState::InProgress, now);
+        }
+        store::save(backlog_file, &backlog);
+        // The agent may well update the backlog itself
+        drop(lock);
+
+        eprintln!("==> {}: handing the task to the agent", task_id);
+        let outcome = match run_agent(&command, backlog_file, &root, &task_id, &prompt) {
+            Ok(()) => check(backlog_file, &root, &task_id, options.run_checks),
+            Err(err) => Err(err),
+        };
+        match outcome {
+            Ok(note) => {
+                let backlog = finish(backlog_file, &task_id, &note);
+                let ids = [task_id.clone()];
+                notifications::tasks(backlog_file, &backlog, NotifyEvent::Done, &ids).await;
+                eprintln!("==> {}: done", task_id);
+                run.completed.push(task_id);
+            }
+            Err(reason) => {
+                let backlog = block(backlog_file, &task_id, &reason);
+                let ids = [task_id.clone()];
+                notifications::tasks(backlog_file, &backlog, NotifyEvent::Blocked, &ids).await;
+                run.failed = Some(AgentFailure { task_id, reason });
+                break;
+            }
+        }
+    }
+
+    output::print(format, &run);
+    if run.failed.is_some() {
+        error::exit(ErrorKind::Other);
+    }
+}
+
+/// Splits the command template into words and fills in their placeholders, `{prompt}` included.
+fn command(
+    template: &str,
+    backlog_file: &Path,
+    backlog: &Backlog,
+    task: &Task,
+    prompt: &str,
+) -> Result<Vec<String>, String> {
+    let words = aliases::split_words(template)?;
+    if words.is_empty() {
+        return Err("the command is empty".to_string());
+    }
+    words
+        .iter()
+        .map(|word| {
+            // The prompt goes in after the other placeholders, so braces within it are kept as is
+            word.split("{prompt}")
+                .map(|part| cmd_next::fill(part, backlog_file, backlog, task))
+                .collect::<Result<Vec<String>, String>>()
+                .map(|parts| parts.join(prompt))
+        })
+        .collect()
+}
+
+/// Runs the agent command on a task, returning why it failed if it did.
+fn run_agent(
+    command: &[String],
+    backlog_file: &Path,
+    root: &Path,
+    task_id: &str,
+    prompt: &str,
+) -> Result<(), String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err("The agent command is empty.".to_string());
+    };
+    tracing::info!(task = task_id, ?command, "running agent");
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(root)
+        .env("TASKAI_BACKLOG", backlog_file)
+        .env("TASKAI_TASK_ID", task_id)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Error running {}: {}", program, err))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Agents taking the prompt as an argument may not read their input at all
+        let _ = stdin.write_all(prompt.as_bytes());
+    }
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("The agent command failed ({}).", status)),
+        Err(err) => Err(format!("Error waiting for {}: {}", program, err)),
+    }
+}
+
+/// Checks the deliverables and the `done_when` criteria of the task the agent worked on, returning
+/// the note to leave on the task, or what is missing.
+fn check(
+    backlog_file: &Path,
+    root: &Path,
+    task_id: &str,
+    run_commands: bool,
+) -> Result<String, String> {
+    let backlog = store::load(backlog_file);
+    let Some(task) = backlog.find_task(task_id) else {
+        return Err(format!("Task {} is no longer in the backlog.", task_id));
+    };
+
+    let missing = taskai_core::verify_task(task, root).missing().join(", ");
+    if !missing.is_empty() {
+        return Err(format!("Missing deliverable(s): {}", missing));
+    }
+    let checks = taskai_core::run_checks(task, root, run_commands, &mut HashMap::new());
+    let failed: Vec<&str> = checks
+        .criteria
+        .iter()
+        .filter(|c| c.status == taskai_core::CheckStatus::Failed)
+        .map(|c| c.criterion.as_str())
+        .collect();
+    if !failed.is_empty() {
+        return Err(format!("Failed done_when check(s): {}", failed.join("; ")));
+    }
+    if !checks.criteria.is_empty() && !checks.passed() {
+        Ok("Completed by the agent; review the manual done_when criteria.".to_string())
+    } else {
+        Ok("Completed by the agent; all checks passed.".to_string())
+    }
+}
+
+/// Marks the task as done with the note, unless the agent already did, returning the backlog.
+fn finish(backlog_file: &Path, task_id: &str, note: &str) -> Backlog {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+    if let Some(task) = backlog.find_task_mut(task_id) {
+        if !matches!(task.state, TaskState::Done) {
+            task.set_state(TaskState::Done, now);
+        }
+        task.add_note(note, now);
+    }
+    store::save(backlog_file, &backlog);
+    backlog
+}
+
+/// Blocks the task with the reason the agent failed on, returning the backlog.
+fn block(backlog_file: &Path, task_id: &str, reason: &str) -> Backlog {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+    if let Some(task) = backlog.find_task_mut(task_id) {
+        task.set_state(TaskState::Blocked, now);
+        task.blocker = Some(Blocker {
+            reason: reason.to_string(),
+            link: None,
+        });
+        task.add_note(&format!("Blocked: {}", reason), now);
+    }
+    store::save(backlog_file, &backlog);
+    backlog
+}
+
+impl Render for AgentRun {
+    fn print_text(&self) {
+        match self.completed.len() {
+            0 if self.failed.is_none() => println!("No tasks are ready to work on."),
+            count => println!(
+                "The agent completed {} task(s): {}",
+                count,
+                self.completed.join(", ")
+            ),
+        }
+        if let Some(failure) = &self.failed {
+            println!("Task {} blocked: {}", failure.task_id, failure.reason);
+        }
+    }
+}
+
+impl Render for AgentPlan {
+    fn print_text(&self) {
+        println!("Next task: {}", self.task_id);
+        println!("Command: {}", self.command.join(" "));
+        println!();
+        print!("{}", self.prompt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that the loop works through the ready tasks, checking each one before marking it done.
+    #[tokio::test]
+    async fn test_agent_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            r#"
+project: test
+tasks:
+  - id: T-1
+    title: Write the notes
+    deliverable: notes.txt
+  - id: T-2
+    title: Check the notes
+    depends: [T-1]
+    done_when:
+      - "$ test -f notes.txt"
+"#,
+        )
+        .unwrap();
+
+        let options = AgentOptions {
+            command: Some("touch notes.txt".to_string()),
+            filter: ReadyFilter::default(),
+            max_tasks: None,
+            dry_run: false,
+            run_checks: true,
+        };
+        execute(&backlog_file, options, Format::Json).await;
+
+        let backlog = store::load(&backlog_file);
+        for task in backlog.all_tasks() {
+            assert!(matches!(task.state, TaskState::Done), "{}", task.id);
+        }
+        let note = &backlog.tasks[1].notes[0];
+        assert_eq!(note.text, "Completed by the agent; all checks passed.");
+    }
+}
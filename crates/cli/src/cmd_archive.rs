@@ -0,0 +1,193 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::Backlog;
+
+/// Where archived tasks go and how old they must be.
+pub struct ArchiveOptions<'a> {
+    /// Only archive tasks completed more than this many days ago.
+    pub older_than: Option<i64>,
+    /// Backlog file receiving the archived tasks, instead of the archive section of the backlog.
+    pub to: Option<&'a Path>,
+    /// Report what would be archived without writing anything.
+    pub dry_run: bool,
+}
+
+/// A task moved to the archive.
+#[derive(Serialize)]
+struct ArchivedTask {
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// The tasks archived by the command and where they went.
+#[derive(Serialize)]
+struct ArchiveReport<'a> {
+    destination: &'a Path,
+    dry_run: bool,
+    archived: Vec<ArchivedTask>,
+}
+
+/// Executes the "archive" command: moves Done tasks out of the active backlog.
+///
+/// Tasks go to the `archive` section of the backlog, where dependencies on them keep resolving, or
+/// to the archive section of a separate backlog file. In the latter case, dependencies on the moved
+/// tasks are dropped from the active backlog since they are satisfied anyway. A separate file that
+/// is the backlog file itself, under another path, stands for the archive section.
+pub fn execute(backlog_file: &Path, options: ArchiveOptions, format: Format) {
+    // Locking the backlog a second time as the archive would wait forever
+    let separate = options.to.filter(|to| !same_file(to, backlog_file));
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let completed_before = options
+        .older_than
+        .map(|days| Utc::now() - chrono::Duration::days(days));
+
+    let taken = taskai_core::take_archivable_tasks(&mut backlog, completed_before);
+    let report = ArchiveReport {
+        destination: options.to.unwrap_or(backlog_file),
+        dry_run: options.dry_run,
+        archived: taken
+            .iter()
+            .map(|t| ArchivedTask {
+                id: t.id.clone(),
+                title: t.title.clone(),
+                completed_at: t.completed_at,
+            })
+            .collect(),
+    };
+
+    if !options.dry_run && !taken.is_empty() {
+        match separate {
+            Some(archive_file) => {
+                // Write the archive first, so that a failure never loses tasks.
+                let _archive_lock = store::lock(archive_file);
+                let mut archive = if archive_file.exists() {
+                    store::load(archive_file)
+                } else {
+                    Backlog {
+                        project: backlog.project.clone(),
+                        ..Default::default()
+                    }
+                };
+                let ids: Vec<String> = taken.iter().map(|t| t.id.clone()).collect();
+                archive.archive.extend(taken);
+                store::save(archive_file, &archive);
+
+                taskai_core::forget_tasks(&mut backlog, &ids);
+                store::save(backlog_file, &backlog);
+            }
+            None => {
+                backlog.archive.extend(taken);
+                store::save(backlog_file, &backlog);
+            }
+        }
+    }
+
+    output::print(format, &report);
+}
+
+/// Returns true if both paths lead to the same existing file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl Render for ArchiveReport<'_> {
+    fn print_text(&self) {
+        if self.archived.is_empty() {
+            println!("No tasks to archive.");
+            return;
+        }
+
+        println!(
+            "{} {} task(s) to {}:",
+            if self.dry_run {
+                "Would archive"
+            } else {
+                "Archived"
+            },
+            self.archived.len(),
+            self.destination.display()
+        );
+        for task in &self.archived {
+            match task.completed_at {
+                Some(at) => println!("  {}: {} (done {})", task.id, task.title, at.date_naive()),
+                None => println!("  {}: {}", task.id, task.title),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that Done tasks are moved to a separate archive file and dependencies on them dropped.
+    #[test]
+    fn test_archive_to_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+    state: Done
+  - id: T-2
+    title: Second
+    depends: [T-1]
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let archive_file = dir.path().join("archive.yaml");
+
+        let options = ArchiveOptions {
+            older_than: None,
+            to: Some(&archive_file),
+            dry_run: false,
+        };
+        execute(file.path(), options, Format::Table);
+
+        let backlog = store::load(file.path());
+        assert_eq!(backlog.tasks.len(), 1);
+        assert!(backlog.tasks[0].depends.is_empty());
+
+        let archive = store::load(&archive_file);
+        assert_eq!(archive.project, "test-project");
+        assert_eq!(archive.archive[0].id, "T-1");
+    }
+
+    /// Tests that archiving to the backlog file itself uses its archive section.
+    #[test]
+    fn test_archive_to_same_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yaml");
+        std::fs::write(
+            &backlog_file,
+            "project: test-project\ntasks:\n  - id: T-1\n    title: First\n    state: Done\n",
+        )
+        .unwrap();
+
+        let options = ArchiveOptions {
+            older_than: None,
+            to: Some(&dir.path().join(".").join("tasks.yaml")),
+            dry_run: false,
+        };
+        execute(&backlog_file, options, Format::Json);
+
+        let backlog = store::load(&backlog_file);
+        assert!(backlog.tasks.is_empty());
+        assert_eq!(backlog.archive[0].id, "T-1");
+    }
+}
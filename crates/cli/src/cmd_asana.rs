@@ -0,0 +1,304 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::AsanaClient;
+use taskai_schema::Backlog;
+
+/// An Asana section or task created, or to be created, for an epic or a task.
+#[derive(Serialize)]
+struct ExportedItem {
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gid: Option<String>,
+}
+
+/// The sections, tasks and dependencies created by `export asana`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    project: &'a str,
+    dry_run: bool,
+    sections: Vec<ExportedItem>,
+    tasks: Vec<ExportedItem>,
+    /// Tasks whose dependencies were set, with the tasks they depend on.
+    dependencies: Vec<(String, Vec<String>)>,
+    /// Epics and tasks skipped because they were already exported.
+    skipped: usize,
+}
+
+/// Executes the "export asana" command: mirrors the backlog in the Asana project `project`
+/// (defaulting to `asana.project` in the configuration).
+///
+/// Each epic becomes a section of the project and each task an Asana task in the section of its
+/// epic, completed if the task is Done. Dependencies involving a newly created task are then set
+/// through Asana dependencies. The GIDs of the sections and tasks are recorded in `external_refs`,
+/// so the command can be run again as the backlog grows. Requires `ASANA_ACCESS_TOKEN`, unless
+/// `dry_run` is set.
+pub async fn export(backlog_file: &Path, project: Option<String>, dry_run: bool, format: Format) {
+    let Some(project) = project.or_else(|| store::config(backlog_file).asana.project) else {
+        error::fail(
+            ErrorKind::Usage,
+            "Pass --project or set asana.project in .taskai.toml.",
+        );
+    };
+    let client = AsanaClient::from_env();
+    if !dry_run && !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set ASANA_ACCESS_TOKEN to a token allowed to create tasks in the project.",
+        );
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        project: &project,
+        dry_run,
+        sections: Vec::new(),
+        tasks: Vec::new(),
+        dependencies: Vec::new(),
+        skipped: 0,
+    };
+
+    let failure = create_objects(&client, &mut backlog, &project, &mut report).await;
+    let created = report
+        .sections
+        .iter()
+        .chain(&report.tasks)
+        .any(|i| i.gid.is_some());
+    if created {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Creates the sections of the epics and the tasks not exported yet, then their dependencies,
+/// recording them in `report`. Returns the error that stopped the export, if any.
+async fn create_objects(
+    client: &AsanaClient,
+    backlog: &mut Backlog,
+    project: &str,
+    report: &mut ExportReport<'_>,
+) -> Option<String> {
+    let dry_run = report.dry_run;
+
+    for epic in &mut backlog.epics {
+        if taskai_core::asana_gid(&epic.external_refs).is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        let mut item = ExportedItem {
+            id: epic.id.clone(),
+            title: epic.title.clone(),
+            gid: None,
+        };
+        if !dry_run {
+            match client.create_section(project, &epic.title).await {
+                Ok(section) => {
+                    epic.external_refs.push(taskai_core::asana_ref(&section));
+                    item.gid = Some(section.gid);
+                }
+                Err(err) => {
+                    return Some(format!(
+                        "Error creating the section of {}: {}",
+                        epic.id, err
+                    ))
+                }
+            }
+        }
+        report.sections.push(item);
+    }
+
+    let pending: Vec<(String, serde_json::Value)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::asana_gid(&task.external_refs).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| {
+            let section = epic.and_then(|e| taskai_core::asana_gid(&e.external_refs));
+            (
+                task.id.clone(),
+                taskai_core::asana_task_data(task, project, section),
+            )
+        })
+        .collect();
+    let mut created = Vec::new();
+    for (task_id, data) in pending {
+        let mut item = ExportedItem {
+            id: task_id.clone(),
+            title: data["name"].as_str().unwrap_or_default().to_string(),
+            gid: None,
+        };
+        if !dry_run {
+            match client.create_task(&data).await {
+                Ok(task) => {
+                    if let Some(backlog_task) = backlog.find_task_mut(&task_id) {
+                        backlog_task
+                            .external_refs
+                            .push(taskai_core::asana_ref(&task));
+                    }
+                    item.gid = Some(task.gid);
+                }
+                Err(err) => return Some(format!("Error creating the task {}: {}", task_id, err)),
+            }
+        }
+        report.tasks.push(item);
+        created.push(task_id);
+    }
+
+    // Dependencies between tasks exported by an earlier run are already set
+    let gid_of = |backlog: &Backlog, id: &str| {
+        let task = backlog.find_task(id)?;
+        taskai_core::asana_gid(&task.external_refs).map(str::to_string)
+    };
+    let dependencies: Vec<(String, Vec<String>)> = backlog
+        .all_tasks()
+        .iter()
+        .map(|t| {
+            let involved = t
+                .depends
+                .iter()
+                .filter(|dep| created.contains(&t.id) || created.contains(dep))
+                .filter(|dep| dry_run || gid_of(backlog, dep).is_some())
+                .cloned()
+                .collect::<Vec<_>>();
+            (t.id.clone(), involved)
+        })
+        .filter(|(_, deps)| !deps.is_empty())
+        .collect();
+    for (task_id, deps) in dependencies {
+        if !dry_run {
+            let Some(gid) = gid_of(backlog, &task_id) else {
+                continue;
+            };
+            let gids: Vec<String> = deps.iter().filter_map(|d| gid_of(backlog, d)).collect();
+            if let Err(err) = client.add_dependencies(&gid, &gids).await {
+                return Some(format!(
+                    "Error setting the dependencies of {}: {}",
+                    task_id, err
+                ));
+            }
+        }
+        report.dependencies.push((task_id, deps));
+    }
+    None
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.sections.is_empty() && self.tasks.is_empty() {
+            println!("No epics or tasks to export to project {}.", self.project);
+        } else {
+            println!(
+                "{} {} section(s) and {} task(s) in project {}:",
+                if self.dry_run {
+                    "Would create"
+                } else {
+                    "Created"
+                },
+                self.sections.len(),
+                self.tasks.len(),
+                self.project
+            );
+        }
+        for item in self.sections.iter().chain(&self.tasks) {
+            match &item.gid {
+                Some(gid) => println!("  {} -> {}", item.id, gid),
+                None => println!("  {}: {}", item.id, item.title),
+            }
+        }
+        for (task, deps) in &self.dependencies {
+            println!(
+                "{} {} as depending on {}",
+                if self.dry_run { "Would mark" } else { "Marked" },
+                task,
+                deps.join(", ")
+            );
+        }
+        if self.skipped > 0 {
+            println!(
+                "Skipped {} epic(s) and task(s) already exported.",
+                self.skipped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the plan of a dry run: a section per epic, the tasks not exported yet and the
+    /// dependencies involving them.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+    external_refs:
+      - system: asana
+        id: "111"
+  - id: T-2
+    title: Second
+    depends: [T-1]
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Endpoint
+        depends: [T-2]
+"#,
+        )
+        .unwrap();
+        let original = backlog.clone();
+        let mut report = ExportReport {
+            project: "99",
+            dry_run: true,
+            sections: Vec::new(),
+            tasks: Vec::new(),
+            dependencies: Vec::new(),
+            skipped: 0,
+        };
+
+        let failure =
+            create_objects(&AsanaClient::from_env(), &mut backlog, "99", &mut report).await;
+        assert!(failure.is_none());
+
+        let ids = |items: &[ExportedItem]| items.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&report.sections), vec!["E-1"]);
+        assert_eq!(report.sections[0].title, "API");
+        assert_eq!(ids(&report.tasks), vec!["T-2", "E-1-1"]);
+        assert!(report.tasks.iter().all(|i| i.gid.is_none()));
+        assert_eq!(
+            report.dependencies,
+            vec![
+                ("T-2".to_string(), vec!["T-1".to_string()]),
+                ("E-1-1".to_string(), vec!["T-2".to_string()]),
+            ]
+        );
+        assert_eq!(report.skipped, 1);
+        assert_eq!(
+            serde_yaml::to_string(&backlog).unwrap(),
+            serde_yaml::to_string(&original).unwrap()
+        );
+    }
+}
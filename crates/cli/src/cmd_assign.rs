@@ -0,0 +1,94 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+
+/// Outcome of the "assign" command.
+#[derive(Serialize)]
+struct Assignment<'a> {
+    id: &'a str,
+    assignee: Option<&'a str>,
+}
+
+/// Executes the "assign" command: assigns a task to a person, or unassigns it when `person` is `None`.
+///
+/// When a `.taskai.toml` next to the backlog (or in a parent directory) lists team members, the
+/// person must be one of them.
+pub fn execute(backlog_file: &Path, task_id: &str, person: Option<&str>, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    if let Some(person) = person {
+        if let Err(err) = store::config(backlog_file).team.check_member(person) {
+            error::fail(ErrorKind::Validation, err);
+        }
+    }
+
+    match backlog.find_task_mut(task_id) {
+        Some(task) => {
+            task.assignee = person.map(str::to_string);
+            task.updated_at = Some(chrono::Utc::now());
+        }
+        None => {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", task_id),
+            );
+        }
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &Assignment {
+            id: task_id,
+            assignee: person,
+        },
+    );
+}
+
+impl Render for Assignment<'_> {
+    fn print_text(&self) {
+        match self.assignee {
+            Some(person) => println!("Task {} assigned to {}.", self.id, person),
+            None => println!("Task {} unassigned.", self.id),
+        }
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use taskai_core::CONFIG_FILE;
+
+    /// Tests that tasks are assigned to team members only, and unassigned.
+    #[test]
+    fn test_assign() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[team]\nmembers = [\"alice\"]\n",
+        )
+        .unwrap();
+        let backlog_file = dir.path().join("backlog.yaml");
+        fs::write(
+            &backlog_file,
+            "project: test-project\ntasks:\n  - id: T-1\n    title: First\n",
+        )
+        .unwrap();
+
+        execute(&backlog_file, "T-1", Some("alice"), Format::Table);
+        let backlog = store::load(&backlog_file);
+        assert_eq!(backlog.tasks[0].assignee.as_deref(), Some("alice"));
+
+        execute(&backlog_file, "T-1", None, Format::Table);
+        let backlog = store::load(&backlog_file);
+        assert_eq!(backlog.tasks[0].assignee, None);
+    }
+}
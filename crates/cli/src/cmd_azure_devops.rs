@@ -0,0 +1,392 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::AzureDevOpsClient;
+use taskai_schema::{Backlog, TaskState};
+
+/// An Azure DevOps work item created, or to be created, for an epic or a task.
+#[derive(Serialize)]
+struct ExportedItem {
+    id: String,
+    title: String,
+    work_item_type: String,
+    /// ID of the epic whose work item is the parent of this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work_item: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+/// The work items and links created by `export azure-devops`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    organization: &'a str,
+    project: &'a str,
+    dry_run: bool,
+    epics: Vec<ExportedItem>,
+    tasks: Vec<ExportedItem>,
+    /// Tasks whose predecessors were linked, with the tasks they depend on.
+    predecessors: Vec<(String, Vec<String>)>,
+    /// Epics and tasks skipped because they were already exported.
+    skipped: usize,
+}
+
+/// Executes the "export azure-devops" command: creates Azure DevOps work items for the epics and
+/// tasks of the backlog in `project` of `organization` (defaulting to `azure_devops.organization`
+/// and `azure_devops.project` in the configuration).
+///
+/// Each epic becomes an Epic work item and each task a User Story (or the `task_type` of the
+/// configuration) parented to the work item of its epic, moved to the done state if the task is
+/// Done. Dependencies involving a newly created task are then linked as predecessors. The IDs of
+/// the work items are recorded in `external_refs`, so the command can be run again as the backlog
+/// grows. Requires a personal access token, in `azure_devops.token` or `AZURE_DEVOPS_PAT`, unless
+/// `dry_run` is set.
+pub async fn export(
+    backlog_file: &Path,
+    organization: Option<String>,
+    project: Option<String>,
+    dry_run: bool,
+    format: Format,
+) {
+    let config = store::config(backlog_file).azure_devops;
+    let Some(organization) = organization.or_else(|| config.organization.clone()) else {
+        error::fail(
+            ErrorKind::Usage,
+            "Pass --organization or set azure_devops.organization in .taskai.toml.",
+        );
+    };
+    let Some(project) = project.or_else(|| config.project.clone()) else {
+        error::fail(
+            ErrorKind::Usage,
+            "Pass --project or set azure_devops.project in .taskai.toml.",
+        );
+    };
+    let client = AzureDevOpsClient::from_env(&organization, &project, config.token.as_deref());
+    if !dry_run && !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set AZURE_DEVOPS_PAT to a personal access token with the Work Items (read and write) \
+             scope.",
+        );
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        organization: &organization,
+        project: &project,
+        dry_run,
+        epics: Vec::new(),
+        tasks: Vec::new(),
+        predecessors: Vec::new(),
+        skipped: 0,
+    };
+
+    let failure = create_work_items(&client, &mut backlog, &config, &mut report).await;
+    let created = report
+        .epics
+        .iter()
+        .chain(&report.tasks)
+        .any(|i| i.work_item.is_some());
+    if created {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Creates the work items of the epics and tasks not exported yet, then their predecessor links,
+/// recording them in `report`. Returns the error that stopped the export, if any.
+async fn create_work_items(
+    client: &AzureDevOpsClient,
+    backlog: &mut Backlog,
+    config: &taskai_core::AzureDevOpsConfig,
+    report: &mut ExportReport<'_>,
+) -> Option<String> {
+    let dry_run = report.dry_run;
+    let epic_type = config.epic_type.as_deref().unwrap_or("Epic");
+    let task_type = config.task_type.as_deref().unwrap_or("User Story");
+
+    for epic in &mut backlog.epics {
+        if taskai_core::azure_work_item_id(&epic.external_refs).is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        let mut item = ExportedItem {
+            id: epic.id.clone(),
+            title: epic.title.clone(),
+            work_item_type: epic_type.to_string(),
+            parent: None,
+            work_item: None,
+            url: None,
+        };
+        if !dry_run {
+            let patch = taskai_core::azure_epic_patch(epic, config);
+            match client.create_work_item(epic_type, &patch).await {
+                Ok(work_item) => {
+                    epic.external_refs.push(taskai_core::azure_ref(&work_item));
+                    item.url = work_item.web_url().map(str::to_string);
+                    item.work_item = Some(work_item.id);
+                }
+                Err(err) => {
+                    return Some(format!(
+                        "Error creating the work item of {}: {}",
+                        epic.id, err
+                    ))
+                }
+            }
+        }
+        report.epics.push(item);
+    }
+
+    let pending: Vec<(ExportedItem, bool, serde_json::Value)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::azure_work_item_id(&task.external_refs).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| {
+            let parent = epic
+                .and_then(|e| taskai_core::azure_work_item_id(&e.external_refs))
+                .map(|id| client.work_item_url(id));
+            let item = ExportedItem {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                work_item_type: task_type.to_string(),
+                parent: epic.map(|e| e.id.clone()),
+                work_item: None,
+                url: None,
+            };
+            (
+                item,
+                matches!(task.state, TaskState::Done),
+                taskai_core::azure_task_patch(task, parent.as_deref(), config),
+            )
+        })
+        .collect();
+    let mut created = Vec::new();
+    for (mut item, done, patch) in pending {
+        let task_id = item.id.clone();
+        if !dry_run {
+            let work_item = match client.create_work_item(task_type, &patch).await {
+                Ok(work_item) => work_item,
+                Err(err) => {
+                    return Some(format!(
+                        "Error creating the work item of {}: {}",
+                        task_id, err
+                    ))
+                }
+            };
+            if let Some(task) = backlog.find_task_mut(&task_id) {
+                task.external_refs.push(taskai_core::azure_ref(&work_item));
+            }
+            item.url = work_item.web_url().map(str::to_string);
+            item.work_item = Some(work_item.id);
+            // Work items are created in their initial state
+            if done {
+                let patch = taskai_core::azure_done_patch(config);
+                if let Err(err) = client.update_work_item(work_item.id, &patch).await {
+                    report.tasks.push(item);
+                    return Some(format!(
+                        "Error closing the work item of {}: {}",
+                        task_id, err
+                    ));
+                }
+            }
+        }
+        report.tasks.push(item);
+        created.push(task_id);
+    }
+
+    // Dependencies between tasks exported by an earlier run are already linked
+    let id_of = |backlog: &Backlog, id: &str| {
+        let task = backlog.find_task(id)?;
+        taskai_core::azure_work_item_id(&task.external_refs)
+    };
+    let predecessors: Vec<(String, Vec<String>)> = backlog
+        .all_tasks()
+        .iter()
+        .map(|t| {
+            let involved = t
+                .depends
+                .iter()
+                .filter(|dep| created.contains(&t.id) || created.contains(dep))
+                .filter(|dep| dry_run || id_of(backlog, dep).is_some())
+                .cloned()
+                .collect::<Vec<_>>();
+            (t.id.clone(), involved)
+        })
+        .filter(|(_, deps)| !deps.is_empty())
+        .collect();
+    for (task_id, deps) in predecessors {
+        if !dry_run {
+            let Some(id) = id_of(backlog, &task_id) else {
+                continue;
+            };
+            for dep in &deps {
+                let Some(dep_id) = id_of(backlog, dep) else {
+                    continue;
+                };
+                let patch = taskai_core::azure_predecessor_patch(&client.work_item_url(dep_id));
+                if let Err(err) = client.update_work_item(id, &patch).await {
+                    return Some(format!(
+                        "Error linking {} to its predecessor {}: {}",
+                        task_id, dep, err
+                    ));
+                }
+            }
+        }
+        report.predecessors.push((task_id, deps));
+    }
+    None
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.epics.is_empty() && self.tasks.is_empty() {
+            println!(
+                "No epics or tasks to export to {}/{}.",
+                self.organization, self.project
+            );
+        } else {
+            println!(
+                "{} {} epic(s) and {} task(s) in {}/{}:",
+                if self.dry_run {
+                    "Would create"
+                } else {
+                    "Created"
+                },
+                self.epics.len(),
+                self.tasks.len(),
+                self.organization,
+                self.project
+            );
+        }
+        for item in self.epics.iter().chain(&self.tasks) {
+            match (item.work_item, &item.url) {
+                (Some(_), Some(url)) => println!("  {} -> {}", item.id, url),
+                (Some(id), None) => println!("  {} -> #{}", item.id, id),
+                _ => println!("  {} ({}): {}", item.id, item.work_item_type, item.title),
+            }
+        }
+        for (task, deps) in &self.predecessors {
+            println!(
+                "{} {} to its predecessor(s) {}",
+                if self.dry_run { "Would link" } else { "Linked" },
+                task,
+                deps.join(", ")
+            );
+        }
+        if self.skipped > 0 {
+            println!(
+                "Skipped {} epic(s) and task(s) already exported.",
+                self.skipped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the plan of a dry run: the work item types, parents and predecessors of the epics and
+    /// tasks not exported yet.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+    external_refs:
+      - system: azure-devops
+        id: "12"
+  - id: T-2
+    title: Second
+    state: Done
+    depends: [T-1]
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Endpoint
+  - id: E-2
+    title: UI
+    external_refs:
+      - system: azure-devops
+        id: "7"
+    tasks:
+      - id: E-2-1
+        title: Page
+        depends: [T-2]
+"#,
+        )
+        .unwrap();
+        let config = taskai_core::AzureDevOpsConfig {
+            task_type: Some("Task".to_string()),
+            ..Default::default()
+        };
+        let mut report = ExportReport {
+            organization: "acme",
+            project: "App",
+            dry_run: true,
+            epics: Vec::new(),
+            tasks: Vec::new(),
+            predecessors: Vec::new(),
+            skipped: 0,
+        };
+
+        let client = AzureDevOpsClient::from_env("acme", "App", None);
+        let failure = create_work_items(&client, &mut backlog, &config, &mut report).await;
+        assert!(failure.is_none());
+
+        fn plan(items: &[ExportedItem]) -> Vec<(&str, &str, Option<&str>)> {
+            items
+                .iter()
+                .map(|i| {
+                    (
+                        i.id.as_str(),
+                        i.work_item_type.as_str(),
+                        i.parent.as_deref(),
+                    )
+                })
+                .collect()
+        }
+        assert_eq!(plan(&report.epics), vec![("E-1", "Epic", None)]);
+        assert_eq!(
+            plan(&report.tasks),
+            vec![
+                ("T-2", "Task", None),
+                ("E-1-1", "Task", Some("E-1")),
+                ("E-2-1", "Task", Some("E-2")),
+            ]
+        );
+        assert_eq!(
+            report.predecessors,
+            vec![
+                ("T-2".to_string(), vec!["T-1".to_string()]),
+                ("E-2-1".to_string(), vec!["T-2".to_string()]),
+            ]
+        );
+        assert_eq!(report.skipped, 2);
+        assert!(backlog.find_task("T-2").unwrap().external_refs.is_empty());
+    }
+}
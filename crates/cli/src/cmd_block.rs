@@ -0,0 +1,152 @@
+use crate::error::{self, ErrorKind};
+use crate::notifications;
+use crate::output::{self, Format, TaskUpdate};
+use crate::store;
+use std::path::Path;
+use taskai_core::NotifyEvent;
+use taskai_schema::{Backlog, Blocker, Task, TaskState};
+
+/// Executes the "block" command: marks a task as Blocked, recording why and, optionally, the
+/// external item it waits on.
+///
+/// The reason is also left as a note on the task, so that it stays in its history once unblocked.
+/// Blocked tasks are never ready, so `taskai next` skips them until they are unblocked. The task is
+/// then posted to the chat integrations configured for blocked tasks.
+pub async fn block(
+    backlog_file: &Path,
+    task_id: &str,
+    reason: &str,
+    link: Option<&str>,
+    format: Format,
+) {
+    let lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+
+    let task = find_task(&mut backlog, task_id);
+    if matches!(task.state, TaskState::Done) {
+        error::fail(
+            ErrorKind::Validation,
+            format!("Task {} is Done; reopen it before blocking it.", task_id),
+        );
+    }
+    task.set_state(TaskState::Blocked, now);
+    task.blocker = Some(Blocker {
+        reason: reason.to_string(),
+        link: link.map(str::to_string),
+    });
+    match link {
+        Some(link) => task.add_note(&format!("Blocked: {} ({})", reason, link), now),
+        None => task.add_note(&format!("Blocked: {}", reason), now),
+    }
+
+    store::save(backlog_file, &backlog);
+    drop(lock);
+    output::print(
+        format,
+        &TaskUpdate {
+            id: task_id,
+            state: &TaskState::Blocked,
+            message: format!("Task {} blocked: {}", task_id, reason),
+        },
+    );
+    let ids = [task_id.to_string()];
+    notifications::tasks(backlog_file, &backlog, NotifyEvent::Blocked, &ids).await;
+}
+
+/// Executes the "unblock" command: sets a Blocked task back to Todo, or to InProgress with
+/// `resume` set, and clears its blocker.
+pub fn unblock(backlog_file: &Path, task_id: &str, resume: bool, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+
+    let task = find_task(&mut backlog, task_id);
+    if !matches!(task.state, TaskState::Blocked) {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "Task {} is not Blocked (current state: {:?}).",
+                task_id, task.state
+            ),
+        );
+    }
+    let state = if resume {
+        TaskState::InProgress
+    } else {
+        TaskState::Todo
+    };
+    task.set_state(state.clone(), now);
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &TaskUpdate {
+            id: task_id,
+            state: &state,
+            message: format!("Task {} unblocked ({:?}).", task_id, state),
+        },
+    );
+}
+
+fn find_task<'a>(backlog: &'a mut Backlog, task_id: &str) -> &'a mut Task {
+    match backlog.find_task_mut(task_id) {
+        Some(task) => task,
+        None => {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", task_id),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a blocked task records its blocker, is not ready, and is ready again once unblocked.
+    #[tokio::test]
+    async fn test_block_and_unblock() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: TEST-1
+    title: Test Task
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        block(
+            file.path(),
+            "TEST-1",
+            "Waiting for API keys",
+            Some("github:acme/infra#12"),
+            Format::Table,
+        )
+        .await;
+        let backlog = store::load(file.path());
+        let task = &backlog.tasks[0];
+        assert!(matches!(task.state, TaskState::Blocked));
+        assert_eq!(
+            task.blocker.as_ref().unwrap().reason,
+            "Waiting for API keys"
+        );
+        assert_eq!(
+            task.notes[0].text,
+            "Blocked: Waiting for API keys (github:acme/infra#12)"
+        );
+        assert!(taskai_core::get_ready_tasks(&backlog).is_empty());
+
+        unblock(file.path(), "TEST-1", false, Format::Table);
+        let backlog = store::load(file.path());
+        assert!(matches!(backlog.tasks[0].state, TaskState::Todo));
+        assert!(backlog.tasks[0].blocker.is_none());
+        assert_eq!(taskai_core::get_ready_tasks(&backlog).len(), 1);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::cmd_git::{git, git_error};
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::TaskState;
+
+/// The branch checked out by `branch`.
+#[derive(Serialize)]
+struct BranchReport {
+    task: String,
+    branch: String,
+    /// Whether the branch was created, rather than an existing one checked out.
+    created: bool,
+    state: TaskState,
+}
+
+/// Executes the "branch" command: creates and checks out the git branch of a task, or checks it
+/// out if it exists, then records it on the task and moves the task to InProgress.
+///
+/// The branch name comes from `template`, then `git.branch_template` in the configuration, then
+/// `feat/{id}-{slug}`; a task that already records a branch keeps it. Todo and Blocked tasks are
+/// moved to InProgress, Done tasks keep their state.
+///
+/// The backlog is loaded again once the branch is checked out, so the task is recorded in the
+/// branch's version of the backlog rather than in the one the command started from.
+pub fn execute(backlog_file: &Path, task_id: &str, template: Option<String>, format: Format) {
+    let template = template
+        .or_else(|| store::config(backlog_file).git.branch_template)
+        .unwrap_or_else(|| taskai_core::DEFAULT_BRANCH_TEMPLATE.to_string());
+    let dir = store::project_root(backlog_file);
+
+    let backlog = store::load(backlog_file);
+    let epic = backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task_id))
+        .map(|e| e.id.as_str());
+    let branch = match backlog.find_task(task_id) {
+        Some(task) => task
+            .branch
+            .clone()
+            .unwrap_or_else(|| taskai_core::branch_name(&template, task, epic)),
+        None => error::fail(
+            ErrorKind::NotFound,
+            format!("Task with ID '{}' not found in the backlog.", task_id),
+        ),
+    };
+    if branch.is_empty() {
+        error::fail(
+            ErrorKind::Usage,
+            format!("The branch template '{}' gives an empty name.", template),
+        );
+    }
+
+    let reference = format!("refs/heads/{}", branch);
+    let exists = git(&dir, &["rev-parse", "--verify", "--quiet", &reference]).is_ok();
+    let args: &[&str] = if exists {
+        &["checkout", "--quiet", &branch]
+    } else {
+        &["checkout", "--quiet", "-b", &branch]
+    };
+    git(&dir, args).unwrap_or_else(git_error);
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let Some(task) = backlog.find_task_mut(task_id) else {
+        error::fail(
+            ErrorKind::NotFound,
+            format!(
+                "Task with ID '{}' not found in the backlog of branch {}.",
+                task_id, branch
+            ),
+        );
+    };
+    let now = chrono::Utc::now();
+    if matches!(task.state, TaskState::Todo | TaskState::Blocked) {
+        task.set_state(TaskState::InProgress, now);
+    }
+    if task.branch.as_deref() != Some(branch.as_str()) {
+        task.branch = Some(branch.clone());
+        task.updated_at = Some(now);
+    }
+    let report = BranchReport {
+        task: task.id.clone(),
+        branch,
+        created: !exists,
+        state: task.state.clone(),
+    };
+    store::save(backlog_file, &backlog);
+    output::print(format, &report);
+}
+
+impl Render for BranchReport {
+    fn print_text(&self) {
+        println!(
+            "{} branch {} for {} ({:?}).",
+            if self.created {
+                "Created"
+            } else {
+                "Switched to"
+            },
+            self.branch,
+            self.task,
+            self.state
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that the branch is created from the template and recorded on the task.
+    #[test]
+    fn test_branch_created_and_recorded() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        let backlog_file = dir.join("taskai.yaml");
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-12\n    title: Short title\n",
+        )
+        .unwrap();
+        let run = |args: &[&str]| git(dir, args).unwrap();
+        run(&["init", "--quiet"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        run(&["add", "taskai.yaml"]);
+        run(&["commit", "--quiet", "-m", "Add the backlog"]);
+
+        execute(&backlog_file, "T-12", None, Format::Json);
+
+        assert_eq!(run(&["branch", "--show-current"]), "feat/T-12-short-title");
+        let backlog = store::load(&backlog_file);
+        assert_eq!(
+            backlog.tasks[0].branch.as_deref(),
+            Some("feat/T-12-short-title")
+        );
+        assert!(matches!(backlog.tasks[0].state, TaskState::InProgress));
+    }
+
+    /// Tests that switching to an existing branch records the task in that branch's backlog.
+    #[test]
+    fn test_branch_keeps_checked_out_backlog() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        let backlog_file = dir.join("taskai.yaml");
+        let run = |args: &[&str]| git(dir, args).unwrap();
+        run(&["init", "--quiet", "--initial-branch", "main"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-12\n    title: Short title\n",
+        )
+        .unwrap();
+        run(&["add", "taskai.yaml"]);
+        run(&["commit", "--quiet", "-m", "Add the backlog"]);
+        run(&["checkout", "--quiet", "-b", "feat/T-12-short-title"]);
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-12\n    title: Short title\n  - id: T-13\n    title: Follow-up\n",
+        )
+        .unwrap();
+        run(&["commit", "--quiet", "-am", "Add a follow-up"]);
+        run(&["checkout", "--quiet", "main"]);
+
+        execute(&backlog_file, "T-12", None, Format::Json);
+
+        assert_eq!(run(&["branch", "--show-current"]), "feat/T-12-short-title");
+        let backlog = store::load(&backlog_file);
+        assert_eq!(backlog.tasks.len(), 2);
+        assert!(matches!(backlog.tasks[0].state, TaskState::InProgress));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use taskai_core::{CheckStatus, TaskChecks};
+use taskai_schema::TaskState;
+
+/// Check results of every candidate task, and the tasks marked as done.
+#[derive(Serialize)]
+struct CheckReport {
+    tasks: Vec<TaskChecks>,
+    marked_done: Vec<String>,
+}
+
+/// Executes the "check" command: runs the checks derived from the `done_when` criteria of tasks.
+///
+/// When `task_id` is given, only that task is checked. Otherwise every task that is not Done yet and
+/// declares criteria is checked. Criteria marked as commands (`$ cmd`) only run with `run` set, in
+/// `root`, which defaults to the directory containing the backlog file; they are reported as manual
+/// otherwise. With `apply` set, tasks whose criteria all pass are marked as done. Exits with a
+/// non-zero status if any check failed.
+pub fn execute(
+    backlog_file: &Path,
+    task_id: Option<&str>,
+    root: Option<&Path>,
+    run: bool,
+    apply: bool,
+    format: Format,
+) {
+    let backlog = store::load(backlog_file);
+    let root = match root {
+        Some(root) => root.to_path_buf(),
+        None => store::project_root(backlog_file),
+    };
+
+    let tasks = match task_id {
+        Some(id) => match backlog.find_task(id) {
+            Some(task) => vec![task],
+            None => {
+                error::fail(
+                    ErrorKind::NotFound,
+                    format!("Task with ID '{}' not found in the backlog.", id),
+                );
+            }
+        },
+        None => backlog
+            .all_tasks()
+            .into_iter()
+            .filter(|t| !matches!(t.state, TaskState::Done) && !t.done_when.is_empty())
+            .collect(),
+    };
+
+    let mut outcomes = HashMap::new();
+    let results: Vec<TaskChecks> = tasks
+        .into_iter()
+        .map(|task| taskai_core::run_checks(task, &root, run, &mut outcomes))
+        .collect();
+
+    let mut marked_done = Vec::new();
+    if apply {
+        // Checks may take a while: apply their results to the backlog as it is now
+        let _lock = store::lock(backlog_file);
+        let mut backlog = store::load(backlog_file);
+        let now = chrono::Utc::now();
+        for result in results.iter().filter(|r| r.passed()) {
+            if let Some(task) = backlog.find_task_mut(&result.task_id) {
+                if !matches!(task.state, TaskState::Done) {
+                    task.set_state(TaskState::Done, now);
+                    task.add_note("All done_when checks passed.", now);
+                    marked_done.push(result.task_id.clone());
+                }
+            }
+        }
+        if !marked_done.is_empty() {
+            store::save(backlog_file, &backlog);
+        }
+    }
+
+    let report = CheckReport {
+        tasks: results,
+        marked_done,
+    };
+    output::print(format, &report);
+
+    if report.tasks.iter().any(|t| t.failed()) {
+        error::exit(ErrorKind::Validation);
+    }
+}
+
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Passed => "pass",
+        CheckStatus::Failed => "fail",
+        CheckStatus::Manual => "manual",
+    }
+}
+
+impl Render for CheckReport {
+    fn print_text(&self) {
+        if self.tasks.is_empty() {
+            println!("No tasks to check.");
+            return;
+        }
+
+        for result in &self.tasks {
+            println!("{}: {}", result.task_id, result.title);
+            for criterion in &result.criteria {
+                println!(
+                    "  {:<7} {}",
+                    format!("{}:", status_label(criterion.status)),
+                    criterion.criterion
+                );
+            }
+            println!();
+        }
+
+        for id in &self.marked_done {
+            println!("Task {} marked as done.", id);
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.tasks.is_empty() {
+            println!("No tasks to check.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .tasks
+            .iter()
+            .flat_map(|result| {
+                result.criteria.iter().map(move |c| {
+                    vec![
+                        result.task_id.clone(),
+                        c.criterion.clone(),
+                        status_label(c.status).to_string(),
+                    ]
+                })
+            })
+            .collect();
+        output::print_table(true, 0, &["Task", "Criterion", "Status"], &rows);
+
+        for id in &self.marked_done {
+            println!();
+            println!("- Task {} marked as done.", id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that a task whose checks all pass is marked as done with `apply`.
+    #[test]
+    fn test_check_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("main.rs"), "").unwrap();
+        let backlog_file = dir.path().join("backlog.yaml");
+        fs::write(
+            &backlog_file,
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: Write main
+    done_when:
+      - "`main.rs` exists"
+"#,
+        )
+        .unwrap();
+
+        execute(&backlog_file, None, None, false, true, Format::Table);
+
+        let backlog = store::load(&backlog_file);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+        assert_eq!(backlog.tasks[0].notes.len(), 1);
+    }
+}
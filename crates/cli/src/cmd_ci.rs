@@ -0,0 +1,258 @@
+use crate::cmd_validate::format_diagnostic;
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use taskai_core::{Diagnostic, LintConfig, LintOptions, Severity};
+use taskai_schema::Backlog;
+
+/// How `ci` reports the problems found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Annotations {
+    /// GitHub Actions workflow commands, shown as annotations on the backlog file.
+    GitHub,
+    /// Plain lines in the job log, plus a GitLab Code Quality report.
+    GitLab,
+    /// Plain lines, as printed by `validate` and `lint`.
+    Text,
+}
+
+impl Annotations {
+    /// Picks the annotations of the CI system the command runs in, from its environment.
+    pub fn detect() -> Self {
+        let set = |name: &str| std::env::var(name).is_ok_and(|v| v == "true");
+        if set("GITHUB_ACTIONS") {
+            Annotations::GitHub
+        } else if set("GITLAB_CI") {
+            Annotations::GitLab
+        } else {
+            Annotations::Text
+        }
+    }
+}
+
+/// Problems found by the validation and lint rules.
+#[derive(Serialize)]
+struct CiReport {
+    file: PathBuf,
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+    #[serde(skip)]
+    annotations: Annotations,
+}
+
+/// Executes the "ci" command: validates and lints the backlog, reporting every problem as an
+/// annotation of the CI system pointing at its line in the backlog file.
+///
+/// Lint rules only run when the backlog parses, and follow the `lint` configuration. With GitLab
+/// annotations, a Code Quality report is also written to `report`. Exits with status 3 if the
+/// backlog has errors, or warnings when `deny_warnings` is set, so that a broken backlog fails
+/// the build.
+pub fn execute(
+    backlog_file: &Path,
+    annotations: Annotations,
+    strict: bool,
+    deny_warnings: bool,
+    report: &Path,
+    format: Format,
+) {
+    let source = store::read(backlog_file);
+    let config = store::config(backlog_file).lint;
+    let (errors, warnings) = check(&source, strict, &config)
+        .into_iter()
+        .partition(|d| d.severity == Severity::Error);
+
+    // Annotations point at files relative to the workspace, where CI jobs run
+    let file = std::env::current_dir()
+        .ok()
+        .and_then(|dir| backlog_file.strip_prefix(dir).ok())
+        .unwrap_or(backlog_file)
+        .to_path_buf();
+    let ci = CiReport {
+        file,
+        errors,
+        warnings,
+        annotations,
+    };
+
+    if annotations == Annotations::GitLab {
+        let written = serde_json::to_string_pretty(&code_quality(&ci))
+            .map_err(|err| err.to_string())
+            .and_then(|json| fs::write(report, json).map_err(|err| err.to_string()));
+        if let Err(err) = written {
+            error::fail(
+                ErrorKind::Other,
+                format!("Error writing {}: {}", report.display(), err),
+            );
+        }
+    }
+    output::print(format, &ci);
+
+    if !ci.errors.is_empty() || (deny_warnings && !ci.warnings.is_empty()) {
+        error::exit(ErrorKind::Validation);
+    }
+}
+
+/// Validates and lints backlog source, returning the problems found.
+///
+/// Lint rules only run when the backlog parses, and follow the `lint` configuration.
+pub fn check(source: &str, strict: bool, config: &LintConfig) -> Vec<Diagnostic> {
+    let validation = taskai_core::validate_source(source, strict);
+    let mut diagnostics: Vec<Diagnostic> = validation
+        .errors
+        .into_iter()
+        .chain(validation.warnings)
+        .collect();
+    if let Ok(backlog) = serde_yaml::from_str::<Backlog>(source) {
+        let min_severity = config.severity.unwrap_or(Severity::Warning);
+        let options = LintOptions {
+            stale_after: chrono::Duration::days(config.days.unwrap_or(7)),
+            ..Default::default()
+        };
+        diagnostics.extend(
+            taskai_core::lint_backlog(&backlog, source, &options)
+                .into_iter()
+                .filter(|d| d.severity >= min_severity),
+        );
+    }
+    diagnostics
+}
+
+/// Formats a diagnostic as a GitHub Actions workflow command.
+fn github_annotation(file: &Path, diagnostic: &Diagnostic) -> String {
+    // Workflow commands escape their data, and further escape `:` and `,` in properties
+    let data = |text: &str| {
+        text.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let property = |text: &str| data(text).replace(':', "%3A").replace(',', "%2C");
+
+    let command = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let mut properties = vec![format!("file={}", property(&file.to_string_lossy()))];
+    if let Some(location) = &diagnostic.location {
+        properties.extend(location.line.map(|line| format!("line={}", line)));
+        properties.extend(location.column.map(|column| format!("col={}", column)));
+    }
+    properties.push(format!("title={}", property(&diagnostic.code)));
+    format!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        data(&diagnostic.message)
+    )
+}
+
+/// Builds the GitLab Code Quality report of the problems found.
+fn code_quality(report: &CiReport) -> serde_json::Value {
+    let path = report.file.to_string_lossy();
+    let issues: Vec<serde_json::Value> = report
+        .errors
+        .iter()
+        .chain(&report.warnings)
+        .map(|d| {
+            let line = d.location.as_ref().and_then(|l| l.line).unwrap_or(1);
+            json!({
+                "description": d.message,
+                "check_name": d.code,
+                "fingerprint": fingerprint(d, &path),
+                "severity": match d.severity {
+                    Severity::Error => "major",
+                    Severity::Warning => "minor",
+                },
+                "location": { "path": path, "lines": { "begin": line } },
+            })
+        })
+        .collect();
+    json!(issues)
+}
+
+/// Identifies a problem across pipelines: the SHA-256 of its code, message, task and file, so that
+/// GitLab tracks it from one run to the next whatever Rust release built the tool.
+fn fingerprint(diagnostic: &Diagnostic, path: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        &diagnostic.code,
+        &diagnostic.message,
+        diagnostic.task_id.as_deref().unwrap_or(""),
+        path,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl Render for CiReport {
+    fn print_text(&self) {
+        for diagnostic in self.errors.iter().chain(&self.warnings) {
+            match self.annotations {
+                Annotations::GitHub => println!("{}", github_annotation(&self.file, diagnostic)),
+                Annotations::GitLab | Annotations::Text => {
+                    println!("{}", format_diagnostic(&self.file, diagnostic))
+                }
+            }
+        }
+        println!(
+            "{}: {} error(s), {} warning(s).",
+            self.file.display(),
+            self.errors.len(),
+            self.warnings.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_core::Location;
+
+    #[test]
+    fn test_github_annotation() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            code: "missing-dependency".to_string(),
+            message: "Task T-2 depends on T-9, which does not exist\n100% sure".to_string(),
+            task_id: Some("T-2".to_string()),
+            location: Some(Location {
+                path: "tasks[1].depends".to_string(),
+                line: Some(7),
+                column: Some(5),
+            }),
+        };
+
+        assert_eq!(
+            github_annotation(Path::new("plan, v2.yaml"), &diagnostic),
+            "::error file=plan%2C v2.yaml,line=7,col=5,title=missing-dependency::\
+             Task T-2 depends on T-9, which does not exist%0A100%25 sure"
+        );
+    }
+
+    /// Tests that fingerprints are a plain SHA-256, so they never change between builds.
+    #[test]
+    fn test_fingerprint_is_stable() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            code: "missing-dependency".to_string(),
+            message: "Task T-2 depends on T-9, which does not exist".to_string(),
+            task_id: Some("T-2".to_string()),
+            location: None,
+        };
+
+        assert_eq!(
+            fingerprint(&diagnostic, "tasks.yaml"),
+            "7ad992cf5a5ed89d19be7955a8301c68e3a32f7accf2ff0482080042af885920"
+        );
+    }
+}
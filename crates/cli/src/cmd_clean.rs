@@ -0,0 +1,122 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::Utc;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::CleanSummary;
+
+/// What the command cleaned, or would clean with `dry_run` set.
+#[derive(Serialize)]
+struct CleanReport {
+    dry_run: bool,
+    #[serde(flatten)]
+    summary: CleanSummary,
+}
+
+/// Executes the "clean" command: prunes empty epics, deduplicates `depends` entries and removes
+/// references to deleted tasks in one go.
+///
+/// With `archive_done` set, Done tasks completed more than that many days ago are first moved to
+/// the archive section of the backlog. With `dry_run` set, the changes are reported but the
+/// backlog is not written.
+pub fn execute(backlog_file: &Path, archive_done: Option<i64>, dry_run: bool, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let cutoff = archive_done.map(|days| Utc::now() - chrono::Duration::days(days));
+
+    let summary = taskai_core::clean_backlog(&mut backlog, cutoff);
+    if !dry_run && !summary.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &CleanReport { dry_run, summary });
+}
+
+impl Render for CleanReport {
+    fn print_text(&self) {
+        let summary = &self.summary;
+        if summary.is_empty() {
+            println!("Nothing to clean.");
+            return;
+        }
+
+        let verb = |done: &'static str, planned: &'static str| {
+            if self.dry_run {
+                planned
+            } else {
+                done
+            }
+        };
+        if !summary.archived.is_empty() {
+            println!(
+                "{} {} Done task(s): {}",
+                verb("Archived", "Would archive"),
+                summary.archived.len(),
+                summary.archived.join(", ")
+            );
+        }
+        if !summary.removed_epics.is_empty() {
+            println!(
+                "{} {} empty epic(s): {}",
+                verb("Removed", "Would remove"),
+                summary.removed_epics.len(),
+                summary.removed_epics.join(", ")
+            );
+        }
+        for (task, dep) in &summary.duplicate_depends {
+            println!(
+                "{} duplicate dependency of {} on {}",
+                verb("Removed", "Would remove"),
+                task,
+                dep
+            );
+        }
+        for (task, reference) in &summary.dangling_refs {
+            println!(
+                "{} reference of {} to missing task {}",
+                verb("Removed", "Would remove"),
+                task,
+                reference
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a dry run leaves the backlog untouched and a real run cleans it.
+    #[test]
+    fn test_clean() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+    state: Done
+    completed_at: 2024-01-01T00:00:00Z
+  - id: T-2
+    title: Second
+    depends: [T-1, T-1]
+epics:
+  - id: E-1
+    title: Empty
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(file.path(), Some(30), true, Format::Table);
+        assert_eq!(store::load(file.path()).epics.len(), 1);
+
+        execute(file.path(), Some(30), false, Format::Table);
+        let backlog = store::load(file.path());
+        assert!(backlog.epics.is_empty());
+        assert_eq!(backlog.archive[0].id, "T-1");
+        assert_eq!(backlog.tasks[0].depends, ["T-1"]);
+    }
+}
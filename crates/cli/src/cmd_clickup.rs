@@ -0,0 +1,393 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{ClickUpClient, ClickUpStatus};
+use taskai_schema::Backlog;
+
+/// A ClickUp task created, or to be created, for an epic or a task.
+#[derive(Serialize)]
+struct ExportedItem {
+    id: String,
+    title: String,
+    /// ID of the epic whose ClickUp task this one is a subtask of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clickup_id: Option<String>,
+}
+
+/// The tasks and dependencies created by `export clickup`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    list: &'a str,
+    dry_run: bool,
+    epics: Vec<ExportedItem>,
+    tasks: Vec<ExportedItem>,
+    /// Tasks whose dependencies were set, with the tasks they depend on.
+    dependencies: Vec<(String, Vec<String>)>,
+    /// Epics and tasks skipped because they were already exported.
+    skipped: usize,
+}
+
+/// Executes the "export clickup" command: creates ClickUp tasks for the epics and tasks of the
+/// backlog in the list `list` (defaulting to `clickup.list` in the configuration).
+///
+/// Each epic becomes a task of the list and each task a subtask of the task of its epic, in the
+/// status of the list matching its state. Dependencies involving a newly created task are then set
+/// through ClickUp dependencies. The IDs of the ClickUp tasks are recorded in `external_refs`, so
+/// the command can be run again as the backlog grows. Requires `CLICKUP_API_TOKEN`, unless
+/// `dry_run` is set.
+pub async fn export(backlog_file: &Path, list: Option<String>, dry_run: bool, format: Format) {
+    let list = list_id(backlog_file, list);
+    let client = ClickUpClient::from_env();
+    if !dry_run && !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set CLICKUP_API_TOKEN to a personal token of a member of the list.",
+        );
+    }
+    // Statuses only matter to the tasks actually created
+    let statuses = if dry_run {
+        Vec::new()
+    } else {
+        match client.get_list(&list).await {
+            Ok(clickup_list) => clickup_list.statuses,
+            Err(err) => error::fail(
+                ErrorKind::Other,
+                format!("Error fetching the list {}: {}", list, err),
+            ),
+        }
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        list: &list,
+        dry_run,
+        epics: Vec::new(),
+        tasks: Vec::new(),
+        dependencies: Vec::new(),
+        skipped: 0,
+    };
+
+    let failure = create_tasks(&client, &mut backlog, &statuses, &mut report).await;
+    let created = report
+        .epics
+        .iter()
+        .chain(&report.tasks)
+        .any(|i| i.clickup_id.is_some());
+    if created {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Creates the ClickUp tasks of the epics and tasks not exported yet, then their dependencies,
+/// recording them in `report`. Returns the error that stopped the export, if any.
+async fn create_tasks(
+    client: &ClickUpClient,
+    backlog: &mut Backlog,
+    statuses: &[ClickUpStatus],
+    report: &mut ExportReport<'_>,
+) -> Option<String> {
+    let dry_run = report.dry_run;
+    let list = report.list;
+
+    for epic in &mut backlog.epics {
+        if taskai_core::clickup_task_id(&epic.external_refs).is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        let mut item = ExportedItem {
+            id: epic.id.clone(),
+            title: epic.title.clone(),
+            parent: None,
+            clickup_id: None,
+        };
+        if !dry_run {
+            let data = serde_json::json!({ "name": epic.title.trim() });
+            match client.create_task(list, &data).await {
+                Ok(task) => {
+                    epic.external_refs
+                        .push(taskai_core::clickup_ref(&task, None));
+                    item.clickup_id = Some(task.id);
+                }
+                Err(err) => {
+                    return Some(format!(
+                        "Error creating the ClickUp task of {}: {}",
+                        epic.id, err
+                    ))
+                }
+            }
+        }
+        report.epics.push(item);
+    }
+
+    let pending: Vec<(String, Option<String>, serde_json::Value)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::clickup_task_id(&task.external_refs).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| {
+            let parent = epic.and_then(|e| taskai_core::clickup_task_id(&e.external_refs));
+            let status = taskai_core::clickup_status_for(&task.state, statuses);
+            (
+                task.id.clone(),
+                epic.map(|e| e.id.clone()),
+                taskai_core::clickup_task_data(task, parent, status),
+            )
+        })
+        .collect();
+    let mut created = Vec::new();
+    for (task_id, epic_id, data) in pending {
+        let mut item = ExportedItem {
+            id: task_id.clone(),
+            title: data["name"].as_str().unwrap_or_default().to_string(),
+            parent: epic_id,
+            clickup_id: None,
+        };
+        if !dry_run {
+            match client.create_task(list, &data).await {
+                Ok(task) => {
+                    if let Some(backlog_task) = backlog.find_task_mut(&task_id) {
+                        let synced_at = backlog_task.updated_at.max(task.updated_at());
+                        backlog_task
+                            .external_refs
+                            .push(taskai_core::clickup_ref(&task, synced_at));
+                    }
+                    item.clickup_id = Some(task.id);
+                }
+                Err(err) => {
+                    return Some(format!(
+                        "Error creating the ClickUp task of {}: {}",
+                        task_id, err
+                    ))
+                }
+            }
+        }
+        report.tasks.push(item);
+        created.push(task_id);
+    }
+
+    // Dependencies between tasks exported by an earlier run are already set
+    let id_of = |backlog: &Backlog, id: &str| {
+        let task = backlog.find_task(id)?;
+        taskai_core::clickup_task_id(&task.external_refs).map(str::to_string)
+    };
+    let dependencies: Vec<(String, Vec<String>)> = backlog
+        .all_tasks()
+        .iter()
+        .map(|t| {
+            let involved = t
+                .depends
+                .iter()
+                .filter(|dep| created.contains(&t.id) || created.contains(dep))
+                .filter(|dep| dry_run || id_of(backlog, dep).is_some())
+                .cloned()
+                .collect::<Vec<_>>();
+            (t.id.clone(), involved)
+        })
+        .filter(|(_, deps)| !deps.is_empty())
+        .collect();
+    for (task_id, deps) in dependencies {
+        if !dry_run {
+            let Some(clickup_id) = id_of(backlog, &task_id) else {
+                continue;
+            };
+            for dep in &deps {
+                let Some(depends_on) = id_of(backlog, dep) else {
+                    continue;
+                };
+                if let Err(err) = client.add_dependency(&clickup_id, &depends_on).await {
+                    return Some(format!(
+                        "Error setting the dependencies of {}: {}",
+                        task_id, err
+                    ));
+                }
+            }
+        }
+        report.dependencies.push((task_id, deps));
+    }
+    None
+}
+
+/// Executes the "import clickup" command: brings the tasks of the list `list` into the backlog.
+///
+/// ClickUp tasks already tracked by a task update its title and state, and the others become new
+/// tasks, with their state from their status. Top-level ClickUp tasks with subtasks become epics.
+/// Dependencies between tasks of the list become dependencies. The backlog file is created if it
+/// does not exist yet. Requires `CLICKUP_API_TOKEN`.
+pub async fn import(backlog_file: &Path, list: Option<String>, format: Format) {
+    let list = list_id(backlog_file, list);
+    let client = ClickUpClient::from_env();
+    if !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set CLICKUP_API_TOKEN to a personal token of a member of the list.",
+        );
+    }
+    // Fetched before locking the backlog, since a large list takes a while
+    let clickup_list = match client.get_list(&list).await {
+        Ok(clickup_list) => clickup_list,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error fetching the list {}: {}", list, err),
+        ),
+    };
+    let tasks = match client.list_tasks(&list).await {
+        Ok(tasks) => tasks,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listing the tasks of {}: {}", list, err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: clickup_list.name.clone(),
+            ..Default::default()
+        }
+    };
+
+    let summary = taskai_core::import_clickup_tasks(&mut backlog, &tasks, chrono::Utc::now());
+    if !summary.added.is_empty() || !summary.updated.is_empty() || !summary.new_epics.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &summary);
+}
+
+/// Returns the list given on the command line or in the configuration, exiting with a usage error
+/// if there is none.
+pub fn list_id(backlog_file: &Path, list: Option<String>) -> String {
+    match list.or_else(|| store::config(backlog_file).clickup.list) {
+        Some(list) => list,
+        None => error::fail(
+            ErrorKind::Usage,
+            "Pass --list or set clickup.list in .taskai.toml.",
+        ),
+    }
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.epics.is_empty() && self.tasks.is_empty() {
+            println!("No epics or tasks to export to list {}.", self.list);
+        } else {
+            println!(
+                "{} {} epic(s) and {} task(s) in list {}:",
+                if self.dry_run {
+                    "Would create"
+                } else {
+                    "Created"
+                },
+                self.epics.len(),
+                self.tasks.len(),
+                self.list
+            );
+        }
+        for item in self.epics.iter().chain(&self.tasks) {
+            match &item.clickup_id {
+                Some(id) => println!("  {} -> {}", item.id, id),
+                None => println!("  {}: {}", item.id, item.title),
+            }
+        }
+        for (task, deps) in &self.dependencies {
+            println!(
+                "{} {} as waiting on {}",
+                if self.dry_run { "Would mark" } else { "Marked" },
+                task,
+                deps.join(", ")
+            );
+        }
+        if self.skipped > 0 {
+            println!(
+                "Skipped {} epic(s) and task(s) already exported.",
+                self.skipped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the plan of a dry run: a task per epic, the tasks not exported yet as subtasks of
+    /// their epic, and the dependencies involving them.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+    external_refs:
+      - system: clickup
+        id: "86a1"
+  - id: T-2
+    title: Second
+    depends: [T-1]
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Endpoint
+        depends: [T-2]
+"#,
+        )
+        .unwrap();
+        let mut report = ExportReport {
+            list: "901",
+            dry_run: true,
+            epics: Vec::new(),
+            tasks: Vec::new(),
+            dependencies: Vec::new(),
+            skipped: 0,
+        };
+
+        let failure =
+            create_tasks(&ClickUpClient::from_env(), &mut backlog, &[], &mut report).await;
+        assert!(failure.is_none());
+
+        fn plan(items: &[ExportedItem]) -> Vec<(&str, &str, Option<&str>)> {
+            items
+                .iter()
+                .map(|i| (i.id.as_str(), i.title.as_str(), i.parent.as_deref()))
+                .collect()
+        }
+        assert_eq!(plan(&report.epics), vec![("E-1", "API", None)]);
+        assert_eq!(
+            plan(&report.tasks),
+            vec![("T-2", "Second", None), ("E-1-1", "Endpoint", Some("E-1"))]
+        );
+        assert_eq!(
+            report.dependencies,
+            vec![
+                ("T-2".to_string(), vec!["T-1".to_string()]),
+                ("E-1-1".to_string(), vec!["T-2".to_string()]),
+            ]
+        );
+        assert_eq!(report.skipped, 1);
+        assert!(backlog.epics[0].external_refs.is_empty());
+    }
+}
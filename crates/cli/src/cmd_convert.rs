@@ -0,0 +1,104 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+
+/// The backlog written by `convert`.
+#[derive(Serialize)]
+struct ConvertReport {
+    from: String,
+    to: String,
+    /// `directory` for a backlog directory, `file` for a single YAML file.
+    storage: &'static str,
+    tasks: usize,
+}
+
+/// Executes the "convert" command: writes the backlog to `to` in the other storage mode, as a
+/// single YAML file when `to` has a `.yaml` or `.yml` extension, and as a backlog directory (an
+/// index and a Markdown file per task) otherwise.
+///
+/// The original backlog is left in place, to be removed once the new one is checked in. Refuses to
+/// overwrite an existing file or directory.
+pub fn execute(backlog_file: &Path, to: &Path, format: Format) {
+    if to.exists() {
+        error::fail(
+            ErrorKind::Usage,
+            format!("{} already exists; pick a new path.", to.display()),
+        );
+    }
+    let backlog = store::load(backlog_file);
+    let as_file = to
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    if as_file {
+        store::save(to, &backlog);
+    } else if let Err(err) = store::write_dir(to, &backlog) {
+        error::fail(ErrorKind::Other, err);
+    }
+
+    let report = ConvertReport {
+        from: backlog_file.display().to_string(),
+        to: to.display().to_string(),
+        storage: if as_file { "file" } else { "directory" },
+        tasks: backlog.all_tasks().len() + backlog.archive.len(),
+    };
+    output::print(format, &report);
+}
+
+impl Render for ConvertReport {
+    fn print_text(&self) {
+        let storage = match self.storage {
+            "file" => "a YAML file",
+            _ => "a directory of Markdown task files",
+        };
+        println!(
+            "Wrote {} task(s) from {} to {}, as {}.",
+            self.tasks, self.from, self.to, storage
+        );
+        println!(
+            "{} is left as is; remove it and point --backlog or TASKAI_BACKLOG at {} if needed.",
+            self.from, self.to
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that a backlog converted to a directory loads, saves and converts back unchanged.
+    #[test]
+    fn test_convert_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            "project: test\nepics:\n- id: E-1\n  title: API\n  tasks:\n  - id: E-1-1\n    \
+             title: Routes\n    depends: [T-1]\ntasks:\n- id: T-1\n  title: Setup\n  \
+             description: Create the workspace.\n",
+        )
+        .unwrap();
+        let backlog_dir = dir.path().join("backlog");
+
+        execute(&backlog_file, &backlog_dir, Format::Json);
+        assert!(store::is_backlog_dir(&backlog_dir));
+        let task_file = backlog_dir.join("tasks/T-1.md");
+        assert!(fs::read_to_string(&task_file)
+            .unwrap()
+            .ends_with("---\n\nCreate the workspace.\n"));
+
+        let mut backlog = store::load(&backlog_dir);
+        assert_eq!(backlog.epics[0].tasks[0].depends, vec!["T-1"]);
+        backlog.tasks.remove(0);
+        store::save(&backlog_dir, &backlog);
+        assert!(!task_file.exists());
+
+        let round_trip = dir.path().join("round-trip.yaml");
+        execute(&backlog_dir, &round_trip, Format::Json);
+        let backlog = store::load(&round_trip);
+        assert!(backlog.tasks.is_empty());
+        assert_eq!(backlog.epics[0].tasks[0].title, "Routes");
+    }
+}
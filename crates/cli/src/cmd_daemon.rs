@@ -0,0 +1,477 @@
+use crate::error::{self, ErrorKind};
+use crate::store;
+use crate::watch;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use taskai_core::ReadyFilter;
+use taskai_schema::{Backlog, Task, TaskState};
+use tokio::sync::broadcast;
+
+/// Executes the "daemon" command: keeps the backlog parsed in memory, reloading it whenever the
+/// file changes, and answers JSON-RPC requests on a unix socket, so that editor plugins and UIs
+/// don't pay the parse cost on every query.
+///
+/// Requests and responses are JSON-RPC messages, one per line. The methods are `list`, `next`,
+/// `get` and `mutate`; after `subscribe`, the connection also receives a `changed` notification
+/// carrying the new version every time the backlog changes. The socket is removed on Ctrl-C.
+#[cfg(unix)]
+pub async fn serve(backlog_file: &Path, socket: &Path) {
+    use tokio::net::{UnixListener, UnixStream};
+
+    if socket.exists() {
+        if UnixStream::connect(socket).await.is_ok() {
+            error::fail(
+                ErrorKind::Usage,
+                format!("A daemon is already listening on {}.", socket.display()),
+            );
+        }
+        // Left behind by a daemon that did not shut down cleanly
+        let _ = fs::remove_file(socket);
+    }
+    let listener = match UnixListener::bind(socket) {
+        Ok(listener) => listener,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listening on {}: {}", socket.display(), err),
+        ),
+    };
+
+    let daemon = Arc::new(Daemon::new(backlog_file));
+    daemon.watch();
+
+    eprintln!(
+        "Serving {} on {} (Ctrl-C to stop)...",
+        backlog_file.display(),
+        socket.display()
+    );
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(connection(Arc::clone(&daemon), stream));
+                }
+                Err(err) => tracing::warn!(%err, "error accepting a connection"),
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    let _ = fs::remove_file(socket);
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_backlog_file: &Path, _socket: &Path) {
+    error::fail(
+        ErrorKind::Usage,
+        "The daemon serves a unix socket and is only available on unix systems.",
+    );
+}
+
+/// Serves the requests of one client until it disconnects.
+#[cfg(unix)]
+async fn connection(daemon: Arc<Daemon>, stream: tokio::net::UnixStream) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::mpsc;
+
+    let (reader, mut writer) = stream.into_split();
+    // Responses and change notifications share the connection, so they go through one writer
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let line = format!("{}\n", message);
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    let mut subscribed = false;
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => message,
+            Err(err) => {
+                let _ = tx.send(failure(
+                    Value::Null,
+                    -32700,
+                    format!("Parse error: {}", err),
+                ));
+                continue;
+            }
+        };
+        let Some(id) = message.get("id").cloned() else {
+            continue;
+        };
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            Some("subscribe") => {
+                if !subscribed {
+                    subscribed = true;
+                    let mut changes = daemon.changes.subscribe();
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        loop {
+                            let version = match changes.recv().await {
+                                Ok(version) => version,
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => break,
+                            };
+                            let notification = json!({
+                                "jsonrpc": "2.0",
+                                "method": "changed",
+                                "params": { "version": version },
+                            });
+                            if tx.send(notification).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                Ok(json!({ "version": daemon.version() }))
+            }
+            Some(method) => daemon.handle(method, params),
+            None => Err((-32600, "Invalid request: missing method".to_string())),
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(id, code, message),
+        };
+        if tx.send(response).is_err() {
+            break;
+        }
+    }
+}
+
+/// The backlog kept in memory by the daemon, also behind `taskai serve`.
+pub(crate) struct Daemon {
+    backlog_file: PathBuf,
+    state: Mutex<State>,
+    /// Publishes the new version every time the backlog changes.
+    changes: broadcast::Sender<u64>,
+}
+
+struct State {
+    /// Content of the backlog file, to tell real changes from events that leave it as is.
+    source: String,
+    /// The parsed backlog, or why it cannot be parsed.
+    backlog: Result<Backlog, String>,
+    /// Incremented every time the content of the file changes.
+    version: u64,
+}
+
+/// Parameters of the `list` method.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ListParams {
+    state: Option<TaskState>,
+    epic: Option<String>,
+    tag: Option<String>,
+    assignee: Option<String>,
+}
+
+/// Parameters of the `next` method.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NextParams {
+    epic: Option<String>,
+    tag: Option<String>,
+    assignee: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct GetParams {
+    id: String,
+}
+
+/// Parameters of the `mutate` method; the fields left out are kept as they are.
+#[derive(Deserialize)]
+struct MutateParams {
+    id: String,
+    state: Option<TaskState>,
+    /// Person to assign the task to; an empty string unassigns it.
+    assignee: Option<String>,
+    /// Note added to the task.
+    note: Option<String>,
+}
+
+impl Daemon {
+    pub(crate) fn new(backlog_file: &Path) -> Self {
+        let (source, backlog) = read(backlog_file);
+        let (changes, _) = broadcast::channel(16);
+        Daemon {
+            backlog_file: backlog_file.to_path_buf(),
+            state: Mutex::new(State {
+                source,
+                backlog,
+                version: 0,
+            }),
+            changes,
+        }
+    }
+
+    /// Reloads the backlog from a background thread whenever the file changes.
+    pub(crate) fn watch(self: &Arc<Self>) {
+        let (watcher, events) = watch::start(&self.backlog_file);
+        let watched = Arc::clone(self);
+        std::thread::spawn(move || {
+            let _watcher = watcher;
+            while watch::wait(&watched.backlog_file, &events) {
+                watched.reload();
+            }
+        });
+    }
+
+    /// Reads the backlog file again, bumping the version and notifying the subscribers when its
+    /// content changed.
+    pub(crate) fn reload(&self) {
+        let (source, backlog) = read(&self.backlog_file);
+        let version = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.source == source {
+                return;
+            }
+            state.source = source;
+            state.backlog = backlog;
+            state.version += 1;
+            state.version
+        };
+        tracing::debug!(version, "backlog reloaded");
+        let _ = self.changes.send(version);
+    }
+
+    pub(crate) fn version(&self) -> u64 {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).version
+    }
+
+    /// Handles a request, returning its result or a JSON-RPC error code and message.
+    pub(crate) fn handle(&self, method: &str, params: Value) -> Result<Value, (i64, String)> {
+        match method {
+            "ping" => Ok(json!({})),
+            "list" => {
+                let params: ListParams = parameters(params)?;
+                self.query(|backlog| {
+                    backlog
+                        .all_tasks()
+                        .into_iter()
+                        .filter(|t| {
+                            params
+                                .state
+                                .as_ref()
+                                .is_none_or(|s| same_state(s, &t.state))
+                        })
+                        .filter(|t| params.tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+                        .filter(|t| {
+                            params.assignee.is_none()
+                                || t.assignee.as_ref() == params.assignee.as_ref()
+                        })
+                        .filter(|t| {
+                            params
+                                .epic
+                                .as_ref()
+                                .is_none_or(|epic| epic_of(backlog, &t.id) == Some(epic.as_str()))
+                        })
+                        .map(|t| task_json(backlog, t))
+                        .collect()
+                })
+            }
+            "next" => {
+                let params: NextParams = parameters(params)?;
+                let filter = ReadyFilter {
+                    epic: params.epic,
+                    tag: params.tag,
+                    assignee: params.assignee,
+                };
+                self.query(|backlog| {
+                    taskai_core::filter_ready_tasks(backlog, &filter)
+                        .into_iter()
+                        .take(params.limit.unwrap_or(usize::MAX))
+                        .map(|t| task_json(backlog, t))
+                        .collect()
+                })
+            }
+            "get" => {
+                let GetParams { id } = parameters(params)?;
+                let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let backlog = state.backlog.as_ref().map_err(|e| (-32000, e.clone()))?;
+                let task = backlog.find_task(&id).ok_or_else(|| not_found(&id))?;
+                Ok(json!({ "version": state.version, "task": task_json(backlog, task) }))
+            }
+            "mutate" => self.mutate(parameters(params)?),
+            _ => Err((-32601, format!("Method not found: {}", method))),
+        }
+    }
+
+    /// Tells whether the backlog in memory has a task with the ID `id`; false if it cannot be
+    /// parsed.
+    pub(crate) fn has_task(&self, id: &str) -> bool {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .backlog
+            .as_ref()
+            .is_ok_and(|backlog| backlog.find_task(id).is_some())
+    }
+
+    /// Calls `f` with the backlog in memory, or returns the error it could not be parsed with.
+    pub(crate) fn with_backlog<R>(&self, f: impl FnOnce(&Backlog) -> R) -> Result<R, String> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.backlog.as_ref().map(f).map_err(String::clone)
+    }
+
+    /// Returns the tasks picked by `tasks` from the backlog in memory, with its version.
+    fn query(&self, tasks: impl FnOnce(&Backlog) -> Vec<Value>) -> Result<Value, (i64, String)> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let backlog = state.backlog.as_ref().map_err(|e| (-32000, e.clone()))?;
+        Ok(json!({ "version": state.version, "tasks": tasks(backlog) }))
+    }
+
+    /// Changes a task in the backlog file, then reloads it so that the subscribers are notified
+    /// right away rather than when the watcher catches up.
+    fn mutate(&self, params: MutateParams) -> Result<Value, (i64, String)> {
+        let now = chrono::Utc::now();
+        let lock = store::lock(&self.backlog_file);
+        let mut backlog = store::try_load(&self.backlog_file).map_err(|e| (-32000, e))?;
+        let task = backlog
+            .find_task_mut(&params.id)
+            .ok_or_else(|| not_found(&params.id))?;
+        if let Some(state) = params.state {
+            task.set_state(state, now);
+        }
+        if let Some(assignee) = params.assignee {
+            task.assignee = (!assignee.is_empty()).then_some(assignee);
+            task.updated_at = Some(now);
+        }
+        if let Some(note) = &params.note {
+            task.add_note(note, now);
+        }
+        store::try_save(&self.backlog_file, &backlog).map_err(|e| (-32000, e))?;
+        drop(lock);
+
+        self.reload();
+        let task = backlog
+            .find_task(&params.id)
+            .ok_or_else(|| not_found(&params.id))?;
+        Ok(json!({ "version": self.version(), "task": task_json(&backlog, task) }))
+    }
+}
+
+/// Reads the backlog file, returning its content and the parsed backlog.
+fn read(backlog_file: &Path) -> (String, Result<Backlog, String>) {
+    match store::try_read(backlog_file) {
+        Ok(source) => {
+            let backlog = serde_yaml::from_str(&source)
+                .map_err(|err| format!("Error parsing backlog file: {}", err));
+            (source, backlog)
+        }
+        Err(err) => (String::new(), Err(err)),
+    }
+}
+
+/// Returns the task as JSON, with the ID of its epic.
+fn task_json(backlog: &Backlog, task: &Task) -> Value {
+    let mut value = serde_json::to_value(task).unwrap_or(Value::Null);
+    if let (Some(epic), Some(fields)) = (epic_of(backlog, &task.id), value.as_object_mut()) {
+        fields.insert("epic".to_string(), json!(epic));
+    }
+    value
+}
+
+fn epic_of<'a>(backlog: &'a Backlog, task_id: &str) -> Option<&'a str> {
+    backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task_id))
+        .map(|e| e.id.as_str())
+}
+
+fn same_state(a: &TaskState, b: &TaskState) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn parameters<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    // Methods without required parameters may be called without any
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| (-32602, format!("Invalid params: {}", e)))
+}
+
+fn not_found(id: &str) -> (i64, String) {
+    (
+        -32000,
+        format!("Task with ID '{}' not found in the backlog.", id),
+    )
+}
+
+fn failure(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that queries are answered from memory and that mutations reach the file and bump the
+    /// version.
+    #[test]
+    fn test_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            r#"
+project: test
+epics:
+  - id: E-1
+    title: Core
+    tasks:
+      - id: T-1
+        title: Parser
+      - id: T-2
+        title: Interpreter
+        depends: [T-1]
+tasks:
+  - id: T-3
+    title: Docs
+    tags: [docs]
+"#,
+        )
+        .unwrap();
+        let daemon = Daemon::new(&backlog_file);
+        let mut changes = daemon.changes.subscribe();
+
+        let listed = daemon
+            .handle("list", json!({ "epic": "E-1", "state": "Todo" }))
+            .unwrap();
+        assert_eq!(listed["tasks"].as_array().unwrap().len(), 2);
+        assert_eq!(listed["tasks"][1]["epic"], "E-1");
+        let next = daemon.handle("next", json!({ "tag": "docs" })).unwrap();
+        assert_eq!(next["tasks"][0]["id"], "T-3");
+
+        let mutated = daemon
+            .handle(
+                "mutate",
+                json!({ "id": "T-1", "state": "Done", "note": "Merged" }),
+            )
+            .unwrap();
+        assert_eq!(mutated["version"], 1);
+        assert_eq!(mutated["task"]["state"], "Done");
+        assert_eq!(changes.try_recv().unwrap(), 1);
+        assert!(store::read(&backlog_file).contains("Merged"));
+
+        let next = daemon.handle("next", json!({ "epic": "E-1" })).unwrap();
+        assert_eq!(next["tasks"][0]["id"], "T-2");
+        assert_eq!(daemon.handle("ping", Value::Null).unwrap(), json!({}));
+        let missing = daemon.handle("get", json!({ "id": "T-9" })).unwrap_err();
+        assert_eq!(missing.0, -32000);
+        assert_eq!(daemon.handle("nope", Value::Null).unwrap_err().0, -32601);
+    }
+}
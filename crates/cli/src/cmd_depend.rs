@@ -0,0 +1,175 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::{Backlog, Task};
+
+/// Dependencies of a task after a "depend" subcommand.
+#[derive(Serialize)]
+struct DependencyUpdate<'a> {
+    id: &'a str,
+    depends: Vec<String>,
+    /// Message printed in text and markdown output.
+    #[serde(skip)]
+    message: String,
+}
+
+/// Executes the "depend add" command: makes a task depend on other tasks.
+///
+/// Every task must exist, and the new edges are refused, leaving the file untouched, if they would
+/// create a dependency cycle. Dependencies the task already has are left as they are.
+pub fn add(backlog_file: &Path, task_id: &str, on: &[String], format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    for dep_id in on {
+        if backlog.find_task(dep_id).is_none() {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", dep_id),
+            );
+        }
+        if dep_id == task_id {
+            error::fail(
+                ErrorKind::Validation,
+                format!("Task {} cannot depend on itself.", task_id),
+            );
+        }
+    }
+
+    let task = find_task(&mut backlog, task_id);
+    let mut added: Vec<String> = Vec::new();
+    for dep_id in on {
+        if !task.depends.contains(dep_id) && !added.contains(dep_id) {
+            added.push(dep_id.clone());
+        }
+    }
+    if added.is_empty() {
+        let update = DependencyUpdate {
+            id: task_id,
+            depends: task.depends.clone(),
+            message: format!("Task {} already depends on {}.", task_id, on.join(", ")),
+        };
+        output::print(format, &update);
+        return;
+    }
+    task.depends.extend(added.iter().cloned());
+    task.updated_at = Some(chrono::Utc::now());
+    let depends = task.depends.clone();
+
+    if let Some(cycle) = backlog.find_cycle() {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "Making {} depend on {} would create a dependency cycle: {}",
+                task_id,
+                added.join(", "),
+                cycle.join(" -> ")
+            ),
+        );
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &DependencyUpdate {
+            id: task_id,
+            depends,
+            message: format!("Task {} now depends on {}.", task_id, added.join(", ")),
+        },
+    );
+}
+
+/// Executes the "depend rm" command: removes dependencies of a task.
+///
+/// Fails if the task does not depend on one of the given tasks. Dependencies on tasks missing from
+/// the backlog can be removed too.
+pub fn remove(backlog_file: &Path, task_id: &str, on: &[String], format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let task = find_task(&mut backlog, task_id);
+    if let Some(dep_id) = on.iter().find(|dep_id| !task.depends.contains(dep_id)) {
+        error::fail(
+            ErrorKind::Validation,
+            format!("Task {} does not depend on {}.", task_id, dep_id),
+        );
+    }
+    task.depends.retain(|dep_id| !on.contains(dep_id));
+    task.updated_at = Some(chrono::Utc::now());
+    let depends = task.depends.clone();
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &DependencyUpdate {
+            id: task_id,
+            depends,
+            message: format!("Task {} no longer depends on {}.", task_id, on.join(", ")),
+        },
+    );
+}
+
+impl Render for DependencyUpdate<'_> {
+    fn print_text(&self) {
+        println!("{}", self.message);
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+fn find_task<'a>(backlog: &'a mut Backlog, task_id: &str) -> &'a mut Task {
+    match backlog.find_task_mut(task_id) {
+        Some(task) => task,
+        None => {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", task_id),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that dependencies are added and removed, keeping the ones already there.
+    #[test]
+    fn test_add_and_remove() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: Models
+  - id: T-2
+    title: API
+    depends: [T-1]
+  - id: T-3
+    title: UI
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        add(
+            file.path(),
+            "T-3",
+            &["T-1".to_string(), "T-2".to_string()],
+            Format::Table,
+        );
+        assert_eq!(store::load(file.path()).tasks[2].depends, ["T-1", "T-2"]);
+
+        remove(file.path(), "T-3", &["T-1".to_string()], Format::Table);
+        let backlog = store::load(file.path());
+        assert_eq!(backlog.tasks[2].depends, ["T-2"]);
+        assert!(backlog.validate().is_ok());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::output::{self, Color, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{BacklogDiff, TaskChange};
+
+/// Number of tasks added, removed and changed between two backlogs, printed with `--stat`.
+#[derive(Serialize)]
+struct DiffStat {
+    added: usize,
+    removed: usize,
+    changed: usize,
+}
+
+/// Executes the "diff" command: compares two backlog files task by task and prints the semantic differences.
+///
+/// Tasks are matched by ID, so regenerated or reordered backlogs only show real changes. With
+/// `stat`, only the number of added, removed and changed tasks is printed.
+pub fn execute(old_file: &Path, new_file: &Path, stat: bool, format: Format) {
+    let old = store::load(old_file);
+    let new = store::load(new_file);
+
+    let diff = taskai_core::diff_backlogs(&old, &new);
+    if stat {
+        let stat = DiffStat {
+            added: diff.added.len(),
+            removed: diff.removed.len(),
+            changed: diff.modified.len(),
+        };
+        output::print(format, &stat);
+    } else {
+        output::print(format, &diff);
+    }
+}
+
+impl Render for DiffStat {
+    fn print_text(&self) {
+        println!(
+            "{} added, {} removed, {} changed",
+            output::paint(&self.added.to_string(), Color::Green),
+            output::paint(&self.removed.to_string(), Color::Red),
+            output::paint(&self.changed.to_string(), Color::Yellow)
+        );
+    }
+
+    fn print_markdown(&self) {
+        println!(
+            "**{}** added, **{}** removed, **{}** changed",
+            self.added, self.removed, self.changed
+        );
+    }
+}
+
+impl Render for BacklogDiff {
+    fn print_text(&self) {
+        if self.is_empty() {
+            println!("No differences.");
+            return;
+        }
+
+        if !self.added.is_empty() {
+            println!("Added:");
+            for task in &self.added {
+                let line = format!("  + {}: {}", task.id, task.title);
+                println!("{}", output::paint(&line, Color::Green));
+            }
+            println!();
+        }
+
+        if !self.removed.is_empty() {
+            println!("Removed:");
+            for task in &self.removed {
+                let line = format!("  - {}: {}", task.id, task.title);
+                println!("{}", output::paint(&line, Color::Red));
+            }
+            println!();
+        }
+
+        if !self.modified.is_empty() {
+            println!("Modified:");
+            for change in &self.modified {
+                let line = format!("  ~ {}: {}", change.id, change.title);
+                println!("{}", output::paint(&line, Color::Yellow));
+
+                for detail in change_details(change) {
+                    println!("      {}", detail);
+                }
+            }
+            println!();
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.is_empty() {
+            println!("No differences.");
+            return;
+        }
+
+        let sections = [("Added", &self.added), ("Removed", &self.removed)];
+        for (heading, tasks) in sections {
+            if !tasks.is_empty() {
+                println!("### {}", heading);
+                println!();
+                for task in tasks {
+                    println!("- **{}**: {}", task.id, task.title);
+                }
+                println!();
+            }
+        }
+
+        if !self.modified.is_empty() {
+            println!("### Modified");
+            println!();
+            for change in &self.modified {
+                println!("- **{}**: {}", change.id, change.title);
+                for detail in change_details(change) {
+                    println!("  - {}", detail);
+                }
+            }
+        }
+    }
+}
+
+/// Describes each change of a modified task on its own line.
+fn change_details(change: &TaskChange) -> Vec<String> {
+    let mut details = Vec::new();
+
+    if let Some(state) = &change.state {
+        details.push(format!("state: {:?} -> {:?}", state.from, state.to));
+    }
+
+    if !change.depends_added.is_empty() || !change.depends_removed.is_empty() {
+        let deps: Vec<String> = change
+            .depends_added
+            .iter()
+            .map(|d| format!("+{}", d))
+            .chain(change.depends_removed.iter().map(|d| format!("-{}", d)))
+            .collect();
+        details.push(format!("depends: {}", deps.join(" ")));
+    }
+
+    if let Some(epic) = &change.epic {
+        details.push(format!(
+            "epic: {} -> {}",
+            epic.from.as_deref().unwrap_or("(none)"),
+            epic.to.as_deref().unwrap_or("(none)")
+        ));
+    }
+
+    for field in &change.fields {
+        details.push(format!("{}: {} -> {}", field.field, field.old, field.new));
+    }
+
+    details
+}
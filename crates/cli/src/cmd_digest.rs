@@ -0,0 +1,151 @@
+use crate::error::{self, ErrorKind};
+use crate::html;
+use crate::output::{self, Format, Render};
+use crate::store;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::path::Path;
+use taskai_core::{Digest, DigestTask, EmailConfig};
+
+/// Executes the "digest" command: summarizes the tasks completed over the last day, or the last
+/// week with `weekly` set, the tasks that became ready and the overdue ones.
+///
+/// With `email` set, the digest is rendered as HTML and sent to the recipients of the `[email]`
+/// configuration, or written to stdout for an external mailer when no SMTP server is configured
+/// or `stdout` is set.
+pub async fn execute(backlog_file: &Path, weekly: bool, email: bool, stdout: bool, format: Format) {
+    let backlog = store::load(backlog_file);
+    let days = if weekly { 7 } else { 1 };
+    let since = chrono::Utc::now() - chrono::Duration::days(days);
+    let today = chrono::Local::now().date_naive();
+    let digest = taskai_core::digest(&backlog, since, today);
+    if !email {
+        output::print(format, &digest);
+        return;
+    }
+
+    let title = format!(
+        "{} digest for {}",
+        if weekly { "Weekly" } else { "Daily" },
+        backlog.project
+    );
+    let html = html::digest(&digest, &title);
+    let config = store::config(backlog_file).email;
+    if stdout || config.smtp_host.is_none() {
+        print!("{}", html);
+        return;
+    }
+    match send(&config, &title, html).await {
+        Ok(()) => eprintln!("Digest sent to {}.", config.to.join(", ")),
+        Err(err) => error::fail(ErrorKind::Other, err),
+    }
+}
+
+/// Sends the HTML digest through the SMTP server of the configuration. The password comes from
+/// `TASKAI_SMTP_PASSWORD`.
+async fn send(config: &EmailConfig, subject: &str, html: String) -> Result<(), String> {
+    let host = config.smtp_host.as_deref().unwrap_or_default();
+    let Some(from) = config.from.as_ref().or(config.username.as_ref()) else {
+        return Err("Set email.from to the sender of the digest.".to_string());
+    };
+    if config.to.is_empty() {
+        return Err("Set email.to to the recipients of the digest.".to_string());
+    }
+    let mailbox = |address: &String| {
+        address
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid email address '{}': {}", address, e))
+    };
+    let mut message = Message::builder().from(mailbox(from)?).subject(subject);
+    for to in &config.to {
+        message = message.to(mailbox(to)?);
+    }
+    let message = message
+        .header(ContentType::TEXT_HTML)
+        .body(html)
+        .map_err(|e| format!("Error building the digest email: {}", e))?;
+
+    let mut transport = match config.tls.as_deref().unwrap_or("starttls") {
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            host,
+        )),
+        other => {
+            return Err(format!(
+                "Unknown email.tls mode '{}'; use starttls, tls or none.",
+                other
+            ))
+        }
+    }
+    .map_err(|e| format!("Invalid SMTP server '{}': {}", host, e))?;
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let Some(username) = &config.username {
+        let password = std::env::var("TASKAI_SMTP_PASSWORD").unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    transport
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Error sending the digest through {}: {}", host, e))
+}
+
+impl Render for Digest {
+    fn print_text(&self) {
+        println!(
+            "Digest for {} since {}",
+            self.project,
+            self.since
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        );
+        let sections = [
+            ("Completed", &self.completed),
+            ("Newly ready", &self.newly_ready),
+            ("Overdue", &self.overdue),
+        ];
+        for (heading, tasks) in sections {
+            println!("{} ({}):", heading, tasks.len());
+            for task in tasks {
+                println!("  {}", line(task));
+            }
+        }
+    }
+}
+
+fn line(task: &DigestTask) -> String {
+    let mut line = format!("{} {}", task.id, task.title);
+    if let Some(assignee) = &task.assignee {
+        line.push_str(&format!(" ({})", assignee));
+    }
+    if let Some(due) = task.due {
+        line.push_str(&format!(", due {}", due));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that sending without recipients fails before connecting to the server.
+    #[tokio::test]
+    async fn test_send_requires_recipients() {
+        let config = EmailConfig {
+            smtp_host: Some("smtp.invalid".to_string()),
+            from: Some("TaskAI <taskai@example.com>".to_string()),
+            ..Default::default()
+        };
+
+        let err = send(&config, "Daily digest", "<p>Hi</p>".to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Set email.to to the recipients of the digest.");
+    }
+}
@@ -1,87 +1,125 @@
-use std::fs;
+use crate::error::{self, ErrorKind};
+use crate::notifications;
+use crate::output::{self, Format, TaskUpdate};
+use crate::store;
+use std::collections::HashSet;
 use std::path::Path;
-use std::process;
-use taskai_schema::{Backlog, TaskState};
+use taskai_core::NotifyEvent;
+use taskai_schema::TaskState;
 
-/// Marks a task as done in the backlog file given its ID.
+/// Marks tasks as done in the backlog file given their IDs, and every task of the given epics.
 ///
-/// This function reads the backlog YAML file, searches for the task with the specified `task_id`
-/// (either as a standalone task or within an epic), marks it as done, and writes the updated
-/// backlog back to the file. If the task is not found or if any file operation fails, the process exits with an error.
-pub fn execute(backlog_file: &Path, task_id: &str) {
-    let content = match fs::read_to_string(backlog_file) {
-        Ok(c) => c,
-        Err(err) => {
-            eprintln!("Error reading backlog file: {}", err);
-            process::exit(1);
-        }
-    };
+/// All the tasks are updated in a single read-modify-write of the backlog file. Nothing is written
+/// if any task or epic is not found, in which case the process exits with an error. Tasks that are
+/// already done are left untouched. With `note` set, the note is added to every task newly marked as
+/// done, recording how the work was completed; a warning names the tasks that were already done.
+/// Tasks newly marked as done are then posted to the chat integrations configured for completions.
+pub async fn execute(
+    backlog_file: &Path,
+    task_ids: &[String],
+    epic_ids: &[String],
+    note: Option<&str>,
+    format: Format,
+) {
+    let lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
 
-    let mut backlog: Backlog = match serde_yaml::from_str(&content) {
-        Ok(b) => b,
-        Err(err) => {
-            eprintln!("Error parsing backlog file: {}", err);
-            process::exit(1);
+    let mut ids: Vec<String> = Vec::new();
+    for id in task_ids {
+        if backlog.find_task(id).is_none() {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", id),
+            );
         }
-    };
-
-    let mut found = false;
-
-    for task in &mut backlog.tasks {
-        if task.id == task_id {
-            task.state = TaskState::Done;
-            found = true;
-            break;
+        ids.push(id.clone());
+    }
+    for epic_id in epic_ids {
+        match backlog.epics.iter().find(|e| &e.id == epic_id) {
+            Some(epic) => ids.extend(epic.tasks.iter().map(|t| t.id.clone())),
+            None => {
+                error::fail(
+                    ErrorKind::NotFound,
+                    format!("Epic with ID '{}' not found in the backlog.", epic_id),
+                );
+            }
         }
     }
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
 
-    if !found {
-        for epic in &mut backlog.epics {
-            for task in &mut epic.tasks {
-                if task.id == task_id {
-                    task.state = TaskState::Done;
-                    found = true;
-                    break;
-                }
-            }
-            if found {
-                break;
+    let completed: Vec<String> = ids
+        .iter()
+        .filter(|id| {
+            backlog
+                .find_task(id)
+                .is_some_and(|t| !matches!(t.state, TaskState::Done))
+        })
+        .cloned()
+        .collect();
+    for id in &completed {
+        if let Some(task) = backlog.find_task_mut(id) {
+            task.set_state(TaskState::Done, now);
+            if let Some(note) = note {
+                task.add_note(note, now);
             }
         }
     }
-
-    if !found {
-        eprintln!("Task with ID '{}' not found in the backlog.", task_id);
-        process::exit(1);
+    if note.is_some() && completed.len() < ids.len() {
+        let already_done: Vec<&str> = ids
+            .iter()
+            .filter(|id| !completed.contains(id))
+            .map(String::as_str)
+            .collect();
+        eprintln!(
+            "Warning: note not added to task(s) already done: {}",
+            already_done.join(", ")
+        );
     }
 
-    match serde_yaml::to_string(&backlog) {
-        Ok(yaml) => {
-            if let Err(err) = fs::write(backlog_file, yaml) {
-                eprintln!("Error writing to backlog file: {}", err);
-                process::exit(1);
+    for id in &ids {
+        if let Some(task) = backlog.find_task(id) {
+            let unfinished = taskai_core::unfinished_dependencies(&backlog, task);
+            if !unfinished.is_empty() {
+                let dep_ids: Vec<&str> = unfinished.iter().map(|t| t.id.as_str()).collect();
+                eprintln!(
+                    "Warning: task {} depends on unfinished task(s): {}",
+                    id,
+                    dep_ids.join(", ")
+                );
             }
-            println!("Task {} marked as done.", task_id);
-        }
-        Err(err) => {
-            eprintln!("Error serializing backlog to YAML: {}", err);
-            process::exit(1);
         }
     }
+
+    store::save(backlog_file, &backlog);
+    drop(lock);
+
+    let updates: Vec<TaskUpdate> = ids
+        .iter()
+        .map(|id| TaskUpdate {
+            id,
+            state: &TaskState::Done,
+            message: format!("Task {} marked as done.", id),
+        })
+        .collect();
+    output::print(format, &updates);
+    notifications::tasks(backlog_file, &backlog, NotifyEvent::Done, &completed).await;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use std::io::Write;
+    use taskai_schema::Backlog;
     use tempfile::NamedTempFile;
-    use taskai_schema::{Backlog};
 
     /// Tests that a task can be marked as done in the backlog file.
-    #[test]
-    fn test_mark_done() {
+    #[tokio::test]
+    async fn test_mark_done() {
         let mut file = NamedTempFile::new().unwrap();
-        
+
         let test_yaml = r#"
 project: test-project
 tasks:
@@ -90,19 +128,132 @@ tasks:
     depends: []
     state: Todo
 "#;
-        
+
         file.write_all(test_yaml.as_bytes()).unwrap();
         file.flush().unwrap();
-        
-        execute(file.path(), "TEST-1");
-        
+
+        execute(
+            file.path(),
+            &["TEST-1".to_string()],
+            &[],
+            None,
+            Format::Table,
+        )
+        .await;
+
         let content = fs::read_to_string(file.path()).unwrap();
         let backlog: Backlog = serde_yaml::from_str(&content).unwrap();
-        
+
         assert_eq!(backlog.tasks.len(), 1);
         match backlog.tasks[0].state {
-            TaskState::Done => {},
+            TaskState::Done => {}
             _ => panic!("Task was not marked as done"),
         }
     }
-}
\ No newline at end of file
+
+    /// Tests that the note given with `--note` is added to the task marked as done.
+    #[tokio::test]
+    async fn test_mark_done_with_note() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"project: test-project\ntasks:\n  - id: TEST-1\n    title: Test Task\n")
+            .unwrap();
+        file.flush().unwrap();
+
+        execute(
+            file.path(),
+            &["TEST-1".to_string()],
+            &[],
+            Some("implemented via axum router"),
+            Format::Table,
+        )
+        .await;
+
+        let backlog = store::load(file.path());
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+        assert_eq!(backlog.tasks[0].notes.len(), 1);
+        assert_eq!(
+            backlog.tasks[0].notes[0].text,
+            "implemented via axum router"
+        );
+    }
+
+    /// Tests that a task already done keeps its notes and completion date.
+    #[tokio::test]
+    async fn test_mark_done_twice() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: TEST-1
+    title: Test Task
+    state: Done
+    completed_at: 2024-01-01T00:00:00Z
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(
+            file.path(),
+            &["TEST-1".to_string()],
+            &[],
+            Some("done again"),
+            Format::Table,
+        )
+        .await;
+
+        let backlog = store::load(file.path());
+        assert!(backlog.tasks[0].notes.is_empty());
+        assert_eq!(
+            backlog.tasks[0].completed_at,
+            Some("2024-01-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(backlog.tasks[0].updated_at, None);
+    }
+
+    /// Tests that several tasks and a whole epic are marked as done at once.
+    #[tokio::test]
+    async fn test_mark_done_batch() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+  - id: T-2
+    title: Second
+  - id: T-3
+    title: Third
+epics:
+  - id: E-1
+    title: Epic
+    tasks:
+      - id: E-1-1
+        title: Epic task
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let tasks = ["T-1".to_string(), "T-3".to_string()];
+        execute(
+            file.path(),
+            &tasks,
+            &["E-1".to_string()],
+            None,
+            Format::Json,
+        )
+        .await;
+
+        let backlog = store::load(file.path());
+        let done: Vec<&str> = backlog
+            .all_tasks()
+            .into_iter()
+            .filter(|t| matches!(t.state, TaskState::Done))
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(done, vec!["T-1", "T-3", "E-1-1"]);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::{Backlog, Epic, TaskState};
+
+/// What happens to the tasks of an epic being removed.
+pub enum TaskDisposal {
+    /// Refuse to remove an epic that still has tasks.
+    Keep,
+    /// Move the tasks to another epic.
+    MoveTo(String),
+    /// Move the tasks to the standalone task list.
+    Standalone,
+    /// Delete the tasks along with the epic.
+    Delete,
+}
+
+/// Summary of an epic for `epic list`.
+#[derive(Serialize)]
+struct EpicSummary<'a> {
+    id: &'a str,
+    title: &'a str,
+    tasks: usize,
+    done: usize,
+}
+
+/// Epic added, renamed or removed by an `epic` subcommand.
+#[derive(Serialize)]
+struct EpicChange {
+    id: String,
+    title: String,
+    /// Message printed in text and markdown output.
+    #[serde(skip)]
+    message: String,
+}
+
+/// Epics of the backlog, in order.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct EpicList<'a>(Vec<EpicSummary<'a>>);
+
+/// Executes the "epic list" command: lists the epics with their task counts.
+pub fn list(backlog_file: &Path, format: Format) {
+    let backlog = store::load(backlog_file);
+    let epics = EpicList(
+        backlog
+            .epics
+            .iter()
+            .map(|e| EpicSummary {
+                id: &e.id,
+                title: &e.title,
+                tasks: e.tasks.len(),
+                done: e
+                    .tasks
+                    .iter()
+                    .filter(|t| matches!(t.state, TaskState::Done))
+                    .count(),
+            })
+            .collect(),
+    );
+    output::print(format, &epics);
+}
+
+/// Executes the "epic add" command: appends a new, empty epic. The ID is minted when not given.
+pub fn add(backlog_file: &Path, title: &str, id: Option<&str>, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let id = match id {
+        Some(id) => {
+            ensure_epic_id_free(&backlog, id);
+            id.to_string()
+        }
+        None => taskai_core::next_epic_id(&backlog),
+    };
+    backlog.epics.push(Epic {
+        id: id.clone(),
+        title: title.to_string(),
+        ..Default::default()
+    });
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &EpicChange {
+            message: format!("Epic {} added.", id),
+            id,
+            title: title.to_string(),
+        },
+    );
+}
+
+/// Executes the "epic rename" command: changes the title of an epic and, with `new_id`, its ID.
+pub fn rename(
+    backlog_file: &Path,
+    epic_id: &str,
+    title: Option<&str>,
+    new_id: Option<&str>,
+    format: Format,
+) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    if let Some(new_id) = new_id {
+        if new_id != epic_id {
+            ensure_epic_id_free(&backlog, new_id);
+        }
+    }
+    let epic = find_epic(&mut backlog, epic_id);
+    if let Some(title) = title {
+        epic.title = title.to_string();
+    }
+    if let Some(new_id) = new_id {
+        epic.id = new_id.to_string();
+    }
+    let change = EpicChange {
+        id: epic.id.clone(),
+        title: epic.title.clone(),
+        message: format!("Epic {} renamed.", epic.id),
+    };
+
+    store::save(backlog_file, &backlog);
+    output::print(format, &change);
+}
+
+/// Executes the "epic rm" command: removes an epic, handling its tasks as `disposal` says.
+///
+/// An epic that still has tasks is only removed when told what to do with them. Deleting tasks that
+/// other tasks depend on is refused, since the backlog is validated before being written.
+pub fn remove(backlog_file: &Path, epic_id: &str, disposal: TaskDisposal, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let epic = find_epic(&mut backlog, epic_id);
+    let title = epic.title.clone();
+    let tasks = std::mem::take(&mut epic.tasks);
+    let task_count = tasks.len();
+    let outcome = match disposal {
+        _ if tasks.is_empty() => String::new(),
+        TaskDisposal::Keep => {
+            error::fail(
+                ErrorKind::Usage,
+                format!(
+                    "Epic {} still has {} task(s); use --move-to, --standalone or --delete-tasks.",
+                    epic_id, task_count
+                ),
+            );
+        }
+        TaskDisposal::MoveTo(target) if target == epic_id => {
+            error::fail(
+                ErrorKind::Usage,
+                format!("Cannot move the tasks of epic {} to itself.", epic_id),
+            );
+        }
+        TaskDisposal::MoveTo(target) => {
+            find_epic(&mut backlog, &target).tasks.extend(tasks);
+            format!(" {} task(s) moved to epic {}.", task_count, target)
+        }
+        TaskDisposal::Standalone => {
+            backlog.tasks.extend(tasks);
+            format!(" {} task(s) moved to the standalone tasks.", task_count)
+        }
+        TaskDisposal::Delete => format!(" {} task(s) deleted.", task_count),
+    };
+    backlog.epics.retain(|e| e.id != epic_id);
+
+    if let Err(err) = backlog.validate() {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "Backlog would be invalid after removing epic {}: {}",
+                epic_id, err
+            ),
+        );
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &EpicChange {
+            id: epic_id.to_string(),
+            title,
+            message: format!("Epic {} removed.{}", epic_id, outcome),
+        },
+    );
+}
+
+/// Exits the process with a usage error if the backlog already has an epic with the given ID.
+fn ensure_epic_id_free(backlog: &Backlog, id: &str) {
+    if backlog.epics.iter().any(|e| e.id == id) {
+        error::fail(
+            ErrorKind::Usage,
+            format!("An epic with ID '{}' already exists.", id),
+        );
+    }
+}
+
+/// Returns the epic with the given ID, exiting the process if there is none.
+fn find_epic<'a>(backlog: &'a mut Backlog, epic_id: &str) -> &'a mut Epic {
+    match backlog.epics.iter_mut().find(|e| e.id == epic_id) {
+        Some(epic) => epic,
+        None => {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Epic with ID '{}' not found in the backlog.", epic_id),
+            );
+        }
+    }
+}
+
+impl Render for EpicChange {
+    fn print_text(&self) {
+        println!("{}", self.message);
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+impl Render for EpicList<'_> {
+    fn print_text(&self) {
+        self.print_epics(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_epics(true);
+    }
+}
+
+impl EpicList<'_> {
+    fn print_epics(&self, markdown: bool) {
+        if self.0.is_empty() {
+            println!("No epics.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|e| {
+                vec![
+                    e.id.to_string(),
+                    e.title.to_string(),
+                    format!("{}/{}", e.done, e.tasks),
+                ]
+            })
+            .collect();
+        output::print_table(markdown, 0, &["ID", "TITLE", "DONE"], &rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests adding, renaming and removing epics.
+    #[test]
+    fn test_epic_lifecycle() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+epics:
+  - id: E-1
+    title: First
+    tasks:
+      - id: T-1
+        title: Task
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        add(file.path(), "Second", None, Format::Table);
+        rename(file.path(), "E-2", Some("Renamed"), None, Format::Table);
+        remove(
+            file.path(),
+            "E-1",
+            TaskDisposal::MoveTo("E-2".to_string()),
+            Format::Table,
+        );
+
+        let backlog = store::load(file.path());
+        assert_eq!(backlog.epics.len(), 1);
+        assert_eq!(backlog.epics[0].id, "E-2");
+        assert_eq!(backlog.epics[0].title, "Renamed");
+        assert_eq!(backlog.epics[0].tasks[0].id, "T-1");
+    }
+}
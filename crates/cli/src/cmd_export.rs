@@ -0,0 +1,105 @@
+use crate::html;
+use crate::output::{self, Format, Render};
+use crate::store;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::TaskRow;
+
+/// File format written by `taskai export --to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma-separated values, one row per task, for spreadsheets.
+    Csv,
+    /// Self-contained HTML report.
+    Html,
+    /// Emacs Org document.
+    Org,
+    /// todo.txt list, one task per line.
+    TodoTxt,
+    /// iCalendar file of due dates and milestones.
+    Ics,
+    /// PlantUML dependency diagram and Gantt chart of the plan.
+    #[value(name = "plantuml")]
+    PlantUml,
+}
+
+/// Tasks of the backlog, one row each.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct TaskTable(Vec<TaskRow>);
+
+/// Executes the "export" command: prints every task of the backlog as a row with its epic, state,
+/// estimate, assignee and dependencies, for scripts, or writes it as a file format given by `to`.
+///
+/// `--to csv` prints the rows for spreadsheets, with the columns `taskai import` reads back.
+/// `--to html` prints a single-file report instead, with progress bars, the dependency graph and a
+/// filterable task table, and `--to org` an Org document with the tasks nested under their epics,
+/// `--to todo-txt` a todo.txt list, `--to ics` an iCalendar file of the due dates and milestone
+/// targets, and `--to plantuml` a dependency diagram and a Gantt chart of the remaining tasks from
+/// today.
+pub fn execute(backlog_file: &Path, to: Option<ExportFormat>, format: Format) {
+    let backlog = store::load(backlog_file);
+    let today = chrono::Local::now().date_naive();
+    match to {
+        Some(ExportFormat::Csv) => {
+            output::print_csv_records(&taskai_core::task_rows(&backlog));
+        }
+        Some(ExportFormat::Html) => {
+            print!("{}", html::report(&backlog, &today.to_string()));
+        }
+        Some(ExportFormat::Org) => print!("{}", taskai_core::format_org(&backlog)),
+        Some(ExportFormat::TodoTxt) => print!("{}", taskai_core::format_todo_txt(&backlog)),
+        Some(ExportFormat::Ics) => {
+            let targets = store::config(backlog_file).milestones;
+            print!(
+                "{}",
+                taskai_core::format_ics(&backlog, &targets, chrono::Utc::now())
+            );
+        }
+        Some(ExportFormat::PlantUml) => {
+            print!("{}", taskai_core::format_plantuml(&backlog, today));
+        }
+        None => output::print(format, &TaskTable(taskai_core::task_rows(&backlog))),
+    }
+}
+
+impl Render for TaskTable {
+    fn print_text(&self) {
+        self.print_rows(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_rows(true);
+    }
+}
+
+impl TaskTable {
+    fn print_rows(&self, markdown: bool) {
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|row| {
+                let cell = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+                vec![
+                    row.id.clone(),
+                    cell(row.epic.clone()),
+                    cell(row.state.clone()),
+                    cell(row.priority.map(|p| p.to_string())),
+                    cell(row.estimate.map(|e| e.to_string())),
+                    cell(row.assignee.clone()),
+                    cell(Some(row.depends.clone()).filter(|d| !d.is_empty())),
+                    row.title.clone(),
+                ]
+            })
+            .collect();
+        output::print_table(
+            markdown,
+            0,
+            &[
+                "ID", "EPIC", "STATE", "PRIORITY", "ESTIMATE", "ASSIGNEE", "DEPENDS", "TITLE",
+            ],
+            &rows,
+        );
+    }
+}
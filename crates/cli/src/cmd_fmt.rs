@@ -0,0 +1,98 @@
+use crate::error::{self, ErrorKind};
+use crate::store;
+use std::path::Path;
+use taskai_schema::Backlog;
+
+/// Executes the "fmt" command: rewrites the backlog file in canonical form.
+///
+/// With `check` set, the file is left untouched and the command exits with status 3 if it is not
+/// already formatted, which makes it usable in CI. Files with unknown fields are refused, since
+/// rewriting them would drop those fields.
+pub fn execute(backlog_file: &Path, check: bool) {
+    let _lock = store::lock(backlog_file);
+    let source = store::read(backlog_file);
+
+    let backlog = match Backlog::from_yaml_with_unknown_fields(&source) {
+        Ok((_, unknown)) if !unknown.is_empty() => {
+            error::fail(
+                ErrorKind::Validation,
+                format!(
+                    "Refusing to format {}: unknown field(s) would be lost: {}",
+                    backlog_file.display(),
+                    unknown.join(", ")
+                ),
+            );
+        }
+        Ok((backlog, _)) => backlog,
+        Err(err) => {
+            error::fail(
+                ErrorKind::Parse,
+                format!("Error parsing backlog file: {}", err),
+            );
+        }
+    };
+
+    let formatted = match taskai_core::format_backlog(&backlog) {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            error::fail(
+                ErrorKind::Other,
+                format!("Error formatting backlog: {}", err),
+            );
+        }
+    };
+
+    // The content of a backlog directory is joined from its files, which are what gets formatted
+    let is_formatted = if store::is_backlog_dir(backlog_file) {
+        store::is_dir_written(backlog_file, &backlog).unwrap_or(false)
+    } else {
+        formatted == source
+    };
+    if is_formatted {
+        println!("{} is already formatted.", backlog_file.display());
+        return;
+    }
+
+    if check {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "{} is not formatted; run `taskai fmt` to fix it.",
+                backlog_file.display()
+            ),
+        );
+    }
+
+    store::save_formatted(backlog_file, &backlog);
+    println!("Formatted {}.", backlog_file.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that formatting rewrites the file and is idempotent.
+    #[test]
+    fn test_fmt() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+tasks:
+  - {id: T-2, title: 'Second', depends: [T-1, T-0]}
+project: "test-project"
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(file.path(), false);
+        let first = fs::read_to_string(file.path()).unwrap();
+        assert!(first.starts_with("project: test-project\n"));
+
+        execute(file.path(), true);
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), first);
+    }
+}
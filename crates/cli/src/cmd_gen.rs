@@ -0,0 +1,78 @@
+use crate::store;
+use std::path::Path;
+use taskai_core::MergeSummary;
+use taskai_schema::Backlog;
+
+/// Writes a generated backlog to `output` for the "gen" command.
+///
+/// An existing file is only replaced when `force` is set. With `merge` set, the generated backlog is
+/// merged into the existing file instead, preserving the progress of the tasks it already contains;
+/// the summary of the merge is returned.
+pub fn write_backlog(
+    output: &Path,
+    backlog: Backlog,
+    merge: bool,
+    force: bool,
+) -> Result<Option<MergeSummary>, String> {
+    if !output.exists() {
+        store::try_save(output, &backlog)?;
+        return Ok(None);
+    }
+
+    if merge {
+        let _lock = store::lock(output);
+        let existing = store::try_load(output)?;
+        let (merged, summary) = taskai_core::merge_generated(&existing, backlog);
+        store::try_save(output, &merged)?;
+        return Ok(Some(summary));
+    }
+
+    if !force {
+        return Err(format!(
+            "{} already exists; use --merge to merge into it or --force to overwrite it.",
+            output.display()
+        ));
+    }
+    store::try_save(output, &backlog)?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use taskai_schema::TaskState;
+
+    /// Tests that existing files are protected and merged into with `merge`.
+    #[test]
+    fn test_write_backlog() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("backlog.yaml");
+        let generated: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+"#,
+        )
+        .unwrap();
+
+        assert!(write_backlog(&output, generated.clone(), false, false)
+            .unwrap()
+            .is_none());
+        assert!(write_backlog(&output, generated.clone(), false, false).is_err());
+
+        let mut existing = store::load(&output);
+        existing.tasks[0].state = TaskState::Done;
+        store::save(&output, &existing);
+
+        let summary = write_backlog(&output, generated, true, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.preserved, vec!["T-1"]);
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("state: Done"));
+    }
+}
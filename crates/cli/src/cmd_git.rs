@@ -0,0 +1,273 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use taskai_core::CommitUpdate;
+
+/// Command run by the installed hook, used to recognize hooks installed by taskai.
+const HOOK_COMMAND: &str = "git post-commit";
+
+/// Set while the hook amends the commit, so that the amend does not run the hook again.
+const HOOK_ENV: &str = "TASKAI_HOOK";
+
+/// Runs git in `dir` and returns its trimmed standard output, or its error message.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| format!("Cannot run git: {}", err))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Exits with the error of a failed git command.
+pub(crate) fn git_error<T>(err: String) -> T {
+    error::fail(ErrorKind::Other, err)
+}
+
+/// The hook written by `git install-hooks`.
+#[derive(Serialize)]
+struct InstalledHook {
+    path: PathBuf,
+    replaced: bool,
+}
+
+/// Executes the "git install-hooks" command: installs a `post-commit` hook in the git repository
+/// of the backlog, running `taskai git post-commit` after each commit.
+///
+/// The backlog path is written into the hook relative to the repository root, where git runs
+/// hooks. An existing hook is only replaced if it was installed by taskai, or with `force` set.
+pub fn install_hooks(backlog_file: &Path, force: bool, format: Format) {
+    let dir = store::project_root(backlog_file);
+    let root =
+        PathBuf::from(git(&dir, &["rev-parse", "--show-toplevel"]).unwrap_or_else(git_error));
+    let hooks =
+        dir.join(git(&dir, &["rev-parse", "--git-path", "hooks"]).unwrap_or_else(git_error));
+    let backlog_path = backlog_file
+        .canonicalize()
+        .unwrap_or_else(|_| backlog_file.to_path_buf());
+    let relative = root
+        .canonicalize()
+        .ok()
+        .and_then(|root| backlog_path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or(backlog_path);
+
+    let path = hooks.join("post-commit");
+    let existing = fs::read_to_string(&path).ok();
+    let replaced = existing.is_some();
+    if existing.is_some_and(|hook| !hook.contains(HOOK_COMMAND)) && !force {
+        error::fail(
+            ErrorKind::Usage,
+            format!(
+                "{} already exists; pass --force to replace it.",
+                path.display()
+            ),
+        );
+    }
+
+    // The hook calls this very binary, which may not be on the PATH of git
+    let taskai = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|_| "taskai".to_string());
+    let hook = format!(
+        "#!/bin/sh\n\
+         # Installed by `taskai git install-hooks`: updates the tasks referenced by the commit.\n\
+         [ -n \"${}\" ] && exit 0\n\
+         exec '{}' {} '{}'\n",
+        HOOK_ENV,
+        taskai.replace('\'', r"'\''"),
+        HOOK_COMMAND,
+        relative.display().to_string().replace('\'', r"'\''")
+    );
+    let written = fs::create_dir_all(&hooks).and_then(|_| fs::write(&path, hook));
+    if let Err(err) = written {
+        error::fail(
+            ErrorKind::Other,
+            format!("Error writing {}: {}", path.display(), err),
+        );
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(err) = fs::set_permissions(&path, fs::Permissions::from_mode(0o755)) {
+            error::fail(
+                ErrorKind::Other,
+                format!("Error making {} executable: {}", path.display(), err),
+            );
+        }
+    }
+    output::print(format, &InstalledHook { path, replaced });
+}
+
+/// What the hook did after a commit.
+#[derive(Serialize)]
+struct HookReport {
+    commit: String,
+    updated: Vec<CommitUpdate>,
+    /// Whether the state changes were amended into the commit.
+    amended: bool,
+}
+
+/// Executes the "git post-commit" command, run by the hook after each commit: marks the tasks
+/// closed by the message of the last commit as Done, and the ones it references as InProgress.
+///
+/// When the backlog file is tracked by git, the state changes are then amended into the commit,
+/// with any other change made to the backlog file; other staged changes are left out of it. Does
+/// nothing while a rebase is in progress.
+pub fn post_commit(backlog_file: &Path, format: Format) {
+    let dir = store::project_root(backlog_file);
+    let rebasing = ["rebase-merge", "rebase-apply"].iter().any(|name| {
+        git(&dir, &["rev-parse", "--git-path", name]).is_ok_and(|path| dir.join(path).exists())
+    });
+    if rebasing || !backlog_file.exists() {
+        return;
+    }
+    let message = git(&dir, &["log", "-1", "--format=%B"]).unwrap_or_else(git_error);
+    // Named by its subject, since amending changes its hash
+    let commit = git(&dir, &["log", "-1", "--format=%s"]).unwrap_or_else(git_error);
+
+    let lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let refs = taskai_core::parse_commit_refs(&backlog, &message);
+    let now = chrono::Utc::now();
+    let updated =
+        taskai_core::apply_commit_refs(&mut backlog, &refs, &format!("\"{}\"", commit), now);
+    if updated.is_empty() {
+        return;
+    }
+    store::save(backlog_file, &backlog);
+    drop(lock);
+
+    // Git runs in the directory of the backlog file
+    let file = backlog_file
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let tracked = git(&dir, &["ls-files", "--error-unmatch", "--", &file]).is_ok();
+    let amended = tracked
+        && Command::new("git")
+            .args([
+                "commit",
+                "--amend",
+                "--no-edit",
+                "--no-verify",
+                "--quiet",
+                "--only",
+                "--",
+            ])
+            .arg(file.as_ref())
+            .current_dir(&dir)
+            .env(HOOK_ENV, "1")
+            .status()
+            .is_ok_and(|status| status.success());
+    output::print(
+        format,
+        &HookReport {
+            commit,
+            updated,
+            amended,
+        },
+    );
+}
+
+impl Render for InstalledHook {
+    fn print_text(&self) {
+        println!(
+            "{} {}",
+            if self.replaced {
+                "Replaced"
+            } else {
+                "Installed"
+            },
+            self.path.display()
+        );
+    }
+}
+
+impl Render for HookReport {
+    fn print_text(&self) {
+        for update in &self.updated {
+            println!(
+                "taskai: {} {:?} ({}) after \"{}\"",
+                update.task_id, update.state, update.title, self.commit
+            );
+        }
+        if !self.amended {
+            println!("taskai: the backlog changes were not amended into the commit; commit them separately.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the hook closes the referenced tasks and amends the backlog into the commit.
+    #[test]
+    fn test_post_commit_amends_backlog() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        let backlog_file = dir.join("taskai.yaml");
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-1\n    title: First\n",
+        )
+        .unwrap();
+        let run = |args: &[&str]| git(dir, args).unwrap();
+        run(&["init", "--quiet"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        run(&["add", "taskai.yaml"]);
+        run(&[
+            "commit",
+            "--quiet",
+            "-m",
+            "Add the first feature\n\nCloses T-1",
+        ]);
+
+        post_commit(&backlog_file, Format::Json);
+
+        let committed = run(&["show", "HEAD:taskai.yaml"]);
+        assert!(committed.contains("state: Done"));
+        assert_eq!(run(&["rev-list", "--count", "HEAD"]), "1");
+        assert_eq!(run(&["diff", "HEAD", "--", "taskai.yaml"]), "");
+    }
+
+    /// Tests that changes staged after the commit are not folded into it by the hook.
+    #[test]
+    fn test_post_commit_leaves_staged_changes() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        let backlog_file = dir.join("taskai.yaml");
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-1\n    title: First\n",
+        )
+        .unwrap();
+        let run = |args: &[&str]| git(dir, args).unwrap();
+        run(&["init", "--quiet"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        run(&["add", "taskai.yaml"]);
+        run(&["commit", "--quiet", "-m", "Fix the parser\n\nCloses T-1"]);
+        fs::write(dir.join("unrelated.txt"), "work in progress\n").unwrap();
+        run(&["add", "unrelated.txt"]);
+
+        post_commit(&backlog_file, Format::Json);
+
+        assert!(run(&["show", "HEAD:taskai.yaml"]).contains("state: Done"));
+        assert_eq!(run(&["ls-tree", "--name-only", "HEAD"]), "taskai.yaml");
+        assert_eq!(run(&["diff", "--cached", "--name-only"]), "unrelated.txt");
+    }
+}
@@ -0,0 +1,374 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{GitHubClient, NewIssue, ProjectSyncItem, ProjectTarget};
+use taskai_schema::{Backlog, TaskState};
+
+/// An issue created, or to be created, for a task.
+#[derive(Serialize)]
+struct ExportedTask {
+    task: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+/// What `sync github-project` did, or would do with `dry_run` set, for each task of the backlog.
+#[derive(Serialize)]
+struct ProjectSyncReport<'a> {
+    project: &'a str,
+    dry_run: bool,
+    items: Vec<ProjectSyncItem>,
+}
+
+/// The issues created by `export github`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    repo: &'a str,
+    dry_run: bool,
+    exported: Vec<ExportedTask>,
+    /// Tasks skipped because they already have an issue in the repository.
+    skipped: usize,
+}
+
+/// Executes the "export github" command: creates an issue in `repo` for each task of the backlog.
+///
+/// Issues get the title of the task, its description and `done_when` criteria as body, and its tags
+/// and epic title as labels; issues of Done tasks are closed right away. The created issues are
+/// recorded in the `external_refs` of the tasks, so tasks that already have an issue in the
+/// repository are skipped and the command can be run again as tasks are added. Requires
+/// `GITHUB_TOKEN`, unless `dry_run` is set.
+pub async fn export(backlog_file: &Path, repo: &str, dry_run: bool, format: Format) {
+    check_repo(repo);
+    let client = GitHubClient::from_env();
+    if !dry_run && !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set GITHUB_TOKEN to a token allowed to create issues in the repository.",
+        );
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        repo,
+        dry_run,
+        exported: Vec::new(),
+        skipped: 0,
+    };
+
+    let pending: Vec<(String, NewIssue, bool)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::github_issue_number(task, repo).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| {
+            let done = matches!(task.state, TaskState::Done);
+            (
+                task.id.clone(),
+                taskai_core::issue_for_task(task, epic),
+                done,
+            )
+        })
+        .collect();
+
+    let mut failure = None;
+    for (task_id, issue, done) in pending {
+        if dry_run {
+            report.exported.push(ExportedTask {
+                task: task_id,
+                title: issue.title,
+                issue: None,
+                url: None,
+            });
+            continue;
+        }
+
+        let created = match client.create_issue(repo, &issue).await {
+            Ok(created) => created,
+            Err(err) => {
+                failure = Some(format!("Error creating the issue of {}: {}", task_id, err));
+                break;
+            }
+        };
+        // Recorded before closing, so that a failure never leads to a duplicate issue
+        let external_ref = taskai_core::github_ref(repo, &created, chrono::Utc::now());
+        if let Some(task) = backlog.find_task_mut(&task_id) {
+            task.external_refs.push(external_ref.clone());
+        }
+        report.exported.push(ExportedTask {
+            task: task_id.clone(),
+            title: issue.title,
+            issue: Some(external_ref.id),
+            url: external_ref.url,
+        });
+        if done {
+            if let Err(err) = client.set_issue_closed(repo, created.number, true).await {
+                failure = Some(format!("Error closing the issue of {}: {}", task_id, err));
+                break;
+            }
+        }
+    }
+
+    if !report.exported.is_empty() && !dry_run {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Executes the "import github" command: brings the issues of `repo` into the backlog.
+///
+/// Issues already tracked by a task update its title and state (closed issues are Done), and the
+/// others become new tasks, grouped into epics after their milestones. The backlog file is created,
+/// named after the repository, if it does not exist yet. `GITHUB_TOKEN` is only needed for private
+/// repositories.
+pub async fn import(backlog_file: &Path, repo: &str, format: Format) {
+    check_repo(repo);
+    // Fetched before locking the backlog, since a large repository takes a while
+    let issues = match GitHubClient::from_env().list_issues(repo).await {
+        Ok(issues) => issues,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listing the issues of {}: {}", repo, err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: repo.rsplit('/').next().unwrap_or(repo).to_string(),
+            ..Default::default()
+        }
+    };
+
+    let summary =
+        taskai_core::import_github_issues(&mut backlog, repo, &issues, chrono::Utc::now());
+    if !summary.added.is_empty() || !summary.updated.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &summary);
+}
+
+/// Executes the "sync github-project" command: places every task of the backlog on the Projects
+/// board `project` (`owner/number`, defaulting to `github.project` in the configuration).
+///
+/// Tasks with a GitHub issue are added to the board by issue, the others as draft items recorded in
+/// their `external_refs`. Each item is then moved to the column of its task state and, when the
+/// board has an epic field, given the value named after its epic; the field names and columns come
+/// from the `[github]` section of the configuration. Requires `GITHUB_TOKEN`.
+pub async fn sync_project(
+    backlog_file: &Path,
+    project: Option<String>,
+    dry_run: bool,
+    format: Format,
+) {
+    let config = store::config(backlog_file).github;
+    let Some(project) = project.or_else(|| config.project.clone()) else {
+        error::fail(
+            ErrorKind::Usage,
+            "Pass --project or set github.project in .taskai.toml.",
+        );
+    };
+    if taskai_core::parse_project(&project).is_none() {
+        error::fail(
+            ErrorKind::Usage,
+            format!(
+                "Invalid project '{}'; expected the form owner/number.",
+                project
+            ),
+        );
+    }
+    let client = GitHubClient::from_env();
+    if !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set GITHUB_TOKEN to a token allowed to read and write the project.",
+        );
+    }
+    let board = match client.project_board(&project).await {
+        Ok(board) => board,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error reading the project {}: {}", project, err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let items = match taskai_core::plan_project_sync(&backlog, &project, &board, &config) {
+        Ok(items) => items,
+        Err(err) => error::fail(ErrorKind::Validation, err),
+    };
+    let report = ProjectSyncReport {
+        project: &project,
+        dry_run,
+        items,
+    };
+
+    let mut changed = false;
+    let mut failure = None;
+    for item in report.items.iter().filter(|i| !dry_run && !i.is_in_sync()) {
+        let added = match &item.target {
+            ProjectTarget::Item(id) => Ok(id.clone()),
+            ProjectTarget::Issue(issue) => client.add_project_issue(&board.id, issue).await,
+            ProjectTarget::Draft => match backlog.find_task(&item.task_id) {
+                Some(task) => client.add_project_draft(&board.id, task).await,
+                None => continue,
+            },
+        };
+        let item_id = match added {
+            Ok(item_id) => item_id,
+            Err(err) => {
+                failure = Some(format!(
+                    "Error adding {} to the project: {}",
+                    item.task_id, err
+                ));
+                break;
+            }
+        };
+        if matches!(item.target, ProjectTarget::Draft) {
+            // Recorded before setting the fields, so that a failure never leads to a duplicate draft
+            if let Some(task) = backlog.find_task_mut(&item.task_id) {
+                task.external_refs
+                    .push(taskai_core::project_ref(&project, &item_id));
+                changed = true;
+            }
+        }
+        for update in &item.updates {
+            if let Err(err) = client.set_project_field(&board.id, &item_id, update).await {
+                failure = Some(format!(
+                    "Error setting {} of {}: {}",
+                    update.field, item.task_id, err
+                ));
+                break;
+            }
+        }
+        if failure.is_some() {
+            break;
+        }
+    }
+
+    if changed {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Exits with a usage error unless `repo` looks like `owner/name`.
+pub fn check_repo(repo: &str) {
+    if !taskai_core::is_github_repo(repo) {
+        error::fail(
+            ErrorKind::Usage,
+            format!(
+                "Invalid repository '{}'; expected the form owner/name.",
+                repo
+            ),
+        );
+    }
+}
+
+impl Render for ProjectSyncReport<'_> {
+    fn print_text(&self) {
+        let mut in_sync = 0;
+        for item in &self.items {
+            for warning in &item.warnings {
+                println!("{}: warning: {}", item.task_id, warning);
+            }
+            if item.is_in_sync() {
+                in_sync += 1;
+                continue;
+            }
+            let mut changes: Vec<String> = match &item.target {
+                ProjectTarget::Item(_) => Vec::new(),
+                ProjectTarget::Issue(issue) => vec![format!("add {}", issue)],
+                ProjectTarget::Draft => vec!["add as draft".to_string()],
+            };
+            changes.extend(
+                item.updates
+                    .iter()
+                    .map(|u| format!("set {} to {}", u.field, u.value)),
+            );
+            let prefix = if self.dry_run { "would " } else { "" };
+            println!("{}: {}{}", item.task_id, prefix, changes.join(", "));
+        }
+        println!("{} task(s) already in place on {}.", in_sync, self.project);
+    }
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.exported.is_empty() {
+            println!("No tasks to export to {}.", self.repo);
+        } else if self.dry_run {
+            println!(
+                "Would create {} issue(s) in {}:",
+                self.exported.len(),
+                self.repo
+            );
+        } else {
+            println!("Created {} issue(s) in {}:", self.exported.len(), self.repo);
+        }
+        for task in &self.exported {
+            match (&task.issue, &task.url) {
+                (Some(issue), Some(url)) => {
+                    println!("  {} -> {} ({})", task.task, issue, url)
+                }
+                _ => println!("  {}: {}", task.task, task.title),
+            }
+        }
+        if self.skipped > 0 {
+            println!("Skipped {} task(s) already exported.", self.skipped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a dry run lists the tasks without an issue yet and leaves the backlog untouched.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut file = NamedTempFile::new().unwrap();
+        let content = br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: Exported
+    external_refs:
+      - system: github
+        id: acme/app#3
+  - id: T-2
+    title: New
+"#;
+        file.write_all(content).unwrap();
+        file.flush().unwrap();
+
+        export(file.path(), "acme/app", true, Format::Json).await;
+        assert_eq!(std::fs::read(file.path()).unwrap(), content);
+    }
+}
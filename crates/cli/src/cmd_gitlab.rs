@@ -0,0 +1,401 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{GitLabClient, GitLabConfig, GitLabEpics};
+use taskai_schema::{Backlog, TaskState};
+
+/// A GitLab milestone, epic or issue created, or to be created, for an epic or a task.
+#[derive(Serialize)]
+struct ExportedItem {
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+}
+
+/// The milestones or epics, issues and links created by `export gitlab`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    project: &'a str,
+    dry_run: bool,
+    epics: Vec<ExportedItem>,
+    tasks: Vec<ExportedItem>,
+    /// Dependencies turned into blocking links, as `(task, dependency)`.
+    links: Vec<(String, String)>,
+    /// Epics and tasks skipped because they are already tracked in GitLab.
+    skipped: usize,
+}
+
+/// Executes the "export gitlab" command: creates a GitLab issue in `project` for each task of the
+/// backlog, and a milestone or an epic for each epic, then links the issues of dependent tasks.
+///
+/// Epics become milestones of the project, or epics of its group when `gitlab.epics` is `epic`;
+/// milestones with the same title are reused. Issues get the title of the task, its description
+/// and `done_when` criteria as description, its tags as labels and the milestone or epic of its
+/// epic; issues of Done tasks are closed right away. Dependencies involving a newly created issue
+/// become blocking links. Everything created is recorded in the `external_refs` of the epics and
+/// tasks, so the command can be run again as the backlog grows. Requires `GITLAB_TOKEN`, unless
+/// `dry_run` is set.
+pub async fn export(backlog_file: &Path, project: Option<String>, dry_run: bool, format: Format) {
+    let config = store::config(backlog_file).gitlab;
+    let project = project_path(project, &config);
+    let client = GitLabClient::from_env(config.url.as_deref());
+    if !dry_run && !client.has_token() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set GITLAB_TOKEN to a token allowed to create issues in the project.",
+        );
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        project: &project,
+        dry_run,
+        epics: Vec::new(),
+        tasks: Vec::new(),
+        links: Vec::new(),
+        skipped: 0,
+    };
+
+    let failure = create_issues(&client, &mut backlog, &config, &mut report).await;
+    let created = report
+        .epics
+        .iter()
+        .chain(&report.tasks)
+        .any(|i| i.reference.is_some());
+    if created {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Creates the milestones or epics and the issues of the epics and tasks not tracked yet, then the
+/// links of their dependencies, recording them in `report`. Returns the error that stopped the
+/// export, if any.
+async fn create_issues(
+    client: &GitLabClient,
+    backlog: &mut Backlog,
+    config: &GitLabConfig,
+    report: &mut ExportReport<'_>,
+) -> Option<String> {
+    let project = report.project;
+    let dry_run = report.dry_run;
+    let mode = config.epics.unwrap_or_default();
+    let group = config.group_of(project);
+    let mut milestones = None;
+    let mut created: Vec<String> = Vec::new();
+
+    for index in 0..backlog.epics.len() {
+        let epic = &backlog.epics[index];
+        let tracked = match mode {
+            GitLabEpics::Milestone => taskai_core::gitlab_milestone_id(epic, project).is_some(),
+            GitLabEpics::Epic => taskai_core::gitlab_epic_iid(epic, &group).is_some(),
+        };
+        if tracked {
+            report.skipped += 1;
+            continue;
+        }
+        let mut item = ExportedItem {
+            id: epic.id.clone(),
+            title: epic.title.clone(),
+            reference: None,
+        };
+        if !dry_run {
+            let external_ref = match mode {
+                GitLabEpics::Milestone => {
+                    if milestones.is_none() {
+                        match client.list_milestones(project).await {
+                            Ok(list) => milestones = Some(list),
+                            Err(err) => return Some(format!("Error listing milestones: {}", err)),
+                        }
+                    }
+                    let existing = milestones
+                        .iter()
+                        .flatten()
+                        .find(|m| m.title.eq_ignore_ascii_case(epic.title.trim()))
+                        .cloned();
+                    let milestone = match existing {
+                        Some(milestone) => Ok(milestone),
+                        None => client.create_milestone(project, epic.title.trim()).await,
+                    };
+                    milestone.map(|m| taskai_core::gitlab_milestone_ref(project, &m))
+                }
+                GitLabEpics::Epic => client
+                    .create_epic(&group, epic.title.trim())
+                    .await
+                    .map(|e| taskai_core::gitlab_epic_ref(&group, &e)),
+            };
+            match external_ref {
+                Ok(external_ref) => {
+                    item.reference = Some(external_ref.id.clone());
+                    backlog.epics[index].external_refs.push(external_ref);
+                }
+                Err(err) => return Some(format!("Error creating the epic {}: {}", item.id, err)),
+            }
+        }
+        report.epics.push(item);
+    }
+
+    let pending: Vec<(String, Option<u64>, Option<u64>)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::gitlab_issue_iid(task, project).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| match mode {
+            GitLabEpics::Milestone => (
+                task.id.clone(),
+                epic.and_then(|e| taskai_core::gitlab_milestone_id(e, project)),
+                None,
+            ),
+            GitLabEpics::Epic => (
+                task.id.clone(),
+                None,
+                epic.and_then(|e| taskai_core::gitlab_epic_iid(e, &group)),
+            ),
+        })
+        .collect();
+    for (task_id, milestone, epic_iid) in pending {
+        let Some(task) = backlog.find_task(&task_id) else {
+            continue;
+        };
+        let mut item = ExportedItem {
+            id: task_id.clone(),
+            title: task.title.clone(),
+            reference: None,
+        };
+        let done = matches!(task.state, TaskState::Done);
+        created.push(task_id.clone());
+        if dry_run {
+            report.tasks.push(item);
+            continue;
+        }
+
+        let issue = taskai_core::gitlab_issue_for_task(task, milestone);
+        let created_issue = match client.create_issue(project, &issue).await {
+            Ok(created_issue) => created_issue,
+            Err(err) => return Some(format!("Error creating the issue of {}: {}", task_id, err)),
+        };
+        // Recorded before anything else, so that a failure never leads to a duplicate issue
+        let external_ref = taskai_core::gitlab_ref(project, &created_issue, chrono::Utc::now());
+        if let Some(task) = backlog.find_task_mut(&task_id) {
+            task.external_refs.push(external_ref.clone());
+        }
+        item.reference = Some(external_ref.id);
+        report.tasks.push(item);
+        if let Some(epic_iid) = epic_iid {
+            if let Err(err) = client
+                .add_epic_issue(&group, epic_iid, created_issue.id)
+                .await
+            {
+                return Some(format!(
+                    "Error adding the issue of {} to its epic: {}",
+                    task_id, err
+                ));
+            }
+        }
+        if done {
+            if let Err(err) = client
+                .set_issue_closed(project, created_issue.iid, true)
+                .await
+            {
+                return Some(format!("Error closing the issue of {}: {}", task_id, err));
+            }
+        }
+    }
+
+    // Links already exist between issues exported by an earlier run
+    let iid_of = |backlog: &Backlog, id: &str| {
+        let task = backlog.find_task(id)?;
+        taskai_core::gitlab_issue_iid(task, project)
+    };
+    let links: Vec<(String, String)> = backlog
+        .all_tasks()
+        .iter()
+        .flat_map(|t| t.depends.iter().map(move |dep| (t.id.clone(), dep.clone())))
+        .filter(|(task, dep)| created.contains(task) || created.contains(dep))
+        .filter(|(_, dep)| dry_run || iid_of(backlog, dep).is_some())
+        .collect();
+    for (task, dep) in links {
+        if !dry_run {
+            let (Some(blocked), Some(blocker)) = (iid_of(backlog, &task), iid_of(backlog, &dep))
+            else {
+                continue;
+            };
+            if let Err(err) = client.link_issues(project, blocker, blocked).await {
+                return Some(format!("Error linking {} to {}: {}", task, dep, err));
+            }
+        }
+        report.links.push((task, dep));
+    }
+    None
+}
+
+/// Executes the "import gitlab" command: brings the issues of `project` into the backlog.
+///
+/// Issues already tracked by a task update its title and state (closed issues are Done), and the
+/// others become new tasks, grouped into epics after their GitLab epic or milestone. Blocking links
+/// between issues become dependencies. The backlog file is created, named after the project, if it
+/// does not exist yet. `GITLAB_TOKEN` is only needed for private projects.
+pub async fn import(backlog_file: &Path, project: Option<String>, format: Format) {
+    let config = store::config(backlog_file).gitlab;
+    let project = project_path(project, &config);
+    let client = GitLabClient::from_env(config.url.as_deref());
+    // Fetched before locking the backlog, since a large project takes a while
+    let issues = match client.list_issues(&project).await {
+        Ok(issues) => issues,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listing the issues of {}: {}", project, err),
+        ),
+    };
+    let mut links = Vec::new();
+    for issue in &issues {
+        match client.list_issue_links(&project, issue.iid).await {
+            Ok(issue_links) => links.push((issue.iid, issue_links)),
+            Err(err) => error::fail(
+                ErrorKind::Other,
+                format!(
+                    "Error listing the links of {}#{}: {}",
+                    project, issue.iid, err
+                ),
+            ),
+        }
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: project.rsplit('/').next().unwrap_or(&project).to_string(),
+            ..Default::default()
+        }
+    };
+
+    let summary = taskai_core::import_gitlab_issues(
+        &mut backlog,
+        &project,
+        &config.group_of(&project),
+        &issues,
+        &links,
+        chrono::Utc::now(),
+    );
+    if !summary.added.is_empty() || !summary.updated.is_empty() || !summary.new_epics.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &summary);
+}
+
+/// Returns the project path given on the command line or in the configuration, exiting with a
+/// usage error if there is none or it is not a project path.
+pub fn project_path(project: Option<String>, config: &GitLabConfig) -> String {
+    let Some(project) = project.or_else(|| config.project.clone()) else {
+        error::fail(
+            ErrorKind::Usage,
+            "Pass --project or set gitlab.project in .taskai.toml.",
+        );
+    };
+    if !taskai_core::is_gitlab_project(&project) {
+        error::fail(
+            ErrorKind::Usage,
+            format!(
+                "Invalid project '{}'; expected its path, such as group/name.",
+                project
+            ),
+        );
+    }
+    project
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.epics.is_empty() && self.tasks.is_empty() {
+            println!("No epics or tasks to export to {}.", self.project);
+        } else {
+            let verb = if self.dry_run {
+                "Would create"
+            } else {
+                "Created"
+            };
+            println!(
+                "{} {} epic(s) and {} issue(s) in {}:",
+                verb,
+                self.epics.len(),
+                self.tasks.len(),
+                self.project
+            );
+        }
+        for item in self.epics.iter().chain(&self.tasks) {
+            match &item.reference {
+                Some(reference) => println!("  {} -> {}", item.id, reference),
+                None => println!("  {}: {}", item.id, item.title),
+            }
+        }
+        for (task, dep) in &self.links {
+            let verb = if self.dry_run { "Would link" } else { "Linked" };
+            println!("{} {} as blocked by {}", verb, task, dep);
+        }
+        if self.skipped > 0 {
+            println!(
+                "Skipped {} epic(s) and task(s) already exported.",
+                self.skipped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a dry run lists the epics, tasks and links to create and leaves the backlog
+    /// untouched.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut file = NamedTempFile::new().unwrap();
+        let content = br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: Exported
+    external_refs:
+      - system: gitlab
+        id: acme/app#3
+  - id: T-2
+    title: New
+    depends: [T-1]
+epics:
+  - id: E-1
+    title: API
+"#;
+        file.write_all(content).unwrap();
+        file.flush().unwrap();
+
+        export(
+            file.path(),
+            Some("acme/app".to_string()),
+            true,
+            Format::Json,
+        )
+        .await;
+        assert_eq!(std::fs::read(file.path()).unwrap(), content);
+    }
+}
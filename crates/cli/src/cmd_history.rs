@@ -0,0 +1,174 @@
+use crate::backup::{self, Backup};
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::BacklogDiff;
+
+/// A recorded change to the backlog.
+#[derive(Serialize)]
+struct HistoryEntry {
+    /// Number of the change, 1 being the latest, as counted by `taskai undo --steps`.
+    change: usize,
+    at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    /// Number of tasks added, removed and changed, unless a version cannot be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    added: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    removed: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed: Option<usize>,
+}
+
+/// Recorded changes to the backlog, latest first.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct History(Vec<HistoryEntry>);
+
+/// Executes the "history" command: lists the changes made to the backlog by taskai commands, from
+/// the backups taken before each of them, or prints the diff of change `show`.
+///
+/// A change is compared with the next recorded version, or with the current file for the latest
+/// one.
+pub fn execute(backlog_file: &Path, show: Option<usize>, format: Format) {
+    let backups = backup::list(backlog_file);
+
+    if let Some(change) = show {
+        if change == 0 || change > backups.len() {
+            error::fail(
+                ErrorKind::NotFound,
+                format!(
+                    "No change {} in the history of {} ({} recorded).",
+                    change,
+                    backlog_file.display(),
+                    backups.len()
+                ),
+            );
+        }
+        match change_diff(backlog_file, &backups, change - 1) {
+            Ok(diff) => output::print(format, &diff),
+            Err(err) => error::fail(ErrorKind::Parse, err),
+        }
+        return;
+    }
+
+    let history = History(
+        backups
+            .iter()
+            .enumerate()
+            .map(|(i, backup)| {
+                let diff = change_diff(backlog_file, &backups, i).ok();
+                HistoryEntry {
+                    change: i + 1,
+                    at: backup.taken_at,
+                    user: backup.user.clone(),
+                    command: backup.command.clone(),
+                    added: diff.as_ref().map(|d| d.added.len()),
+                    removed: diff.as_ref().map(|d| d.removed.len()),
+                    changed: diff.as_ref().map(|d| d.modified.len()),
+                }
+            })
+            .collect(),
+    );
+    output::print(format, &history);
+}
+
+/// Computes the diff of the change at the given index of the backups, latest first.
+fn change_diff(
+    backlog_file: &Path,
+    backups: &[Backup],
+    index: usize,
+) -> Result<BacklogDiff, String> {
+    let before = store::try_load(&backups[index].path)?;
+    let after = match index {
+        0 => store::try_load(backlog_file)?,
+        _ => store::try_load(&backups[index - 1].path)?,
+    };
+    Ok(taskai_core::diff_backlogs(&before, &after))
+}
+
+impl Render for History {
+    fn print_text(&self) {
+        self.print_entries(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_entries(true);
+    }
+}
+
+impl History {
+    fn print_entries(&self, markdown: bool) {
+        if self.0.is_empty() {
+            println!("No recorded changes.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|entry| {
+                let changes = match (entry.added, entry.removed, entry.changed) {
+                    (Some(added), Some(removed), Some(changed)) => {
+                        format!("+{} -{} ~{}", added, removed, changed)
+                    }
+                    _ => "unreadable".to_string(),
+                };
+                vec![
+                    entry.change.to_string(),
+                    entry
+                        .at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string(),
+                    entry.user.clone().unwrap_or_else(|| "-".to_string()),
+                    entry.command.clone().unwrap_or_else(|| "-".to_string()),
+                    changes,
+                ]
+            })
+            .collect();
+        output::print_table(
+            markdown,
+            0,
+            &["#", "WHEN", "WHO", "COMMAND", "TASKS"],
+            &rows,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::Backlog;
+
+    /// Tests that each change is diffed against the version that followed it.
+    #[test]
+    fn test_change_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.yml");
+        let mut backlog: Backlog = serde_yaml::from_str("project: p\n").unwrap();
+        store::save(&path, &backlog);
+
+        let task = serde_yaml::from_str("{id: T-1, title: First}").unwrap();
+        backlog.tasks.push(task);
+        store::save(&path, &backlog);
+        backlog.tasks[0].title = "First task".to_string();
+        store::save(&path, &backlog);
+
+        let backups = backup::list(&path);
+        assert_eq!(backups.len(), 2);
+        assert!(backups[0].command.is_some());
+
+        let latest = change_diff(&path, &backups, 0).unwrap();
+        assert_eq!(latest.modified.len(), 1);
+        assert!(latest.added.is_empty());
+        let first = change_diff(&path, &backups, 1).unwrap();
+        assert_eq!(first.added.len(), 1);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use clap::ValueEnum;
+use std::fs;
+use std::path::Path;
+use taskai_core::{BacklogGenerator, Config, ImportSummary, TaskRow};
+use taskai_schema::Backlog;
+
+/// File format read by `taskai import --from`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// Comma-separated values, as written by `taskai export --to csv`.
+    Csv,
+    /// Plain text list, one task per line.
+    Lines,
+    /// Emacs Org document.
+    Org,
+    /// todo.txt list, one task per line.
+    TodoTxt,
+}
+
+/// Executes the "import" command: reads tasks from a CSV file, as written by `taskai export --to
+/// csv`, from an Org document or a todo.txt list, or from a plain list of tasks into the backlog.
+///
+/// CSV rows, Org headings and todo.txt lines are matched by ID: known tasks are updated, unknown
+/// ones added, and tasks missing from the file are kept. Columns missing from a CSV file leave the
+/// fields of the tasks alone. Each non-empty line of a list becomes a
+/// new Todo task; with `infer` set, the LLM groups them into epics and infers their dependencies.
+/// The backlog file is created, named after the file, if it does not exist yet. The file is read as
+/// CSV with `--from csv` or a `.csv` extension, as Org with `--from org` or a `.org` extension, as
+/// todo.txt with `--from todo-txt`, and as a list with `--from lines` or a `.txt` extension.
+pub async fn execute(
+    backlog_file: &Path,
+    file: &Path,
+    from: Option<ImportFormat>,
+    infer: bool,
+    format: Format,
+) {
+    let extension = file.extension().and_then(|ext| ext.to_str());
+    let input_format = match (from, extension) {
+        (Some(from), _) => from,
+        (None, Some("csv")) => ImportFormat::Csv,
+        (None, Some("txt")) => ImportFormat::Lines,
+        (None, Some("org")) => ImportFormat::Org,
+        (None, _) => error::fail(
+            ErrorKind::Usage,
+            format!(
+                "Cannot tell the format of {}; pass --from csv, lines, org or todo-txt.",
+                file.display()
+            ),
+        ),
+    };
+    let is_csv = input_format == ImportFormat::Csv;
+    let is_lines = input_format == ImportFormat::Lines;
+    if infer && !is_lines {
+        error::fail(
+            ErrorKind::Usage,
+            "--infer only applies to lists of tasks (--from lines).",
+        );
+    }
+
+    let (rows, columns) = if is_csv {
+        match read_rows(file) {
+            Ok(read) => read,
+            Err(err) => error::fail(ErrorKind::Parse, err),
+        }
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let text = if is_csv {
+        String::new()
+    } else {
+        match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(err) => error::fail(
+                ErrorKind::Other,
+                format!("Error reading {}: {}", file.display(), err),
+            ),
+        }
+    };
+    let titles = if is_lines {
+        taskai_core::parse_task_lines(&text)
+    } else {
+        Vec::new()
+    };
+
+    // Asked before locking the backlog, since the LLM may take a while
+    let structure = if infer && !titles.is_empty() {
+        let mut generator = BacklogGenerator::new();
+        if let Ok(Some((_, config))) = Config::discover(&store::project_root(backlog_file)) {
+            if let Some(model) = &config.generate.model {
+                generator = generator.with_model(model);
+            }
+        }
+        match generator.suggest_line_structure(&titles).await {
+            Ok(structure) => Some(structure),
+            Err(err) => error::fail(
+                ErrorKind::Llm,
+                format!("Error inferring epics and dependencies: {}", err),
+            ),
+        }
+    } else {
+        None
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let summary = if is_lines {
+        taskai_core::append_line_tasks(&mut backlog, &titles, structure.as_ref(), now)
+    } else if is_csv {
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        taskai_core::apply_task_rows(&mut backlog, &rows, &columns, now)
+    } else if input_format == ImportFormat::Org {
+        taskai_core::import_org(&mut backlog, &text, now)
+    } else {
+        taskai_core::import_todo_txt(&mut backlog, &text, now)
+    };
+    let summary = match summary {
+        Ok(summary) => summary,
+        Err(err) => error::fail(
+            ErrorKind::Validation,
+            format!("Cannot import {}: {}", file.display(), err),
+        ),
+    };
+
+    if !summary.added.is_empty() || !summary.updated.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+
+    output::print(format, &summary);
+}
+
+/// Executes `taskai import pivotal` and `taskai import shortcut`: reads the stories of a Pivotal
+/// Tracker or Shortcut export, as CSV or JSON, into the backlog.
+///
+/// Stories already imported update their task; the backlog file is created, named after the file,
+/// if it does not exist yet.
+pub fn stories(backlog_file: &Path, file: &Path, tracker: &str, format: Format) {
+    let text = match fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error reading {}: {}", file.display(), err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let summary = match tracker {
+        "pivotal" => taskai_core::import_pivotal(&mut backlog, &text, now),
+        _ => taskai_core::import_shortcut(&mut backlog, &text, now),
+    };
+    let summary = match summary {
+        Ok(summary) => summary,
+        Err(err) => error::fail(
+            ErrorKind::Parse,
+            format!("Cannot import {}: {}", file.display(), err),
+        ),
+    };
+    if !summary.added.is_empty() || !summary.updated.is_empty() || !summary.new_epics.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &summary);
+}
+
+/// Reads task rows from a CSV file with a header line, along with the columns of the header.
+fn read_rows(file: &Path) -> Result<(Vec<TaskRow>, Vec<String>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(file)
+        .map_err(|err| format!("Error reading {}: {}", file.display(), err))?;
+    let columns = reader
+        .headers()
+        .map_err(|err| format!("Error parsing {}: {}", file.display(), err))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let rows = reader
+        .deserialize()
+        .collect::<Result<Vec<TaskRow>, _>>()
+        .map_err(|err| format!("Error parsing {}: {}", file.display(), err))?;
+    Ok((rows, columns))
+}
+
+impl Render for ImportSummary {
+    fn print_text(&self) {
+        println!(
+            "{} task(s) added, {} updated, {} unchanged.",
+            self.added.len(),
+            self.updated.len(),
+            self.unchanged
+        );
+        if !self.new_epics.is_empty() {
+            println!("New epic(s): {}", self.new_epics.join(", "));
+        }
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that an edited spreadsheet updates and adds tasks, and that lists append tasks.
+    #[tokio::test]
+    async fn test_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            "project: test\ntasks:\n  - id: T-1\n    title: Setup\n",
+        )
+        .unwrap();
+
+        let csv_file = dir.path().join("tasks.csv");
+        fs::write(
+            &csv_file,
+            "id,title,epic,state,estimate,depends\n\
+             T-1,Setup,,done,2,\n\
+             T-2,\"Write docs, then publish\",E-1,Todo,,T-1\n",
+        )
+        .unwrap();
+
+        execute(&backlog_file, &csv_file, None, false, Format::Table).await;
+
+        let backlog = store::load(&backlog_file);
+        assert_eq!(backlog.tasks[0].estimate, Some(2.0));
+        assert!(backlog.tasks[0].completed_at.is_some());
+        assert_eq!(backlog.epics[0].tasks[0].title, "Write docs, then publish");
+        assert_eq!(backlog.epics[0].tasks[0].depends, vec!["T-1"]);
+
+        let list_file = dir.path().join("todo.txt");
+        fs::write(&list_file, "- Release\n\n- Announce it\n").unwrap();
+        execute(&backlog_file, &list_file, None, false, Format::Table).await;
+
+        let backlog = store::load(&backlog_file);
+        let titles: Vec<&str> = backlog.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Setup", "Release", "Announce it"]);
+        assert!(matches!(
+            backlog.tasks[2].state,
+            taskai_schema::TaskState::Todo
+        ));
+    }
+}
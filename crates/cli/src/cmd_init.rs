@@ -0,0 +1,192 @@
+use crate::error::{self, ErrorKind};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use taskai_core::{Config, GenerateConfig, ProjectConfig, CONFIG_FILE};
+
+/// Answers collected by the "init" command.
+pub struct Answers {
+    /// Name of the project.
+    pub name: String,
+    /// Kind of project (e.g. `cli`, `web`, `library`).
+    pub kind: String,
+    /// Programming language of the project.
+    pub language: String,
+    /// Constraints the implementation must respect.
+    pub constraints: Vec<String>,
+}
+
+/// Executes the "init" command: asks a few questions about the project, then writes a starter
+/// specification and a `.taskai.toml` pointing `taskai gen` at it.
+///
+/// Existing files are only overwritten when `force` is set.
+pub fn execute(dir: &Path, spec_name: &str, force: bool) {
+    let default_name = dir
+        .canonicalize()
+        .ok()
+        .and_then(|d| d.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "my-project".to_string());
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let answers = Answers {
+        name: prompt(&mut input, "Project name", &default_name),
+        kind: prompt(
+            &mut input,
+            "Project type (cli, web, library, service)",
+            "cli",
+        ),
+        language: prompt(&mut input, "Programming language", "Rust"),
+        constraints: prompt(&mut input, "Constraints (comma-separated)", "")
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+    };
+
+    match write_files(dir, spec_name, &answers, force) {
+        Ok(written) => {
+            for path in &written {
+                println!("Created {}", path.display());
+            }
+            println!();
+            println!(
+                "Describe your project in {}, then run `taskai gen > backlog.yaml`.",
+                spec_name
+            );
+        }
+        Err(err) => {
+            error::fail(ErrorKind::Other, err);
+        }
+    }
+}
+
+/// Asks a question on stdout and reads the answer, falling back to `default` on an empty answer.
+fn prompt(input: &mut impl BufRead, label: &str, default: &str) -> String {
+    if default.is_empty() {
+        print!("{}: ", label);
+    } else {
+        print!("{} [{}]: ", label, default);
+    }
+    let _ = io::stdout().flush();
+
+    let mut line = String::new();
+    if input.read_line(&mut line).is_err() {
+        return default.to_string();
+    }
+    match line.trim() {
+        "" => default.to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+/// Writes the starter specification and the configuration file, returning their paths.
+fn write_files(
+    dir: &Path,
+    spec_name: &str,
+    answers: &Answers,
+    force: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let spec_path = dir.join(spec_name);
+    let config_path = dir.join(CONFIG_FILE);
+
+    for path in [&spec_path, &config_path] {
+        if path.exists() && !force {
+            return Err(format!(
+                "{} already exists; use --force to overwrite it.",
+                path.display()
+            ));
+        }
+    }
+
+    let config = Config {
+        project: ProjectConfig {
+            name: Some(answers.name.clone()),
+            kind: Some(answers.kind.clone()),
+            language: Some(answers.language.clone()),
+        },
+        generate: GenerateConfig {
+            spec: Some(PathBuf::from(spec_name)),
+            lang: Some("en".to_string()),
+            style: Some("standard".to_string()),
+            model: None,
+        },
+        ..Default::default()
+    };
+    let config_toml = config.to_toml()?;
+
+    fs::write(&spec_path, render_spec(answers))
+        .map_err(|e| format!("Error writing {}: {}", spec_path.display(), e))?;
+    fs::write(&config_path, config_toml)
+        .map_err(|e| format!("Error writing {}: {}", config_path.display(), e))?;
+
+    Ok(vec![spec_path, config_path])
+}
+
+/// Renders the starter specification from the answers.
+fn render_spec(answers: &Answers) -> String {
+    let constraints = if answers.constraints.is_empty() {
+        "-\n".to_string()
+    } else {
+        answers
+            .constraints
+            .iter()
+            .map(|c| format!("- {}\n", c))
+            .collect()
+    };
+
+    format!(
+        "# {name}
+
+A {kind} project written in {language}.
+
+## Goals
+
+<!-- Describe what the project should do. Each goal usually becomes an epic. -->
+
+-
+
+## Constraints
+
+{constraints}
+## Success criteria
+
+<!-- How will you know the project is done? -->
+
+-
+",
+        name = answers.name,
+        kind = answers.kind,
+        language = answers.language,
+        constraints = constraints
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the spec and config are written and existing files are protected.
+    #[test]
+    fn test_write_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let answers = Answers {
+            name: "weather".to_string(),
+            kind: "cli".to_string(),
+            language: "Rust".to_string(),
+            constraints: vec!["No network in tests".to_string()],
+        };
+
+        write_files(dir.path(), "spec.md", &answers, false).unwrap();
+
+        let spec = fs::read_to_string(dir.path().join("spec.md")).unwrap();
+        assert!(spec.starts_with("# weather\n"));
+        assert!(spec.contains("- No network in tests\n"));
+
+        let config = Config::load(&dir.path().join(CONFIG_FILE)).unwrap();
+        assert_eq!(config.generate.spec, Some(PathBuf::from("spec.md")));
+
+        assert!(write_files(dir.path(), "spec.md", &answers, false).is_err());
+        assert!(write_files(dir.path(), "spec.md", &answers, true).is_ok());
+    }
+}
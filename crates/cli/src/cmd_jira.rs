@@ -0,0 +1,355 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{JiraClient, JiraConfig};
+use taskai_schema::{Backlog, TaskState};
+
+/// A Jira issue created, or to be created, for an epic or a task.
+#[derive(Serialize)]
+struct ExportedItem {
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue: Option<String>,
+}
+
+/// The issues and links created by `export jira`.
+#[derive(Serialize)]
+struct ExportReport<'a> {
+    project: &'a str,
+    dry_run: bool,
+    epics: Vec<ExportedItem>,
+    tasks: Vec<ExportedItem>,
+    /// Dependencies turned into issue links, as `(task, dependency)`.
+    links: Vec<(String, String)>,
+    /// Epics and tasks skipped because they already have an issue.
+    skipped: usize,
+}
+
+/// Executes the "export jira" command: creates a Jira issue in `project` for each epic and task of
+/// the backlog, and links the issues of dependent tasks.
+///
+/// Epics become Jira epics and tasks issues of the configured type under their epic, with the
+/// description, `done_when` criteria, tags, due date and mapped custom fields; issues of Done tasks
+/// are moved to a done status. Dependencies involving a newly created issue become issue links. The
+/// created issues are recorded in the `external_refs` of the epics and tasks, so the command can be
+/// run again as the backlog grows. Requires `JIRA_EMAIL` and `JIRA_API_TOKEN`, unless `dry_run` is
+/// set.
+pub async fn export(backlog_file: &Path, project: Option<String>, dry_run: bool, format: Format) {
+    let config = store::config(backlog_file).jira;
+    let project = project_key(project, &config);
+    let client = client(&config);
+    if !dry_run && !client.has_credentials() {
+        error::fail(
+            ErrorKind::Usage,
+            "Set JIRA_EMAIL and JIRA_API_TOKEN to an account allowed to create issues.",
+        );
+    }
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = ExportReport {
+        project: &project,
+        dry_run,
+        epics: Vec::new(),
+        tasks: Vec::new(),
+        links: Vec::new(),
+        skipped: 0,
+    };
+
+    let failure = create_issues(&client, &mut backlog, &project, &config, &mut report).await;
+    let created = report
+        .epics
+        .iter()
+        .chain(&report.tasks)
+        .any(|i| i.issue.is_some());
+    if created {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+}
+
+/// Creates the issues of the epics and tasks without one, then the links of their dependencies,
+/// recording them in `report`. Returns the error that stopped the export, if any.
+async fn create_issues(
+    client: &JiraClient,
+    backlog: &mut Backlog,
+    project: &str,
+    config: &JiraConfig,
+    report: &mut ExportReport<'_>,
+) -> Option<String> {
+    let dry_run = report.dry_run;
+    let mut created: Vec<String> = Vec::new();
+
+    for index in 0..backlog.epics.len() {
+        let epic = &backlog.epics[index];
+        if taskai_core::jira_key(&epic.external_refs).is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        let mut item = ExportedItem {
+            id: epic.id.clone(),
+            title: epic.title.clone(),
+            issue: None,
+        };
+        if !dry_run {
+            let fields = taskai_core::jira_epic_fields(epic, project, config);
+            match client.create_issue(&fields).await {
+                Ok(key) => {
+                    let external_ref = taskai_core::jira_ref(&key, &client.browse_url(&key));
+                    backlog.epics[index].external_refs.push(external_ref);
+                    item.issue = Some(key);
+                }
+                Err(err) => return Some(format!("Error creating the epic {}: {}", item.id, err)),
+            }
+        }
+        created.push(item.id.clone());
+        report.epics.push(item);
+    }
+
+    let pending: Vec<(String, Option<String>)> = backlog
+        .tasks
+        .iter()
+        .map(|t| (t, None))
+        .chain(
+            backlog
+                .epics
+                .iter()
+                .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e)))),
+        )
+        .filter(|(task, _)| {
+            let exported = taskai_core::jira_key(&task.external_refs).is_some();
+            report.skipped += usize::from(exported);
+            !exported
+        })
+        .map(|(task, epic)| {
+            let epic_key = epic.and_then(|e| taskai_core::jira_key(&e.external_refs));
+            (task.id.clone(), epic_key.map(str::to_string))
+        })
+        .collect();
+    for (task_id, epic_key) in pending {
+        let Some(task) = backlog.find_task(&task_id) else {
+            continue;
+        };
+        let mut item = ExportedItem {
+            id: task_id.clone(),
+            title: task.title.clone(),
+            issue: None,
+        };
+        let done = matches!(task.state, TaskState::Done);
+        if !dry_run {
+            let fields = taskai_core::jira_task_fields(task, epic_key.as_deref(), project, config);
+            let key = match client.create_issue(&fields).await {
+                Ok(key) => key,
+                Err(err) => {
+                    return Some(format!("Error creating the issue of {}: {}", task_id, err))
+                }
+            };
+            // Recorded before the transition, so that a failure never leads to a duplicate issue
+            if let Some(task) = backlog.find_task_mut(&task_id) {
+                let external_ref = taskai_core::jira_ref(&key, &client.browse_url(&key));
+                task.external_refs.push(external_ref);
+            }
+            item.issue = Some(key.clone());
+            report.tasks.push(item);
+            if done {
+                if let Err(err) = client.transition_to_done(&key).await {
+                    return Some(format!("Error closing the issue of {}: {}", task_id, err));
+                }
+            }
+        } else {
+            report.tasks.push(item);
+        }
+        created.push(task_id);
+    }
+
+    // Links already exist between issues exported by an earlier run
+    let link_type = config.link_type.as_deref().unwrap_or("Blocks");
+    let key_of = |backlog: &Backlog, id: &str| {
+        let task = backlog.find_task(id)?;
+        taskai_core::jira_key(&task.external_refs).map(str::to_string)
+    };
+    let links: Vec<(String, String)> = backlog
+        .all_tasks()
+        .iter()
+        .flat_map(|t| t.depends.iter().map(move |dep| (t.id.clone(), dep.clone())))
+        .filter(|(task, dep)| created.contains(task) || created.contains(dep))
+        .filter(|(_, dep)| dry_run || key_of(backlog, dep).is_some())
+        .collect();
+    for (task, dep) in links {
+        if !dry_run {
+            let (Some(blocked), Some(blocker)) = (key_of(backlog, &task), key_of(backlog, &dep))
+            else {
+                continue;
+            };
+            if let Err(err) = client.link_issues(link_type, &blocker, &blocked).await {
+                return Some(format!("Error linking {} to {}: {}", task, dep, err));
+            }
+        }
+        report.links.push((task, dep));
+    }
+    None
+}
+
+/// Executes the "import jira" command: brings the issues of `project` into the backlog.
+///
+/// Jira epics become epics and the other issues tasks under the epic they belong to, issue links
+/// becoming dependencies. Epics and tasks already imported or exported get their title and state
+/// updated. The backlog file is created, named after the project, if it does not exist yet.
+pub async fn import(backlog_file: &Path, project: Option<String>, format: Format) {
+    let config = store::config(backlog_file).jira;
+    let project = project_key(project, &config);
+    let client = client(&config);
+    // Fetched before locking the backlog, since a large project takes a while
+    let issues = match client.search_issues(&project, &config).await {
+        Ok(issues) => issues,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listing the issues of {}: {}", project, err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = if backlog_file.exists() {
+        store::load(backlog_file)
+    } else {
+        Backlog {
+            project: project.clone(),
+            ..Default::default()
+        }
+    };
+
+    let summary = taskai_core::import_jira_issues(
+        &mut backlog,
+        &issues,
+        &config,
+        |key| client.browse_url(key),
+        chrono::Utc::now(),
+    );
+    if !summary.added.is_empty() || !summary.updated.is_empty() || !summary.new_epics.is_empty() {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &summary);
+}
+
+/// Returns the project key given on the command line or in the configuration, exiting with a
+/// usage error if there is none.
+pub fn project_key(project: Option<String>, config: &JiraConfig) -> String {
+    match project.or_else(|| config.project.clone()) {
+        Some(project) => project,
+        None => error::fail(
+            ErrorKind::Usage,
+            "Pass --project or set jira.project in .taskai.toml.",
+        ),
+    }
+}
+
+/// Creates the Jira client, exiting with a usage error if no Jira site is configured.
+pub fn client(config: &JiraConfig) -> JiraClient {
+    match JiraClient::from_env(config.url.as_deref()) {
+        Ok(client) => client,
+        Err(err) => error::fail(ErrorKind::Usage, err),
+    }
+}
+
+impl Render for ExportReport<'_> {
+    fn print_text(&self) {
+        if self.epics.is_empty() && self.tasks.is_empty() {
+            println!("No epics or tasks to export to {}.", self.project);
+        } else {
+            let verb = if self.dry_run {
+                "Would create"
+            } else {
+                "Created"
+            };
+            println!(
+                "{} {} epic(s) and {} issue(s) in {}:",
+                verb,
+                self.epics.len(),
+                self.tasks.len(),
+                self.project
+            );
+        }
+        for item in self.epics.iter().chain(&self.tasks) {
+            match &item.issue {
+                Some(issue) => println!("  {} -> {}", item.id, issue),
+                None => println!("  {}: {}", item.id, item.title),
+            }
+        }
+        for (task, dep) in &self.links {
+            let verb = if self.dry_run { "Would link" } else { "Linked" };
+            println!("{} {} as blocked by {}", verb, task, dep);
+        }
+        if self.skipped > 0 {
+            println!(
+                "Skipped {} epic(s) and task(s) already exported.",
+                self.skipped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the plan of a dry run: the epics and tasks without an issue, and the links of the
+    /// dependencies involving them.
+    #[tokio::test]
+    async fn test_export_dry_run() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: Exported
+    external_refs:
+      - system: jira
+        id: APP-3
+  - id: T-2
+    title: New
+    depends: [T-1]
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Endpoint
+        depends: [T-2]
+"#,
+        )
+        .unwrap();
+        let config = JiraConfig::default();
+        let client = JiraClient::from_env(Some("https://jira.example.com")).unwrap();
+        let mut report = ExportReport {
+            project: "APP",
+            dry_run: true,
+            epics: Vec::new(),
+            tasks: Vec::new(),
+            links: Vec::new(),
+            skipped: 0,
+        };
+
+        let failure = create_issues(&client, &mut backlog, "APP", &config, &mut report).await;
+        assert!(failure.is_none());
+
+        let ids = |items: &[ExportedItem]| items.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&report.epics), vec!["E-1"]);
+        assert_eq!(ids(&report.tasks), vec!["T-2", "E-1-1"]);
+        assert!(report.tasks.iter().all(|i| i.issue.is_none()));
+        assert_eq!(
+            report.links,
+            vec![
+                ("T-2".to_string(), "T-1".to_string()),
+                ("E-1-1".to_string(), "T-2".to_string()),
+            ]
+        );
+        assert_eq!(report.skipped, 1);
+        assert!(backlog.find_task("T-2").unwrap().external_refs.is_empty());
+    }
+}
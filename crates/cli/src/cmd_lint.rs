@@ -0,0 +1,168 @@
+use crate::cmd_validate::{format_diagnostic, severity_name};
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{Diagnostic, LintOptions, Severity};
+
+/// Minimum severity of the problems reported, selected with `taskai lint --severity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MinSeverity {
+    /// Report warnings and errors.
+    Warning,
+    /// Report errors only.
+    Error,
+}
+
+impl From<MinSeverity> for Severity {
+    fn from(severity: MinSeverity) -> Self {
+        match severity {
+            MinSeverity::Warning => Severity::Warning,
+            MinSeverity::Error => Severity::Error,
+        }
+    }
+}
+
+/// Problems found by the lint rules, and the fixes applied before checking.
+#[derive(Serialize)]
+struct LintReport<'a> {
+    file: &'a Path,
+    #[serde(skip)]
+    fixed: bool,
+    fixes: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Executes the "lint" command: runs the lint rules on the backlog and reports the problems found.
+///
+/// With `fix` set, auto-fixable problems (non-canonical IDs, duplicate or self dependencies) are fixed
+/// and the backlog file is rewritten before the remaining problems are reported. Only problems at or
+/// above `min_severity` are reported. Exits with status 3 if any problem is reported.
+pub fn execute(
+    backlog_file: &Path,
+    fix: bool,
+    min_severity: Severity,
+    stale_days: i64,
+    format: Format,
+) {
+    let _lock = fix.then(|| store::lock(backlog_file));
+    let mut backlog = store::load(backlog_file);
+
+    let mut fixes = Vec::new();
+    if fix {
+        fixes = taskai_core::fix_backlog(&mut backlog);
+        if !fixes.is_empty() {
+            store::save(backlog_file, &backlog);
+        }
+    }
+
+    let options = LintOptions {
+        stale_after: chrono::Duration::days(stale_days),
+        ..Default::default()
+    };
+    let source = store::read(backlog_file);
+    let diagnostics: Vec<Diagnostic> = taskai_core::lint_backlog(&backlog, &source, &options)
+        .into_iter()
+        .filter(|d| d.severity >= min_severity)
+        .collect();
+
+    let report = LintReport {
+        file: backlog_file,
+        fixed: fix,
+        fixes,
+        diagnostics,
+    };
+    output::print(format, &report);
+
+    if !report.diagnostics.is_empty() {
+        error::exit(ErrorKind::Validation);
+    }
+}
+
+impl Render for LintReport<'_> {
+    fn print_text(&self) {
+        for fix in &self.fixes {
+            println!("fixed: {}", fix);
+        }
+
+        for diagnostic in &self.diagnostics {
+            let mut line = format_diagnostic(self.file, diagnostic);
+            if !self.fixed && taskai_core::is_fixable(&diagnostic.code) {
+                line.push_str(" (fixable with --fix)");
+            }
+            println!("{}", line);
+        }
+
+        if self.diagnostics.is_empty() {
+            println!("No lint problems found.");
+        } else {
+            println!("{} lint problem(s) found.", self.diagnostics.len());
+        }
+    }
+
+    fn print_markdown(&self) {
+        for fix in &self.fixes {
+            println!("- Fixed: {}", fix);
+        }
+        if !self.fixes.is_empty() {
+            println!();
+        }
+
+        if self.diagnostics.is_empty() {
+            println!("No lint problems found.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                vec![
+                    severity_name(&d.severity).to_string(),
+                    format!("`{}`", d.code),
+                    d.task_id.clone().unwrap_or_default(),
+                    d.message.clone(),
+                ]
+            })
+            .collect();
+        output::print_table(true, 0, &["Severity", "Code", "Task", "Message"], &rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use taskai_schema::Backlog;
+    use tempfile::NamedTempFile;
+
+    /// Tests that fixable problems are fixed in the backlog file.
+    #[test]
+    fn test_lint_fix() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: api_1
+    title: First
+  - id: API-2
+    title: Second
+    depends: [api_1, api_1]
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(file.path(), true, Severity::Warning, 7, Format::Table);
+
+        let content = fs::read_to_string(file.path()).unwrap();
+        let backlog: Backlog = serde_yaml::from_str(&content).unwrap();
+
+        assert_eq!(backlog.tasks[0].id, "API-1");
+        assert_eq!(backlog.tasks[1].depends, vec!["API-1"]);
+    }
+}
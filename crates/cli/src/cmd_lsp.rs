@@ -0,0 +1,344 @@
+use crate::cmd_ci;
+use crate::error::{self, ErrorKind};
+use crate::store;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use taskai_core::{Config, Diagnostic, Severity};
+use taskai_schema::Backlog;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Executes the "lsp" command: serves the Language Server Protocol over stdio for backlog files,
+/// so that editors show validation and lint problems as diagnostics, complete task IDs in
+/// `depends`, show task details on hover and jump from a dependency to its task.
+///
+/// Documents are synchronized in full on every change. Stdout carries the protocol, so nothing
+/// else is printed there; the server stops on the `exit` notification or when stdin is closed.
+pub async fn serve() {
+    let mut server = Server::default();
+    let mut stdin = tokio::io::BufReader::new(tokio::io::stdin());
+    let mut stdout = tokio::io::stdout();
+    loop {
+        let message = match read_message(&mut stdin).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => error::fail(ErrorKind::Other, format!("Error reading stdin: {}", err)),
+        };
+        let exit = message.get("method").and_then(Value::as_str) == Some("exit");
+        for response in server.handle(message) {
+            if let Err(err) = write_message(&mut stdout, &response).await {
+                error::fail(ErrorKind::Other, format!("Error writing stdout: {}", err));
+            }
+        }
+        if exit {
+            break;
+        }
+    }
+}
+
+/// Language server over the backlog files open in the editor.
+#[derive(Default)]
+struct Server {
+    /// Content of the open documents, by URI.
+    documents: HashMap<String, String>,
+}
+
+impl Server {
+    /// Handles a JSON-RPC message, returning the messages to write back: the response to a request,
+    /// and the diagnostics published after a document changed.
+    fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+        let Some(id) = message.get("id").cloned() else {
+            return self.notify(method, &uri, &params);
+        };
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": ["[", ",", " "] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": { "name": "taskai", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/completion" => Ok(self.completion(&uri, &params)),
+            "textDocument/hover" => Ok(self.hover(&uri, &params)),
+            "textDocument/definition" => Ok(self.definition(&uri, &params)),
+            _ => Err(json!({
+                "code": -32601,
+                "message": format!("Method not found: {}", method),
+            })),
+        };
+        vec![match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        }]
+    }
+
+    /// Handles a notification, returning the diagnostics to publish when a document changed.
+    fn notify(&mut self, method: &str, uri: &str, params: &Value) -> Vec<Value> {
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // Full synchronization: the last change holds the whole document
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return vec![publish(uri, Vec::new())];
+            }
+            _ => None,
+        };
+        let Some(text) = text else {
+            return Vec::new();
+        };
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![publish(uri, diagnostics(uri, text))]
+    }
+
+    /// Completes the task IDs of the document within `depends` fields.
+    fn completion(&self, uri: &str, params: &Value) -> Value {
+        let Some((source, line, column)) = self.position(uri, params) else {
+            return Value::Null;
+        };
+        if !taskai_core::in_depends(source, line, column) {
+            return json!([]);
+        }
+        let Ok(backlog) = serde_yaml::from_str::<Backlog>(source) else {
+            return json!([]);
+        };
+        let items: Vec<Value> = backlog
+            .all_tasks()
+            .into_iter()
+            .map(|task| {
+                let summary = taskai_core::task_summary(&backlog, &task.id);
+                json!({
+                    "label": task.id,
+                    // Reference
+                    "kind": 18,
+                    "detail": task.title,
+                    "documentation": { "kind": "markdown", "value": summary },
+                })
+            })
+            .collect();
+        json!(items)
+    }
+
+    /// Shows the details of the task whose ID is under the cursor.
+    fn hover(&self, uri: &str, params: &Value) -> Value {
+        let summary = self
+            .position(uri, params)
+            .and_then(|(source, line, column)| {
+                let id = taskai_core::word_at(source, line, column)?;
+                let backlog = serde_yaml::from_str::<Backlog>(source).ok()?;
+                taskai_core::task_summary(&backlog, id)
+            });
+        match summary {
+            Some(summary) => json!({ "contents": { "kind": "markdown", "value": summary } }),
+            None => Value::Null,
+        }
+    }
+
+    /// Points at the declaration of the task whose ID is under the cursor.
+    fn definition(&self, uri: &str, params: &Value) -> Value {
+        let line = self
+            .position(uri, params)
+            .and_then(|(source, line, column)| {
+                let id = taskai_core::word_at(source, line, column)?;
+                taskai_core::task_definition(source, id)
+            });
+        match line {
+            Some(line) => json!({ "uri": uri, "range": range(line, 0, 0) }),
+            None => Value::Null,
+        }
+    }
+
+    /// Returns the document and the 0-based line and column of a position request.
+    fn position(&self, uri: &str, params: &Value) -> Option<(&str, usize, usize)> {
+        let source = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64()?;
+        let column = params["position"]["character"].as_u64()?;
+        Some((source, line as usize, column as usize))
+    }
+}
+
+/// Validates and lints a document, following the `lint` configuration of its project.
+fn diagnostics(uri: &str, text: &str) -> Vec<Value> {
+    let config = file_path(uri)
+        .and_then(|path| Config::discover(&store::project_root(&path)).ok().flatten())
+        .map(|(_, config)| config.lint)
+        .unwrap_or_default();
+    cmd_ci::check(text, false, &config)
+        .iter()
+        .map(|diagnostic| lsp_diagnostic(text, diagnostic))
+        .collect()
+}
+
+/// Converts a diagnostic into an LSP one, spanning the rest of its line.
+fn lsp_diagnostic(text: &str, diagnostic: &Diagnostic) -> Value {
+    let location = diagnostic.location.as_ref();
+    let line = location.and_then(|l| l.line).unwrap_or(1) - 1;
+    let column = location.and_then(|l| l.column).unwrap_or(1) - 1;
+    let end = text
+        .lines()
+        .nth(line)
+        .map_or(column, |l| l.encode_utf16().count().max(column));
+    json!({
+        "range": range(line, column, end),
+        "severity": match diagnostic.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+        },
+        "code": diagnostic.code,
+        "source": "taskai",
+        "message": diagnostic.message,
+    })
+}
+
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn range(line: usize, start: usize, end: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end },
+    })
+}
+
+/// Returns the path of a `file://` URI, decoding its percent-encoded bytes.
+fn file_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let hex = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (encoded[i], hex) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// Reads a message framed by a `Content-Length` header, or `None` at the end of the input.
+async fn read_message<R: AsyncBufRead + Unpin>(input: &mut R) -> Result<Option<Value>, String> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        let read = input
+            .read_line(&mut header)
+            .await
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let Some(length) = length else {
+        return Err("missing Content-Length header".to_string());
+    };
+    let mut body = vec![0; length];
+    input
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| format!("invalid message: {}", e))
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    let framed = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+    output.write_all(framed.as_bytes()).await?;
+    output.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests diagnostics, completion, hover and go-to-definition on an open document.
+    #[tokio::test]
+    async fn test_language_features() {
+        let text = "\
+project: test
+tasks:
+  - id: T-1
+    title: Parser
+  - id: T-2
+    title: Interpreter
+    depends: [T-1, T-9]
+";
+        let body = json!(text).to_string();
+        let framed = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut input = framed.as_bytes();
+        let read = read_message(&mut input).await.unwrap().unwrap();
+        assert_eq!(read, json!(text));
+
+        let mut server = Server::default();
+        let uri = "file:///tmp/project%20a/tasks.yml";
+        assert_eq!(
+            file_path(uri),
+            Some(PathBuf::from("/tmp/project a/tasks.yml"))
+        );
+        let opened = server.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "text": text } },
+        }));
+        let diagnostics = &opened[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics[0]["code"], "missing-dependency");
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 6);
+
+        let request = |method: &str, line: usize, character: usize| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": {
+                    "textDocument": { "uri": uri },
+                    "position": { "line": line, "character": character },
+                },
+            })
+        };
+        let completion = server.handle(request("textDocument/completion", 6, 19));
+        assert_eq!(completion[0]["result"][0]["label"], "T-1");
+        let hover = server.handle(request("textDocument/hover", 6, 15));
+        let contents = hover[0]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(contents.starts_with("**T-1** Parser"));
+        let definition = server.handle(request("textDocument/definition", 6, 15));
+        assert_eq!(definition[0]["result"]["range"]["start"]["line"], 2);
+    }
+}
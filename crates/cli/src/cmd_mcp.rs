@@ -0,0 +1,313 @@
+use crate::cmd_add::{self, NewTask};
+use crate::cmd_gen;
+use crate::error::{self, ErrorKind};
+use crate::notifications;
+use crate::store;
+use crate::tools::{self, AddTask, GenerateBacklog, GetTask, ListReadyTasks, MarkDone};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use taskai_core::{BacklogGenerator, NotifyEvent, ReadyFilter};
+use taskai_schema::TaskState;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Version of the Model Context Protocol spoken by the server.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Executes the "mcp" command: serves the backlog as Model Context Protocol tools over stdio, so
+/// that agents can list, read, add and complete tasks, and generate backlogs, without a shell.
+///
+/// Requests and responses are JSON-RPC messages, one per line. Stdout carries the protocol, so
+/// nothing else is printed there; the server stops when stdin is closed.
+pub async fn serve(backlog_file: &Path) {
+    let server = Server {
+        backlog_file: backlog_file.to_path_buf(),
+    };
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => error::fail(ErrorKind::Other, format!("Error reading stdin: {}", err)),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => server.handle(message).await,
+            Err(err) => Some(failure(
+                Value::Null,
+                -32700,
+                format!("Parse error: {}", err),
+            )),
+        };
+        if let Some(response) = response {
+            let written = stdout
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .and(stdout.flush().await);
+            if let Err(err) = written {
+                error::fail(ErrorKind::Other, format!("Error writing stdout: {}", err));
+            }
+        }
+    }
+}
+
+/// MCP server over one backlog file.
+struct Server {
+    backlog_file: PathBuf,
+}
+
+impl Server {
+    /// Handles a JSON-RPC message, returning the response to write back. Notifications, which have
+    /// no `id`, get none.
+    async fn handle(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match message.get("method").and_then(Value::as_str) {
+            Some("initialize") => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "taskai", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            Some("ping") => Ok(json!({})),
+            Some("tools/list") => Ok(json!({ "tools": tools::mcp(&tools::definitions()) })),
+            Some("tools/call") => self.call(&params).await,
+            Some(method) => Err((-32601, format!("Method not found: {}", method))),
+            None => Err((-32600, "Invalid request: missing method".to_string())),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(id, code, message),
+        })
+    }
+
+    /// Calls a tool. Failures of the tool itself, such as an unknown task, are reported in the
+    /// result for the agent to read, not as protocol errors.
+    async fn call(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+        let args = params.get("arguments").cloned().unwrap_or(json!({}));
+        let outcome = match name {
+            "list_ready_tasks" => self.list_ready_tasks(args),
+            "get_task" => self.get_task(args),
+            "mark_done" => self.mark_done(args).await,
+            "add_task" => self.add_task(args),
+            "generate_backlog" => self.generate_backlog(args).await,
+            _ => return Err((-32602, format!("Unknown tool: {}", name))),
+        };
+        let (text, is_error) = match outcome {
+            Ok(text) => (text, false),
+            Err(err) => (err, true),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    fn list_ready_tasks(&self, args: Value) -> Result<String, String> {
+        let args: ListReadyTasks = arguments("list_ready_tasks", args)?;
+        let backlog = store::try_load(&self.backlog_file)?;
+        let filter = ReadyFilter {
+            epic: args.epic,
+            tag: args.tag,
+            assignee: args.assignee,
+        };
+        let limit = args.limit.unwrap_or(20);
+        let tasks: Vec<_> = taskai_core::filter_ready_tasks(&backlog, &filter)
+            .into_iter()
+            .take(limit)
+            .collect();
+        to_json(&tasks)
+    }
+
+    fn get_task(&self, args: Value) -> Result<String, String> {
+        let GetTask { id } = arguments("get_task", args)?;
+        let backlog = store::try_load(&self.backlog_file)?;
+        let task = backlog
+            .find_task(&id)
+            .ok_or_else(|| format!("Task with ID '{}' not found in the backlog.", id))?;
+        let epic = backlog
+            .epics
+            .iter()
+            .find(|e| e.tasks.iter().any(|t| t.id == id))
+            .map(|e| json!({ "id": e.id, "title": e.title }));
+        let mut task = serde_json::to_value(task).map_err(|e| e.to_string())?;
+        if let (Some(epic), Some(fields)) = (epic, task.as_object_mut()) {
+            fields.insert("epic".to_string(), epic);
+        }
+        to_json(&task)
+    }
+
+    async fn mark_done(&self, args: Value) -> Result<String, String> {
+        let MarkDone { id, note } = arguments("mark_done", args)?;
+        let now = chrono::Utc::now();
+        let lock = store::lock(&self.backlog_file);
+        let mut backlog = store::try_load(&self.backlog_file)?;
+        let Some(task) = backlog.find_task_mut(&id) else {
+            return Err(format!("Task with ID '{}' not found in the backlog.", id));
+        };
+        let completed = !matches!(task.state, TaskState::Done);
+        task.set_state(TaskState::Done, now);
+        if let Some(note) = &note {
+            task.add_note(note, now);
+        }
+        store::try_save(&self.backlog_file, &backlog)?;
+        drop(lock);
+
+        let mut message = format!("Task {} marked as done.", id);
+        if let Some(task) = backlog.find_task(&id) {
+            let unfinished = taskai_core::unfinished_dependencies(&backlog, task);
+            if !unfinished.is_empty() {
+                let dep_ids: Vec<&str> = unfinished.iter().map(|t| t.id.as_str()).collect();
+                message.push_str(&format!(
+                    " Warning: it depends on unfinished task(s): {}",
+                    dep_ids.join(", ")
+                ));
+            }
+        }
+        if completed {
+            notifications::tasks(&self.backlog_file, &backlog, NotifyEvent::Done, &[id]).await;
+        }
+        Ok(message)
+    }
+
+    fn add_task(&self, args: Value) -> Result<String, String> {
+        let args: AddTask = arguments("add_task", args)?;
+        let new_task = NewTask {
+            title: args.title,
+            id: args.id,
+            epic: args.epic,
+            depends: args.depends,
+            deliverables: args.deliverables,
+            description: args.description,
+        };
+        let _lock = store::lock(&self.backlog_file);
+        let mut backlog = store::try_load(&self.backlog_file)?;
+        let id = cmd_add::insert_task(&mut backlog, new_task, chrono::Utc::now())
+            .map_err(|(_, message)| message)?;
+        store::try_save(&self.backlog_file, &backlog)?;
+        Ok(format!("Task {} added.", id))
+    }
+
+    async fn generate_backlog(&self, args: Value) -> Result<String, String> {
+        let args: GenerateBacklog = arguments("generate_backlog", args)?;
+        let config = store::config(&self.backlog_file).generate;
+        let lang = args
+            .lang
+            .or(config.lang)
+            .unwrap_or_else(|| "en".to_string());
+        let style = args
+            .style
+            .or(config.style)
+            .unwrap_or_else(|| "standard".to_string());
+        let mut generator = BacklogGenerator::new()
+            .with_language(&lang)
+            .with_style(&style);
+        if let Some(model) = &config.model {
+            generator = generator.with_model(model);
+        }
+        let backlog = generator
+            .generate(&args.spec)
+            .await
+            .map_err(|e| format!("Error generating backlog: {}", e))?;
+        let tasks = backlog.all_tasks().len();
+        let path = self.backlog_file.display();
+        match cmd_gen::write_backlog(&self.backlog_file, backlog, args.merge, false)? {
+            Some(summary) => Ok(format!(
+                "Merged into {}: {} task(s) preserved, {} added, {} kept from the existing backlog.",
+                path,
+                summary.preserved.len(),
+                summary.added.len(),
+                summary.kept.len()
+            )),
+            None => Ok(format!("Backlog of {} task(s) written to {}.", tasks, path)),
+        }
+    }
+}
+
+fn failure(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Parses the arguments of a tool call.
+fn arguments<T: DeserializeOwned>(name: &str, args: Value) -> Result<T, String> {
+    serde_json::from_value(args).map_err(|e| format!("Invalid arguments for {}: {}", name, e))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Error serializing to JSON: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests the handshake, and that tasks added through the tools can be read back.
+    #[tokio::test]
+    async fn test_tools() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+        let server = Server {
+            backlog_file: file.path().to_path_buf(),
+        };
+
+        let init = server
+            .handle(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }))
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["serverInfo"]["name"], "taskai");
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle(notification).await.is_none());
+
+        let call = |name: &str, arguments: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments },
+            })
+        };
+        let added = server
+            .handle(call(
+                "add_task",
+                json!({ "title": "Second", "depends": ["T-1"] }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(added["result"]["content"][0]["text"], "Task T-2 added.");
+
+        let task = server
+            .handle(call("get_task", json!({ "id": "T-2" })))
+            .await
+            .unwrap();
+        let text = task["result"]["content"][0]["text"].as_str().unwrap();
+        let task: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(task["depends"], json!(["T-1"]));
+
+        let missing = server
+            .handle(call("get_task", json!({ "id": "T-9" })))
+            .await
+            .unwrap();
+        assert_eq!(missing["result"]["isError"], true);
+
+        let unknown = server.handle(call("delete_task", json!({}))).await.unwrap();
+        assert_eq!(unknown["error"]["code"], -32602);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::cmd_gen;
+use crate::error::{self, ErrorKind};
+use crate::store;
+use std::path::Path;
+
+/// Executes the "merge-backlogs" command: combines two backlog files into one.
+///
+/// Task and epic IDs of the second backlog that collide with the first are namespaced with `prefix`,
+/// which defaults to the second project's name (`web-app` gives `WEB-APP-T-1`). The merged backlog is
+/// printed as YAML, or written to `output` (existing files are only replaced with `force`).
+pub fn execute(
+    first_file: &Path,
+    second_file: &Path,
+    prefix: Option<&str>,
+    output: Option<&Path>,
+    force: bool,
+) {
+    let first = store::load(first_file);
+    let second = store::load(second_file);
+
+    let prefix = match prefix {
+        Some(prefix) => prefix.to_string(),
+        None => taskai_core::normalize_task_id(&second.project),
+    };
+    let (combined, summary) = match taskai_core::combine_backlogs(first, second, &prefix) {
+        Ok(result) => result,
+        Err(err) => {
+            error::fail(
+                ErrorKind::Validation,
+                format!("Error merging backlogs: {}", err),
+            );
+        }
+    };
+
+    for (old, new) in &summary.renamed {
+        eprintln!("Renamed {} from {} to {}.", old, second_file.display(), new);
+    }
+    for key in &summary.environment_conflicts {
+        eprintln!(
+            "Warning: environment variable {} differs; keeping the value from {}.",
+            key,
+            first_file.display()
+        );
+    }
+
+    match output {
+        Some(output) => {
+            if let Err(err) = cmd_gen::write_backlog(output, combined, false, force) {
+                error::fail(ErrorKind::Other, err);
+            }
+            println!("Merged backlog written to {}.", output.display());
+        }
+        None => match taskai_core::format_backlog(&combined) {
+            Ok(yaml) => print!("{}", yaml),
+            Err(err) => {
+                error::fail(
+                    ErrorKind::Other,
+                    format!("Error serializing backlog to YAML: {}", err),
+                );
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that colliding IDs of the second backlog are namespaced in the merged file.
+    #[test]
+    fn test_merge_backlogs() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("backend.yaml");
+        let second = dir.path().join("frontend.yaml");
+        let output = dir.path().join("merged.yaml");
+        fs::write(
+            &first,
+            "project: backend\ntasks:\n  - id: T-1\n    title: API\n",
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            "project: web app\ntasks:\n  - id: T-1\n    title: Pages\n",
+        )
+        .unwrap();
+
+        execute(&first, &second, None, Some(&output), false);
+
+        let merged = store::load(&output);
+        let ids: Vec<&str> = merged.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["T-1", "WEB-APP-T-1"]);
+    }
+}
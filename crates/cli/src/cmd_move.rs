@@ -0,0 +1,83 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+
+/// Tasks moved by the "move" command and where they went.
+#[derive(Serialize)]
+struct MoveReport<'a> {
+    moved: Vec<String>,
+    epic: Option<&'a str>,
+}
+
+/// Executes the "move" command: moves a task and its subtasks to another epic, or to the standalone
+/// task list when `epic` is `None`.
+///
+/// The backlog is validated before being written, so a move never leaves it inconsistent.
+pub fn execute(backlog_file: &Path, task_id: &str, epic: Option<&str>, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let moved = match taskai_core::move_task(&mut backlog, task_id, epic) {
+        Ok(moved) => moved,
+        Err(err) => {
+            error::fail(ErrorKind::NotFound, err);
+        }
+    };
+    if let Err(err) = backlog.validate() {
+        error::fail(
+            ErrorKind::Validation,
+            format!("Backlog is invalid after the move: {}", err),
+        );
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(format, &MoveReport { moved, epic });
+}
+
+impl Render for MoveReport<'_> {
+    fn print_text(&self) {
+        let destination = match self.epic {
+            Some(epic) => format!("epic {}", epic),
+            None => "the standalone tasks".to_string(),
+        };
+        println!("Moved {} to {}.", self.moved.join(", "), destination);
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a standalone task is moved into an epic.
+    #[test]
+    fn test_move() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+epics:
+  - id: E-1
+    title: Epic
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(file.path(), "T-1", Some("E-1"), Format::Table);
+
+        let backlog = store::load(file.path());
+        assert!(backlog.tasks.is_empty());
+        assert_eq!(backlog.epics[0].tasks[0].id, "T-1");
+    }
+}
@@ -1,59 +1,426 @@
-use std::fs;
+use crate::aliases;
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use crate::watch;
+use serde::Serialize;
 use std::path::Path;
-use std::process;
-use taskai_schema::Backlog;
+use std::process::Command;
+use taskai_core::ReadyFilter;
+use taskai_schema::{Backlog, DeliverableSpec, Task, TaskState};
+
+/// Options controlling which ready tasks are printed and how.
+pub struct NextOptions {
+    /// Print only the task IDs, one per line.
+    pub ids_only: bool,
+    /// Criteria the printed tasks must match.
+    pub filter: ReadyFilter,
+    /// Maximum number of tasks to print.
+    pub limit: Option<usize>,
+    /// Print only the single best task to work on.
+    pub one: bool,
+    /// Re-render whenever the backlog file changes.
+    pub watch: bool,
+    /// Claim the best ready task for this person instead of listing the ready tasks.
+    pub claim: Option<String>,
+    /// Command to run for each ready task (or the claimed one), with `{field}` placeholders filled
+    /// in from the task; see `run`.
+    pub exec: Option<String>,
+}
+
+/// A ready task along with the epic it belongs to.
+#[derive(Serialize)]
+struct ReadyTask<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epic: Option<&'a str>,
+    #[serde(flatten)]
+    task: &'a Task,
+}
+
+/// The list of ready tasks printed by the command.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct ReadyTasks<'a>(Vec<ReadyTask<'a>>);
+
+/// The task claimed with `--claim`.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct ClaimedTask<'a>(ReadyTask<'a>);
 
 /// Executes the "next" command: reads the backlog file, parses it, and prints the list of tasks that are ready to be worked on.
 /// A task is considered ready if it is in the Todo state and all its dependencies are in the Done state.
-/// For each ready task, prints its ID, title, description (if any), and deliverables (if any).
-pub fn execute(backlog_file: &Path) {
-    let content = match fs::read_to_string(backlog_file) {
-        Ok(c) => c,
-        Err(err) => {
-            eprintln!("Error reading backlog file: {}", err);
-            process::exit(1);
+/// For each ready task, prints its ID, title, description (if any), and deliverables (if any); with `ids_only`, prints one task ID per line.
+/// Tasks can be narrowed down by epic, tag and assignee, and limited in number; `one` keeps only the best task to start with.
+/// With `watch` set, the list is printed again every time the backlog file changes.
+/// With `claim` set, the best ready task is claimed instead; see `claim`.
+/// With `exec` set, a command is run for each ready task instead of printing them; see `run`.
+pub fn execute(backlog_file: &Path, options: NextOptions, format: Format) {
+    if let Some(assignee) = &options.claim {
+        claim(backlog_file, assignee, &options, format);
+    } else if options.watch {
+        watch::watch(backlog_file, format, |backlog| {
+            render(backlog, &options, format)
+        });
+    } else if let Some(template) = &options.exec {
+        let backlog = store::load(backlog_file);
+        let tasks = ready_tasks(&backlog, &options);
+        if tasks.is_empty() {
+            println!("No tasks are ready to work on.");
+            return;
         }
-    };
+        for task in &tasks {
+            run(template, backlog_file, &backlog, task);
+        }
+        println!("Ran the command for {} task(s).", tasks.len());
+    } else {
+        render(&store::load(backlog_file), &options, format);
+    }
+}
+
+/// Returns the ready tasks of the backlog matching the options, in backlog order.
+fn ready_tasks<'a>(backlog: &'a Backlog, options: &NextOptions) -> Vec<&'a Task> {
+    let mut ready_tasks = taskai_core::filter_ready_tasks(backlog, &options.filter);
+    if options.one {
+        ready_tasks = taskai_core::best_ready_task(backlog, &ready_tasks)
+            .into_iter()
+            .collect();
+    }
+    if let Some(limit) = options.limit {
+        ready_tasks.truncate(limit);
+    }
+    ready_tasks
+}
+
+/// Prints the ready tasks of the backlog.
+fn render(backlog: &Backlog, options: &NextOptions, format: Format) {
+    let ready_tasks = ready_tasks(backlog, options);
+
+    if options.ids_only {
+        for task in ready_tasks {
+            println!("{}", task.id);
+        }
+        return;
+    }
 
-    let backlog: Backlog = match serde_yaml::from_str(&content) {
-        Ok(b) => b,
-        Err(err) => {
-            eprintln!("Error parsing backlog file: {}", err);
-            process::exit(1);
+    let ready = ReadyTasks(
+        ready_tasks
+            .into_iter()
+            .map(|task| ReadyTask {
+                epic: epic_of(backlog, &task.id),
+                task,
+            })
+            .collect(),
+    );
+    output::print(format, &ready);
+}
+
+impl Render for ReadyTasks<'_> {
+    fn print_text(&self) {
+        if self.0.is_empty() {
+            println!("No tasks are ready to work on.");
+            return;
         }
+
+        println!("Tasks ready to work on:");
+        for ReadyTask { task, .. } in &self.0 {
+            match task.priority {
+                Some(priority) => println!(
+                    "{}: {} [{}]",
+                    task.id,
+                    task.title,
+                    output::priority_label(priority)
+                ),
+                None => println!("{}: {}", task.id, task.title),
+            }
+
+            if let Some(desc) = &task.description {
+                for line in desc.lines() {
+                    println!("  {}", line);
+                }
+            }
+
+            if let Some(deliverable) = &task.deliverable {
+                match deliverable {
+                    DeliverableSpec::Single(path) => {
+                        println!("  Deliverable: {}", path);
+                    }
+                    DeliverableSpec::Multiple(paths) => {
+                        println!("  Deliverables:");
+                        for path in paths {
+                            println!("    - {}", path);
+                        }
+                    }
+                }
+            }
+
+            println!();
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.0.is_empty() {
+            println!("No tasks are ready to work on.");
+            return;
+        }
+
+        println!("## Tasks ready to work on");
+        println!();
+        for ReadyTask { task, .. } in &self.0 {
+            println!("- [ ] **{}**: {}", task.id, task.title);
+            if let Some(deliverable) = &task.deliverable {
+                for path in deliverable.paths() {
+                    println!("  - `{}`", path);
+                }
+            }
+        }
+    }
+}
+
+/// Claims the best ready task matching the filter for `assignee`: marks it as InProgress, assigns
+/// it and prints it.
+///
+/// The backlog is locked from reading to writing, so that concurrent claims never pick the same
+/// task. Tasks already assigned to someone else are skipped. Exits with a not-found error if there
+/// is no task to claim.
+fn claim(backlog_file: &Path, assignee: &str, options: &NextOptions, format: Format) {
+    let lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let ready: Vec<&Task> = taskai_core::filter_ready_tasks(&backlog, &options.filter)
+        .into_iter()
+        .filter(|t| t.assignee.as_deref().is_none_or(|a| a == assignee))
+        .collect();
+    let Some(task_id) = taskai_core::best_ready_task(&backlog, &ready).map(|t| t.id.clone()) else {
+        error::fail(ErrorKind::NotFound, "No ready task to claim.");
     };
 
-    let ready_tasks = taskai_core::get_ready_tasks(&backlog);
+    let now = chrono::Utc::now();
+    if let Some(task) = backlog.find_task_mut(&task_id) {
+        task.set_state(TaskState::InProgress, now);
+        task.assignee = Some(assignee.to_string());
+        task.updated_at = Some(now);
+    }
+    store::save(backlog_file, &backlog);
+    // The command run on the task may well update the backlog itself
+    drop(lock);
 
-    if ready_tasks.is_empty() {
-        println!("No tasks are ready to work on.");
+    let Some(task) = backlog.find_task(&task_id) else {
         return;
+    };
+    if options.ids_only {
+        println!("{}", task_id);
+    } else {
+        let claimed = ClaimedTask(ReadyTask {
+            epic: epic_of(&backlog, &task_id),
+            task,
+        });
+        output::print(format, &claimed);
+    }
+    if let Some(template) = &options.exec {
+        run(template, backlog_file, &backlog, task);
     }
+}
 
-    println!("Tasks ready to work on:");
-    for task in ready_tasks {
-        println!("{}: {}", task.id, task.title);
+/// Runs the command of `--exec` for a task, exiting the process if it cannot be run or fails.
+///
+/// The command is split into words, with quotes keeping spaces within a word, and run directly
+/// rather than through a shell; `{field}` placeholders are replaced within each word, so task
+/// fields never need quoting. The backlog and the task ID are also passed to the command in the
+/// `TASKAI_BACKLOG` and `TASKAI_TASK_ID` environment variables.
+fn run(template: &str, backlog_file: &Path, backlog: &Backlog, task: &Task) {
+    let words = aliases::split_words(template)
+        .and_then(|words| {
+            words
+                .iter()
+                .map(|word| fill(word, backlog_file, backlog, task))
+                .collect::<Result<Vec<String>, String>>()
+        })
+        .unwrap_or_else(|err| {
+            error::fail(ErrorKind::Usage, format!("Invalid --exec command: {}", err))
+        });
+    let Some((program, args)) = words.split_first() else {
+        error::fail(ErrorKind::Usage, "The --exec command is empty.");
+    };
+
+    tracing::info!(task = task.id.as_str(), command = ?words, "running command");
+    let status = Command::new(program)
+        .args(args)
+        .env("TASKAI_BACKLOG", backlog_file)
+        .env("TASKAI_TASK_ID", &task.id)
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => error::fail(
+            ErrorKind::Other,
+            format!("Command for {} failed ({}).", task.id, status),
+        ),
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error running {}: {}", program, err),
+        ),
+    }
+}
 
+/// Replaces the `{field}` placeholders of a word with the fields of the task: `id`, `title`,
+/// `description`, `epic`, `assignee`, `priority`, `estimate`, `tags` and `deliverables` (both
+/// comma-separated), and `backlog` for the path of the backlog file. Missing fields are replaced
+/// with nothing, and braces not around a placeholder name are kept as they are.
+pub fn fill(
+    word: &str,
+    backlog_file: &Path,
+    backlog: &Backlog,
+    task: &Task,
+) -> Result<String, String> {
+    let mut filled = String::new();
+    let mut rest = word;
+    while let Some(open) = rest.find('{') {
+        filled.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let name = after.find('}').map(|close| &after[..close]);
+        let Some(name) = name
+            .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        else {
+            filled.push('{');
+            rest = after;
+            continue;
+        };
+        let value = match name {
+            "id" => task.id.clone(),
+            "title" => task.title.clone(),
+            "description" => task.description.clone().unwrap_or_default(),
+            "epic" => epic_of(backlog, &task.id).unwrap_or_default().to_string(),
+            "assignee" => task.assignee.clone().unwrap_or_default(),
+            "priority" => task.priority.map(|p| p.to_string()).unwrap_or_default(),
+            "estimate" => task.estimate.map(|e| e.to_string()).unwrap_or_default(),
+            "tags" => task.tags.join(","),
+            "deliverables" => task
+                .deliverable
+                .as_ref()
+                .map(|d| d.paths().join(","))
+                .unwrap_or_default(),
+            "backlog" => backlog_file.display().to_string(),
+            _ => return Err(format!("unknown placeholder {{{}}}", name)),
+        };
+        filled.push_str(&value);
+        rest = &after[name.len() + 1..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+impl Render for ClaimedTask<'_> {
+    fn print_text(&self) {
+        let task = self.0.task;
+        println!(
+            "Claimed {}: {} (assigned to {}).",
+            task.id,
+            task.title,
+            task.assignee.as_deref().unwrap_or_default()
+        );
         if let Some(desc) = &task.description {
             for line in desc.lines() {
                 println!("  {}", line);
             }
         }
-
         if let Some(deliverable) = &task.deliverable {
-            match deliverable {
-                taskai_schema::DeliverableSpec::Single(path) => {
-                    println!("  Deliverable: {}", path);
-                },
-                taskai_schema::DeliverableSpec::Multiple(paths) => {
-                    println!("  Deliverables:");
-                    for path in paths {
-                        println!("    - {}", path);
-                    }
-                }
+            for path in deliverable.paths() {
+                println!("  Deliverable: {}", path);
             }
         }
+    }
+}
 
-        println!();
+/// Returns the ID of the epic containing the given task, if any.
+fn epic_of<'a>(backlog: &'a Backlog, task_id: &str) -> Option<&'a str> {
+    backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task_id))
+        .map(|e| e.id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that claims take the best ready task and skip tasks claimed or assigned to others.
+    #[test]
+    fn test_claim() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            r#"
+project: test
+tasks:
+  - id: T-1
+    title: Low priority
+    priority: 3
+  - id: T-2
+    title: Reserved
+    priority: 1
+    assignee: bob
+  - id: T-3
+    title: High priority
+    priority: 2
+"#,
+        )
+        .unwrap();
+        let options = |claim: &str| NextOptions {
+            ids_only: true,
+            filter: ReadyFilter::default(),
+            limit: None,
+            one: false,
+            watch: false,
+            claim: Some(claim.to_string()),
+            exec: None,
+        };
+
+        execute(&backlog_file, options("alice"), Format::Table);
+        execute(&backlog_file, options("carol"), Format::Table);
+
+        let backlog = store::load(&backlog_file);
+        let claimed = |id: &str| {
+            let task = backlog.find_task(id).unwrap();
+            assert!(matches!(task.state, TaskState::InProgress));
+            assert!(task.started_at.is_some());
+            task.assignee.clone().unwrap()
+        };
+        assert_eq!(claimed("T-3"), "alice");
+        assert_eq!(claimed("T-1"), "carol");
+        assert!(matches!(
+            backlog.find_task("T-2").unwrap().state,
+            TaskState::Todo
+        ));
+    }
+
+    /// Tests that placeholders are filled in from the task, and unknown ones refused.
+    #[test]
+    fn test_fill() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Add "login" endpoint
+        tags: [api, auth]
+"#,
+        )
+        .unwrap();
+        let task = backlog.find_task("E-1-1").unwrap();
+        let fill = |word: &str| fill(word, Path::new("tasks.yml"), &backlog, task);
+
+        assert_eq!(
+            fill("{id}: {title}").unwrap(),
+            "E-1-1: Add \"login\" endpoint"
+        );
+        assert_eq!(fill("--tags={tags}@{epic}").unwrap(), "--tags=api,auth@E-1");
+        assert_eq!(fill("{assignee}|{backlog}").unwrap(), "|tasks.yml");
+        assert_eq!(fill("{} {not closed").unwrap(), "{} {not closed");
+        assert!(fill("{name}").is_err());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,100 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The notes written by `export obsidian`.
+#[derive(Serialize)]
+struct ExportReport {
+    folder: PathBuf,
+    notes: usize,
+}
+
+/// Executes the "export obsidian" command: writes the backlog as notes in the folder `folder`
+/// (defaulting to the project name) of the Obsidian vault `vault`.
+///
+/// Each task gets a note with its fields as frontmatter and its dependencies as wikilinks, and
+/// each epic an index note, as built by `obsidian_notes`. Notes written by an earlier export are
+/// overwritten, so edits belong in the backlog; notes of removed tasks are left in place.
+pub fn export(backlog_file: &Path, vault: &Path, folder: Option<String>, format: Format) {
+    if !vault.is_dir() {
+        error::fail(
+            ErrorKind::Usage,
+            format!("The vault {} is not a directory.", vault.display()),
+        );
+    }
+    let backlog = store::load(backlog_file);
+    let folder = folder.unwrap_or_else(|| match backlog.project.trim() {
+        "" => "Backlog".to_string(),
+        project => project.replace(['/', '\\', ':'], "-"),
+    });
+    let root = vault.join(&folder);
+
+    let notes = taskai_core::obsidian_notes(&backlog);
+    for note in &notes {
+        let path = root.join(&note.path);
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, &note.content));
+        if let Err(err) = written {
+            error::fail(
+                ErrorKind::Other,
+                format!("Error writing {}: {}", path.display(), err),
+            );
+        }
+    }
+    output::print(
+        format,
+        &ExportReport {
+            folder: root,
+            notes: notes.len(),
+        },
+    );
+}
+
+impl Render for ExportReport {
+    fn print_text(&self) {
+        println!("Wrote {} note(s) to {}.", self.notes, self.folder.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_export_writes_notes() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: demo
+tasks:
+  - id: T-1
+    title: First
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: T-2
+        title: Second
+        depends: [T-1]
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+        let vault = tempfile::tempdir().unwrap();
+
+        export(file.path(), vault.path(), None, Format::Json);
+
+        let folder = vault.path().join("demo");
+        assert!(folder.join("demo.md").is_file());
+        assert!(folder.join("Epics/E-1.md").is_file());
+        let note = fs::read_to_string(folder.join("Tasks/T-2.md")).unwrap();
+        assert!(note.contains("depends:\n- '[[T-1]]'\n"));
+    }
+}
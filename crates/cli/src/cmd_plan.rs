@@ -0,0 +1,111 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+
+/// A group of tasks that can be worked on in parallel.
+#[derive(Serialize)]
+struct Wave<'a> {
+    wave: usize,
+    tasks: Vec<PlannedTask<'a>>,
+}
+
+/// A task scheduled in a wave.
+#[derive(Serialize)]
+struct PlannedTask<'a> {
+    id: &'a str,
+    title: &'a str,
+    depends: &'a [String],
+}
+
+/// The execution plan printed by the command.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Plan<'a>(Vec<Wave<'a>>);
+
+/// Executes the "plan" command: prints the remaining tasks grouped into waves that can run in parallel.
+///
+/// Wave 1 contains the tasks that can start right away; each following wave can start once the previous
+/// one is done.
+pub fn execute(backlog_file: &Path, format: Format) {
+    let backlog = store::load(backlog_file);
+
+    let levels = match taskai_core::execution_levels(&backlog) {
+        Ok(levels) => levels,
+        Err(err) => {
+            error::fail(
+                ErrorKind::Validation,
+                format!("Error computing plan: {}", err),
+            );
+        }
+    };
+
+    let plan = Plan(
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| Wave {
+                wave: i + 1,
+                tasks: level
+                    .iter()
+                    .map(|t| PlannedTask {
+                        id: &t.id,
+                        title: &t.title,
+                        depends: &t.depends,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    );
+    output::print(format, &plan);
+}
+
+impl Render for Plan<'_> {
+    fn print_text(&self) {
+        if self.0.is_empty() {
+            println!("All tasks are done.");
+            return;
+        }
+
+        for wave in &self.0 {
+            println!("Wave {} ({} task(s)):", wave.wave, wave.tasks.len());
+            for task in &wave.tasks {
+                if task.depends.is_empty() {
+                    println!("  {}: {}", task.id, task.title);
+                } else {
+                    println!(
+                        "  {}: {} (after {})",
+                        task.id,
+                        task.title,
+                        task.depends.join(", ")
+                    );
+                }
+            }
+            println!();
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.0.is_empty() {
+            println!("All tasks are done.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .flat_map(|wave| {
+                wave.tasks.iter().map(move |task| {
+                    vec![
+                        wave.wave.to_string(),
+                        task.id.to_string(),
+                        task.title.to_string(),
+                        task.depends.join(", "),
+                    ]
+                })
+            })
+            .collect();
+        output::print_table(true, 0, &["Wave", "Task", "Title", "After"], &rows);
+    }
+}
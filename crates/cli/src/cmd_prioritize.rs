@@ -0,0 +1,205 @@
+use crate::error::{self, ErrorKind};
+use crate::store;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use taskai_schema::{Backlog, TaskState};
+
+/// Executes the "prioritize" command: walks through the Todo tasks and writes their priorities back.
+///
+/// By default, a priority is asked for each task in turn (1 being the most important); an empty
+/// answer keeps the current one and `q` stops early, saving what was entered so far. With `pairwise`
+/// set, tasks are compared two at a time instead and ranked from 1 to N; the ranking is only saved
+/// once all comparisons are done.
+pub fn execute(backlog_file: &Path, pairwise: bool) {
+    let source = store::read(backlog_file);
+    let mut backlog = store::parse(&source);
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let changed = if pairwise {
+        match rank_pairwise(&mut backlog, &mut input) {
+            Some(changed) => changed,
+            None => {
+                error::fail(
+                    ErrorKind::Other,
+                    "Ranking interrupted; no priorities were changed.",
+                );
+            }
+        }
+    } else {
+        ask_priorities(&mut backlog, &mut input)
+    };
+
+    if changed > 0 {
+        if let Err(err) = store::try_save_unchanged(backlog_file, &source, &backlog) {
+            error::fail(ErrorKind::Other, err);
+        }
+    }
+    println!("Updated the priority of {} task(s).", changed);
+}
+
+/// IDs of the tasks to prioritize, in backlog order.
+fn todo_task_ids(backlog: &Backlog) -> Vec<String> {
+    backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| matches!(t.state, TaskState::Todo))
+        .map(|t| t.id.clone())
+        .collect()
+}
+
+/// Asks a priority for each Todo task, returning the number of tasks whose priority changed.
+fn ask_priorities(backlog: &mut Backlog, input: &mut impl BufRead) -> usize {
+    let mut changed = 0;
+
+    for id in todo_task_ids(backlog) {
+        let Some(task) = backlog.find_task_mut(&id) else {
+            continue;
+        };
+        let current = task
+            .priority
+            .map(|p| format!(" [priority {}]", p))
+            .unwrap_or_default();
+        println!("{}: {}{}", task.id, task.title, current);
+
+        let priority = loop {
+            print!("  Priority (1 = highest, Enter to keep, q to stop): ");
+            let _ = io::stdout().flush();
+            let Some(answer) = read_answer(input) else {
+                return changed;
+            };
+            match answer.as_str() {
+                "" => break task.priority,
+                "q" => return changed,
+                answer => match answer.parse::<u32>() {
+                    Ok(priority) if priority > 0 => break Some(priority),
+                    _ => println!("  Please enter a positive number."),
+                },
+            }
+        };
+
+        if priority != task.priority {
+            task.priority = priority;
+            task.updated_at = Some(chrono::Utc::now());
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// Ranks the Todo tasks through pairwise comparisons (binary insertion), then sets their priority to
+/// their rank. Returns the number of tasks whose priority changed, or `None` if input ran out.
+fn rank_pairwise(backlog: &mut Backlog, input: &mut impl BufRead) -> Option<usize> {
+    let ids = todo_task_ids(backlog);
+    let mut ranked: Vec<String> = Vec::new();
+
+    for id in ids {
+        let (mut low, mut high) = (0, ranked.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            match compare(backlog, &id, &ranked[middle], input)? {
+                Ordering::Less => high = middle,
+                _ => low = middle + 1,
+            }
+        }
+        ranked.insert(low, id);
+    }
+
+    let now = chrono::Utc::now();
+    let mut changed = 0;
+    for (rank, id) in ranked.iter().enumerate() {
+        if let Some(task) = backlog.find_task_mut(id) {
+            let priority = Some(rank as u32 + 1);
+            if task.priority != priority {
+                task.priority = priority;
+                task.updated_at = Some(now);
+                changed += 1;
+            }
+        }
+    }
+    Some(changed)
+}
+
+/// Asks which of two tasks is more important; `Less` means the first one is.
+fn compare(backlog: &Backlog, a: &str, b: &str, input: &mut impl BufRead) -> Option<Ordering> {
+    let title = |id: &str| {
+        backlog
+            .find_task(id)
+            .map(|t| t.title.clone())
+            .unwrap_or_default()
+    };
+    println!("Which task is more important?");
+    println!("  1) {}: {}", a, title(a));
+    println!("  2) {}: {}", b, title(b));
+
+    loop {
+        print!("Choice (1 or 2, q to stop): ");
+        let _ = io::stdout().flush();
+        match read_answer(input)?.as_str() {
+            "1" => return Some(Ordering::Less),
+            "2" => return Some(Ordering::Greater),
+            "q" => return None,
+            _ => println!("Please answer 1 or 2."),
+        }
+    }
+}
+
+/// Reads a trimmed line, or `None` at the end of input.
+fn read_answer(input: &mut impl BufRead) -> Option<String> {
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn backlog() -> Backlog {
+        serde_yaml::from_str(
+            r#"
+project: test-project
+tasks:
+  - id: T-1
+    title: First
+  - id: T-2
+    title: Second
+    priority: 4
+  - id: T-3
+    title: Third
+"#,
+        )
+        .unwrap()
+    }
+
+    /// Tests that priorities are asked per task and that `q` stops early.
+    #[test]
+    fn test_ask_priorities() {
+        let mut backlog = backlog();
+        let changed = ask_priorities(&mut backlog, &mut Cursor::new("x\n2\n\nq\n"));
+
+        assert_eq!(changed, 1);
+        assert_eq!(backlog.tasks[0].priority, Some(2));
+        assert_eq!(backlog.tasks[1].priority, Some(4));
+        assert_eq!(backlog.tasks[2].priority, None);
+    }
+
+    /// Tests that pairwise comparisons produce a full ranking.
+    #[test]
+    fn test_rank_pairwise() {
+        let mut backlog = backlog();
+        // T-1 beats T-2, then T-3 beats T-2 but loses to T-1.
+        let changed = rank_pairwise(&mut backlog, &mut Cursor::new("2\n1\n2\n")).unwrap();
+
+        assert_eq!(changed, 3);
+        let priorities: Vec<Option<u32>> = backlog.tasks.iter().map(|t| t.priority).collect();
+        assert_eq!(priorities, vec![Some(1), Some(3), Some(2)]);
+
+        assert!(rank_pairwise(&mut backlog, &mut Cursor::new("")).is_none());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::RefIssue;
+
+/// The reference problems printed by the command.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct RefIssues(Vec<RefIssue>);
+
+/// Executes the "refs" command: validates the external references and links carried by tasks.
+///
+/// Formats are always checked. With `online` set, GitHub issues, Jira tickets and links are also
+/// looked up over the network and dead references are reported. Exits with a non-zero status if any problem is found.
+pub async fn execute(backlog_file: &Path, online: bool, format: Format) {
+    let backlog = store::load(backlog_file);
+
+    let mut issues = taskai_core::check_ref_formats(&backlog);
+    if online {
+        issues.extend(taskai_core::check_refs_online(&backlog).await);
+    }
+
+    let issues = RefIssues(issues);
+    output::print(format, &issues);
+
+    if !issues.0.is_empty() {
+        error::fail(
+            ErrorKind::Validation,
+            format!("{} invalid reference(s) found.", issues.0.len()),
+        );
+    }
+}
+
+impl Render for RefIssues {
+    fn print_text(&self) {
+        if self.0.is_empty() {
+            println!("All references are valid.");
+            return;
+        }
+
+        for issue in &self.0 {
+            println!("{}: {}: {}", issue.task_id, issue.reference, issue.message);
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.0.is_empty() {
+            println!("All references are valid.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|i| vec![i.task_id.clone(), i.reference.clone(), i.message.clone()])
+            .collect();
+        output::print_table(true, 0, &["Task", "Reference", "Problem"], &rows);
+    }
+}
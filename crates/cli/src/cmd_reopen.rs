@@ -0,0 +1,95 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, TaskUpdate};
+use crate::store;
+use std::path::Path;
+use taskai_schema::TaskState;
+
+/// Executes the "reopen" command: flips a Done task back to Todo.
+///
+/// Clears the completion timestamp of the task and warns about downstream tasks that were already
+/// started or finished on the assumption that this task was complete.
+pub fn execute(backlog_file: &Path, task_id: &str, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+
+    match backlog.find_task_mut(task_id) {
+        Some(task) => {
+            if !matches!(task.state, TaskState::Done) {
+                error::fail(
+                    ErrorKind::Validation,
+                    format!(
+                        "Task {} is not Done (current state: {:?}).",
+                        task_id, task.state
+                    ),
+                );
+            }
+            task.set_state(TaskState::Todo, now);
+        }
+        None => {
+            error::fail(
+                ErrorKind::NotFound,
+                format!("Task with ID '{}' not found in the backlog.", task_id),
+            );
+        }
+    }
+
+    let affected: Vec<String> = taskai_core::downstream_tasks(&backlog, task_id)
+        .into_iter()
+        .filter(|t| !matches!(t.state, TaskState::Todo))
+        .map(|t| format!("{} ({:?})", t.id, t.state))
+        .collect();
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &TaskUpdate {
+            id: task_id,
+            state: &TaskState::Todo,
+            message: format!("Task {} reopened.", task_id),
+        },
+    );
+
+    if !affected.is_empty() {
+        eprintln!(
+            "Warning: downstream task(s) already started assuming {} was done: {}",
+            task_id,
+            affected.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use taskai_schema::Backlog;
+    use tempfile::NamedTempFile;
+
+    /// Tests that a Done task is reopened and its completion timestamp cleared.
+    #[test]
+    fn test_reopen() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: TEST-1
+    title: Test Task
+    state: Done
+    completed_at: 2024-01-01T00:00:00Z
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        execute(file.path(), "TEST-1", Format::Table);
+
+        let content = fs::read_to_string(file.path()).unwrap();
+        let backlog: Backlog = serde_yaml::from_str(&content).unwrap();
+
+        assert!(matches!(backlog.tasks[0].state, TaskState::Todo));
+        assert!(backlog.tasks[0].completed_at.is_none());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::NaiveDate;
+use std::path::Path;
+use taskai_core::{Burndown, BurndownDay, WorkUnit};
+
+/// Height of the burndown chart, in lines.
+const CHART_HEIGHT: usize = 10;
+
+/// Maximum width of the burndown chart, in columns; longer periods are sampled.
+const CHART_WIDTH: usize = 60;
+
+/// Width of the longest velocity bar, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// Executes the "report" command: prints the burndown of the backlog since `since` (or its first
+/// recorded activity) and its weekly velocity, averaged over the last `window_weeks` weeks.
+///
+/// The text output draws both as ASCII charts; with `csv` set, the burndown is printed as CSV
+/// instead, one row per day.
+pub fn execute(
+    backlog_file: &Path,
+    since: Option<NaiveDate>,
+    window_weeks: u32,
+    csv: bool,
+    format: Format,
+) {
+    let backlog = store::load(backlog_file);
+    let today = chrono::Local::now().date_naive();
+    let report = taskai_core::burndown(&backlog, since, today, window_weeks);
+    if csv {
+        print_csv(&report);
+    } else {
+        output::print(format, &report);
+    }
+}
+
+impl Render for Burndown {
+    fn print_text(&self) {
+        let (Some(first), Some(last)) = (self.days.first(), self.days.last()) else {
+            println!("No activity to report.");
+            return;
+        };
+        let unit = match self.unit {
+            WorkUnit::Estimate => "estimate",
+            WorkUnit::Tasks => "tasks",
+        };
+
+        println!("Burndown ({}), {} to {}:", unit, first.date, last.date);
+        for line in chart_lines(&self.days) {
+            println!("{}", line);
+        }
+        println!(
+            "Scope {}, completed {}, remaining {}.",
+            last.scope, last.completed, last.remaining
+        );
+
+        println!();
+        println!("Velocity per week:");
+        let max = self.weeks.iter().map(|w| w.completed).fold(0.0, f64::max);
+        for week in &self.weeks {
+            let width = if max > 0.0 {
+                (week.completed / max * BAR_WIDTH as f64).round() as usize
+            } else {
+                0
+            };
+            println!(
+                "  {}  {:<BAR_WIDTH$}  {}",
+                week.week,
+                "#".repeat(width),
+                week.completed
+            );
+        }
+        println!(
+            "Rolling velocity: {:.1} per week over the last {} week(s).",
+            last.velocity, self.window_weeks
+        );
+        match self.projected_completion {
+            Some(date) => println!("Projected completion: {}.", date),
+            None if last.remaining == 0.0 => println!("All work is completed."),
+            None => println!("Projected completion: unknown, nothing was completed recently."),
+        }
+    }
+}
+
+/// Prints the burndown as CSV, one row per day.
+fn print_csv(report: &Burndown) {
+    let rows: Vec<Vec<String>> = report
+        .days
+        .iter()
+        .map(|day| {
+            vec![
+                day.date.to_string(),
+                day.scope.to_string(),
+                day.completed.to_string(),
+                day.remaining.to_string(),
+                format!("{:.2}", day.velocity),
+            ]
+        })
+        .collect();
+    output::print_csv(
+        &["date", "scope", "completed", "remaining", "velocity"],
+        &rows,
+    );
+}
+
+/// Draws the remaining work as bars (`#`), under the completed part of the scope (`.`).
+fn chart_lines(days: &[BurndownDay]) -> Vec<String> {
+    let width = days.len().min(CHART_WIDTH);
+    let columns: Vec<&BurndownDay> = (0..width)
+        .map(|i| match width {
+            1 => &days[0],
+            _ => &days[i * (days.len() - 1) / (width - 1)],
+        })
+        .collect();
+    let max = columns.iter().map(|d| d.scope).fold(0.0, f64::max);
+    let height = |value: f64| {
+        if max > 0.0 {
+            (value / max * CHART_HEIGHT as f64).round() as usize
+        } else {
+            0
+        }
+    };
+
+    let label_width = max.to_string().len();
+    let mut lines: Vec<String> = (1..=CHART_HEIGHT)
+        .rev()
+        .map(|row| {
+            let label = match row {
+                CHART_HEIGHT => max.to_string(),
+                _ => String::new(),
+            };
+            let cells: String = columns
+                .iter()
+                .map(|day| match row {
+                    r if r <= height(day.remaining) => '#',
+                    r if r <= height(day.scope) => '.',
+                    _ => ' ',
+                })
+                .collect();
+            format!("{:>label_width$} |{}", label, cells.trim_end())
+        })
+        .collect();
+    lines.push(format!("{:>label_width$} +{}", 0, "-".repeat(width)));
+
+    // Dates of the first and last day under the axis, when there is room for both
+    let (first, last) = (
+        days[0].date.to_string(),
+        days[days.len() - 1].date.to_string(),
+    );
+    let gap = (width + 1).saturating_sub(first.len() + last.len());
+    lines.push(match gap {
+        0 => format!("{:>label_width$}  {}", "", first),
+        _ => format!("{:>label_width$}  {}{}{}", "", first, " ".repeat(gap), last),
+    });
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_lines() {
+        let day = |n: u32, scope: f64, remaining: f64| BurndownDay {
+            date: NaiveDate::from_ymd_opt(2024, 1, n).unwrap(),
+            scope,
+            completed: scope - remaining,
+            remaining,
+            velocity: 0.0,
+        };
+        let days = vec![day(1, 10.0, 10.0), day(2, 10.0, 5.0), day(3, 10.0, 0.0)];
+
+        let lines = chart_lines(&days);
+        assert_eq!(lines.len(), CHART_HEIGHT + 2);
+        assert_eq!(lines[0], "10 |#..");
+        assert_eq!(lines[5], "   |##.");
+        assert_eq!(lines[CHART_HEIGHT], " 0 +---");
+        assert_eq!(lines[CHART_HEIGHT + 1], "    2024-01-01");
+    }
+}
@@ -0,0 +1,148 @@
+use crate::cmd_git::{git, git_error};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::ScanMatch;
+use taskai_schema::TaskState;
+
+/// Tasks whose deliverables were changed in a revision range.
+#[derive(Serialize)]
+struct ScanReport<'a> {
+    rev: &'a str,
+    /// Number of changed files.
+    changed: usize,
+    /// Tasks with every deliverable changed, which appear completed.
+    completed: Vec<ScanMatch>,
+    /// Tasks with only some deliverables changed.
+    touched: Vec<ScanMatch>,
+    /// Whether the completed tasks were marked as done.
+    applied: bool,
+}
+
+/// Executes the "scan" command: matches the files changed in `rev` against the deliverables of
+/// the Todo and InProgress tasks, and suggests the tasks whose deliverables were all changed as
+/// completed.
+///
+/// `rev` is passed to `git diff`: a range such as `HEAD~5..HEAD`, or a single revision to compare
+/// the working tree with. Deleted files are ignored. Changed paths are made relative to the
+/// directory of the backlog, which deliverables are resolved against. With `apply` set, the
+/// suggested tasks are marked as done, with a note naming `rev`.
+pub fn execute(backlog_file: &Path, rev: &str, apply: bool, format: Format) {
+    let dir = store::project_root(backlog_file);
+    let prefix = git(&dir, &["rev-parse", "--show-prefix"]).unwrap_or_else(git_error);
+    let diff = git(
+        &dir,
+        &["diff", "--name-only", "-z", "--diff-filter=ACMR", rev, "--"],
+    )
+    .unwrap_or_else(git_error);
+    // Git prints paths relative to the repository root
+    let changed: Vec<String> = diff
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| path.strip_prefix(prefix.as_str()))
+        .map(str::to_string)
+        .collect();
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let (completed, touched): (Vec<ScanMatch>, Vec<ScanMatch>) =
+        taskai_core::scan_changes(&backlog, &changed)
+            .into_iter()
+            .partition(|m| m.is_complete());
+
+    let applied = apply && !completed.is_empty();
+    if applied {
+        let now = chrono::Utc::now();
+        for found in &completed {
+            if let Some(task) = backlog.find_task_mut(&found.task_id) {
+                task.set_state(TaskState::Done, now);
+                task.add_note(&format!("Deliverables changed in {}", rev), now);
+            }
+        }
+        store::save(backlog_file, &backlog);
+    }
+    output::print(
+        format,
+        &ScanReport {
+            rev,
+            changed: changed.len(),
+            completed,
+            touched,
+            applied,
+        },
+    );
+}
+
+impl Render for ScanReport<'_> {
+    fn print_text(&self) {
+        println!("{} changed file(s) in {}.", self.changed, self.rev);
+        if !self.completed.is_empty() {
+            println!(
+                "{}",
+                if self.applied {
+                    "Marked as done:"
+                } else {
+                    "Appear completed (mark them with --apply):"
+                }
+            );
+        }
+        for found in &self.completed {
+            println!("  {} {} ({:?})", found.task_id, found.title, found.state);
+        }
+        if !self.touched.is_empty() {
+            println!("Partially changed:");
+        }
+        for found in &self.touched {
+            let missing: Vec<&str> = found
+                .deliverables
+                .iter()
+                .filter(|d| d.paths.is_empty())
+                .map(|d| d.pattern.as_str())
+                .collect();
+            println!(
+                "  {} {} (unchanged: {})",
+                found.task_id,
+                found.title,
+                missing.join(", ")
+            );
+        }
+        if self.completed.is_empty() && self.touched.is_empty() {
+            println!("No open task has deliverables among them.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that a task whose deliverables were committed is marked as done with `--apply`.
+    #[test]
+    fn test_scan_apply() {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        let backlog_file = dir.join("taskai.yaml");
+        fs::write(
+            &backlog_file,
+            "project: demo\ntasks:\n  - id: T-1\n    title: Parser\n    deliverable: \"src/*.rs\"\n",
+        )
+        .unwrap();
+        let run = |args: &[&str]| git(dir, args).unwrap();
+        run(&["init", "--quiet"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        run(&["add", "taskai.yaml"]);
+        run(&["commit", "--quiet", "-m", "Add the backlog"]);
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src/parser.rs"), "fn parse() {}\n").unwrap();
+        run(&["add", "src"]);
+        run(&["commit", "--quiet", "-m", "Add the parser"]);
+
+        execute(&backlog_file, "HEAD~1..HEAD", true, Format::Json);
+
+        let backlog = store::load(&backlog_file);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::SearchOptions;
+use taskai_schema::TaskState;
+
+/// A task matching the search.
+#[derive(Serialize)]
+struct SearchHit<'a> {
+    id: &'a str,
+    title: &'a str,
+    state: &'a TaskState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epic: Option<&'a str>,
+    fields: Vec<&'static str>,
+}
+
+/// Tasks matching the search, in backlog order.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct SearchResults<'a>(Vec<SearchHit<'a>>);
+
+/// Executes the "search" command: finds the tasks whose ID, title, description or `done_when`
+/// criteria match the query, as plain text or as a regex.
+///
+/// Like grep, exits with a non-zero status (5, not found) when nothing matches.
+pub fn execute(backlog_file: &Path, query: &str, options: SearchOptions, format: Format) {
+    let backlog = store::load(backlog_file);
+
+    let matches = match taskai_core::search_tasks(&backlog, query, options) {
+        Ok(matches) => matches,
+        Err(err) => {
+            error::fail(ErrorKind::Usage, err);
+        }
+    };
+    let results = SearchResults(
+        matches
+            .into_iter()
+            .map(|m| SearchHit {
+                id: &m.task.id,
+                title: &m.task.title,
+                state: &m.task.state,
+                epic: m.epic,
+                fields: m.fields,
+            })
+            .collect(),
+    );
+
+    output::print(format, &results);
+
+    if results.0.is_empty() {
+        error::exit(ErrorKind::NotFound);
+    }
+}
+
+impl Render for SearchResults<'_> {
+    fn print_text(&self) {
+        self.print_hits(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_hits(true);
+    }
+}
+
+impl SearchResults<'_> {
+    fn print_hits(&self, markdown: bool) {
+        if self.0.is_empty() {
+            println!("No matching tasks.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|hit| {
+                let state = format!("{:?}", hit.state);
+                vec![
+                    hit.id.to_string(),
+                    if markdown {
+                        state
+                    } else {
+                        output::paint_state(hit.state, &state)
+                    },
+                    hit.epic.unwrap_or("-").to_string(),
+                    hit.title.to_string(),
+                    hit.fields.join(", "),
+                ]
+            })
+            .collect();
+        output::print_table(
+            markdown,
+            0,
+            &["ID", "STATE", "EPIC", "TITLE", "MATCHED"],
+            &rows,
+        );
+    }
+}
@@ -0,0 +1,464 @@
+use crate::cmd_daemon::Daemon;
+use crate::error::{self, ErrorKind};
+use crate::graphql::{self, BacklogSchema};
+use crate::{dashboard, html, store};
+use axum::extract::{Path as UrlPath, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use taskai_core::BacklogGenerator;
+use taskai_proto::{BacklogGrpc, BacklogServiceServer, BacklogSource};
+use taskai_schema::{Backlog, TaskState};
+
+/// State shared by the handlers of the server.
+struct Server {
+    backlog_file: PathBuf,
+    daemon: Arc<Daemon>,
+    /// Token that requests must carry as `Authorization: Bearer <token>`, if any.
+    token: Option<String>,
+    /// Schema answering `/graphql`, when the GraphQL endpoint is enabled.
+    graphql: Option<BacklogSchema>,
+}
+
+/// Body of `PATCH /tasks/{id}/state`.
+#[derive(Deserialize)]
+struct StateChange {
+    state: TaskState,
+    /// Note added to the task along with the change.
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// Body of `POST /generate`.
+#[derive(Deserialize)]
+struct GenerateRequest {
+    spec: String,
+    #[serde(default)]
+    lang: Option<String>,
+    #[serde(default)]
+    style: Option<String>,
+    /// Merge the generated backlog into the backlog file instead of only returning it.
+    #[serde(default)]
+    merge: bool,
+}
+
+/// An error answered as `{"error": message}` with its status.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Executes the "serve" command: serves a REST API over the backlog on `host:port` until Ctrl-C.
+///
+/// The backlog is kept in memory and reloaded when the file changes, as by the daemon; changes are
+/// written to the file. With `token` set, every request must carry it as a bearer token. With
+/// `graphql` set, the backlog is also served as GraphQL on `/graphql`, and with `grpc_port` set,
+/// as the gRPC `BacklogService` of `taskai-proto` on that port.
+pub async fn execute(
+    backlog_file: &Path,
+    host: &str,
+    port: u16,
+    token: Option<String>,
+    graphql: bool,
+    grpc_port: Option<u16>,
+) {
+    let daemon = Arc::new(Daemon::new(backlog_file));
+    let server = Arc::new(Server {
+        backlog_file: backlog_file.to_path_buf(),
+        graphql: graphql.then(|| graphql::schema(Arc::clone(&daemon))),
+        daemon,
+        token: token.filter(|t| !t.is_empty()),
+    });
+    server.daemon.watch();
+
+    let address = format!("{}:{}", host, port);
+    let listener = match tokio::net::TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listening on {}: {}", address, err),
+        ),
+    };
+    eprintln!(
+        "Serving {} on http://{} (Ctrl-C to stop)...",
+        backlog_file.display(),
+        address
+    );
+    let grpc = async {
+        if let Some(grpc_port) = grpc_port {
+            let daemon = Arc::clone(&server.daemon);
+            serve_grpc(
+                daemon,
+                &format!("{}:{}", host, grpc_port),
+                server.token.clone(),
+            )
+            .await;
+        }
+    };
+    let rest = async {
+        let shutdown = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        if let Err(err) = axum::serve(listener, router(Arc::clone(&server)))
+            .with_graceful_shutdown(shutdown)
+            .await
+        {
+            error::fail(ErrorKind::Other, format!("Error serving the API: {}", err));
+        }
+    };
+    tokio::join!(rest, grpc);
+}
+
+/// Serves the backlog of `daemon` over gRPC on `address` until Ctrl-C. With `token` set, every
+/// call must carry it as `authorization: Bearer <token>` metadata.
+async fn serve_grpc(daemon: Arc<Daemon>, address: &str, token: Option<String>) {
+    let resolved = tokio::net::lookup_host(address)
+        .await
+        .ok()
+        .and_then(|mut a| a.next());
+    let Some(socket) = resolved else {
+        error::fail(ErrorKind::Usage, format!("Invalid address: {}", address));
+    };
+    let authorize = move |request: tonic::Request<()>| {
+        let Some(token) = &token else {
+            return Ok(request);
+        };
+        let bearer = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if bearer == Some(token.as_str()) {
+            Ok(request)
+        } else {
+            Err(tonic::Status::unauthenticated(
+                "Missing or invalid bearer token.",
+            ))
+        }
+    };
+    let service = BacklogServiceServer::with_interceptor(BacklogGrpc::new(daemon), authorize);
+    eprintln!("Serving gRPC on {}...", socket);
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    if let Err(err) = tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(socket, shutdown)
+        .await
+    {
+        error::fail(ErrorKind::Other, format!("Error serving gRPC: {}", err));
+    }
+}
+
+/// The daemon serves its backlog in memory over gRPC, and writes changes to the file.
+impl BacklogSource for Daemon {
+    fn backlog(&self) -> Result<Backlog, String> {
+        self.with_backlog(Backlog::clone)
+    }
+
+    fn set_state(&self, id: &str, state: TaskState, note: Option<&str>) -> Result<(), String> {
+        self.handle("mutate", json!({ "id": id, "state": state, "note": note }))
+            .map(|_| ())
+            .map_err(|(_, message)| message)
+    }
+}
+
+/// Builds the routes of the API, and those of the dashboard, which are not behind the token.
+fn router(server: Arc<Server>) -> Router {
+    Router::new()
+        .route("/tasks", get(list_tasks))
+        .route("/tasks/{id}", get(get_task))
+        .route("/tasks/{id}/state", patch(set_state))
+        .route("/next", get(next_tasks))
+        .route("/graph.svg", get(graph))
+        .route("/graphql", get(graphql_schema).post(graphql_query))
+        .route("/generate", post(generate))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&server),
+            authorize,
+        ))
+        .merge(dashboard::routes())
+        .with_state(server)
+}
+
+/// Rejects the requests without the token of the server, when it has one.
+async fn authorize(
+    State(server): State<Arc<Server>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(token) = &server.token {
+        let bearer = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if bearer != Some(token.as_str()) {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid bearer token.".to_string(),
+            ));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// Answers a daemon request, mapping its JSON-RPC errors to statuses.
+fn answer(daemon: &Daemon, method: &str, params: Value) -> Result<Json<Value>, ApiError> {
+    daemon
+        .handle(method, params)
+        .map(Json)
+        .map_err(|(code, message)| {
+            let status = match code {
+                -32602 | -32600 => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            ApiError(status, message)
+        })
+}
+
+/// Turns query parameters into daemon parameters, with numbers as numbers.
+fn query_params(query: HashMap<String, String>) -> Value {
+    query
+        .into_iter()
+        .map(|(key, value)| {
+            let value = value
+                .parse::<u64>()
+                .map_or_else(|_| json!(value), |n| json!(n));
+            (key, value)
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError(
+        StatusCode::NOT_FOUND,
+        format!("Task with ID '{}' not found in the backlog.", id),
+    )
+}
+
+/// `GET /tasks`, filtered by `state`, `epic`, `tag` and `assignee`.
+async fn list_tasks(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, ApiError> {
+    answer(&server.daemon, "list", query_params(query))
+}
+
+/// `GET /tasks/{id}`.
+async fn get_task(
+    State(server): State<Arc<Server>>,
+    UrlPath(id): UrlPath<String>,
+) -> Result<Json<Value>, ApiError> {
+    if !server.daemon.has_task(&id) {
+        return Err(not_found(&id));
+    }
+    answer(&server.daemon, "get", json!({ "id": id }))
+}
+
+/// `GET /next`: the ready tasks, filtered by `epic`, `tag` and `assignee`, at most `limit`.
+async fn next_tasks(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, ApiError> {
+    answer(&server.daemon, "next", query_params(query))
+}
+
+/// `PATCH /tasks/{id}/state`, with the new state and an optional note.
+async fn set_state(
+    State(server): State<Arc<Server>>,
+    UrlPath(id): UrlPath<String>,
+    Json(change): Json<StateChange>,
+) -> Result<Json<Value>, ApiError> {
+    if !server.daemon.has_task(&id) {
+        return Err(not_found(&id));
+    }
+    let params = json!({ "id": id, "state": change.state, "note": change.note });
+    answer(&server.daemon, "mutate", params)
+}
+
+/// `GET /graph.svg`: the dependency graph of the backlog, as in the HTML report.
+async fn graph(State(server): State<Arc<Server>>) -> Result<Response, ApiError> {
+    let svg = server
+        .daemon
+        .with_backlog(html::dependency_graph)
+        .map_err(|err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response())
+}
+
+/// Returns the GraphQL schema of the server, or a 404 when the endpoint is not enabled.
+fn graphql_enabled(server: &Server) -> Result<&BacklogSchema, ApiError> {
+    server.graphql.as_ref().ok_or_else(|| {
+        ApiError(
+            StatusCode::NOT_FOUND,
+            "GraphQL is not enabled; start the server with --graphql.".to_string(),
+        )
+    })
+}
+
+/// `POST /graphql`: answers a GraphQL request over the backlog.
+async fn graphql_query(
+    State(server): State<Arc<Server>>,
+    Json(request): Json<async_graphql::Request>,
+) -> Result<Json<async_graphql::Response>, ApiError> {
+    let schema = graphql_enabled(&server)?;
+    Ok(Json(schema.execute(request).await))
+}
+
+/// `GET /graphql`: the GraphQL schema, in SDL.
+async fn graphql_schema(State(server): State<Arc<Server>>) -> Result<String, ApiError> {
+    Ok(graphql_enabled(&server)?.sdl())
+}
+
+/// `POST /generate`: generates a backlog from a specification, merged into the backlog file with
+/// `merge` set.
+async fn generate(
+    State(server): State<Arc<Server>>,
+    Json(request): Json<GenerateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let config = taskai_core::Config::discover(&store::project_root(&server.backlog_file))
+        .ok()
+        .flatten()
+        .map(|(_, config)| config)
+        .unwrap_or_default();
+    let lang = request.lang.or(config.generate.lang);
+    let style = request.style.or(config.generate.style);
+    let mut generator = BacklogGenerator::new()
+        .with_language(lang.as_deref().unwrap_or("en"))
+        .with_style(style.as_deref().unwrap_or("standard"));
+    if let Some(model) = &config.generate.model {
+        generator = generator.with_model(model);
+    }
+    let backlog = generator
+        .generate(&request.spec)
+        .await
+        .map_err(|err| ApiError(StatusCode::BAD_GATEWAY, err))?;
+    if !request.merge {
+        return Ok(Json(json!({ "backlog": backlog })));
+    }
+
+    let summary = {
+        let _lock = store::lock(&server.backlog_file);
+        let error = |err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, err);
+        let existing = store::try_load(&server.backlog_file).map_err(error)?;
+        let (merged, summary) = taskai_core::merge_generated(&existing, backlog);
+        store::try_save(&server.backlog_file, &merged).map_err(error)?;
+        summary
+    };
+    server.daemon.reload();
+    Ok(Json(
+        json!({ "version": server.daemon.version(), "merge": summary }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::fs;
+    use tower::ServiceExt;
+
+    async fn send(router: &Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    /// Tests the routes of the API, token checks and that state changes reach the file.
+    #[tokio::test]
+    async fn test_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            "project: test\ntasks:\n  - id: T-1\n    title: Parser\n  - id: T-2\n    \
+             title: Interpreter\n    depends: [T-1]\n",
+        )
+        .unwrap();
+        let router = router(Arc::new(Server {
+            backlog_file: backlog_file.clone(),
+            daemon: Arc::new(Daemon::new(&backlog_file)),
+            token: Some("secret".to_string()),
+            graphql: None,
+        }));
+        let request = |method: &str, uri: &str, body: Option<Value>| {
+            let builder = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, "Bearer secret")
+                .header(header::CONTENT_TYPE, "application/json");
+            builder
+                .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+                .unwrap()
+        };
+
+        let unauthorized = Request::get("/tasks").body(Body::empty()).unwrap();
+        assert_eq!(
+            send(&router, unauthorized).await.0,
+            StatusCode::UNAUTHORIZED
+        );
+
+        let (status, body) = send(&router, request("GET", "/tasks", None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tasks"].as_array().unwrap().len(), 2);
+        let (_, body) = send(&router, request("GET", "/next?limit=5", None)).await;
+        assert_eq!(body["tasks"][0]["id"], "T-1");
+
+        let done = json!({ "state": "Done", "note": "Merged" });
+        let (status, body) = send(
+            &router,
+            request("PATCH", "/tasks/T-1/state", Some(done.clone())),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["task"]["state"], "Done");
+        assert!(store::read(&backlog_file).contains("Merged"));
+        let (_, body) = send(&router, request("GET", "/next", None)).await;
+        assert_eq!(body["tasks"][0]["id"], "T-2");
+
+        let (status, _) = send(&router, request("PATCH", "/tasks/T-9/state", Some(done))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&router, request("GET", "/tasks/T-9", None)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let response = router
+            .clone()
+            .oneshot(request("GET", "/graph.svg", None))
+            .await
+            .unwrap();
+        let svg = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&svg).contains("data-id=\"T-2\""));
+
+        // The dashboard loads without the token, and asks for it when the API needs it
+        let page = Request::get("/").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(page).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        let script = Request::get("/dashboard.js").body(Body::empty()).unwrap();
+        assert_eq!(send(&router, script).await.0, StatusCode::OK);
+
+        let query = json!({ "query": "{ backlog { project } }" });
+        let (status, _) = send(&router, request("POST", "/graphql", Some(query))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::{Backlog, Task};
+
+/// A task printed by the "show" command, along with the epic it belongs to.
+#[derive(Serialize)]
+struct TaskDetails<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epic: Option<&'a str>,
+    #[serde(flatten)]
+    task: &'a Task,
+    /// State glyph of each dependency, in the order of `depends`; `[?]` for unknown tasks.
+    #[serde(skip)]
+    depends: Vec<(&'a str, &'static str)>,
+}
+
+/// Executes the "show" command: prints every detail of a task of the backlog.
+pub fn execute(backlog_file: &Path, task_id: &str, format: Format) {
+    let backlog = store::load(backlog_file);
+    output::print(format, &details(&backlog, task_id));
+}
+
+/// Returns the details of a task, exiting with a not-found error if the backlog has no such task.
+fn details<'a>(backlog: &'a Backlog, task_id: &str) -> TaskDetails<'a> {
+    let Some(task) = backlog.find_task(task_id) else {
+        error::fail(
+            ErrorKind::NotFound,
+            format!("Task with ID '{}' not found in the backlog.", task_id),
+        );
+    };
+    let epic = backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task_id))
+        .map(|e| e.id.as_str());
+    let depends = task
+        .depends
+        .iter()
+        .map(|dep| {
+            let glyph = backlog
+                .find_task(dep)
+                .map(|t| output::state_glyph(&t.state))
+                .unwrap_or("[?]");
+            (dep.as_str(), glyph)
+        })
+        .collect();
+    TaskDetails {
+        epic,
+        task,
+        depends,
+    }
+}
+
+impl Render for TaskDetails<'_> {
+    fn print_text(&self) {
+        let task = self.task;
+        println!("{}: {}", task.id, task.title);
+        println!("State: {:?}", task.state);
+        if let Some(epic) = self.epic {
+            println!("Epic: {}", epic);
+        }
+        if let Some(parent) = &task.parent {
+            println!("Parent: {}", parent);
+        }
+        if let Some(priority) = task.priority {
+            println!("Priority: {}", output::priority_label(priority));
+        }
+        if let Some(assignee) = &task.assignee {
+            println!("Assignee: {}", assignee);
+        }
+        if let Some(estimate) = task.estimate {
+            println!("Estimate: {}", output::hours(estimate));
+        }
+        if let Some(spent) = task.time_spent {
+            println!("Time spent: {}", output::hours(spent));
+        }
+        if let Some(due) = task.due {
+            println!("Due: {}", due);
+        }
+        if let Some(blocker) = &task.blocker {
+            println!("Blocked: {}", blocker.reason);
+        }
+        if !task.tags.is_empty() {
+            println!("Tags: {}", task.tags.join(", "));
+        }
+        if !self.depends.is_empty() {
+            println!("Depends on:");
+            for (dep, glyph) in &self.depends {
+                println!("  {} {}", glyph, dep);
+            }
+        }
+        if let Some(description) = &task.description {
+            println!();
+            println!("{}", description.trim_end());
+        }
+        if let Some(deliverable) = &task.deliverable {
+            println!();
+            println!("Deliverables:");
+            for path in deliverable.paths() {
+                println!("  - {}", path);
+            }
+        }
+        if !task.done_when.is_empty() {
+            println!();
+            println!("Done when:");
+            for criterion in &task.done_when {
+                println!("  - {}", criterion);
+            }
+        }
+        if !task.notes.is_empty() {
+            println!();
+            println!("Notes:");
+            for note in &task.notes {
+                println!("  {}  {}", note.at.format("%Y-%m-%d %H:%M"), note.text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the details of a task carry its epic and the state of its dependencies.
+    #[test]
+    fn test_details() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+project: test-project
+epics:
+  - id: API
+    title: API
+    tasks:
+      - id: API-1
+        title: Build the API
+        state: Done
+      - id: API-2
+        title: Deploy the API
+        depends: [API-1, GONE-1]
+"#,
+        )
+        .unwrap();
+
+        let details = details(&backlog, "API-2");
+        assert_eq!(details.epic, Some("API"));
+        assert_eq!(details.task.title, "Deploy the API");
+        assert_eq!(
+            details.depends,
+            vec![
+                (
+                    "API-1",
+                    output::state_glyph(&taskai_schema::TaskState::Done)
+                ),
+                ("GONE-1", "[?]")
+            ]
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::SortOrder;
+
+/// Outcome of the "sort" command.
+#[derive(Serialize)]
+struct SortReport {
+    file: String,
+    /// Number of tasks that changed place; zero when the file was already sorted.
+    moved: usize,
+}
+
+/// Executes the "sort" command: reorders the tasks of the backlog file so that it reads in
+/// execution order, each task after its dependencies.
+///
+/// Tasks stay in their epic, and epics keep their order. The file is only written if a task moved.
+pub fn execute(backlog_file: &Path, order: SortOrder, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let moved = taskai_core::sort_tasks(&mut backlog, order);
+    if moved > 0 {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(
+        format,
+        &SortReport {
+            file: backlog_file.display().to_string(),
+            moved,
+        },
+    );
+}
+
+impl Render for SortReport {
+    fn print_text(&self) {
+        if self.moved == 0 {
+            println!("{} is already sorted.", self.file);
+        } else {
+            println!("Sorted {}: {} task(s) moved.", self.file, self.moved);
+        }
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
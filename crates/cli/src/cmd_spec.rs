@@ -0,0 +1,260 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// A skeleton specification for a common kind of project.
+#[derive(Serialize)]
+pub struct SpecTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Language suggested when none is given.
+    #[serde(skip)]
+    language: &'static str,
+    /// Sections specific to the kind of project, as headings and guidance.
+    #[serde(skip)]
+    sections: &'static [(&'static str, &'static str)],
+}
+
+/// Templates available to `taskai spec new`.
+pub const TEMPLATES: [SpecTemplate; 5] = [
+    SpecTemplate {
+        name: "web-api",
+        description: "HTTP API service with endpoints, a data model and authentication",
+        language: "Rust",
+        sections: &[
+            (
+                "Endpoints",
+                "Each endpoint with its method, path, input and output, e.g. `GET /users/{id}`.",
+            ),
+            (
+                "Data model",
+                "Entities, their fields and relations, and where they are stored.",
+            ),
+            (
+                "Authentication",
+                "Who can call the API and how they authenticate (API keys, OAuth...).",
+            ),
+            (
+                "Operations",
+                "Configuration, logging, health checks and deployment target.",
+            ),
+        ],
+    },
+    SpecTemplate {
+        name: "cli",
+        description: "Command-line tool with subcommands, options and output formats",
+        language: "Rust",
+        sections: &[
+            (
+                "Commands",
+                "Each command with its arguments and what it does, e.g. `tool sync <dir>`.",
+            ),
+            (
+                "Configuration",
+                "Configuration files and environment variables, and their precedence.",
+            ),
+            ("Output", "Output formats (text, JSON...) and exit codes."),
+        ],
+    },
+    SpecTemplate {
+        name: "library",
+        description: "Reusable library with a public API, examples and documentation",
+        language: "Rust",
+        sections: &[
+            (
+                "Public API",
+                "Main types and functions, with a short example of how they are used.",
+            ),
+            (
+                "Errors",
+                "What can go wrong and how errors are reported to callers.",
+            ),
+            (
+                "Documentation",
+                "Docs, examples and guides to ship with the library.",
+            ),
+        ],
+    },
+    SpecTemplate {
+        name: "web-app",
+        description: "Web application with pages, user flows and a backend",
+        language: "TypeScript",
+        sections: &[
+            (
+                "Pages",
+                "List each page or screen and what the user can do there.",
+            ),
+            (
+                "User flows",
+                "Step-by-step flows such as sign-up, checkout or onboarding.",
+            ),
+            (
+                "Backend",
+                "APIs, storage and third-party services the application relies on.",
+            ),
+        ],
+    },
+    SpecTemplate {
+        name: "data-pipeline",
+        description: "Data pipeline with sources, transformations and outputs",
+        language: "Python",
+        sections: &[
+            (
+                "Sources",
+                "Where the data comes from, its format, volume and update frequency.",
+            ),
+            (
+                "Transformations",
+                "Each processing step, in order, with its input and output.",
+            ),
+            (
+                "Outputs",
+                "Where the results go (tables, files, dashboards) and their schema.",
+            ),
+            (
+                "Scheduling",
+                "When the pipeline runs and how failures are retried and reported.",
+            ),
+        ],
+    },
+];
+
+/// The list of templates printed by `taskai spec templates`.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct TemplateList(&'static [SpecTemplate]);
+
+/// Executes the "spec new" command: writes a skeleton specification for the given kind of project,
+/// with the sections `taskai gen` turns into epics, tasks, deliverables and completion criteria.
+///
+/// The project name defaults to the name of the current directory. An existing file is only
+/// overwritten when `force` is set.
+pub fn new(path: &Path, template: &str, name: Option<&str>, language: Option<&str>, force: bool) {
+    let Some(template) = TEMPLATES.iter().find(|t| t.name == template) else {
+        error::fail(
+            ErrorKind::NotFound,
+            format!(
+                "Unknown template '{}'; run `taskai spec templates` to list them.",
+                template
+            ),
+        );
+    };
+    if path.exists() && !force {
+        error::fail(
+            ErrorKind::Other,
+            format!(
+                "{} already exists; use --force to overwrite it.",
+                path.display()
+            ),
+        );
+    }
+
+    let name = name.map(str::to_string).unwrap_or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|d| d.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "my-project".to_string())
+    });
+    let spec = render(template, &name, language.unwrap_or(template.language));
+    if let Err(err) = fs::write(path, spec) {
+        error::fail(
+            ErrorKind::Other,
+            format!("Error writing {}: {}", path.display(), err),
+        );
+    }
+    println!(
+        "Created {} from the {} template. Fill it in, then run `taskai gen {}`.",
+        path.display(),
+        template.name,
+        path.display()
+    );
+}
+
+/// Executes the "spec templates" command: lists the templates available to `taskai spec new`.
+pub fn templates(format: Format) {
+    output::print(format, &TemplateList(&TEMPLATES));
+}
+
+impl Render for TemplateList {
+    fn print_text(&self) {
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|t| vec![t.name.to_string(), t.description.to_string()])
+            .collect();
+        output::print_table(false, 0, &["TEMPLATE", "DESCRIPTION"], &rows);
+    }
+
+    fn print_markdown(&self) {
+        for template in self.0 {
+            println!("- **{}**: {}", template.name, template.description);
+        }
+    }
+}
+
+/// Renders the skeleton specification: the `Project:`, `Language:` and `Goal:` header the
+/// generation prompts recognize, then goals, the sections of the template, deliverables,
+/// constraints and success criteria.
+fn render(template: &SpecTemplate, name: &str, language: &str) -> String {
+    let mut spec = format!(
+        "# {name}
+
+Project: {name}
+Language: {language}
+Goal: <!-- One sentence: what the project does, and for whom. -->
+
+## Goals
+
+<!-- Each goal usually becomes an epic. -->
+
+-
+"
+    );
+    for (heading, guidance) in template.sections {
+        spec.push_str(&format!("\n## {}\n\n<!-- {} -->\n\n-\n", heading, guidance));
+    }
+    spec.push_str(
+        "
+## Deliverables
+
+<!-- Files and artifacts the project must produce; tasks point at them. -->
+
+-
+
+## Constraints
+
+<!-- Technical or organizational constraints the implementation must respect. -->
+
+-
+
+## Success criteria
+
+<!-- How will you know the project is done? These become `done_when` criteria. -->
+
+-
+",
+    );
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that every template renders the sections the generation prompts rely on.
+    #[test]
+    fn test_render() {
+        for template in &TEMPLATES {
+            let spec = render(template, "weather", "Go");
+            assert!(spec.starts_with("# weather\n\nProject: weather\nLanguage: Go\nGoal:"));
+            for heading in ["Goals", "Deliverables", "Constraints", "Success criteria"] {
+                assert!(spec.contains(&format!("\n## {}\n", heading)));
+            }
+            for (heading, _) in template.sections {
+                assert!(spec.contains(&format!("\n## {}\n", heading)));
+            }
+        }
+    }
+}
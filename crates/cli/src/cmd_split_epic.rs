@@ -0,0 +1,111 @@
+use crate::error::{self, ErrorKind};
+use crate::store;
+use std::path::Path;
+use taskai_core::{BacklogGenerator, Config, EpicSplit};
+
+/// How the tasks of the epic are split.
+pub enum SplitBy {
+    /// The given tasks move to a new epic with the given title.
+    Selection { tasks: Vec<String>, title: String },
+    /// The LLM suggests the grouping and the title of the new epic.
+    Suggestion,
+}
+
+/// Executes the "split-epic" command: splits an epic in two, moving some of its tasks (with their
+/// subtasks) to a new epic inserted right after it.
+///
+/// Task IDs and dependencies are preserved, and the backlog is validated before being written. The
+/// new epic ID is minted when not given.
+pub async fn execute(backlog_file: &Path, epic_id: &str, split_by: SplitBy, new_id: Option<&str>) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let split = match split_by {
+        SplitBy::Selection { tasks, title } => EpicSplit { title, tasks },
+        SplitBy::Suggestion => {
+            let Some(epic) = backlog.epics.iter().find(|e| e.id == epic_id) else {
+                error::fail(
+                    ErrorKind::NotFound,
+                    format!("Epic with ID '{}' not found in the backlog.", epic_id),
+                );
+            };
+            let mut generator = BacklogGenerator::new();
+            if let Ok(Some((_, config))) = Config::discover(&store::project_root(backlog_file)) {
+                if let Some(model) = &config.generate.model {
+                    generator = generator.with_model(model);
+                }
+            }
+            match generator.suggest_epic_split(epic).await {
+                Ok(split) => split,
+                Err(err) => {
+                    error::fail(ErrorKind::Llm, format!("Error suggesting a split: {}", err));
+                }
+            }
+        }
+    };
+
+    let new_id = new_id
+        .map(str::to_string)
+        .unwrap_or_else(|| taskai_core::next_epic_id(&backlog));
+    let moved = match taskai_core::split_epic(&mut backlog, epic_id, &new_id, &split) {
+        Ok(moved) => moved,
+        Err(err) => {
+            error::fail(ErrorKind::Validation, err);
+        }
+    };
+    if let Err(err) = backlog.validate() {
+        error::fail(
+            ErrorKind::Validation,
+            format!("Backlog is invalid after the split: {}", err),
+        );
+    }
+
+    store::save(backlog_file, &backlog);
+    println!(
+        "Moved {} from epic {} to new epic {}: {}.",
+        moved.join(", "),
+        epic_id,
+        new_id,
+        split.title
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests that the selected tasks move to a new epic.
+    #[tokio::test]
+    async fn test_split_epic() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+epics:
+  - id: E-1
+    title: Big
+    tasks:
+      - id: T-1
+        title: API
+      - id: T-2
+        title: UI
+        depends: [T-1]
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let split_by = SplitBy::Selection {
+            tasks: vec!["T-2".to_string()],
+            title: "Frontend".to_string(),
+        };
+        execute(file.path(), "E-1", split_by, None).await;
+
+        let backlog = store::load(file.path());
+        assert_eq!(backlog.epics[1].id, "E-2");
+        assert_eq!(backlog.epics[1].title, "Frontend");
+        assert_eq!(backlog.epics[1].tasks[0].depends, vec!["T-1"]);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::TaskState;
+
+/// Tasks found stale by the command.
+#[derive(Serialize)]
+struct StaleReport<'a> {
+    days: i64,
+    tasks: Vec<StaleEntry<'a>>,
+}
+
+/// A task stuck in its current state.
+#[derive(Serialize)]
+struct StaleEntry<'a> {
+    id: &'a str,
+    title: &'a str,
+    state: &'a TaskState,
+    since: DateTime<Utc>,
+    age_days: i64,
+}
+
+/// Executes the "stale" command: reports tasks stuck in InProgress or Blocked for longer than `days` days.
+///
+/// Exits with a non-zero status if stale tasks are found, so the check can gate CI.
+pub fn execute(backlog_file: &Path, days: i64, format: Format) {
+    let backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+
+    let stale = taskai_core::stale_tasks(&backlog, chrono::Duration::days(days), now);
+    let report = StaleReport {
+        days,
+        tasks: stale
+            .iter()
+            .map(|entry| StaleEntry {
+                id: &entry.task.id,
+                title: &entry.task.title,
+                state: &entry.task.state,
+                since: entry.since,
+                age_days: entry.age.num_days(),
+            })
+            .collect(),
+    };
+
+    output::print(format, &report);
+
+    if !report.tasks.is_empty() {
+        error::exit(ErrorKind::Validation);
+    }
+}
+
+impl Render for StaleReport<'_> {
+    fn print_text(&self) {
+        if self.tasks.is_empty() {
+            println!("No stale tasks.");
+            return;
+        }
+
+        println!("Tasks untouched for more than {} day(s):", self.days);
+        for entry in &self.tasks {
+            println!(
+                "{}: {} ({} for {} day(s), since {})",
+                entry.id,
+                entry.title,
+                output::paint_state(entry.state, &format!("{:?}", entry.state)),
+                entry.age_days,
+                entry.since.format("%Y-%m-%d")
+            );
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.tasks.is_empty() {
+            println!("No stale tasks.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .tasks
+            .iter()
+            .map(|entry| {
+                vec![
+                    entry.id.to_string(),
+                    entry.title.to_string(),
+                    format!("{:?}", entry.state),
+                    entry.age_days.to_string(),
+                    entry.since.format("%Y-%m-%d").to_string(),
+                ]
+            })
+            .collect();
+        output::print_table(true, 0, &["Task", "Title", "State", "Days", "Since"], &rows);
+    }
+}
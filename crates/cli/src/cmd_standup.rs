@@ -0,0 +1,55 @@
+use crate::notifications;
+use crate::output::{self, Format, Render};
+use crate::store;
+use std::path::Path;
+use taskai_core::{Standup, StandupTask};
+
+/// Executes the "standup" command: lists the tasks completed in the last `hours` hours, the tasks
+/// in progress and the blocked ones, then posts the summary to the chat integrations configured for
+/// standups unless `post` is false.
+pub async fn execute(backlog_file: &Path, hours: i64, post: bool, format: Format) {
+    let backlog = store::load(backlog_file);
+    let since = chrono::Utc::now() - chrono::Duration::hours(hours);
+    let standup = taskai_core::standup(&backlog, since);
+    output::print(format, &standup);
+    if post {
+        notifications::standup(backlog_file, &standup).await;
+    }
+}
+
+impl Render for Standup {
+    fn print_text(&self) {
+        println!(
+            "Standup for {} since {}",
+            self.project,
+            self.since
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        );
+        let sections = [
+            ("Done", &self.done),
+            ("In progress", &self.in_progress),
+            ("Blocked", &self.blocked),
+        ];
+        for (heading, tasks) in sections {
+            println!("{}:", heading);
+            if tasks.is_empty() {
+                println!("  (nothing)");
+            }
+            for task in tasks {
+                println!("  {}", line(task));
+            }
+        }
+    }
+}
+
+fn line(task: &StandupTask) -> String {
+    let mut line = format!("{} {}", task.id, task.title);
+    if let Some(assignee) = &task.assignee {
+        line.push_str(&format!(" ({})", assignee));
+    }
+    if let Some(reason) = &task.reason {
+        line.push_str(&format!(": {}", reason));
+    }
+    line
+}
@@ -0,0 +1,104 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{GroupStats, StatsBy};
+
+/// Statistics per group printed by the command.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Stats(Vec<GroupStats>);
+
+/// Executes the "stats" command: prints open and done counts, total and remaining estimates, and
+/// the average cycle time of the tasks of each group (assignee, tag, epic or kind of task).
+///
+/// With `csv` set, the statistics are printed as CSV instead, one row per group.
+pub fn execute(backlog_file: &Path, by: StatsBy, csv: bool, format: Format) {
+    let backlog = store::load(backlog_file);
+    let stats = Stats(taskai_core::group_stats(&backlog, by));
+    if csv {
+        stats.print_csv();
+    } else {
+        output::print(format, &stats);
+    }
+}
+
+impl Stats {
+    fn print_table(&self, markdown: bool) {
+        if self.0.is_empty() {
+            println!("No tasks.");
+            return;
+        }
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|stats| {
+                vec![
+                    stats.group.clone(),
+                    stats.open.to_string(),
+                    stats.done.to_string(),
+                    stats.estimate.to_string(),
+                    stats.remaining.to_string(),
+                    stats
+                        .average_cycle_days
+                        .map(|days| format!("{:.1}d", days))
+                        .unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect();
+        output::print_table(
+            markdown,
+            0,
+            &[
+                "GROUP",
+                "OPEN",
+                "DONE",
+                "ESTIMATE",
+                "REMAINING",
+                "CYCLE TIME",
+            ],
+            &rows,
+        );
+    }
+
+    fn print_csv(&self) {
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|stats| {
+                vec![
+                    stats.group.clone(),
+                    stats.open.to_string(),
+                    stats.done.to_string(),
+                    stats.estimate.to_string(),
+                    stats.remaining.to_string(),
+                    stats
+                        .average_cycle_days
+                        .map(|days| format!("{:.2}", days))
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        output::print_csv(
+            &[
+                "group",
+                "open",
+                "done",
+                "estimate",
+                "remaining",
+                "cycle_days",
+            ],
+            &rows,
+        );
+    }
+}
+
+impl Render for Stats {
+    fn print_text(&self) {
+        self.print_table(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_table(true);
+    }
+}
@@ -0,0 +1,376 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use crate::watch;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use taskai_core::{EstimateRollup, RollupBy};
+use taskai_schema::{Backlog, BacklogProgress, Blocker, Progress, Task, TaskState};
+
+/// Width of the progress bars, in characters.
+const BAR_WIDTH: usize = 20;
+
+/// Maximum number of upcoming due dates shown in the text dashboard.
+const MAX_UPCOMING: usize = 5;
+
+/// Data shown by the status dashboard.
+#[derive(Serialize)]
+struct Dashboard<'a> {
+    project: &'a str,
+    #[serde(flatten)]
+    progress: BacklogProgress,
+    ready: usize,
+    blocked: Vec<TaskSummary<'a>>,
+    upcoming: Vec<TaskSummary<'a>>,
+    estimates: BTreeMap<&'static str, Vec<EstimateRollup>>,
+}
+
+/// Short description of a task listed on the dashboard.
+#[derive(Serialize)]
+struct TaskSummary<'a> {
+    id: &'a str,
+    title: &'a str,
+    state: &'a TaskState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocker: Option<&'a Blocker>,
+}
+
+impl<'a> TaskSummary<'a> {
+    fn new(task: &'a Task) -> Self {
+        TaskSummary {
+            id: &task.id,
+            title: &task.title,
+            state: &task.state,
+            due: task.due,
+            blocker: task.blocker.as_ref(),
+        }
+    }
+
+    /// Describes why the task is blocked, with the blocking item when known.
+    fn blocked_because(&self) -> Option<String> {
+        let blocker = self.blocker?;
+        Some(match &blocker.link {
+            Some(link) => format!("{} (waiting on {})", blocker.reason, link),
+            None => blocker.reason.clone(),
+        })
+    }
+}
+
+/// Executes the "status" command: prints a dashboard summarizing the backlog.
+///
+/// Shows task counts by state, progress bars overall and per epic, a breakdown per tag, the number of
+/// ready tasks, the blocked tasks with why they are blocked, upcoming due dates, and completed vs remaining estimates per epic,
+/// milestone and sprint. With `watch` set, the dashboard is printed again every time the backlog file changes.
+pub fn execute(backlog_file: &Path, watch: bool, format: Format) {
+    if watch {
+        watch::watch(backlog_file, format, |backlog| {
+            output::print(format, &build(backlog))
+        });
+    } else {
+        output::print(format, &build(&store::load(backlog_file)));
+    }
+}
+
+/// Gathers the dashboard data from the backlog.
+fn build(backlog: &Backlog) -> Dashboard<'_> {
+    let all_tasks = backlog.all_tasks();
+
+    let blocked = all_tasks
+        .iter()
+        .filter(|t| matches!(t.state, TaskState::Blocked))
+        .map(|t| TaskSummary::new(t))
+        .collect();
+
+    let mut upcoming: Vec<TaskSummary> = all_tasks
+        .iter()
+        .filter(|t| t.due.is_some() && !matches!(t.state, TaskState::Done))
+        .map(|t| TaskSummary::new(t))
+        .collect();
+    upcoming.sort_by_key(|t| t.due);
+
+    Dashboard {
+        project: &backlog.project,
+        progress: backlog.progress(),
+        ready: taskai_core::get_ready_tasks(backlog).len(),
+        blocked,
+        upcoming,
+        estimates: estimate_tables(backlog),
+    }
+}
+
+impl Render for Dashboard<'_> {
+    fn print_text(&self) {
+        print_text(self, chrono::Local::now().date_naive());
+    }
+
+    fn print_markdown(&self) {
+        let overall = &self.progress.overall;
+        let today = chrono::Local::now().date_naive();
+
+        println!("## {}", self.project);
+        println!();
+        println!(
+            "**{}** ({} ready to work on)",
+            summarize(overall),
+            self.ready
+        );
+        println!();
+
+        let mut rows = vec![vec![
+            "All tasks".to_string(),
+            overall.total.to_string(),
+            overall.done.to_string(),
+            overall.in_progress.to_string(),
+            overall.blocked.to_string(),
+            format!("{:.1}%", overall.percent_done()),
+        ]];
+        for epic in &self.progress.by_epic {
+            rows.push(vec![
+                format!("{} {}", epic.id, epic.title),
+                epic.progress.total.to_string(),
+                epic.progress.done.to_string(),
+                epic.progress.in_progress.to_string(),
+                epic.progress.blocked.to_string(),
+                format!("{:.1}%", epic.progress.percent_done()),
+            ]);
+        }
+        output::print_table(
+            true,
+            0,
+            &[
+                "Scope",
+                "Tasks",
+                "Done",
+                "In progress",
+                "Blocked",
+                "Progress",
+            ],
+            &rows,
+        );
+
+        if !self.blocked.is_empty() {
+            println!();
+            println!("### Blocked");
+            println!();
+            for task in &self.blocked {
+                match task.blocked_because() {
+                    Some(because) => println!("- **{}**: {} — {}", task.id, task.title, because),
+                    None => println!("- **{}**: {}", task.id, task.title),
+                }
+            }
+        }
+
+        if !self.upcoming.is_empty() {
+            println!();
+            println!("### Upcoming due dates");
+            println!();
+            for task in &self.upcoming {
+                if let Some(due) = task.due {
+                    println!(
+                        "- {} **{}**: {} ({})",
+                        due,
+                        task.id,
+                        task.title,
+                        relative_days(due, today)
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Prints the dashboard in a human-readable form.
+fn print_text(dashboard: &Dashboard, today: NaiveDate) {
+    let overall = &dashboard.progress.overall;
+
+    println!("Project: {}", dashboard.project);
+    println!(
+        "Tasks: {} total, {} done, {} in progress, {} blocked, {} todo",
+        overall.total, overall.done, overall.in_progress, overall.blocked, overall.todo
+    );
+    println!(
+        "Progress: {} {}",
+        progress_bar(overall.percent_done()),
+        summarize(overall)
+    );
+    println!("Ready to work on: {}", dashboard.ready);
+
+    if !dashboard.progress.by_epic.is_empty() {
+        let width = dashboard
+            .progress
+            .by_epic
+            .iter()
+            .map(|e| e.id.len() + e.title.len() + 1)
+            .max()
+            .unwrap_or(0);
+
+        println!();
+        println!("Epics:");
+        for epic in &dashboard.progress.by_epic {
+            println!(
+                "  {:<width$}  {} {}",
+                format!("{} {}", epic.id, epic.title),
+                progress_bar(epic.progress.percent_done()),
+                summarize(&epic.progress),
+                width = width
+            );
+        }
+    }
+
+    if !dashboard.progress.by_tag.is_empty() {
+        println!();
+        println!("Tags:");
+        for (tag, tag_progress) in &dashboard.progress.by_tag {
+            println!("  {}: {}", tag, summarize(tag_progress));
+        }
+    }
+
+    if !dashboard.blocked.is_empty() {
+        println!();
+        println!("Blocked:");
+        for task in &dashboard.blocked {
+            let id = output::paint_state(task.state, &format!("[!] {}", task.id));
+            match task.blocked_because() {
+                Some(because) => println!("  {}: {} — {}", id, task.title, because),
+                None => println!("  {}: {}", id, task.title),
+            }
+        }
+    }
+
+    if !dashboard.upcoming.is_empty() {
+        println!();
+        println!("Upcoming due dates:");
+        for task in dashboard.upcoming.iter().take(MAX_UPCOMING) {
+            if let Some(due) = task.due {
+                println!(
+                    "  {}  {}: {} ({})",
+                    due,
+                    task.id,
+                    task.title,
+                    relative_days(due, today)
+                );
+            }
+        }
+        if dashboard.upcoming.len() > MAX_UPCOMING {
+            println!("  ... and {} more", dashboard.upcoming.len() - MAX_UPCOMING);
+        }
+    }
+
+    if overall.estimate_total > 0.0 {
+        for (name, rows) in &dashboard.estimates {
+            println!();
+            println!("Estimates by {}:", name);
+            print_rollup_table(rows);
+        }
+    }
+}
+
+/// Renders a percentage as a fixed-width text progress bar.
+fn progress_bar(percent: f64) -> String {
+    let filled = ((percent / 100.0) * BAR_WIDTH as f64).round() as usize;
+    let filled = filled.min(BAR_WIDTH);
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))
+}
+
+/// Describes a due date relative to today.
+fn relative_days(due: NaiveDate, today: NaiveDate) -> String {
+    match (due - today).num_days() {
+        0 => "due today".to_string(),
+        d if d > 0 => format!("in {} day(s)", d),
+        d => format!("overdue by {} day(s)", -d),
+    }
+}
+
+/// Formats the completion of a group of tasks on a single line.
+fn summarize(progress: &Progress) -> String {
+    let mut line = format!(
+        "{}/{} done ({:.1}%)",
+        progress.done,
+        progress.total,
+        progress.percent_done()
+    );
+
+    if progress.estimate_total > 0.0 {
+        line.push_str(&format!(
+            ", estimate {}/{} ({:.1}%)",
+            progress.estimate_done,
+            progress.estimate_total,
+            progress.percent_done_weighted()
+        ));
+    }
+
+    line
+}
+
+/// Computes the estimate rollups worth showing: always per epic, and per milestone or sprint
+/// only when at least one task uses them.
+fn estimate_tables(backlog: &Backlog) -> BTreeMap<&'static str, Vec<EstimateRollup>> {
+    let all_tasks = backlog.all_tasks();
+    let mut tables = BTreeMap::new();
+    tables.insert(
+        "epic",
+        taskai_core::estimate_rollups(backlog, RollupBy::Epic),
+    );
+
+    if all_tasks.iter().any(|t| t.milestone.is_some()) {
+        tables.insert(
+            "milestone",
+            taskai_core::estimate_rollups(backlog, RollupBy::Milestone),
+        );
+    }
+
+    if all_tasks.iter().any(|t| t.sprint.is_some()) {
+        tables.insert(
+            "sprint",
+            taskai_core::estimate_rollups(backlog, RollupBy::Sprint),
+        );
+    }
+
+    tables
+}
+
+/// Prints estimate rollups as an aligned table.
+fn print_rollup_table(rows: &[EstimateRollup]) {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|r| {
+            vec![
+                r.group.clone(),
+                r.completed.to_string(),
+                r.remaining.to_string(),
+                r.total.to_string(),
+                r.unestimated.to_string(),
+            ]
+        })
+        .collect();
+    output::print_table(
+        false,
+        2,
+        &["GROUP", "COMPLETED", "REMAINING", "TOTAL", "UNESTIMATED"],
+        &rows,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_bar_and_due_dates() {
+        assert_eq!(
+            progress_bar(50.0),
+            format!("[{}{}]", "#".repeat(10), "-".repeat(10))
+        );
+        assert_eq!(progress_bar(0.0), format!("[{}]", "-".repeat(BAR_WIDTH)));
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        assert_eq!(relative_days(today, today), "due today");
+        assert_eq!(
+            relative_days(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap(), today),
+            "overdue by 2 day(s)"
+        );
+    }
+}
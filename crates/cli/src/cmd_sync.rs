@@ -0,0 +1,242 @@
+use crate::cmd_clickup;
+use crate::cmd_github;
+use crate::cmd_gitlab;
+use crate::cmd_jira;
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{ClickUpClient, GitHubClient, GitLabClient, LinearClient};
+use taskai_sync::{
+    ClickUpProvider, GitHubProvider, GitLabProvider, JiraProvider, LinearProvider, SyncAction,
+    SyncItem, SyncOptions, SyncOutcome, TrackerProvider,
+};
+
+/// What `taskai sync` did, or would do with `dry_run` set, for each task.
+#[derive(Serialize)]
+struct SyncReport<'a> {
+    provider: &'a str,
+    target: &'a str,
+    dry_run: bool,
+    items: Vec<SyncItem>,
+}
+
+/// Builds the provider of the tracker `name` (`github`, `gitlab`, `clickup`, `linear` or `jira`)
+/// for `target`, which defaults to the section of the tracker in the configuration.
+/// `linear_project` is the Linear project new issues go to.
+///
+/// Exits with a usage error when the target is missing or the credentials of the tracker are not
+/// set; a dry run only needs them for trackers that cannot be read anonymously.
+async fn provider(
+    backlog_file: &Path,
+    name: &str,
+    target: Option<String>,
+    linear_project: Option<String>,
+    dry_run: bool,
+) -> Box<dyn TrackerProvider> {
+    let config = store::config(backlog_file);
+    let usage = |message: &str| -> ! { error::fail(ErrorKind::Usage, message) };
+    let connected = |result: Result<Box<dyn TrackerProvider>, String>| match result {
+        Ok(provider) => provider,
+        Err(err) => error::fail(ErrorKind::Other, err),
+    };
+    match name {
+        "github" => {
+            let Some(repo) = target else {
+                usage("Pass --target with the repository.");
+            };
+            cmd_github::check_repo(&repo);
+            let client = GitHubClient::from_env();
+            if !dry_run && !client.has_token() {
+                usage("Set GITHUB_TOKEN to a token allowed to update issues in the repository.");
+            }
+            Box::new(GitHubProvider::new(client, &repo))
+        }
+        "gitlab" => {
+            let project = cmd_gitlab::project_path(target, &config.gitlab);
+            let client = GitLabClient::from_env(config.gitlab.url.as_deref());
+            if !dry_run && !client.has_token() {
+                usage("Set GITLAB_TOKEN to a token allowed to update issues in the project.");
+            }
+            Box::new(GitLabProvider::new(client, &project))
+        }
+        "clickup" => {
+            let list = cmd_clickup::list_id(backlog_file, target);
+            let client = ClickUpClient::from_env();
+            if !client.has_token() {
+                usage("Set CLICKUP_API_TOKEN to a personal token of a member of the list.");
+            }
+            let provider = ClickUpProvider::connect(client, &list).await;
+            connected(provider.map(|p| Box::new(p) as Box<dyn TrackerProvider>))
+        }
+        "linear" => {
+            let Some(team) = target.or(config.linear.team) else {
+                usage("Pass --target or set linear.team in .taskai.toml.");
+            };
+            let client = LinearClient::from_env();
+            if !client.has_key() {
+                usage(
+                    "Set LINEAR_API_KEY to a Linear API key allowed to update issues in the team.",
+                );
+            }
+            let project = linear_project.or(config.linear.project);
+            let provider = LinearProvider::connect(client, &team, project.as_deref()).await;
+            connected(provider.map(|p| Box::new(p) as Box<dyn TrackerProvider>))
+        }
+        "jira" => {
+            let project = cmd_jira::project_key(target, &config.jira);
+            let client = cmd_jira::client(&config.jira);
+            if !dry_run && !client.has_credentials() {
+                usage("Set JIRA_EMAIL and JIRA_API_TOKEN to an account allowed to update issues.");
+            }
+            Box::new(JiraProvider::new(client, &project, config.jira))
+        }
+        _ => usage("Pass --provider or a tracker subcommand, such as `taskai sync github`."),
+    }
+}
+
+/// Executes `taskai sync` with the tracker `name`: reconciles the tasks of the backlog with the
+/// issues of `target` through the sync engine, in both directions.
+///
+/// Done tasks go with closed issues. When a task and its issue disagree, the side that changed
+/// since they were last synced wins, and each reference records the new sync time. Pairs where
+/// both sides changed are reported as conflicts and left alone, unless `options.prefer` says which
+/// side wins; the command then exits with a non-zero status. `options.create` also creates issues
+/// for the tasks not tracked yet, and `options.update` pushes the title and description of the
+/// tasks edited since the last sync.
+pub async fn execute(
+    backlog_file: &Path,
+    name: &str,
+    target: Option<String>,
+    linear_project: Option<String>,
+    options: SyncOptions,
+    dry_run: bool,
+    format: Format,
+) {
+    let provider = provider(backlog_file, name, target, linear_project, dry_run).await;
+    let provider = provider.as_ref();
+    let issues = match provider.list_issues().await {
+        Ok(issues) => issues,
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error listing the issues of {}: {}", provider.target(), err),
+        ),
+    };
+
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let mut report = SyncReport {
+        provider: provider.system(),
+        target: provider.target(),
+        dry_run,
+        items: taskai_sync::plan_sync(provider, &backlog, &issues, &options),
+    };
+    let outcome = if dry_run {
+        SyncOutcome::default()
+    } else {
+        let now = chrono::Utc::now();
+        taskai_sync::apply_sync(provider, &mut backlog, &issues, &mut report.items, now).await
+    };
+
+    if outcome.changed {
+        store::save(backlog_file, &backlog);
+    }
+    output::print(format, &report);
+    if let Some(failure) = outcome.failure {
+        error::fail(ErrorKind::Other, failure);
+    }
+    let conflicts = report
+        .items
+        .iter()
+        .filter(|i| i.action == SyncAction::Conflict)
+        .count();
+    if conflicts > 0 {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "{} task(s) changed on both sides; update one side or pass --prefer.",
+                conflicts
+            ),
+        );
+    }
+}
+
+impl Render for SyncReport<'_> {
+    fn print_text(&self) {
+        let unchanged = |i: &&SyncItem| i.action == SyncAction::InSync && !i.update;
+        let in_sync = self.items.iter().filter(unchanged).count();
+        let verb =
+            |done: &str, planned: &str| if self.dry_run { planned } else { done }.to_string();
+        for item in &self.items {
+            let mut outcome = match item.action {
+                SyncAction::InSync if item.update => {
+                    verb("updated the issue", "would update the issue")
+                }
+                SyncAction::InSync => continue,
+                SyncAction::Create => verb("created an issue", "would create an issue"),
+                SyncAction::Push { close: true } => {
+                    verb("closed the issue", "would close the issue")
+                }
+                SyncAction::Push { close: false } => {
+                    verb("reopened the issue", "would reopen the issue")
+                }
+                SyncAction::Pull { done: true } => {
+                    verb("marked the task Done", "would mark the task Done")
+                }
+                SyncAction::Pull { done: false } => {
+                    verb("reopened the task", "would reopen the task")
+                }
+                SyncAction::Conflict => {
+                    "conflict: both sides changed since the last sync".to_string()
+                }
+                SyncAction::MissingIssue => "issue not found".to_string(),
+            };
+            if item.update && item.action != SyncAction::InSync {
+                outcome.push_str(" and updated its fields");
+            }
+            let issue = item.issue.as_deref().unwrap_or(self.target);
+            println!("{} <-> {}: {}", item.task_id, issue, outcome);
+        }
+        println!(
+            "{} task(s) already in sync with {} {}.",
+            in_sync, self.provider, self.target
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the JSON output of a sync: issues still to create have no ID and the actions are
+    /// tagged in snake case.
+    #[test]
+    fn test_sync_report_json() {
+        let report = SyncReport {
+            provider: "github",
+            target: "acme/app",
+            dry_run: true,
+            items: vec![
+                SyncItem {
+                    task_id: "T-1".into(),
+                    issue: None,
+                    action: SyncAction::Create,
+                    update: false,
+                },
+                SyncItem {
+                    task_id: "T-2".into(),
+                    issue: Some("acme/app#3".into()),
+                    action: SyncAction::Pull { done: true },
+                    update: true,
+                },
+            ],
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["target"], "acme/app");
+        assert_eq!(json["items"][0]["action"], "create");
+        assert!(json["items"][0].get("issue").is_none());
+        assert_eq!(json["items"][1]["issue"], "acme/app#3");
+        assert_eq!(json["items"][1]["update"], true);
+    }
+}
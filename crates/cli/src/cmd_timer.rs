@@ -0,0 +1,176 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::{Backlog, TaskState};
+
+/// A timer stopped by the "start" or "stop" command.
+#[derive(Serialize)]
+struct StoppedTimer {
+    task: String,
+    /// Length of the session, in hours.
+    hours: f64,
+    /// Total hours logged on the task.
+    time_spent: f64,
+}
+
+/// Outcome of the "start" and "stop" commands.
+#[derive(Serialize)]
+struct TimerChange {
+    stopped: Vec<StoppedTimer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    started: Option<String>,
+}
+
+/// Executes the "start" command: starts a timer on the task, stopping any other running timer of
+/// the backlog first, so that time is only logged on one task at a time.
+///
+/// A Todo task is moved to InProgress.
+pub fn start(backlog_file: &Path, task_id: &str, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+    let now = chrono::Utc::now();
+
+    let Some(task) = backlog.find_task(task_id) else {
+        error::fail(
+            ErrorKind::NotFound,
+            format!("Task with ID '{}' not found in the backlog.", task_id),
+        );
+    };
+    if task.running_timer().is_some() {
+        error::fail(
+            ErrorKind::Usage,
+            format!("A timer is already running on task {}.", task_id),
+        );
+    }
+
+    let stopped = stop_timers(&mut backlog, |_| true, now);
+    if let Some(task) = backlog.find_task_mut(task_id) {
+        if matches!(task.state, TaskState::Todo) {
+            task.set_state(TaskState::InProgress, now);
+        }
+        task.start_timer(now);
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &TimerChange {
+            stopped,
+            started: Some(task_id.to_string()),
+        },
+    );
+}
+
+/// Executes the "stop" command: stops the running timer of the task, or every running timer of
+/// the backlog when no task is given, and adds the sessions to the time spent on the tasks.
+pub fn stop(backlog_file: &Path, task_id: Option<&str>, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let mut backlog = store::load(backlog_file);
+
+    let stopped = stop_timers(
+        &mut backlog,
+        |id| task_id.is_none_or(|task_id| id == task_id),
+        chrono::Utc::now(),
+    );
+    if stopped.is_empty() {
+        let message = match task_id {
+            Some(id) => format!("No timer is running on task {}.", id),
+            None => "No timer is running.".to_string(),
+        };
+        error::fail(ErrorKind::NotFound, message);
+    }
+
+    store::save(backlog_file, &backlog);
+    output::print(
+        format,
+        &TimerChange {
+            stopped,
+            started: None,
+        },
+    );
+}
+
+/// Stops the running timers of the tasks whose ID is accepted by `include`.
+fn stop_timers(
+    backlog: &mut Backlog,
+    include: impl Fn(&str) -> bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<StoppedTimer> {
+    let running: Vec<String> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| t.running_timer().is_some() && include(&t.id))
+        .map(|t| t.id.clone())
+        .collect();
+
+    running
+        .into_iter()
+        .filter_map(|id| {
+            let task = backlog.find_task_mut(&id)?;
+            let hours = task.stop_timer(now)?;
+            Some(StoppedTimer {
+                time_spent: task.time_spent.unwrap_or(hours),
+                task: id,
+                hours,
+            })
+        })
+        .collect()
+}
+
+impl Render for TimerChange {
+    fn print_text(&self) {
+        for timer in &self.stopped {
+            println!(
+                "Stopped the timer on task {} after {} ({} in total).",
+                timer.task,
+                output::hours(timer.hours),
+                output::hours(timer.time_spent)
+            );
+        }
+        if let Some(task) = &self.started {
+            println!("Started a timer on task {}.", task);
+        }
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests that starting a timer stops the running one and moves the task to InProgress.
+    #[test]
+    fn test_start_and_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            "project: test\ntasks:\n  - id: T-1\n    title: Setup\n  - id: T-2\n    title: Docs\n",
+        )
+        .unwrap();
+
+        start(&backlog_file, "T-1", Format::Table);
+        start(&backlog_file, "T-2", Format::Table);
+
+        let backlog = store::load(&backlog_file);
+        let first = backlog.find_task("T-1").unwrap();
+        assert!(first.running_timer().is_none());
+        assert!(first.time_spent.is_some());
+        let second = backlog.find_task("T-2").unwrap();
+        assert!(second.running_timer().is_some());
+        assert!(matches!(second.state, TaskState::InProgress));
+
+        stop(&backlog_file, None, Format::Table);
+        let backlog = store::load(&backlog_file);
+        assert!(backlog
+            .all_tasks()
+            .iter()
+            .all(|t| t.running_timer().is_none()));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::NaiveDate;
+use std::path::Path;
+use taskai_core::Timesheet;
+
+/// Executes the "timesheet" command: prints the time logged with `taskai start` and `taskai stop`
+/// per day and per task, from `since` to `until` when given, next to the estimate of each task.
+///
+/// With `csv` set, the time is printed as CSV instead, one row per day and task, with hours as
+/// decimal numbers.
+pub fn execute(
+    backlog_file: &Path,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    csv: bool,
+    format: Format,
+) {
+    let backlog = store::load(backlog_file);
+    let sheet = taskai_core::timesheet(&backlog, since, until, chrono::Utc::now());
+    if csv {
+        print_csv(&sheet);
+    } else {
+        output::print(format, &sheet);
+    }
+}
+
+impl Render for Timesheet {
+    fn print_text(&self) {
+        print_tables(self, false);
+    }
+
+    fn print_markdown(&self) {
+        print_tables(self, true);
+    }
+}
+
+/// Prints the time logged as CSV, one row per day and task.
+fn print_csv(sheet: &Timesheet) {
+    let rows: Vec<Vec<String>> = sheet
+        .entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.date.to_string(),
+                entry.task.clone(),
+                entry.title.clone(),
+                format!("{:.2}", entry.hours),
+            ]
+        })
+        .collect();
+    output::print_csv(&["date", "task", "title", "hours"], &rows);
+}
+
+/// Prints the time per day, then per task, as tables.
+fn print_tables(sheet: &Timesheet, markdown: bool) {
+    if sheet.entries.is_empty() {
+        println!("No time logged.");
+        return;
+    }
+
+    let rows: Vec<Vec<String>> = sheet
+        .entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.date.to_string(),
+                entry.task.clone(),
+                output::hours(entry.hours),
+                entry.title.clone(),
+            ]
+        })
+        .collect();
+    output::print_table(markdown, 0, &["DATE", "TASK", "TIME", "TITLE"], &rows);
+
+    println!();
+    let rows: Vec<Vec<String>> = sheet
+        .tasks
+        .iter()
+        .map(|task| {
+            let running = if task.running { " (running)" } else { "" };
+            vec![
+                task.task.clone(),
+                output::hours(task.hours),
+                task.estimate
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                format!("{}{}", task.title, running),
+            ]
+        })
+        .collect();
+    output::print_table(markdown, 0, &["TASK", "SPENT", "ESTIMATE", "TITLE"], &rows);
+
+    println!();
+    println!("Total: {}.", output::hours(sheet.total_hours));
+}
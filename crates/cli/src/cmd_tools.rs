@@ -0,0 +1,94 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::tools::{self, Tool};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Function calling API the definitions are printed for with `taskai tools --schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ToolSchema {
+    /// OpenAI function definitions.
+    #[value(name = "openai")]
+    OpenAi,
+    /// Anthropic tool definitions.
+    Anthropic,
+}
+
+/// The tools exposed to agents.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Tools(Vec<Tool>);
+
+/// Executes the "tools" command: prints the definitions of the backlog operations served by
+/// `taskai mcp`, for wiring them into LLM agents through function calling.
+///
+/// `--schema openai` prints them as the `tools` of an OpenAI Chat Completions request, and
+/// `--schema anthropic` as the `tools` of an Anthropic Messages request; without a schema, they are
+/// listed with their argument schemas in the output format.
+pub fn execute(schema: Option<ToolSchema>, format: Format) {
+    let definitions = tools::definitions();
+    let tools = match schema {
+        Some(ToolSchema::OpenAi) => tools::openai(&definitions),
+        Some(ToolSchema::Anthropic) => tools::anthropic(&definitions),
+        None => return output::print(format, &Tools(definitions)),
+    };
+    match serde_json::to_string_pretty(&tools) {
+        Ok(json) => println!("{}", json),
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error serializing tools to JSON: {}", err),
+        ),
+    }
+}
+
+impl Render for Tools {
+    fn print_text(&self) {
+        self.print_rows(false);
+    }
+
+    fn print_markdown(&self) {
+        self.print_rows(true);
+    }
+}
+
+impl Tools {
+    fn print_rows(&self, markdown: bool) {
+        let rows: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|t| vec![t.name.to_string(), t.description.clone()])
+            .collect();
+        output::print_table(markdown, 0, &["TOOL", "DESCRIPTION"], &rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the argument schemas are derived from the argument types.
+    #[test]
+    fn test_definitions() {
+        let anthropic = tools::anthropic(&tools::definitions());
+        let add_task = anthropic
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "add_task")
+            .unwrap();
+
+        assert_eq!(
+            add_task["description"],
+            "Add a Todo task to the backlog, returning its ID."
+        );
+        let schema = &add_task["input_schema"];
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], serde_json::json!(["title"]));
+        assert_eq!(schema["properties"]["epic"]["type"], "string");
+        assert_eq!(schema["properties"]["depends"]["type"], "array");
+
+        let openai = tools::openai(&tools::definitions());
+        assert_eq!(openai[0]["type"], "function");
+        assert_eq!(openai[0]["function"]["name"], "list_ready_tasks");
+    }
+}
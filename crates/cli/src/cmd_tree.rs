@@ -0,0 +1,265 @@
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// The backlog as a tree of epics, tasks and subtasks.
+#[derive(Serialize)]
+struct Tree<'a> {
+    project: &'a str,
+    groups: Vec<Group<'a>>,
+}
+
+/// An epic, or the standalone tasks, with the top-level tasks it contains.
+#[derive(Serialize)]
+struct Group<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epic: Option<&'a str>,
+    title: &'a str,
+    tasks: Vec<Node<'a>>,
+}
+
+/// A task with its subtasks.
+#[derive(Serialize)]
+struct Node<'a> {
+    id: &'a str,
+    title: &'a str,
+    state: &'a TaskState,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    depends: &'a [String],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subtasks: Vec<Node<'a>>,
+}
+
+/// Executes the "tree" command: prints the backlog as a tree of epics, tasks and subtasks.
+///
+/// Each task is shown with a glyph for its state (`[ ]` Todo, `[~]` InProgress, `[!]` Blocked,
+/// `[x]` Done) and the tasks it depends on. Subtasks are nested under the task named in their
+/// `parent` field.
+pub fn execute(backlog_file: &Path, format: Format) {
+    let backlog = store::load(backlog_file);
+    output::print(format, &build(&backlog));
+}
+
+/// Prints the backlog as a nested markdown checklist.
+pub fn print_markdown(backlog: &Backlog) {
+    build(backlog).print_markdown();
+}
+
+/// Arranges the tasks of the backlog into a tree.
+fn build(backlog: &Backlog) -> Tree<'_> {
+    let all_ids: HashSet<&str> = backlog.all_tasks().iter().map(|t| t.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&Task>> = HashMap::new();
+    for task in backlog.all_tasks() {
+        if let Some(parent) = task.parent.as_deref() {
+            if parent != task.id && all_ids.contains(parent) {
+                children.entry(parent).or_default().push(task);
+            }
+        }
+    }
+
+    // Tasks whose parent does not exist are shown at the top level rather than hidden.
+    let is_root = |task: &Task| match task.parent.as_deref() {
+        Some(parent) => parent == task.id || !all_ids.contains(parent),
+        None => true,
+    };
+
+    let mut visited = HashSet::new();
+    let mut groups: Vec<Group> = Vec::new();
+    for epic in &backlog.epics {
+        let roots: Vec<&Task> = epic.tasks.iter().filter(|t| is_root(t)).collect();
+        groups.push(Group {
+            epic: Some(&epic.id),
+            title: &epic.title,
+            tasks: nodes(&roots, &children, &mut visited),
+        });
+    }
+    if !backlog.tasks.is_empty() {
+        let roots: Vec<&Task> = backlog.tasks.iter().filter(|t| is_root(t)).collect();
+        groups.push(Group {
+            epic: None,
+            title: "(no epic)",
+            tasks: nodes(&roots, &children, &mut visited),
+        });
+    }
+
+    Tree {
+        project: &backlog.project,
+        groups,
+    }
+}
+
+/// Builds the nodes of the given tasks and, recursively, of their subtasks.
+fn nodes<'a>(
+    tasks: &[&'a Task],
+    children: &HashMap<&str, Vec<&'a Task>>,
+    visited: &mut HashSet<&'a str>,
+) -> Vec<Node<'a>> {
+    let mut result = Vec::new();
+    for task in tasks {
+        // Guards against parent cycles, which would otherwise recurse forever.
+        let subtasks = match children.get(task.id.as_str()) {
+            Some(subtasks) if visited.insert(task.id.as_str()) => {
+                nodes(subtasks, children, visited)
+            }
+            _ => Vec::new(),
+        };
+        result.push(Node {
+            id: &task.id,
+            title: &task.title,
+            state: &task.state,
+            depends: &task.depends,
+            subtasks,
+        });
+    }
+    result
+}
+
+impl Render for Tree<'_> {
+    fn print_text(&self) {
+        for line in self.lines() {
+            println!("{}", line);
+        }
+    }
+
+    fn print_markdown(&self) {
+        println!("## {}", self.project);
+        for group in &self.groups {
+            println!();
+            match group.epic {
+                Some(id) => println!("### {} {}", id, group.title),
+                None => println!("### {}", group.title),
+            }
+            println!();
+            print_markdown_nodes(&group.tasks, 0);
+        }
+    }
+}
+
+impl Tree<'_> {
+    /// Renders the tree as lines of text.
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.project.to_string()];
+        for (i, group) in self.groups.iter().enumerate() {
+            let last = i + 1 == self.groups.len();
+            let label = match group.epic {
+                Some(id) => format!("{} {}", id, group.title),
+                None => group.title.to_string(),
+            };
+            lines.push(format!("{}{}", branch(last), label));
+            render_nodes(&group.tasks, indent(last), &mut lines);
+        }
+        lines
+    }
+}
+
+/// Appends the given nodes and, recursively, their subtasks to `lines`.
+fn render_nodes(nodes: &[Node], prefix: &str, lines: &mut Vec<String>) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        lines.push(format!("{}{}{}", prefix, branch(last), describe(node)));
+
+        let prefix = format!("{}{}", prefix, indent(last));
+        render_nodes(&node.subtasks, &prefix, lines);
+    }
+}
+
+/// Prints the given nodes as a nested markdown checklist.
+fn print_markdown_nodes(nodes: &[Node], depth: usize) {
+    for node in nodes {
+        let checked = if matches!(node.state, TaskState::Done) {
+            "x"
+        } else {
+            " "
+        };
+        let mut line = format!(
+            "{}- [{}] **{}**: {}",
+            "  ".repeat(depth),
+            checked,
+            node.id,
+            node.title
+        );
+        if !node.depends.is_empty() {
+            line.push_str(&format!(" (depends on {})", node.depends.join(", ")));
+        }
+        println!("{}", line);
+        print_markdown_nodes(&node.subtasks, depth + 1);
+    }
+}
+
+/// Formats a task as its state glyph, ID, title and dependencies.
+fn describe(node: &Node) -> String {
+    let mut line = format!(
+        "{} {}: {}",
+        output::paint_state(node.state, output::state_glyph(node.state)),
+        node.id,
+        node.title
+    );
+    if !node.depends.is_empty() {
+        line.push_str(&format!(" (depends on {})", node.depends.join(", ")));
+    }
+    line
+}
+
+/// Returns the connector drawn before a tree node.
+fn branch(last: bool) -> &'static str {
+    if last {
+        "└── "
+    } else {
+        "├── "
+    }
+}
+
+/// Returns the indentation drawn below a tree node for its children.
+fn indent(last: bool) -> &'static str {
+    if last {
+        "    "
+    } else {
+        "│   "
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tree() {
+        let yaml = r#"
+project: test
+epics:
+  - id: E-1
+    title: Core
+    tasks:
+      - id: T-1
+        title: Parent
+        state: Done
+      - id: T-2
+        title: Child
+        parent: T-1
+        depends: [T-3]
+      - id: T-3
+        title: Other
+        state: InProgress
+tasks:
+  - id: T-4
+    title: Loose
+"#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            build(&backlog).lines(),
+            vec![
+                "test",
+                "├── E-1 Core",
+                "│   ├── [x] T-1: Parent",
+                "│   │   └── [ ] T-2: Child (depends on T-3)",
+                "│   └── [~] T-3: Other",
+                "└── (no epic)",
+                "    └── [ ] T-4: Loose",
+            ]
+        );
+    }
+}
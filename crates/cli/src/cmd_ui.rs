@@ -0,0 +1,459 @@
+use crate::error::{self, ErrorKind};
+use crate::output;
+use crate::store;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::path::{Path, PathBuf};
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Label of the pseudo-epic listing every task.
+const ALL_TASKS: &str = "(all tasks)";
+
+/// Label of the pseudo-epic listing standalone tasks.
+const NO_EPIC: &str = "(no epic)";
+
+/// Keys shown at the bottom of the screen.
+const HELP: &str =
+    "tab: switch pane  ↑↓: move  s: cycle state  a: assign  n: note  /: filter  q: quit";
+
+/// Pane that receives navigation keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Epics,
+    Tasks,
+}
+
+/// Text being typed at the bottom of the screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Input {
+    Filter(String),
+    Assign(String),
+    Note(String),
+}
+
+/// State of the terminal UI.
+struct App {
+    backlog: Backlog,
+    /// Content of the backlog file when it was last loaded or saved.
+    source: String,
+    path: PathBuf,
+    focus: Pane,
+    epic: usize,
+    task: usize,
+    filter: String,
+    input: Option<Input>,
+    message: Option<String>,
+}
+
+/// Executes the "ui" command: opens an interactive terminal UI on the backlog file.
+///
+/// The screen shows the epics, the tasks of the selected epic and the details of the selected task.
+/// Tasks can change state, be assigned and receive notes; every change is saved to the file right away.
+pub fn execute(backlog_file: &Path) {
+    let app = App::new(store::read(backlog_file), backlog_file);
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+
+    if let Err(err) = result {
+        error::fail(
+            ErrorKind::Other,
+            format!("Error running the terminal UI: {}", err),
+        );
+    }
+}
+
+impl App {
+    fn new(source: String, path: &Path) -> Self {
+        App {
+            backlog: store::parse(&source),
+            source,
+            path: path.to_path_buf(),
+            focus: Pane::Tasks,
+            epic: 0,
+            task: 0,
+            filter: String::new(),
+            input: None,
+            message: None,
+        }
+    }
+
+    /// Draws the screen and handles keys until the user quits.
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if !self.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Names of the entries of the epic pane.
+    fn epic_labels(&self) -> Vec<String> {
+        let mut labels = vec![ALL_TASKS.to_string()];
+        labels.extend(
+            self.backlog
+                .epics
+                .iter()
+                .map(|e| format!("{} {}", e.id, e.title)),
+        );
+        if !self.backlog.tasks.is_empty() {
+            labels.push(NO_EPIC.to_string());
+        }
+        labels
+    }
+
+    /// Tasks of the selected epic that match the filter.
+    fn visible_tasks(&self) -> Vec<&Task> {
+        let tasks: Vec<&Task> = match self.epic {
+            0 => self.backlog.all_tasks(),
+            i if i <= self.backlog.epics.len() => self.backlog.epics[i - 1].tasks.iter().collect(),
+            _ => self.backlog.tasks.iter().collect(),
+        };
+
+        let filter = self.filter.to_lowercase();
+        tasks
+            .into_iter()
+            .filter(|t| filter.is_empty() || matches_filter(t, &filter))
+            .collect()
+    }
+
+    /// ID of the selected task, if any.
+    fn selected_id(&self) -> Option<String> {
+        self.visible_tasks().get(self.task).map(|t| t.id.clone())
+    }
+
+    /// Applies a change to the selected task and saves the backlog.
+    fn update_selected(&mut self, change: impl FnOnce(&mut Task)) {
+        let Some(id) = self.selected_id() else {
+            return;
+        };
+        if let Some(task) = self.backlog.find_task_mut(&id) {
+            change(task);
+        }
+        self.message = Some(
+            match store::try_save_unchanged(&self.path, &self.source, &self.backlog) {
+                Ok(source) => {
+                    self.source = source;
+                    format!("Saved {}", id)
+                }
+                Err(err) => err,
+            },
+        );
+    }
+
+    /// Handles a key press. Returns false when the UI should close.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Some(input) = self.input.take() {
+            self.handle_input(input, key);
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                self.focus = match self.focus {
+                    Pane::Epics => Pane::Tasks,
+                    Pane::Tasks => Pane::Epics,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Char('s') => {
+                let now = chrono::Utc::now();
+                self.update_selected(|task| {
+                    let next = next_state(&task.state);
+                    task.set_state(next, now);
+                });
+            }
+            KeyCode::Char('a') => {
+                let current = self
+                    .selected_id()
+                    .and_then(|id| self.backlog.find_task(&id))
+                    .and_then(|t| t.assignee.clone())
+                    .unwrap_or_default();
+                self.input = Some(Input::Assign(current));
+            }
+            KeyCode::Char('n') => self.input = Some(Input::Note(String::new())),
+            KeyCode::Char('/') => self.input = Some(Input::Filter(self.filter.clone())),
+            _ => {}
+        }
+        true
+    }
+
+    /// Handles a key press while text is being typed.
+    fn handle_input(&mut self, mut input: Input, key: KeyCode) {
+        let buffer = match &mut input {
+            Input::Filter(b) | Input::Assign(b) | Input::Note(b) => b,
+        };
+
+        match key {
+            KeyCode::Esc => return,
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c) => buffer.push(c),
+            KeyCode::Enter => {
+                let now = chrono::Utc::now();
+                match input {
+                    Input::Filter(_) => {}
+                    Input::Assign(name) => {
+                        let name = name.trim().to_string();
+                        self.update_selected(|task| {
+                            task.assignee = if name.is_empty() { None } else { Some(name) };
+                            task.updated_at = Some(now);
+                        });
+                    }
+                    Input::Note(text) if !text.trim().is_empty() => {
+                        self.update_selected(|task| task.add_note(text.trim(), now));
+                    }
+                    Input::Note(_) => {}
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        // The filter applies live, while it is being typed.
+        if let Input::Filter(filter) = &input {
+            self.filter = filter.clone();
+            self.task = 0;
+        }
+        self.input = Some(input);
+    }
+
+    /// Moves the selection of the focused pane up or down.
+    fn move_selection(&mut self, delta: isize) {
+        let len = match self.focus {
+            Pane::Epics => self.epic_labels().len(),
+            Pane::Tasks => self.visible_tasks().len(),
+        };
+        if len == 0 {
+            return;
+        }
+
+        let index = match self.focus {
+            Pane::Epics => &mut self.epic,
+            Pane::Tasks => &mut self.task,
+        };
+        *index = (*index as isize + delta).clamp(0, len as isize - 1) as usize;
+        if self.focus == Pane::Epics {
+            self.task = 0;
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [epics, tasks, detail] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(35),
+            Constraint::Percentage(40),
+        ])
+        .areas(main);
+
+        let labels = self.epic_labels();
+        let items: Vec<ListItem> = labels.iter().map(|l| ListItem::new(l.as_str())).collect();
+        self.draw_list(frame, epics, "Epics", items, self.epic, Pane::Epics);
+
+        let visible = self.visible_tasks();
+        let items: Vec<ListItem> = visible
+            .iter()
+            .map(|t| {
+                ListItem::new(format!(
+                    "{} {} {}",
+                    output::state_glyph(&t.state),
+                    t.id,
+                    t.title
+                ))
+            })
+            .collect();
+        let title = if self.filter.is_empty() {
+            "Tasks".to_string()
+        } else {
+            format!("Tasks (filter: {})", self.filter)
+        };
+        self.draw_list(frame, tasks, &title, items, self.task, Pane::Tasks);
+
+        let lines = match visible.get(self.task) {
+            Some(task) => detail_lines(&self.backlog, task),
+            None => vec![Line::from("No task selected.")],
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("Detail")),
+            detail,
+        );
+
+        let status_line = match &self.input {
+            Some(Input::Filter(b)) => format!("filter: {}", b),
+            Some(Input::Assign(b)) => format!("assign to: {}", b),
+            Some(Input::Note(b)) => format!("note: {}", b),
+            None => self.message.clone().unwrap_or_else(|| HELP.to_string()),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+
+    fn draw_list(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        items: Vec<ListItem>,
+        selected: usize,
+        pane: Pane,
+    ) {
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string());
+        if self.focus == pane {
+            block = block.border_style(Style::default().add_modifier(Modifier::BOLD));
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+/// Returns true if the task matches a lowercase filter on its ID, title, tags or assignee.
+fn matches_filter(task: &Task, filter: &str) -> bool {
+    task.id.to_lowercase().contains(filter)
+        || task.title.to_lowercase().contains(filter)
+        || task.tags.iter().any(|t| t.to_lowercase().contains(filter))
+        || task
+            .assignee
+            .as_ref()
+            .is_some_and(|a| a.to_lowercase().contains(filter))
+}
+
+/// Returns the state a task moves to when cycling with the `s` key.
+fn next_state(state: &TaskState) -> TaskState {
+    match state {
+        TaskState::Todo => TaskState::InProgress,
+        TaskState::InProgress => TaskState::Done,
+        TaskState::Done => TaskState::Blocked,
+        TaskState::Blocked => TaskState::Todo,
+    }
+}
+
+/// Lines of the detail pane for a task.
+fn detail_lines<'a>(backlog: &Backlog, task: &'a Task) -> Vec<Line<'a>> {
+    let mut lines = vec![
+        Line::styled(
+            format!("{}: {}", task.id, task.title),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::from(format!("State: {:?}", task.state)),
+    ];
+
+    if let Some(assignee) = &task.assignee {
+        lines.push(Line::from(format!("Assignee: {}", assignee)));
+    }
+    if let Some(due) = task.due {
+        lines.push(Line::from(format!("Due: {}", due)));
+    }
+    if !task.tags.is_empty() {
+        lines.push(Line::from(format!("Tags: {}", task.tags.join(", "))));
+    }
+    if !task.depends.is_empty() {
+        lines.push(Line::from("Depends on:"));
+        for dep in &task.depends {
+            let state = backlog
+                .find_task(dep)
+                .map(|t| output::state_glyph(&t.state))
+                .unwrap_or("[?]");
+            lines.push(Line::from(format!("  {} {}", state, dep)));
+        }
+    }
+    if let Some(description) = &task.description {
+        lines.push(Line::from(""));
+        lines.extend(description.lines().map(Line::from));
+    }
+    if let Some(deliverable) = &task.deliverable {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Deliverables:"));
+        for path in deliverable.paths() {
+            lines.push(Line::from(format!("  - {}", path)));
+        }
+    }
+    if !task.done_when.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Done when:"));
+        for criterion in &task.done_when {
+            lines.push(Line::from(format!("  - {}", criterion)));
+        }
+    }
+    if !task.notes.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Notes:"));
+        for note in &task.notes {
+            lines.push(Line::from(format!(
+                "  {}  {}",
+                note.at.format("%Y-%m-%d %H:%M"),
+                note.text
+            )));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Tests filtering, state cycling and notes through key presses.
+    #[test]
+    fn test_keys_update_backlog() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            br#"
+project: test-project
+tasks:
+  - id: API-1
+    title: Build the API
+  - id: WEB-1
+    title: Build the site
+"#,
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let mut app = App::new(store::read(file.path()), file.path());
+        for key in [KeyCode::Char('/'), KeyCode::Char('w'), KeyCode::Char('e')] {
+            app.handle_key(key);
+        }
+        assert_eq!(app.visible_tasks().len(), 1);
+        app.handle_key(KeyCode::Enter);
+
+        app.handle_key(KeyCode::Char('s'));
+        app.handle_key(KeyCode::Char('n'));
+        for c in "started".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+
+        let saved = store::load(file.path());
+        let task = saved.find_task("WEB-1").unwrap();
+        assert!(matches!(task.state, TaskState::InProgress));
+        assert_eq!(task.notes[0].text, "started");
+        assert!(!app.handle_key(KeyCode::Char('q')));
+    }
+}
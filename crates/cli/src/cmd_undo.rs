@@ -0,0 +1,56 @@
+use crate::backup;
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Result of an undo.
+#[derive(Serialize)]
+struct Undo {
+    backlog: PathBuf,
+    steps: usize,
+    /// When the first undone change replaced the restored version.
+    replaced_at: DateTime<Utc>,
+    /// Backups still available for further undos.
+    remaining: usize,
+}
+
+/// Executes the "undo" command: restores the backlog file to its version from `steps` changes ago,
+/// using the backups taken before every change.
+///
+/// The backups of the undone changes are dropped, so a following undo goes further back.
+pub fn execute(backlog_file: &Path, steps: usize, format: Format) {
+    let _lock = store::lock(backlog_file);
+    let backup = match backup::restore(backlog_file, steps) {
+        Ok(backup) => backup,
+        Err(err) => error::fail(ErrorKind::NotFound, err),
+    };
+
+    output::print(
+        format,
+        &Undo {
+            backlog: backlog_file.to_path_buf(),
+            steps,
+            replaced_at: backup.taken_at,
+            remaining: backup::list(backlog_file).len(),
+        },
+    );
+}
+
+impl Render for Undo {
+    fn print_text(&self) {
+        println!(
+            "Undid {} change(s) to {}, back to its version before {}. {} backup(s) left.",
+            self.steps,
+            self.backlog.display(),
+            self.replaced_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.remaining
+        );
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
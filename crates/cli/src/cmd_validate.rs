@@ -0,0 +1,117 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::{Diagnostic, Severity, ValidationReport};
+
+/// The validation report along with the file it is about.
+#[derive(Serialize)]
+struct FileReport<'a> {
+    file: &'a Path,
+    #[serde(flatten)]
+    report: ValidationReport,
+}
+
+/// Executes the "validate" command: runs schema and semantic validation on the backlog and reports every problem found.
+///
+/// With `strict` set, unknown or misspelled fields are errors instead of warnings. Each error and warning
+/// is listed with its location in the file.
+/// Exits with status 3 if the backlog has errors, or warnings when `deny_warnings` is set, and 0 otherwise.
+pub fn execute(backlog_file: &Path, strict: bool, deny_warnings: bool, format: Format) {
+    let source = store::read(backlog_file);
+    let report = FileReport {
+        file: backlog_file,
+        report: taskai_core::validate_source(&source, strict),
+    };
+
+    output::print(format, &report);
+
+    let report = &report.report;
+    if !report.valid || (deny_warnings && !report.warnings.is_empty()) {
+        error::exit(ErrorKind::Validation);
+    }
+}
+
+impl Render for FileReport<'_> {
+    fn print_text(&self) {
+        print_text(self.file, &self.report);
+    }
+
+    fn print_markdown(&self) {
+        let report = &self.report;
+        let rows: Vec<Vec<String>> = report
+            .errors
+            .iter()
+            .chain(&report.warnings)
+            .map(|d| {
+                vec![
+                    severity_name(&d.severity).to_string(),
+                    format!("`{}`", d.code),
+                    d.location
+                        .as_ref()
+                        .and_then(|l| l.line)
+                        .map(|line| line.to_string())
+                        .unwrap_or_default(),
+                    d.message.clone(),
+                ]
+            })
+            .collect();
+
+        println!(
+            "**{}**: {} error(s), {} warning(s)",
+            self.file.display(),
+            report.errors.len(),
+            report.warnings.len()
+        );
+        if !rows.is_empty() {
+            println!();
+            output::print_table(true, 0, &["Severity", "Code", "Line", "Message"], &rows);
+        }
+    }
+}
+
+/// Prints the report in a compiler-like human-readable form.
+fn print_text(backlog_file: &Path, report: &ValidationReport) {
+    for diagnostic in report.errors.iter().chain(&report.warnings) {
+        println!("{}", format_diagnostic(backlog_file, diagnostic));
+    }
+
+    if report.valid && report.warnings.is_empty() {
+        println!("Backlog is valid.");
+    } else if report.valid {
+        println!(
+            "Backlog is valid with {} warning(s).",
+            report.warnings.len()
+        );
+    } else {
+        println!(
+            "Backlog is invalid: {} error(s), {} warning(s).",
+            report.errors.len(),
+            report.warnings.len()
+        );
+    }
+}
+
+/// Formats a diagnostic as `file:line: severity[code]: message`.
+pub fn format_diagnostic(backlog_file: &Path, diagnostic: &Diagnostic) -> String {
+    let severity = severity_name(&diagnostic.severity);
+
+    let position = match diagnostic.location.as_ref().and_then(|l| l.line) {
+        Some(line) => format!("{}:{}", backlog_file.display(), line),
+        None => backlog_file.display().to_string(),
+    };
+
+    format!(
+        "{}: {}[{}]: {}",
+        position, severity, diagnostic.code, diagnostic.message
+    )
+}
+
+/// Returns the lowercase name of a severity.
+pub fn severity_name(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::{self, ErrorKind};
+use crate::output::{self, Format, Render};
+use crate::store;
+use serde::Serialize;
+use std::path::Path;
+use taskai_core::TaskVerification;
+use taskai_schema::TaskState;
+
+/// Deliverable checks of every verified task.
+#[derive(Serialize)]
+struct VerifyReport {
+    tasks: Vec<TaskVerification>,
+    missing: usize,
+    failing_tasks: usize,
+}
+
+/// Executes the "verify" command: checks that the deliverables of tasks exist in the working tree.
+///
+/// When `task_id` is given, only that task is verified, whatever its state. Otherwise every Done task
+/// is verified, along with tasks that are ready to work on when `include_ready` is set.
+/// Deliverables are resolved relative to `root`, which defaults to the directory containing the backlog file.
+/// Exits with a non-zero status if any deliverable is missing.
+pub fn execute(
+    backlog_file: &Path,
+    task_id: Option<&str>,
+    root: Option<&Path>,
+    include_ready: bool,
+    format: Format,
+) {
+    let backlog = store::load(backlog_file);
+
+    let root = match root {
+        Some(root) => root.to_path_buf(),
+        None => store::project_root(backlog_file),
+    };
+
+    let tasks = match task_id {
+        Some(id) => match backlog.all_tasks().into_iter().find(|t| t.id == id) {
+            Some(task) => vec![task],
+            None => {
+                error::fail(
+                    ErrorKind::NotFound,
+                    format!("Task with ID '{}' not found in the backlog.", id),
+                );
+            }
+        },
+        None => {
+            let ready = if include_ready {
+                taskai_core::get_ready_tasks(&backlog)
+            } else {
+                vec![]
+            };
+            backlog
+                .all_tasks()
+                .into_iter()
+                .filter(|t| {
+                    matches!(t.state, TaskState::Done) || ready.iter().any(|r| r.id == t.id)
+                })
+                .collect()
+        }
+    };
+
+    let results: Vec<TaskVerification> = tasks
+        .into_iter()
+        .map(|task| taskai_core::verify_task(task, &root))
+        .collect();
+    let report = VerifyReport {
+        missing: results.iter().map(|r| r.missing().len()).sum(),
+        failing_tasks: results.iter().filter(|r| !r.missing().is_empty()).count(),
+        tasks: results,
+    };
+
+    output::print(format, &report);
+
+    if report.missing > 0 {
+        error::fail(
+            ErrorKind::Validation,
+            format!(
+                "{} deliverable(s) missing across {} task(s).",
+                report.missing, report.failing_tasks
+            ),
+        );
+    }
+}
+
+impl Render for VerifyReport {
+    fn print_text(&self) {
+        if self.tasks.is_empty() {
+            println!("No tasks to verify.");
+            return;
+        }
+
+        for result in &self.tasks {
+            println!("{}: {}", result.task_id, result.title);
+
+            if result.deliverables.is_empty() {
+                println!("  (no deliverables declared)");
+            }
+
+            for deliverable in &result.deliverables {
+                if deliverable.found {
+                    println!("  found:   {}", deliverable.pattern);
+                } else {
+                    println!("  missing: {}", deliverable.pattern);
+                }
+            }
+            println!();
+        }
+
+        if self.missing == 0 {
+            println!("All deliverables present.");
+        }
+    }
+
+    fn print_markdown(&self) {
+        if self.tasks.is_empty() {
+            println!("No tasks to verify.");
+            return;
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .tasks
+            .iter()
+            .flat_map(|result| {
+                result.deliverables.iter().map(move |d| {
+                    vec![
+                        result.task_id.clone(),
+                        format!("`{}`", d.pattern),
+                        if d.found { "found" } else { "missing" }.to_string(),
+                    ]
+                })
+            })
+            .collect();
+        output::print_table(true, 0, &["Task", "Deliverable", "Status"], &rows);
+    }
+}
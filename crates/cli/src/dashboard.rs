@@ -0,0 +1,28 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+/// Page of the dashboard served by `taskai serve`.
+const INDEX: &str = include_str!("../assets/dashboard/index.html");
+
+/// Styles of the dashboard, including those of the dependency graph from `html::dependency_graph`.
+const STYLE: &str = include_str!("../assets/dashboard/dashboard.css");
+
+/// Script of the dashboard: the board, the graph view and the state buttons, over the REST API.
+const SCRIPT: &str = include_str!("../assets/dashboard/dashboard.js");
+
+/// Routes serving the assets of the dashboard, embedded in the binary.
+///
+/// They hold no backlog data and are served without the token of the server, which the page asks
+/// for when the API requires it.
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route("/", get(|| asset("text/html; charset=utf-8", INDEX)))
+        .route("/dashboard.css", get(|| asset("text/css", STYLE)))
+        .route("/dashboard.js", get(|| asset("text/javascript", SCRIPT)))
+}
+
+async fn asset(content_type: &'static str, body: &'static str) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, content_type)], body)
+}
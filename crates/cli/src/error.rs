@@ -0,0 +1,115 @@
+use crate::output::Format;
+use serde::Serialize;
+use std::fmt::Display;
+use std::process;
+use std::sync::OnceLock;
+
+/// Category of a failure, which decides the exit code of the process.
+///
+/// | Code | Kind                          |
+/// |------|-------------------------------|
+/// | 0    | success                       |
+/// | 1    | usage error or other failure  |
+/// | 2    | backlog or config parse error |
+/// | 3    | validation error              |
+/// | 4    | LLM error                     |
+/// | 5    | task, epic or file not found  |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Invalid command-line arguments.
+    Usage,
+    /// Any other failure, such as an unreadable or unwritable file.
+    Other,
+    /// The backlog or the configuration cannot be parsed.
+    Parse,
+    /// The backlog is invalid, a check failed, or a change would break the backlog.
+    Validation,
+    /// The LLM could not be reached or gave an unusable answer.
+    Llm,
+    /// A task, epic or other referenced item does not exist.
+    NotFound,
+}
+
+impl ErrorKind {
+    /// Returns the exit code of the process for this kind of failure.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Usage | ErrorKind::Other => 1,
+            ErrorKind::Parse => 2,
+            ErrorKind::Validation => 3,
+            ErrorKind::Llm => 4,
+            ErrorKind::NotFound => 5,
+        }
+    }
+}
+
+/// Machine-readable error printed on stderr with `--format json`.
+#[derive(Serialize)]
+struct ErrorEnvelope<'a> {
+    error: CliError<'a>,
+}
+
+/// An error ending the command.
+#[derive(Serialize)]
+struct CliError<'a> {
+    kind: ErrorKind,
+    code: i32,
+    message: &'a str,
+}
+
+/// Output format of the command, which decides how errors are printed. Set once by `init`.
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Sets the output format errors are printed for.
+pub fn init(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+/// Prints the error on stderr and exits with the exit code of its kind.
+///
+/// The message is printed as is, or wrapped in a JSON envelope (`{"error": {"kind", "code",
+/// "message"}}`) when the output format is JSON.
+pub fn fail(kind: ErrorKind, message: impl Display) -> ! {
+    let message = message.to_string();
+    match FORMAT.get() {
+        Some(Format::Json) => eprintln!("{}", envelope(kind, &message)),
+        _ => eprintln!("{}", message),
+    }
+    exit(kind)
+}
+
+/// Exits with the exit code of the kind, without printing anything: for commands that already
+/// printed their report, such as a failing `validate`.
+pub fn exit(kind: ErrorKind) -> ! {
+    process::exit(kind.exit_code())
+}
+
+/// Renders the JSON envelope of an error.
+fn envelope(kind: ErrorKind, message: &str) -> String {
+    let envelope = ErrorEnvelope {
+        error: CliError {
+            kind,
+            code: kind.exit_code(),
+            message,
+        },
+    };
+    serde_json::to_string(&envelope).unwrap_or_else(|_| message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        assert_eq!(
+            envelope(
+                ErrorKind::NotFound,
+                "Task with ID 'T-9' not found in the backlog."
+            ),
+            r#"{"error":{"kind":"not_found","code":5,"message":"Task with ID 'T-9' not found in the backlog."}}"#
+        );
+        assert_eq!(ErrorKind::Usage.exit_code(), ErrorKind::Other.exit_code());
+    }
+}
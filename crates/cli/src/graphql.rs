@@ -0,0 +1,306 @@
+use crate::cmd_daemon::Daemon;
+use async_graphql::{Context, EmptySubscription, Enum, Error, Object, Result, Schema};
+use serde_json::json;
+use std::sync::Arc;
+use taskai_core::ReadyFilter;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// GraphQL schema of the backlog served by `taskai serve --graphql`.
+pub type BacklogSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Builds the schema over the backlog kept in memory by `daemon`.
+pub fn schema(daemon: Arc<Daemon>) -> BacklogSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(daemon)
+        .finish()
+}
+
+/// State of a task, as in the backlog file.
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(name = "TaskState", remote = "TaskState")]
+enum State {
+    Todo,
+    InProgress,
+    Blocked,
+    Done,
+}
+
+/// Returns a snapshot of the backlog in memory, so that a query sees a single version of it.
+fn snapshot(ctx: &Context<'_>) -> Result<Arc<Backlog>> {
+    let daemon = ctx.data::<Arc<Daemon>>()?;
+    daemon
+        .with_backlog(|backlog| Arc::new(backlog.clone()))
+        .map_err(Error::new)
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The backlog.
+    async fn backlog(&self, ctx: &Context<'_>) -> Result<BacklogNode> {
+        Ok(BacklogNode(snapshot(ctx)?))
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Changes the state of a task, with an optional note, and writes it to the backlog file.
+    async fn set_state(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        state: State,
+        note: Option<String>,
+    ) -> Result<TaskNode> {
+        let daemon = ctx.data::<Arc<Daemon>>()?;
+        let state = TaskState::from(state);
+        daemon
+            .handle("mutate", json!({ "id": id, "state": state, "note": note }))
+            .map_err(|(_, message)| Error::new(message))?;
+        let backlog = snapshot(ctx)?;
+        TaskNode::find(&backlog, &id).ok_or_else(|| Error::new(format!("Task {} not found.", id)))
+    }
+}
+
+struct BacklogNode(Arc<Backlog>);
+
+#[Object(name = "Backlog")]
+impl BacklogNode {
+    async fn project(&self) -> &str {
+        &self.0.project
+    }
+
+    async fn epics(&self) -> Vec<EpicNode> {
+        (0..self.0.epics.len())
+            .map(|index| EpicNode {
+                backlog: Arc::clone(&self.0),
+                index,
+            })
+            .collect()
+    }
+
+    /// The tasks outside of any epic.
+    async fn tasks(&self) -> Vec<TaskNode> {
+        TaskNode::all(&self.0, &self.0.tasks)
+    }
+
+    /// Every task of the backlog, standalone ones first.
+    async fn all_tasks(&self) -> Vec<TaskNode> {
+        let tasks: Vec<Task> = self.0.all_tasks().into_iter().cloned().collect();
+        TaskNode::all(&self.0, &tasks)
+    }
+
+    async fn task(&self, id: String) -> Option<TaskNode> {
+        TaskNode::find(&self.0, &id)
+    }
+
+    /// The tasks whose dependencies are all Done, in backlog order, filtered by epic, tag and
+    /// assignee and at most `limit`.
+    async fn ready(
+        &self,
+        epic: Option<String>,
+        tag: Option<String>,
+        assignee: Option<String>,
+        limit: Option<usize>,
+    ) -> Vec<TaskNode> {
+        let filter = ReadyFilter {
+            epic,
+            tag,
+            assignee,
+        };
+        let tasks: Vec<Task> = taskai_core::filter_ready_tasks(&self.0, &filter)
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        TaskNode::all(&self.0, &tasks)
+    }
+}
+
+struct EpicNode {
+    backlog: Arc<Backlog>,
+    index: usize,
+}
+
+#[Object(name = "Epic")]
+impl EpicNode {
+    async fn id(&self) -> &str {
+        &self.backlog.epics[self.index].id
+    }
+
+    async fn title(&self) -> &str {
+        &self.backlog.epics[self.index].title
+    }
+
+    async fn tasks(&self) -> Vec<TaskNode> {
+        TaskNode::all(&self.backlog, &self.backlog.epics[self.index].tasks)
+    }
+}
+
+struct TaskNode {
+    backlog: Arc<Backlog>,
+    task: Task,
+}
+
+impl TaskNode {
+    fn all(backlog: &Arc<Backlog>, tasks: &[Task]) -> Vec<TaskNode> {
+        tasks
+            .iter()
+            .map(|task| TaskNode {
+                backlog: Arc::clone(backlog),
+                task: task.clone(),
+            })
+            .collect()
+    }
+
+    fn find(backlog: &Arc<Backlog>, id: &str) -> Option<TaskNode> {
+        backlog.find_task(id).map(|task| TaskNode {
+            backlog: Arc::clone(backlog),
+            task: task.clone(),
+        })
+    }
+}
+
+#[Object(name = "Task")]
+impl TaskNode {
+    async fn id(&self) -> &str {
+        &self.task.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.task.title
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.task.description.as_deref()
+    }
+
+    async fn state(&self) -> State {
+        State::from(self.task.state.clone())
+    }
+
+    /// ID of the epic of the task, if any.
+    async fn epic(&self) -> Option<&str> {
+        self.backlog
+            .epics
+            .iter()
+            .find(|e| e.tasks.iter().any(|t| t.id == self.task.id))
+            .map(|e| e.id.as_str())
+    }
+
+    /// IDs of the tasks this one depends on.
+    async fn depends(&self) -> &[String] {
+        &self.task.depends
+    }
+
+    /// The tasks this one depends on; unknown IDs are left out.
+    async fn dependencies(&self) -> Vec<TaskNode> {
+        self.task
+            .depends
+            .iter()
+            .filter_map(|id| TaskNode::find(&self.backlog, id))
+            .collect()
+    }
+
+    /// The tasks depending on this one.
+    async fn dependents(&self) -> Vec<TaskNode> {
+        let tasks: Vec<Task> = self
+            .backlog
+            .all_tasks()
+            .into_iter()
+            .filter(|t| t.depends.contains(&self.task.id))
+            .cloned()
+            .collect();
+        TaskNode::all(&self.backlog, &tasks)
+    }
+
+    async fn done_when(&self) -> &[String] {
+        &self.task.done_when
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.task.tags
+    }
+
+    async fn assignee(&self) -> Option<&str> {
+        self.task.assignee.as_deref()
+    }
+
+    async fn priority(&self) -> Option<u32> {
+        self.task.priority
+    }
+
+    async fn estimate(&self) -> Option<f64> {
+        self.task.estimate
+    }
+
+    /// Due date of the task, as `YYYY-MM-DD`.
+    async fn due(&self) -> Option<String> {
+        self.task.due.map(|d| d.to_string())
+    }
+
+    async fn milestone(&self) -> Option<&str> {
+        self.task.milestone.as_deref()
+    }
+
+    async fn sprint(&self) -> Option<&str> {
+        self.task.sprint.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Tests nested queries over ready tasks and their dependents, and state mutations.
+    #[tokio::test]
+    async fn test_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(
+            &backlog_file,
+            "project: test\ntasks:\n  - id: T-1\n    title: Parser\nepics:\n  - id: E-1\n    \
+             title: Runtime\n    tasks:\n      - id: T-2\n        title: Interpreter\n        \
+             depends: [T-1]\n",
+        )
+        .unwrap();
+        let schema = schema(Arc::new(Daemon::new(&backlog_file)));
+
+        let query = "{ backlog { project ready { id state dependents { id epic } } \
+                     epics { id tasks { id dependencies { title } } } } }";
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["backlog"]["ready"],
+            json!([{ "id": "T-1", "state": "TODO", "dependents": [{ "id": "T-2", "epic": "E-1" }] }])
+        );
+        assert_eq!(
+            data["backlog"]["epics"][0]["tasks"][0]["dependencies"][0]["title"],
+            "Parser"
+        );
+
+        let mutation = r#"mutation { setState(id: "T-1", state: DONE, note: "Merged") { state } }"#;
+        let response = schema.execute(mutation).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["setState"]["state"],
+            "DONE"
+        );
+        assert!(fs::read_to_string(&backlog_file)
+            .unwrap()
+            .contains("Merged"));
+        let response = schema.execute("{ backlog { ready { id } } }").await;
+        assert_eq!(
+            response.data.into_json().unwrap()["backlog"]["ready"],
+            json!([{ "id": "T-2" }])
+        );
+
+        let missing = r#"mutation { setState(id: "T-9", state: DONE) { id } }"#;
+        assert!(!schema.execute(missing).await.errors.is_empty());
+    }
+}
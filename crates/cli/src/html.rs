@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use taskai_core::{Digest, DigestTask};
+use taskai_schema::{Backlog, Progress, Task, TaskState};
+
+/// Width and height of a task box in the dependency graph, in pixels.
+const NODE_WIDTH: usize = 120;
+const NODE_HEIGHT: usize = 32;
+
+/// Distance between the columns and the rows of the dependency graph, in pixels.
+const COLUMN_GAP: usize = 60;
+const ROW_GAP: usize = 16;
+
+/// Styles of the report, inlined so the file is self-contained.
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 72rem; padding: 0 1rem; color: #1f2328; }
+h1 { margin-bottom: 0; }
+.meta { color: #656d76; margin-top: .25rem; }
+.progress { display: grid; grid-template-columns: minmax(8rem, 20rem) 1fr auto; gap: .5rem 1rem; align-items: center; }
+.bar { background: #eaeef2; border-radius: 4px; height: .75rem; overflow: hidden; }
+.bar span { display: block; height: 100%; background: #2da44e; }
+.graph { overflow: auto; border: 1px solid #d0d7de; border-radius: 6px; padding: .5rem; }
+.graph text { font-size: 12px; dominant-baseline: middle; text-anchor: middle; }
+.graph path { fill: none; stroke: #8c959f; }
+.node rect { stroke: #8c959f; rx: 4; }
+.Todo rect { fill: #ffffff; } .InProgress rect { fill: #ddf4ff; } .Blocked rect { fill: #ffebe9; } .Done rect { fill: #dafbe1; }
+.filters { display: flex; gap: .5rem; margin-bottom: .5rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #d0d7de; padding: .35rem .5rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+.state { border-radius: 1rem; padding: 0 .5rem; font-size: .85em; }
+td.Todo .state { background: #eaeef2; } td.InProgress .state { background: #ddf4ff; } td.Blocked .state { background: #ffebe9; } td.Done .state { background: #dafbe1; }
+"#;
+
+/// Script filtering the task table by text and state.
+const SCRIPT: &str = r##"
+const search = document.getElementById("search");
+const state = document.getElementById("state");
+function filter() {
+  const text = search.value.toLowerCase();
+  for (const row of document.querySelectorAll("#tasks tbody tr")) {
+    const matches = row.textContent.toLowerCase().includes(text)
+      && (state.value === "" || row.dataset.state === state.value);
+    row.hidden = !matches;
+  }
+}
+search.addEventListener("input", filter);
+state.addEventListener("change", filter);
+"##;
+
+/// Renders the backlog as a single-file HTML report: progress bars overall and per epic, the
+/// dependency graph as inline SVG, and a task table filterable by text and state.
+pub fn report(backlog: &Backlog, generated_on: &str) -> String {
+    let progress = backlog.progress();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{project} backlog</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{project}</h1>\n<p class=\"meta\">{done} of {total} tasks done. Generated on {generated_on} by taskai.</p>\n",
+        project = escape(&backlog.project),
+        done = progress.overall.done,
+        total = progress.overall.total,
+    );
+
+    html.push_str("<h2>Progress</h2>\n<div class=\"progress\">\n");
+    html.push_str(&progress_row("All tasks", &progress.overall));
+    for epic in &progress.by_epic {
+        html.push_str(&progress_row(
+            &format!("{} {}", epic.id, epic.title),
+            &epic.progress,
+        ));
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Dependencies</h2>\n<div class=\"graph\">\n");
+    html.push_str(&dependency_graph(backlog));
+    html.push_str("</div>\n");
+
+    html.push_str(
+        "<h2>Tasks</h2>\n<div class=\"filters\">\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Filter tasks\">\n\
+         <select id=\"state\"><option value=\"\">All states</option>\
+         <option>Todo</option><option>InProgress</option><option>Blocked</option><option>Done</option></select>\n\
+         </div>\n",
+    );
+    html.push_str(&task_table(backlog));
+
+    let _ = write!(html, "<script>{SCRIPT}</script>\n</body>\n</html>\n");
+    html
+}
+
+/// Renders a digest as an HTML email, titled `title`: the completed, newly ready and overdue
+/// tasks. Styles are inlined, as mail clients drop style sheets.
+pub fn digest(digest: &Digest, title: &str) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n</head>\n\
+         <body style=\"font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #1f2328;\">\n\
+         <h1 style=\"font-size: 1.4em;\">{title}</h1>\n\
+         <p style=\"color: #656d76;\">Since {since}, as of {today}.</p>\n",
+        title = escape(title),
+        since = digest.since.format("%Y-%m-%d %H:%M UTC"),
+        today = digest.today,
+    );
+    let sections = [
+        ("Completed", &digest.completed, "#2da44e"),
+        ("Newly ready", &digest.newly_ready, "#0969da"),
+        ("Overdue", &digest.overdue, "#cf222e"),
+    ];
+    for (heading, tasks, color) in sections {
+        let _ = writeln!(
+            html,
+            "<h2 style=\"font-size: 1.1em; border-bottom: 2px solid {color};\">{heading} ({})</h2>",
+            tasks.len()
+        );
+        if tasks.is_empty() {
+            html.push_str("<p style=\"color: #656d76;\">Nothing.</p>\n");
+            continue;
+        }
+        html.push_str("<ul>\n");
+        for task in tasks {
+            let _ = writeln!(html, "<li>{}</li>", digest_item(task));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str(
+        "<p style=\"color: #656d76; font-size: .85em;\">Sent by taskai.</p>\n</body>\n</html>\n",
+    );
+    html
+}
+
+/// Renders a task of a digest: its ID, title, assignee and due date.
+fn digest_item(task: &DigestTask) -> String {
+    let mut item = format!("<b>{}</b> {}", escape(&task.id), escape(&task.title));
+    if let Some(assignee) = &task.assignee {
+        let _ = write!(item, " &middot; {}", escape(assignee));
+    }
+    if let Some(due) = task.due {
+        let _ = write!(item, " &middot; due {}", due);
+    }
+    item
+}
+
+/// Renders the progress bar of a group of tasks.
+fn progress_row(label: &str, progress: &Progress) -> String {
+    let percent = progress.percent_done();
+    format!(
+        "<div>{}</div><div class=\"bar\"><span style=\"width: {:.1}%\"></span></div><div>{}/{} ({:.0}%)</div>\n",
+        escape(label),
+        percent,
+        progress.done,
+        progress.total,
+        percent
+    )
+}
+
+/// Renders the task table, with the epic of each task.
+fn task_table(backlog: &Backlog) -> String {
+    let mut html = String::from(
+        "<table id=\"tasks\">\n<thead><tr><th>ID</th><th>Title</th><th>Epic</th><th>State</th>\
+         <th>Priority</th><th>Assignee</th><th>Depends on</th><th>Due</th></tr></thead>\n<tbody>\n",
+    );
+
+    let standalone = backlog.tasks.iter().map(|t| (t, ""));
+    let in_epics = backlog
+        .epics
+        .iter()
+        .flat_map(|e| e.tasks.iter().map(move |t| (t, e.id.as_str())));
+    for (task, epic) in standalone.chain(in_epics) {
+        let state = state_name(&task.state);
+        let _ = writeln!(
+            html,
+            "<tr data-state=\"{state}\"><td>{}</td><td>{}</td><td>{}</td><td class=\"{state}\"><span class=\"state\">{state}</span></td>\
+             <td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&task.id),
+            escape(&task.title),
+            escape(epic),
+            task.priority.map(|p| p.to_string()).unwrap_or_default(),
+            escape(task.assignee.as_deref().unwrap_or_default()),
+            escape(&task.depends.join(", ")),
+            task.due.map(|d| d.to_string()).unwrap_or_default(),
+        );
+    }
+
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
+/// Renders the dependency graph as SVG: each task sits in the column after its deepest
+/// dependency, with arrows from dependencies to the tasks depending on them. Each task is a `g`
+/// element with the class of its state and its ID in `data-id`.
+pub fn dependency_graph(backlog: &Backlog) -> String {
+    let tasks = backlog.all_tasks();
+    let depths = dependency_depths(&tasks);
+
+    // Position of each task: column by depth, row by order within the column
+    let mut rows_per_column: Vec<usize> = Vec::new();
+    let mut positions: HashMap<&str, (usize, usize)> = HashMap::new();
+    for task in &tasks {
+        let column = depths[task.id.as_str()];
+        if rows_per_column.len() <= column {
+            rows_per_column.resize(column + 1, 0);
+        }
+        let x = column * (NODE_WIDTH + COLUMN_GAP);
+        let y = rows_per_column[column] * (NODE_HEIGHT + ROW_GAP);
+        rows_per_column[column] += 1;
+        positions.insert(&task.id, (x, y));
+    }
+
+    let width = rows_per_column.len() * (NODE_WIDTH + COLUMN_GAP);
+    let height = rows_per_column.iter().max().copied().unwrap_or(0) * (NODE_HEIGHT + ROW_GAP);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+
+    for task in &tasks {
+        let (x, y) = positions[task.id.as_str()];
+        for dep in &task.depends {
+            if let Some(&(dep_x, dep_y)) = positions.get(dep.as_str()) {
+                let (x1, y1) = (dep_x + NODE_WIDTH, dep_y + NODE_HEIGHT / 2);
+                let (x2, y2) = (x, y + NODE_HEIGHT / 2);
+                let middle = (x1 + x2) / 2;
+                let _ = writeln!(
+                    svg,
+                    "<path d=\"M{x1},{y1} C{middle},{y1} {middle},{y2} {x2},{y2}\"/>"
+                );
+            }
+        }
+    }
+
+    for task in &tasks {
+        let (x, y) = positions[task.id.as_str()];
+        let _ = writeln!(
+            svg,
+            "<g class=\"node {}\" data-id=\"{}\"><title>{}</title><rect x=\"{x}\" y=\"{y}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text></g>",
+            state_name(&task.state),
+            escape(&task.id),
+            escape(&format!("{}: {}", task.id, task.title)),
+            x + NODE_WIDTH / 2,
+            y + NODE_HEIGHT / 2,
+            escape(&task.id),
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns the depth of each task: 0 without dependencies, or one more than its deepest
+/// dependency. Dependencies on unknown tasks are ignored, and cycles are cut off.
+fn dependency_depths<'a>(tasks: &[&'a Task]) -> HashMap<&'a str, usize> {
+    let mut depths: HashMap<&str, usize> = tasks.iter().map(|t| (t.id.as_str(), 0)).collect();
+    // Depths only grow, and settle within as many passes as there are tasks when there is no cycle
+    for _ in 0..tasks.len() {
+        let mut changed = false;
+        for task in tasks {
+            let depth = task
+                .depends
+                .iter()
+                .filter_map(|dep| depths.get(dep.as_str()))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0)
+                .min(tasks.len());
+            if depth > depths[task.id.as_str()] {
+                depths.insert(&task.id, depth);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    depths
+}
+
+/// Returns the name of the state, as used in CSS classes and the state filter.
+fn state_name(state: &TaskState) -> &'static str {
+    match state {
+        TaskState::Todo => "Todo",
+        TaskState::InProgress => "InProgress",
+        TaskState::Blocked => "Blocked",
+        TaskState::Done => "Done",
+    }
+}
+
+/// Escapes text for HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let yaml = r#"
+        project: "<b>web</b>"
+        tasks:
+          - id: T-1
+            title: Setup & config
+            state: Done
+          - id: T-2
+            title: Build
+            depends: [T-1]
+          - id: T-3
+            title: Ship
+            depends: [T-2, T-1]
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let depths = dependency_depths(&backlog.all_tasks());
+        assert_eq!((depths["T-1"], depths["T-2"], depths["T-3"]), (0, 1, 2));
+
+        let html = report(&backlog, "2024-01-10");
+        assert!(html.contains("<h1>&lt;b&gt;web&lt;/b&gt;</h1>"));
+        assert!(html.contains("<td>Setup &amp; config</td>"));
+        assert!(html.contains("1 of 3 tasks done"));
+        assert_eq!(html.matches("<path ").count(), 3);
+        assert_eq!(html.matches("<tr data-state=").count(), 3);
+    }
+}
@@ -0,0 +1,90 @@
+//! TaskAI as a library: the backlog schema, generation of backlogs from natural language, readiness
+//! of tasks, diffs and merges of backlogs, and validation and lint.
+//!
+//! This crate is the supported API of TaskAI. `taskai-schema` and `taskai-core` are the crates it
+//! is built from, and may change between releases without notice. To use the library without the
+//! command-line tool and its dependencies, turn off the default features:
+//!
+//! ```toml
+//! [dependencies]
+//! taskai = { version = "0.1", default-features = false }
+//! ```
+//!
+//! A backlog is parsed from YAML, queried, changed and written back:
+//!
+//! ```
+//! use taskai::{Backlog, TaskState};
+//!
+//! let yaml = "project: demo\ntasks:\n  - id: T-1\n    title: Parser\n  - id: T-2\n    \
+//!             title: Interpreter\n    depends: [T-1]\n";
+//! let mut backlog = Backlog::from_yaml_strict(yaml).unwrap();
+//! assert!(taskai::lint::validate_source(yaml, true).valid);
+//!
+//! let ready = taskai::ready::get_ready_tasks(&backlog);
+//! assert_eq!(ready[0].id, "T-1");
+//!
+//! let task = backlog.find_task_mut("T-1").unwrap();
+//! task.set_state(TaskState::Done, chrono::Utc::now());
+//! let next = taskai::ready::get_ready_tasks(&backlog);
+//! assert_eq!(next[0].id, "T-2");
+//!
+//! // Comments and formatting of the original text are kept
+//! let updated = taskai::fmt::update_yaml(yaml, &backlog).unwrap();
+//! assert!(updated.contains("state: Done"));
+//! ```
+//!
+//! The `examples` directory of the crate has complete programs.
+
+/// The backlog schema: backlogs, epics, tasks and their fields.
+pub use taskai_schema as schema;
+
+/// The types of a backlog, for the common case.
+pub use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// Generation of backlogs from a specification with an LLM, and merging of regenerated backlogs
+/// into existing ones.
+///
+/// [`BacklogGenerator`](generate::BacklogGenerator) reads the API key from `OPENAI_API_KEY`.
+/// Programs that are not async call `generate_blocking` and
+/// [`fetch_spec_blocking`](generate::fetch_spec_blocking), which run a runtime of their own.
+pub mod generate {
+    pub use taskai_core::{
+        fetch_spec, fetch_spec_blocking, merge_generated, BacklogGenerator, MergeSummary,
+        MAX_FOLLOWED_PAGES,
+    };
+}
+
+/// Tasks ready to be worked on, the one to pick first, and the waves of tasks that can be worked
+/// on in parallel.
+pub mod ready {
+    pub use taskai_core::{
+        best_ready_task, downstream_tasks, execution_levels, filter_ready_tasks, get_ready_tasks,
+        unfinished_dependencies, ReadyFilter,
+    };
+}
+
+/// Differences between two versions of a backlog, and merges of backlogs.
+pub mod diff {
+    pub use taskai_core::{
+        combine_backlogs, diff_backlogs, merge_generated, BacklogDiff, CombineSummary, EpicChange,
+        FieldChange, MergeSummary, StateChange, TaskChange, TaskRef,
+    };
+}
+
+/// Validation of backlogs, with the location of each problem in the file, lint rules for their
+/// hygiene, and checks of their references to GitHub and Jira.
+///
+/// [`check_refs_online`](lint::check_refs_online) looks references up over the network; programs
+/// that are not async call [`check_refs_online_blocking`](lint::check_refs_online_blocking).
+pub mod lint {
+    pub use taskai_core::{
+        check_backlog, check_ref_formats, check_refs_online, check_refs_online_blocking,
+        fix_backlog, is_fixable, lint_backlog, stale_tasks, validate_source, Diagnostic,
+        LintOptions, Location, RefIssue, Severity, StaleTask, ValidationReport,
+    };
+}
+
+/// Writing backlogs: in canonical form, or by editing the YAML they were read from in place.
+pub mod fmt {
+    pub use taskai_core::{canonicalize, format_backlog, update_yaml};
+}
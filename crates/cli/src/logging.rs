@@ -0,0 +1,43 @@
+use std::io::IsTerminal;
+use tracing_subscriber::EnvFilter;
+
+/// Sets up logging to stderr at the level selected by the `-v` and `--quiet` flags.
+///
+/// Warnings are logged by default, `-v` adds progress information (file operations, LLM latency)
+/// and `-vv` debugging details. `RUST_LOG`, when set, takes precedence over the flags.
+pub fn init(verbose: u8, quiet: bool) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        let level = level(verbose, quiet);
+        EnvFilter::new(format!("warn,taskai={0},taskai_core={0}", level))
+    });
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .init();
+}
+
+/// Returns the log level of the taskai crates for the given flags.
+fn level(verbose: u8, quiet: bool) -> &'static str {
+    match (quiet, verbose) {
+        (true, _) => "error",
+        (false, 0) => "warn",
+        (false, 1) => "info",
+        (false, 2) => "debug",
+        (false, _) => "trace",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0, false), "warn");
+        assert_eq!(level(2, false), "debug");
+        assert_eq!(level(5, false), "trace");
+        assert_eq!(level(0, true), "error");
+    }
+}
@@ -4,101 +4,2064 @@
 /// list tasks that are ready to work on, and mark tasks as done.
 mod cmd_next;
 mod cmd_done;
+mod cmd_verify;
+mod cmd_diff;
+mod cmd_status;
+mod cmd_refs;
+mod cmd_validate;
+mod cmd_plan;
+mod cmd_stale;
+mod cmd_add;
+mod cmd_reopen;
+mod cmd_tree;
+mod cmd_lint;
+mod cmd_fmt;
+mod cmd_init;
+mod cmd_ui;
+mod cmd_archive;
+mod cmd_gen;
+mod cmd_check;
+mod cmd_assign;
+mod cmd_prioritize;
+mod cmd_move;
+mod cmd_epic;
+mod cmd_split_epic;
+mod cmd_merge_backlogs;
+mod cmd_search;
+mod cmd_undo;
+mod cmd_history;
+mod cmd_report;
+mod cmd_export;
+mod cmd_import;
+mod cmd_timer;
+mod cmd_timesheet;
+mod cmd_sort;
+mod cmd_spec;
+mod cmd_stats;
+mod cmd_block;
+mod cmd_depend;
+mod cmd_clean;
+mod cmd_github;
+mod cmd_jira;
+mod cmd_sync;
+mod cmd_asana;
+mod cmd_obsidian;
+mod cmd_git;
+mod cmd_branch;
+mod cmd_scan;
+mod cmd_ci;
+mod cmd_standup;
+mod cmd_digest;
+mod cmd_mcp;
+mod cmd_agent;
+mod cmd_tools;
+mod cmd_lsp;
+mod cmd_daemon;
+mod cmd_serve;
+mod cmd_gitlab;
+mod cmd_azure_devops;
+mod cmd_clickup;
+mod cmd_convert;
+mod cmd_show;
+mod aliases;
+mod backup;
+mod dashboard;
+mod error;
+mod graphql;
+mod html;
+mod logging;
+mod notifications;
+mod output;
+mod picker;
+mod store;
+mod tools;
+mod watch;
 
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::{CommandFactory, Parser, Subcommand};
+use error::ErrorKind;
+use output::Format;
+use std::path::{Path, PathBuf};
 use std::{fs, process};
-use taskai_core;
+use taskai_schema::TaskState;
+use taskai_sync::{SyncOptions, SyncSide};
 
 /// CLI argument parser structure.
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Output format: table (or text), yaml, json or markdown.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Backlog file used by commands given none (defaults to `taskai.yaml` or
+    /// `.taskai/backlog.yaml`, searched upward from the current directory).
+    #[arg(long, short = 'b', global = true, env = "TASKAI_BACKLOG", value_name = "FILE")]
+    backlog: Option<PathBuf>,
+
+    /// Disable colored output (also off with `NO_COLOR` or when output is not a terminal).
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Log more details to stderr: -v for progress, -vv for debugging (overridden by `RUST_LOG`).
+    #[arg(long, short, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Only log errors.
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
-/// Enum representing the available CLI commands.
+/// Enum representing the available CLI commands.
+#[derive(Subcommand)]
+enum Commands {
+    /// Generate a task backlog from a specification.
+    Gen {
+        /// Path to the specification file. Defaults to the spec named in `.taskai.toml`.
+        spec_file: Option<PathBuf>,
+
+        /// Fetch the specification from this web page or raw document instead; HTML is stripped to
+        /// text and Markdown passed through.
+        #[arg(long, conflicts_with = "spec_file")]
+        url: Option<String>,
+
+        /// Also use up to this many pages (at most 20) linked from `--url` under its directory.
+        #[arg(long, requires = "url", default_value_t = 0, value_name = "PAGES")]
+        follow: usize,
+        
+        /// Language for prompts (en, fr). Defaults to `.taskai.toml`, then `en`.
+        #[arg(long)]
+        lang: Option<String>,
+        
+        /// Style of the generated backlog. Defaults to `.taskai.toml`, then `standard`.
+        #[arg(long)]
+        style: Option<String>,
+
+        /// Write the backlog to this file instead of printing it.
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Merge into the existing output file, preserving the state of tasks it already contains.
+        #[arg(long, requires = "output")]
+        merge: bool,
+
+        /// Overwrite the output file if it already exists.
+        #[arg(long, requires = "output", conflicts_with = "merge")]
+        force: bool,
+    },
+    
+    /// List tasks that are ready to work on.
+    Next {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Print only the IDs of the ready tasks, one per line.
+        #[arg(long)]
+        ids_only: bool,
+
+        /// Only show tasks of this epic.
+        #[arg(long)]
+        epic: Option<String>,
+
+        /// Only show tasks carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only show tasks assigned to this person.
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Show at most this many tasks.
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Show only the single best task to work on next.
+        #[arg(long)]
+        one: bool,
+
+        /// Re-render whenever the backlog file changes.
+        #[arg(long)]
+        watch: bool,
+
+        /// Claim the best ready task instead: mark it InProgress and assign it to ASSIGNEE
+        /// (defaults to the current user). Concurrent claims never get the same task.
+        #[arg(long, value_name = "ASSIGNEE", num_args = 0..=1, conflicts_with = "watch")]
+        claim: Option<Option<String>>,
+
+        /// Run COMMAND for each ready task (or the task claimed with --claim), replacing `{id}`,
+        /// `{title}` and other task fields in it, e.g. `--exec 'my-agent --task "{id}: {title}"'`.
+        #[arg(long, value_name = "COMMAND", conflicts_with_all = ["watch", "ids_only"])]
+        exec: Option<String>,
+    },
+    
+    /// Mark one or more tasks as done, picking the task interactively when none is given.
+    #[command(name = "mark-done")]
+    MarkDone {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+        
+        /// ID of a task to mark as done (can be repeated).
+        #[arg(long)]
+        task: Vec<String>,
+
+        /// Comma-separated IDs of tasks to mark as done.
+        #[arg(long, value_delimiter = ',')]
+        tasks: Vec<String>,
+
+        /// Mark every task of this epic as done (can be repeated).
+        #[arg(long)]
+        epic: Vec<String>,
+
+        /// Completion note stored with a timestamp on the tasks.
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// Check that task deliverables exist in the working tree.
+    Verify {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of a single task to verify (defaults to all Done tasks).
+        task_id: Option<String>,
+
+        /// Directory deliverable paths are resolved against (defaults to the backlog's directory).
+        #[arg(long)]
+        root: Option<PathBuf>,
+
+        /// Also verify tasks that are ready to work on.
+        #[arg(long)]
+        include_ready: bool,
+    },
+
+    /// Show the semantic differences between two backlog files.
+    Diff {
+        /// Path to the old backlog file.
+        old_file: PathBuf,
+
+        /// Path to the new backlog file.
+        new_file: PathBuf,
+
+        /// Only print how many tasks were added, removed and changed.
+        #[arg(long)]
+        stat: bool,
+    },
+
+    /// Show progress metrics for the backlog.
+    Status {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Re-render whenever the backlog file changes.
+        #[arg(long)]
+        watch: bool,
+    },
+
+    /// Validate the external references and links of tasks.
+    Refs {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Also check that referenced GitHub/Jira items and links exist.
+        #[arg(long)]
+        online: bool,
+    },
+
+    /// Validate the backlog structure and dependencies.
+    Validate {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Reject unknown or misspelled fields.
+        #[arg(long)]
+        strict: bool,
+
+        /// Exit with a non-zero status when warnings are found.
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+
+    /// Show the remaining tasks grouped into waves that can run in parallel.
+    Plan {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+
+    /// Report tasks stuck in InProgress or Blocked for too long.
+    Stale {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Number of days after which an untouched task is considered stale (defaults to 7).
+        #[arg(long)]
+        days: Option<i64>,
+    },
+
+    /// Add a new task to the backlog.
+    Add {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Title of the task.
+        #[arg(long)]
+        title: String,
+
+        /// Explicit ID for the task (minted from existing IDs by default).
+        #[arg(long)]
+        id: Option<String>,
+
+        /// ID of the epic to add the task to.
+        #[arg(long)]
+        epic: Option<String>,
+
+        /// Comma-separated IDs of the tasks this task depends on.
+        #[arg(long, value_delimiter = ',')]
+        depends: Vec<String>,
+
+        /// Deliverable path (can be repeated).
+        #[arg(long)]
+        deliverable: Vec<String>,
+
+        /// Description of the task.
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Show every detail of a task.
+    Show {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to show (picked interactively when omitted in a terminal).
+        task_id: Option<String>,
+    },
+
+    /// Reopen a Done task, setting it back to Todo.
+    Reopen {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to reopen (picked interactively when omitted in a terminal).
+        #[arg(long)]
+        task: Option<String>,
+    },
+
+    /// Check the backlog for hygiene problems, optionally fixing them.
+    Lint {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Fix auto-fixable problems and rewrite the backlog file.
+        #[arg(long)]
+        fix: bool,
+
+        /// Minimum severity of the problems to report (defaults to warning).
+        #[arg(long, value_enum)]
+        severity: Option<cmd_lint::MinSeverity>,
+
+        /// Number of days after which an untouched task is considered stale (defaults to 7).
+        #[arg(long)]
+        days: Option<i64>,
+    },
+
+    /// Rewrite the backlog file in canonical form.
+    Fmt {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Check that the file is formatted without rewriting it.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Scaffold a starter specification and a `.taskai.toml` configuration.
+    Init {
+        /// Directory to initialize.
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Name of the specification file to create.
+        #[arg(long, default_value = "spec.md")]
+        spec: String,
+
+        /// Overwrite existing files.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Open an interactive terminal UI to browse and update the backlog.
+    Ui {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+
+    /// Show the backlog as a tree of epics, tasks and subtasks.
+    Tree {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+
+    /// Move Done tasks out of the active backlog into its archive section or another file.
+    Archive {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Only archive tasks completed more than this many days ago.
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<i64>,
+
+        /// Move the tasks to the archive section of this backlog file instead.
+        #[arg(long, value_name = "FILE")]
+        to: Option<PathBuf>,
+
+        /// Show what would be archived without changing any file.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Run the checks derived from `done_when` criteria (commands, file existence).
+    Check {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of a single task to check (defaults to all tasks not done yet).
+        task_id: Option<String>,
+
+        /// Directory commands run in (defaults to the backlog's directory).
+        #[arg(long)]
+        root: Option<PathBuf>,
+
+        /// Run the criteria marked as commands (`$ cmd`); they are reported as manual otherwise.
+        #[arg(long)]
+        run: bool,
+
+        /// Mark tasks whose criteria all pass as done.
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Assign a task to a person, or unassign it.
+    Assign {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to assign (picked interactively when omitted in a terminal).
+        task_id: Option<String>,
+
+        /// Person to assign the task to (checked against the team in `.taskai.toml`, if any).
+        person: Option<String>,
+
+        /// Person to assign the task to, so that the task ID can be left out and picked.
+        #[arg(long, conflicts_with_all = ["person", "unassign"])]
+        to: Option<String>,
+
+        /// Remove the current assignee instead.
+        #[arg(long)]
+        unassign: bool,
+    },
+
+    /// Interactively set the priority of Todo tasks.
+    Prioritize {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Rank tasks by comparing them two at a time.
+        #[arg(long)]
+        pairwise: bool,
+    },
+
+    /// Move a task and its subtasks to another epic or to the standalone tasks.
+    #[command(allow_missing_positional = true)]
+    Move {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to move.
+        task_id: String,
+
+        /// ID of the epic to move the task to.
+        #[arg(long, required_unless_present = "standalone")]
+        epic: Option<String>,
+
+        /// Move the task out of its epic, to the standalone tasks.
+        #[arg(long, conflicts_with = "epic")]
+        standalone: bool,
+    },
+
+    /// Manage the epics of the backlog.
+    Epic {
+        #[command(subcommand)]
+        command: EpicCommands,
+    },
+
+    /// Split an oversized epic in two, by task selection or with an LLM-suggested grouping.
+    #[command(allow_missing_positional = true)]
+    SplitEpic {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the epic to split.
+        epic_id: String,
+
+        /// Comma-separated IDs of the tasks moving to the new epic.
+        #[arg(long, value_delimiter = ',', required_unless_present = "suggest", requires = "title")]
+        tasks: Vec<String>,
+
+        /// Title of the new epic.
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Let the LLM suggest how to group the tasks.
+        #[arg(long, conflicts_with_all = ["tasks", "title"])]
+        suggest: bool,
+
+        /// ID of the new epic (defaults to the next `E-N`).
+        #[arg(long)]
+        new_id: Option<String>,
+    },
+
+    /// Combine two backlog files into one, namespacing colliding task IDs.
+    MergeBacklogs {
+        /// Path to the first backlog file.
+        first_file: PathBuf,
+
+        /// Path to the second backlog file.
+        second_file: PathBuf,
+
+        /// Prefix for colliding IDs of the second backlog (defaults to its project name).
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Write the merged backlog to this file instead of printing it.
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists.
+        #[arg(long, requires = "output")]
+        force: bool,
+    },
+
+    /// Search task IDs, titles, descriptions and done_when criteria.
+    #[command(allow_missing_positional = true)]
+    Search {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Text to search for (a regular expression with --regex).
+        query: String,
+
+        /// Treat the query as a regular expression.
+        #[arg(long, short = 'e')]
+        regex: bool,
+
+        /// Match letter case exactly.
+        #[arg(long, short = 's')]
+        case_sensitive: bool,
+    },
+
+    /// Restore the backlog as it was before the last change(s).
+    Undo {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Number of changes to undo.
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+
+    /// List the changes made to the backlog, or show the diff of one of them.
+    History {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Show the diff of this change (1 being the latest).
+        #[arg(long, value_name = "CHANGE")]
+        show: Option<usize>,
+    },
+
+    /// Show the burndown and velocity of the backlog as charts, or export them as CSV or JSON.
+    Report {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Start the burndown on this date (YYYY-MM-DD) instead of the first recorded activity.
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+
+        /// Number of weeks the rolling velocity is averaged over.
+        #[arg(long, default_value_t = 3)]
+        window: u32,
+
+        /// Print the burndown as CSV, one row per day.
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Summarize the work done, in progress and blocked, and post it to the configured chats.
+    Standup {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// List the tasks completed in this many hours.
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+
+        /// Only print the summary, without posting it.
+        #[arg(long)]
+        no_post: bool,
+    },
+
+    /// Summarize the completed, newly ready and overdue tasks, and mail it as an HTML digest.
+    Digest {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Cover the last week instead of the last day.
+        #[arg(long)]
+        weekly: bool,
+
+        /// Render the digest as HTML and send it with the SMTP settings of `[email]`.
+        #[arg(long)]
+        email: bool,
+
+        /// With --email, write the HTML to stdout instead of sending it.
+        #[arg(long, requires = "email")]
+        stdout: bool,
+    },
+
+    /// Export the tasks as rows for spreadsheets (`--to csv`) or as a report (`--to html`).
+    #[command(args_conflicts_with_subcommands = true)]
+    Export {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// File format to write instead of the task rows in the output format.
+        #[arg(long, value_enum)]
+        to: Option<cmd_export::ExportFormat>,
+
+        #[command(subcommand)]
+        command: Option<ExportCommands>,
+    },
+
+    /// Import tasks from a CSV file, updating known tasks and adding new ones, or from a list.
+    #[command(
+        allow_missing_positional = true,
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Import {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// File to import: CSV with `--from csv` or a `.csv` extension, Org with `--from org` or a
+        /// `.org` extension, todo.txt with `--from todo-txt`, or a plain list of tasks, one per
+        /// line, with `--from lines` or a `.txt` extension.
+        file: Option<PathBuf>,
+
+        /// Format of the file to import (defaults to the one of its extension).
+        #[arg(long, value_enum)]
+        from: Option<cmd_import::ImportFormat>,
+
+        /// With a list of tasks, ask the LLM to group them into epics and infer their
+        /// dependencies.
+        #[arg(long)]
+        infer: bool,
+
+        #[command(subcommand)]
+        command: Option<ImportCommands>,
+    },
+
+    /// Create and check out the git branch of a task, moving the task to InProgress.
+    Branch {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to work on (picked interactively when omitted in a terminal).
+        task_id: Option<String>,
+
+        /// Template of the branch name, with `{id}`, `{slug}` and `{epic}` placeholders
+        /// (defaults to `git.branch_template` in `.taskai.toml`, then `feat/{id}-{slug}`).
+        #[arg(long)]
+        template: Option<String>,
+    },
+
+    /// Suggest the open tasks completed by the files changed in a git revision range.
+    Scan {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Revision range to diff, such as `HEAD~5..HEAD`, or a revision to compare the working
+        /// tree with.
+        #[arg(long, default_value = "HEAD")]
+        rev: String,
+
+        /// Mark the tasks whose deliverables were all changed as done.
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Validate and lint the backlog in CI, annotating the problems and failing on errors.
+    Ci {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Annotations to emit: github, gitlab or text (detected from the CI environment).
+        #[arg(long, value_parser = ["github", "gitlab", "text"])]
+        annotations: Option<String>,
+
+        /// Reject unknown or misspelled fields.
+        #[arg(long)]
+        strict: bool,
+
+        /// Exit with a non-zero status when warnings are found.
+        #[arg(long)]
+        deny_warnings: bool,
+
+        /// Path of the GitLab Code Quality report written with gitlab annotations.
+        #[arg(long, default_value = "gl-code-quality-report.json")]
+        report: PathBuf,
+    },
+
+    /// Serve the backlog as Model Context Protocol tools over stdio, for agents to drive it.
+    Mcp {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+
+    /// Hand the ready tasks to a coding agent one by one, checking its work before closing them.
+    Agent {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Command running the agent, with `{prompt}` replaced by the task rendered as a prompt,
+        /// e.g. `claude -p {prompt}` (defaults to `agent.command` in `.taskai.toml`).
+        #[arg(long, value_name = "COMMAND")]
+        command: Option<String>,
+
+        /// Only hand over tasks of this epic.
+        #[arg(long)]
+        epic: Option<String>,
+
+        /// Only hand over tasks carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Stop after this many tasks.
+        #[arg(long)]
+        max_tasks: Option<usize>,
+
+        /// Print the prompt and the command of the next task without running anything.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run the `done_when` criteria marked as commands (`$ cmd`) to check the agent's work.
+        #[arg(long)]
+        run_checks: bool,
+    },
+
+    /// Print the agent tools of `taskai mcp` as function definitions (`--schema openai|anthropic`).
+    Tools {
+        /// Function calling API to print the definitions for.
+        #[arg(long, value_enum)]
+        schema: Option<cmd_tools::ToolSchema>,
+    },
+
+    /// Serve the Language Server Protocol over stdio, for editing backlog files in editors.
+    Lsp,
+
+    /// Keep the backlog in memory and serve queries and changes over a unix socket (JSON-RPC).
+    Daemon {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Path of the socket (defaults to `.taskai.sock` next to the backlog).
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+
+    /// Serve a REST API and a web dashboard over the backlog.
+    Serve {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Port to listen on.
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to listen on; use `0.0.0.0` to accept requests from other machines.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Bearer token that requests must carry in their `Authorization` header.
+        #[arg(long, env = "TASKAI_SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// Also serve the backlog as GraphQL on `/graphql`.
+        #[arg(long)]
+        graphql: bool,
+
+        /// Also serve the backlog over gRPC on this port.
+        #[arg(long)]
+        grpc_port: Option<u16>,
+    },
+
+    /// Convert the backlog between a YAML file and a directory of Markdown task files.
+    Convert {
+        /// Path to the backlog file or directory (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Path to write the converted backlog to: a YAML file if it ends in `.yaml` or `.yml`, a
+        /// backlog directory otherwise.
+        #[arg(long)]
+        to: PathBuf,
+    },
+
+    /// Start a timer on a task, stopping the one running on another task.
+    Start {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to work on (picked interactively when omitted in a terminal).
+        task_id: Option<String>,
+    },
+
+    /// Stop the running timer and log the time spent on its task.
+    Stop {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Only stop the timer of this task.
+        task_id: Option<String>,
+    },
+
+    /// Show the time logged per day and per task, next to the estimates.
+    Timesheet {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Only show time logged on or after this date (YYYY-MM-DD).
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+
+        /// Only show time logged on or before this date (YYYY-MM-DD).
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+
+        /// Print the time logged as CSV, one row per day and task.
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Reorder the tasks of the backlog file in execution order.
+    Sort {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Order of tasks that do not depend on each other: dependencies (file order, the
+        /// default) or priority (most important first).
+        #[arg(long, value_parser = ["dependencies", "priority"], default_value = "dependencies")]
+        by: String,
+    },
+
+    /// Write specifications from templates.
+    Spec {
+        #[command(subcommand)]
+        command: SpecCommands,
+    },
+
+    /// Show open and done counts, estimates and cycle time per assignee, tag, epic or kind.
+    Stats {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// How to group the tasks: assignee, tag, epic or kind (the prefix of the task ID).
+        #[arg(long, value_parser = ["assignee", "tag", "epic", "kind"], default_value = "assignee")]
+        by: String,
+
+        /// Print the statistics as CSV, one row per group.
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Mark a task as Blocked, recording why so that `next` skips it until it is unblocked.
+    Block {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to block (picked interactively when omitted in a terminal).
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Why the task is blocked.
+        #[arg(long)]
+        reason: String,
+
+        /// URL or reference of the blocking item, e.g. `github:owner/repo#12`.
+        #[arg(long)]
+        link: Option<String>,
+    },
+
+    /// Unblock a Blocked task, setting it back to Todo.
+    Unblock {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the task to unblock (picked interactively when omitted in a terminal).
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Set the task back to InProgress instead of Todo.
+        #[arg(long)]
+        resume: bool,
+    },
+
+    /// Add or remove task dependencies, refusing missing tasks and cycles.
+    Depend {
+        #[command(subcommand)]
+        command: DependCommands,
+    },
+
+    /// Synchronize the backlog with another tracker, picked by subcommand or with `--provider`.
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Tracker to synchronize with.
+        #[arg(long, value_parser = ["github", "gitlab", "linear", "clickup", "jira"])]
+        provider: Option<String>,
+
+        /// Where the tracker keeps the tasks: the repository (`owner/name`) on GitHub, the project
+        /// path on GitLab, the team key on Linear, the list ID on ClickUp or the project key on
+        /// Jira; defaults to the section of the tracker in `.taskai.toml`.
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Side that wins when a task and its issue both changed since the last sync.
+        #[arg(long, value_parser = ["backlog", "tracker"])]
+        prefer: Option<String>,
+
+        /// Also create an issue for each task not tracked yet (always on with Linear).
+        #[arg(long)]
+        create: bool,
+
+        /// Also push the title and description of the tasks edited since the last sync.
+        #[arg(long)]
+        update: bool,
+
+        /// Show what would change without updating the tracker or the backlog.
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+    },
+
+    /// Integrate the backlog with git.
+    Git {
+        #[command(subcommand)]
+        command: GitCommands,
+    },
+
+    /// Prune empty epics, duplicate dependencies and references to deleted tasks.
+    Clean {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Also archive Done tasks completed more than this many days ago.
+        #[arg(long, value_name = "DAYS")]
+        archive_done: Option<i64>,
+
+        /// Show what would be cleaned without changing the backlog.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Subcommands of `taskai epic`.
+#[derive(Subcommand)]
+enum EpicCommands {
+    /// List the epics with their number of tasks done.
+    List {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+
+    /// Add an empty epic.
+    #[command(allow_missing_positional = true)]
+    Add {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Title of the epic.
+        title: String,
+
+        /// ID of the epic (defaults to the next `E-N`).
+        #[arg(long)]
+        id: Option<String>,
+    },
+
+    /// Change the title or the ID of an epic.
+    Rename {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the epic to rename.
+        epic_id: Option<String>,
+
+        /// New title of the epic.
+        title: Option<String>,
+
+        /// New ID of the epic.
+        #[arg(long)]
+        new_id: Option<String>,
+    },
+
+    /// Remove an epic, saying what to do with its tasks.
+    #[command(allow_missing_positional = true)]
+    Rm {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the epic to remove.
+        epic_id: String,
+
+        /// Move the tasks of the epic to this epic.
+        #[arg(long, conflicts_with_all = ["standalone", "delete_tasks"])]
+        move_to: Option<String>,
+
+        /// Move the tasks of the epic to the standalone tasks.
+        #[arg(long, conflicts_with = "delete_tasks")]
+        standalone: bool,
+
+        /// Delete the tasks of the epic.
+        #[arg(long)]
+        delete_tasks: bool,
+    },
+}
+
+/// Subcommands of `taskai depend`.
+#[derive(Subcommand)]
+enum DependCommands {
+    /// Make a task depend on other tasks.
+    #[command(allow_missing_positional = true)]
+    Add {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the dependent task.
+        task_id: String,
+
+        /// ID of a task it depends on (can be repeated or comma-separated).
+        #[arg(long, required = true, value_delimiter = ',')]
+        on: Vec<String>,
+    },
+
+    /// Remove dependencies of a task.
+    #[command(allow_missing_positional = true)]
+    Rm {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the dependent task.
+        task_id: String,
+
+        /// ID of a task it should no longer depend on (can be repeated or comma-separated).
+        #[arg(long, required = true, value_delimiter = ',')]
+        on: Vec<String>,
+    },
+}
+
+/// Subcommands of `taskai export`, exporting to other trackers.
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Create a GitHub issue for each task not exported yet (requires `GITHUB_TOKEN`).
+    Github {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Repository to create the issues in, as `owner/name`.
+        #[arg(long)]
+        repo: String,
+
+        /// Show the issues that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create a GitLab issue for each task not exported yet, epics as milestones or epics.
+    Gitlab {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Path of the GitLab project, as `group/name` (defaults to `gitlab.project`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show the milestones, issues and links that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create a Jira issue for each epic and task not exported yet, linking dependencies.
+    Jira {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Key of the Jira project (defaults to `jira.project` in `.taskai.toml`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show the issues and links that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Mirror the backlog in an Asana project: epics as sections, tasks and their dependencies.
+    Asana {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// GID of the Asana project (defaults to `asana.project` in `.taskai.toml`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show the sections and tasks that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create Azure DevOps work items for epics and tasks, with predecessor links from `depends`.
+    AzureDevops {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Azure DevOps organization (defaults to `azure_devops.organization` in `.taskai.toml`).
+        #[arg(long)]
+        organization: Option<String>,
+
+        /// Azure DevOps project (defaults to `azure_devops.project` in `.taskai.toml`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show the work items that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create ClickUp tasks for epics and tasks, in the status of their state, with dependencies.
+    Clickup {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the ClickUp list (defaults to `clickup.list` in `.taskai.toml`).
+        #[arg(long)]
+        list: Option<String>,
+
+        /// Show the tasks that would be created without creating them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write a note per task and an index note per epic into an Obsidian vault.
+    Obsidian {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Directory of the Obsidian vault.
+        #[arg(long)]
+        vault: PathBuf,
+
+        /// Folder of the vault to write the notes in (defaults to the project name).
+        #[arg(long)]
+        folder: Option<String>,
+    },
+}
+
+/// Subcommands of `taskai import`, importing from other trackers.
 #[derive(Subcommand)]
-enum Commands {
-    /// Generate a task backlog from a specification.
-    Gen {
-        /// Path to the specification file.
-        spec_file: PathBuf,
-        
-        /// Language for prompts (en, fr).
-        #[arg(long, default_value = "en")]
-        lang: String,
-        
-        /// Style of the generated backlog.
-        #[arg(long, default_value = "standard")]
-        style: String,
+enum ImportCommands {
+    /// Turn the issues of a GitHub repository into tasks, updating the tasks already imported.
+    Github {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// Repository to read the issues from, as `owner/name`.
+        #[arg(long)]
+        repo: String,
     },
-    
-    /// List tasks that are ready to work on.
-    Next {
-        /// Path to the backlog file.
-        backlog_file: PathBuf,
+
+    /// Turn the issues of a GitLab project into tasks, blocking links into dependencies.
+    Gitlab {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// Path of the GitLab project, as `group/name` (defaults to `gitlab.project`).
+        #[arg(long)]
+        project: Option<String>,
     },
-    
-    /// Mark a task as done.
-    #[command(name = "mark-done")]
-    MarkDone {
-        /// Path to the backlog file.
-        backlog_file: PathBuf,
-        
-        /// ID of the task to mark as done.
+
+    /// Turn the epics and issues of a Jira project into epics and tasks, links into dependencies.
+    Jira {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// Key of the Jira project (defaults to `jira.project` in `.taskai.toml`).
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Turn the tasks of a ClickUp list into tasks, parent tasks into epics and dependencies.
+    Clickup {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// ID of the ClickUp list (defaults to `clickup.list` in `.taskai.toml`).
+        #[arg(long)]
+        list: Option<String>,
+    },
+
+    /// Turn the stories of a Pivotal Tracker export (CSV or JSON) into tasks, blockers into
+    /// dependencies.
+    Pivotal {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// Export to import; when it is the only path given, the backlog is discovered.
+        file: Option<PathBuf>,
+    },
+
+    /// Turn the stories of a Shortcut export (CSV or JSON) into tasks, epics into epics and
+    /// blocking links into dependencies.
+    Shortcut {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery); created if missing.
+        backlog_file: Option<PathBuf>,
+
+        /// Export to import; when it is the only path given, the backlog is discovered.
+        file: Option<PathBuf>,
+    },
+}
+
+/// Subcommands of `taskai git`.
+#[derive(Subcommand)]
+enum GitCommands {
+    /// Install a post-commit hook marking the tasks referenced by commit messages.
+    InstallHooks {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Replace a post-commit hook not installed by taskai.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Update the tasks referenced by the last commit; run by the installed hook.
+    #[command(hide = true)]
+    PostCommit {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+    },
+}
+
+/// Subcommands of `taskai sync`.
+#[derive(Subcommand)]
+enum SyncCommands {
+    /// Reconcile task states with the states of their GitHub issues, in both directions.
+    Github {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Repository of the issues, as `owner/name`.
+        #[arg(long)]
+        repo: String,
+
+        /// Side that wins when a task and its issue both changed since the last sync.
+        #[arg(long, value_parser = ["backlog", "github"])]
+        prefer: Option<String>,
+
+        /// Show what would change without updating the issues or the backlog.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Place the tasks on a GitHub Projects board, in the column of their state.
+    #[command(name = "github-project")]
+    GithubProject {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Project board, as `owner/number` (defaults to `github.project` in `.taskai.toml`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would change without updating the board or the backlog.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Reconcile task states with the states of their GitLab issues, in both directions.
+    Gitlab {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Path of the GitLab project, as `group/name` (defaults to `gitlab.project`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Side that wins when a task and its issue both changed since the last sync.
+        #[arg(long, value_parser = ["backlog", "gitlab"])]
+        prefer: Option<String>,
+
+        /// Show what would change without updating the issues or the backlog.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create Linear issues for new tasks and pull the state of tracked issues back.
+    Linear {
+        /// Path to the backlog file (defaults to `--backlog`, then discovery).
+        backlog_file: Option<PathBuf>,
+
+        /// Key of the Linear team (defaults to `linear.team` in `.taskai.toml`).
+        #[arg(long)]
+        team: Option<String>,
+
+        /// Linear project to add new issues to (defaults to `linear.project`).
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Show what would change without creating issues or updating the backlog.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Subcommands of `taskai spec`.
+#[derive(Subcommand)]
+enum SpecCommands {
+    /// Write a skeleton specification for a common kind of project.
+    New {
+        /// Specification file to create.
+        #[arg(default_value = "spec.md")]
+        file: PathBuf,
+
+        /// Kind of project (see `taskai spec templates`).
+        #[arg(long, value_parser = ["web-api", "cli", "library", "web-app", "data-pipeline"])]
+        template: String,
+
+        /// Name of the project (defaults to the name of the current directory).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Programming language (defaults to the usual one for the template).
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Overwrite the file if it already exists.
         #[arg(long)]
-        task: String,
+        force: bool,
     },
+
+    /// List the specification templates.
+    Templates,
 }
 
 /// Asynchronous main function for the CLI application.
 /// Handles command parsing and dispatches to the appropriate command handler.
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    // Aliases come from the configuration of the project
+    let config = match taskai_core::Config::discover(&store::search_root()) {
+        Ok(Some((_, config))) => config,
+        _ => taskai_core::Config::default(),
+    };
+    let args = aliases::expand(&Cli::command(), std::env::args_os().collect(), &config.aliases)
+        .unwrap_or_else(|err| error::fail(ErrorKind::Usage, err));
+
+    // Usage errors exit with 1 like other failures, while --help and --version exit with 0
+    let cli = Cli::try_parse_from(args).unwrap_or_else(|err| {
+        let _ = err.print();
+        process::exit(if err.use_stderr() { 1 } else { 0 });
+    });
+    error::init(cli.format);
+    logging::init(cli.verbose, cli.quiet);
+    // The output theme follows the project of the backlog, which `--backlog` may put elsewhere
+    let output_config = cli
+        .backlog
+        .clone()
+        .or_else(|| store::discover(&store::search_root()))
+        .and_then(|file| taskai_core::Config::discover(&store::project_root(&file)).ok())
+        .flatten()
+        .map_or(config.output, |(_, config)| config.output);
+    output::init_colors(cli.no_color, &output_config);
+
+    // Resolves the backlog of commands given no path: `--backlog`/`TASKAI_BACKLOG`, then discovery
+    let backlog = |file: Option<PathBuf>| store::resolve(file.or_else(|| cli.backlog.clone()));
 
     match cli.command {
-        Commands::Gen { spec_file, lang, style } => {
-            // Read the specification file
-            let spec = match fs::read_to_string(&spec_file) {
-                Ok(content) => content,
+        Commands::Gen { spec_file, url, follow, lang, style, output, merge, force } => {
+            // Fill in missing options from the project configuration
+            let (config_dir, config) = match taskai_core::Config::discover(&store::search_root()) {
+                Ok(Some((path, config))) => {
+                    (path.parent().map(Path::to_path_buf).unwrap_or_default(), config)
+                }
+                Ok(None) => (PathBuf::new(), taskai_core::Config::default()),
                 Err(err) => {
-                    eprintln!("Error reading specification file: {}", err);
-                    process::exit(1);
+                    error::fail(ErrorKind::Parse, err);
+                }
+            };
+
+            let lang = lang.or(config.generate.lang).unwrap_or_else(|| "en".to_string());
+            let style = style.or(config.generate.style).unwrap_or_else(|| "standard".to_string());
+
+            let spec = match url {
+                // Fetch the specification from the web
+                Some(url) => match taskai_core::fetch_spec(&url, follow).await {
+                    Ok(spec) => spec,
+                    Err(err) => {
+                        error::fail(
+                            ErrorKind::Other,
+                            format!("Error fetching specification: {}", err),
+                        );
+                    }
+                },
+                None => {
+                    let configured_spec = config.generate.spec.map(|spec| config_dir.join(spec));
+                    let spec_file = match spec_file.or(configured_spec) {
+                        Some(spec_file) => spec_file,
+                        None => {
+                            error::fail(
+                                ErrorKind::Usage,
+                                format!(
+                                    "No specification file given and none configured in {}; run \
+                                     `taskai init` to create one, or pass --url.",
+                                    taskai_core::CONFIG_FILE
+                                ),
+                            );
+                        }
+                    };
+
+                    // Read the specification file
+                    let path = spec_file.display();
+                    tracing::info!(%path, %lang, %style, "reading specification");
+                    match fs::read_to_string(&spec_file) {
+                        Ok(content) => content,
+                        Err(err) => {
+                            error::fail(
+                                ErrorKind::Other,
+                                format!("Error reading specification file: {}", err),
+                            );
+                        }
+                    }
                 }
             };
 
             // Generate the backlog
-            let generator = taskai_core::BacklogGenerator::new()
+            let mut generator = taskai_core::BacklogGenerator::new()
                 .with_language(&lang)
                 .with_style(&style);
+            if let Some(model) = &config.generate.model {
+                generator = generator.with_model(model);
+            }
 
-            match generator.generate(&spec).await {
-                Ok(backlog) => {
-                    // Output YAML to stdout
-                    match serde_yaml::to_string(&backlog) {
-                        Ok(yaml) => println!("{}", yaml),
+            match (generator.generate(&spec).await, output) {
+                (Ok(backlog), Some(output)) => {
+                    match cmd_gen::write_backlog(&output, backlog, merge, force) {
+                        Ok(Some(summary)) => println!(
+                            "Merged into {}: {} task(s) preserved, {} added, {} kept from the existing backlog.",
+                            output.display(),
+                            summary.preserved.len(),
+                            summary.added.len(),
+                            summary.kept.len()
+                        ),
+                        Ok(None) => println!("Backlog written to {}.", output.display()),
                         Err(err) => {
-                            eprintln!("Error serializing backlog to YAML: {}", err);
-                            process::exit(1);
+                            error::fail(ErrorKind::Other, err);
                         }
                     }
                 }
-                Err(err) => {
-                    eprintln!("Error generating backlog: {}", err);
-                    process::exit(1);
+                (Ok(backlog), None) => match cli.format {
+                    // Output YAML to stdout, the backlog's own format
+                    Format::Table | Format::Yaml => match serde_yaml::to_string(&backlog) {
+                        Ok(yaml) => println!("{}", yaml),
+                        Err(err) => {
+                            error::fail(
+                                ErrorKind::Other,
+                                format!("Error serializing backlog to YAML: {}", err),
+                            );
+                        }
+                    },
+                    Format::Json => match serde_json::to_string_pretty(&backlog) {
+                        Ok(json) => println!("{}", json),
+                        Err(err) => {
+                            error::fail(
+                                ErrorKind::Other,
+                                format!("Error serializing backlog to JSON: {}", err),
+                            );
+                        }
+                    },
+                    Format::Markdown => cmd_tree::print_markdown(&backlog),
+                },
+                (Err(err), _) => {
+                    error::fail(
+                        ErrorKind::Llm,
+                        format!("Error generating backlog: {}", err),
+                    );
                 }
             }
         }
         
-        Commands::Next { backlog_file } => {
-            cmd_next::execute(&backlog_file);
+        Commands::Next {
+            backlog_file, ids_only, epic, tag, assignee, limit, one, watch, claim, exec,
+        } => {
+            let backlog_file = backlog(backlog_file);
+            let defaults = store::config(&backlog_file).next;
+            let claim = claim.map(|name| {
+                name.or_else(backup::current_user).unwrap_or_else(|| {
+                    error::fail(
+                        ErrorKind::Usage,
+                        "Give the person claiming the task: --claim NAME.",
+                    )
+                })
+            });
+            cmd_next::execute(
+                &backlog_file,
+                cmd_next::NextOptions {
+                    ids_only,
+                    filter: taskai_core::ReadyFilter {
+                        epic: epic.or(defaults.epic),
+                        tag: tag.or(defaults.tag),
+                        assignee: assignee.or(defaults.assignee),
+                    },
+                    limit: limit.or(defaults.limit),
+                    one,
+                    watch,
+                    claim,
+                    exec,
+                },
+                cli.format,
+            );
         }
         
-        Commands::MarkDone { backlog_file, task } => {
-            cmd_done::execute(&backlog_file, &task);
+        Commands::MarkDone { backlog_file, mut task, tasks, epic, note } => {
+            let backlog_file = backlog(backlog_file);
+            task.extend(tasks);
+            if task.is_empty() && epic.is_empty() {
+                task.push(picker::task_id(&backlog_file, "mark as done", |t| {
+                    !matches!(t.state, TaskState::Done)
+                }));
+            }
+            cmd_done::execute(&backlog_file, &task, &epic, note.as_deref(), cli.format).await;
+        }
+
+        Commands::Verify { backlog_file, task_id, root, include_ready } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            cmd_verify::execute(
+                &backlog(backlog_file),
+                task_id.as_deref(),
+                root.as_deref(),
+                include_ready,
+                cli.format,
+            );
+        }
+
+        Commands::Diff { old_file, new_file, stat } => {
+            cmd_diff::execute(&old_file, &new_file, stat, cli.format);
+        }
+
+        Commands::Status { backlog_file, watch } => {
+            cmd_status::execute(&backlog(backlog_file), watch, cli.format);
+        }
+
+        Commands::Refs { backlog_file, online } => {
+            cmd_refs::execute(&backlog(backlog_file), online, cli.format).await;
+        }
+
+        Commands::Validate { backlog_file, strict, deny_warnings } => {
+            cmd_validate::execute(&backlog(backlog_file), strict, deny_warnings, cli.format);
+        }
+
+        Commands::Plan { backlog_file } => {
+            cmd_plan::execute(&backlog(backlog_file), cli.format);
+        }
+
+        Commands::Stale { backlog_file, days } => {
+            let backlog_file = backlog(backlog_file);
+            let days = days.or(store::config(&backlog_file).stale.days).unwrap_or(7);
+            cmd_stale::execute(&backlog_file, days, cli.format);
+        }
+
+        Commands::Add { backlog_file, title, id, epic, depends, deliverable, description } => {
+            cmd_add::execute(
+                &backlog(backlog_file),
+                cmd_add::NewTask {
+                    title,
+                    id,
+                    epic,
+                    depends,
+                    deliverables: deliverable,
+                    description,
+                },
+                cli.format,
+            );
         }
+
+        Commands::Show { backlog_file, task_id } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            let backlog_file = backlog(backlog_file);
+            let task_id = task_id
+                .unwrap_or_else(|| picker::task_id(&backlog_file, "show", |_| true));
+            cmd_show::execute(&backlog_file, &task_id, cli.format);
+        }
+
+        Commands::Reopen { backlog_file, task } => {
+            let backlog_file = backlog(backlog_file);
+            let task = task.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "reopen", |t| matches!(t.state, TaskState::Done))
+            });
+            cmd_reopen::execute(&backlog_file, &task, cli.format);
+        }
+
+        Commands::Lint { backlog_file, fix, severity, days } => {
+            let backlog_file = backlog(backlog_file);
+            let defaults = store::config(&backlog_file).lint;
+            let severity = severity
+                .map(taskai_core::Severity::from)
+                .or(defaults.severity)
+                .unwrap_or(taskai_core::Severity::Warning);
+            let days = days.or(defaults.days).unwrap_or(7);
+            cmd_lint::execute(&backlog_file, fix, severity, days, cli.format);
+        }
+
+        Commands::Fmt { backlog_file, check } => {
+            cmd_fmt::execute(&backlog(backlog_file), check);
+        }
+
+        Commands::Init { dir, spec, force } => {
+            cmd_init::execute(&dir, &spec, force);
+        }
+
+        Commands::Ui { backlog_file } => {
+            cmd_ui::execute(&backlog(backlog_file));
+        }
+
+        Commands::Tree { backlog_file } => {
+            cmd_tree::execute(&backlog(backlog_file), cli.format);
+        }
+
+        Commands::Archive { backlog_file, older_than, to, dry_run } => {
+            let backlog_file = backlog(backlog_file);
+            let older_than = older_than.or(store::config(&backlog_file).archive.older_than);
+            cmd_archive::execute(
+                &backlog_file,
+                cmd_archive::ArchiveOptions {
+                    older_than,
+                    to: to.as_deref(),
+                    dry_run,
+                },
+                cli.format,
+            );
+        }
+
+        Commands::Check { backlog_file, task_id, root, run, apply } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            cmd_check::execute(
+                &backlog(backlog_file),
+                task_id.as_deref(),
+                root.as_deref(),
+                run,
+                apply,
+                cli.format,
+            );
+        }
+
+        Commands::Assign { backlog_file, task_id, person, to, unassign } => {
+            let (backlog_file, [task_id, person]) =
+                shift_positionals(backlog_file, [task_id, person]);
+            let backlog_file = backlog(backlog_file);
+            let person = person.or(to);
+            let task_id = task_id.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "assign", |t| !matches!(t.state, TaskState::Done))
+            });
+            let person = match (person, unassign) {
+                (Some(_), true) => {
+                    error::fail(ErrorKind::Usage, "Give either a person or --unassign, not both.")
+                }
+                (None, false) => error::fail(
+                    ErrorKind::Usage,
+                    "Give the person to assign the task to, or --unassign.",
+                ),
+                (person, _) => person,
+            };
+            cmd_assign::execute(&backlog_file, &task_id, person.as_deref(), cli.format);
+        }
+
+        Commands::Prioritize { backlog_file, pairwise } => {
+            cmd_prioritize::execute(&backlog(backlog_file), pairwise);
+        }
+
+        Commands::Move { backlog_file, task_id, epic, standalone: _ } => {
+            cmd_move::execute(&backlog(backlog_file), &task_id, epic.as_deref(), cli.format);
+        }
+
+        Commands::Epic { command } => match command {
+            EpicCommands::List { backlog_file } => {
+                cmd_epic::list(&backlog(backlog_file), cli.format);
+            }
+            EpicCommands::Add { backlog_file, title, id } => {
+                cmd_epic::add(&backlog(backlog_file), &title, id.as_deref(), cli.format);
+            }
+            EpicCommands::Rename { backlog_file, epic_id, title, new_id } => {
+                let (backlog_file, [epic_id, title]) =
+                    shift_positionals(backlog_file, [epic_id, title]);
+                let epic_id = epic_id.unwrap_or_else(|| {
+                    error::fail(ErrorKind::Usage, "Give the ID of the epic to rename.")
+                });
+                if title.is_none() && new_id.is_none() {
+                    error::fail(ErrorKind::Usage, "Give the new title of the epic, or --new-id.");
+                }
+                cmd_epic::rename(
+                    &backlog(backlog_file),
+                    &epic_id,
+                    title.as_deref(),
+                    new_id.as_deref(),
+                    cli.format,
+                );
+            }
+            EpicCommands::Rm { backlog_file, epic_id, move_to, standalone, delete_tasks } => {
+                let disposal = match move_to {
+                    Some(target) => cmd_epic::TaskDisposal::MoveTo(target),
+                    None if standalone => cmd_epic::TaskDisposal::Standalone,
+                    None if delete_tasks => cmd_epic::TaskDisposal::Delete,
+                    None => cmd_epic::TaskDisposal::Keep,
+                };
+                cmd_epic::remove(&backlog(backlog_file), &epic_id, disposal, cli.format);
+            }
+        },
+
+        Commands::SplitEpic { backlog_file, epic_id, tasks, title, suggest, new_id } => {
+            let split_by = match title {
+                Some(title) if !suggest => cmd_split_epic::SplitBy::Selection { tasks, title },
+                _ => cmd_split_epic::SplitBy::Suggestion,
+            };
+            cmd_split_epic::execute(&backlog(backlog_file), &epic_id, split_by, new_id.as_deref()).await;
+        }
+
+        Commands::MergeBacklogs { first_file, second_file, prefix, output, force } => {
+            cmd_merge_backlogs::execute(
+                &first_file,
+                &second_file,
+                prefix.as_deref(),
+                output.as_deref(),
+                force,
+            );
+        }
+
+        Commands::Search { backlog_file, query, regex, case_sensitive } => {
+            let options = taskai_core::SearchOptions { regex, case_sensitive };
+            cmd_search::execute(&backlog(backlog_file), &query, options, cli.format);
+        }
+
+        Commands::Undo { backlog_file, steps } => {
+            cmd_undo::execute(&backlog(backlog_file), steps, cli.format);
+        }
+
+        Commands::History { backlog_file, show } => {
+            cmd_history::execute(&backlog(backlog_file), show, cli.format);
+        }
+
+        Commands::Report { backlog_file, since, window, csv } => {
+            cmd_report::execute(&backlog(backlog_file), since, window, csv, cli.format);
+        }
+
+        Commands::Standup { backlog_file, hours, no_post } => {
+            cmd_standup::execute(&backlog(backlog_file), hours, !no_post, cli.format).await;
+        }
+
+        Commands::Digest { backlog_file, weekly, email, stdout } => {
+            cmd_digest::execute(&backlog(backlog_file), weekly, email, stdout, cli.format).await;
+        }
+
+        Commands::Export { backlog_file, to, command } => match command {
+            None => cmd_export::execute(&backlog(backlog_file), to, cli.format),
+            Some(ExportCommands::Github { backlog_file, repo, dry_run }) => {
+                cmd_github::export(&backlog(backlog_file), &repo, dry_run, cli.format).await;
+            }
+            Some(ExportCommands::Gitlab { backlog_file, project, dry_run }) => {
+                cmd_gitlab::export(&backlog(backlog_file), project, dry_run, cli.format).await;
+            }
+            Some(ExportCommands::Jira { backlog_file, project, dry_run }) => {
+                cmd_jira::export(&backlog(backlog_file), project, dry_run, cli.format).await;
+            }
+            Some(ExportCommands::Asana { backlog_file, project, dry_run }) => {
+                cmd_asana::export(&backlog(backlog_file), project, dry_run, cli.format).await;
+            }
+            Some(ExportCommands::AzureDevops { backlog_file, organization, project, dry_run }) => {
+                let backlog_file = backlog(backlog_file);
+                cmd_azure_devops::export(&backlog_file, organization, project, dry_run, cli.format)
+                    .await;
+            }
+            Some(ExportCommands::Clickup { backlog_file, list, dry_run }) => {
+                cmd_clickup::export(&backlog(backlog_file), list, dry_run, cli.format).await;
+            }
+            Some(ExportCommands::Obsidian { backlog_file, vault, folder }) => {
+                cmd_obsidian::export(&backlog(backlog_file), &vault, folder, cli.format);
+            }
+        },
+
+        Commands::Import { backlog_file, file, from, infer, command } => match (command, file) {
+            (Some(ImportCommands::Github { backlog_file, repo }), _) => {
+                cmd_github::import(&backlog(backlog_file), &repo, cli.format).await;
+            }
+            (Some(ImportCommands::Gitlab { backlog_file, project }), _) => {
+                cmd_gitlab::import(&backlog(backlog_file), project, cli.format).await;
+            }
+            (Some(ImportCommands::Jira { backlog_file, project }), _) => {
+                cmd_jira::import(&backlog(backlog_file), project, cli.format).await;
+            }
+            (Some(ImportCommands::Clickup { backlog_file, list }), _) => {
+                cmd_clickup::import(&backlog(backlog_file), list, cli.format).await;
+            }
+            (Some(ImportCommands::Pivotal { backlog_file, file }), _) => {
+                let (backlog_file, file) = export_file(backlog_file, file);
+                cmd_import::stories(&backlog(backlog_file), &file, "pivotal", cli.format);
+            }
+            (Some(ImportCommands::Shortcut { backlog_file, file }), _) => {
+                let (backlog_file, file) = export_file(backlog_file, file);
+                cmd_import::stories(&backlog(backlog_file), &file, "shortcut", cli.format);
+            }
+            (None, Some(file)) => {
+                cmd_import::execute(&backlog(backlog_file), &file, from, infer, cli.format).await;
+            }
+            (None, None) => error::fail(ErrorKind::Usage, "Give the file to import."),
+        },
+
+        Commands::Branch { backlog_file, task_id, template } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            let backlog_file = backlog(backlog_file);
+            let task_id = task_id.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "work on", |t| !matches!(t.state, TaskState::Done))
+            });
+            cmd_branch::execute(&backlog_file, &task_id, template, cli.format);
+        }
+
+        Commands::Scan { backlog_file, rev, apply } => {
+            cmd_scan::execute(&backlog(backlog_file), &rev, apply, cli.format);
+        }
+
+        Commands::Ci { backlog_file, annotations, strict, deny_warnings, report } => {
+            let annotations = match annotations.as_deref() {
+                Some("github") => cmd_ci::Annotations::GitHub,
+                Some("gitlab") => cmd_ci::Annotations::GitLab,
+                Some(_) => cmd_ci::Annotations::Text,
+                None => cmd_ci::Annotations::detect(),
+            };
+            let backlog_file = backlog(backlog_file);
+            cmd_ci::execute(&backlog_file, annotations, strict, deny_warnings, &report, cli.format);
+        }
+
+        Commands::Mcp { backlog_file } => {
+            cmd_mcp::serve(&backlog(backlog_file)).await;
+        }
+
+        Commands::Agent { backlog_file, command, epic, tag, max_tasks, dry_run, run_checks } => {
+            let options = cmd_agent::AgentOptions {
+                command,
+                filter: taskai_core::ReadyFilter { epic, tag, assignee: None },
+                max_tasks,
+                dry_run,
+                run_checks,
+            };
+            cmd_agent::execute(&backlog(backlog_file), options, cli.format).await;
+        }
+
+        Commands::Tools { schema } => cmd_tools::execute(schema, cli.format),
+
+        Commands::Lsp => cmd_lsp::serve().await,
+
+        Commands::Daemon { backlog_file, socket } => {
+            let backlog_file = backlog(backlog_file);
+            let socket =
+                socket.unwrap_or_else(|| store::project_root(&backlog_file).join(".taskai.sock"));
+            cmd_daemon::serve(&backlog_file, &socket).await;
+        }
+
+        Commands::Serve { backlog_file, port, host, token, graphql, grpc_port } => {
+            let backlog_file = backlog(backlog_file);
+            cmd_serve::execute(&backlog_file, &host, port, token, graphql, grpc_port).await;
+        }
+
+        Commands::Convert { backlog_file, to } => {
+            cmd_convert::execute(&backlog(backlog_file), &to, cli.format);
+        }
+
+        Commands::Start { backlog_file, task_id } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            let backlog_file = backlog(backlog_file);
+            let task_id = task_id.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "work on", |t| !matches!(t.state, TaskState::Done))
+            });
+            cmd_timer::start(&backlog_file, &task_id, cli.format);
+        }
+
+        Commands::Stop { backlog_file, task_id } => {
+            let (backlog_file, [task_id]) = shift_positionals(backlog_file, [task_id]);
+            cmd_timer::stop(&backlog(backlog_file), task_id.as_deref(), cli.format);
+        }
+
+        Commands::Timesheet { backlog_file, since, until, csv } => {
+            cmd_timesheet::execute(&backlog(backlog_file), since, until, csv, cli.format);
+        }
+
+        Commands::Sort { backlog_file, by } => {
+            let order = match by.as_str() {
+                "priority" => taskai_core::SortOrder::Priority,
+                _ => taskai_core::SortOrder::Dependencies,
+            };
+            cmd_sort::execute(&backlog(backlog_file), order, cli.format);
+        }
+
+        Commands::Spec { command } => match command {
+            SpecCommands::New { file, template, name, language, force } => {
+                cmd_spec::new(&file, &template, name.as_deref(), language.as_deref(), force);
+            }
+            SpecCommands::Templates => cmd_spec::templates(cli.format),
+        },
+
+        Commands::Stats { backlog_file, by, csv } => {
+            let by = match by.as_str() {
+                "tag" => taskai_core::StatsBy::Tag,
+                "epic" => taskai_core::StatsBy::Epic,
+                "kind" => taskai_core::StatsBy::Kind,
+                _ => taskai_core::StatsBy::Assignee,
+            };
+            cmd_stats::execute(&backlog(backlog_file), by, csv, cli.format);
+        }
+
+        Commands::Block { backlog_file, task, reason, link } => {
+            let backlog_file = backlog(backlog_file);
+            let task = task.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "block", |t| {
+                    matches!(t.state, TaskState::Todo | TaskState::InProgress)
+                })
+            });
+            cmd_block::block(&backlog_file, &task, &reason, link.as_deref(), cli.format).await;
+        }
+
+        Commands::Unblock { backlog_file, task, resume } => {
+            let backlog_file = backlog(backlog_file);
+            let task = task.unwrap_or_else(|| {
+                picker::task_id(&backlog_file, "unblock", |t| matches!(t.state, TaskState::Blocked))
+            });
+            cmd_block::unblock(&backlog_file, &task, resume, cli.format);
+        }
+
+        Commands::Depend { command } => match command {
+            DependCommands::Add { backlog_file, task_id, on } => {
+                cmd_depend::add(&backlog(backlog_file), &task_id, &on, cli.format);
+            }
+            DependCommands::Rm { backlog_file, task_id, on } => {
+                cmd_depend::remove(&backlog(backlog_file), &task_id, &on, cli.format);
+            }
+        },
+
+        Commands::Git { command } => match command {
+            GitCommands::InstallHooks { backlog_file, force } => {
+                cmd_git::install_hooks(&backlog(backlog_file), force, cli.format);
+            }
+            GitCommands::PostCommit { backlog_file } => {
+                cmd_git::post_commit(&backlog(backlog_file), cli.format);
+            }
+        },
+
+        Commands::Sync {
+            backlog_file,
+            provider,
+            target,
+            prefer,
+            create,
+            update,
+            dry_run,
+            command: None,
+        } => {
+            let provider = provider.unwrap_or_default();
+            let options = SyncOptions {
+                prefer: sync_side(prefer, "tracker"),
+                create: create || provider == "linear",
+                update,
+            };
+            let backlog_file = backlog(backlog_file);
+            cmd_sync::execute(&backlog_file, &provider, target, None, options, dry_run, cli.format)
+                .await;
+        }
+        Commands::Sync { command: Some(command), .. } => match command {
+            SyncCommands::Github { backlog_file, repo, prefer, dry_run } => {
+                let prefer = sync_side(prefer, "github");
+                let options = SyncOptions { prefer, ..Default::default() };
+                let backlog_file = backlog(backlog_file);
+                let (target, format) = (Some(repo), cli.format);
+                cmd_sync::execute(&backlog_file, "github", target, None, options, dry_run, format)
+                    .await;
+            }
+            SyncCommands::GithubProject { backlog_file, project, dry_run } => {
+                let backlog_file = backlog(backlog_file);
+                cmd_github::sync_project(&backlog_file, project, dry_run, cli.format).await;
+            }
+            SyncCommands::Gitlab { backlog_file, project, prefer, dry_run } => {
+                let prefer = sync_side(prefer, "gitlab");
+                let options = SyncOptions { prefer, ..Default::default() };
+                let backlog_file = backlog(backlog_file);
+                let format = cli.format;
+                cmd_sync::execute(&backlog_file, "gitlab", project, None, options, dry_run, format)
+                    .await;
+            }
+            SyncCommands::Linear { backlog_file, team, project, dry_run } => {
+                let options = SyncOptions { create: true, ..Default::default() };
+                let backlog_file = backlog(backlog_file);
+                let format = cli.format;
+                cmd_sync::execute(&backlog_file, "linear", team, project, options, dry_run, format)
+                    .await;
+            }
+        },
+
+        Commands::Clean { backlog_file, archive_done, dry_run } => {
+            cmd_clean::execute(&backlog(backlog_file), archive_done, dry_run, cli.format);
+        }
+    }
+}
+
+/// Splits the paths given to `taskai import pivotal` and `taskai import shortcut` into the backlog
+/// file, if any, and the export to import, which is the last one.
+fn export_file(backlog_file: Option<PathBuf>, file: Option<PathBuf>) -> (Option<PathBuf>, PathBuf) {
+    match (backlog_file, file) {
+        (backlog_file, Some(file)) => (backlog_file, file),
+        (Some(file), None) => (None, file),
+        (None, None) => error::fail(ErrorKind::Usage, "Give the export file to import."),
+    }
+}
+
+/// Parses the `--prefer` side of a sync, where `tracker` names the tracker side.
+fn sync_side(prefer: Option<String>, tracker: &str) -> Option<SyncSide> {
+    prefer.map(|side| if side == tracker { SyncSide::Tracker } else { SyncSide::Backlog })
+}
+
+/// Splits an optional leading backlog path from the optional positional arguments following it.
+///
+/// Position alone cannot tell `taskai verify T-1` from `taskai verify tasks.yml`, so a leading
+/// argument that does not look like a backlog file is shifted into the following positionals, the
+/// backlog then being resolved from `--backlog` or discovered.
+fn shift_positionals<const N: usize>(
+    backlog_file: Option<PathBuf>,
+    mut args: [Option<String>; N],
+) -> (Option<PathBuf>, [Option<String>; N]) {
+    match backlog_file {
+        Some(path) if !store::is_backlog_path(&path) && args[N - 1].is_none() => {
+            args.rotate_right(1);
+            args[0] = Some(path.to_string_lossy().into_owned());
+            (None, args)
+        }
+        backlog_file => (backlog_file, args),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,61 @@
+use crate::store;
+use std::path::Path;
+use taskai_core::{DiscordClient, NotifyEvent, SlackClient, SlackMessage, Standup};
+use taskai_schema::Backlog;
+
+/// Posts messages about tasks that were marked as done or blocked to the chat integrations
+/// configured for the event.
+///
+/// Failures are only reported as warnings: the backlog is saved by then, and a chat outage must not
+/// fail the command.
+pub async fn tasks(
+    backlog_file: &Path,
+    backlog: &Backlog,
+    event: NotifyEvent,
+    task_ids: &[String],
+) {
+    if task_ids.is_empty() {
+        return;
+    }
+    let config = store::config(backlog_file);
+    if config.slack.posts(event) {
+        let messages = taskai_core::slack_task_messages(&config.slack, backlog, event, task_ids);
+        post_slack(&SlackClient::new(&config.slack), &messages).await;
+    }
+    if let Some(content) = taskai_core::discord_task_message(backlog, event, task_ids) {
+        post_discord(&DiscordClient::new(&config.discord), event, &content).await;
+    }
+}
+
+/// Posts the standup to the chat integrations configured for standups.
+pub async fn standup(backlog_file: &Path, standup: &Standup) {
+    let config = store::config(backlog_file);
+    if config.slack.posts(NotifyEvent::Standup) {
+        let message = taskai_core::slack_standup_message(standup);
+        post_slack(&SlackClient::new(&config.slack), &[message]).await;
+    }
+    let content = taskai_core::discord_standup_message(standup);
+    post_discord(
+        &DiscordClient::new(&config.discord),
+        NotifyEvent::Standup,
+        &content,
+    )
+    .await;
+}
+
+async fn post_slack(client: &SlackClient, messages: &[SlackMessage]) {
+    if !client.is_configured() {
+        return;
+    }
+    for message in messages {
+        if let Err(err) = client.post(message).await {
+            eprintln!("Warning: Slack notification failed: {}", err);
+        }
+    }
+}
+
+async fn post_discord(client: &DiscordClient, event: NotifyEvent, content: &str) {
+    if let Err(err) = client.post(event, content).await {
+        eprintln!("Warning: Discord notification failed: {}", err);
+    }
+}
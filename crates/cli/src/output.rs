@@ -0,0 +1,337 @@
+use crate::error::{self, ErrorKind};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+use taskai_core::{OutputConfig, Theme};
+use taskai_schema::TaskState;
+
+/// Output format shared by every command, selected with the global `--format` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    /// Human-readable text, with aligned tables where it applies.
+    #[default]
+    #[value(alias = "text")]
+    Table,
+    /// YAML document.
+    Yaml,
+    /// JSON document.
+    Json,
+    /// Markdown, ready to paste into issues, pull requests or wikis.
+    Markdown,
+}
+
+/// Data printed by a command, renderable in every output format.
+///
+/// JSON and YAML output serialize the value directly; text and markdown output go through the
+/// `print_text` and `print_markdown` methods.
+pub trait Render: Serialize {
+    /// Prints the value as human-readable text.
+    fn print_text(&self);
+
+    /// Prints the value as markdown. Defaults to the text output wrapped in a code block.
+    fn print_markdown(&self) {
+        println!("```text");
+        self.print_text();
+        println!("```");
+    }
+}
+
+/// Prints the value in the given format.
+pub fn print<T: Render + ?Sized>(format: Format, value: &T) {
+    match format {
+        Format::Table => value.print_text(),
+        Format::Markdown => value.print_markdown(),
+        Format::Json => match serde_json::to_string_pretty(value) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                error::fail(
+                    ErrorKind::Other,
+                    format!("Error serializing output to JSON: {}", err),
+                );
+            }
+        },
+        Format::Yaml => match serde_yaml::to_string(value) {
+            Ok(yaml) => print!("{}", yaml),
+            Err(err) => {
+                error::fail(
+                    ErrorKind::Other,
+                    format!("Error serializing output to YAML: {}", err),
+                );
+            }
+        },
+    }
+}
+
+/// Prints rows as a table: aligned columns for text, a pipe table for markdown.
+///
+/// `indent` is the number of spaces before each line of a text table.
+pub fn print_table(markdown: bool, indent: usize, headers: &[&str], rows: &[Vec<String>]) {
+    for line in table_lines(markdown, indent, headers, rows) {
+        println!("{}", line);
+    }
+}
+
+/// Prints rows as CSV, with a header line. Cells are quoted when needed.
+pub fn print_csv(headers: &[&str], rows: &[Vec<String>]) {
+    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+    let result = writer
+        .write_record(headers)
+        .and_then(|_| rows.iter().try_for_each(|row| writer.write_record(row)));
+    finish_csv(writer, result);
+}
+
+/// Prints serializable records as CSV, their field names giving the header line.
+pub fn print_csv_records<T: Serialize>(records: &[T]) {
+    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+    let result = records
+        .iter()
+        .try_for_each(|record| writer.serialize(record));
+    finish_csv(writer, result);
+}
+
+/// Flushes CSV output, exiting the process on failure.
+fn finish_csv<W: io::Write>(mut writer: csv::Writer<W>, result: csv::Result<()>) {
+    if let Err(err) = result.and_then(|_| writer.flush().map_err(csv::Error::from)) {
+        error::fail(
+            ErrorKind::Other,
+            format!("Error writing CSV output: {}", err),
+        );
+    }
+}
+
+/// Lays out the lines of a table printed by `print_table`.
+fn table_lines(
+    markdown: bool,
+    indent: usize,
+    headers: &[&str],
+    rows: &[Vec<String>],
+) -> Vec<String> {
+    if markdown {
+        let mut lines = vec![
+            format!("| {} |", headers.join(" | ")),
+            format!("|{}", "---|".repeat(headers.len())),
+        ];
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+            lines.push(format!("| {} |", cells.join(" | ")));
+        }
+        return lines;
+    }
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| visible_width(cell))
+                .chain(std::iter::once(header.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |cells: Vec<&str>| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - visible_width(cell))))
+            .collect();
+        format!("{}{}", " ".repeat(indent), padded.join("  ").trim_end())
+    };
+
+    std::iter::once(format_row(headers.to_vec()))
+        .chain(
+            rows.iter()
+                .map(|row| format_row(row.iter().map(String::as_str).collect())),
+        )
+        .collect()
+}
+
+/// Colors used to highlight human-readable output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+}
+
+/// Theme of the output, or `None` when it is not colored. Set once by `init_colors`.
+static THEME: OnceLock<Option<Theme>> = OnceLock::new();
+
+/// Decides whether output is colored and with which theme.
+///
+/// Colors are off with `no_color` (the `--no-color` flag), a non-empty `NO_COLOR` variable,
+/// `color = false` in the configuration, or when stdout is not a terminal.
+pub fn init_colors(no_color: bool, config: &OutputConfig) {
+    let _ = THEME.set(detect_theme(no_color, config));
+}
+
+/// Returns the theme to color output with, or `None` if output must not be colored.
+fn detect_theme(no_color: bool, config: &OutputConfig) -> Option<Theme> {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = !no_color
+        && !no_color_env
+        && config.color != Some(false)
+        && std::io::stdout().is_terminal();
+    enabled.then(|| config.theme.unwrap_or_default())
+}
+
+/// Wraps text in the ANSI escape codes of the color, unless output is not colored.
+pub fn paint(text: &str, color: Color) -> String {
+    let theme = *THEME.get_or_init(|| detect_theme(false, &OutputConfig::default()));
+    paint_with(theme, text, color)
+}
+
+/// Wraps text in the ANSI escape codes of the color in the given theme.
+fn paint_with(theme: Option<Theme>, text: &str, color: Color) -> String {
+    let code = match (theme, color) {
+        (None, _) => return text.to_string(),
+        (Some(Theme::Default), Color::Red) => "31",
+        (Some(Theme::Default), Color::Green) => "32",
+        (Some(Theme::Default), Color::Yellow) => "33",
+        (Some(Theme::Bright), Color::Red) => "91",
+        (Some(Theme::Bright), Color::Green) => "92",
+        (Some(Theme::Bright), Color::Yellow) => "93",
+        (Some(Theme::Mono), Color::Red) => "1",
+        (Some(Theme::Mono), Color::Green) => "2",
+        (Some(Theme::Mono), Color::Yellow) => "4",
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+/// Colors text after a task state: in progress, blocked and done tasks stand out from todo ones.
+pub fn paint_state(state: &TaskState, text: &str) -> String {
+    match state {
+        TaskState::Todo => text.to_string(),
+        TaskState::InProgress => paint(text, Color::Yellow),
+        TaskState::Blocked => paint(text, Color::Red),
+        TaskState::Done => paint(text, Color::Green),
+    }
+}
+
+/// Returns the label of a priority (`P1`), highlighting the two most important levels.
+pub fn priority_label(priority: u32) -> String {
+    let label = format!("P{}", priority);
+    match priority {
+        1 => paint(&label, Color::Red),
+        2 => paint(&label, Color::Yellow),
+        _ => label,
+    }
+}
+
+/// Returns the number of characters of the text shown on screen, ignoring ANSI color codes.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to its final `m`.
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Returns the glyph shown for a task state in lists and trees.
+pub fn state_glyph(state: &TaskState) -> &'static str {
+    match state {
+        TaskState::Todo => "[ ]",
+        TaskState::InProgress => "[~]",
+        TaskState::Blocked => "[!]",
+        TaskState::Done => "[x]",
+    }
+}
+
+/// Formats a duration in hours as hours and minutes (`1h05m`).
+pub fn hours(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as u64;
+    format!("{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+/// Outcome of a command that changed a single task, such as `mark-done` or `reopen`.
+#[derive(Serialize)]
+pub struct TaskUpdate<'a> {
+    /// ID of the task.
+    pub id: &'a str,
+    /// State of the task after the change.
+    pub state: &'a TaskState,
+    /// Message printed in text and markdown output.
+    #[serde(skip)]
+    pub message: String,
+}
+
+impl Render for TaskUpdate<'_> {
+    fn print_text(&self) {
+        println!("{}", self.message);
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+impl Render for Vec<TaskUpdate<'_>> {
+    fn print_text(&self) {
+        for update in self {
+            update.print_text();
+        }
+    }
+
+    fn print_markdown(&self) {
+        self.print_text();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_layout() {
+        assert_eq!(Format::from_str("text", true), Ok(Format::Table));
+
+        let rows = vec![
+            vec!["T-1".to_string(), "Short".to_string()],
+            vec!["T-10".to_string(), "A | pipe".to_string()],
+        ];
+
+        assert_eq!(
+            table_lines(false, 2, &["ID", "TITLE"], &rows),
+            vec!["  ID    TITLE", "  T-1   Short", "  T-10  A | pipe"]
+        );
+        assert_eq!(
+            table_lines(true, 2, &["ID", "TITLE"], &rows),
+            vec![
+                "| ID | TITLE |",
+                "|---|---|",
+                "| T-1 | Short |",
+                "| T-10 | A \\| pipe |"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint_with(None, "+ T-1", Color::Green), "+ T-1");
+        assert_eq!(
+            paint_with(Some(Theme::Default), "- T-1", Color::Red),
+            "\x1b[31m- T-1\x1b[0m"
+        );
+        assert_eq!(
+            paint_with(Some(Theme::Mono), "- T-1", Color::Red),
+            "\x1b[1m- T-1\x1b[0m"
+        );
+
+        let colored = paint_with(Some(Theme::Bright), "Done", Color::Green);
+        assert_eq!(visible_width(&colored), 4);
+        let rows = vec![vec![colored.clone(), "x".to_string()]];
+        assert_eq!(
+            table_lines(false, 0, &["STATE", "ID"], &rows),
+            vec!["STATE  ID".to_string(), format!("{}   x", colored)]
+        );
+    }
+}
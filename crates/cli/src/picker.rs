@@ -0,0 +1,256 @@
+use crate::error::{self, ErrorKind};
+use crate::output;
+use crate::store;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::cmp::Reverse;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use taskai_schema::Task;
+
+/// Keys shown at the bottom of the picker.
+const HELP: &str = "type to filter  ↑↓: move  enter: select  esc: cancel";
+
+/// Returns true if a task can be picked interactively: stdin and stdout are both terminals.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Returns the ID of the task a command applies to when none was given, letting the user pick it
+/// among the tasks of the backlog accepted by `include`.
+///
+/// `action` completes "Give the ID of the task to ..." and "No task to ...". Exits the process with
+/// a usage error when not running in a terminal, and with an error when the picker is cancelled.
+pub fn task_id(backlog_file: &Path, action: &str, include: impl Fn(&Task) -> bool) -> String {
+    if !is_interactive() {
+        error::fail(
+            ErrorKind::Usage,
+            format!("Give the ID of the task to {}.", action),
+        );
+    }
+
+    let backlog = store::load(backlog_file);
+    let tasks: Vec<&Task> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| include(t))
+        .collect();
+    if tasks.is_empty() {
+        error::fail(ErrorKind::NotFound, format!("No task to {}.", action));
+    }
+
+    let picker = Picker::new(&tasks, &format!("Task to {}", action));
+    let mut terminal = ratatui::init();
+    let result = picker.run(&mut terminal);
+    ratatui::restore();
+
+    match result {
+        Ok(Some(id)) => id,
+        Ok(None) => error::fail(ErrorKind::Other, "No task selected."),
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error running the task picker: {}", err),
+        ),
+    }
+}
+
+/// State of the fuzzy finder.
+struct Picker {
+    title: String,
+    /// ID and label of each task, in backlog order.
+    items: Vec<(String, String)>,
+    query: String,
+    selected: usize,
+}
+
+impl Picker {
+    fn new(tasks: &[&Task], title: &str) -> Self {
+        let id_width = tasks.iter().map(|t| t.id.len()).max().unwrap_or(0);
+        let items = tasks
+            .iter()
+            .map(|t| {
+                let assignee = t
+                    .assignee
+                    .as_ref()
+                    .map(|a| format!(" @{}", a))
+                    .unwrap_or_default();
+                let label = format!(
+                    "{} {:<id_width$}  {}{}",
+                    output::state_glyph(&t.state),
+                    t.id,
+                    t.title,
+                    assignee
+                );
+                (t.id.clone(), label)
+            })
+            .collect();
+        Picker {
+            title: title.to_string(),
+            items,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Draws the picker and handles keys until a task is selected (`Some`) or the picker is
+    /// cancelled (`None`).
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<Option<String>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if let Some(outcome) = self.handle_key(key) {
+                    return Ok(outcome);
+                }
+            }
+        }
+    }
+
+    /// Indices of the items matching the query, best matches first.
+    fn matches(&self) -> Vec<usize> {
+        let mut scored: Vec<(usize, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, label))| fuzzy_score(label, &self.query).map(|score| (i, score)))
+            .collect();
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Handles a key press. Returns the outcome of the picker once the user is done.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Option<String>> {
+        match key.code {
+            KeyCode::Esc => return Some(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(None)
+            }
+            KeyCode::Enter => {
+                let matches = self.matches();
+                return matches
+                    .get(self.selected)
+                    .map(|&i| Some(self.items[i].0.clone()));
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                let len = self.matches().len();
+                self.selected = (self.selected + 1).min(len.saturating_sub(1));
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [input, list, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.query)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str()),
+            ),
+            input,
+        );
+
+        let matches = self.matches();
+        let items: Vec<ListItem> = matches
+            .iter()
+            .map(|&i| ListItem::new(self.items[i].1.as_str()))
+            .collect();
+        let list_widget = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "{}/{}",
+                matches.len(),
+                self.items.len()
+            )))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list_widget, list, &mut state);
+
+        frame.render_widget(Paragraph::new(HELP), help);
+    }
+}
+
+/// Scores how well the query matches the text, case-insensitively: every character of the query
+/// must appear in order. Consecutive characters and characters starting a word score higher.
+/// Returns `None` when the text does not match.
+fn fuzzy_score(text: &str, query: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 2;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests fuzzy matching and selecting a task by typing part of its title.
+    #[test]
+    fn test_pick_by_title() {
+        assert!(fuzzy_score("T-17 Write the docs", "wdocs").is_some());
+        assert!(fuzzy_score("T-17 Write the docs", "docsw").is_none());
+        assert!(fuzzy_score("T-1 Deploy", "dep") > fuzzy_score("T-2 Add endpoints", "dep"));
+
+        let tasks = [
+            Task {
+                id: "T-17".to_string(),
+                title: "Add endpoints".to_string(),
+                ..Default::default()
+            },
+            Task {
+                id: "T-18".to_string(),
+                title: "Deploy the API".to_string(),
+                ..Default::default()
+            },
+        ];
+        let refs: Vec<&Task> = tasks.iter().collect();
+        let mut picker = Picker::new(&refs, "Task to assign");
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert_eq!(picker.matches(), vec![0, 1]);
+        for c in "dep".chars() {
+            assert_eq!(picker.handle_key(key(KeyCode::Char(c))), None);
+        }
+        assert_eq!(picker.matches()[0], 1);
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            Some(Some("T-18".to_string()))
+        );
+        assert_eq!(picker.handle_key(key(KeyCode::Esc)), Some(None));
+    }
+}
@@ -0,0 +1,473 @@
+use crate::backup;
+use crate::error::{self, ErrorKind};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use taskai_core::Config;
+use taskai_schema::Backlog;
+
+/// Backlog files looked for, in order, when no backlog path is given; the last one is a backlog
+/// directory.
+pub const BACKLOG_FILES: [&str; 3] = ["taskai.yaml", ".taskai/backlog.yaml", ".taskai/backlog"];
+
+/// Returns the backlog file to use: the given path, or else the first of `BACKLOG_FILES` found in
+/// the current directory or its ancestors.
+///
+/// Exits the process with an error message if no backlog file is given or found.
+pub fn resolve(backlog_file: Option<PathBuf>) -> PathBuf {
+    if let Some(backlog_file) = backlog_file {
+        return backlog_file;
+    }
+    match discover(&search_root()) {
+        Some(backlog_file) => {
+            tracing::info!(path = %backlog_file.display(), "using discovered backlog");
+            backlog_file
+        }
+        None => {
+            error::fail(
+                ErrorKind::Usage,
+                format!(
+                    "No backlog file given and no {} found; pass a path, use --backlog or set TASKAI_BACKLOG.",
+                    BACKLOG_FILES.join(" or ")
+                ),
+            );
+        }
+    }
+}
+
+/// Returns the directory from which the backlog and `.taskai.toml` are looked for: `TASKAI_ROOT`
+/// when set, as `cargo taskai` does with the workspace root, the current directory otherwise.
+pub fn search_root() -> PathBuf {
+    std::env::var_os("TASKAI_ROOT")
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Looks for one of `BACKLOG_FILES` in the given directory and its ancestors.
+pub fn discover(start: &Path) -> Option<PathBuf> {
+    // Relative paths such as `.` have no ancestors to walk up to.
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    start.ancestors().find_map(|dir| {
+        BACKLOG_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file() || is_backlog_dir(path))
+    })
+}
+
+/// Returns true if the path is a backlog directory: a directory holding an index file and one
+/// Markdown file per task, rather than a single YAML file.
+pub fn is_backlog_dir(path: &Path) -> bool {
+    path.join(taskai_core::INDEX_FILE).is_file()
+}
+
+/// Returns true if the path looks like a backlog file: an existing file or backlog directory, or a
+/// YAML file name.
+///
+/// Used to tell an optional leading backlog path from the positional arguments that follow it.
+pub fn is_backlog_path(path: &Path) -> bool {
+    path.is_file()
+        || is_backlog_dir(path)
+        || path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+}
+
+/// Reads and parses the backlog file at the given path.
+///
+/// Exits the process with an error message if the file cannot be read or parsed.
+pub fn load(backlog_file: &Path) -> Backlog {
+    parse(&read(backlog_file))
+}
+
+/// Reads and parses the backlog file at the given path, returning an error message on failure
+/// instead of exiting.
+pub fn try_load(backlog_file: &Path) -> Result<Backlog, String> {
+    let content = try_read(backlog_file)?;
+    serde_yaml::from_str(&content).map_err(|err| format!("Error parsing backlog file: {}", err))
+}
+
+/// Returns the directory containing the backlog file, against which task paths are resolved.
+pub fn project_root(backlog_file: &Path) -> PathBuf {
+    match backlog_file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Returns the project configuration found in the backlog's directory or its ancestors, or the
+/// default configuration when there is none.
+///
+/// Exits the process with an error message if the configuration file cannot be read or parsed.
+pub fn config(backlog_file: &Path) -> Config {
+    match Config::discover(&project_root(backlog_file)) {
+        Ok(config) => config.map(|(_, config)| config).unwrap_or_default(),
+        Err(err) => {
+            error::fail(ErrorKind::Parse, err);
+        }
+    }
+}
+
+/// Reads the raw content of the backlog file, exiting the process on failure.
+pub fn read(backlog_file: &Path) -> String {
+    match try_read(backlog_file) {
+        Ok(content) => content,
+        Err(err) => error::fail(ErrorKind::Other, err),
+    }
+}
+
+/// Reads the raw content of the backlog file, returning an error message on failure instead of
+/// exiting. The content of a backlog directory is the YAML of the whole backlog, joined from its
+/// files.
+pub fn try_read(backlog_file: &Path) -> Result<String, String> {
+    if is_backlog_dir(backlog_file) {
+        return read_dir(backlog_file);
+    }
+    tracing::debug!(path = %backlog_file.display(), "reading backlog");
+    fs::read_to_string(backlog_file).map_err(|err| format!("Error reading backlog file: {}", err))
+}
+
+/// Reads a backlog directory: its index and its task files, joined into the YAML of the backlog.
+fn read_dir(dir: &Path) -> Result<String, String> {
+    tracing::debug!(path = %dir.display(), "reading backlog directory");
+    let index = fs::read_to_string(dir.join(taskai_core::INDEX_FILE))
+        .map_err(|err| format!("Error reading backlog index: {}", err))?;
+    let mut task_files = Vec::new();
+    let tasks_dir = dir.join(taskai_core::TASKS_DIR);
+    if tasks_dir.is_dir() {
+        let entries = fs::read_dir(&tasks_dir)
+            .map_err(|err| format!("Error reading {}: {}", tasks_dir.display(), err))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|err| format!("Error reading {}: {}", tasks_dir.display(), err))?
+                .path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let content = fs::read_to_string(&path)
+                .map_err(|err| format!("Error reading {}: {}", path.display(), err))?;
+            task_files.push((name, content));
+        }
+    }
+    task_files.sort();
+    taskai_core::join_backlog(&index, &task_files)
+}
+
+/// Writes the backlog back to the given path, keeping the comments and formatting of the file.
+///
+/// Exits the process with an error message if serialization or writing fails.
+pub fn save(backlog_file: &Path, backlog: &Backlog) {
+    if let Err(err) = try_save(backlog_file, backlog) {
+        error::fail(ErrorKind::Other, err);
+    }
+}
+
+/// Writes the backlog back to the given path, returning an error message on failure instead of
+/// exiting.
+///
+/// Only the fields, tasks and epics that changed are rewritten, so comments, key order and quoting
+/// are kept everywhere else. When the changes cannot be made in place (tasks reordered, top-level
+/// fields changed...), or the file does not exist yet, the whole backlog is written in canonical
+/// form instead.
+pub fn try_save(backlog_file: &Path, backlog: &Backlog) -> Result<(), String> {
+    if is_backlog_dir(backlog_file) {
+        return save_dir(backlog_file, backlog);
+    }
+    let previous = fs::read_to_string(backlog_file).ok();
+    let yaml = match previous
+        .as_deref()
+        .and_then(|previous| taskai_core::update_yaml(previous, backlog))
+    {
+        Some(yaml) => yaml,
+        None => taskai_core::format_backlog(backlog)
+            .map_err(|err| format!("Error serializing backlog to YAML: {}", err))?,
+    };
+    write(backlog_file, previous.as_deref(), &yaml)
+}
+
+/// Writes the backlog in canonical form to the given path, exiting the process on failure.
+pub fn save_formatted(backlog_file: &Path, backlog: &Backlog) {
+    // Backlog directories are always written in canonical form
+    if is_backlog_dir(backlog_file) {
+        return save(backlog_file, backlog);
+    }
+    let result = taskai_core::format_backlog(backlog)
+        .map_err(|err| format!("Error serializing backlog to YAML: {}", err))
+        .and_then(|yaml| {
+            let previous = fs::read_to_string(backlog_file).ok();
+            write(backlog_file, previous.as_deref(), &yaml)
+        });
+    if let Err(err) = result {
+        error::fail(ErrorKind::Other, err);
+    }
+}
+
+/// Writes the backlog back like `try_save`, unless the file no longer holds `loaded`, the content
+/// the backlog was read from. Interactive commands save this way rather than keeping the backlog
+/// locked while waiting for the user, so that they never overwrite changes made in the meantime.
+///
+/// Returns the new content of the file.
+pub fn try_save_unchanged(
+    backlog_file: &Path,
+    loaded: &str,
+    backlog: &Backlog,
+) -> Result<String, String> {
+    let _lock = lock(backlog_file);
+    let read = || try_read(backlog_file);
+    if read()? != loaded {
+        return Err(format!(
+            "{} was changed by another command since it was loaded; changes not saved.",
+            backlog_file.display()
+        ));
+    }
+    try_save(backlog_file, backlog)?;
+    read()
+}
+
+/// Parses backlog YAML read from a file, exiting the process with an error message on failure.
+pub fn parse(content: &str) -> Backlog {
+    match serde_yaml::from_str(content) {
+        Ok(backlog) => backlog,
+        Err(err) => error::fail(
+            ErrorKind::Parse,
+            format!("Error parsing backlog file: {}", err),
+        ),
+    }
+}
+
+/// Writes YAML to the backlog file, backing up the previous content, if any and different, so that
+/// `taskai undo` can restore it.
+fn write(backlog_file: &Path, previous: Option<&str>, yaml: &str) -> Result<(), String> {
+    if let Some(previous) = previous {
+        if previous != yaml {
+            backup::snapshot(backlog_file, previous)?;
+        }
+    }
+    write_atomic(backlog_file, yaml)
+        .map_err(|err| format!("Error writing to backlog file: {}", err))?;
+    tracing::info!(path = %backlog_file.display(), bytes = yaml.len(), "wrote backlog");
+    Ok(())
+}
+
+/// Writes the backlog to a backlog directory, backing up the previous backlog, as YAML, if any
+/// file changes, so that `taskai undo` can restore it.
+fn save_dir(dir: &Path, backlog: &Backlog) -> Result<(), String> {
+    if is_dir_written(dir, backlog)? {
+        return Ok(());
+    }
+    if let Ok(previous) = read_dir(dir) {
+        backup::snapshot(dir, &previous)?;
+    }
+    write_dir(dir, backlog)
+}
+
+/// Tells whether the backlog directory holds exactly the files `write_dir` would write for the
+/// backlog, in canonical form.
+pub fn is_dir_written(dir: &Path, backlog: &Backlog) -> Result<bool, String> {
+    let (index, task_files) = taskai_core::split_backlog(backlog)?;
+    let tasks_dir = dir.join(taskai_core::TASKS_DIR);
+    let same =
+        |path: &Path, content: &str| fs::read_to_string(path).ok().as_deref() == Some(content);
+    let files = fs::read_dir(&tasks_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "md"))
+                .count()
+        })
+        .unwrap_or(0);
+    Ok(same(&dir.join(taskai_core::INDEX_FILE), &index)
+        && files == task_files.len()
+        && task_files
+            .iter()
+            .all(|(name, content)| same(&tasks_dir.join(name), content)))
+}
+
+/// Writes the backlog as a backlog directory, creating it if needed: the index, and a Markdown
+/// file per task. Only the files whose content changed are rewritten, and the files of tasks no
+/// longer in the backlog are removed, so that a change shows as such in `git diff`.
+pub fn write_dir(dir: &Path, backlog: &Backlog) -> Result<(), String> {
+    let (index, task_files) = taskai_core::split_backlog(backlog)?;
+    let tasks_dir = dir.join(taskai_core::TASKS_DIR);
+    fs::create_dir_all(&tasks_dir)
+        .map_err(|err| format!("Error creating {}: {}", tasks_dir.display(), err))?;
+
+    let write_changed = |path: &Path, content: &str| {
+        if fs::read_to_string(path).ok().as_deref() == Some(content) {
+            return Ok(());
+        }
+        write_atomic(path, content)
+            .map_err(|err| format!("Error writing to {}: {}", path.display(), err))
+    };
+    for (name, content) in &task_files {
+        write_changed(&tasks_dir.join(name), content)?;
+    }
+    let entries = fs::read_dir(&tasks_dir)
+        .map_err(|err| format!("Error reading {}: {}", tasks_dir.display(), err))?;
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let stale = path.extension().is_some_and(|ext| ext == "md")
+            && !task_files.iter().any(|(file, _)| file.as_str() == name);
+        if stale {
+            fs::remove_file(&path)
+                .map_err(|err| format!("Error removing {}: {}", path.display(), err))?;
+        }
+    }
+    // The index goes last, so that it never lists a task whose file is not written yet
+    write_changed(&dir.join(taskai_core::INDEX_FILE), &index)?;
+    tracing::info!(path = %dir.display(), tasks = task_files.len(), "wrote backlog directory");
+    Ok(())
+}
+
+/// Replaces the content of a file atomically: the content is written to a temporary file in the
+/// same directory, flushed to disk, then renamed over the file. An interrupted write leaves either
+/// the old or the new content, never a truncated file. Temporary files get a unique name, so that
+/// concurrent saves and files left behind by a crash never get in the way.
+///
+/// Symbolic links are followed, and the permissions of the existing file are kept.
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Removed when dropped, should anything fail before it is renamed over the file
+    let mut temp = tempfile::Builder::new()
+        .prefix(&format!(".{}.", name))
+        .suffix(".tmp")
+        .tempfile_in(&dir)?;
+    if let Ok(metadata) = fs::metadata(&path) {
+        temp.as_file().set_permissions(metadata.permissions())?;
+    }
+    temp.write_all(content.as_bytes())?;
+    temp.as_file().sync_all()?;
+    temp.persist(&path).map_err(|err| err.error)?;
+
+    // Persist the rename itself; directories cannot be opened for this on every platform
+    if let Ok(dir) = File::open(&dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Exclusive advisory lock on a backlog file, released when dropped.
+pub struct BacklogLock {
+    _file: File,
+}
+
+/// Takes an exclusive advisory lock on the backlog file, waiting while another process holds it.
+///
+/// The lock is taken on a `.<name>.lock` file next to the backlog rather than on the backlog itself,
+/// so that it does not depend on how the backlog is written. Exits the process with an error
+/// message if the lock file cannot be created or locked.
+pub fn lock(backlog_file: &Path) -> BacklogLock {
+    let lock_file = lock_path(backlog_file);
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_file)
+        .and_then(|file| file.lock().map(|()| file));
+    match file {
+        Ok(file) => {
+            tracing::debug!(path = %lock_file.display(), "locked backlog");
+            BacklogLock { _file: file }
+        }
+        Err(err) => error::fail(
+            ErrorKind::Other,
+            format!("Error locking {}: {}", lock_file.display(), err),
+        ),
+    }
+}
+
+/// Returns the path of the lock file of the backlog file.
+fn lock_path(backlog_file: &Path) -> PathBuf {
+    let name = backlog_file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    backlog_file.with_file_name(format!(".{}.lock", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the backlog is found in an ancestor directory, `taskai.yaml` first.
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src").join("bin");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(discover(&nested), None);
+
+        fs::create_dir(dir.path().join(".taskai")).unwrap();
+        fs::write(dir.path().join(".taskai/backlog.yaml"), "project: p\n").unwrap();
+        let found = discover(&nested).unwrap();
+        assert!(found.ends_with(".taskai/backlog.yaml"));
+
+        fs::write(dir.path().join("taskai.yaml"), "project: p\n").unwrap();
+        let found = discover(&nested).unwrap();
+        assert!(found.ends_with("taskai.yaml"));
+
+        assert!(is_backlog_path(Path::new("missing.yml")));
+        assert!(!is_backlog_path(Path::new("T-1")));
+    }
+
+    /// Tests that a locked backlog cannot be locked again until the lock is dropped.
+    #[test]
+    fn test_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        let lock_file = dir.path().join(".tasks.yml.lock");
+
+        let guard = lock(&backlog_file);
+        let other = File::options().write(true).open(&lock_file).unwrap();
+        assert!(other.try_lock().is_err());
+        drop(guard);
+        assert!(other.try_lock().is_ok());
+    }
+
+    /// Tests that interactive saves refuse to overwrite changes made since the backlog was loaded.
+    #[test]
+    fn test_try_save_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+        fs::write(&backlog_file, "project: a\n").unwrap();
+        let backlog = Backlog {
+            project: "b".to_string(),
+            ..Default::default()
+        };
+
+        let saved = try_save_unchanged(&backlog_file, "project: a\n", &backlog).unwrap();
+        assert_eq!(saved, "project: b\n");
+        assert!(try_save_unchanged(&backlog_file, "project: a\n", &backlog).is_err());
+    }
+
+    /// Tests that atomic writes replace the content and leave no temporary file behind.
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let backlog_file = dir.path().join("tasks.yml");
+
+        write_atomic(&backlog_file, "project: a\n").unwrap();
+        write_atomic(&backlog_file, "project: b\n").unwrap();
+        assert_eq!(fs::read_to_string(&backlog_file).unwrap(), "project: b\n");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // A temporary file left behind by a crashed process is no obstacle
+        let stale = dir.path().join(format!(".tasks.yml.{}.tmp", std::process::id()));
+        fs::write(&stale, "project: c\n").unwrap();
+        write_atomic(&backlog_file, "project: d\n").unwrap();
+        assert_eq!(fs::read_to_string(&backlog_file).unwrap(), "project: d\n");
+    }
+}
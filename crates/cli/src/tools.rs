@@ -0,0 +1,161 @@
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// List the tasks that are ready to work on: Todo tasks whose dependencies are all done, in backlog
+/// order.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListReadyTasks {
+    /// Only list tasks of this epic.
+    pub epic: Option<String>,
+    /// Only list tasks with this tag.
+    pub tag: Option<String>,
+    /// Only list tasks assigned to this person.
+    pub assignee: Option<String>,
+    /// Maximum number of tasks, 20 by default.
+    pub limit: Option<usize>,
+}
+
+/// Get a task of the backlog with all its fields and its epic.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTask {
+    /// ID of the task.
+    pub id: String,
+}
+
+/// Mark a task as done, optionally recording how it was completed.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MarkDone {
+    /// ID of the task.
+    pub id: String,
+    /// Note added to the task.
+    pub note: Option<String>,
+}
+
+/// Add a Todo task to the backlog, returning its ID.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddTask {
+    /// Title of the task.
+    pub title: String,
+    /// ID of the epic to add the task to.
+    pub epic: Option<String>,
+    /// IDs of the tasks it depends on.
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// Paths of the files it delivers.
+    #[serde(default)]
+    pub deliverables: Vec<String>,
+    /// Description of the task.
+    pub description: Option<String>,
+    /// Explicit ID, minted from the backlog numbering when absent.
+    pub id: Option<String>,
+}
+
+/// Generate a backlog from a project specification with the configured LLM and write it to the
+/// backlog file, or merge it into the existing one.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GenerateBacklog {
+    /// Markdown specification of the project.
+    pub spec: String,
+    /// Merge into the existing backlog, keeping the progress of its tasks.
+    #[serde(default)]
+    pub merge: bool,
+    /// Language of the prompts (en, fr), en by default.
+    pub lang: Option<String>,
+    /// Style of the generated backlog, standard by default.
+    pub style: Option<String>,
+}
+
+/// A backlog operation exposed to LLM agents as a tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct Tool {
+    pub name: &'static str,
+    pub description: String,
+    /// JSON schema of the arguments.
+    pub input_schema: Value,
+}
+
+/// Returns the tools served by `taskai mcp` and exported by `taskai tools`. Their descriptions and
+/// argument schemas are derived from the argument types above.
+pub fn definitions() -> Vec<Tool> {
+    vec![
+        tool::<ListReadyTasks>("list_ready_tasks"),
+        tool::<GetTask>("get_task"),
+        tool::<MarkDone>("mark_done"),
+        tool::<AddTask>("add_task"),
+        tool::<GenerateBacklog>("generate_backlog"),
+    ]
+}
+
+/// Builds the tool taking arguments of type `T`, described by the doc comment of the type.
+fn tool<T: JsonSchema>(name: &'static str) -> Tool {
+    let generator = SchemaSettings::draft07()
+        .with(|settings| {
+            // Optional arguments are left out rather than null
+            settings.option_nullable = false;
+            settings.option_add_null_type = false;
+            settings.inline_subschemas = true;
+        })
+        .into_generator();
+    let schema = generator.into_root_schema_for::<T>();
+    let mut input_schema = serde_json::to_value(schema).unwrap_or_else(|_| json!({}));
+    let mut description = String::new();
+    if let Some(fields) = input_schema.as_object_mut() {
+        fields.remove("$schema");
+        fields.remove("title");
+        if let Some(Value::String(text)) = fields.remove("description") {
+            description = text;
+        }
+    }
+    Tool {
+        name,
+        description,
+        input_schema,
+    }
+}
+
+/// Formats the tools for the Model Context Protocol `tools/list` method.
+pub fn mcp(tools: &[Tool]) -> Value {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "inputSchema": t.input_schema,
+            })
+        })
+        .collect()
+}
+
+/// Formats the tools as OpenAI function definitions, for the `tools` of Chat Completions requests.
+pub fn openai(tools: &[Tool]) -> Value {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.input_schema,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Formats the tools as Anthropic tool definitions, for the `tools` of Messages requests.
+pub fn anthropic(tools: &[Tool]) -> Value {
+    tools
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "input_schema": t.input_schema,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use crate::error::{self, ErrorKind};
+use crate::output::Format;
+use crate::store;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+use taskai_schema::Backlog;
+
+/// How long to wait for a burst of file events to settle before re-rendering.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Renders the backlog, then re-renders it every time the backlog file changes, until interrupted.
+///
+/// For text output on a terminal, the screen is cleared before each render; other output is
+/// appended, so that a stream of JSON or YAML documents stays free of escape codes, and the
+/// watching notice goes to stderr. A file that cannot be parsed (e.g. while being written) shows
+/// the error until the next change.
+pub fn watch(backlog_file: &Path, format: Format, mut render: impl FnMut(&Backlog)) {
+    let (_watcher, events) = start(backlog_file);
+    let redraw = format == Format::Table && io::stdout().is_terminal();
+    loop {
+        if redraw {
+            print!("\x1B[2J\x1B[H");
+        }
+        match store::try_load(backlog_file) {
+            Ok(backlog) => render(&backlog),
+            Err(err) => eprintln!("{}", err),
+        }
+        let notice = format!(
+            "Watching {} for changes (Ctrl-C to stop)...",
+            backlog_file.display()
+        );
+        if redraw {
+            println!();
+            println!("{}", notice);
+        } else {
+            eprintln!("{}", notice);
+        }
+        if !wait(backlog_file, &events) {
+            return;
+        }
+    }
+}
+
+/// Starts watching the backlog file, returning the watcher, to be kept alive, and its events.
+///
+/// The parent directory is watched rather than the file itself, so editors that save by replacing
+/// the file are picked up too; a backlog directory is watched with all its files. Exits the process
+/// with an error message if the watcher cannot start.
+pub fn start(backlog_file: &Path) -> (RecommendedWatcher, Receiver<notify::Result<Event>>) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error::fail(
+                ErrorKind::Other,
+                format!("Error starting file watcher: {}", err),
+            );
+        }
+    };
+
+    let (dir, mode) = if store::is_backlog_dir(backlog_file) {
+        (backlog_file.to_path_buf(), RecursiveMode::Recursive)
+    } else {
+        (
+            store::project_root(backlog_file),
+            RecursiveMode::NonRecursive,
+        )
+    };
+    if let Err(err) = watcher.watch(&dir, mode) {
+        error::fail(
+            ErrorKind::Other,
+            format!("Error watching {}: {}", dir.display(), err),
+        );
+    }
+    (watcher, rx)
+}
+
+/// Waits for a change to the backlog file, then drains the rest of the burst. Access events are
+/// ignored, since loading the backlog produces some. Returns false once the watcher has stopped.
+pub fn wait(backlog_file: &Path, events: &Receiver<notify::Result<Event>>) -> bool {
+    // Everything watched in a backlog directory belongs to the backlog
+    let file_name = if store::is_backlog_dir(backlog_file) {
+        None
+    } else {
+        backlog_file.file_name()
+    };
+    loop {
+        match events.recv() {
+            Ok(Ok(event)) if is_change(&event, file_name) => {
+                tracing::debug!(kind = ?event.kind, "backlog changed");
+                break;
+            }
+            Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+    while events.recv_timeout(DEBOUNCE).is_ok() {}
+    true
+}
+
+/// Tells whether the event creates, modifies or removes the file named `file_name`, or any file
+/// when `None`.
+fn is_change(event: &Event, file_name: Option<&OsStr>) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && (file_name.is_none() || event.paths.iter().any(|p| p.file_name() == file_name))
+}
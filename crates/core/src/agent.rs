@@ -0,0 +1,99 @@
+use taskai_schema::{Backlog, Task};
+
+/// Renders the prompt handing a task to a coding agent: the task with its epic, description,
+/// deliverables and `done_when` criteria, the dependencies it builds on, then the `instructions`
+/// of the configuration.
+pub fn agent_prompt(backlog: &Backlog, task: &Task, instructions: Option<&str>) -> String {
+    let mut prompt = format!(
+        "You are working on the project {}. Implement the following task.\n\nTask {}: {}\n",
+        backlog.project, task.id, task.title
+    );
+    if let Some(epic) = backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task.id))
+    {
+        prompt.push_str(&format!("Epic {}: {}\n", epic.id, epic.title));
+    }
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            prompt.push_str(&format!("\n{}\n", description));
+        }
+    }
+
+    let mut section = |heading: &str, items: Vec<String>| {
+        if !items.is_empty() {
+            prompt.push_str(&format!("\n{}:\n", heading));
+            for item in items {
+                prompt.push_str(&format!("- {}\n", item));
+            }
+        }
+    };
+    section(
+        "Deliverables",
+        task.deliverable
+            .as_ref()
+            .map(|d| d.paths().into_iter().map(str::to_string).collect())
+            .unwrap_or_default(),
+    );
+    section("Done when", task.done_when.clone());
+    section(
+        "Builds on the completed tasks",
+        task.depends
+            .iter()
+            .filter_map(|id| backlog.find_task(id))
+            .map(|t| format!("{}: {}", t.id, t.title))
+            .collect(),
+    );
+
+    if let Some(instructions) = instructions.map(str::trim) {
+        if !instructions.is_empty() {
+            prompt.push_str(&format!("\n{}\n", instructions));
+        }
+    }
+    prompt.push_str(
+        "\nThe deliverables and the done_when criteria are checked once you are finished, before \
+         the task is marked as done.\n",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_prompt() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: weather
+        epics:
+          - id: E-1
+            title: Forecasts
+            tasks:
+              - id: W-1
+                title: Fetch forecasts
+                state: Done
+              - id: W-2
+                title: Cache forecasts
+                description: Keep them for an hour.
+                depends: [W-1]
+                deliverable: src/cache.rs
+                done_when:
+                  - "$ cargo test cache"
+        "#,
+        )
+        .unwrap();
+        let task = backlog.find_task("W-2").unwrap();
+
+        let prompt = agent_prompt(&backlog, task, Some("Use tokio."));
+        assert!(prompt.starts_with(
+            "You are working on the project weather. Implement the following task.\n\n\
+             Task W-2: Cache forecasts\nEpic E-1: Forecasts\n\nKeep them for an hour.\n"
+        ));
+        assert!(prompt.contains("\nDeliverables:\n- src/cache.rs\n"));
+        assert!(prompt.contains("\nDone when:\n- $ cargo test cache\n"));
+        assert!(prompt.contains("\nBuilds on the completed tasks:\n- W-1: Fetch forecasts\n"));
+        assert!(prompt.contains("\nUse tokio.\n"));
+    }
+}
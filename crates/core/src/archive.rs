@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Removes the Done tasks that can be archived from the active backlog and returns them, in backlog
+/// order.
+///
+/// With `completed_before` set, only tasks completed before that instant are taken; tasks without a
+/// completion timestamp are then kept, as their age is unknown. A task that is the parent of a task
+/// staying in the backlog is kept too, so that subtask trees are never split.
+pub fn take_archivable_tasks(
+    backlog: &mut Backlog,
+    completed_before: Option<DateTime<Utc>>,
+) -> Vec<Task> {
+    let mut ids: HashSet<String> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| matches!(t.state, TaskState::Done))
+        .filter(|t| match completed_before {
+            Some(cutoff) => t.completed_at.is_some_and(|at| at < cutoff),
+            None => true,
+        })
+        .map(|t| t.id.clone())
+        .collect();
+
+    loop {
+        let kept_parents: Vec<String> = backlog
+            .all_tasks()
+            .into_iter()
+            .filter(|t| !ids.contains(&t.id))
+            .filter_map(|t| t.parent.clone())
+            .filter(|parent| ids.contains(parent))
+            .collect();
+        if kept_parents.is_empty() {
+            break;
+        }
+        for parent in kept_parents {
+            ids.remove(&parent);
+        }
+    }
+
+    let mut taken = Vec::new();
+    let mut take = |tasks: &mut Vec<Task>| {
+        let (archived, kept) = tasks.drain(..).partition(|t: &Task| ids.contains(&t.id));
+        *tasks = kept;
+        taken.extend(archived);
+    };
+    take(&mut backlog.tasks);
+    for epic in &mut backlog.epics {
+        take(&mut epic.tasks);
+    }
+    taken
+}
+
+/// Drops every dependency and parent reference to the given tasks from the backlog, archived tasks
+/// included.
+///
+/// Used when archived tasks are moved to another file: they are Done, so the dependencies on them
+/// are satisfied and can be forgotten without changing which tasks are ready.
+pub fn forget_tasks(backlog: &mut Backlog, ids: &[String]) {
+    for task in backlog
+        .tasks
+        .iter_mut()
+        .chain(backlog.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+        .chain(backlog.archive.iter_mut())
+    {
+        task.depends.retain(|dep| !ids.contains(dep));
+        if task
+            .parent
+            .as_ref()
+            .is_some_and(|parent| ids.contains(parent))
+        {
+            task.parent = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn take_old_done_tasks() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: Epic
+            tasks:
+              - id: T-1
+                title: Old
+                state: Done
+                completed_at: 2024-01-01T00:00:00Z
+              - id: T-2
+                title: Recent
+                state: Done
+                completed_at: 2024-06-01T00:00:00Z
+              - id: T-3
+                title: Parent of an open subtask
+                state: Done
+                completed_at: 2024-01-01T00:00:00Z
+              - id: T-4
+                title: Open subtask
+                parent: T-3
+                depends: [T-1]
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let cutoff = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+
+        let taken = take_archivable_tasks(&mut backlog, Some(cutoff));
+        let ids: Vec<&str> = taken.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["T-1"]);
+        assert_eq!(backlog.epics[0].tasks.len(), 3);
+
+        backlog.archive.extend(taken);
+        assert!(backlog.validate().is_ok());
+
+        forget_tasks(&mut backlog, &["T-1".to_string()]);
+        assert!(backlog.find_task("T-4").unwrap().depends.is_empty());
+    }
+
+    #[test]
+    fn forget_tasks_everywhere() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-2
+            title: Subtask
+            parent: T-1
+            depends: [T-1, T-3]
+        archive:
+          - id: T-3
+            title: Archived subtask
+            state: Done
+            parent: T-1
+            depends: [T-1]
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        forget_tasks(&mut backlog, &["T-1".to_string()]);
+        assert_eq!(backlog.tasks[0].depends, vec!["T-3"]);
+        assert_eq!(backlog.tasks[0].parent, None);
+        assert!(backlog.archive[0].depends.is_empty());
+        assert_eq!(backlog.archive[0].parent, None);
+        assert!(backlog.validate().is_ok());
+    }
+}
@@ -0,0 +1,198 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use taskai_schema::{ExternalRef, Task, TaskState};
+
+/// An Asana object created by the client: a task or a section.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AsanaObject {
+    pub gid: String,
+    /// Web page of the object, returned for tasks.
+    #[serde(default)]
+    pub permalink_url: Option<String>,
+}
+
+/// Client of the Asana REST API.
+///
+/// Requests are authenticated with `ASANA_ACCESS_TOKEN`, and sent to `ASANA_API_URL`
+/// (`https://app.asana.com/api/1.0` by default).
+pub struct AsanaClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl AsanaClient {
+    /// Creates a client configured from the environment.
+    pub fn from_env() -> Self {
+        AsanaClient {
+            client: crate::web::http_client(),
+            api_url: std::env::var("ASANA_API_URL")
+                .unwrap_or_else(|_| "https://app.asana.com/api/1.0".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token: std::env::var("ASANA_ACCESS_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which every Asana request requires.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Creates a section named `name` in `project`.
+    pub async fn create_section(&self, project: &str, name: &str) -> Result<AsanaObject, String> {
+        let url = format!("{}/projects/{}/sections", self.api_url, project);
+        self.send(&url, json!({ "name": name })).await
+    }
+
+    /// Creates a task from the data built by `asana_task_data`.
+    pub async fn create_task(&self, data: &Value) -> Result<AsanaObject, String> {
+        let url = format!("{}/tasks?opt_fields=permalink_url", self.api_url);
+        self.send(&url, data.clone()).await
+    }
+
+    /// Marks the task `task` as dependent on the tasks `dependencies`.
+    pub async fn add_dependencies(
+        &self,
+        task: &str,
+        dependencies: &[String],
+    ) -> Result<(), String> {
+        let url = format!("{}/tasks/{}/addDependencies", self.api_url, task);
+        self.send::<Value>(&url, json!({ "dependencies": dependencies }))
+            .await
+            .map(|_| ())
+    }
+
+    /// Posts `data` and returns the `data` of the response.
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        data: Value,
+    ) -> Result<T, String> {
+        let mut request = self.client.post(url).json(&json!({ "data": data }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Asana request failed: {}", e))?;
+        let status = response.status();
+        let mut body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Asana response: {}", e))?;
+        if !status.is_success() {
+            let messages: Vec<&str> = body["errors"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e["message"].as_str())
+                .collect();
+            return Err(format!(
+                "Asana API returned status {}: {}",
+                status.as_u16(),
+                messages.join("; ")
+            ));
+        }
+        serde_json::from_value(body["data"].take())
+            .map_err(|e| format!("Invalid Asana response: {}", e))
+    }
+}
+
+/// Returns the GID of the Asana task or section among the references of a task or an epic, if any.
+pub fn asana_gid(external_refs: &[ExternalRef]) -> Option<&str> {
+    external_refs
+        .iter()
+        .find(|r| r.system == "asana")
+        .map(|r| r.id.as_str())
+}
+
+/// Returns the external reference recording that a task or an epic was exported to an Asana task
+/// or section.
+pub fn asana_ref(object: &AsanaObject) -> ExternalRef {
+    ExternalRef {
+        system: "asana".to_string(),
+        id: object.gid.clone(),
+        url: object.permalink_url.clone(),
+        synced_at: None,
+    }
+}
+
+/// Builds the data of the Asana task mirroring a task of `project`: its title, notes made of its
+/// description and `done_when` criteria, its due date and whether it is done. The task goes to the
+/// section `section` when given.
+pub fn asana_task_data(task: &Task, project: &str, section: Option<&str>) -> Value {
+    let mut notes = String::new();
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            notes.push_str(description);
+            notes.push_str("\n\n");
+        }
+    }
+    if !task.done_when.is_empty() {
+        notes.push_str("Done when:\n");
+        for criterion in &task.done_when {
+            notes.push_str(&format!("- {}\n", criterion));
+        }
+        notes.push('\n');
+    }
+    notes.push_str(&format!("Task {} of the taskai backlog.", task.id));
+
+    let mut data = json!({
+        "name": task.title,
+        "notes": notes,
+        "completed": matches!(task.state, TaskState::Done),
+    });
+    match section {
+        Some(section) => {
+            data["memberships"] = json!([{ "project": project, "section": section }]);
+        }
+        None => data["projects"] = json!([project]),
+    }
+    if let Some(due) = task.due {
+        data["due_on"] = json!(due.to_string());
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::Backlog;
+
+    #[test]
+    fn test_asana_task_data() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Ship
+            state: Done
+            done_when: [Released]
+            due: 2024-03-01
+            external_refs:
+              - {system: asana, id: "1201"}
+        "#,
+        )
+        .unwrap();
+        let task = &backlog.tasks[0];
+
+        assert_eq!(
+            asana_task_data(task, "99", Some("42")),
+            json!({
+                "name": "Ship",
+                "notes": "Done when:\n- Released\n\nTask T-1 of the taskai backlog.",
+                "completed": true,
+                "memberships": [{ "project": "99", "section": "42" }],
+                "due_on": "2024-03-01",
+            })
+        );
+        assert_eq!(asana_task_data(task, "99", None)["projects"], json!(["99"]));
+        assert_eq!(asana_gid(&task.external_refs), Some("1201"));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::config::AzureDevOpsConfig;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use taskai_schema::{Epic, ExternalRef, Task};
+
+/// Version of the Azure DevOps REST API the client speaks.
+const API_VERSION: &str = "7.1";
+
+/// A work item created or updated by the client.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AzureWorkItem {
+    pub id: u64,
+    /// API URL of the work item, used to link other work items to it.
+    pub url: String,
+    #[serde(default, rename = "_links")]
+    links: Value,
+}
+
+impl AzureWorkItem {
+    /// Returns the web page of the work item.
+    pub fn web_url(&self) -> Option<&str> {
+        self.links["html"]["href"].as_str()
+    }
+}
+
+/// Client of the work item tracking API of Azure DevOps.
+///
+/// Requests are sent to the project of an organization on `AZURE_DEVOPS_URL`
+/// (`https://dev.azure.com` by default, or the collection URL of an Azure DevOps Server), and
+/// authenticated with a personal access token.
+pub struct AzureDevOpsClient {
+    client: reqwest::Client,
+    /// URL of the organization.
+    org_url: String,
+    project: String,
+    token: Option<String>,
+}
+
+impl AzureDevOpsClient {
+    /// Creates a client for `project` of `organization`, authenticated with `token`, or
+    /// `AZURE_DEVOPS_PAT` when not given.
+    pub fn from_env(organization: &str, project: &str, token: Option<&str>) -> Self {
+        let server = std::env::var("AZURE_DEVOPS_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| "https://dev.azure.com".to_string());
+        let token = match token {
+            Some(token) => Some(token.to_string()),
+            None => std::env::var("AZURE_DEVOPS_PAT").ok(),
+        };
+        AzureDevOpsClient {
+            client: crate::web::http_client(),
+            org_url: format!("{}/{}", server.trim_end_matches('/'), encode(organization)),
+            project: project.to_string(),
+            token: token.filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which creating work items requires.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Creates a work item of type `kind` from the JSON Patch document `patch`.
+    pub async fn create_work_item(
+        &self,
+        kind: &str,
+        patch: &Value,
+    ) -> Result<AzureWorkItem, String> {
+        let url = format!(
+            "{}/{}/_apis/wit/workitems/${}?api-version={}",
+            self.org_url,
+            encode(&self.project),
+            encode(kind),
+            API_VERSION
+        );
+        self.send(self.client.post(&url), patch).await
+    }
+
+    /// Applies the JSON Patch document `patch` to the work item `id`.
+    pub async fn update_work_item(&self, id: u64, patch: &Value) -> Result<AzureWorkItem, String> {
+        let url = format!(
+            "{}/_apis/wit/workitems/{}?api-version={}",
+            self.org_url, id, API_VERSION
+        );
+        self.send(self.client.patch(&url), patch).await
+    }
+
+    /// Returns the API URL of the work item `id`, which relations point to.
+    pub fn work_item_url(&self, id: u64) -> String {
+        format!("{}/_apis/wit/workItems/{}", self.org_url, id)
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        patch: &Value,
+    ) -> Result<AzureWorkItem, String> {
+        let mut request = request
+            .header("Content-Type", "application/json-patch+json")
+            .body(patch.to_string());
+        if let Some(token) = &self.token {
+            // Personal access tokens go as the password of a blank user
+            request = request.basic_auth("", Some(token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Azure DevOps request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|body| body["message"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            return Err(format!(
+                "Azure DevOps API returned status {}: {}",
+                status.as_u16(),
+                message
+            ));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Azure DevOps response: {}", e))
+    }
+}
+
+/// Encodes a name for use in an URL path, where organizations, projects and work item types may
+/// contain spaces.
+fn encode(name: &str) -> String {
+    name.replace('%', "%25").replace(' ', "%20")
+}
+
+/// Returns the ID of the work item among the references of a task or an epic, if any.
+pub fn azure_work_item_id(external_refs: &[ExternalRef]) -> Option<u64> {
+    external_refs
+        .iter()
+        .filter(|r| r.system == "azure-devops")
+        .find_map(|r| r.id.parse().ok())
+}
+
+/// Returns the external reference recording that a task or an epic was exported to a work item.
+pub fn azure_ref(work_item: &AzureWorkItem) -> ExternalRef {
+    ExternalRef {
+        system: "azure-devops".to_string(),
+        id: work_item.id.to_string(),
+        url: work_item.web_url().map(str::to_string),
+        synced_at: None,
+    }
+}
+
+/// Builds the JSON Patch document creating the work item of an epic.
+pub fn azure_epic_patch(epic: &Epic, config: &AzureDevOpsConfig) -> Value {
+    let mut patch = vec![field("System.Title", json!(epic.title.trim()))];
+    if let Some(area_path) = &config.area_path {
+        patch.push(field("System.AreaPath", json!(area_path)));
+    }
+    Value::Array(patch)
+}
+
+/// Builds the JSON Patch document creating the work item of a task: its title, a description made
+/// of its description and `done_when` criteria, its tags, its estimate when mapped to a field, and
+/// the work item of its epic, `parent_url`, as parent.
+pub fn azure_task_patch(
+    task: &Task,
+    parent_url: Option<&str>,
+    config: &AzureDevOpsConfig,
+) -> Value {
+    // Descriptions are HTML
+    let mut description = String::new();
+    if let Some(text) = task.description.as_deref().map(str::trim) {
+        for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+            description.push_str(&format!("<p>{}</p>", escape(paragraph.trim())));
+        }
+    }
+    if !task.done_when.is_empty() {
+        description.push_str("<h3>Done when</h3><ul>");
+        for criterion in &task.done_when {
+            description.push_str(&format!("<li>{}</li>", escape(criterion)));
+        }
+        description.push_str("</ul>");
+    }
+    description.push_str(&format!(
+        "<p><em>Task <code>{}</code> of the taskai backlog.</em></p>",
+        escape(&task.id)
+    ));
+
+    let mut patch = vec![
+        field("System.Title", json!(task.title)),
+        field("System.Description", json!(description)),
+    ];
+    if !task.tags.is_empty() {
+        // Semicolons separate the tags
+        let tags: Vec<String> = task.tags.iter().map(|t| t.replace(';', ",")).collect();
+        patch.push(field("System.Tags", json!(tags.join("; "))));
+    }
+    if let Some(area_path) = &config.area_path {
+        patch.push(field("System.AreaPath", json!(area_path)));
+    }
+    if let (Some(estimate_field), Some(estimate)) = (&config.estimate_field, task.estimate) {
+        patch.push(field(estimate_field, json!(estimate)));
+    }
+    if let Some(parent_url) = parent_url {
+        patch.push(relation("System.LinkTypes.Hierarchy-Reverse", parent_url));
+    }
+    Value::Array(patch)
+}
+
+/// Builds the JSON Patch document moving a work item to the done state of `config`.
+pub fn azure_done_patch(config: &AzureDevOpsConfig) -> Value {
+    let state = config.done_state.as_deref().unwrap_or("Closed");
+    json!([field("System.State", json!(state))])
+}
+
+/// Builds the JSON Patch document making the work item at `predecessor_url` a predecessor of the
+/// patched work item.
+pub fn azure_predecessor_patch(predecessor_url: &str) -> Value {
+    json!([relation(
+        "System.LinkTypes.Dependency-Reverse",
+        predecessor_url
+    )])
+}
+
+fn field(name: &str, value: Value) -> Value {
+    json!({ "op": "add", "path": format!("/fields/{}", name), "value": value })
+}
+
+fn relation(kind: &str, url: &str) -> Value {
+    json!({ "op": "add", "path": "/relations/-", "value": { "rel": kind, "url": url } })
+}
+
+/// Escapes text for HTML content.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::Backlog;
+
+    #[test]
+    fn test_azure_task_patch() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Ship
+            description: Build <it>.
+            done_when: [Released]
+            tags: [release, ops]
+            estimate: 3
+            external_refs:
+              - {system: azure-devops, id: "1201"}
+        "#,
+        )
+        .unwrap();
+        let task = &backlog.tasks[0];
+        let config = AzureDevOpsConfig {
+            estimate_field: Some("Microsoft.VSTS.Scheduling.StoryPoints".to_string()),
+            ..Default::default()
+        };
+
+        let patch = azure_task_patch(
+            task,
+            Some("https://dev.azure.com/acme/_apis/wit/workItems/7"),
+            &config,
+        );
+        assert_eq!(
+            patch,
+            json!([
+                { "op": "add", "path": "/fields/System.Title", "value": "Ship" },
+                {
+                    "op": "add",
+                    "path": "/fields/System.Description",
+                    "value": "<p>Build &lt;it&gt;.</p><h3>Done when</h3><ul><li>Released</li></ul>\
+                              <p><em>Task <code>T-1</code> of the taskai backlog.</em></p>",
+                },
+                { "op": "add", "path": "/fields/System.Tags", "value": "release; ops" },
+                {
+                    "op": "add",
+                    "path": "/fields/Microsoft.VSTS.Scheduling.StoryPoints",
+                    "value": 3.0,
+                },
+                {
+                    "op": "add",
+                    "path": "/relations/-",
+                    "value": {
+                        "rel": "System.LinkTypes.Hierarchy-Reverse",
+                        "url": "https://dev.azure.com/acme/_apis/wit/workItems/7",
+                    },
+                },
+            ])
+        );
+        assert_eq!(azure_work_item_id(&task.external_refs), Some(1201));
+        assert_eq!(azure_done_patch(&config)[0]["value"], "Closed");
+    }
+}
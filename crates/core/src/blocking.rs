@@ -0,0 +1,19 @@
+use std::future::Future;
+
+/// Runs `future` to completion on a runtime of its own, for callers that are not async.
+///
+/// Fails instead of panicking when called from within a Tokio runtime, where the async version of
+/// the function should be awaited instead.
+pub(crate) fn block_on<F: Future>(future: F) -> Result<F::Output, String> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(
+            "Blocking calls cannot be made from within an async runtime; await the async version instead."
+                .to_string(),
+        );
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Error starting the async runtime: {}", e))?;
+    Ok(runtime.block_on(future))
+}
@@ -0,0 +1,84 @@
+use taskai_schema::Task;
+
+/// Template of the branch names of tasks when none is configured.
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "feat/{id}-{slug}";
+
+/// Maximum length of the slug of a task title in a branch name.
+const SLUG_LENGTH: usize = 40;
+
+/// Builds the name of the git branch of a task from a template.
+///
+/// `{id}` is replaced with the ID of the task, `{slug}` with its title in lower case with words
+/// joined by `-` (cut to 40 characters at a word boundary), and `{epic}` with the ID of its epic,
+/// if any. Characters git does not accept in branch names are then replaced with `-`.
+pub fn branch_name(template: &str, task: &Task, epic: Option<&str>) -> String {
+    let name = template
+        .replace("{id}", &task.id)
+        .replace("{slug}", &slug(&task.title))
+        .replace("{epic}", epic.unwrap_or_default());
+
+    let mut clean = String::new();
+    for c in name.chars() {
+        let c = if c.is_control() || " ~^:?*[\\".contains(c) {
+            '-'
+        } else {
+            c
+        };
+        // No empty path components, repeated dashes or `..`, which an empty `{epic}` may leave
+        let previous = clean.chars().last();
+        if (c == '/' || c == '-' || c == '.') && previous == Some(c) {
+            continue;
+        }
+        if c == '/' && previous == Some('-') {
+            clean.pop();
+        }
+        if c == '-' && previous == Some('/') {
+            continue;
+        }
+        clean.push(c);
+    }
+    let clean = clean.trim_matches(|c| c == '/' || c == '-' || c == '.');
+    clean.strip_suffix(".lock").unwrap_or(clean).to_string()
+}
+
+/// Turns a title into lower-case words joined by `-`.
+fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    let words = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty());
+    for word in words {
+        let word = word.to_lowercase();
+        if !slug.is_empty() && slug.len() + 1 + word.len() > SLUG_LENGTH {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_name() {
+        let task = Task {
+            id: "T-12".to_string(),
+            title: "Add OAuth login: Google & GitHub (phase 1) for the mobile and web clients"
+                .to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            branch_name(DEFAULT_BRANCH_TEMPLATE, &task, None),
+            "feat/T-12-add-oauth-login-google-github-phase-1"
+        );
+        assert_eq!(branch_name("{epic}/{id}", &task, Some("E-2")), "E-2/T-12");
+        assert_eq!(branch_name("{epic}/{id}", &task, None), "T-12");
+        assert_eq!(branch_name("users/me: {id}", &task, None), "users/me-T-12");
+    }
+}
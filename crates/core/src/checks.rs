@@ -0,0 +1,243 @@
+use crate::verify::deliverable_exists;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use taskai_schema::Task;
+
+/// An automated check derived from a `done_when` criterion.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "target", rename_all = "snake_case")]
+pub enum Check {
+    /// A shell command that must exit successfully.
+    Command(String),
+    /// A path or glob pattern that must match something.
+    FileExists(String),
+    /// A criterion that cannot be checked automatically.
+    Manual,
+}
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// The check succeeded.
+    Passed,
+    /// The check failed.
+    Failed,
+    /// The criterion has to be verified by a person.
+    Manual,
+}
+
+/// Result of checking a single `done_when` criterion.
+#[derive(Debug, Clone, Serialize)]
+pub struct CriterionResult {
+    /// The criterion as written in the backlog.
+    pub criterion: String,
+    /// The check derived from the criterion.
+    pub check: Check,
+    /// Outcome of the check.
+    pub status: CheckStatus,
+}
+
+/// Results of checking all `done_when` criteria of a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskChecks {
+    /// ID of the checked task.
+    pub task_id: String,
+    /// Title of the checked task.
+    pub title: String,
+    /// Result of each criterion, in order.
+    pub criteria: Vec<CriterionResult>,
+}
+
+impl TaskChecks {
+    /// Returns true if the task has criteria and all of them passed automatically.
+    pub fn passed(&self) -> bool {
+        !self.criteria.is_empty()
+            && self
+                .criteria
+                .iter()
+                .all(|c| c.status == CheckStatus::Passed)
+    }
+
+    /// Returns true if any criterion failed.
+    pub fn failed(&self) -> bool {
+        self.criteria
+            .iter()
+            .any(|c| c.status == CheckStatus::Failed)
+    }
+}
+
+/// Derives an automated check from a `done_when` criterion.
+///
+/// - `$ cmd` runs `cmd`.
+/// - A criterion with a backticked part mentioning existence (``` `src/main.rs` exists ```) checks
+///   that the path exists.
+/// - `<path> exists` checks that the path exists.
+///
+/// Anything else is a manual criterion, including other backticked text such as
+/// ``` `cargo test` passes ```: criteria are often written by an LLM or imported from trackers, so
+/// only those explicitly marked as commands are ever run.
+pub fn parse_check(criterion: &str) -> Check {
+    let criterion = criterion.trim();
+    if let Some(command) = criterion.strip_prefix("$ ") {
+        return Check::Command(command.trim().to_string());
+    }
+
+    let lower = criterion.to_lowercase();
+    let mentions_existence = lower.contains("exist");
+
+    let mut parts = criterion.split('`');
+    if let (Some(_), Some(quoted), Some(_)) = (parts.next(), parts.next(), parts.next()) {
+        let quoted = quoted.trim();
+        if !quoted.is_empty() && mentions_existence {
+            return Check::FileExists(quoted.to_string());
+        }
+    }
+
+    const EXISTS: &str = " exists";
+    if lower.ends_with(EXISTS) {
+        if let Some(path) = criterion.get(..criterion.len() - EXISTS.len()) {
+            let path = path.strip_prefix("file ").unwrap_or(path).trim();
+            if !path.is_empty() && !path.contains(char::is_whitespace) {
+                return Check::FileExists(path.to_string());
+            }
+        }
+    }
+
+    Check::Manual
+}
+
+/// Runs the checks derived from the `done_when` criteria of a task in the `root` directory.
+///
+/// Commands only run with `run_commands` set, and are reported as manual otherwise. Their outcomes
+/// are memoized in `outcomes`, keyed by command, so that a command shared by many tasks (e.g.
+/// `cargo test`) only runs once.
+pub fn run_checks(
+    task: &Task,
+    root: &Path,
+    run_commands: bool,
+    outcomes: &mut HashMap<String, bool>,
+) -> TaskChecks {
+    let criteria = task
+        .done_when
+        .iter()
+        .map(|criterion| {
+            let check = parse_check(criterion);
+            let status = match &check {
+                Check::Command(_) if !run_commands => CheckStatus::Manual,
+                Check::Command(command) => {
+                    let passed = *outcomes
+                        .entry(command.clone())
+                        .or_insert_with(|| run_command(command, root));
+                    if passed {
+                        CheckStatus::Passed
+                    } else {
+                        CheckStatus::Failed
+                    }
+                }
+                Check::FileExists(path) => {
+                    if deliverable_exists(path, root) {
+                        CheckStatus::Passed
+                    } else {
+                        CheckStatus::Failed
+                    }
+                }
+                Check::Manual => CheckStatus::Manual,
+            };
+            CriterionResult {
+                criterion: criterion.clone(),
+                check,
+                status,
+            }
+        })
+        .collect();
+
+    TaskChecks {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        criteria,
+    }
+}
+
+/// Runs a command through the platform shell, returning true if it exits successfully.
+fn run_command(command: &str, root: &Path) -> bool {
+    tracing::info!(command, root = %root.display(), "running check");
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    tracing::debug!(command, ?status, "check finished");
+    status.is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_criteria() {
+        assert_eq!(parse_check("`cargo test` passes"), Check::Manual);
+        assert_eq!(
+            parse_check("$ make lint"),
+            Check::Command("make lint".to_string())
+        );
+        assert_eq!(
+            parse_check("`src/main.rs` exists"),
+            Check::FileExists("src/main.rs".to_string())
+        );
+        assert_eq!(
+            parse_check("README.md exists"),
+            Check::FileExists("README.md".to_string())
+        );
+        assert_eq!(parse_check("Code is reviewed"), Check::Manual);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn run_task_checks() {
+        let dir = std::env::temp_dir();
+        let task = Task {
+            id: "T-1".to_string(),
+            title: "Task".to_string(),
+            done_when: vec![
+                "$ true".to_string(),
+                "$ false".to_string(),
+                "Looks good".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        let checks = run_checks(&task, &dir, true, &mut HashMap::new());
+        let statuses: Vec<CheckStatus> = checks.criteria.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CheckStatus::Passed,
+                CheckStatus::Failed,
+                CheckStatus::Manual
+            ]
+        );
+        assert!(!checks.passed());
+        assert!(checks.failed());
+
+        let checks = run_checks(&task, &dir, false, &mut HashMap::new());
+        assert!(checks
+            .criteria
+            .iter()
+            .all(|c| c.status == CheckStatus::Manual));
+    }
+}
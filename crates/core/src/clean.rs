@@ -0,0 +1,142 @@
+use crate::archive::take_archivable_tasks;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskai_schema::Backlog;
+
+/// What `clean_backlog` changed, for reporting.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CleanSummary {
+    /// IDs of the Done tasks moved to the archive section.
+    pub archived: Vec<String>,
+    /// IDs of the epics removed because they had no tasks.
+    pub removed_epics: Vec<String>,
+    /// Dependencies listed more than once, as `(task, dependency)`.
+    pub duplicate_depends: Vec<(String, String)>,
+    /// Dependency and parent references to tasks missing from the backlog, as `(task, reference)`.
+    pub dangling_refs: Vec<(String, String)>,
+}
+
+impl CleanSummary {
+    /// Tells whether nothing needed cleaning.
+    pub fn is_empty(&self) -> bool {
+        self.archived.is_empty()
+            && self.removed_epics.is_empty()
+            && self.duplicate_depends.is_empty()
+            && self.dangling_refs.is_empty()
+    }
+}
+
+/// Tidies the backlog in one pass and returns what was changed.
+///
+/// With `archive_done_before` set, Done tasks completed before that instant are first moved to the
+/// archive section, as `taskai archive` does. Then epics left without tasks are removed, duplicate
+/// `depends` entries are dropped, and dependency and parent references to tasks that exist neither
+/// in the backlog nor in its archive are removed. Archived tasks are left untouched.
+pub fn clean_backlog(
+    backlog: &mut Backlog,
+    archive_done_before: Option<DateTime<Utc>>,
+) -> CleanSummary {
+    let mut summary = CleanSummary::default();
+
+    if let Some(cutoff) = archive_done_before {
+        let taken = take_archivable_tasks(backlog, Some(cutoff));
+        summary.archived = taken.iter().map(|t| t.id.clone()).collect();
+        backlog.archive.extend(taken);
+    }
+
+    backlog.epics.retain(|epic| {
+        if epic.tasks.is_empty() {
+            summary.removed_epics.push(epic.id.clone());
+        }
+        !epic.tasks.is_empty()
+    });
+
+    let known: Vec<String> = backlog
+        .all_tasks()
+        .iter()
+        .map(|t| t.id.clone())
+        .chain(backlog.archive.iter().map(|t| t.id.clone()))
+        .collect();
+    for task in backlog
+        .tasks
+        .iter_mut()
+        .chain(backlog.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+    {
+        let mut seen: Vec<String> = Vec::new();
+        let mut kept = Vec::new();
+        for dep in task.depends.drain(..) {
+            if seen.contains(&dep) {
+                summary.duplicate_depends.push((task.id.clone(), dep));
+            } else if !known.contains(&dep) {
+                seen.push(dep.clone());
+                summary.dangling_refs.push((task.id.clone(), dep));
+            } else {
+                seen.push(dep.clone());
+                kept.push(dep);
+            }
+        }
+        task.depends = kept;
+
+        if let Some(parent) = task.parent.clone().filter(|p| !known.contains(p)) {
+            task.parent = None;
+            summary.dangling_refs.push((task.id.clone(), parent));
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_backlog() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Old
+            state: Done
+            completed_at: 2024-01-01T00:00:00Z
+          - id: T-2
+            title: Recent
+            state: Done
+            completed_at: 2024-03-01T00:00:00Z
+          - id: T-3
+            title: Messy
+            depends: [T-1, T-2, T-2, T-9, T-9]
+            parent: T-8
+        epics:
+          - id: E-1
+            title: Empty
+          - id: E-2
+            title: Archived
+            tasks:
+              - id: A-1
+                title: Shipped
+                state: Done
+                completed_at: 2024-01-01T00:00:00Z
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let cutoff = "2024-02-01T00:00:00Z".parse().unwrap();
+
+        let summary = clean_backlog(&mut backlog, Some(cutoff));
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(summary.archived, ["T-1", "A-1"]);
+        assert_eq!(summary.removed_epics, ["E-1", "E-2"]);
+        assert_eq!(
+            summary.duplicate_depends,
+            [pair("T-3", "T-2"), pair("T-3", "T-9")]
+        );
+        assert_eq!(
+            summary.dangling_refs,
+            [pair("T-3", "T-9"), pair("T-3", "T-8")]
+        );
+        assert_eq!(backlog.tasks[1].depends, ["T-1", "T-2"]);
+        assert!(backlog.tasks[1].parent.is_none());
+        assert!(backlog.validate().is_ok());
+
+        assert!(clean_backlog(&mut backlog, None).is_empty());
+    }
+}
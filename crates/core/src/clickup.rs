@@ -0,0 +1,529 @@
+use crate::github::issue_for_task;
+use crate::gitlab::tracking_epic;
+use crate::ids::next_task_id;
+use crate::rows::ImportSummary;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use taskai_schema::{Backlog, ExternalRef, Task, TaskState};
+
+/// A status of a ClickUp list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpStatus {
+    pub status: String,
+    /// `open` for the first status, `custom` for the statuses in between, and `done` or `closed`
+    /// for the statuses of completed tasks.
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl ClickUpStatus {
+    /// Tells whether tasks in this status are completed.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.kind.as_str(), "done" | "closed")
+    }
+}
+
+/// A ClickUp list and the statuses of its tasks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpList {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub statuses: Vec<ClickUpStatus>,
+}
+
+/// A task of a ClickUp list, as returned by the API (version 2).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpTask {
+    pub id: String,
+    pub name: String,
+    /// Description of the task as plain text.
+    #[serde(default)]
+    pub text_content: Option<String>,
+    pub status: ClickUpStatus,
+    #[serde(default)]
+    pub tags: Vec<ClickUpTag>,
+    #[serde(default)]
+    pub assignees: Vec<ClickUpUser>,
+    /// Dependencies of the task and on the task.
+    #[serde(default)]
+    pub dependencies: Vec<ClickUpDependency>,
+    /// ID of the task this one is a subtask of, if any.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Milliseconds since the Unix epoch, as a string.
+    #[serde(default)]
+    pub date_created: Option<String>,
+    #[serde(default)]
+    pub date_updated: Option<String>,
+    #[serde(default)]
+    pub date_closed: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl ClickUpTask {
+    /// Returns when the task was last updated, if known.
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        timestamp(self.date_updated.as_deref())
+    }
+}
+
+/// A tag of a ClickUp task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpTag {
+    pub name: String,
+}
+
+/// A user a ClickUp task is assigned to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpUser {
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+/// A dependency between ClickUp tasks: `task_id` waits on `depends_on`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpDependency {
+    pub task_id: String,
+    pub depends_on: String,
+}
+
+/// Converts a ClickUp timestamp, in milliseconds as a string, into a time.
+fn timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(value?.parse().ok()?)
+}
+
+/// Client of the ClickUp REST API.
+///
+/// Requests are sent to `CLICKUP_API_URL` (`https://api.clickup.com/api/v2` by default) and
+/// authenticated with the personal token in `CLICKUP_API_TOKEN`.
+pub struct ClickUpClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl ClickUpClient {
+    /// Creates a client configured from the environment.
+    pub fn from_env() -> Self {
+        ClickUpClient {
+            client: crate::web::http_client(),
+            api_url: std::env::var("CLICKUP_API_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| "https://api.clickup.com/api/v2".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token: std::env::var("CLICKUP_API_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which the ClickUp API always requires.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Returns the list `list_id` with its statuses.
+    pub async fn get_list(&self, list_id: &str) -> Result<ClickUpList, String> {
+        let url = format!("{}/list/{}", self.api_url, list_id);
+        self.send(self.client.get(&url)).await
+    }
+
+    /// Lists all the tasks of the list `list_id`, open and closed, with their subtasks.
+    pub async fn list_tasks(&self, list_id: &str) -> Result<Vec<ClickUpTask>, String> {
+        #[derive(Deserialize)]
+        struct Page {
+            tasks: Vec<ClickUpTask>,
+            #[serde(default)]
+            last_page: bool,
+        }
+
+        let url = format!("{}/list/{}/task", self.api_url, list_id);
+        let mut tasks = Vec::new();
+        // Pages of 100 tasks are numbered from 0
+        for page in 0.. {
+            let page = page.to_string();
+            let request = self.client.get(&url).query(&[
+                ("include_closed", "true"),
+                ("subtasks", "true"),
+                ("page", page.as_str()),
+            ]);
+            let batch: Page = self.send(request).await?;
+            let last_page = batch.last_page || batch.tasks.is_empty();
+            tasks.extend(batch.tasks);
+            if last_page {
+                break;
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Creates a task in the list `list_id` from `data`, as built by `clickup_task_data`.
+    pub async fn create_task(&self, list_id: &str, data: &Value) -> Result<ClickUpTask, String> {
+        let url = format!("{}/list/{}/task", self.api_url, list_id);
+        self.send(self.client.post(&url).json(data)).await
+    }
+
+    /// Updates the task `task_id` with `data`, such as its `name` and `markdown_description`.
+    pub async fn update_task(&self, task_id: &str, data: &Value) -> Result<ClickUpTask, String> {
+        let url = format!("{}/task/{}", self.api_url, task_id);
+        self.send(self.client.put(&url).json(data)).await
+    }
+
+    /// Moves the task `task_id` to `status`.
+    pub async fn set_task_status(
+        &self,
+        task_id: &str,
+        status: &str,
+    ) -> Result<ClickUpTask, String> {
+        let url = format!("{}/task/{}", self.api_url, task_id);
+        let body = json!({ "status": status });
+        self.send(self.client.put(&url).json(&body)).await
+    }
+
+    /// Makes the task `task_id` wait on the task `depends_on`.
+    pub async fn add_dependency(&self, task_id: &str, depends_on: &str) -> Result<(), String> {
+        let url = format!("{}/task/{}/dependency", self.api_url, task_id);
+        let body = json!({ "depends_on": depends_on });
+        self.send::<Value>(self.client.post(&url).json(&body))
+            .await
+            .map(|_| ())
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        mut request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("ClickUp request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.json::<Value>().await.unwrap_or_default();
+            return Err(format!(
+                "ClickUp API returned status {}: {}",
+                status.as_u16(),
+                body["err"].as_str().unwrap_or_default()
+            ));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid ClickUp response: {}", e))
+    }
+}
+
+/// Returns the ID of the ClickUp task among the references of a task or an epic, if any.
+pub fn clickup_task_id(external_refs: &[ExternalRef]) -> Option<&str> {
+    external_refs
+        .iter()
+        .find(|r| r.system == "clickup")
+        .map(|r| r.id.as_str())
+}
+
+/// Returns the external reference to a ClickUp task, synced as of `synced_at`.
+pub fn clickup_ref(task: &ClickUpTask, synced_at: Option<DateTime<Utc>>) -> ExternalRef {
+    ExternalRef {
+        system: "clickup".to_string(),
+        id: task.id.clone(),
+        url: task.url.clone(),
+        synced_at,
+    }
+}
+
+/// Sets the `synced_at` time of the reference of a task to its ClickUp task. Returns true if it
+/// changed.
+pub fn mark_clickup_synced(task: &mut Task, at: DateTime<Utc>) -> bool {
+    match task
+        .external_refs
+        .iter_mut()
+        .find(|r| r.system == "clickup")
+    {
+        Some(external_ref) if external_ref.synced_at != Some(at) => {
+            external_ref.synced_at = Some(at);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns the state of the tasks in a ClickUp status: Done for the done and closed statuses,
+/// Todo for the open one, Blocked for custom statuses mentioning it, and InProgress for the other
+/// custom statuses.
+pub fn clickup_state(status: &ClickUpStatus) -> TaskState {
+    if status.is_closed() {
+        TaskState::Done
+    } else if status.kind == "open" {
+        TaskState::Todo
+    } else if status.status.to_lowercase().contains("block") {
+        TaskState::Blocked
+    } else {
+        TaskState::InProgress
+    }
+}
+
+/// Picks the status of a list matching a task state, the reverse of `clickup_state`. Blocked
+/// tasks go to the first custom status when the list has no blocked one, and InProgress tasks to
+/// the open status when it has no custom one; `None` when the list has no status for the state.
+pub fn clickup_status_for<'a>(state: &TaskState, statuses: &'a [ClickUpStatus]) -> Option<&'a str> {
+    let open = || statuses.iter().find(|s| s.kind == "open");
+    let custom = |blocked: bool| {
+        statuses
+            .iter()
+            .find(|s| s.kind == "custom" && s.status.to_lowercase().contains("block") == blocked)
+    };
+    let status = match state {
+        TaskState::Todo => open(),
+        TaskState::InProgress => custom(false).or_else(open),
+        TaskState::Blocked => custom(true).or_else(|| custom(false)).or_else(open),
+        // Lists may have both a done and a closed status; done is the one set by hand
+        TaskState::Done => statuses
+            .iter()
+            .find(|s| s.kind == "done")
+            .or_else(|| statuses.iter().find(|s| s.is_closed())),
+    };
+    status.map(|s| s.status.as_str())
+}
+
+/// Builds the request creating the ClickUp task of a task: its title, a Markdown description made
+/// of its description and `done_when` criteria, its tags, the status of its state when given, and
+/// the ClickUp task of its epic as parent.
+pub fn clickup_task_data(task: &Task, parent: Option<&str>, status: Option<&str>) -> Value {
+    let issue = issue_for_task(task, None);
+    let mut data = json!({
+        "name": issue.title,
+        "markdown_description": issue.body,
+        "tags": task.tags,
+    });
+    if let Some(parent) = parent {
+        data["parent"] = json!(parent);
+    }
+    if let Some(status) = status {
+        data["status"] = json!(status);
+    }
+    data
+}
+
+/// Brings the tasks of a ClickUp list into the backlog.
+///
+/// Tasks already tracked by a task of the backlog update its title and state. Top-level ClickUp
+/// tasks with subtasks become epics, their subtasks the tasks of the epic; other ClickUp tasks
+/// become standalone tasks, with their description, tags, first assignee and state from their
+/// status. Finally, ClickUp dependencies between tasks of the list become dependencies.
+pub fn import_clickup_tasks(
+    backlog: &mut Backlog,
+    tasks: &[ClickUpTask],
+    now: DateTime<Utc>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let is_epic = |task: &ClickUpTask| {
+        task.parent.is_none() && tasks.iter().any(|t| t.parent.as_ref() == Some(&task.id))
+    };
+    let task_of = |backlog: &Backlog, id: &str| {
+        backlog
+            .all_tasks()
+            .iter()
+            .find(|t| clickup_task_id(&t.external_refs) == Some(id))
+            .map(|t| t.id.clone())
+    };
+
+    for clickup_task in tasks.iter().filter(|t| !is_epic(t)) {
+        let state = clickup_state(&clickup_task.status);
+        if let Some(task_id) = task_of(backlog, &clickup_task.id) {
+            let Some(task) = backlog.find_task_mut(&task_id) else {
+                continue;
+            };
+            let mut changed = false;
+            if task.title != clickup_task.name {
+                task.title = clickup_task.name.clone();
+                task.updated_at = Some(now);
+                changed = true;
+            }
+            if std::mem::discriminant(&task.state) != std::mem::discriminant(&state) {
+                task.set_state(state, now);
+                changed = true;
+            }
+            if changed {
+                mark_clickup_synced(task, now);
+                summary.updated.push(task_id);
+            } else {
+                summary.unchanged += 1;
+            }
+            continue;
+        }
+
+        let parent = clickup_task
+            .parent
+            .as_ref()
+            .and_then(|id| tasks.iter().find(|t| &t.id == id));
+        let epic_id = parent.map(|parent| {
+            tracking_epic(
+                backlog,
+                &parent.name,
+                clickup_ref(parent, None),
+                &mut summary,
+            )
+        });
+        let mut task = Task {
+            id: next_task_id(backlog, epic_id.as_deref()),
+            title: clickup_task.name.clone(),
+            description: clickup_task
+                .text_content
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            tags: clickup_task.tags.iter().map(|t| t.name.clone()).collect(),
+            assignee: clickup_task
+                .assignees
+                .first()
+                .and_then(|user| user.username.clone().or_else(|| user.email.clone())),
+            created_at: timestamp(clickup_task.date_created.as_deref()),
+            updated_at: Some(now),
+            external_refs: vec![clickup_ref(clickup_task, Some(now))],
+            ..Default::default()
+        };
+        if !matches!(state, TaskState::Todo) {
+            let at = match state {
+                TaskState::Done => timestamp(clickup_task.date_closed.as_deref()),
+                _ => None,
+            };
+            task.set_state(state, at.unwrap_or(now));
+            task.updated_at = Some(now);
+        }
+        summary.added.push(task.id.clone());
+        match epic_id.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+    }
+
+    for clickup_task in tasks {
+        let Some(task_id) = task_of(backlog, &clickup_task.id) else {
+            continue;
+        };
+        let blockers: Vec<String> = clickup_task
+            .dependencies
+            .iter()
+            .filter(|d| d.task_id == clickup_task.id)
+            .filter_map(|d| task_of(backlog, &d.depends_on))
+            .filter(|id| id != &task_id)
+            .collect();
+        let Some(task) = backlog.find_task_mut(&task_id) else {
+            continue;
+        };
+        let mut changed = false;
+        for blocker in blockers {
+            if !task.depends.contains(&blocker) {
+                task.depends.push(blocker);
+                changed = true;
+            }
+        }
+        if changed && !summary.added.contains(&task_id) && !summary.updated.contains(&task_id) {
+            task.updated_at = Some(now);
+            summary.updated.push(task_id);
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: &str, kind: &str) -> ClickUpStatus {
+        ClickUpStatus {
+            status: status.to_string(),
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_clickup_status_for() {
+        let statuses = vec![
+            status("to do", "open"),
+            status("in progress", "custom"),
+            status("review", "custom"),
+            status("complete", "closed"),
+        ];
+        assert_eq!(
+            clickup_status_for(&TaskState::Todo, &statuses),
+            Some("to do")
+        );
+        assert_eq!(
+            clickup_status_for(&TaskState::InProgress, &statuses),
+            Some("in progress")
+        );
+        // No blocked status in the list
+        assert_eq!(
+            clickup_status_for(&TaskState::Blocked, &statuses),
+            Some("in progress")
+        );
+        assert_eq!(
+            clickup_status_for(&TaskState::Done, &statuses),
+            Some("complete")
+        );
+        assert!(matches!(
+            clickup_state(&status("Blocked", "custom")),
+            TaskState::Blocked
+        ));
+    }
+
+    #[test]
+    fn test_import_clickup_tasks() {
+        let tasks: Vec<ClickUpTask> = serde_json::from_value(json!([
+            {
+                "id": "86a1",
+                "name": "Checkout",
+                "status": { "status": "to do", "type": "open" },
+            },
+            {
+                "id": "86a2",
+                "name": "Cart",
+                "text_content": "Keep items.",
+                "status": { "status": "in progress", "type": "custom" },
+                "tags": [{ "name": "web" }],
+                "assignees": [{ "username": "alice", "email": "alice@example.com" }],
+                "parent": "86a1",
+            },
+            {
+                "id": "86a3",
+                "name": "Payment",
+                "status": { "status": "complete", "type": "closed" },
+                "parent": "86a1",
+                "date_closed": "1767225600000",
+                "dependencies": [{ "task_id": "86a3", "depends_on": "86a2" }],
+            },
+        ]))
+        .unwrap();
+        let mut backlog = Backlog::default();
+        let now: DateTime<Utc> = "2026-01-02T00:00:00Z".parse().unwrap();
+
+        let summary = import_clickup_tasks(&mut backlog, &tasks, now);
+        assert_eq!(summary.new_epics, vec!["E-1"]);
+        assert_eq!(summary.added.len(), 2);
+        let epic = &backlog.epics[0];
+        assert_eq!(epic.title, "Checkout");
+        assert_eq!(clickup_task_id(&epic.external_refs), Some("86a1"));
+        let (cart, payment) = (&epic.tasks[0], &epic.tasks[1]);
+        assert!(matches!(cart.state, TaskState::InProgress));
+        assert_eq!(cart.assignee.as_deref(), Some("alice"));
+        assert_eq!(cart.tags, vec!["web"]);
+        assert!(matches!(payment.state, TaskState::Done));
+        assert_eq!(payment.depends, vec![cart.id.clone()]);
+
+        // A second import leaves the backlog as is
+        let summary = import_clickup_tasks(&mut backlog, &tasks, now);
+        assert!(summary.added.is_empty() && summary.updated.is_empty());
+        assert_eq!(summary.unchanged, 2);
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskai_schema::{Backlog, TaskState};
+
+/// Words closing the tasks that follow them, anywhere in a commit message.
+const CLOSING_WORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved", "done",
+];
+
+/// Trailers marking the tasks they list as in progress.
+const PROGRESS_TRAILERS: &[&str] = &["task", "tasks", "ref", "refs", "progress"];
+
+/// A task referenced by a commit message.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommitRef {
+    pub task_id: String,
+    /// Whether the commit completes the task, rather than only working on it.
+    pub closes: bool,
+}
+
+/// A task whose state was changed after a commit.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitUpdate {
+    pub task_id: String,
+    pub title: String,
+    pub state: TaskState,
+}
+
+/// Finds the tasks of the backlog referenced by a commit message.
+///
+/// `Closes T-12`, `Fixes T-3, T-4` and the other closing words (`close`, `fix`, `resolve` and
+/// their forms, `done`) close the tasks following them, anywhere in the message and also as
+/// trailers such as `Closes: T-12`. Trailers such as `Task: T-12` or `Refs: T-1, T-2` mark the
+/// tasks as in progress. Only IDs of tasks of the backlog are returned, each once; closing wins
+/// when a task is referenced both ways.
+pub fn parse_commit_refs(backlog: &Backlog, message: &str) -> Vec<CommitRef> {
+    let mut refs: Vec<CommitRef> = Vec::new();
+    let mut add = |task_id: &str, closes: bool| {
+        if !backlog.has_task(task_id) {
+            return;
+        }
+        match refs.iter_mut().find(|r| r.task_id == task_id) {
+            Some(existing) => existing.closes |= closes,
+            None => refs.push(CommitRef {
+                task_id: task_id.to_string(),
+                closes,
+            }),
+        }
+    };
+    let clean = |word: &str| {
+        word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+            .to_string()
+    };
+
+    for line in message.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            if PROGRESS_TRAILERS.contains(&key.trim().to_lowercase().as_str()) {
+                for word in value.split([',', ' ']) {
+                    add(&clean(word), false);
+                }
+                continue;
+            }
+        }
+
+        let mut closing = false;
+        for word in line.split_whitespace() {
+            let word = clean(word);
+            if CLOSING_WORDS.contains(&word.to_lowercase().as_str()) {
+                closing = true;
+            } else if closing && backlog.has_task(&word) {
+                add(&word, true);
+            } else if word != "and" && !word.is_empty() {
+                closing = false;
+            }
+        }
+    }
+    refs
+}
+
+/// Applies the references of the commit named `commit` to the backlog: closed tasks are marked Done,
+/// and referenced Todo tasks InProgress, with a note naming the commit. Tasks already in those
+/// states, or Blocked, are left as they are. Returns the tasks updated.
+pub fn apply_commit_refs(
+    backlog: &mut Backlog,
+    refs: &[CommitRef],
+    commit: &str,
+    now: DateTime<Utc>,
+) -> Vec<CommitUpdate> {
+    let mut updates = Vec::new();
+    for commit_ref in refs {
+        let Some(task) = backlog.find_task_mut(&commit_ref.task_id) else {
+            continue;
+        };
+        let (state, note) = match (&task.state, commit_ref.closes) {
+            (TaskState::Done, _) => continue,
+            (_, true) => (TaskState::Done, format!("Closed by commit {}", commit)),
+            (TaskState::Todo, false) => (
+                TaskState::InProgress,
+                format!("Started in commit {}", commit),
+            ),
+            _ => continue,
+        };
+        task.set_state(state.clone(), now);
+        task.add_note(&note, now);
+        updates.push(CommitUpdate {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            state,
+        });
+    }
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_refs() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: One
+          - id: T-2
+            title: Two
+          - id: T-3
+            title: Three
+            state: Blocked
+          - id: T-4
+            title: Four
+        "#,
+        )
+        .unwrap();
+        let message = "Fix UTF-8 handling, closes T-1 and T-2.\n\nTask: T-3, T-4\nRefs: T-2 T-9\n";
+
+        let refs = parse_commit_refs(&backlog, message);
+        let found: Vec<(&str, bool)> = refs
+            .iter()
+            .map(|r| (r.task_id.as_str(), r.closes))
+            .collect();
+        assert_eq!(
+            found,
+            [("T-1", true), ("T-2", true), ("T-3", false), ("T-4", false)]
+        );
+
+        let updates = apply_commit_refs(&mut backlog, &refs, "abc1234", Utc::now());
+        let updated: Vec<&str> = updates.iter().map(|u| u.task_id.as_str()).collect();
+        assert_eq!(updated, ["T-1", "T-2", "T-4"]);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+        assert_eq!(backlog.tasks[0].notes[0].text, "Closed by commit abc1234");
+        assert!(matches!(backlog.tasks[2].state, TaskState::Blocked));
+        assert!(matches!(backlog.tasks[3].state, TaskState::InProgress));
+    }
+}
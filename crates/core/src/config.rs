@@ -0,0 +1,635 @@
+use crate::Severity;
+use chrono::NaiveDate;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project configuration file.
+pub const CONFIG_FILE: &str = ".taskai.toml";
+
+/// Project configuration read from `.taskai.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// Description of the project.
+    #[serde(default)]
+    pub project: ProjectConfig,
+    /// Defaults for `taskai gen`, also accepted as a `[gen]` section.
+    #[serde(default, alias = "gen")]
+    pub generate: GenerateConfig,
+    /// People working on the project.
+    #[serde(default, skip_serializing_if = "TeamConfig::is_empty")]
+    pub team: TeamConfig,
+    /// Defaults for `taskai next`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub next: NextConfig,
+    /// Defaults for `taskai stale`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub stale: StaleConfig,
+    /// Defaults for `taskai lint`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub lint: LintConfig,
+    /// Defaults for `taskai archive`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub archive: ArchiveConfig,
+    /// How human-readable output looks.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub output: OutputConfig,
+    /// Settings of the GitHub integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub github: GitHubConfig,
+    /// Settings of the GitLab integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub gitlab: GitLabConfig,
+    /// Settings of the Jira integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub jira: JiraConfig,
+    /// Settings of the Linear integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub linear: LinearConfig,
+    /// Settings of the Asana integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub asana: AsanaConfig,
+    /// Settings of the Azure DevOps integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub azure_devops: AzureDevOpsConfig,
+    /// Settings of the ClickUp integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub clickup: ClickUpConfig,
+    /// Settings of the git integration.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub git: GitConfig,
+    /// Settings of the Slack notifications.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub slack: SlackConfig,
+    /// Settings of the Discord notifications.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub discord: DiscordConfig,
+    /// SMTP settings used to mail digests.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub email: EmailConfig,
+    /// Settings of `taskai agent`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub agent: AgentConfig,
+    /// Target dates of milestones, such as `v1 = 2026-12-01`.
+    #[serde(
+        default,
+        deserialize_with = "milestone_dates",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub milestones: BTreeMap<String, NaiveDate>,
+    /// Command aliases: each name expands to the command line it maps to, such as
+    /// `n = "next --limit 1"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// Description of the project the backlog is for.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// Name of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Kind of project (e.g. `cli`, `web`, `library`).
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Programming language of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Defaults used when generating a backlog.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerateConfig {
+    /// Specification file to generate from, relative to the configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<PathBuf>,
+    /// Language of the prompts (`en`, `fr`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Style of the generated backlog.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// LLM model to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Defaults for listing ready tasks. Flags given on the command line take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NextConfig {
+    /// Maximum number of tasks to show.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Only show tasks of this epic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic: Option<String>,
+    /// Only show tasks carrying this tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Only show tasks assigned to this person.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+/// Defaults for reporting stale tasks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StaleConfig {
+    /// Number of days after which an untouched task is considered stale.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<i64>,
+}
+
+/// Defaults for linting the backlog.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Minimum severity of the problems to report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Number of days after which an untouched task is considered stale.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<i64>,
+}
+
+/// Defaults for archiving Done tasks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// Only archive tasks completed more than this many days ago.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<i64>,
+}
+
+/// Settings of the human-readable output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Set to `false` to never color the output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<bool>,
+    /// Colors used for task states, priorities and diffs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+}
+
+/// Settings of the GitHub integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    /// Projects (v2) board kept in sync by `taskai sync github-project`, as `owner/number`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Single-select field of the board holding the column of an item (`Status` by default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_field: Option<String>,
+    /// Single-select or iteration field of the board named after the epic of a task (`Epic` by
+    /// default); left alone when the board has no such field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_field: Option<String>,
+    /// Column of the board for each task state, such as `Blocked = "On hold"`. States not listed
+    /// go to `Todo`, `In Progress` (InProgress and Blocked) and `Done`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub columns: BTreeMap<String, String>,
+}
+
+/// Settings of the GitLab integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitLabConfig {
+    /// Base URL of the GitLab instance (defaults to `GITLAB_URL`, then `https://gitlab.com`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Path of the GitLab project, such as `acme/app`, used by the gitlab commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// What epics of the backlog become in GitLab: milestones of the project (the default), or
+    /// epics of a group, which requires GitLab Premium.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epics: Option<GitLabEpics>,
+    /// Path of the group holding the GitLab epics (defaults to the namespace of the project).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+impl GitLabConfig {
+    /// Returns the path of the group holding the GitLab epics of `project`.
+    pub fn group_of(&self, project: &str) -> String {
+        match &self.group {
+            Some(group) => group.clone(),
+            None => project
+                .rsplit_once('/')
+                .map_or(project, |(namespace, _)| namespace)
+                .to_string(),
+        }
+    }
+}
+
+/// What epics of the backlog map to in GitLab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitLabEpics {
+    /// Milestones of the project.
+    #[default]
+    Milestone,
+    /// Epics of the group of the project.
+    Epic,
+}
+
+/// Settings of the Jira integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JiraConfig {
+    /// Base URL of the Jira site, such as `https://acme.atlassian.net` (defaults to
+    /// `JIRA_BASE_URL`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Key of the Jira project used by `taskai import jira` and `taskai export jira`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Issue type of exported tasks (`Task` by default, or `Story`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+    /// Issue type of epics (`Epic` by default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_type: Option<String>,
+    /// Issue link type standing for dependencies (`Blocks` by default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_type: Option<String>,
+    /// Custom fields of the Jira site holding task data.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub fields: JiraFieldMapping,
+}
+
+/// IDs of the Jira custom fields (such as `customfield_10016`) matching task data. Data without a
+/// field is neither exported nor imported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JiraFieldMapping {
+    /// Number field holding the estimate, such as story points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<String>,
+    /// `Epic Name` field, required to create epics on company-managed projects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_name: Option<String>,
+    /// `Epic Link` field of company-managed projects; the parent of an issue is used otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_link: Option<String>,
+}
+
+/// Settings of the Linear integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinearConfig {
+    /// Key of the Linear team the issues belong to, such as `ENG`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Name of the Linear project new issues are added to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+/// Settings of the Asana integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AsanaConfig {
+    /// GID of the Asana project tasks are exported to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+/// Settings of the Azure DevOps integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AzureDevOpsConfig {
+    /// Name of the Azure DevOps organization, as in `https://dev.azure.com/acme`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// Name of the project work items are created in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Personal access token with the Work Items scope (defaults to `AZURE_DEVOPS_PAT`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Work item type of tasks (`User Story` by default; `Product Backlog Item` on Scrum
+    /// projects, `Issue` on Basic ones, or `Task`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+    /// Work item type of epics (`Epic` by default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_type: Option<String>,
+    /// State of the work items of Done tasks (`Closed` by default; `Done` on Scrum and Basic
+    /// projects).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_state: Option<String>,
+    /// Area path of the created work items (the project root by default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area_path: Option<String>,
+    /// Field receiving task estimates, such as `Microsoft.VSTS.Scheduling.StoryPoints`;
+    /// estimates are not exported when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate_field: Option<String>,
+}
+
+/// Settings of the ClickUp integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClickUpConfig {
+    /// ID of the ClickUp list tasks are imported from, exported to and synced with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list: Option<String>,
+}
+
+/// Settings of the git integration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Template of the branch names created by `taskai branch`, such as `feat/{id}-{slug}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_template: Option<String>,
+}
+
+/// Settings of the Slack notifications, posted through an incoming webhook or a bot token.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// URL of the incoming webhook messages are posted to (defaults to `SLACK_WEBHOOK_URL`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    /// Bot token posting to channels with `chat.postMessage` (defaults to `SLACK_BOT_TOKEN`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Channel the bot posts to when no route matches, such as `#dev`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Events to post about; all of them when not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<NotifyEvent>>,
+    /// Channels receiving the messages about the tasks of an epic or with a tag, the first
+    /// matching route winning.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<SlackRoute>,
+}
+
+impl SlackConfig {
+    /// Tells whether messages are posted about the event.
+    pub fn posts(&self, event: NotifyEvent) -> bool {
+        self.events
+            .as_ref()
+            .is_none_or(|events| events.contains(&event))
+    }
+}
+
+/// Destination of the Slack messages about some of the tasks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlackRoute {
+    /// Matches the tasks of this epic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic: Option<String>,
+    /// Matches the tasks carrying this tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Channel the bot posts to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Incoming webhook posting to the channel, used instead of the bot token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+}
+
+/// Settings of the Discord notifications, posted through channel webhooks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiscordConfig {
+    /// URL of the webhook messages are posted to (defaults to `DISCORD_WEBHOOK_URL`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    /// Events to post about; all of them when not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<NotifyEvent>>,
+    /// Webhooks used instead of `webhook` for some events, such as `standup = "https://..."` to
+    /// post standups to another channel.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub webhooks: BTreeMap<NotifyEvent, String>,
+}
+
+impl DiscordConfig {
+    /// Tells whether messages are posted about the event.
+    pub fn posts(&self, event: NotifyEvent) -> bool {
+        self.events
+            .as_ref()
+            .is_none_or(|events| events.contains(&event))
+    }
+}
+
+/// SMTP settings used by `taskai digest --email`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    /// Host name of the SMTP server; without it, digests are written to stdout instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_host: Option<String>,
+    /// Port of the SMTP server (defaults to 587, 465 with `tls = "tls"` and 25 with `tls = "none"`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_port: Option<u16>,
+    /// How the connection is secured: `starttls` (the default), `tls`, or `none` for local relays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<String>,
+    /// User name to log in with; the password comes from `TASKAI_SMTP_PASSWORD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Sender of the digests, such as `TaskAI <taskai@example.com>`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Recipients of the digests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to: Vec<String>,
+}
+
+/// Settings of `taskai agent`, which hands ready tasks to a coding agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// Command run for each task, with a `{prompt}` placeholder besides the ones of
+    /// `next --exec`, such as `claude -p {prompt}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Instructions appended to the prompt of every task, such as the conventions to follow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// Event of the backlog that notifications are posted about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyEvent {
+    /// Tasks were marked as done.
+    Done,
+    /// A task was blocked.
+    Blocked,
+    /// `taskai standup` ran.
+    Standup,
+}
+
+/// Color theme of the human-readable output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Standard terminal colors.
+    #[default]
+    Default,
+    /// Bright colors, easier to read on dark backgrounds.
+    Bright,
+    /// No hues, only bold, underlined and dimmed text.
+    Mono,
+}
+
+/// Reads the target dates of milestones, written as TOML dates or as strings.
+fn milestone_dates<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, NaiveDate>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Date {
+        Toml(toml::value::Datetime),
+        Text(String),
+    }
+
+    BTreeMap::<String, Date>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(milestone, date)| {
+            let text = match date {
+                Date::Toml(date) => date.to_string(),
+                Date::Text(text) => text,
+            };
+            match text.parse() {
+                Ok(date) => Ok((milestone, date)),
+                Err(_) => Err(de::Error::custom(format!(
+                    "invalid date '{}' for milestone {}",
+                    text, milestone
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Returns true if the section holds no setting, so it is left out of the written file.
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// The team working on the project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeamConfig {
+    /// Names tasks can be assigned to. When empty, any name is accepted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+}
+
+impl TeamConfig {
+    /// Returns true if no team is configured.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Checks that the person can be assigned tasks, returning an error listing the members if not.
+    pub fn check_member(&self, person: &str) -> Result<(), String> {
+        if self.members.is_empty() || self.members.iter().any(|m| m == person) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} is not a team member (known members: {}).",
+                person,
+                self.members.join(", ")
+            ))
+        }
+    }
+}
+
+impl Config {
+    /// Parses a configuration from TOML.
+    pub fn from_toml(content: &str) -> Result<Config, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    /// Renders the configuration as TOML.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Reads the configuration file at the given path.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+        Self::from_toml(&content).map_err(|e| format!("Error parsing {}: {}", path.display(), e))
+    }
+
+    /// Looks for `.taskai.toml` in the given directory and its ancestors.
+    ///
+    /// Returns the path of the configuration file found along with its content, or `Ok(None)` when
+    /// there is none.
+    pub fn discover(start: &Path) -> Result<Option<(PathBuf, Config)>, String> {
+        // Relative paths such as `.` have no ancestors to walk up to.
+        let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+        for dir in start.ancestors() {
+            let path = dir.join(CONFIG_FILE);
+            if path.is_file() {
+                tracing::debug!(path = %path.display(), "using configuration");
+                let config = Self::load(&path)?;
+                return Ok(Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_roundtrip() {
+        let config = Config {
+            project: ProjectConfig {
+                name: Some("weather".to_string()),
+                kind: Some("cli".to_string()),
+                language: Some("Rust".to_string()),
+            },
+            generate: GenerateConfig {
+                spec: Some(PathBuf::from("spec.md")),
+                lang: Some("en".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let toml = config.to_toml().unwrap();
+        assert!(toml.contains("type = \"cli\""));
+        assert!(!toml.contains("[team]"));
+        assert_eq!(Config::from_toml(&toml).unwrap(), config);
+        assert_eq!(Config::from_toml("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn team_members() {
+        let config = Config::from_toml("[team]\nmembers = [\"alice\", \"bob\"]\n").unwrap();
+        assert!(config.team.check_member("alice").is_ok());
+        assert!(config.team.check_member("carol").is_err());
+        assert!(TeamConfig::default().check_member("carol").is_ok());
+    }
+
+    #[test]
+    fn command_sections() {
+        let config = Config::from_toml(
+            "[gen]\nstyle = \"agile\"\n\n[next]\nlimit = 3\n\n[lint]\nseverity = \"error\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.generate.style.as_deref(), Some("agile"));
+        assert_eq!(config.next.limit, Some(3));
+        assert_eq!(config.lint.severity, Some(Severity::Error));
+        assert!(Config::from_toml("[output]\ntheme = \"mono\"\n").is_ok());
+        assert!(Config::from_toml("[output]\ntheme = \"neon\"\n").is_err());
+
+        let milestones = Config::from_toml("[milestones]\nv1 = 2026-12-01\nv2 = \"2027-01-15\"\n")
+            .unwrap()
+            .milestones;
+        assert_eq!(
+            milestones["v1"],
+            NaiveDate::from_ymd_opt(2026, 12, 1).unwrap()
+        );
+        assert_eq!(
+            milestones["v2"],
+            NaiveDate::from_ymd_opt(2027, 1, 15).unwrap()
+        );
+        assert!(Config::from_toml("[milestones]\nv1 = 2026-12-01T10:00:00Z\n").is_err());
+
+        let toml = config.to_toml().unwrap();
+        assert!(toml.contains("[next]"));
+        assert!(!toml.contains("[stale]"));
+        assert_eq!(Config::from_toml(&toml).unwrap(), config);
+    }
+}
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use taskai_schema::{Backlog, TaskState};
+
+/// Severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The backlog is usable but something looks wrong.
+    Warning,
+    /// The backlog is invalid.
+    Error,
+}
+
+/// Where a diagnostic applies in the backlog file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Location {
+    /// Path of the element in the document (e.g. `epics[0].tasks[2].depends`).
+    pub path: String,
+    /// 1-based line number in the file, when it could be determined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-based column number in the file, when it could be determined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+/// A single problem found in a backlog.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Severity of the problem.
+    pub severity: Severity,
+    /// Stable, machine-readable identifier of the problem (e.g. `missing-dependency`).
+    pub code: String,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// ID of the task concerned, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// Location of the problem, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
+
+/// Result of validating a backlog file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationReport {
+    /// True if no error was found (warnings do not make a backlog invalid).
+    pub valid: bool,
+    /// Problems that make the backlog invalid.
+    pub errors: Vec<Diagnostic>,
+    /// Problems worth fixing that do not make the backlog invalid.
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Builds a report from a list of diagnostics, splitting them by severity.
+    pub fn from_diagnostics(diagnostics: Vec<Diagnostic>) -> Self {
+        let (errors, warnings): (Vec<Diagnostic>, Vec<Diagnostic>) = diagnostics
+            .into_iter()
+            .partition(|d| d.severity == Severity::Error);
+
+        ValidationReport {
+            valid: errors.is_empty(),
+            errors,
+            warnings,
+        }
+    }
+}
+
+/// Validates the YAML source of a backlog, collecting every problem instead of stopping at the first one.
+///
+/// Parse errors are reported with their line and column. Unknown fields are reported as warnings,
+/// or as errors when `strict` is set. Semantic checks cover duplicate IDs, missing dependencies,
+/// dependency cycles and malformed external references.
+pub fn validate_source(source: &str, strict: bool) -> ValidationReport {
+    let (backlog, unknown_fields) = match Backlog::from_yaml_with_unknown_fields(source) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let location = err.location().map(|l| Location {
+                path: String::new(),
+                line: Some(l.line()),
+                column: Some(l.column()),
+            });
+            return ValidationReport::from_diagnostics(vec![Diagnostic {
+                severity: Severity::Error,
+                code: "parse-error".to_string(),
+                message: err.to_string(),
+                task_id: None,
+                location,
+            }]);
+        }
+    };
+
+    let index = TaskIndex::new(&backlog, source);
+    let mut diagnostics = Vec::new();
+
+    for field_path in unknown_fields {
+        let segments: Vec<&str> = field_path.split('.').collect();
+        let field = segments.last().copied().unwrap_or_default();
+        let task_path = task_path_of(&segments);
+        let task_id = task_path.as_ref().and_then(|p| index.id_at(p));
+        let line = task_id
+            .as_deref()
+            .and_then(|id| index.line_of(id))
+            .and_then(|task_line| find_line(source, field, task_line))
+            .or_else(|| find_line(source, field, 1));
+
+        diagnostics.push(Diagnostic {
+            severity: if strict { Severity::Error } else { Severity::Warning },
+            code: "unknown-field".to_string(),
+            message: format!("Unknown field '{}'", field),
+            task_id,
+            location: Some(Location {
+                path: to_index_path(&segments),
+                line,
+                column: None,
+            }),
+        });
+    }
+
+    diagnostics.extend(check_backlog(&backlog, source));
+
+    ValidationReport::from_diagnostics(diagnostics)
+}
+
+/// Runs the semantic checks on an already parsed backlog.
+///
+/// `source` is only used to resolve line numbers and may be empty.
+pub fn check_backlog(backlog: &Backlog, source: &str) -> Vec<Diagnostic> {
+    let index = TaskIndex::new(backlog, source);
+    let mut diagnostics = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for task in backlog.all_tasks().into_iter().chain(&backlog.archive) {
+        *seen.entry(task.id.as_str()).or_insert(0) += 1;
+    }
+
+    let mut reported_duplicates = Vec::new();
+    for task in backlog.all_tasks() {
+        if seen[task.id.as_str()] > 1 && !reported_duplicates.contains(&task.id) {
+            reported_duplicates.push(task.id.clone());
+            diagnostics.push(index.diagnostic(
+                Severity::Error,
+                "duplicate-id",
+                format!("Task ID {} is used by more than one task", task.id),
+                &task.id,
+                None,
+            ));
+        }
+
+        for dep_id in &task.depends {
+            if !seen.contains_key(dep_id.as_str()) {
+                diagnostics.push(index.diagnostic(
+                    Severity::Error,
+                    "missing-dependency",
+                    format!("Task {} depends on non-existent task {}", task.id, dep_id),
+                    &task.id,
+                    Some("depends"),
+                ));
+            }
+        }
+
+        if let Some(parent) = &task.parent {
+            if parent == &task.id || !seen.contains_key(parent.as_str()) {
+                diagnostics.push(index.diagnostic(
+                    Severity::Error,
+                    "invalid-parent",
+                    format!("Task {} has invalid parent task {}", task.id, parent),
+                    &task.id,
+                    Some("parent"),
+                ));
+            }
+        }
+
+        if matches!(task.state, TaskState::Done) {
+            let unfinished = crate::next::unfinished_dependencies(backlog, task);
+            if !unfinished.is_empty() {
+                let ids: Vec<&str> = unfinished.iter().map(|t| t.id.as_str()).collect();
+                diagnostics.push(index.diagnostic(
+                    Severity::Warning,
+                    "done-with-unfinished-dependencies",
+                    format!(
+                        "Task {} is Done but depends on unfinished task(s) {}",
+                        task.id,
+                        ids.join(", ")
+                    ),
+                    &task.id,
+                    Some("state"),
+                ));
+            }
+        }
+    }
+
+    if let Some(cycle) = backlog.find_cycle() {
+        let first = cycle.first().cloned().unwrap_or_default();
+        diagnostics.push(index.diagnostic(
+            Severity::Error,
+            "dependency-cycle",
+            format!("Dependency cycle detected: {}", cycle.join(" -> ")),
+            &first,
+            Some("depends"),
+        ));
+    }
+
+    for issue in crate::refs::check_ref_formats(backlog) {
+        diagnostics.push(index.diagnostic(
+            Severity::Warning,
+            "invalid-reference",
+            format!("{}: {}", issue.reference, issue.message),
+            &issue.task_id,
+            None,
+        ));
+    }
+
+    diagnostics
+}
+
+/// Maps task IDs to their path in the document and their line in the source.
+pub(crate) struct TaskIndex<'a> {
+    paths: Vec<(String, String)>,
+    source: &'a str,
+}
+
+impl<'a> TaskIndex<'a> {
+    pub(crate) fn new(backlog: &Backlog, source: &'a str) -> Self {
+        let mut paths = Vec::new();
+
+        for (i, task) in backlog.tasks.iter().enumerate() {
+            paths.push((task.id.clone(), format!("tasks[{}]", i)));
+        }
+
+        for (e, epic) in backlog.epics.iter().enumerate() {
+            for (i, task) in epic.tasks.iter().enumerate() {
+                paths.push((task.id.clone(), format!("epics[{}].tasks[{}]", e, i)));
+            }
+        }
+
+        TaskIndex { paths, source }
+    }
+
+    /// Returns the document path of the task with the given ID.
+    pub(crate) fn path_of(&self, id: &str) -> Option<&str> {
+        self.paths
+            .iter()
+            .find(|(task_id, _)| task_id == id)
+            .map(|(_, path)| path.as_str())
+    }
+
+    /// Returns the ID of the task at the given document path.
+    fn id_at(&self, path: &str) -> Option<String> {
+        self.paths
+            .iter()
+            .find(|(_, task_path)| task_path == path)
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Returns the 1-based line where the task with the given ID is declared.
+    pub(crate) fn line_of(&self, id: &str) -> Option<usize> {
+        self.source.lines().enumerate().find_map(|(i, line)| {
+            let line = line.trim_start().trim_start_matches('-').trim_start();
+            let value = line.strip_prefix("id:")?.trim();
+            let value = value.trim_matches(|c| c == '"' || c == '\'');
+            if value == id {
+                Some(i + 1)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Builds a diagnostic located on a task, or on one of its fields.
+    pub(crate) fn diagnostic(
+        &self,
+        severity: Severity,
+        code: &str,
+        message: String,
+        task_id: &str,
+        field: Option<&str>,
+    ) -> Diagnostic {
+        let task_line = self.line_of(task_id);
+        let line = match (field, task_line) {
+            (Some(field), Some(task_line)) => {
+                find_line(self.source, field, task_line).or(Some(task_line))
+            }
+            _ => task_line,
+        };
+
+        let location = self.path_of(task_id).map(|path| Location {
+            path: match field {
+                Some(field) => format!("{}.{}", path, field),
+                None => path.to_string(),
+            },
+            line,
+            column: None,
+        });
+
+        Diagnostic {
+            severity,
+            code: code.to_string(),
+            message,
+            task_id: Some(task_id.to_string()),
+            location,
+        }
+    }
+}
+
+/// Finds the first line at or after `start_line` (1-based) declaring the given key.
+fn find_line(source: &str, key: &str, start_line: usize) -> Option<usize> {
+    let prefix = format!("{}:", key);
+    source
+        .lines()
+        .enumerate()
+        .skip(start_line.saturating_sub(1))
+        .find(|(_, line)| {
+            line.trim_start()
+                .trim_start_matches('-')
+                .trim_start()
+                .starts_with(&prefix)
+        })
+        .map(|(i, _)| i + 1)
+}
+
+/// Extracts the task path (e.g. `epics[0].tasks[1]`) from the segments of an unknown field path.
+fn task_path_of(segments: &[&str]) -> Option<String> {
+    match segments {
+        ["tasks", i, _, ..] => Some(format!("tasks[{}]", i)),
+        ["epics", e, "tasks", i, _, ..] => Some(format!("epics[{}].tasks[{}]", e, i)),
+        _ => None,
+    }
+}
+
+/// Converts a dotted path (`epics.0.tasks.1.foo`) into an indexed path (`epics[0].tasks[1].foo`).
+fn to_index_path(segments: &[&str]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        if segment.chars().all(|c| c.is_ascii_digit()) && !segment.is_empty() {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_collects_all_problems() {
+        let source = "project: test
+tasks:
+  - id: T-1
+    title: First
+    depends: [T-9]
+  - id: T-2
+    title: Second
+    depands: [T-1]
+  - id: T-2
+    title: Duplicate
+";
+
+        let report = validate_source(source, false);
+
+        assert!(!report.valid);
+        let codes: Vec<&str> = report.errors.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, vec!["missing-dependency", "duplicate-id"]);
+
+        let missing = &report.errors[0];
+        let location = missing.location.as_ref().unwrap();
+        assert_eq!(location.path, "tasks[0].depends");
+        assert_eq!(location.line, Some(5));
+
+        assert_eq!(report.warnings.len(), 1);
+        let unknown = report.warnings[0].location.as_ref().unwrap();
+        assert_eq!(unknown.path, "tasks[1].depands");
+        assert_eq!(unknown.line, Some(8));
+
+        let strict = validate_source(source, true);
+        assert_eq!(strict.errors.len(), 3);
+    }
+
+    #[test]
+    fn report_done_with_unfinished_dependencies() {
+        let source = "project: test
+tasks:
+  - id: T-1
+    title: First
+    state: Todo
+  - id: T-2
+    title: Second
+    state: Done
+    depends: [T-1]
+";
+
+        let report = validate_source(source, false);
+
+        assert!(report.valid);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].code, "done-with-unfinished-dependencies");
+        assert_eq!(report.warnings[0].task_id.as_deref(), Some("T-2"));
+    }
+
+    #[test]
+    fn report_parse_error() {
+        let report = validate_source("project: [unclosed", false);
+        assert!(!report.valid);
+        assert_eq!(report.errors[0].code, "parse-error");
+        assert!(report.errors[0].location.as_ref().unwrap().line.is_some());
+    }
+}
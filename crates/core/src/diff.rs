@@ -0,0 +1,292 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Semantic difference between two versions of a backlog, with tasks matched by ID.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BacklogDiff {
+    /// Tasks present only in the new backlog.
+    pub added: Vec<TaskRef>,
+    /// Tasks present only in the old backlog.
+    pub removed: Vec<TaskRef>,
+    /// Tasks present in both backlogs whose content changed.
+    pub modified: Vec<TaskChange>,
+}
+
+impl BacklogDiff {
+    /// Returns true if the two backlogs contain the same tasks with the same content.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Identifies a task in a diff.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRef {
+    /// ID of the task.
+    pub id: String,
+    /// Title of the task.
+    pub title: String,
+    /// ID of the epic containing the task, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic: Option<String>,
+}
+
+/// Describes how a task changed between two backlogs.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskChange {
+    /// ID of the task.
+    pub id: String,
+    /// Title of the task in the new backlog.
+    pub title: String,
+    /// State transition, if the state changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateChange>,
+    /// Dependencies present only in the new version of the task.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_added: Vec<String>,
+    /// Dependencies present only in the old version of the task.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_removed: Vec<String>,
+    /// Epic move, if the task changed epic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic: Option<EpicChange>,
+    /// Other fields whose value changed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldChange>,
+}
+
+/// A task state transition.
+#[derive(Debug, Clone, Serialize)]
+pub struct StateChange {
+    /// State in the old backlog.
+    pub from: TaskState,
+    /// State in the new backlog.
+    pub to: TaskState,
+}
+
+/// A task moving between epics (`None` meaning the standalone task list).
+#[derive(Debug, Clone, Serialize)]
+pub struct EpicChange {
+    /// Epic in the old backlog.
+    pub from: Option<String>,
+    /// Epic in the new backlog.
+    pub to: Option<String>,
+}
+
+/// A change in a generic task field.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    /// Name of the field as it appears in the YAML.
+    pub field: String,
+    /// Old value, or null if the field was not set.
+    pub old: serde_json::Value,
+    /// New value, or null if the field is no longer set.
+    pub new: serde_json::Value,
+}
+
+/// Fields handled explicitly by `TaskChange` rather than as generic field changes.
+const SPECIAL_FIELDS: [&str; 3] = ["id", "state", "depends"];
+
+/// Computes the semantic difference between two backlogs.
+///
+/// Tasks are matched by ID regardless of their position in the file, so reordering or
+/// regenerating a backlog only reports actual content changes.
+pub fn diff_backlogs(old: &Backlog, new: &Backlog) -> BacklogDiff {
+    let old_tasks = index_tasks(old);
+    let new_tasks = index_tasks(new);
+
+    let mut diff = BacklogDiff::default();
+
+    for (task, epic) in &new_tasks {
+        match old_tasks.iter().find(|(t, _)| t.id == task.id) {
+            None => diff.added.push(task_ref(task, epic)),
+            Some((old_task, old_epic)) => {
+                if let Some(change) = diff_task(old_task, old_epic, task, epic) {
+                    diff.modified.push(change);
+                }
+            }
+        }
+    }
+
+    for (task, epic) in &old_tasks {
+        if !new_tasks.iter().any(|(t, _)| t.id == task.id) {
+            diff.removed.push(task_ref(task, epic));
+        }
+    }
+
+    diff
+}
+
+/// Lists all tasks of a backlog along with the ID of their epic, in file order.
+fn index_tasks(backlog: &Backlog) -> Vec<(&Task, Option<String>)> {
+    let mut tasks: Vec<(&Task, Option<String>)> =
+        backlog.tasks.iter().map(|t| (t, None)).collect();
+
+    for epic in &backlog.epics {
+        for task in &epic.tasks {
+            tasks.push((task, Some(epic.id.clone())));
+        }
+    }
+
+    tasks
+}
+
+fn task_ref(task: &Task, epic: &Option<String>) -> TaskRef {
+    TaskRef {
+        id: task.id.clone(),
+        title: task.title.clone(),
+        epic: epic.clone(),
+    }
+}
+
+/// Compares two versions of the same task, returning `None` if nothing changed.
+fn diff_task(
+    old: &Task,
+    old_epic: &Option<String>,
+    new: &Task,
+    new_epic: &Option<String>,
+) -> Option<TaskChange> {
+    let state = if std::mem::discriminant(&old.state) != std::mem::discriminant(&new.state) {
+        Some(StateChange {
+            from: old.state.clone(),
+            to: new.state.clone(),
+        })
+    } else {
+        None
+    };
+
+    let depends_added: Vec<String> = new
+        .depends
+        .iter()
+        .filter(|d| !old.depends.contains(d))
+        .cloned()
+        .collect();
+    let depends_removed: Vec<String> = old
+        .depends
+        .iter()
+        .filter(|d| !new.depends.contains(d))
+        .cloned()
+        .collect();
+
+    let epic = if old_epic != new_epic {
+        Some(EpicChange {
+            from: old_epic.clone(),
+            to: new_epic.clone(),
+        })
+    } else {
+        None
+    };
+
+    let fields = diff_fields(old, new);
+
+    if state.is_none()
+        && depends_added.is_empty()
+        && depends_removed.is_empty()
+        && epic.is_none()
+        && fields.is_empty()
+    {
+        return None;
+    }
+
+    Some(TaskChange {
+        id: new.id.clone(),
+        title: new.title.clone(),
+        state,
+        depends_added,
+        depends_removed,
+        epic,
+        fields,
+    })
+}
+
+/// Compares the serialized fields of two tasks, ignoring the ones handled explicitly.
+fn diff_fields(old: &Task, new: &Task) -> Vec<FieldChange> {
+    let old_fields = to_field_map(old);
+    let new_fields = to_field_map(new);
+
+    let mut names: Vec<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| !SPECIAL_FIELDS.contains(&name.as_str()))
+        .filter_map(|name| {
+            let old_value = old_fields.get(name).cloned().unwrap_or(serde_json::Value::Null);
+            let new_value = new_fields.get(name).cloned().unwrap_or(serde_json::Value::Null);
+            if old_value == new_value {
+                None
+            } else {
+                Some(FieldChange {
+                    field: name.clone(),
+                    old: old_value,
+                    new: new_value,
+                })
+            }
+        })
+        .collect()
+}
+
+fn to_field_map(task: &Task) -> HashMap<String, serde_json::Value> {
+    match serde_json::to_value(task) {
+        Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),
+        _ => HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_by_id() {
+        let old: Backlog = serde_yaml::from_str(
+            r#"
+            project: test
+            tasks:
+              - id: T-1
+                title: "Setup"
+                state: Todo
+              - id: T-2
+                title: "Build"
+                depends: [T-1]
+              - id: T-3
+                title: "Removed"
+            "#,
+        )
+        .unwrap();
+        let new: Backlog = serde_yaml::from_str(
+            r#"
+            project: test
+            tasks:
+              - id: T-2
+                title: "Build it"
+                depends: []
+              - id: T-1
+                title: "Setup"
+                state: Done
+              - id: T-4
+                title: "Added"
+            "#,
+        )
+        .unwrap();
+
+        let diff = diff_backlogs(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id, "T-4");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].id, "T-3");
+        assert_eq!(diff.modified.len(), 2);
+
+        let t2 = diff.modified.iter().find(|c| c.id == "T-2").unwrap();
+        assert_eq!(t2.depends_removed, vec!["T-1"]);
+        assert_eq!(t2.fields.len(), 1);
+        assert_eq!(t2.fields[0].field, "title");
+
+        let t1 = diff.modified.iter().find(|c| c.id == "T-1").unwrap();
+        assert!(matches!(t1.state.as_ref().unwrap().to, TaskState::Done));
+        assert!(t1.fields.is_empty());
+    }
+}
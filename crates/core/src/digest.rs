@@ -0,0 +1,132 @@
+use crate::next::get_ready_tasks;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// A task listed in a digest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DigestTask {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+}
+
+/// Summary of the changes to a backlog over a period, to be mailed to the team.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub project: String,
+    pub since: DateTime<Utc>,
+    pub today: NaiveDate,
+    /// Tasks completed since `since`, oldest first.
+    pub completed: Vec<DigestTask>,
+    /// Tasks that became ready since `since`: created then, or with a dependency completed then.
+    pub newly_ready: Vec<DigestTask>,
+    /// Open tasks due before `today`, most overdue first.
+    pub overdue: Vec<DigestTask>,
+}
+
+impl Digest {
+    /// Tells whether the digest has nothing to report.
+    pub fn is_empty(&self) -> bool {
+        self.completed.is_empty() && self.newly_ready.is_empty() && self.overdue.is_empty()
+    }
+}
+
+/// Builds the digest of the backlog for the period starting at `since`.
+///
+/// Archived tasks count as completed tasks and as completed dependencies.
+pub fn digest(backlog: &Backlog, since: DateTime<Utc>, today: NaiveDate) -> Digest {
+    let tasks: Vec<&Task> = backlog
+        .all_tasks()
+        .into_iter()
+        .chain(&backlog.archive)
+        .collect();
+    let recent = |at: Option<DateTime<Utc>>| at.is_some_and(|at| at >= since);
+
+    let mut completed: Vec<&Task> = tasks
+        .iter()
+        .copied()
+        .filter(|t| matches!(t.state, TaskState::Done) && recent(t.completed_at))
+        .collect();
+    completed.sort_by_key(|t| t.completed_at);
+
+    let newly_ready = get_ready_tasks(backlog)
+        .into_iter()
+        .filter(|task| {
+            recent(task.created_at)
+                || task
+                    .depends
+                    .iter()
+                    .any(|dep| tasks.iter().any(|t| &t.id == dep && recent(t.completed_at)))
+        })
+        .map(digest_task)
+        .collect();
+
+    let mut overdue: Vec<&Task> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| !matches!(t.state, TaskState::Done) && t.due.is_some_and(|due| due < today))
+        .collect();
+    overdue.sort_by_key(|t| t.due);
+
+    Digest {
+        project: backlog.project.clone(),
+        since,
+        today,
+        completed: completed.into_iter().map(digest_task).collect(),
+        newly_ready,
+        overdue: overdue.into_iter().map(digest_task).collect(),
+    }
+}
+
+fn digest_task(task: &Task) -> DigestTask {
+    DigestTask {
+        id: task.id.clone(),
+        title: task.title.clone(),
+        assignee: task.assignee.clone(),
+        due: task.due,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Parser
+            state: Done
+            completed_at: 2026-10-15T16:00:00Z
+          - id: T-2
+            title: Interpreter
+            depends: [T-1]
+            created_at: 2026-09-01T10:00:00Z
+          - id: T-3
+            title: Docs
+            created_at: 2026-09-01T10:00:00Z
+            due: 2026-10-10
+          - id: T-4
+            title: Logo
+            created_at: 2026-10-15T11:00:00Z
+            due: 2026-10-01
+        "#,
+        )
+        .unwrap();
+        let since = "2026-10-15T00:00:00Z".parse().unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        let digest = digest(&backlog, since, today);
+        let ids = |tasks: &[DigestTask]| tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&digest.completed), ["T-1"]);
+        assert_eq!(ids(&digest.newly_ready), ["T-2", "T-4"]);
+        assert_eq!(ids(&digest.overdue), ["T-4", "T-3"]);
+    }
+}
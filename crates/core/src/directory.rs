@@ -0,0 +1,259 @@
+use crate::fmt::canonicalize;
+use crate::ids::split_task_id;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use taskai_schema::{Backlog, Task};
+
+/// Index of a backlog directory, holding the project metadata, the epics and the order of tasks.
+pub const INDEX_FILE: &str = "index.yaml";
+
+/// Subdirectory of a backlog directory holding one Markdown file per task.
+pub const TASKS_DIR: &str = "tasks";
+
+/// Splits a backlog into the files of a backlog directory: the index, relative to the directory,
+/// and a task file per task, relative to `TASKS_DIR`, both in canonical form.
+///
+/// The index is the backlog with each list of tasks (standalone, in epics and archived) replaced
+/// by the IDs of its tasks, so that it records where tasks belong and in what order while the
+/// tasks themselves live in their own files.
+pub fn split_backlog(backlog: &Backlog) -> Result<(String, Vec<(String, String)>), String> {
+    let mut backlog = backlog.clone();
+    canonicalize(&mut backlog);
+
+    let mut files = Vec::new();
+    let mut index = to_mapping(&backlog)?;
+    let mut ids_of = |tasks: &[Task]| -> Result<Value, String> {
+        for task in tasks {
+            files.push((task_file_name(&task.id), format_task_file(task)?));
+        }
+        Ok(Value::Sequence(
+            tasks.iter().map(|t| Value::from(t.id.as_str())).collect(),
+        ))
+    };
+    for key in ["tasks", "archive"] {
+        if index.contains_key(key) {
+            let tasks = if key == "tasks" {
+                &backlog.tasks
+            } else {
+                &backlog.archive
+            };
+            index.insert(key.into(), ids_of(tasks)?);
+        }
+    }
+    if let Some(Value::Sequence(epics)) = index.get_mut("epics") {
+        for (value, epic) in epics.iter_mut().zip(&backlog.epics) {
+            if let Value::Mapping(value) = value {
+                value.insert("tasks".into(), ids_of(&epic.tasks)?);
+            }
+        }
+    }
+
+    let index = serde_yaml::to_string(&index).map_err(|e| e.to_string())?;
+    Ok((index, files))
+}
+
+/// Joins the files of a backlog directory back into the YAML of the whole backlog, from the content
+/// of the index and the name and content of each file of `TASKS_DIR`. Fields are kept as written,
+/// unknown ones included, so that the YAML can be validated like a backlog file.
+///
+/// Tasks found in a file but listed nowhere in the index, such as a task file just added by hand,
+/// are appended to the standalone tasks in the order of their IDs. Returns an error naming the
+/// file at fault if a file cannot be parsed, or if the index lists a task without a file.
+pub fn join_backlog(index: &str, task_files: &[(String, String)]) -> Result<String, String> {
+    let mut index: Mapping = serde_yaml::from_str(index)
+        .map_err(|err| format!("Error parsing {}: {}", INDEX_FILE, err))?;
+
+    let mut tasks: HashMap<String, Value> = HashMap::new();
+    for (name, content) in task_files {
+        let task = parse_task_file(content)
+            .map_err(|err| format!("Error parsing {}/{}: {}", TASKS_DIR, name, err))?;
+        let id = match task.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            _ => name.trim_end_matches(".md").to_string(),
+        };
+        tasks.insert(id, Value::Mapping(task));
+    }
+
+    let mut take = |ids: Option<&Value>| -> Result<Value, String> {
+        let ids = match ids {
+            Some(Value::Sequence(ids)) => ids.clone(),
+            _ => Vec::new(),
+        };
+        let mut listed = Vec::new();
+        for id in ids {
+            let id = id.as_str().unwrap_or_default().to_string();
+            match tasks.remove(&id) {
+                Some(task) => listed.push(task),
+                None => {
+                    return Err(format!(
+                        "{} lists task {}, which has no file {}/{}",
+                        INDEX_FILE,
+                        id,
+                        TASKS_DIR,
+                        task_file_name(&id)
+                    ))
+                }
+            }
+        }
+        Ok(Value::Sequence(listed))
+    };
+    if let Some(Value::Sequence(epics)) = index.get_mut("epics") {
+        for epic in epics.iter_mut() {
+            if let Value::Mapping(epic) = epic {
+                let listed = take(epic.get("tasks"))?;
+                epic.insert("tasks".into(), listed);
+            }
+        }
+    }
+    let archive = take(index.get("archive"))?;
+    let mut standalone = take(index.get("tasks"))?;
+
+    // Tasks without an entry in the index, in the order of their IDs
+    let mut unlisted: Vec<(String, Value)> = tasks.into_iter().collect();
+    unlisted.sort_by(|(a, _), (b, _)| id_order(a).cmp(&id_order(b)));
+    if let Value::Sequence(standalone) = &mut standalone {
+        standalone.extend(unlisted.into_iter().map(|(_, task)| task));
+    }
+    for (key, tasks) in [("tasks", standalone), ("archive", archive)] {
+        match tasks {
+            Value::Sequence(tasks) if tasks.is_empty() => index.remove(key),
+            tasks => index.insert(key.into(), tasks),
+        };
+    }
+    serde_yaml::to_string(&index).map_err(|e| e.to_string())
+}
+
+/// Returns the name of the file of a task within `TASKS_DIR`.
+pub fn task_file_name(id: &str) -> String {
+    format!("{}.md", id)
+}
+
+/// Renders a task as Markdown with YAML frontmatter: the fields of the task in the frontmatter and
+/// its description as the body.
+pub fn format_task_file(task: &Task) -> Result<String, String> {
+    let mut fields = to_mapping(task)?;
+    fields.remove("description");
+    let frontmatter = serde_yaml::to_string(&fields).map_err(|e| e.to_string())?;
+    let mut content = format!("---\n{}---\n", frontmatter);
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            content.push('\n');
+            content.push_str(description);
+            content.push('\n');
+        }
+    }
+    Ok(content)
+}
+
+/// Parses a task file into the fields of the task, its body becoming the description.
+fn parse_task_file(content: &str) -> Result<Mapping, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+        .ok_or("missing YAML frontmatter")?;
+    if let Some(body) = rest.strip_prefix("---") {
+        let body = body.split_once('\n').map_or("", |(_, body)| body);
+        return parse_fields("", body);
+    }
+    let (frontmatter, body) = match rest.find("\n---") {
+        Some(end) => {
+            let body = &rest[end + 4..];
+            let body = body.split_once('\n').map_or("", |(_, body)| body);
+            (&rest[..end + 1], body)
+        }
+        None => return Err("unterminated YAML frontmatter".to_string()),
+    };
+    parse_fields(frontmatter, body)
+}
+
+fn parse_fields(frontmatter: &str, body: &str) -> Result<Mapping, String> {
+    let mut fields: Mapping = if frontmatter.trim().is_empty() {
+        Mapping::new()
+    } else {
+        serde_yaml::from_str(frontmatter).map_err(|e| e.to_string())?
+    };
+    let body = body.trim();
+    if !body.is_empty() {
+        fields.insert("description".into(), body.into());
+    }
+    Ok(fields)
+}
+
+fn to_mapping<T: serde::Serialize>(value: &T) -> Result<Mapping, String> {
+    match serde_yaml::to_value(value).map_err(|e| e.to_string())? {
+        Value::Mapping(mapping) => Ok(mapping),
+        _ => Ok(Mapping::new()),
+    }
+}
+
+/// Orders task IDs by prefix then number, so that `T-2` comes before `T-10`.
+fn id_order(id: &str) -> (&str, u64, &str) {
+    match split_task_id(id) {
+        Some((prefix, number)) => (prefix, number, id),
+        None => (id, 0, id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_join_backlog() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: E-1-1
+                title: Routes
+                description: |
+                  Serve the routes.
+
+                  With a body.
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+            depends: []
+        "#,
+        )
+        .unwrap();
+
+        let (index, files) = split_backlog(&backlog).unwrap();
+        assert_eq!(
+            index,
+            "project: test\nepics:\n- id: E-1\n  title: API\n  tasks:\n  - E-1-1\ntasks:\n- T-1\n"
+        );
+        assert_eq!(files[1].0, "E-1-1.md");
+        assert_eq!(
+            files[1].1,
+            "---\nid: E-1-1\ntitle: Routes\ndepends: []\nstate: Todo\n---\n\nServe the routes.\n\nWith a body.\n"
+        );
+
+        // A task file added by hand joins the standalone tasks
+        let mut files = files;
+        files.push((
+            "T-10.md".to_string(),
+            "---\nid: T-10\ntitle: Later\n---\n".to_string(),
+        ));
+        files.push((
+            "T-2.md".to_string(),
+            "---\nid: T-2\ntitle: Next\n---\nBody\n".to_string(),
+        ));
+        let joined: Backlog = serde_yaml::from_str(&join_backlog(&index, &files).unwrap()).unwrap();
+        assert_eq!(
+            joined.epics[0].tasks[0].description.as_deref(),
+            Some("Serve the routes.\n\nWith a body.")
+        );
+        let ids: Vec<&str> = joined.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["T-1", "T-2", "T-10"]);
+        assert_eq!(joined.tasks[1].description.as_deref(), Some("Body"));
+
+        let error = join_backlog(&index, &files[1..]).unwrap_err();
+        assert!(error.contains("task T-1"));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::config::{DiscordConfig, NotifyEvent};
+use crate::standup::{Standup, StandupTask};
+use serde_json::json;
+use taskai_schema::{Backlog, Task};
+
+/// Maximum length of the content of a Discord message, in characters.
+const MAX_CONTENT: usize = 2000;
+
+/// Client posting messages to Discord channels through webhooks.
+///
+/// The webhook of an event comes from `[discord.webhooks]`, then `webhook` in the configuration,
+/// then `DISCORD_WEBHOOK_URL`.
+pub struct DiscordClient {
+    client: reqwest::Client,
+    config: DiscordConfig,
+    webhook: Option<String>,
+}
+
+impl DiscordClient {
+    /// Creates a client from the configuration, completed by the environment.
+    pub fn new(config: &DiscordConfig) -> Self {
+        DiscordClient {
+            client: crate::web::http_client(),
+            config: config.clone(),
+            webhook: config.webhook.clone().or_else(|| {
+                std::env::var("DISCORD_WEBHOOK_URL")
+                    .ok()
+                    .filter(|v| !v.is_empty())
+            }),
+        }
+    }
+
+    /// Returns the webhook messages about the event are posted to, if it is posted about.
+    pub fn webhook(&self, event: NotifyEvent) -> Option<&str> {
+        if !self.config.posts(event) {
+            return None;
+        }
+        self.config
+            .webhooks
+            .get(&event)
+            .or(self.webhook.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Posts a message about the event, if a webhook is set for it.
+    ///
+    /// Mentions in the message are not notified, so that task titles cannot ping anyone.
+    pub async fn post(&self, event: NotifyEvent, content: &str) -> Result<(), String> {
+        let Some(webhook) = self.webhook(event) else {
+            return Ok(());
+        };
+        let response = self
+            .client
+            .post(webhook)
+            .json(&json!({
+                "content": content,
+                "allowed_mentions": { "parse": [] },
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Discord request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Discord returned status {}: {}",
+                status.as_u16(),
+                body
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Builds the message about tasks that were marked as done or blocked, one line per task. Tasks
+/// not found in the backlog are left out; returns `None` if none is left.
+pub fn discord_task_message(
+    backlog: &Backlog,
+    event: NotifyEvent,
+    task_ids: &[String],
+) -> Option<String> {
+    let lines: Vec<String> = task_ids
+        .iter()
+        .filter_map(|id| backlog.find_task(id))
+        .map(|task| task_line(&backlog.project, task, event))
+        .collect();
+    (!lines.is_empty()).then(|| truncate(lines.join("\n")))
+}
+
+/// Builds the standup message.
+pub fn discord_standup_message(standup: &Standup) -> String {
+    let mut content = format!(
+        "**Standup for {}** since <t:{}:f>",
+        escape(&standup.project),
+        standup.since.timestamp()
+    );
+    let sections = [
+        ("Done", &standup.done),
+        ("In progress", &standup.in_progress),
+        ("Blocked", &standup.blocked),
+    ];
+    for (heading, tasks) in sections {
+        content.push_str(&format!("\n**{}**", heading));
+        if tasks.is_empty() {
+            content.push_str("\n- *nothing*");
+        }
+        for task in tasks {
+            content.push_str(&format!("\n- {}", standup_line(task)));
+        }
+    }
+    truncate(content)
+}
+
+fn task_line(project: &str, task: &Task, event: NotifyEvent) -> String {
+    let name = format!(
+        "[{}] **{}** {}",
+        escape(project),
+        escape(&task.id),
+        escape(&task.title)
+    );
+    match (event, &task.blocker) {
+        (NotifyEvent::Blocked, Some(blocker)) => match &blocker.link {
+            Some(link) => format!(
+                ":no_entry: {} is blocked: {} (<{}>)",
+                name,
+                escape(&blocker.reason),
+                link
+            ),
+            None => format!(
+                ":no_entry: {} is blocked: {}",
+                name,
+                escape(&blocker.reason)
+            ),
+        },
+        (NotifyEvent::Blocked, None) => format!(":no_entry: {} is blocked", name),
+        _ => format!(":white_check_mark: {} is done", name),
+    }
+}
+
+fn standup_line(task: &StandupTask) -> String {
+    let mut line = format!("**{}** {}", escape(&task.id), escape(&task.title));
+    if let Some(assignee) = &task.assignee {
+        line.push_str(&format!(" ({})", escape(assignee)));
+    }
+    if let Some(reason) = &task.reason {
+        line.push_str(&format!(": {}", escape(reason)));
+    }
+    line
+}
+
+/// Escapes the characters Discord reads as markdown within a line of message content.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\*_~`|[]".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Cuts the content to the length Discord accepts, ending it with `…` when cut.
+fn truncate(content: String) -> String {
+    if content.chars().count() <= MAX_CONTENT {
+        return content;
+    }
+    let mut cut: String = content.chars().take(MAX_CONTENT - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discord_messages() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: demo
+        tasks:
+          - id: T-1
+            title: Parse **bold** @everyone
+          - id: T-2
+            title: Docs
+            state: Blocked
+            blocker:
+              reason: Waiting for review
+              link: https://example.com/pr/1
+        "#,
+        )
+        .unwrap();
+        let ids = ["T-1", "T-9"].map(String::from);
+
+        assert_eq!(
+            discord_task_message(&backlog, NotifyEvent::Done, &ids).as_deref(),
+            Some(":white_check_mark: [demo] **T-1** Parse \\*\\*bold\\*\\* @everyone is done")
+        );
+        assert_eq!(
+            discord_task_message(&backlog, NotifyEvent::Blocked, &["T-2".to_string()]).as_deref(),
+            Some(
+                ":no_entry: [demo] **T-2** Docs is blocked: Waiting for review \
+                 (<https://example.com/pr/1>)"
+            )
+        );
+        assert_eq!(discord_task_message(&backlog, NotifyEvent::Done, &[]), None);
+
+        let mut config = DiscordConfig {
+            webhook: Some("https://discord.com/api/webhooks/1/a".to_string()),
+            events: Some(vec![NotifyEvent::Done, NotifyEvent::Standup]),
+            ..Default::default()
+        };
+        config.webhooks.insert(
+            NotifyEvent::Standup,
+            "https://discord.com/api/webhooks/2/b".to_string(),
+        );
+        let client = DiscordClient::new(&config);
+        assert_eq!(
+            client.webhook(NotifyEvent::Done),
+            Some("https://discord.com/api/webhooks/1/a")
+        );
+        assert_eq!(
+            client.webhook(NotifyEvent::Standup),
+            Some("https://discord.com/api/webhooks/2/b")
+        );
+        assert_eq!(client.webhook(NotifyEvent::Blocked), None);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::diagnostics::TaskIndex;
+use taskai_schema::Backlog;
+
+/// Returns the 0-based line where the task with the given ID is declared in the backlog source.
+pub fn task_definition(source: &str, id: &str) -> Option<usize> {
+    TaskIndex::new(&Backlog::default(), source)
+        .line_of(id)
+        .map(|line| line - 1)
+}
+
+/// Returns the task ID under the cursor: the word made of ID characters around the given 0-based
+/// line and column, the column counting UTF-16 code units as editors do.
+pub fn word_at(source: &str, line: usize, column: usize) -> Option<&str> {
+    let text = source.lines().nth(line)?;
+    let cursor = byte_offset(text, column);
+    let is_id_char = |c: char| c.is_alphanumeric() || c == '-' || c == '_' || c == '.';
+    let start = text[..cursor]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_id_char(*c))
+        .last()
+        .map_or(cursor, |(i, _)| i);
+    let end = text[cursor..]
+        .char_indices()
+        .find(|(_, c)| !is_id_char(*c))
+        .map_or(text.len(), |(i, _)| cursor + i);
+    let word = text[start..end].trim_matches('.');
+    (!word.is_empty()).then_some(word)
+}
+
+/// Tells whether the cursor is within the value of a `depends` field, either in a flow sequence
+/// such as `depends: [T-1, ` or in an item of a block sequence under `depends:`.
+pub fn in_depends(source: &str, line: usize, column: usize) -> bool {
+    let lines: Vec<&str> = source.lines().collect();
+    let text = lines.get(line).copied().unwrap_or("");
+    let before = &text[..byte_offset(text, column)];
+    let key = before.trim_start().trim_start_matches('-').trim_start();
+    if key.starts_with("depends:") {
+        return true;
+    }
+    if !before.trim_start().starts_with('-') {
+        return false;
+    }
+    let indent = indentation(text);
+    for previous in lines[..line.min(lines.len())].iter().rev() {
+        let trimmed = previous.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.starts_with('-') && indentation(previous) == indent {
+            continue;
+        }
+        let key = trimmed.trim_start_matches('-').trim_start();
+        return key.trim_end() == "depends:" && indentation(previous) <= indent;
+    }
+    false
+}
+
+/// Renders the details of a task as Markdown, for editor hovers.
+pub fn task_summary(backlog: &Backlog, id: &str) -> Option<String> {
+    let task = backlog.find_task(id)?;
+    let mut summary = format!("**{}** {}\n\n{:?}", task.id, task.title, task.state);
+    if let Some(epic) = backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == id))
+    {
+        summary.push_str(&format!(" · epic {} {}", epic.id, epic.title));
+    }
+    if let Some(assignee) = &task.assignee {
+        summary.push_str(&format!(" · {}", assignee));
+    }
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            summary.push_str(&format!("\n\n{}", description));
+        }
+    }
+    if !task.depends.is_empty() {
+        summary.push_str(&format!("\n\nDepends on: {}", task.depends.join(", ")));
+    }
+    if let Some(deliverable) = &task.deliverable {
+        summary.push_str(&format!(
+            "\n\nDeliverables: {}",
+            deliverable.paths().join(", ")
+        ));
+    }
+    Some(summary)
+}
+
+/// Converts a column counted in UTF-16 code units into a byte offset within the line.
+fn byte_offset(text: &str, column: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= column {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_positions() {
+        let source = "\
+project: test
+tasks:
+  - id: T-1
+    title: Parser
+  - id: T-2
+    title: Interpreter
+    depends: [T-1, ]
+  - id: T-3
+    title: REPL
+    depends:
+      - T-2
+      -
+    deliverable: src/repl.rs
+";
+        assert_eq!(task_definition(source, "T-2"), Some(4));
+        assert_eq!(word_at(source, 6, 16), Some("T-1"));
+        assert_eq!(word_at(source, 6, 19), None);
+
+        assert!(in_depends(source, 6, 19));
+        assert!(in_depends(source, 11, 8));
+        assert!(!in_depends(source, 12, 17));
+        assert!(!in_depends(source, 3, 11));
+
+        let backlog: Backlog = serde_yaml::from_str(&source.replace("      -\n", "")).unwrap();
+        let summary = task_summary(&backlog, "T-3").unwrap();
+        assert!(summary.starts_with("**T-3** REPL\n\nTodo"));
+        assert!(summary.contains("Depends on: T-2"));
+    }
+}
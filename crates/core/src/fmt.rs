@@ -0,0 +1,50 @@
+use taskai_schema::Backlog;
+
+/// Puts the backlog into canonical form: dependency lists are sorted and deduplicated.
+pub fn canonicalize(backlog: &mut Backlog) {
+    for task in backlog
+        .tasks
+        .iter_mut()
+        .chain(backlog.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+    {
+        task.depends.sort();
+        task.depends.dedup();
+    }
+}
+
+/// Renders the backlog as canonical YAML.
+///
+/// Keys follow the schema order, scalars are quoted only when needed and nested lists use a
+/// consistent indentation, so two backlogs with the same content always render the same way.
+pub fn format_backlog(backlog: &Backlog) -> Result<String, String> {
+    let mut backlog = backlog.clone();
+    canonicalize(&mut backlog);
+    serde_yaml::to_string(&backlog).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_output_is_stable() {
+        let messy = r#"
+tasks:
+  - title: 'Second'
+    id: T-2
+    depends: [T-1, T-0, T-1]
+  - {id: T-1, title: "First"}
+  - id: T-0
+    title: Zero
+project: "test"
+"#;
+        let backlog: Backlog = serde_yaml::from_str(messy).unwrap();
+        let formatted = format_backlog(&backlog).unwrap();
+
+        assert!(formatted.starts_with("project: test\n"));
+        assert!(formatted.contains("depends:\n  - T-0\n  - T-1\n"));
+
+        let reparsed: Backlog = serde_yaml::from_str(&formatted).unwrap();
+        assert_eq!(format_backlog(&reparsed).unwrap(), formatted);
+    }
+}
\ No newline at end of file
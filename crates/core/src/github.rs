@@ -0,0 +1,480 @@
+use crate::ids::next_task_id;
+use crate::lines::epic_with_title;
+use crate::rows::ImportSummary;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use taskai_schema::{Backlog, Epic, ExternalRef, Task, TaskState};
+
+/// Number of issues asked for per page when listing the issues of a repository.
+const PAGE_SIZE: usize = 100;
+
+/// An issue of a GitHub repository, as returned by the REST API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitHubIssue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// `open` or `closed`.
+    pub state: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub labels: Vec<GitHubLabel>,
+    #[serde(default)]
+    pub milestone: Option<GitHubMilestone>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Set when the issue is actually a pull request.
+    #[serde(default, skip_serializing)]
+    pull_request: Option<serde_json::Value>,
+}
+
+impl GitHubIssue {
+    /// Tells whether the issue is closed.
+    pub fn is_closed(&self) -> bool {
+        self.state == "closed"
+    }
+}
+
+/// A label set on a GitHub issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitHubLabel {
+    pub name: String,
+}
+
+/// The milestone a GitHub issue belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitHubMilestone {
+    pub number: u64,
+    pub title: String,
+}
+
+/// Content of an issue to create or update.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewIssue {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+/// Client of the GitHub REST API.
+///
+/// Requests are authenticated with `GITHUB_TOKEN` when set, and sent to `GITHUB_API_URL`
+/// (`https://api.github.com` by default, set by GitHub Actions and GitHub Enterprise runners).
+pub struct GitHubClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl GitHubClient {
+    /// Creates a client configured from the environment.
+    pub fn from_env() -> Self {
+        GitHubClient {
+            client: crate::web::http_client(),
+            api_url: std::env::var("GITHUB_API_URL")
+                .unwrap_or_else(|_| "https://api.github.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token: std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which creating or changing issues requires.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Creates an issue in `repo` (`owner/name`).
+    pub async fn create_issue(&self, repo: &str, issue: &NewIssue) -> Result<GitHubIssue, String> {
+        let url = format!("{}/repos/{}/issues", self.api_url, repo);
+        self.send(self.client.post(&url).json(issue)).await
+    }
+
+    /// Replaces the title, body and labels of an issue of `repo`.
+    pub async fn update_issue(
+        &self,
+        repo: &str,
+        number: u64,
+        issue: &NewIssue,
+    ) -> Result<GitHubIssue, String> {
+        let url = format!("{}/repos/{}/issues/{}", self.api_url, repo, number);
+        self.send(self.client.patch(&url).json(issue)).await
+    }
+
+    /// Opens or closes an issue of `repo`.
+    pub async fn set_issue_closed(
+        &self,
+        repo: &str,
+        number: u64,
+        closed: bool,
+    ) -> Result<GitHubIssue, String> {
+        let url = format!("{}/repos/{}/issues/{}", self.api_url, repo, number);
+        let state = if closed { "closed" } else { "open" };
+        let body = serde_json::json!({ "state": state });
+        self.send(self.client.patch(&url).json(&body)).await
+    }
+
+    /// Lists the open and closed issues of `repo`, oldest first, leaving out pull requests.
+    pub async fn list_issues(&self, repo: &str) -> Result<Vec<GitHubIssue>, String> {
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/repos/{}/issues?state=all&sort=created&direction=asc&per_page={}&page={}",
+                self.api_url, repo, PAGE_SIZE, page
+            );
+            let batch: Vec<GitHubIssue> = self.send(self.client.get(&url)).await?;
+            let last_page = batch.len() < PAGE_SIZE;
+            issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+            if last_page {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Runs a GraphQL query or mutation and returns its `data`, failing on any reported error.
+    pub(crate) async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        // GitHub Enterprise serves the REST API under `/api/v3` and GraphQL under `/api/graphql`
+        let base = self.api_url.strip_suffix("/v3").unwrap_or(&self.api_url);
+        let url = format!("{}/graphql", base);
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let mut response: serde_json::Value = self.send(self.client.post(&url).json(&body)).await?;
+        if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            return Err(format!("GitHub GraphQL error: {}", messages.join("; ")));
+        }
+        Ok(response["data"].take())
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let mut request = request
+            .header("User-Agent", "taskai")
+            .header("Accept", "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("GitHub request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body["message"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            return Err(format!(
+                "GitHub API returned status {}: {}",
+                status.as_u16(),
+                message
+            ));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid GitHub response: {}", e))
+    }
+}
+
+/// Tells whether `repo` looks like a GitHub repository name (`owner/name`).
+pub fn is_github_repo(repo: &str) -> bool {
+    crate::refs::parse_github_ref(&format!("{}#1", repo)).is_some()
+}
+
+/// Returns the number of the issue of `repo` the task refers to, if any.
+pub fn github_issue_number(task: &Task, repo: &str) -> Option<u64> {
+    task.external_refs.iter().find_map(|r| github_ref_issue(r, repo))
+}
+
+/// Returns the number of the issue of `repo` a reference points to, if it points to one.
+pub fn github_ref_issue(external_ref: &ExternalRef, repo: &str) -> Option<u64> {
+    if external_ref.system != "github" {
+        return None;
+    }
+    let (owner, name, number) = crate::refs::parse_github_ref(&external_ref.id)?;
+    (format!("{}/{}", owner, name).eq_ignore_ascii_case(repo)).then_some(number)
+}
+
+/// Returns the external reference recording that a task is tracked by an issue of `repo`, the two
+/// being in sync at `synced_at`.
+pub fn github_ref(repo: &str, issue: &GitHubIssue, synced_at: DateTime<Utc>) -> ExternalRef {
+    ExternalRef {
+        system: "github".to_string(),
+        id: format!("{}#{}", repo, issue.number),
+        url: Some(issue.html_url.clone()),
+        synced_at: Some(synced_at),
+    }
+}
+
+/// Records that the task and the issue of `repo` it refers to were in sync at `at`. Returns false
+/// if that was already recorded.
+pub fn mark_github_synced(task: &mut Task, repo: &str, at: DateTime<Utc>) -> bool {
+    let number = github_issue_number(task, repo);
+    let mut changed = false;
+    for external_ref in &mut task.external_refs {
+        let same_issue =
+            crate::refs::parse_github_ref(&external_ref.id).is_some_and(|(owner, name, n)| {
+                format!("{}/{}", owner, name).eq_ignore_ascii_case(repo) && Some(n) == number
+            });
+        if external_ref.system == "github" && same_issue && external_ref.synced_at != Some(at) {
+            external_ref.synced_at = Some(at);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Builds the issue tracking a task: the task title, a body made of its description and `done_when`
+/// criteria as a checklist, and its tags and the title of its epic as labels.
+pub fn issue_for_task(task: &Task, epic: Option<&Epic>) -> NewIssue {
+    let mut body = String::new();
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            body.push_str(description);
+            body.push_str("\n\n");
+        }
+    }
+    if !task.done_when.is_empty() {
+        body.push_str("## Done when\n\n");
+        for criterion in &task.done_when {
+            body.push_str(&format!("- [ ] {}\n", criterion));
+        }
+        body.push('\n');
+    }
+    body.push_str(&format!("_Task `{}` of the taskai backlog._\n", task.id));
+
+    let mut labels = task.tags.clone();
+    if let Some(epic) = epic {
+        if !labels.contains(&epic.title) {
+            labels.push(epic.title.clone());
+        }
+    }
+
+    NewIssue {
+        title: task.title.clone(),
+        body,
+        labels,
+    }
+}
+
+/// Brings the issues of `repo` into the backlog.
+///
+/// Issues already tracked by a task, through a GitHub reference in its `external_refs`, update the
+/// title of the task and its state: closed issues make it Done, and reopened issues set a Done task
+/// back to Todo, while open issues leave other states alone. Issues of archived tasks are left
+/// alone, so that they are not imported again. Other issues become new tasks, with
+/// the body of the issue as description, its labels as tags and the issue as reference; they go
+/// to the epic named after their milestone, created if needed, or are standalone tasks otherwise.
+pub fn import_github_issues(
+    backlog: &mut Backlog,
+    repo: &str,
+    issues: &[GitHubIssue],
+    now: DateTime<Utc>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+
+    for issue in issues {
+        let archived = backlog
+            .archive
+            .iter()
+            .any(|t| github_issue_number(t, repo) == Some(issue.number));
+        if archived {
+            summary.unchanged += 1;
+            continue;
+        }
+        let tracking = backlog
+            .all_tasks()
+            .iter()
+            .find(|t| github_issue_number(t, repo) == Some(issue.number))
+            .map(|t| t.id.clone());
+        if let Some(task_id) = tracking {
+            let Some(task) = backlog.find_task_mut(&task_id) else {
+                continue;
+            };
+            let mut changed = false;
+            if task.title != issue.title {
+                task.title = issue.title.clone();
+                task.updated_at = Some(now);
+                changed = true;
+            }
+            let done = matches!(task.state, TaskState::Done);
+            if issue.is_closed() != done {
+                let state = if issue.is_closed() {
+                    TaskState::Done
+                } else {
+                    TaskState::Todo
+                };
+                task.set_state(state, now);
+                changed = true;
+            }
+            if changed {
+                mark_github_synced(task, repo, now);
+                summary.updated.push(task_id);
+            } else {
+                summary.unchanged += 1;
+            }
+            continue;
+        }
+
+        let epic_id = issue
+            .milestone
+            .as_ref()
+            .map(|m| epic_with_title(backlog, m.title.trim(), &mut summary));
+        let mut task = Task {
+            id: next_task_id(backlog, epic_id.as_deref()),
+            title: issue.title.clone(),
+            description: issue
+                .body
+                .as_deref()
+                .map(str::trim)
+                .filter(|body| !body.is_empty())
+                .map(str::to_string),
+            tags: issue.labels.iter().map(|l| l.name.clone()).collect(),
+            created_at: Some(issue.created_at),
+            updated_at: Some(now),
+            external_refs: vec![github_ref(repo, issue, now)],
+            ..Default::default()
+        };
+        if issue.is_closed() {
+            let closed_at = issue.closed_at.unwrap_or(now);
+            task.set_state(TaskState::Done, closed_at);
+            task.updated_at = Some(now);
+        }
+        summary.added.push(task.id.clone());
+        match epic_id.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::Backlog;
+
+    #[test]
+    fn test_issue_for_task() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: API-1
+                title: Add the users endpoint
+                description: Serve users as JSON.
+                done_when: ["`cargo test` passes", "Documented"]
+                tags: [backend]
+                external_refs:
+                  - system: github
+                    id: acme/app#12
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let (epic, task) = (&backlog.epics[0], &backlog.epics[0].tasks[0]);
+
+        let issue = issue_for_task(task, Some(epic));
+        assert_eq!(issue.title, "Add the users endpoint");
+        assert_eq!(
+            issue.body,
+            "Serve users as JSON.\n\n## Done when\n\n- [ ] `cargo test` passes\n- [ ] Documented\n\n\
+             _Task `API-1` of the taskai backlog._\n"
+        );
+        assert_eq!(issue.labels, ["backend", "API"]);
+
+        assert_eq!(github_issue_number(task, "Acme/App"), Some(12));
+        assert_eq!(github_issue_number(task, "acme/other"), None);
+        assert!(is_github_repo("acme/app") && !is_github_repo("acme"));
+    }
+
+    #[test]
+    fn test_import_github_issues() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: Old title
+            external_refs:
+              - system: github
+                id: acme/app#1
+        "#,
+        )
+        .unwrap();
+        let issues: Vec<GitHubIssue> = serde_json::from_str(
+            r#"[
+            {"number": 1, "title": "Setup", "state": "closed", "html_url": "u1",
+             "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-02T00:00:00Z"},
+            {"number": 2, "title": "Login", "body": "OAuth", "state": "open", "html_url": "u2",
+             "labels": [{"name": "auth"}], "milestone": {"number": 1, "title": "v1"},
+             "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-02T00:00:00Z"},
+            {"number": 3, "title": "Logout", "state": "closed", "html_url": "u3",
+             "milestone": {"number": 1, "title": "v1"}, "closed_at": "2024-01-05T00:00:00Z",
+             "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-05T00:00:00Z"}
+        ]"#,
+        )
+        .unwrap();
+        let now = "2024-02-01T00:00:00Z".parse().unwrap();
+
+        let summary = import_github_issues(&mut backlog, "acme/app", &issues, now);
+        assert_eq!(summary.updated, ["T-1"]);
+        assert_eq!(summary.added, ["T-2", "T-3"]);
+        assert_eq!(summary.new_epics, ["E-1"]);
+        assert_eq!(backlog.tasks[0].title, "Setup");
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+
+        let login = &backlog.epics[0].tasks[0];
+        assert_eq!(backlog.epics[0].title, "v1");
+        assert_eq!(login.description.as_deref(), Some("OAuth"));
+        assert_eq!(login.tags, ["auth"]);
+        assert_eq!(github_issue_number(login, "acme/app"), Some(2));
+        let logout = &backlog.epics[0].tasks[1];
+        assert_eq!(logout.completed_at, issues[2].closed_at);
+
+        let again = import_github_issues(&mut backlog, "acme/app", &issues, now);
+        assert_eq!((again.added.len(), again.unchanged), (0, 3));
+    }
+
+    /// Tests that issues of archived tasks are not imported again.
+    #[test]
+    fn test_import_archived_issue() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        archive:
+          - id: T-1
+            title: Setup
+            state: Done
+            external_refs:
+              - system: github
+                id: acme/app#1
+        "#,
+        )
+        .unwrap();
+        let issues: Vec<GitHubIssue> = serde_json::from_str(
+            r#"[{"number": 1, "title": "Setup", "state": "closed", "html_url": "u1",
+                 "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-02T00:00:00Z"}]"#,
+        )
+        .unwrap();
+        let now = "2024-02-01T00:00:00Z".parse().unwrap();
+
+        let summary = import_github_issues(&mut backlog, "acme/app", &issues, now);
+        assert!(summary.added.is_empty());
+        assert_eq!(summary.unchanged, 1);
+        assert!(backlog.tasks.is_empty());
+    }
+}
@@ -0,0 +1,636 @@
+use crate::config::GitHubConfig;
+use crate::github::{issue_for_task, GitHubClient};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, ExternalRef, Task, TaskState};
+
+/// System name of the references to draft items of a Projects board.
+pub const PROJECT_REF_SYSTEM: &str = "github-project";
+
+/// A GitHub Projects (v2) board: its fields and its items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProjectBoard {
+    /// Node ID of the project.
+    pub id: String,
+    pub title: String,
+    /// Single-select and iteration fields; other fields are left out.
+    pub fields: Vec<ProjectField>,
+    pub items: Vec<ProjectItem>,
+}
+
+/// A single-select or iteration field of a board.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectField {
+    pub id: String,
+    pub name: String,
+    /// True for iteration fields, whose options are the iterations.
+    pub iteration: bool,
+    pub options: Vec<ProjectOption>,
+}
+
+/// An option of a single-select field, or an iteration of an iteration field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectOption {
+    pub id: String,
+    pub name: String,
+}
+
+/// An item of a board.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectItem {
+    pub id: String,
+    /// The issue the item stands for, as `owner/name#number`; unset for draft items.
+    pub issue: Option<String>,
+    /// Option or iteration ID set for each field ID.
+    pub values: BTreeMap<String, String>,
+}
+
+/// How a task gets onto the board.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectTarget {
+    /// The task is already on the board as this item.
+    Item(String),
+    /// The issue of the task (`owner/name#number`) must be added to the board.
+    Issue(String),
+    /// The task has no issue and must be added as a draft item.
+    Draft,
+}
+
+/// A field value to set on the item of a task.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectUpdate {
+    pub field: String,
+    #[serde(skip)]
+    pub field_id: String,
+    pub value: String,
+    #[serde(skip)]
+    pub value_id: String,
+    #[serde(skip)]
+    pub iteration: bool,
+}
+
+/// What keeping a task consistent with the board takes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectSyncItem {
+    pub task_id: String,
+    pub target: ProjectTarget,
+    pub updates: Vec<ProjectUpdate>,
+    /// Values that could not be set, such as an epic missing from the options of the epic field.
+    pub warnings: Vec<String>,
+}
+
+impl ProjectSyncItem {
+    /// Tells whether the task is already on the board with the right values.
+    pub fn is_in_sync(&self) -> bool {
+        matches!(self.target, ProjectTarget::Item(_)) && self.updates.is_empty()
+    }
+}
+
+/// Splits a project given as `owner/number` into its parts.
+pub fn parse_project(project: &str) -> Option<(&str, u64)> {
+    let (owner, number) = project.split_once('/')?;
+    let number = number.parse().ok()?;
+    (!owner.is_empty()).then_some((owner, number))
+}
+
+/// Returns the reference recording that a task is the draft item `item_id` of `project`.
+pub fn project_ref(project: &str, item_id: &str) -> ExternalRef {
+    ExternalRef {
+        system: PROJECT_REF_SYSTEM.to_string(),
+        id: format!("{}#{}", project, item_id),
+        url: None,
+        synced_at: None,
+    }
+}
+
+/// Works out how to make the board reflect the backlog: every task is on the board, in the column
+/// of its state and, when the board has an epic field, with the value named after its epic.
+///
+/// Tasks with a GitHub issue are matched to the item of that issue and added by issue otherwise;
+/// other tasks are matched through their `github-project` reference and added as drafts otherwise.
+/// Columns and the field names come from `config`. Fails if the board has no status field.
+pub fn plan_project_sync(
+    backlog: &Backlog,
+    project: &str,
+    board: &ProjectBoard,
+    config: &GitHubConfig,
+) -> Result<Vec<ProjectSyncItem>, String> {
+    let status_name = config.status_field.as_deref().unwrap_or("Status");
+    let field = |name: &str| {
+        board
+            .fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+    };
+    let status_field = field(status_name).filter(|f| !f.iteration).ok_or_else(|| {
+        format!(
+            "Project '{}' has no single-select field named '{}'.",
+            board.title, status_name
+        )
+    })?;
+    let epic_field = field(config.epic_field.as_deref().unwrap_or("Epic"));
+
+    let tasks = backlog.tasks.iter().map(|t| (t, None)).chain(
+        backlog
+            .epics
+            .iter()
+            .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e.title.as_str())))),
+    );
+    let mut plan = Vec::new();
+    for (task, epic) in tasks {
+        let target = target(task, project, board);
+        let current = match &target {
+            ProjectTarget::Item(id) => board.items.iter().find(|i| &i.id == id),
+            _ => None,
+        };
+        let mut item = ProjectSyncItem {
+            task_id: task.id.clone(),
+            target,
+            updates: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let column = column(config, &task.state);
+        let values = std::iter::once((status_field, column.as_str()))
+            .chain(epic_field.zip(epic))
+            .collect::<Vec<_>>();
+        for (field, value) in values {
+            let Some(option) = field
+                .options
+                .iter()
+                .find(|o| o.name.eq_ignore_ascii_case(value))
+            else {
+                item.warnings
+                    .push(format!("field '{}' has no option '{}'", field.name, value));
+                continue;
+            };
+            if current.and_then(|i| i.values.get(&field.id)) != Some(&option.id) {
+                item.updates.push(ProjectUpdate {
+                    field: field.name.clone(),
+                    field_id: field.id.clone(),
+                    value: option.name.clone(),
+                    value_id: option.id.clone(),
+                    iteration: field.iteration,
+                });
+            }
+        }
+        plan.push(item);
+    }
+    Ok(plan)
+}
+
+/// Finds the item of the board standing for the task.
+fn target(task: &Task, project: &str, board: &ProjectBoard) -> ProjectTarget {
+    let issue = task
+        .external_refs
+        .iter()
+        .find(|r| r.system == "github" && crate::refs::parse_github_ref(&r.id).is_some());
+    if let Some(issue) = issue {
+        return match board.items.iter().find(|i| {
+            i.issue
+                .as_deref()
+                .is_some_and(|id| id.eq_ignore_ascii_case(&issue.id))
+        }) {
+            Some(item) => ProjectTarget::Item(item.id.clone()),
+            None => ProjectTarget::Issue(issue.id.clone()),
+        };
+    }
+
+    task.external_refs
+        .iter()
+        .filter(|r| r.system == PROJECT_REF_SYSTEM)
+        .filter_map(|r| r.id.split_once('#'))
+        .filter(|(p, _)| p.eq_ignore_ascii_case(project))
+        .find(|(_, item_id)| board.items.iter().any(|i| i.id == *item_id))
+        .map(|(_, item_id)| ProjectTarget::Item(item_id.to_string()))
+        .unwrap_or(ProjectTarget::Draft)
+}
+
+/// Returns the column of the board for a task state.
+fn column(config: &GitHubConfig, state: &TaskState) -> String {
+    let (name, default) = match state {
+        TaskState::Todo => ("Todo", "Todo"),
+        TaskState::InProgress => ("InProgress", "In Progress"),
+        TaskState::Blocked => ("Blocked", "In Progress"),
+        TaskState::Done => ("Done", "Done"),
+    };
+    config
+        .columns
+        .get(name)
+        .cloned()
+        .unwrap_or_else(|| default.to_string())
+}
+
+const BOARD_QUERY: &str = r#"
+query($owner: String!, $number: Int!, $after: String) {
+  OWNER(login: $owner) {
+    projectV2(number: $number) {
+      id
+      title
+      fields(first: 50) {
+        nodes {
+          ... on ProjectV2SingleSelectField { id name options { id name } }
+          ... on ProjectV2IterationField {
+            id
+            name
+            configuration { iterations { id title } completedIterations { id title } }
+          }
+        }
+      }
+      items(first: 100, after: $after) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          id
+          content { ... on Issue { number repository { nameWithOwner } } }
+          fieldValues(first: 20) {
+            nodes {
+              ... on ProjectV2ItemFieldSingleSelectValue {
+                optionId
+                field { ... on ProjectV2FieldCommon { id } }
+              }
+              ... on ProjectV2ItemFieldIterationValue {
+                iterationId
+                field { ... on ProjectV2FieldCommon { id } }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BoardData {
+    id: String,
+    title: String,
+    fields: Nodes<FieldData>,
+    items: ItemPage,
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<Option<T>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldData {
+    id: Option<String>,
+    name: Option<String>,
+    options: Option<Vec<ProjectOptionData>>,
+    configuration: Option<IterationConfiguration>,
+}
+
+#[derive(Deserialize)]
+struct ProjectOptionData {
+    id: String,
+    #[serde(alias = "title")]
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IterationConfiguration {
+    iterations: Vec<ProjectOptionData>,
+    completed_iterations: Vec<ProjectOptionData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemPage {
+    page_info: PageInfo,
+    nodes: Vec<Option<ItemData>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemData {
+    id: String,
+    content: Option<IssueContent>,
+    field_values: Nodes<FieldValueData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueContent {
+    number: Option<u64>,
+    repository: Option<RepositoryData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryData {
+    name_with_owner: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldValueData {
+    #[serde(alias = "iterationId")]
+    option_id: Option<String>,
+    field: Option<FieldRef>,
+}
+
+#[derive(Deserialize)]
+struct FieldRef {
+    id: Option<String>,
+}
+
+impl GitHubClient {
+    /// Reads the board of `project` (`owner/number`), owned by an organization or a user.
+    pub async fn project_board(&self, project: &str) -> Result<ProjectBoard, String> {
+        let (owner, number) = parse_project(project)
+            .ok_or_else(|| format!("Invalid project '{}'; expected owner/number.", project))?;
+        match self.project_board_of("organization", owner, number).await {
+            Ok(board) => Ok(board),
+            Err(_) => self.project_board_of("user", owner, number).await,
+        }
+    }
+
+    async fn project_board_of(
+        &self,
+        kind: &str,
+        owner: &str,
+        number: u64,
+    ) -> Result<ProjectBoard, String> {
+        let query = BOARD_QUERY.replace("OWNER", kind);
+        let mut board = ProjectBoard::default();
+        let mut after: Option<String> = None;
+        loop {
+            let data = self
+                .graphql(
+                    &query,
+                    json!({ "owner": owner, "number": number, "after": after }),
+                )
+                .await?;
+            let page: BoardData = serde_json::from_value(data[kind]["projectV2"].clone())
+                .map_err(|_| format!("Project {}/{} not found.", owner, number))?;
+            if after.is_none() {
+                board.id = page.id;
+                board.title = page.title;
+                board.fields = page
+                    .fields
+                    .nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|f| {
+                        let iteration = f.configuration.is_some();
+                        let options = match f.configuration {
+                            Some(c) => c
+                                .iterations
+                                .into_iter()
+                                .chain(c.completed_iterations)
+                                .collect(),
+                            None => f.options?,
+                        };
+                        Some(ProjectField {
+                            id: f.id?,
+                            name: f.name?,
+                            iteration,
+                            options: options
+                                .into_iter()
+                                .map(|o| ProjectOption {
+                                    id: o.id,
+                                    name: o.name,
+                                })
+                                .collect(),
+                        })
+                    })
+                    .collect();
+            }
+            for item in page.items.nodes.into_iter().flatten() {
+                let issue = item
+                    .content
+                    .and_then(|c| Some(format!("{}#{}", c.repository?.name_with_owner, c.number?)));
+                let values = item
+                    .field_values
+                    .nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| Some((v.field?.id?, v.option_id?)))
+                    .collect();
+                board.items.push(ProjectItem {
+                    id: item.id,
+                    issue,
+                    values,
+                });
+            }
+            match page.items.page_info {
+                PageInfo {
+                    has_next_page: true,
+                    end_cursor: Some(cursor),
+                } => after = Some(cursor),
+                _ => return Ok(board),
+            }
+        }
+    }
+
+    /// Adds the issue `owner/name#number` to the board and returns the ID of its item.
+    pub async fn add_project_issue(&self, project_id: &str, issue: &str) -> Result<String, String> {
+        let (owner, name, number) = crate::refs::parse_github_ref(issue)
+            .ok_or_else(|| format!("Invalid GitHub issue reference '{}'.", issue))?;
+        let data = self
+            .graphql(
+                "query($owner: String!, $name: String!, $number: Int!) {
+                  repository(owner: $owner, name: $name) { issue(number: $number) { id } }
+                }",
+                json!({ "owner": owner, "name": name, "number": number }),
+            )
+            .await?;
+        let content_id = data["repository"]["issue"]["id"]
+            .as_str()
+            .ok_or_else(|| format!("Issue {} not found.", issue))?;
+        let data = self
+            .graphql(
+                "mutation($project: ID!, $content: ID!) {
+                  addProjectV2ItemById(input: {projectId: $project, contentId: $content}) {
+                    item { id }
+                  }
+                }",
+                json!({ "project": project_id, "content": content_id }),
+            )
+            .await?;
+        item_id(&data["addProjectV2ItemById"]["item"])
+    }
+
+    /// Adds a draft item for the task to the board and returns its ID.
+    pub async fn add_project_draft(&self, project_id: &str, task: &Task) -> Result<String, String> {
+        let issue = issue_for_task(task, None);
+        let data = self
+            .graphql(
+                "mutation($project: ID!, $title: String!, $body: String) {
+                  addProjectV2DraftIssue(input: {projectId: $project, title: $title, body: $body}) {
+                    projectItem { id }
+                  }
+                }",
+                json!({ "project": project_id, "title": issue.title, "body": issue.body }),
+            )
+            .await?;
+        item_id(&data["addProjectV2DraftIssue"]["projectItem"])
+    }
+
+    /// Sets a single-select or iteration field of an item of the board.
+    pub async fn set_project_field(
+        &self,
+        project_id: &str,
+        item_id: &str,
+        update: &ProjectUpdate,
+    ) -> Result<(), String> {
+        let value = if update.iteration {
+            json!({ "iterationId": update.value_id })
+        } else {
+            json!({ "singleSelectOptionId": update.value_id })
+        };
+        self.graphql(
+            "mutation($project: ID!, $item: ID!, $field: ID!, $value: ProjectV2FieldValue!) {
+              updateProjectV2ItemFieldValue(
+                input: {projectId: $project, itemId: $item, fieldId: $field, value: $value}
+              ) { projectV2Item { id } }
+            }",
+            json!({
+                "project": project_id,
+                "item": item_id,
+                "field": update.field_id,
+                "value": value,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+fn item_id(item: &serde_json::Value) -> Result<String, String> {
+    item["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "GitHub did not return the ID of the new item.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_project_sync() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: On the board
+            state: Done
+            external_refs:
+              - {system: github, id: acme/app#1}
+          - id: T-2
+            title: Issue not on the board
+            external_refs:
+              - {system: github, id: acme/app#2}
+          - id: T-3
+            title: Draft
+            state: Blocked
+            external_refs:
+              - {system: github-project, id: "acme/5#PVTI_3"}
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: API-1
+                title: New
+        "#,
+        )
+        .unwrap();
+        let option = |id: &str, name: &str| ProjectOption {
+            id: id.to_string(),
+            name: name.to_string(),
+        };
+        let board = ProjectBoard {
+            id: "PVT_1".to_string(),
+            title: "Roadmap".to_string(),
+            fields: vec![
+                ProjectField {
+                    id: "F_STATUS".to_string(),
+                    name: "Status".to_string(),
+                    iteration: false,
+                    options: vec![
+                        option("S_TODO", "Todo"),
+                        option("S_HOLD", "On hold"),
+                        option("S_DONE", "Done"),
+                    ],
+                },
+                ProjectField {
+                    id: "F_EPIC".to_string(),
+                    name: "Epic".to_string(),
+                    iteration: true,
+                    options: vec![option("I_API", "API")],
+                },
+            ],
+            items: vec![
+                ProjectItem {
+                    id: "PVTI_1".to_string(),
+                    issue: Some("acme/app#1".to_string()),
+                    values: BTreeMap::from([("F_STATUS".to_string(), "S_DONE".to_string())]),
+                },
+                ProjectItem {
+                    id: "PVTI_3".to_string(),
+                    issue: None,
+                    values: BTreeMap::new(),
+                },
+            ],
+        };
+        let config = GitHubConfig {
+            columns: BTreeMap::from([("Blocked".to_string(), "On hold".to_string())]),
+            ..Default::default()
+        };
+
+        let plan = plan_project_sync(&backlog, "acme/5", &board, &config).unwrap();
+        let summary: Vec<(&ProjectTarget, Vec<&str>)> = plan
+            .iter()
+            .map(|i| {
+                (
+                    &i.target,
+                    i.updates.iter().map(|u| u.value.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (&ProjectTarget::Item("PVTI_1".to_string()), vec![]),
+                (
+                    &ProjectTarget::Issue("acme/app#2".to_string()),
+                    vec!["Todo"]
+                ),
+                (&ProjectTarget::Item("PVTI_3".to_string()), vec!["On hold"]),
+                (&ProjectTarget::Draft, vec!["Todo", "API"]),
+            ]
+        );
+        assert!(plan[0].is_in_sync());
+        assert!(plan.iter().all(|i| i.warnings.is_empty()));
+
+        let config = GitHubConfig::default();
+        let plan = plan_project_sync(&backlog, "acme/5", &board, &config).unwrap();
+        assert_eq!(
+            plan[2].warnings,
+            ["field 'Status' has no option 'In Progress'"]
+        );
+
+        let config = GitHubConfig {
+            status_field: Some("Column".to_string()),
+            ..Default::default()
+        };
+        assert!(plan_project_sync(&backlog, "acme/5", &board, &config).is_err());
+        assert_eq!(parse_project("acme/5"), Some(("acme", 5)));
+    }
+}
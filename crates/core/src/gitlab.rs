@@ -0,0 +1,701 @@
+use crate::github::issue_for_task;
+use crate::ids::{next_epic_id, next_task_id};
+use crate::rows::ImportSummary;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use taskai_schema::{Backlog, Epic, ExternalRef, Task, TaskState};
+
+/// Number of items asked for per page when listing the issues or milestones of a project.
+const PAGE_SIZE: usize = 100;
+
+/// An issue of a GitLab project, as returned by the REST API (version 4).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitLabIssue {
+    /// ID of the issue across the instance.
+    pub id: u64,
+    /// ID of the issue within its project, as shown in `#123`.
+    pub iid: u64,
+    pub project_id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `opened` or `closed`.
+    pub state: String,
+    pub web_url: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub milestone: Option<GitLabMilestone>,
+    /// Epic of the issue, on GitLab Premium.
+    #[serde(default)]
+    pub epic: Option<GitLabEpic>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl GitLabIssue {
+    /// Tells whether the issue is closed.
+    pub fn is_closed(&self) -> bool {
+        self.state == "closed"
+    }
+}
+
+/// A milestone of a GitLab project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitLabMilestone {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub web_url: Option<String>,
+}
+
+/// An epic of a GitLab group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitLabEpic {
+    /// ID of the epic within its group, as shown in `&12`.
+    pub iid: u64,
+    pub title: String,
+    #[serde(default, alias = "url")]
+    pub web_url: Option<String>,
+}
+
+/// An issue linked to another one, as listed by the issue links API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitLabIssueLink {
+    pub iid: u64,
+    pub project_id: u64,
+    /// `relates_to`, `blocks` or `is_blocked_by`, from the point of view of the listed issue.
+    pub link_type: String,
+}
+
+/// Content of an issue to create or update.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewGitLabIssue {
+    pub title: String,
+    pub description: String,
+    /// Labels, separated by commas.
+    pub labels: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub milestone_id: Option<u64>,
+}
+
+/// Client of the GitLab REST API (version 4).
+///
+/// Requests are sent to the instance given by the configuration or `GITLAB_URL`
+/// (`https://gitlab.com` by default), and authenticated with `GITLAB_TOKEN` when set.
+pub struct GitLabClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl GitLabClient {
+    /// Creates a client for the instance at `base_url`, or `GITLAB_URL` when not given.
+    pub fn from_env(base_url: Option<&str>) -> Self {
+        let base_url = match base_url {
+            Some(url) => url.to_string(),
+            None => std::env::var("GITLAB_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| "https://gitlab.com".to_string()),
+        };
+        GitLabClient {
+            client: crate::web::http_client(),
+            api_url: format!("{}/api/v4", base_url.trim_end_matches('/')),
+            token: std::env::var("GITLAB_TOKEN").ok().filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which creating or changing issues requires.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Creates an issue in `project` (`group/name`).
+    pub async fn create_issue(
+        &self,
+        project: &str,
+        issue: &NewGitLabIssue,
+    ) -> Result<GitLabIssue, String> {
+        let url = format!("{}/projects/{}/issues", self.api_url, encode(project));
+        self.send(self.client.post(&url).json(issue)).await
+    }
+
+    /// Replaces the title, description, labels and milestone of an issue of `project`.
+    pub async fn update_issue(
+        &self,
+        project: &str,
+        iid: u64,
+        issue: &NewGitLabIssue,
+    ) -> Result<GitLabIssue, String> {
+        let url = format!(
+            "{}/projects/{}/issues/{}",
+            self.api_url,
+            encode(project),
+            iid
+        );
+        self.send(self.client.put(&url).json(issue)).await
+    }
+
+    /// Closes or reopens an issue of `project`.
+    pub async fn set_issue_closed(
+        &self,
+        project: &str,
+        iid: u64,
+        closed: bool,
+    ) -> Result<GitLabIssue, String> {
+        let url = format!(
+            "{}/projects/{}/issues/{}",
+            self.api_url,
+            encode(project),
+            iid
+        );
+        let state_event = if closed { "close" } else { "reopen" };
+        let body = json!({ "state_event": state_event });
+        self.send(self.client.put(&url).json(&body)).await
+    }
+
+    /// Lists the open and closed issues of `project`, oldest first.
+    pub async fn list_issues(&self, project: &str) -> Result<Vec<GitLabIssue>, String> {
+        let url = format!("{}/projects/{}/issues", self.api_url, encode(project));
+        let query = [
+            ("state", "all"),
+            ("scope", "all"),
+            ("order_by", "created_at"),
+            ("sort", "asc"),
+        ];
+        self.list(&url, &query).await
+    }
+
+    /// Lists the issues linked to an issue of `project`.
+    pub async fn list_issue_links(
+        &self,
+        project: &str,
+        iid: u64,
+    ) -> Result<Vec<GitLabIssueLink>, String> {
+        let url = format!(
+            "{}/projects/{}/issues/{}/links",
+            self.api_url,
+            encode(project),
+            iid
+        );
+        self.send(self.client.get(&url)).await
+    }
+
+    /// Links two issues so that `blocker` blocks `blocked`, both issues of `project`. Blocking
+    /// links require GitLab Premium.
+    pub async fn link_issues(
+        &self,
+        project: &str,
+        blocker: u64,
+        blocked: u64,
+    ) -> Result<(), String> {
+        let url = format!(
+            "{}/projects/{}/issues/{}/links",
+            self.api_url,
+            encode(project),
+            blocker
+        );
+        let body = json!({
+            "target_project_id": project,
+            "target_issue_iid": blocked,
+            "link_type": "blocks",
+        });
+        self.send::<Value>(self.client.post(&url).json(&body))
+            .await
+            .map(|_| ())
+    }
+
+    /// Lists the milestones of `project`.
+    pub async fn list_milestones(&self, project: &str) -> Result<Vec<GitLabMilestone>, String> {
+        let url = format!("{}/projects/{}/milestones", self.api_url, encode(project));
+        self.list(&url, &[]).await
+    }
+
+    /// Creates a milestone in `project`.
+    pub async fn create_milestone(
+        &self,
+        project: &str,
+        title: &str,
+    ) -> Result<GitLabMilestone, String> {
+        let url = format!("{}/projects/{}/milestones", self.api_url, encode(project));
+        let body = json!({ "title": title });
+        self.send(self.client.post(&url).json(&body)).await
+    }
+
+    /// Creates an epic in `group`.
+    pub async fn create_epic(&self, group: &str, title: &str) -> Result<GitLabEpic, String> {
+        let url = format!("{}/groups/{}/epics", self.api_url, encode(group));
+        let body = json!({ "title": title });
+        self.send(self.client.post(&url).json(&body)).await
+    }
+
+    /// Adds an issue, given by its ID across the instance, to an epic of `group`.
+    pub async fn add_epic_issue(
+        &self,
+        group: &str,
+        epic_iid: u64,
+        issue_id: u64,
+    ) -> Result<(), String> {
+        let url = format!(
+            "{}/groups/{}/epics/{}/issues/{}",
+            self.api_url,
+            encode(group),
+            epic_iid,
+            issue_id
+        );
+        self.send::<Value>(self.client.post(&url)).await.map(|_| ())
+    }
+
+    /// Fetches every page of a list endpoint.
+    async fn list<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let per_page = PAGE_SIZE.to_string();
+            let request = self
+                .client
+                .get(url)
+                .query(query)
+                .query(&[("per_page", per_page.as_str()), ("page", page.as_str())]);
+            let batch: Vec<T> = self.send(request).await?;
+            let last_page = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let mut request = request.header("User-Agent", "taskai");
+        if let Some(token) = &self.token {
+            request = request.header("PRIVATE-TOKEN", token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("GitLab request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.json::<Value>().await.unwrap_or_default();
+            // Validation errors come as an object of messages by field
+            let message = match body.get("message").or_else(|| body.get("error")) {
+                Some(Value::String(message)) => message.clone(),
+                Some(message) => message.to_string(),
+                None => String::new(),
+            };
+            return Err(format!(
+                "GitLab API returned status {}: {}",
+                status.as_u16(),
+                message
+            ));
+        }
+        // Adding an issue to an epic answers with an empty body on some versions
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Invalid GitLab response: {}", e))?;
+        let body = if body.trim().is_empty() {
+            "null"
+        } else {
+            &body
+        };
+        serde_json::from_str(body).map_err(|e| format!("Invalid GitLab response: {}", e))
+    }
+}
+
+/// Encodes the path of a project or a group for use in API URLs.
+fn encode(path: &str) -> String {
+    path.replace('/', "%2F")
+}
+
+/// Tells whether `project` looks like the path of a GitLab project (`group/name`, possibly within
+/// subgroups).
+pub fn is_gitlab_project(project: &str) -> bool {
+    crate::refs::parse_gitlab_ref(&format!("{}#1", project)).is_some()
+}
+
+/// Returns the IID of the issue of `project` the task refers to, if any.
+pub fn gitlab_issue_iid(task: &Task, project: &str) -> Option<u64> {
+    task.external_refs.iter().find_map(|r| gitlab_ref_issue(r, project))
+}
+
+/// Returns the IID of the issue of `project` a reference points to, if it points to one.
+pub fn gitlab_ref_issue(external_ref: &ExternalRef, project: &str) -> Option<u64> {
+    if external_ref.system != "gitlab" {
+        return None;
+    }
+    let (path, iid) = crate::refs::parse_gitlab_ref(&external_ref.id)?;
+    path.eq_ignore_ascii_case(project).then_some(iid)
+}
+
+/// Returns the external reference recording that a task is tracked by an issue of `project`, the
+/// two being in sync at `synced_at`.
+pub fn gitlab_ref(project: &str, issue: &GitLabIssue, synced_at: DateTime<Utc>) -> ExternalRef {
+    ExternalRef {
+        system: "gitlab".to_string(),
+        id: format!("{}#{}", project, issue.iid),
+        url: Some(issue.web_url.clone()),
+        synced_at: Some(synced_at),
+    }
+}
+
+/// Returns the external reference recording that an epic is tracked by a milestone of `project`,
+/// written `group/name%ID` after the GitLab reference syntax.
+pub fn gitlab_milestone_ref(project: &str, milestone: &GitLabMilestone) -> ExternalRef {
+    ExternalRef {
+        system: "gitlab".to_string(),
+        id: format!("{}%{}", project, milestone.id),
+        url: milestone.web_url.clone(),
+        synced_at: None,
+    }
+}
+
+/// Returns the external reference recording that an epic is tracked by an epic of `group`,
+/// written `group&IID` after the GitLab reference syntax.
+pub fn gitlab_epic_ref(group: &str, epic: &GitLabEpic) -> ExternalRef {
+    ExternalRef {
+        system: "gitlab".to_string(),
+        id: format!("{}&{}", group, epic.iid),
+        url: epic.web_url.clone(),
+        synced_at: None,
+    }
+}
+
+/// Returns the ID of the milestone of `project` tracking an epic, if any.
+pub fn gitlab_milestone_id(epic: &Epic, project: &str) -> Option<u64> {
+    gitlab_epic_ref_number(epic, project, '%')
+}
+
+/// Returns the IID of the epic of `group` tracking an epic, if any.
+pub fn gitlab_epic_iid(epic: &Epic, group: &str) -> Option<u64> {
+    gitlab_epic_ref_number(epic, group, '&')
+}
+
+fn gitlab_epic_ref_number(epic: &Epic, path: &str, separator: char) -> Option<u64> {
+    epic.external_refs
+        .iter()
+        .filter(|r| r.system == "gitlab")
+        .find_map(|r| {
+            let (ref_path, number) = r.id.rsplit_once(separator)?;
+            ref_path
+                .eq_ignore_ascii_case(path)
+                .then(|| number.parse().ok())
+                .flatten()
+        })
+}
+
+/// Records that the task and the issue of `project` it refers to were in sync at `at`. Returns
+/// false if that was already recorded.
+pub fn mark_gitlab_synced(task: &mut Task, project: &str, at: DateTime<Utc>) -> bool {
+    let iid = gitlab_issue_iid(task, project);
+    let mut changed = false;
+    for external_ref in &mut task.external_refs {
+        let same_issue = crate::refs::parse_gitlab_ref(&external_ref.id)
+            .is_some_and(|(path, n)| path.eq_ignore_ascii_case(project) && Some(n) == iid);
+        if external_ref.system == "gitlab" && same_issue && external_ref.synced_at != Some(at) {
+            external_ref.synced_at = Some(at);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Builds the issue tracking a task: the task title, a description made of its description and
+/// `done_when` criteria as a checklist, its tags as labels, and the milestone of its epic.
+pub fn gitlab_issue_for_task(task: &Task, milestone_id: Option<u64>) -> NewGitLabIssue {
+    let issue = issue_for_task(task, None);
+    NewGitLabIssue {
+        title: issue.title,
+        description: issue.body,
+        // Commas separate the labels of the request
+        labels: task
+            .tags
+            .iter()
+            .map(|tag| tag.replace(',', " "))
+            .collect::<Vec<_>>()
+            .join(","),
+        milestone_id,
+    }
+}
+
+/// Brings the issues of `project` into the backlog.
+///
+/// Issues already tracked by a task update its title and state, like `import_github_issues`.
+/// Other issues become new tasks, with the description of the issue, its labels as tags and the
+/// issue as reference. They go to the epic tracking their GitLab epic, or else their milestone,
+/// created with a reference to it if needed; issues with neither are standalone tasks. Finally,
+/// `links` (the links of each issue, by IID) make the tasks of blocked issues depend on the tasks
+/// of the blocking issues of the project. `group` is the group of the GitLab epics.
+pub fn import_gitlab_issues(
+    backlog: &mut Backlog,
+    project: &str,
+    group: &str,
+    issues: &[GitLabIssue],
+    links: &[(u64, Vec<GitLabIssueLink>)],
+    now: DateTime<Utc>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+
+    for issue in issues {
+        let tracking = backlog
+            .all_tasks()
+            .iter()
+            .find(|t| gitlab_issue_iid(t, project) == Some(issue.iid))
+            .map(|t| t.id.clone());
+        if let Some(task_id) = tracking {
+            let Some(task) = backlog.find_task_mut(&task_id) else {
+                continue;
+            };
+            let mut changed = false;
+            if task.title != issue.title {
+                task.title = issue.title.clone();
+                task.updated_at = Some(now);
+                changed = true;
+            }
+            if issue.is_closed() != matches!(task.state, TaskState::Done) {
+                let state = if issue.is_closed() {
+                    TaskState::Done
+                } else {
+                    TaskState::Todo
+                };
+                task.set_state(state, now);
+                changed = true;
+            }
+            if changed {
+                mark_gitlab_synced(task, project, now);
+                summary.updated.push(task_id);
+            } else {
+                summary.unchanged += 1;
+            }
+            continue;
+        }
+
+        let epic_id = match (&issue.epic, &issue.milestone) {
+            (Some(epic), _) => Some(tracking_epic(
+                backlog,
+                &epic.title,
+                gitlab_epic_ref(group, epic),
+                &mut summary,
+            )),
+            (None, Some(milestone)) => Some(tracking_epic(
+                backlog,
+                &milestone.title,
+                gitlab_milestone_ref(project, milestone),
+                &mut summary,
+            )),
+            (None, None) => None,
+        };
+        let mut task = Task {
+            id: next_task_id(backlog, epic_id.as_deref()),
+            title: issue.title.clone(),
+            description: issue
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            tags: issue.labels.clone(),
+            created_at: Some(issue.created_at),
+            updated_at: Some(now),
+            external_refs: vec![gitlab_ref(project, issue, now)],
+            ..Default::default()
+        };
+        if issue.is_closed() {
+            task.set_state(TaskState::Done, issue.closed_at.unwrap_or(now));
+            task.updated_at = Some(now);
+        }
+        summary.added.push(task.id.clone());
+        match epic_id.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+    }
+
+    let project_id = issues.first().map(|i| i.project_id);
+    let task_of = |backlog: &Backlog, iid: u64| {
+        backlog
+            .all_tasks()
+            .iter()
+            .find(|t| gitlab_issue_iid(t, project) == Some(iid))
+            .map(|t| t.id.clone())
+    };
+    for (iid, issue_links) in links {
+        let Some(task_id) = task_of(backlog, *iid) else {
+            continue;
+        };
+        let blockers: Vec<String> = issue_links
+            .iter()
+            .filter(|l| l.link_type == "is_blocked_by" && Some(l.project_id) == project_id)
+            .filter_map(|l| task_of(backlog, l.iid))
+            .filter(|id| id != &task_id)
+            .collect();
+        let Some(task) = backlog.find_task_mut(&task_id) else {
+            continue;
+        };
+        let mut changed = false;
+        for blocker in blockers {
+            if !task.depends.contains(&blocker) {
+                task.depends.push(blocker);
+                changed = true;
+            }
+        }
+        if changed && !summary.added.contains(&task_id) && !summary.updated.contains(&task_id) {
+            task.updated_at = Some(now);
+            summary.updated.push(task_id);
+        }
+    }
+    summary
+}
+
+/// Returns the ID of the epic tracked by `external_ref`, or else titled `title`, which then gets
+/// the reference; the epic is created if there is none.
+pub(crate) fn tracking_epic(
+    backlog: &mut Backlog,
+    title: &str,
+    external_ref: ExternalRef,
+    summary: &mut ImportSummary,
+) -> String {
+    let title = title.trim();
+    if let Some(epic) = backlog.epics.iter().find(|e| {
+        e.external_refs
+            .iter()
+            .any(|r| r.system == external_ref.system && r.id == external_ref.id)
+    }) {
+        return epic.id.clone();
+    }
+    if let Some(epic) = backlog
+        .epics
+        .iter_mut()
+        .find(|e| e.title.eq_ignore_ascii_case(title))
+    {
+        epic.external_refs.push(external_ref);
+        return epic.id.clone();
+    }
+    let id = next_epic_id(backlog);
+    backlog.epics.push(Epic {
+        id: id.clone(),
+        title: title.to_string(),
+        tasks: Vec::new(),
+        external_refs: vec![external_ref],
+    });
+    summary.new_epics.push(id.clone());
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitlab_issue_for_task() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        epics:
+          - id: E-1
+            title: API
+            external_refs:
+              - {system: gitlab, id: "acme/app%7"}
+            tasks:
+              - id: API-1
+                title: Add the users endpoint
+                description: Serve users as JSON.
+                tags: [backend, "a,b"]
+                external_refs:
+                  - {system: gitlab, id: "acme/app#12"}
+        "#,
+        )
+        .unwrap();
+        let (epic, task) = (&backlog.epics[0], &backlog.epics[0].tasks[0]);
+
+        assert_eq!(gitlab_milestone_id(epic, "acme/app"), Some(7));
+        assert_eq!(gitlab_epic_iid(epic, "acme"), None);
+        let issue = gitlab_issue_for_task(task, Some(7));
+        assert_eq!(issue.labels, "backend,a b");
+        assert_eq!(
+            issue.description,
+            "Serve users as JSON.\n\n_Task `API-1` of the taskai backlog._\n"
+        );
+        assert_eq!(gitlab_issue_iid(task, "Acme/App"), Some(12));
+        assert!(is_gitlab_project("acme/tools/app") && !is_gitlab_project("app"));
+    }
+
+    #[test]
+    fn test_import_gitlab_issues() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        epics:
+          - id: E-1
+            title: v1
+        tasks:
+          - id: T-1
+            title: Old title
+            external_refs:
+              - {system: gitlab, id: "acme/app#1"}
+        "#,
+        )
+        .unwrap();
+        let issues: Vec<GitLabIssue> = serde_json::from_str(
+            r#"[
+            {"id": 101, "iid": 1, "project_id": 9, "title": "Setup", "state": "closed",
+             "web_url": "u1", "created_at": "2024-01-01T00:00:00Z",
+             "updated_at": "2024-01-02T00:00:00Z"},
+            {"id": 102, "iid": 2, "project_id": 9, "title": "Login", "description": "OAuth",
+             "state": "opened", "web_url": "u2", "labels": ["auth"],
+             "milestone": {"id": 5, "title": "V1"},
+             "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-02T00:00:00Z"},
+            {"id": 103, "iid": 3, "project_id": 9, "title": "Billing", "state": "opened",
+             "web_url": "u3", "epic": {"id": 40, "iid": 4, "title": "Payments"},
+             "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-02T00:00:00Z"}
+        ]"#,
+        )
+        .unwrap();
+        let links = vec![(
+            2,
+            vec![
+                GitLabIssueLink {
+                    iid: 1,
+                    project_id: 9,
+                    link_type: "is_blocked_by".to_string(),
+                },
+                GitLabIssueLink {
+                    iid: 3,
+                    project_id: 9,
+                    link_type: "relates_to".to_string(),
+                },
+            ],
+        )];
+        let now = "2024-02-01T00:00:00Z".parse().unwrap();
+
+        let summary = import_gitlab_issues(&mut backlog, "acme/app", "acme", &issues, &links, now);
+        assert_eq!(summary.updated, ["T-1"]);
+        assert_eq!(summary.added, ["T-2", "T-3"]);
+        assert_eq!(summary.new_epics, ["E-2"]);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+
+        let v1 = &backlog.epics[0];
+        assert_eq!(gitlab_milestone_id(v1, "acme/app"), Some(5));
+        assert_eq!(v1.tasks[0].depends, ["T-1"]);
+        assert_eq!(v1.tasks[0].tags, ["auth"]);
+        assert_eq!(gitlab_epic_iid(&backlog.epics[1], "acme"), Some(4));
+
+        let again = import_gitlab_issues(&mut backlog, "acme/app", "acme", &issues, &links, now);
+        assert_eq!((again.added.len(), again.unchanged), (0, 3));
+        assert!(again.updated.is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Maximum length of a content line, in bytes, before it is folded.
+const LINE_LENGTH: usize = 75;
+
+/// Formats the backlog as an iCalendar file: a VTODO for each task with a due date, and an all-day
+/// VEVENT for each milestone on its target date.
+///
+/// Milestone targets come from `targets`; milestones without one fall on the latest due date of
+/// their tasks, and are left out if none of their tasks has one. UIDs are derived from the project
+/// and the task IDs, so calendar apps update entries when the file is imported again. `now` stamps
+/// the entries.
+pub fn format_ics(
+    backlog: &Backlog,
+    targets: &BTreeMap<String, NaiveDate>,
+    now: DateTime<Utc>,
+) -> String {
+    let domain = uid_domain(&backlog.project);
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let tasks = backlog.all_tasks();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//taskai//taskai//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(&backlog.project)),
+    ];
+
+    for task in tasks.iter().filter(|t| t.due.is_some()) {
+        lines.extend(todo(task, &domain, &stamp));
+    }
+
+    let mut milestones: Vec<&str> = Vec::new();
+    for task in &tasks {
+        if let Some(milestone) = task.milestone.as_deref() {
+            if !milestones.contains(&milestone) {
+                milestones.push(milestone);
+            }
+        }
+    }
+    for milestone in milestones {
+        let of_milestone: Vec<&&Task> = tasks
+            .iter()
+            .filter(|t| t.milestone.as_deref() == Some(milestone))
+            .collect();
+        let target = targets
+            .get(milestone)
+            .copied()
+            .or_else(|| of_milestone.iter().filter_map(|t| t.due).max());
+        let Some(target) = target else {
+            continue;
+        };
+        let done = of_milestone
+            .iter()
+            .filter(|t| matches!(t.state, TaskState::Done))
+            .count();
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:milestone-{}@{}", uid_part(milestone), domain),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", target.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (target + Days::new(1)).format("%Y%m%d")
+            ),
+            format!("SUMMARY:{}", escape(&format!("Milestone {}", milestone))),
+            format!(
+                "DESCRIPTION:{}",
+                escape(&format!("{} of {} tasks done.", done, of_milestone.len()))
+            ),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold(&line));
+    }
+    ics
+}
+
+/// Builds the VTODO of a task with a due date.
+fn todo(task: &Task, domain: &str, stamp: &str) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}@{}", uid_part(&task.id), domain),
+        format!("DTSTAMP:{}", stamp),
+        format!("SUMMARY:{}", escape(&format!("{} {}", task.id, task.title))),
+    ];
+    if let Some(due) = task.due {
+        lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")));
+    }
+    let status = match task.state {
+        TaskState::Todo | TaskState::Blocked => "NEEDS-ACTION",
+        TaskState::InProgress => "IN-PROCESS",
+        TaskState::Done => "COMPLETED",
+    };
+    lines.push(format!("STATUS:{}", status));
+    if let (TaskState::Done, Some(completed)) = (&task.state, task.completed_at) {
+        lines.push(format!("COMPLETED:{}", completed.format("%Y%m%dT%H%M%SZ")));
+    }
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+    }
+    let categories: Vec<String> = task
+        .milestone
+        .iter()
+        .chain(&task.tags)
+        .map(|c| escape(c))
+        .collect();
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+    lines.push("END:VTODO".to_string());
+    lines
+}
+
+/// Escapes text property values.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Keeps the characters of an ID that are safe in a UID, replacing the others with `-`.
+fn uid_part(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Builds the domain part of the UIDs, from the project name.
+fn uid_domain(project: &str) -> String {
+    format!("{}.taskai", uid_part(&project.to_lowercase()))
+}
+
+/// Folds a content line into lines of at most 75 bytes, continued lines starting with a space, and
+/// ends it with CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ics() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: Weather CLI
+        tasks:
+          - id: W-1
+            title: Fetch forecasts, hourly; cache them
+            due: 2026-11-02
+            milestone: v1
+            tags: [api]
+          - id: W-2
+            title: Release
+            state: Done
+            completed_at: 2026-10-10T08:00:00Z
+            due: 2026-10-12
+            milestone: v1
+          - id: W-3
+            title: No due date
+            milestone: v2
+        "#,
+        )
+        .unwrap();
+        let now = "2026-10-16T12:00:00Z".parse().unwrap();
+
+        let ics = format_ics(&backlog, &BTreeMap::new(), now);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains(
+            "BEGIN:VTODO\r\nUID:W-1@weather-cli.taskai\r\nDTSTAMP:20261016T120000Z\r\n\
+             SUMMARY:W-1 Fetch forecasts\\, hourly\\; cache them\r\nDUE;VALUE=DATE:20261102\r\n\
+             STATUS:NEEDS-ACTION\r\nCATEGORIES:v1,api\r\nEND:VTODO\r\n"
+        ));
+        assert!(ics.contains("STATUS:COMPLETED\r\nCOMPLETED:20261010T080000Z\r\n"));
+        // v1 falls on the latest due date of its tasks, v2 has no date
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261102\r\nDTEND;VALUE=DATE:20261103\r\n"));
+        assert!(!ics.contains("Milestone v2"));
+
+        let targets = BTreeMap::from([("v2".to_string(), "2026-12-01".parse().unwrap())]);
+        let ics = format_ics(&backlog, &targets, now);
+        assert!(ics.contains("UID:milestone-v2@weather-cli.taskai\r\n"));
+        assert!(ics.contains("DESCRIPTION:0 of 1 tasks done.\r\n"));
+
+        let long = fold(&"x".repeat(80));
+        assert_eq!(
+            long,
+            format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5))
+        );
+    }
+}
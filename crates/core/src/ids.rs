@@ -0,0 +1,116 @@
+use taskai_schema::{Backlog, Task};
+
+/// Prefix used when the backlog has no task to infer one from.
+const DEFAULT_PREFIX: &str = "T";
+
+/// Splits a task ID of the form `PREFIX-N` into its prefix and number.
+pub fn split_task_id(id: &str) -> Option<(&str, u64)> {
+    let (prefix, number) = id.rsplit_once('-')?;
+    if prefix.is_empty() {
+        return None;
+    }
+    number.parse().ok().map(|n| (prefix, n))
+}
+
+/// Mints a new, unused task ID following the numbering already used in the backlog.
+///
+/// The prefix is taken from the last `PREFIX-N` task of the given epic (or of the whole backlog when no
+/// epic is given), and the number is one more than the highest number used with that prefix anywhere in
+/// the backlog. Falls back to `T-1` style IDs when nothing can be inferred.
+pub fn next_task_id(backlog: &Backlog, epic: Option<&str>) -> String {
+    let scope: Vec<&Task> = match epic.and_then(|id| backlog.epics.iter().find(|e| e.id == id)) {
+        Some(epic) if !epic.tasks.is_empty() => epic.tasks.iter().collect(),
+        _ => backlog.all_tasks(),
+    };
+
+    let prefix = scope
+        .iter()
+        .rev()
+        .find_map(|t| split_task_id(&t.id).map(|(prefix, _)| prefix))
+        .unwrap_or(DEFAULT_PREFIX)
+        .to_string();
+
+    next_id_with_prefix(backlog, &prefix)
+}
+
+/// Mints a new, unused task ID with the given prefix. IDs of archived tasks are never reused.
+pub fn next_id_with_prefix(backlog: &Backlog, prefix: &str) -> String {
+    let all_tasks: Vec<&Task> = backlog.all_tasks().into_iter().chain(&backlog.archive).collect();
+    let highest = all_tasks
+        .iter()
+        .filter_map(|t| split_task_id(&t.id))
+        .filter(|(p, _)| *p == prefix)
+        .map(|(_, n)| n)
+        .max()
+        .unwrap_or(0);
+
+    let mut number = highest + 1;
+    loop {
+        let id = format!("{}-{}", prefix, number);
+        if !all_tasks.iter().any(|t| t.id == id) {
+            return id;
+        }
+        number += 1;
+    }
+}
+
+/// Mints a new, unused epic ID following the numbering of the existing epics (`E-N` by default).
+pub fn next_epic_id(backlog: &Backlog) -> String {
+    let prefix = backlog
+        .epics
+        .iter()
+        .rev()
+        .find_map(|e| split_task_id(&e.id).map(|(prefix, _)| prefix))
+        .unwrap_or("E");
+    let highest = backlog
+        .epics
+        .iter()
+        .filter_map(|e| split_task_id(&e.id))
+        .filter(|(p, _)| *p == prefix)
+        .map(|(_, n)| n)
+        .max()
+        .unwrap_or(0);
+
+    let mut number = highest + 1;
+    loop {
+        let id = format!("{}-{}", prefix, number);
+        if !backlog.epics.iter().any(|e| e.id == id) {
+            return id;
+        }
+        number += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mint_ids() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: "API"
+            tasks:
+              - id: API-1
+                title: "One"
+              - id: API-7
+                title: "Seven"
+          - id: E-2
+            title: "Empty"
+        tasks:
+          - id: WEB-2
+            title: "Web"
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(next_task_id(&backlog, Some("E-1")), "API-8");
+        assert_eq!(next_task_id(&backlog, None), "API-8");
+        assert_eq!(next_task_id(&backlog, Some("E-2")), "API-8");
+        assert_eq!(next_id_with_prefix(&backlog, "WEB"), "WEB-3");
+        assert_eq!(next_task_id(&Backlog::default(), None), "T-1");
+        assert_eq!(next_epic_id(&backlog), "E-3");
+        assert_eq!(next_epic_id(&Backlog::default()), "E-1");
+    }
+}
@@ -0,0 +1,687 @@
+use crate::config::JiraConfig;
+use crate::ids::{next_epic_id, next_task_id};
+use crate::rows::ImportSummary;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, Epic, ExternalRef, Task, TaskState};
+
+/// Number of issues asked for per page when searching the issues of a project.
+const PAGE_SIZE: usize = 100;
+
+/// Standard fields read when searching issues; mapped custom fields are added to them.
+const SEARCH_FIELDS: &str =
+    "summary,description,status,issuetype,labels,parent,issuelinks,created,updated,duedate";
+
+/// An issue of a Jira project, as returned by the REST API (version 2).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraIssue {
+    pub key: String,
+    pub fields: JiraIssueFields,
+}
+
+/// Fields of a Jira issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraIssueFields {
+    pub summary: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub status: Option<JiraStatus>,
+    #[serde(default)]
+    pub issuetype: Option<JiraNamed>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub parent: Option<JiraIssueKey>,
+    #[serde(default)]
+    pub issuelinks: Vec<JiraIssueLink>,
+    #[serde(default, deserialize_with = "jira_time")]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "jira_time")]
+    pub updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub duedate: Option<NaiveDate>,
+    /// Custom fields, by ID.
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, Value>,
+}
+
+/// Status of a Jira issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraStatus {
+    pub name: String,
+    /// Category of the status: `new`, `indeterminate` (in progress) or `done`.
+    pub status_category: JiraStatusCategory,
+}
+
+/// Category of a Jira status, shared by all workflows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraStatusCategory {
+    pub key: String,
+}
+
+/// An issue type or an issue link type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraNamed {
+    pub name: String,
+}
+
+/// An issue a field or a link points to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraIssueKey {
+    pub key: String,
+}
+
+/// A link from an issue to another one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraIssueLink {
+    #[serde(rename = "type")]
+    pub kind: JiraNamed,
+    /// Set when the issue is on the inward side of the link, such as "is blocked by" this issue.
+    #[serde(default)]
+    pub inward_issue: Option<JiraIssueKey>,
+    /// Set when the issue is on the outward side of the link, such as "blocks" this issue.
+    #[serde(default)]
+    pub outward_issue: Option<JiraIssueKey>,
+}
+
+/// Reads a Jira timestamp, such as `2024-01-05T10:00:00.000+0000`.
+fn jira_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|time| {
+            DateTime::parse_from_str(&time, "%Y-%m-%dT%H:%M:%S%.f%z")
+                .or_else(|_| DateTime::parse_from_rfc3339(&time))
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(serde::de::Error::custom)
+        })
+        .transpose()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchPage {
+    total: usize,
+    issues: Vec<JiraIssue>,
+}
+
+/// Client of the Jira REST API (version 2).
+///
+/// Requests are sent to the Jira site given by the configuration or `JIRA_BASE_URL`, and
+/// authenticated with `JIRA_EMAIL` and `JIRA_API_TOKEN` when both are set.
+pub struct JiraClient {
+    client: reqwest::Client,
+    base_url: String,
+    credentials: Option<(String, String)>,
+}
+
+impl JiraClient {
+    /// Creates a client for the site at `base_url`, or `JIRA_BASE_URL` when not given.
+    pub fn from_env(base_url: Option<&str>) -> Result<Self, String> {
+        let base_url = match base_url {
+            Some(url) => url.to_string(),
+            None => std::env::var("JIRA_BASE_URL")
+                .map_err(|_| "Set JIRA_BASE_URL or jira.url in .taskai.toml.".to_string())?,
+        };
+        let credentials = match (std::env::var("JIRA_EMAIL"), std::env::var("JIRA_API_TOKEN")) {
+            (Ok(email), Ok(token)) if !email.is_empty() && !token.is_empty() => {
+                Some((email, token))
+            }
+            _ => None,
+        };
+        Ok(JiraClient {
+            client: crate::web::http_client(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            credentials,
+        })
+    }
+
+    /// Tells whether requests are authenticated, which creating issues requires.
+    pub fn has_credentials(&self) -> bool {
+        self.credentials.is_some()
+    }
+
+    /// Returns the web page of an issue.
+    pub fn browse_url(&self, key: &str) -> String {
+        format!("{}/browse/{}", self.base_url, key)
+    }
+
+    /// Creates an issue with the given fields and returns its key.
+    pub async fn create_issue(&self, fields: &Value) -> Result<String, String> {
+        let url = format!("{}/rest/api/2/issue", self.base_url);
+        let created: Value = self
+            .send(self.client.post(&url).json(&json!({ "fields": fields })))
+            .await?;
+        created["key"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Jira did not return the key of the new issue.".to_string())
+    }
+
+    /// Reads an issue with the standard fields.
+    pub async fn get_issue(&self, key: &str) -> Result<JiraIssue, String> {
+        let url = format!("{}/rest/api/2/issue/{}", self.base_url, key);
+        let query = [("fields", SEARCH_FIELDS)];
+        self.send(self.client.get(&url).query(&query)).await
+    }
+
+    /// Sets the given fields of an issue, such as its `summary`, `description` or `labels`.
+    pub async fn update_issue(&self, key: &str, fields: &Value) -> Result<(), String> {
+        let url = format!("{}/rest/api/2/issue/{}", self.base_url, key);
+        self.send::<Value>(self.client.put(&url).json(&json!({ "fields": fields })))
+            .await
+            .map(|_| ())
+    }
+
+    /// Moves an issue to a status of the `done` category, through the first transition leading
+    /// there from its current status.
+    pub async fn transition_to_done(&self, key: &str) -> Result<(), String> {
+        self.transition_to(key, "done").await
+    }
+
+    /// Moves an issue to a status of the status category `category` (`new`, `indeterminate` or
+    /// `done`), through the first transition leading there from its current status.
+    pub async fn transition_to(&self, key: &str, category: &str) -> Result<(), String> {
+        let url = format!("{}/rest/api/2/issue/{}/transitions", self.base_url, key);
+        let transitions: Value = self.send(self.client.get(&url)).await?;
+        let transition = transitions["transitions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|t| t["to"]["statusCategory"]["key"] == category)
+            .and_then(|t| t["id"].as_str())
+            .ok_or_else(|| {
+                format!(
+                    "No transition of {} leads to a status of the {} category.",
+                    key, category
+                )
+            })?;
+        let body = json!({ "transition": { "id": transition } });
+        self.send::<Value>(self.client.post(&url).json(&body))
+            .await
+            .map(|_| ())
+    }
+
+    /// Links two issues so that `blocker` blocks `blocked`, with the link type `link_type`.
+    pub async fn link_issues(
+        &self,
+        link_type: &str,
+        blocker: &str,
+        blocked: &str,
+    ) -> Result<(), String> {
+        let url = format!("{}/rest/api/2/issueLink", self.base_url);
+        // Jira gives the outward description ("blocks") to the inward issue of the request
+        let body = json!({
+            "type": { "name": link_type },
+            "inwardIssue": { "key": blocker },
+            "outwardIssue": { "key": blocked },
+        });
+        self.send::<Value>(self.client.post(&url).json(&body))
+            .await
+            .map(|_| ())
+    }
+
+    /// Lists the issues of `project`, oldest first, with the standard fields and the custom fields
+    /// mapped in `config`.
+    pub async fn search_issues(
+        &self,
+        project: &str,
+        config: &JiraConfig,
+    ) -> Result<Vec<JiraIssue>, String> {
+        let url = format!("{}/rest/api/2/search", self.base_url);
+        let jql = format!("project = \"{}\" ORDER BY created ASC", project);
+        let mapping = &config.fields;
+        let fields = [&mapping.estimate, &mapping.epic_link]
+            .into_iter()
+            .flatten()
+            .fold(SEARCH_FIELDS.to_string(), |fields, custom| {
+                format!("{},{}", fields, custom)
+            });
+
+        let mut issues = Vec::new();
+        loop {
+            let start_at = issues.len().to_string();
+            let max_results = PAGE_SIZE.to_string();
+            let query = [
+                ("jql", jql.as_str()),
+                ("fields", fields.as_str()),
+                ("startAt", start_at.as_str()),
+                ("maxResults", max_results.as_str()),
+            ];
+            let page: SearchPage = self.send(self.client.get(&url).query(&query)).await?;
+            let last_page =
+                page.issues.is_empty() || issues.len() + page.issues.len() >= page.total;
+            issues.extend(page.issues);
+            if last_page {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let mut request = request.header("Accept", "application/json");
+        if let Some((email, token)) = &self.credentials {
+            request = request.basic_auth(email, Some(token));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Jira request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.json::<Value>().await.unwrap_or_default();
+            let messages: Vec<String> = body["errorMessages"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m.as_str().map(str::to_string))
+                .chain(
+                    body["errors"]
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(field, message)| format!("{}: {}", field, message)),
+                )
+                .collect();
+            return Err(format!(
+                "Jira API returned status {}: {}",
+                status.as_u16(),
+                messages.join("; ")
+            ));
+        }
+        // Transitions and links answer with an empty body
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Invalid Jira response: {}", e))?;
+        let body = if body.trim().is_empty() {
+            "null"
+        } else {
+            &body
+        };
+        serde_json::from_str(body).map_err(|e| format!("Invalid Jira response: {}", e))
+    }
+}
+
+/// Returns the key of the Jira issue among the references of a task or an epic, if any.
+pub fn jira_key(external_refs: &[ExternalRef]) -> Option<&str> {
+    external_refs
+        .iter()
+        .find(|r| r.system == "jira")
+        .map(|r| r.id.as_str())
+}
+
+/// Returns the external reference recording that a task or an epic is tracked by a Jira issue.
+pub fn jira_ref(key: &str, url: &str) -> ExternalRef {
+    ExternalRef {
+        system: "jira".to_string(),
+        id: key.to_string(),
+        url: Some(url.to_string()),
+        synced_at: None,
+    }
+}
+
+/// Builds the fields of the Jira epic tracking an epic of `project`.
+pub fn jira_epic_fields(epic: &Epic, project: &str, config: &JiraConfig) -> Value {
+    let mut fields = json!({
+        "project": { "key": project },
+        "summary": epic.title,
+        "issuetype": { "name": config.epic_type.as_deref().unwrap_or("Epic") },
+    });
+    if let Some(epic_name) = &config.fields.epic_name {
+        fields[epic_name] = json!(epic.title);
+    }
+    fields
+}
+
+/// Builds the fields of the Jira issue tracking a task of `project`: the task title, a description
+/// made of its description and `done_when` criteria, its tags as labels, its due date, its estimate
+/// when mapped to a field, and the epic `epic_key` through the epic link field, or as parent.
+pub fn jira_task_fields(
+    task: &Task,
+    epic_key: Option<&str>,
+    project: &str,
+    config: &JiraConfig,
+) -> Value {
+    let mut description = String::new();
+    if let Some(text) = task.description.as_deref().map(str::trim) {
+        if !text.is_empty() {
+            description.push_str(text);
+            description.push_str("\n\n");
+        }
+    }
+    if !task.done_when.is_empty() {
+        description.push_str("h3. Done when\n\n");
+        for criterion in &task.done_when {
+            description.push_str(&format!("* {}\n", criterion));
+        }
+        description.push('\n');
+    }
+    description.push_str(&format!(
+        "_Task {{{{{}}}}} of the taskai backlog._",
+        task.id
+    ));
+
+    // Jira labels cannot contain spaces
+    let labels: Vec<String> = task
+        .tags
+        .iter()
+        .map(|tag| tag.split_whitespace().collect::<Vec<_>>().join("-"))
+        .collect();
+    let mut fields = json!({
+        "project": { "key": project },
+        "summary": task.title,
+        "description": description,
+        "issuetype": { "name": config.task_type.as_deref().unwrap_or("Task") },
+        "labels": labels,
+    });
+    if let Some(due) = task.due {
+        fields["duedate"] = json!(due.to_string());
+    }
+    if let (Some(field), Some(estimate)) = (&config.fields.estimate, task.estimate) {
+        fields[field] = json!(estimate);
+    }
+    match (epic_key, &config.fields.epic_link) {
+        (Some(key), Some(field)) => fields[field] = json!(key),
+        (Some(key), None) => fields["parent"] = json!({ "key": key }),
+        (None, _) => {}
+    }
+    fields
+}
+
+/// Returns the task state matching the status category of a Jira issue.
+pub fn jira_state(issue: &JiraIssue) -> TaskState {
+    match issue
+        .fields
+        .status
+        .as_ref()
+        .map(|s| s.status_category.key.as_str())
+    {
+        Some("done") => TaskState::Done,
+        Some("indeterminate") => TaskState::InProgress,
+        _ => TaskState::Todo,
+    }
+}
+
+/// Brings the issues of a Jira project into the backlog.
+///
+/// Epics (issues of the epic type) become epics, and the other issues tasks, grouped under the epic
+/// of their epic link field or parent. Epics and tasks already tracked, through a Jira reference,
+/// get their title updated, and tasks their state after the status category of the issue, a
+/// Blocked task staying so until its issue is done. Other issues become new epics and tasks, with
+/// the description, labels as tags, due date, estimate when mapped to a field, and the issue as
+/// reference. Finally, issues blocked by other issues through the link type of `config` make their
+/// tasks depend on the tasks of the blocking issues. Renamed epics are listed as updated.
+pub fn import_jira_issues(
+    backlog: &mut Backlog,
+    issues: &[JiraIssue],
+    config: &JiraConfig,
+    browse_url: impl Fn(&str) -> String,
+    now: DateTime<Utc>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let epic_type = config.epic_type.as_deref().unwrap_or("Epic");
+    let is_epic = |issue: &JiraIssue| {
+        issue
+            .fields
+            .issuetype
+            .as_ref()
+            .is_some_and(|t| t.name.eq_ignore_ascii_case(epic_type))
+    };
+
+    for issue in issues.iter().filter(|i| is_epic(i)) {
+        let title = issue.fields.summary.trim();
+        match backlog
+            .epics
+            .iter_mut()
+            .find(|e| jira_key(&e.external_refs) == Some(issue.key.as_str()))
+        {
+            Some(epic) if epic.title != title => {
+                epic.title = title.to_string();
+                summary.updated.push(epic.id.clone());
+            }
+            Some(_) => summary.unchanged += 1,
+            None => {
+                let id = next_epic_id(backlog);
+                backlog.epics.push(Epic {
+                    id: id.clone(),
+                    title: title.to_string(),
+                    tasks: Vec::new(),
+                    external_refs: vec![jira_ref(&issue.key, &browse_url(&issue.key))],
+                });
+                summary.new_epics.push(id);
+            }
+        }
+    }
+
+    for issue in issues.iter().filter(|i| !is_epic(i)) {
+        let tracking = backlog
+            .all_tasks()
+            .iter()
+            .find(|t| jira_key(&t.external_refs) == Some(issue.key.as_str()))
+            .map(|t| t.id.clone());
+        let state = jira_state(issue);
+        if let Some(task_id) = tracking {
+            let Some(task) = backlog.find_task_mut(&task_id) else {
+                continue;
+            };
+            let mut changed = false;
+            if task.title != issue.fields.summary {
+                task.title = issue.fields.summary.clone();
+                task.updated_at = Some(now);
+                changed = true;
+            }
+            let keep =
+                matches!(task.state, TaskState::Blocked) && !matches!(state, TaskState::Done);
+            if !keep && std::mem::discriminant(&task.state) != std::mem::discriminant(&state) {
+                task.set_state(state, now);
+                changed = true;
+            }
+            if changed {
+                summary.updated.push(task_id);
+            } else {
+                summary.unchanged += 1;
+            }
+            continue;
+        }
+
+        let epic_key = config
+            .fields
+            .epic_link
+            .as_ref()
+            .and_then(|field| issue.fields.custom.get(field))
+            .and_then(Value::as_str)
+            .or(issue.fields.parent.as_ref().map(|p| p.key.as_str()));
+        let epic_id = epic_key.and_then(|key| {
+            backlog
+                .epics
+                .iter()
+                .find(|e| jira_key(&e.external_refs) == Some(key))
+                .map(|e| e.id.clone())
+        });
+        let mut task = Task {
+            id: next_task_id(backlog, epic_id.as_deref()),
+            title: issue.fields.summary.clone(),
+            description: issue
+                .fields
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            tags: issue.fields.labels.clone(),
+            estimate: config
+                .fields
+                .estimate
+                .as_ref()
+                .and_then(|field| issue.fields.custom.get(field))
+                .and_then(Value::as_f64),
+            due: issue.fields.duedate,
+            created_at: issue.fields.created,
+            external_refs: vec![jira_ref(&issue.key, &browse_url(&issue.key))],
+            ..Default::default()
+        };
+        if !matches!(state, TaskState::Todo) {
+            task.set_state(state, issue.fields.updated.unwrap_or(now));
+        }
+        task.updated_at = Some(now);
+        summary.added.push(task.id.clone());
+        match epic_id.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+    }
+
+    let link_type = config.link_type.as_deref().unwrap_or("Blocks");
+    let task_of = |backlog: &Backlog, key: &str| {
+        backlog
+            .all_tasks()
+            .iter()
+            .find(|t| jira_key(&t.external_refs) == Some(key))
+            .map(|t| t.id.clone())
+    };
+    for issue in issues.iter().filter(|i| !is_epic(i)) {
+        let Some(task_id) = task_of(backlog, &issue.key) else {
+            continue;
+        };
+        let blockers: Vec<String> = issue
+            .fields
+            .issuelinks
+            .iter()
+            .filter(|l| l.kind.name.eq_ignore_ascii_case(link_type))
+            .filter_map(|l| l.inward_issue.as_ref())
+            .filter_map(|blocker| task_of(backlog, &blocker.key))
+            .filter(|id| id != &task_id)
+            .collect();
+        let Some(task) = backlog.find_task_mut(&task_id) else {
+            continue;
+        };
+        let mut changed = false;
+        for blocker in blockers {
+            if !task.depends.contains(&blocker) {
+                task.depends.push(blocker);
+                changed = true;
+            }
+        }
+        if changed && !summary.added.contains(&task_id) && !summary.updated.contains(&task_id) {
+            task.updated_at = Some(now);
+            summary.updated.push(task_id);
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::JiraFieldMapping;
+
+    fn config() -> JiraConfig {
+        JiraConfig {
+            fields: JiraFieldMapping {
+                estimate: Some("customfield_10016".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_jira_task_fields() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Add the users endpoint
+            description: Serve users as JSON.
+            done_when: [Documented]
+            tags: [back end]
+            estimate: 3
+            due: 2024-03-01
+        "#,
+        )
+        .unwrap();
+
+        let fields = jira_task_fields(&backlog.tasks[0], Some("APP-1"), "APP", &config());
+        assert_eq!(
+            fields,
+            json!({
+                "project": { "key": "APP" },
+                "summary": "Add the users endpoint",
+                "description": "Serve users as JSON.\n\nh3. Done when\n\n* Documented\n\n\
+                                _Task {{T-1}} of the taskai backlog._",
+                "issuetype": { "name": "Task" },
+                "labels": ["back-end"],
+                "duedate": "2024-03-01",
+                "customfield_10016": 3.0,
+                "parent": { "key": "APP-1" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_import_jira_issues() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: Old title
+            external_refs:
+              - {system: jira, id: APP-2}
+        "#,
+        )
+        .unwrap();
+        let issues: Vec<JiraIssue> = serde_json::from_str(
+            r#"[
+            {"key": "APP-1", "fields": {"summary": "Accounts", "issuetype": {"name": "Epic"}}},
+            {"key": "APP-2", "fields": {"summary": "Setup",
+             "status": {"name": "Closed", "statusCategory": {"key": "done"}}}},
+            {"key": "APP-3", "fields": {"summary": "Login", "description": "OAuth",
+             "labels": ["auth"], "parent": {"key": "APP-1"}, "customfield_10016": 5,
+             "created": "2024-01-01T10:00:00.000+0000",
+             "status": {"name": "In Review", "statusCategory": {"key": "indeterminate"}},
+             "issuelinks": [
+               {"type": {"name": "Blocks"}, "inwardIssue": {"key": "APP-2"}},
+               {"type": {"name": "Blocks"}, "outwardIssue": {"key": "APP-9"}}
+             ]}}
+        ]"#,
+        )
+        .unwrap();
+        let now = "2024-02-01T00:00:00Z".parse().unwrap();
+        let browse = |key: &str| format!("https://jira/browse/{}", key);
+
+        let summary = import_jira_issues(&mut backlog, &issues, &config(), browse, now);
+        assert_eq!(summary.new_epics, ["E-1"]);
+        assert_eq!(summary.updated, ["T-1"]);
+        assert_eq!(summary.added, ["T-2"]);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+
+        let epic = &backlog.epics[0];
+        assert_eq!(jira_key(&epic.external_refs), Some("APP-1"));
+        let task = &epic.tasks[0];
+        assert_eq!(task.depends, ["T-1"]);
+        assert_eq!(task.estimate, Some(5.0));
+        assert!(matches!(task.state, TaskState::InProgress));
+        assert_eq!(task.created_at, "2024-01-01T10:00:00Z".parse().ok());
+        assert_eq!(
+            task.external_refs[0].url.as_deref(),
+            Some("https://jira/browse/APP-3")
+        );
+
+        let summary = import_jira_issues(&mut backlog, &issues, &config(), browse, now);
+        assert!(summary.added.is_empty() && summary.updated.is_empty());
+        assert_eq!(summary.unchanged, 3);
+    }
+}
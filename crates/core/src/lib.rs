@@ -1,12 +1,62 @@
 mod validate;
 mod next;
+mod verify;
+mod diff;
+mod refs;
+mod diagnostics;
+mod plan;
+mod rollup;
+mod lint;
+mod ids;
+mod fmt;
+mod config;
+mod archive;
+mod merge;
+mod checks;
+mod relocate;
+mod search;
+mod report;
+mod rows;
+mod timesheet;
+mod sort;
+mod yaml_edit;
+mod lines;
+mod stats;
+mod clean;
+mod directory;
+mod github;
+mod github_project;
+mod gitlab;
+mod jira;
+mod linear;
+mod asana;
+mod azure_devops;
+mod clickup;
+mod stories;
+mod org;
+mod todotxt;
+mod obsidian;
+mod commits;
+mod branch;
+mod scan;
+mod standup;
+mod slack;
+mod discord;
+mod digest;
+mod ical;
+mod plantuml;
+mod agent;
+mod editor;
+mod web;
+mod blocking;
 
 use llm::{
     builder::{LLMBackend, LLMBuilder},
     chat::ChatMessage,
 };
-use taskai_schema::Backlog;
+use taskai_schema::{Backlog, Epic};
 use std::path::Path;
+use std::time::Instant;
 
 /// BacklogGenerator is responsible for generating a project backlog from a specification using an LLM.
 pub struct BacklogGenerator {
@@ -111,6 +161,12 @@ impl BacklogGenerator {
         }
     }
     
+    /// Generates a backlog like [`generate`](Self::generate), blocking until it is done, for programs
+    /// that are not async. Must not be called from within an async runtime.
+    pub fn generate_blocking(&self, spec: &str) -> Result<Backlog, String> {
+        blocking::block_on(self.generate(spec))?
+    }
+    
     /// Calls the LLM API with the given system and user prompts, returning the raw response.
     async fn call_llm(&self, system_prompt: &str, user_prompt: &str) -> Result<String, String> {
         let api_key = std::env::var("OPENAI_API_KEY")
@@ -127,6 +183,7 @@ impl BacklogGenerator {
             .map_err(|e| format!("Failed to build LLM: {}", e))?;
 
         let formatted_prompt = format!("{}\n\n{}", system_prompt, user_prompt);
+        let prompt_chars = formatted_prompt.len();
 
         let messages = vec![
             ChatMessage::user()
@@ -134,13 +191,99 @@ impl BacklogGenerator {
                 .build(),
         ];
 
-        let completion = llm.chat(&messages)
-            .await
-            .map_err(|e| format!("LLM API error: {}", e))?;
-        
-        Ok(completion.to_string())
+        tracing::info!(model = %self.model, prompt_chars, "calling the LLM");
+        let started = Instant::now();
+        let completion = llm.chat(&messages).await.map_err(|e| {
+            tracing::warn!(
+                model = %self.model,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                error = %e,
+                "LLM call failed"
+            );
+            format!("LLM API error: {}", e)
+        })?;
+        let response = completion.to_string();
+        tracing::info!(
+            model = %self.model,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            response_chars = response.len(),
+            "LLM responded"
+        );
+        tracing::trace!(%response, "LLM response");
+
+        Ok(response)
     }
     
+    /// Asks the LLM how to split an oversized epic in two, by grouping its tasks by theme.
+    ///
+    /// Returns the title of the new epic and the IDs of the tasks that should move to it.
+    pub async fn suggest_epic_split(&self, epic: &Epic) -> Result<EpicSplit, String> {
+        #[cfg(test)]
+        return Ok(EpicSplit {
+            title: format!("{} (part 2)", epic.title),
+            tasks: epic.tasks.iter().skip(epic.tasks.len() / 2).map(|t| t.id.clone()).collect(),
+        });
+
+        #[cfg(not(test))]
+        {
+            let system_prompt = "You split oversized epics of a project backlog in two coherent groups. \
+                Answer with YAML only, with a `title` for the new epic and the list of `tasks` IDs moving to it, \
+                keeping at least one task in the original epic.";
+            let tasks: Vec<String> = epic
+                .tasks
+                .iter()
+                .map(|t| match &t.description {
+                    Some(description) => format!("- {}: {} ({})", t.id, t.title, description),
+                    None => format!("- {}: {}", t.id, t.title),
+                })
+                .collect();
+            let user_prompt = format!("Epic {}: {}\n\nTasks:\n{}", epic.id, epic.title, tasks.join("\n"));
+
+            let response = self.call_llm(system_prompt, &user_prompt).await?;
+
+            return parse_epic_split(&response);
+        }
+
+        #[allow(unreachable_code)]
+        {
+            Err("Error: Unreachable code reached".to_string())
+        }
+    }
+
+    /// Asks the LLM how tasks imported from a list fit together: the epic each task belongs to and
+    /// the tasks it depends on, referred to by their number in the list.
+    pub async fn suggest_line_structure(&self, titles: &[String]) -> Result<LineStructure, String> {
+        #[cfg(test)]
+        return Ok(LineStructure {
+            tasks: (2..=titles.len())
+                .map(|line| LinePlacement { line, epic: None, depends: vec![line - 1] })
+                .collect(),
+        });
+
+        #[cfg(not(test))]
+        {
+            let system_prompt = "You organize a list of tasks into a project backlog. \
+                Answer with YAML only: a `tasks` list giving, for each task by its `line` number, \
+                the title of the `epic` it belongs to (omit it for standalone tasks) and the line \
+                numbers of the tasks it `depends` on. Only use dependencies that are really needed.";
+            let lines: Vec<String> = titles
+                .iter()
+                .enumerate()
+                .map(|(index, title)| format!("{}. {}", index + 1, title))
+                .collect();
+            let user_prompt = format!("Tasks:\n{}", lines.join("\n"));
+
+            let response = self.call_llm(system_prompt, &user_prompt).await?;
+
+            return parse_line_structure(&response);
+        }
+
+        #[allow(unreachable_code)]
+        {
+            Err("Error: Unreachable code reached".to_string())
+        }
+    }
+
     /// Determines if the input string is already a structured project specification.
     #[allow(dead_code)]
     fn is_structured_spec(input: &str) -> bool {
@@ -168,8 +311,204 @@ impl BacklogGenerator {
     }
 }
 
-/// Returns a list of tasks that are ready to be worked on.
-pub use next::get_ready_tasks;
+/// Returns a list of tasks that are ready to be worked on, and helpers to filter and rank them.
+pub use next::{
+    best_ready_task, downstream_tasks, filter_ready_tasks, get_ready_tasks, unfinished_dependencies,
+    ReadyFilter,
+};
+
+/// Checks task deliverables against the working tree.
+pub use verify::{verify_task, DeliverableStatus, TaskVerification};
+
+/// Computes semantic differences between two backlogs.
+pub use diff::{
+    diff_backlogs, BacklogDiff, EpicChange, FieldChange, StateChange, TaskChange, TaskRef,
+};
+
+/// Validates external references and links carried by tasks.
+pub use refs::{check_ref_formats, check_refs_online, check_refs_online_blocking, RefIssue};
+
+/// Collects validation errors and warnings with their location in the backlog file.
+pub use diagnostics::{
+    check_backlog, validate_source, Diagnostic, Location, Severity, ValidationReport,
+};
+
+/// Groups remaining tasks into waves that can be worked on in parallel.
+pub use plan::execution_levels;
+
+/// Rolls up completed and remaining estimates per epic, milestone or sprint.
+pub use rollup::{estimate_rollups, EstimateRollup, RollupBy};
+
+/// Lint rules flagging backlog hygiene problems such as stale tasks, and automatic fixes for some of them.
+pub use lint::{
+    fix_backlog, is_fixable, lint_backlog, normalize_task_id, stale_tasks, LintOptions, StaleTask,
+};
+
+/// Task and epic ID policy: parses `PREFIX-N` IDs and mints new ones.
+pub use ids::{next_epic_id, next_id_with_prefix, next_task_id, split_task_id};
+
+/// Canonical formatting of backlogs, keeping diffs after edits minimal.
+pub use fmt::{canonicalize, format_backlog};
+
+/// Project configuration stored in `.taskai.toml`.
+pub use config::{
+    AgentConfig, ArchiveConfig, AsanaConfig, AzureDevOpsConfig, ClickUpConfig, Config,
+    DiscordConfig, EmailConfig, GenerateConfig, GitConfig, GitHubConfig, GitLabConfig,
+    GitLabEpics, JiraConfig, JiraFieldMapping, LinearConfig, LintConfig, NextConfig, NotifyEvent,
+    OutputConfig, ProjectConfig, SlackConfig, SlackRoute, StaleConfig, TeamConfig, Theme,
+    CONFIG_FILE,
+};
+
+/// Moves completed tasks out of the active backlog.
+pub use archive::{forget_tasks, take_archivable_tasks};
+
+/// Merges a regenerated backlog into an existing one, preserving the progress of known tasks, and
+/// combines separate backlogs into one.
+pub use merge::{combine_backlogs, merge_generated, CombineSummary, MergeSummary};
+
+/// Runs the checks derived from the `done_when` criteria of tasks.
+pub use checks::{parse_check, run_checks, Check, CheckStatus, CriterionResult, TaskChecks};
+
+/// Moves tasks, along with their subtasks, between epics, and splits oversized epics.
+pub use relocate::{move_task, parse_epic_split, split_epic, EpicSplit};
+
+/// Searches tasks by text or regex.
+pub use search::{search_tasks, SearchMatch, SearchOptions};
+
+/// Burndown and velocity reports built from task creation and completion dates.
+pub use report::{burndown, Burndown, BurndownDay, WeeklyVelocity, WorkUnit};
+
+/// Flattens tasks into rows for spreadsheets, and applies edited rows back to a backlog.
+pub use rows::{apply_task_rows, task_rows, ImportSummary, TaskRow, TASK_ROW_COLUMNS};
+
+/// Time logged on tasks, per day and per task.
+pub use timesheet::{timesheet, TaskTime, Timesheet, TimesheetEntry};
+
+/// Reorders the tasks of a backlog in execution order.
+pub use sort::{sort_tasks, SortOrder};
+
+/// Edits backlog YAML in place, keeping comments and formatting.
+pub use yaml_edit::update_yaml;
+
+/// Imports tasks from plain text lists, one task per line.
+pub use lines::{
+    append_line_tasks, parse_line_structure, parse_task_lines, LinePlacement, LineStructure,
+};
+
+/// Open and done counts, estimates and cycle times per assignee, tag, epic or kind of task.
+pub use stats::{group_stats, GroupStats, StatsBy};
+
+/// Housekeeping of a backlog: empty epics, duplicate dependencies, dangling references and old
+/// Done tasks.
+pub use clean::{clean_backlog, CleanSummary};
+
+/// GitHub Issues client and the mapping between tasks and issues.
+pub use github::{
+    github_issue_number, github_ref, github_ref_issue, import_github_issues, is_github_repo,
+    issue_for_task, mark_github_synced, GitHubClient, GitHubIssue, GitHubLabel, GitHubMilestone,
+    NewIssue,
+};
+
+/// GitHub Projects (v2) boards and the placement of tasks on them.
+pub use github_project::{
+    parse_project, plan_project_sync, project_ref, ProjectBoard, ProjectField, ProjectItem,
+    ProjectOption, ProjectSyncItem, ProjectTarget, ProjectUpdate, PROJECT_REF_SYSTEM,
+};
+
+/// GitLab REST client and the mapping between epics, tasks and dependencies and GitLab milestones,
+/// epics, issues and issue links.
+pub use gitlab::{
+    gitlab_epic_iid, gitlab_epic_ref, gitlab_issue_for_task, gitlab_issue_iid, gitlab_milestone_id,
+    gitlab_milestone_ref, gitlab_ref, gitlab_ref_issue, import_gitlab_issues, is_gitlab_project,
+    mark_gitlab_synced, GitLabClient, GitLabEpic, GitLabIssue, GitLabIssueLink, GitLabMilestone,
+    NewGitLabIssue,
+};
+
+/// Jira REST client and the mapping between epics, tasks and dependencies and Jira issues.
+pub use jira::{
+    import_jira_issues, jira_epic_fields, jira_key, jira_ref, jira_state, jira_task_fields,
+    JiraClient, JiraIssue, JiraIssueFields, JiraIssueKey, JiraIssueLink, JiraNamed, JiraStatus,
+    JiraStatusCategory,
+};
+
+/// Linear GraphQL client and the mapping between tasks and Linear issues.
+pub use linear::{
+    linear_identifier, linear_ref, linear_state_for, linear_task_state, LinearClient, LinearIssue,
+    LinearState, LinearTeam,
+};
+
+/// Asana REST client and the mapping between epics and tasks and Asana sections and tasks.
+pub use asana::{asana_gid, asana_ref, asana_task_data, AsanaClient, AsanaObject};
+
+/// Azure DevOps REST client and the mapping between epics, tasks and dependencies and Azure
+/// DevOps work items and their links.
+pub use azure_devops::{
+    azure_done_patch, azure_epic_patch, azure_predecessor_patch, azure_ref, azure_task_patch,
+    azure_work_item_id, AzureDevOpsClient, AzureWorkItem,
+};
+
+/// ClickUp REST client and the mapping between epics, tasks, states and dependencies and ClickUp
+/// parent tasks, subtasks, statuses and dependencies.
+pub use clickup::{
+    clickup_ref, clickup_state, clickup_status_for, clickup_task_data, clickup_task_id,
+    import_clickup_tasks, mark_clickup_synced, ClickUpClient, ClickUpDependency, ClickUpList,
+    ClickUpStatus, ClickUpTag, ClickUpTask, ClickUpUser,
+};
+
+/// Pivotal Tracker and Shortcut exports imported as tasks.
+pub use stories::{import_pivotal, import_shortcut};
+
+/// Directory storage: the backlog as an index and one Markdown file per task.
+pub use directory::{
+    format_task_file, join_backlog, split_backlog, task_file_name, INDEX_FILE, TASKS_DIR,
+};
+
+/// Org document export and import.
+pub use org::{format_org, import_org};
+
+/// todo.txt export and import.
+pub use todotxt::{format_todo_txt, import_todo_txt};
+
+/// Obsidian vault export.
+pub use obsidian::{obsidian_notes, VaultNote};
+
+/// Task references in commit messages, for the git hook.
+pub use commits::{apply_commit_refs, parse_commit_refs, CommitRef, CommitUpdate};
+
+/// Git branch names of tasks.
+pub use branch::{branch_name, DEFAULT_BRANCH_TEMPLATE};
+
+/// Matching of changed files against task deliverables.
+pub use scan::{scan_changes, DeliverableChange, ScanMatch};
+
+/// Summary of the work done, in progress and blocked, for standups.
+pub use standup::{standup, Standup, StandupTask};
+
+/// Slack notifications about completed and blocked tasks and standups.
+pub use slack::{
+    slack_standup_message, slack_task_messages, SlackClient, SlackDestination, SlackMessage,
+};
+
+/// Discord notifications about completed and blocked tasks and standups.
+pub use discord::{discord_standup_message, discord_task_message, DiscordClient};
+
+/// Periodic summary of completed, newly ready and overdue tasks, for email digests.
+pub use digest::{digest, Digest, DigestTask};
+
+/// iCalendar export of due dates and milestones.
+pub use ical::format_ics;
+
+/// PlantUML dependency diagram and Gantt chart of the plan.
+pub use plantuml::format_plantuml;
+
+/// Prompts handing tasks to coding agents.
+pub use agent::agent_prompt;
+
+/// Editor support for backlog files: task IDs under the cursor, declarations and summaries.
+pub use editor::{in_depends, task_definition, task_summary, word_at};
+
+/// Web pages and documents fetched as specifications.
+pub use web::{fetch_spec, fetch_spec_blocking, html_to_text, page_links, read_page, WebPage, MAX_FOLLOWED_PAGES};
 
 #[cfg(test)]
 mod tests {
@@ -184,4 +523,16 @@ mod tests {
         assert_eq!(result.project, "mock-project");
         assert_eq!(result.tasks[0].id, "MOCK-1");
     }
+    
+    /// Tests blocking generation, and that it refuses to run within an async runtime.
+    #[test]
+    fn gen_blocking() {
+        let generator = BacklogGenerator::new();
+        let result = generator.generate_blocking("Test specification").unwrap();
+        assert_eq!(result.tasks[0].id, "MOCK-1");
+        
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let err = runtime.block_on(async { generator.generate_blocking("Test specification") });
+        assert!(err.unwrap_err().contains("async runtime"));
+    }
 }
\ No newline at end of file
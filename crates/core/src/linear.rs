@@ -0,0 +1,296 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use taskai_schema::{ExternalRef, Task, TaskState};
+
+/// An issue of a Linear team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearIssue {
+    pub id: String,
+    /// Human-readable identifier, such as `ENG-12`.
+    pub identifier: String,
+    pub title: String,
+    pub url: String,
+    pub state: LinearState,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A workflow state of a Linear team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearState {
+    pub id: String,
+    pub name: String,
+    /// Kind of state: `triage`, `backlog`, `unstarted`, `started`, `completed` or `canceled`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub position: f64,
+}
+
+/// A Linear team and its workflow states.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearTeam {
+    pub id: String,
+    pub key: String,
+    pub states: Vec<LinearState>,
+}
+
+/// Client of the Linear GraphQL API.
+///
+/// Requests are authenticated with `LINEAR_API_KEY`, and sent to `LINEAR_API_URL`
+/// (`https://api.linear.app/graphql` by default).
+pub struct LinearClient {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl LinearClient {
+    /// Creates a client configured from the environment.
+    pub fn from_env() -> Self {
+        LinearClient {
+            client: crate::web::http_client(),
+            api_url: std::env::var("LINEAR_API_URL")
+                .unwrap_or_else(|_| "https://api.linear.app/graphql".to_string()),
+            api_key: std::env::var("LINEAR_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+        }
+    }
+
+    /// Tells whether requests are authenticated, which every Linear request requires.
+    pub fn has_key(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Reads the team with the given key and its workflow states.
+    pub async fn team(&self, key: &str) -> Result<LinearTeam, String> {
+        let data = self
+            .graphql(
+                "query($key: String!) {
+                  teams(filter: {key: {eq: $key}}) {
+                    nodes { id key states { nodes { id name type position } } }
+                  }
+                }",
+                json!({ "key": key }),
+            )
+            .await?;
+        let team = &data["teams"]["nodes"][0];
+        if team.is_null() {
+            return Err(format!("Linear team '{}' not found.", key));
+        }
+        Ok(LinearTeam {
+            id: string(&team["id"])?,
+            key: string(&team["key"])?,
+            states: serde_json::from_value(team["states"]["nodes"].clone())
+                .map_err(|e| format!("Invalid Linear response: {}", e))?,
+        })
+    }
+
+    /// Returns the ID of the project of the team named `name`, compared case-insensitively.
+    pub async fn project_id(&self, team: &LinearTeam, name: &str) -> Result<String, String> {
+        let data = self
+            .graphql(
+                "query($team: String!) {
+                  team(id: $team) { projects(first: 250) { nodes { id name } } }
+                }",
+                json!({ "team": team.id }),
+            )
+            .await?;
+        data["team"]["projects"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|p| {
+                p["name"]
+                    .as_str()
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .map(|p| string(&p["id"]))
+            .unwrap_or_else(|| {
+                Err(format!(
+                    "Linear project '{}' not found in {}.",
+                    name, team.key
+                ))
+            })
+    }
+
+    /// Creates an issue from `input`, with at least its `teamId` and `title`.
+    pub async fn create_issue(&self, input: &Value) -> Result<LinearIssue, String> {
+        let data = self
+            .graphql(
+                "mutation($input: IssueCreateInput!) {
+                  issueCreate(input: $input) {
+                    issue { id identifier title url updatedAt state { id name type position } }
+                  }
+                }",
+                json!({ "input": input }),
+            )
+            .await?;
+        serde_json::from_value(data["issueCreate"]["issue"].clone())
+            .map_err(|e| format!("Invalid Linear response: {}", e))
+    }
+
+    /// Updates the issue with the ID or identifier `id` from `input`, such as its `title`,
+    /// `description` or `stateId`.
+    pub async fn update_issue(&self, id: &str, input: &Value) -> Result<LinearIssue, String> {
+        let data = self
+            .graphql(
+                "mutation($id: String!, $input: IssueUpdateInput!) {
+                  issueUpdate(id: $id, input: $input) {
+                    issue { id identifier title url updatedAt state { id name type position } }
+                  }
+                }",
+                json!({ "id": id, "input": input }),
+            )
+            .await?;
+        serde_json::from_value(data["issueUpdate"]["issue"].clone())
+            .map_err(|e| format!("Invalid Linear response: {}", e))
+    }
+
+    /// Lists the issues of the team, oldest first.
+    pub async fn list_issues(&self, team: &LinearTeam) -> Result<Vec<LinearIssue>, String> {
+        let mut issues = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let data = self
+                .graphql(
+                    "query($team: String!, $after: String) {
+                      team(id: $team) {
+                        issues(first: 100, after: $after, orderBy: createdAt) {
+                          pageInfo { hasNextPage endCursor }
+                          nodes {
+                            id identifier title url updatedAt state { id name type position }
+                          }
+                        }
+                      }
+                    }",
+                    json!({ "team": team.id, "after": after }),
+                )
+                .await?;
+            let page = &data["team"]["issues"];
+            let batch: Vec<LinearIssue> = serde_json::from_value(page["nodes"].clone())
+                .map_err(|e| format!("Invalid Linear response: {}", e))?;
+            issues.extend(batch);
+            match page["pageInfo"]["endCursor"].as_str() {
+                Some(cursor) if page["pageInfo"]["hasNextPage"] == true => {
+                    after = Some(cursor.to_string())
+                }
+                _ => return Ok(issues),
+            }
+        }
+    }
+
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value, String> {
+        let mut request = self
+            .client
+            .post(&self.api_url)
+            .json(&json!({ "query": query, "variables": variables }));
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Linear request failed: {}", e))?;
+        let status = response.status();
+        let mut body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Linear response: {}", e))?;
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|e| e["message"].as_str())
+                .collect();
+            return Err(format!("Linear API error: {}", messages.join("; ")));
+        }
+        if !status.is_success() {
+            return Err(format!("Linear API returned status {}", status.as_u16()));
+        }
+        Ok(body["data"].take())
+    }
+}
+
+fn string(value: &Value) -> Result<String, String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Invalid Linear response.".to_string())
+}
+
+/// Returns the identifier of the Linear issue tracking the task, if any.
+pub fn linear_identifier(task: &Task) -> Option<&str> {
+    task.external_refs
+        .iter()
+        .find(|r| r.system == "linear")
+        .map(|r| r.id.as_str())
+}
+
+/// Returns the external reference recording that a task is tracked by a Linear issue.
+pub fn linear_ref(issue: &LinearIssue) -> ExternalRef {
+    ExternalRef {
+        system: "linear".to_string(),
+        id: issue.identifier.clone(),
+        url: Some(issue.url.clone()),
+        synced_at: None,
+    }
+}
+
+/// Returns the first workflow state of the team matching a task state: unstarted (or backlog) for
+/// Todo, started for InProgress and Blocked, and completed for Done.
+pub fn linear_state_for<'a>(
+    state: &TaskState,
+    states: &'a [LinearState],
+) -> Option<&'a LinearState> {
+    let kinds: &[&str] = match state {
+        TaskState::Todo => &["unstarted", "backlog"],
+        TaskState::InProgress | TaskState::Blocked => &["started"],
+        TaskState::Done => &["completed"],
+    };
+    kinds.iter().find_map(|kind| {
+        states
+            .iter()
+            .filter(|s| s.kind == *kind)
+            .min_by(|a, b| a.position.total_cmp(&b.position))
+    })
+}
+
+/// Returns the task state matching a workflow state: Done for completed and canceled issues,
+/// InProgress for started ones, and Todo for the others.
+pub fn linear_task_state(state: &LinearState) -> TaskState {
+    match state.kind.as_str() {
+        "completed" | "canceled" => TaskState::Done,
+        "started" => TaskState::InProgress,
+        _ => TaskState::Todo,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_states() {
+        let states: Vec<LinearState> = serde_json::from_str(
+            r#"[
+            {"id": "s1", "name": "Backlog", "type": "backlog", "position": 0},
+            {"id": "s3", "name": "Ready", "type": "unstarted", "position": 2},
+            {"id": "s2", "name": "Todo", "type": "unstarted", "position": 1},
+            {"id": "s4", "name": "In Progress", "type": "started", "position": 3},
+            {"id": "s5", "name": "Done", "type": "completed", "position": 4}
+        ]"#,
+        )
+        .unwrap();
+        let name = |state: TaskState| linear_state_for(&state, &states).map(|s| s.name.as_str());
+        assert_eq!(name(TaskState::Todo), Some("Todo"));
+        assert_eq!(name(TaskState::Blocked), Some("In Progress"));
+        assert_eq!(name(TaskState::Done), Some("Done"));
+
+        assert!(matches!(linear_task_state(&states[0]), TaskState::Todo));
+        assert!(matches!(linear_task_state(&states[3]), TaskState::InProgress));
+        assert!(matches!(linear_task_state(&states[4]), TaskState::Done));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::ids::{next_epic_id, next_task_id};
+use crate::relocate::yaml_block;
+use crate::rows::ImportSummary;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// How tasks imported from a list fit together, as suggested by an LLM. Tasks are referred to by
+/// their number in the list, starting at 1.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LineStructure {
+    #[serde(default)]
+    pub tasks: Vec<LinePlacement>,
+}
+
+/// Epic and dependencies of a task of the list.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LinePlacement {
+    /// Number of the task in the list.
+    pub line: usize,
+    /// Title of the epic the task belongs to, if any.
+    #[serde(default)]
+    pub epic: Option<String>,
+    /// Numbers of the tasks it depends on.
+    #[serde(default)]
+    pub depends: Vec<usize>,
+}
+
+/// Reads task titles from a plain text list: one task per non-empty line, with list markers such
+/// as `-`, `*`, `+` or `1.` removed.
+pub fn parse_task_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            let line = line.trim();
+            let unnumbered = line
+                .split_once(". ")
+                .filter(|(number, _)| {
+                    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+                })
+                .map(|(_, rest)| rest);
+            let unmarked = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|marker| line.strip_prefix(marker));
+            unnumbered.or(unmarked).unwrap_or(line).trim().to_string()
+        })
+        .filter(|title| !title.is_empty())
+        .collect()
+}
+
+/// Parses the structure of a list suggested by an LLM, as YAML, possibly wrapped in a code block.
+pub fn parse_line_structure(response: &str) -> Result<LineStructure, String> {
+    serde_yaml::from_str(yaml_block(response))
+        .map_err(|e| format!("Invalid task structure suggestion: {}", e))
+}
+
+/// Appends a Todo task to the backlog for each title, in order, minting their IDs.
+///
+/// With a `structure`, tasks go to the epic it names, matched by title or else created, and get
+/// the dependencies it gives, on tasks earlier or later in the list. Other tasks are added as
+/// standalone tasks. Returns an error, leaving the backlog in an unspecified state, if a dependency
+/// names a line that is not in the list or the dependencies make a cycle.
+pub fn append_line_tasks(
+    backlog: &mut Backlog,
+    titles: &[String],
+    structure: Option<&LineStructure>,
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let placement = |line: usize| structure.and_then(|s| s.tasks.iter().find(|p| p.line == line));
+    let mut summary = ImportSummary::default();
+
+    for (index, title) in titles.iter().enumerate() {
+        let line = index + 1;
+        let epic_title = placement(line)
+            .and_then(|p| p.epic.as_deref())
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        let epic_id = epic_title.map(|title| epic_with_title(backlog, title, &mut summary));
+
+        let id = next_task_id(backlog, epic_id.as_deref());
+        let task = Task {
+            id: id.clone(),
+            title: title.clone(),
+            state: TaskState::Todo,
+            created_at: Some(now),
+            updated_at: Some(now),
+            ..Default::default()
+        };
+        match epic_id.and_then(|epic_id| backlog.epics.iter_mut().find(|e| e.id == epic_id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+        summary.added.push(id);
+    }
+
+    // Dependencies are resolved once every task has its ID, since they may point further down
+    for (index, id) in summary.added.iter().enumerate() {
+        let line = index + 1;
+        let Some(placement) = placement(line) else {
+            continue;
+        };
+        let mut depends = Vec::new();
+        for &dep in &placement.depends {
+            if dep == line {
+                continue;
+            }
+            match dep.checked_sub(1).and_then(|i| summary.added.get(i)) {
+                Some(dep_id) => depends.push(dep_id.clone()),
+                None => {
+                    return Err(format!(
+                        "Line {} depends on line {}, which is not in the list.",
+                        line, dep
+                    ))
+                }
+            }
+        }
+        depends.sort();
+        depends.dedup();
+        if let Some(task) = backlog.find_task_mut(id) {
+            task.depends = depends;
+        }
+    }
+    backlog.validate()?;
+    Ok(summary)
+}
+
+/// Returns the ID of the epic with the given title, compared case-insensitively, creating the epic
+/// and recording it in `summary` if there is none.
+pub(crate) fn epic_with_title(
+    backlog: &mut Backlog,
+    title: &str,
+    summary: &mut ImportSummary,
+) -> String {
+    if let Some(epic) = backlog
+        .epics
+        .iter()
+        .find(|e| e.title.eq_ignore_ascii_case(title))
+    {
+        return epic.id.clone();
+    }
+    let id = next_epic_id(backlog);
+    backlog.epics.push(Epic {
+        id: id.clone(),
+        title: title.to_string(),
+        ..Default::default()
+    });
+    summary.new_epics.push(id.clone());
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_line_tasks() {
+        let titles = parse_task_lines("- Set up CI\n\n2. Write the parser\n  * Ship v1  \n");
+        assert_eq!(titles, ["Set up CI", "Write the parser", "Ship v1"]);
+
+        let mut backlog: Backlog = serde_yaml::from_str(
+            "project: test\ntasks:\n  - id: T-1\n    title: Existing\nepics:\n  - id: E-1\n    title: Core\n",
+        )
+        .unwrap();
+        let structure = parse_line_structure(
+            "```yaml\ntasks:\n  - line: 2\n    epic: core\n    depends: [1]\n  - line: 1\n    depends: [3]\n  - line: 3\n    epic: Release\n```",
+        )
+        .unwrap();
+        let summary =
+            append_line_tasks(&mut backlog, &titles, Some(&structure), Utc::now()).unwrap();
+
+        assert_eq!(summary.added, ["T-2", "T-3", "T-4"]);
+        assert_eq!(summary.new_epics, ["E-2"]);
+        assert_eq!(backlog.tasks[1].title, "Set up CI");
+        assert_eq!(backlog.tasks[1].depends, ["T-4"]);
+        assert_eq!(backlog.epics[0].tasks[0].title, "Write the parser");
+        assert_eq!(backlog.epics[0].tasks[0].depends, ["T-2"]);
+        assert!(backlog.epics[1].tasks[0].depends.is_empty());
+        assert!(backlog.validate().is_ok());
+
+        let unknown = parse_line_structure("tasks:\n  - line: 1\n    depends: [7]\n").unwrap();
+        let mut backlog = Backlog::default();
+        let err = append_line_tasks(&mut backlog, &titles, Some(&unknown), Utc::now()).unwrap_err();
+        assert_eq!(err, "Line 1 depends on line 7, which is not in the list.");
+    }
+}
@@ -0,0 +1,264 @@
+use crate::diagnostics::{Diagnostic, Severity, TaskIndex};
+use chrono::{DateTime, Duration, Utc};
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Settings for the lint rules.
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// How long a task may stay InProgress or Blocked before being reported as stale.
+    pub stale_after: Duration,
+    /// Reference time used to compute task ages.
+    pub now: DateTime<Utc>,
+}
+
+impl Default for LintOptions {
+    /// Returns options flagging tasks stuck for more than 7 days, as of now.
+    fn default() -> Self {
+        Self {
+            stale_after: Duration::days(7),
+            now: Utc::now(),
+        }
+    }
+}
+
+/// A task that has stayed InProgress or Blocked for too long.
+#[derive(Debug, Clone)]
+pub struct StaleTask<'a> {
+    /// The stale task.
+    pub task: &'a Task,
+    /// When the task was last touched.
+    pub since: DateTime<Utc>,
+    /// How long the task has been in its current state.
+    pub age: Duration,
+}
+
+/// Returns the InProgress or Blocked tasks that have not been touched for longer than `stale_after`.
+///
+/// The age of a task is measured from `updated_at`, falling back to `started_at`. Tasks without any
+/// timestamp cannot be judged and are not reported. Results are sorted from oldest to newest.
+pub fn stale_tasks(
+    backlog: &Backlog,
+    stale_after: Duration,
+    now: DateTime<Utc>,
+) -> Vec<StaleTask<'_>> {
+    let mut stale: Vec<StaleTask> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| matches!(t.state, TaskState::InProgress | TaskState::Blocked))
+        .filter_map(|task| {
+            let since = task.updated_at.or(task.started_at)?;
+            let age = now - since;
+            if age > stale_after {
+                Some(StaleTask { task, since, age })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    stale.sort_by_key(|s| s.since);
+    stale
+}
+
+/// Lint codes that `fix_backlog` knows how to fix.
+const FIXABLE: &[&str] = &["duplicate-depends", "self-dependency", "non-canonical-id"];
+
+/// Returns true if problems with the given lint code can be fixed by `fix_backlog`.
+pub fn is_fixable(code: &str) -> bool {
+    FIXABLE.contains(&code)
+}
+
+/// Returns the canonical form of a task ID: trimmed, uppercase, with inner whitespace and
+/// underscores replaced by dashes.
+pub fn normalize_task_id(id: &str) -> String {
+    id.trim()
+        .split(|c: char| c.is_whitespace() || c == '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_uppercase()
+}
+
+/// Runs every lint rule on the backlog and returns the problems found.
+///
+/// `source` is only used to resolve line numbers and may be empty.
+pub fn lint_backlog(backlog: &Backlog, source: &str, options: &LintOptions) -> Vec<Diagnostic> {
+    let index = TaskIndex::new(backlog, source);
+    let mut diagnostics = Vec::new();
+
+    for task in backlog.all_tasks() {
+        let canonical = normalize_task_id(&task.id);
+        if canonical != task.id {
+            diagnostics.push(index.diagnostic(
+                Severity::Warning,
+                "non-canonical-id",
+                format!("Task ID '{}' should be written '{}'", task.id, canonical),
+                &task.id,
+                None,
+            ));
+        }
+
+        if task.title.trim().is_empty() {
+            diagnostics.push(index.diagnostic(
+                Severity::Warning,
+                "empty-title",
+                format!("Task {} has no title", task.id),
+                &task.id,
+                Some("title"),
+            ));
+        }
+
+        if task.depends.contains(&task.id) {
+            diagnostics.push(index.diagnostic(
+                Severity::Warning,
+                "self-dependency",
+                format!("Task {} depends on itself", task.id),
+                &task.id,
+                Some("depends"),
+            ));
+        }
+
+        let mut seen = Vec::new();
+        for dep in &task.depends {
+            if seen.contains(&dep) {
+                diagnostics.push(index.diagnostic(
+                    Severity::Warning,
+                    "duplicate-depends",
+                    format!("Task {} lists dependency {} more than once", task.id, dep),
+                    &task.id,
+                    Some("depends"),
+                ));
+            }
+            seen.push(dep);
+        }
+    }
+
+    for stale in stale_tasks(backlog, options.stale_after, options.now) {
+        diagnostics.push(index.diagnostic(
+            Severity::Warning,
+            "stale-task",
+            format!(
+                "Task {} has been {:?} for {} day(s)",
+                stale.task.id,
+                stale.task.state,
+                stale.age.num_days()
+            ),
+            &stale.task.id,
+            None,
+        ));
+    }
+
+    diagnostics
+}
+
+/// Fixes the problems reported by the fixable lint rules and returns a description of each fix.
+///
+/// Duplicate and self dependencies are dropped, and task IDs are rewritten to their canonical form
+/// along with every reference to them, unless the canonical ID is already taken.
+pub fn fix_backlog(backlog: &mut Backlog) -> Vec<String> {
+    let mut fixes = Vec::new();
+
+    let ids: Vec<String> = backlog.all_tasks().iter().map(|t| t.id.clone()).collect();
+    for id in &ids {
+        let canonical = normalize_task_id(id);
+        if &canonical != id && !canonical.is_empty() && !backlog.has_task(&canonical) {
+            backlog.rename_task(id, &canonical);
+            fixes.push(format!("Renamed task '{}' to '{}'", id, canonical));
+        }
+    }
+
+    for task in backlog
+        .tasks
+        .iter_mut()
+        .chain(backlog.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+    {
+        let before = task.depends.len();
+        let id = task.id.clone();
+        let mut seen = Vec::new();
+        task.depends.retain(|dep| {
+            if *dep == id || seen.contains(dep) {
+                return false;
+            }
+            seen.push(dep.clone());
+            true
+        });
+        if task.depends.len() != before {
+            fixes.push(format!(
+                "Removed {} redundant dependency entr{} from task {}",
+                before - task.depends.len(),
+                if before - task.depends.len() == 1 { "y" } else { "ies" },
+                task.id
+            ));
+        }
+    }
+
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_in_progress_and_blocked() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "Old work"
+            state: InProgress
+            started_at: 2024-01-01T00:00:00Z
+          - id: T-2
+            title: "Recently touched"
+            state: InProgress
+            started_at: 2024-01-01T00:00:00Z
+            updated_at: 2024-01-19T00:00:00Z
+          - id: T-3
+            title: "Blocked for long"
+            state: Blocked
+            updated_at: 2023-12-01T00:00:00Z
+          - id: T-4
+            title: "Old but done"
+            state: Done
+            updated_at: 2023-12-01T00:00:00Z
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let now = "2024-01-20T00:00:00Z".parse().unwrap();
+
+        let stale = stale_tasks(&backlog, Duration::days(7), now);
+        let ids: Vec<&str> = stale.iter().map(|s| s.task.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["T-3", "T-1"]);
+        assert_eq!(stale[1].age.num_days(), 19);
+    }
+
+    #[test]
+    fn lint_and_fix() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: t_1
+            title: "Lowercase"
+          - id: T-2
+            title: "Messy depends"
+            depends: [t_1, t_1, T-2]
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let codes: Vec<String> = lint_backlog(&backlog, "", &LintOptions::default())
+            .into_iter()
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(
+            codes,
+            vec!["non-canonical-id", "self-dependency", "duplicate-depends"]
+        );
+        assert!(codes.iter().all(|c| is_fixable(c)));
+
+        let fixes = fix_backlog(&mut backlog);
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(backlog.tasks[0].id, "T-1");
+        assert_eq!(backlog.tasks[1].depends, vec!["T-1"]);
+        assert!(lint_backlog(&backlog, "", &LintOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::ids::split_task_id;
+use serde::Serialize;
+use std::collections::HashSet;
+use taskai_schema::{Backlog, Epic, Task};
+
+/// Outcome of merging a freshly generated backlog into an existing one.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeSummary {
+    /// Generated tasks matched with an existing task, whose progress was preserved.
+    pub preserved: Vec<String>,
+    /// Generated tasks with no existing counterpart.
+    pub added: Vec<String>,
+    /// Existing tasks absent from the generated backlog, kept as they were.
+    pub kept: Vec<String>,
+}
+
+/// Merges a freshly generated backlog into an existing one.
+///
+/// The generated backlog provides the new structure and task content. Each generated task is matched
+/// with an existing task by title (ignoring case and surrounding whitespace), then by ID; a matched
+/// task takes the existing ID and keeps its progress: state, timestamps, assignee, due date, notes and
+/// external references. Generated tasks whose ID clashes with an unrelated existing task get a fresh
+/// ID. Existing tasks that were not matched are kept in their epic, so no manual work is lost, and
+/// the archive of the existing backlog is carried over.
+pub fn merge_generated(existing: &Backlog, mut generated: Backlog) -> (Backlog, MergeSummary) {
+    let existing_tasks = existing.all_tasks();
+    let mut claimed: HashSet<&str> = HashSet::new();
+    let mut matches: Vec<(String, &Task)> = Vec::new();
+
+    // Titles are matched first, so that a reused ID never takes precedence over the same task
+    // generated under another ID.
+    for by_title in [true, false] {
+        for task in generated.all_tasks() {
+            if matches.iter().any(|(id, _)| id == &task.id) {
+                continue;
+            }
+            let found = existing_tasks.iter().find(|t| {
+                !claimed.contains(t.id.as_str())
+                    && if by_title {
+                        normalize_title(&t.title) == normalize_title(&task.title)
+                    } else {
+                        t.id == task.id
+                    }
+            });
+            if let Some(old) = found {
+                claimed.insert(old.id.as_str());
+                matches.push((task.id.clone(), old));
+            }
+        }
+    }
+
+    // Move clashing unmatched tasks out of the way, then give matched tasks their existing ID. The
+    // matched tasks go through temporary IDs so that swapped IDs never collide mid-way.
+    let mut taken: HashSet<String> = existing
+        .all_tasks()
+        .into_iter()
+        .chain(&existing.archive)
+        .chain(generated.all_tasks())
+        .map(|t| t.id.clone())
+        .collect();
+    let unmatched_clashes: Vec<String> = generated
+        .all_tasks()
+        .into_iter()
+        .filter(|t| !matches.iter().any(|(id, _)| id == &t.id))
+        .filter(|t| existing.has_task(&t.id))
+        .map(|t| t.id.clone())
+        .collect();
+    for id in unmatched_clashes {
+        let fresh = fresh_id(&id, &taken);
+        taken.insert(fresh.clone());
+        generated.rename_task(&id, &fresh);
+    }
+    for (index, (id, _)) in matches.iter().enumerate() {
+        generated.rename_task(id, &format!("\u{0}merge-{}", index));
+    }
+    for (index, (_, old)) in matches.iter().enumerate() {
+        generated.rename_task(&format!("\u{0}merge-{}", index), &old.id);
+    }
+
+    let mut summary = MergeSummary::default();
+    for task in generated
+        .tasks
+        .iter_mut()
+        .chain(generated.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+    {
+        match matches.iter().find(|(_, old)| old.id == task.id) {
+            Some((_, old)) => {
+                preserve_progress(task, old);
+                summary.preserved.push(task.id.clone());
+            }
+            None => summary.added.push(task.id.clone()),
+        }
+    }
+
+    // Keep the existing tasks that were not generated again.
+    for task in existing
+        .tasks
+        .iter()
+        .filter(|t| !claimed.contains(t.id.as_str()))
+    {
+        summary.kept.push(task.id.clone());
+        generated.tasks.push(task.clone());
+    }
+    for epic in &existing.epics {
+        for task in epic
+            .tasks
+            .iter()
+            .filter(|t| !claimed.contains(t.id.as_str()))
+        {
+            summary.kept.push(task.id.clone());
+            let index = match generated.epics.iter().position(|e| e.id == epic.id) {
+                Some(index) => index,
+                None => {
+                    generated.epics.push(Epic {
+                        id: epic.id.clone(),
+                        title: epic.title.clone(),
+                        tasks: Vec::new(),
+                        external_refs: epic.external_refs.clone(),
+                    });
+                    generated.epics.len() - 1
+                }
+            };
+            generated.epics[index].tasks.push(task.clone());
+        }
+    }
+    // Epics generated again keep their references to external trackers.
+    for epic in &mut generated.epics {
+        if let Some(old) = existing.epics.iter().find(|e| e.id == epic.id) {
+            if epic.external_refs.is_empty() {
+                epic.external_refs = old.external_refs.clone();
+            }
+        }
+    }
+    generated.archive = existing.archive.clone();
+
+    (generated, summary)
+}
+
+/// Outcome of combining two backlog files.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CombineSummary {
+    /// Tasks and epics of the second backlog renamed because their ID was already used, as
+    /// `(old, new)` pairs.
+    pub renamed: Vec<(String, String)>,
+    /// Environment keys set to different values in both backlogs; the first backlog's value is kept.
+    pub environment_conflicts: Vec<String>,
+}
+
+/// Combines two backlogs into one, e.g. a frontend and a backend backlog.
+///
+/// Epics, tasks and archived tasks of `second` are appended to those of `first`. Task and epic IDs of
+/// `second` that are already used in `first` are namespaced with `prefix` (`T-1` becomes `WEB-T-1`),
+/// and references to them are updated. Environments and success criteria are merged; the project
+/// name and Rust version of `first` win. Returns an error if the combined backlog is invalid.
+pub fn combine_backlogs(
+    first: Backlog,
+    mut second: Backlog,
+    prefix: &str,
+) -> Result<(Backlog, CombineSummary), String> {
+    let mut summary = CombineSummary::default();
+
+    let colliding: Vec<String> = second
+        .all_tasks()
+        .into_iter()
+        .chain(&second.archive)
+        .filter(|t| first.has_task(&t.id))
+        .map(|t| t.id.clone())
+        .collect();
+    for id in colliding {
+        let new_id = format!("{}-{}", prefix, id);
+        if first.has_task(&new_id) || second.has_task(&new_id) {
+            return Err(format!(
+                "Cannot namespace task {}: {} is already used.",
+                id, new_id
+            ));
+        }
+        second.rename_task(&id, &new_id);
+        summary.renamed.push((id, new_id));
+    }
+    for epic in &mut second.epics {
+        if first.epics.iter().any(|e| e.id == epic.id) {
+            let new_id = format!("{}-{}", prefix, epic.id);
+            summary.renamed.push((epic.id.clone(), new_id.clone()));
+            epic.id = new_id;
+        }
+    }
+
+    let mut combined = first;
+    for (key, value) in second.environment {
+        match combined.environment.get(&key) {
+            Some(existing) if existing != &value => summary.environment_conflicts.push(key),
+            Some(_) => {}
+            None => {
+                combined.environment.insert(key, value);
+            }
+        }
+    }
+    summary.environment_conflicts.sort();
+    for criterion in second.success_criteria {
+        if !combined.success_criteria.contains(&criterion) {
+            combined.success_criteria.push(criterion);
+        }
+    }
+    if combined.rust_version.is_none() {
+        combined.rust_version = second.rust_version;
+    }
+    combined.epics.extend(second.epics);
+    combined.tasks.extend(second.tasks);
+    combined.archive.extend(second.archive);
+
+    combined.validate()?;
+    Ok((combined, summary))
+}
+
+/// Copies the progress made on an existing task onto its regenerated version.
+fn preserve_progress(task: &mut Task, old: &Task) {
+    task.state = old.state.clone();
+    task.created_at = old.created_at;
+    task.started_at = old.started_at;
+    task.completed_at = old.completed_at;
+    task.updated_at = old.updated_at;
+    task.assignee = old.assignee.clone();
+    task.blocker = old.blocker.clone();
+    task.due = old.due;
+    task.notes = old.notes.clone();
+    task.time_spent = old.time_spent;
+    task.time_log = old.time_log.clone();
+    task.external_refs = old.external_refs.clone();
+    task.branch = old.branch.clone();
+}
+
+fn normalize_title(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+/// Returns an ID derived from `id` that is not in `taken`.
+fn fresh_id(id: &str, taken: &HashSet<String>) -> String {
+    let (prefix, mut number) = match split_task_id(id) {
+        Some((prefix, number)) => (prefix.to_string(), number),
+        None => (id.to_string(), 1),
+    };
+    loop {
+        number += 1;
+        let candidate = format!("{}-{}", prefix, number);
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskai_schema::TaskState;
+
+    #[test]
+    fn merge_preserves_progress() {
+        let existing: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Setup project
+            state: Done
+            assignee: alice
+          - id: T-2
+            title: Write parser
+          - id: T-9
+            title: Manual task
+        "#,
+        )
+        .unwrap();
+        let generated: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Add CLI
+            depends: [T-2]
+          - id: T-2
+            title: setup project
+          - id: T-3
+            title: Write parser
+            depends: [T-2]
+        "#,
+        )
+        .unwrap();
+
+        let (merged, summary) = merge_generated(&existing, generated);
+
+        assert_eq!(summary.preserved, vec!["T-1", "T-2"]);
+        assert_eq!(summary.added, vec!["T-4"]);
+        assert_eq!(summary.kept, vec!["T-9"]);
+
+        let setup = merged.find_task("T-1").unwrap();
+        assert_eq!(setup.title, "setup project");
+        assert!(matches!(setup.state, TaskState::Done));
+        assert_eq!(setup.assignee.as_deref(), Some("alice"));
+
+        let cli = merged.find_task("T-4").unwrap();
+        assert_eq!(cli.title, "Add CLI");
+        assert_eq!(cli.depends, vec!["T-1"]);
+        assert_eq!(merged.find_task("T-2").unwrap().depends, vec!["T-1"]);
+        assert!(merged.validate().is_ok());
+    }
+
+    #[test]
+    fn combine_with_collisions() {
+        let first: Backlog = serde_yaml::from_str(
+            r#"
+        project: backend
+        environment:
+          DATABASE: postgres
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-1
+                title: Endpoints
+        "#,
+        )
+        .unwrap();
+        let second: Backlog = serde_yaml::from_str(
+            r#"
+        project: frontend
+        environment:
+          DATABASE: sqlite
+          BUNDLER: vite
+        epics:
+          - id: E-1
+            title: UI
+            tasks:
+              - id: T-1
+                title: Pages
+              - id: T-2
+                title: Forms
+                depends: [T-1]
+        "#,
+        )
+        .unwrap();
+
+        let (combined, summary) = combine_backlogs(first, second, "WEB").unwrap();
+
+        assert_eq!(
+            summary.renamed,
+            vec![
+                ("T-1".to_string(), "WEB-T-1".to_string()),
+                ("E-1".to_string(), "WEB-E-1".to_string())
+            ]
+        );
+        assert_eq!(summary.environment_conflicts, vec!["DATABASE"]);
+        assert_eq!(combined.project, "backend");
+        assert_eq!(combined.environment.len(), 2);
+        assert_eq!(combined.find_task("T-2").unwrap().depends, vec!["WEB-T-1"]);
+        assert_eq!(combined.epics[1].id, "WEB-E-1");
+    }
+}
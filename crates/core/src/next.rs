@@ -31,6 +31,99 @@ pub fn get_ready_tasks(backlog: &Backlog) -> Vec<&Task> {
         .collect()
 }
 
+/// Criteria narrowing down the ready tasks. Unset criteria match every task.
+#[derive(Debug, Clone, Default)]
+pub struct ReadyFilter {
+    /// Only keep tasks of this epic.
+    pub epic: Option<String>,
+    /// Only keep tasks carrying this tag.
+    pub tag: Option<String>,
+    /// Only keep tasks assigned to this person.
+    pub assignee: Option<String>,
+}
+
+/// Returns the ready tasks matching the filter, in backlog order.
+pub fn filter_ready_tasks<'a>(backlog: &'a Backlog, filter: &ReadyFilter) -> Vec<&'a Task> {
+    let epic_tasks: Option<Vec<&str>> = filter.epic.as_ref().map(|epic_id| {
+        backlog
+            .epics
+            .iter()
+            .filter(|e| &e.id == epic_id)
+            .flat_map(|e| e.tasks.iter().map(|t| t.id.as_str()))
+            .collect()
+    });
+
+    get_ready_tasks(backlog)
+        .into_iter()
+        .filter(|t| epic_tasks.as_ref().is_none_or(|ids| ids.contains(&t.id.as_str())))
+        .filter(|t| filter.tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+        .filter(|t| {
+            filter
+                .assignee
+                .as_ref()
+                .is_none_or(|assignee| t.assignee.as_ref() == Some(assignee))
+        })
+        .collect()
+}
+
+/// Picks the task to work on first among the given ready tasks.
+///
+/// Tasks with the highest priority (lowest number) come first, then the ones with the earliest due
+/// date, then the ones unblocking the most downstream work; remaining ties keep backlog order.
+pub fn best_ready_task<'a>(backlog: &'a Backlog, ready: &[&'a Task]) -> Option<&'a Task> {
+    ready
+        .iter()
+        .enumerate()
+        .min_by_key(|(index, task)| {
+            (
+                task.priority.is_none(),
+                task.priority,
+                task.due.is_none(),
+                task.due,
+                std::cmp::Reverse(downstream_tasks(backlog, &task.id).len()),
+                *index,
+            )
+        })
+        .map(|(_, task)| *task)
+}
+
+/// Returns the dependencies of the given task that are not Done yet.
+/// Dependencies on unknown tasks are ignored.
+pub fn unfinished_dependencies<'a>(backlog: &'a Backlog, task: &Task) -> Vec<&'a Task> {
+    let all_tasks = get_all_tasks(backlog);
+
+    task.depends
+        .iter()
+        .filter_map(|dep_id| all_tasks.iter().find(|t| &t.id == dep_id))
+        .filter(|dep| !matches!(dep.state, TaskState::Done))
+        .copied()
+        .collect()
+}
+
+/// Returns every task that depends on the given task, directly or transitively, in backlog order.
+pub fn downstream_tasks<'a>(backlog: &'a Backlog, task_id: &str) -> Vec<&'a Task> {
+    let all_tasks = get_all_tasks(backlog);
+    let mut reached: Vec<&str> = vec![task_id];
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+        for task in &all_tasks {
+            if !reached.contains(&task.id.as_str())
+                && task.depends.iter().any(|dep| reached.contains(&dep.as_str()))
+            {
+                reached.push(task.id.as_str());
+                changed = true;
+            }
+        }
+    }
+
+    all_tasks
+        .into_iter()
+        .filter(|t| t.id != task_id && reached.contains(&t.id.as_str()))
+        .collect()
+}
+
 /// Returns a vector of references to all tasks in the backlog, including both standalone tasks and tasks within epics.
 fn get_all_tasks(backlog: &Backlog) -> Vec<&Task> {
     let mut all_tasks = Vec::new();
@@ -67,29 +160,24 @@ mod tests {
                     title: "Task 1".to_string(),
                     depends: vec![],
                     state: TaskState::Done,
-                    description: None,
-                    deliverable: None,
-                    done_when: vec![],
+                    ..Default::default()
                 },
                 Task {
                     id: "T-2".to_string(),
                     title: "Task 2".to_string(),
                     depends: vec!["T-1".to_string()],
                     state: TaskState::Todo,
-                    description: None,
-                    deliverable: None,
-                    done_when: vec![],
+                    ..Default::default()
                 },
                 Task {
                     id: "T-3".to_string(),
                     title: "Task 3".to_string(),
                     depends: vec!["T-1".to_string(), "T-2".to_string()],
                     state: TaskState::Todo,
-                    description: None,
-                    deliverable: None,
-                    done_when: vec![],
+                    ..Default::default()
                 },
             ],
+            archive: vec![],
         };
 
         let ready_tasks = get_ready_tasks(&backlog);
@@ -97,4 +185,78 @@ mod tests {
         assert_eq!(ready_tasks.len(), 1);
         assert_eq!(ready_tasks[0].id, "T-2");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn downstream_transitive() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "Root"
+          - id: T-2
+            title: "Direct"
+            depends: [T-1]
+          - id: T-3
+            title: "Transitive"
+            depends: [T-2]
+          - id: T-4
+            title: "Unrelated"
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let ids: Vec<&str> = downstream_tasks(&backlog, "T-1")
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["T-2", "T-3"]);
+    }
+
+    #[test]
+    fn filter_and_pick_best() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: "Epic"
+            tasks:
+              - id: T-1
+                title: "Leaf"
+                tags: [backend]
+                assignee: alice
+              - id: T-2
+                title: "Unblocks work"
+                tags: [backend]
+              - id: T-3
+                title: "Downstream"
+                depends: [T-2]
+        tasks:
+          - id: T-4
+            title: "Due soon"
+            due: 2024-03-01
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let ids = |tasks: Vec<&Task>| -> Vec<String> {
+            tasks.iter().map(|t| t.id.clone()).collect()
+        };
+        let filter = ReadyFilter {
+            epic: Some("E-1".to_string()),
+            tag: Some("backend".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(filter_ready_tasks(&backlog, &filter)), vec!["T-1", "T-2"]);
+
+        let mine = ReadyFilter {
+            assignee: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(filter_ready_tasks(&backlog, &mine)), vec!["T-1"]);
+
+        let ready = filter_ready_tasks(&backlog, &filter);
+        assert_eq!(best_ready_task(&backlog, &ready).unwrap().id, "T-2");
+
+        let all = get_ready_tasks(&backlog);
+        assert_eq!(best_ready_task(&backlog, &all).unwrap().id, "T-4");
+    }
+}
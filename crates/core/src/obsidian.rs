@@ -0,0 +1,265 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::PathBuf;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// A note of an Obsidian vault, with its path relative to the folder the backlog is exported to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultNote {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Frontmatter of a task note.
+#[derive(Serialize)]
+struct TaskFrontmatter<'a> {
+    id: &'a str,
+    title: &'a str,
+    state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    epic: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sprint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    aliases: Vec<&'a str>,
+}
+
+/// Frontmatter of an epic note.
+#[derive(Serialize)]
+struct EpicFrontmatter<'a> {
+    id: &'a str,
+    title: &'a str,
+    tasks: usize,
+    done: usize,
+    aliases: Vec<&'a str>,
+}
+
+/// Links to the note of a task or an epic, which is named after its ID.
+fn wikilink(id: &str) -> String {
+    format!("[[{}]]", id)
+}
+
+/// Wraps a frontmatter and a markdown body into a note.
+fn note<T: Serialize>(frontmatter: &T, body: &str) -> String {
+    let yaml = serde_yaml::to_string(frontmatter).unwrap_or_default();
+    format!("---\n{}---\n\n{}", yaml, body)
+}
+
+/// Builds an Obsidian vault mirroring the backlog, so that it can be browsed as a graph.
+///
+/// Each task gets a note `Tasks/<ID>.md` whose YAML frontmatter holds its fields, with its epic,
+/// dependencies and parent as wikilinks, and whose body holds its description, `done_when`
+/// criteria and the tasks it depends on and blocks. Each epic gets an index note `Epics/<ID>.md`
+/// listing its tasks, and `<project>.md` lists the epics and standalone tasks. Notes are named
+/// after IDs and carry the title as alias, so `[[T-1]]` and `[[Title]]` both resolve.
+pub fn obsidian_notes(backlog: &Backlog) -> Vec<VaultNote> {
+    let mut notes = Vec::new();
+    let standalone = backlog.tasks.iter().map(|t| (t, None));
+    let in_epics = backlog
+        .epics
+        .iter()
+        .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e))));
+    for (task, epic) in standalone.chain(in_epics) {
+        notes.push(VaultNote {
+            path: PathBuf::from("Tasks").join(format!("{}.md", task.id)),
+            content: task_note(backlog, task, epic),
+        });
+    }
+    for epic in &backlog.epics {
+        notes.push(VaultNote {
+            path: PathBuf::from("Epics").join(format!("{}.md", epic.id)),
+            content: epic_note(epic),
+        });
+    }
+
+    let project = if backlog.project.trim().is_empty() {
+        "Backlog"
+    } else {
+        backlog.project.trim()
+    };
+    let mut index = format!("# {}\n", project);
+    if !backlog.epics.is_empty() {
+        index.push_str("\n## Epics\n\n");
+        for epic in &backlog.epics {
+            let done = epic
+                .tasks
+                .iter()
+                .filter(|t| matches!(t.state, TaskState::Done))
+                .count();
+            index.push_str(&format!(
+                "- {} {} ({}/{} done)\n",
+                wikilink(&epic.id),
+                epic.title,
+                done,
+                epic.tasks.len()
+            ));
+        }
+    }
+    if !backlog.tasks.is_empty() {
+        index.push_str("\n## Tasks\n\n");
+        index.push_str(&task_list(&backlog.tasks.iter().collect::<Vec<_>>()));
+    }
+    notes.push(VaultNote {
+        path: PathBuf::from(format!("{}.md", project.replace(['/', '\\', ':'], "-"))),
+        content: index,
+    });
+    notes
+}
+
+/// Lists tasks as a checklist of links, checked for Done tasks.
+fn task_list(tasks: &[&Task]) -> String {
+    tasks
+        .iter()
+        .map(|t| {
+            let check = if matches!(t.state, TaskState::Done) {
+                'x'
+            } else {
+                ' '
+            };
+            format!("- [{}] {} {}\n", check, wikilink(&t.id), t.title)
+        })
+        .collect()
+}
+
+fn task_note(backlog: &Backlog, task: &Task, epic: Option<&Epic>) -> String {
+    let frontmatter = TaskFrontmatter {
+        id: &task.id,
+        title: &task.title,
+        state: format!("{:?}", task.state),
+        epic: epic.map(|e| wikilink(&e.id)),
+        depends: task.depends.iter().map(|d| wikilink(d)).collect(),
+        parent: task.parent.as_deref().map(wikilink),
+        priority: task.priority,
+        estimate: task.estimate,
+        assignee: task.assignee.as_deref(),
+        milestone: task.milestone.as_deref(),
+        sprint: task.sprint.as_deref(),
+        due: task.due,
+        // Obsidian tags cannot contain spaces
+        tags: task.tags.iter().map(|t| t.replace(' ', "-")).collect(),
+        aliases: vec![&task.title],
+    };
+
+    let mut body = format!("# {}\n", task.title);
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            body.push_str(&format!("\n{}\n", description));
+        }
+    }
+    if let Some(blocker) = &task.blocker {
+        body.push_str(&format!("\n> [!warning] Blocked\n> {}\n", blocker.reason));
+    }
+    if !task.done_when.is_empty() {
+        let check = if matches!(task.state, TaskState::Done) {
+            'x'
+        } else {
+            ' '
+        };
+        body.push_str("\n## Done when\n\n");
+        for criterion in &task.done_when {
+            body.push_str(&format!("- [{}] {}\n", check, criterion));
+        }
+    }
+    let depends: Vec<&Task> = task
+        .depends
+        .iter()
+        .filter_map(|d| backlog.find_task(d))
+        .collect();
+    if !depends.is_empty() {
+        body.push_str("\n## Depends on\n\n");
+        body.push_str(&task_list(&depends));
+    }
+    let blocks: Vec<&Task> = backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| t.depends.contains(&task.id))
+        .collect();
+    if !blocks.is_empty() {
+        body.push_str("\n## Blocks\n\n");
+        body.push_str(&task_list(&blocks));
+    }
+    note(&frontmatter, &body)
+}
+
+fn epic_note(epic: &Epic) -> String {
+    let tasks: Vec<&Task> = epic.tasks.iter().collect();
+    let frontmatter = EpicFrontmatter {
+        id: &epic.id,
+        title: &epic.title,
+        tasks: tasks.len(),
+        done: tasks
+            .iter()
+            .filter(|t| matches!(t.state, TaskState::Done))
+            .count(),
+        aliases: vec![&epic.title],
+    };
+    let mut body = format!("# {}\n", epic.title);
+    if !tasks.is_empty() {
+        body.push_str("\n## Tasks\n\n");
+        body.push_str(&task_list(&tasks));
+    }
+    note(&frontmatter, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_obsidian_notes() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-2
+                title: "Endpoints: v1"
+                description: Serve JSON.
+                depends: [T-1]
+                done_when: [Documented]
+                tags: [back end]
+        "#,
+        )
+        .unwrap();
+
+        let notes = obsidian_notes(&backlog);
+        let paths: Vec<&str> = notes.iter().map(|n| n.path.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            ["Tasks/T-1.md", "Tasks/T-2.md", "Epics/E-1.md", "app.md"]
+        );
+        assert_eq!(
+            notes[1].content,
+            "---\nid: T-2\ntitle: 'Endpoints: v1'\nstate: Todo\nepic: '[[E-1]]'\n\
+             depends:\n- '[[T-1]]'\ntags:\n- back-end\naliases:\n- 'Endpoints: v1'\n---\n\n\
+             # Endpoints: v1\n\nServe JSON.\n\n## Done when\n\n- [ ] Documented\n\n\
+             ## Depends on\n\n- [x] [[T-1]] Setup\n"
+        );
+        assert!(notes[0]
+            .content
+            .ends_with("## Blocks\n\n- [ ] [[T-2]] Endpoints: v1\n"));
+        assert!(notes[2].content.contains("tasks: 1\ndone: 0\n"));
+        assert!(notes[3].content.contains("- [[E-1]] API (0/1 done)\n"));
+    }
+}
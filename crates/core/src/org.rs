@@ -0,0 +1,408 @@
+use crate::ids::next_epic_id;
+use crate::rows::{apply_rows_without_ids, ImportSummary, TaskRow};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// TODO keywords read on import, with the state they stand for.
+const KEYWORDS: &[(&str, TaskState)] = &[
+    ("TODO", TaskState::Todo),
+    ("NEXT", TaskState::Todo),
+    ("STARTED", TaskState::InProgress),
+    ("DOING", TaskState::InProgress),
+    ("IN-PROGRESS", TaskState::InProgress),
+    ("WAITING", TaskState::Blocked),
+    ("BLOCKED", TaskState::Blocked),
+    ("HOLD", TaskState::Blocked),
+    ("DONE", TaskState::Done),
+    ("CANCELLED", TaskState::Done),
+    ("CANCELED", TaskState::Done),
+];
+
+/// Writes the backlog as an Org document, for Emacs users.
+///
+/// Standalone tasks come first as top-level headings, then each epic as a top-level heading with
+/// its tasks below. Task states become the `TODO`, `STARTED`, `WAITING` and `DONE` keywords,
+/// priorities 1 to 3 the `[#A]` to `[#C]` cookies, tags heading tags, due dates deadlines, and
+/// `done_when` criteria a checklist; IDs, dependencies and the other fields go to the property
+/// drawer. `import_org` reads the document back.
+pub fn format_org(backlog: &Backlog) -> String {
+    let mut org = format!(
+        "#+TITLE: {}\n#+TODO: TODO STARTED WAITING | DONE\n",
+        backlog.project
+    );
+    for task in &backlog.tasks {
+        write_task(&mut org, task, 1);
+    }
+    for epic in &backlog.epics {
+        org.push_str(&format!(
+            "\n* {}\n:PROPERTIES:\n:ID: {}\n:END:\n",
+            epic.title, epic.id
+        ));
+        for task in &epic.tasks {
+            write_task(&mut org, task, 2);
+        }
+    }
+    org
+}
+
+fn write_task(org: &mut String, task: &Task, level: usize) {
+    let keyword = match task.state {
+        TaskState::Todo => "TODO",
+        TaskState::InProgress => "STARTED",
+        TaskState::Blocked => "WAITING",
+        TaskState::Done => "DONE",
+    };
+    let cookie = match task.priority {
+        Some(priority @ 1..=3) => format!(" [#{}]", (b'A' + priority as u8 - 1) as char),
+        _ => String::new(),
+    };
+    // Org tags are made of letters, digits, `_`, `@`, `#` and `%`
+    let tags: Vec<String> = task
+        .tags
+        .iter()
+        .map(|tag| {
+            tag.chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || "_@#%".contains(c) {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let tags = if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" :{}:", tags.join(":"))
+    };
+    org.push_str(&format!(
+        "\n{} {}{} {}{}\n",
+        "*".repeat(level),
+        keyword,
+        cookie,
+        task.title,
+        tags
+    ));
+    if let Some(due) = task.due {
+        org.push_str(&format!("DEADLINE: <{}>\n", due.format("%Y-%m-%d %a")));
+    }
+
+    let mut properties = vec![("ID", task.id.clone())];
+    if !task.depends.is_empty() {
+        properties.push(("DEPENDS", task.depends.join(" ")));
+    }
+    if let Some(priority) = task.priority.filter(|p| !(1..=3).contains(p)) {
+        properties.push(("PRIORITY", priority.to_string()));
+    }
+    let optional = [
+        ("ESTIMATE", task.estimate.map(|e| e.to_string())),
+        ("ASSIGNEE", task.assignee.clone()),
+        ("MILESTONE", task.milestone.clone()),
+        ("SPRINT", task.sprint.clone()),
+        ("PARENT", task.parent.clone()),
+    ];
+    properties.extend(
+        optional
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name, v))),
+    );
+    org.push_str(":PROPERTIES:\n");
+    for (name, value) in properties {
+        org.push_str(&format!(":{}: {}\n", name, value));
+    }
+    org.push_str(":END:\n");
+
+    if let Some(description) = task.description.as_deref().map(str::trim) {
+        if !description.is_empty() {
+            org.push_str(description);
+            org.push('\n');
+        }
+    }
+    for criterion in &task.done_when {
+        org.push_str(&format!("- [ ] {}\n", criterion));
+    }
+}
+
+/// A heading of an Org document, with what is below it up to the next heading.
+#[derive(Debug, Default)]
+struct Heading {
+    level: usize,
+    keyword: Option<TaskState>,
+    priority: Option<u32>,
+    title: String,
+    tags: Vec<String>,
+    /// Properties of the drawer, by upper-case name.
+    properties: BTreeMap<String, String>,
+    deadline: Option<NaiveDate>,
+    body: Vec<String>,
+    checklist: Vec<String>,
+}
+
+/// Splits an Org document into its headings; text before the first heading is ignored.
+fn parse_headings(text: &str) -> Vec<Heading> {
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut in_drawer = false;
+    for line in text.lines() {
+        let stars = line.chars().take_while(|c| *c == '*').count();
+        if stars > 0 && line[stars..].starts_with(' ') {
+            headings.push(parse_heading_line(stars, &line[stars..]));
+            in_drawer = false;
+            continue;
+        }
+        let Some(heading) = headings.last_mut() else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if trimmed.eq_ignore_ascii_case(":PROPERTIES:") {
+            in_drawer = true;
+        } else if in_drawer {
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                in_drawer = false;
+            } else if let Some((name, value)) = trimmed
+                .strip_prefix(':')
+                .and_then(|rest| rest.split_once(':'))
+            {
+                heading
+                    .properties
+                    .insert(name.to_uppercase(), value.trim().to_string());
+            }
+        } else if let Some(position) = trimmed.find("DEADLINE: <") {
+            let date = trimmed[position + "DEADLINE: <".len()..].get(..10);
+            heading.deadline = date.and_then(|d| d.parse().ok());
+        } else if trimmed.starts_with("SCHEDULED:") || trimmed.starts_with("CLOSED:") {
+            continue;
+        } else if let Some(item) = ["- [ ] ", "- [X] ", "- [x] ", "- [-] "]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+        {
+            heading.checklist.push(item.trim().to_string());
+        } else {
+            heading.body.push(trimmed.to_string());
+        }
+    }
+    headings
+}
+
+/// Parses what follows the stars of a heading: `KEYWORD [#A] Title :tag:other:`.
+fn parse_heading_line(level: usize, rest: &str) -> Heading {
+    let mut rest = rest.trim();
+    let mut heading = Heading {
+        level,
+        ..Default::default()
+    };
+
+    let first_word = rest.split_whitespace().next().unwrap_or_default();
+    if let Some((_, state)) = KEYWORDS.iter().find(|(keyword, _)| *keyword == first_word) {
+        heading.keyword = Some(state.clone());
+        rest = rest[first_word.len()..].trim_start();
+    }
+    if let Some(letter) = rest
+        .strip_prefix("[#")
+        .and_then(|r| r.chars().next())
+        .filter(|c| c.is_ascii_uppercase() && rest[3..].starts_with(']'))
+    {
+        heading.priority = Some(u32::from(letter as u8 - b'A') + 1);
+        rest = rest[4..].trim_start();
+    }
+    if let Some((title, tags)) = rest.rsplit_once(char::is_whitespace) {
+        let is_tags = tags.len() > 2 && tags.starts_with(':') && tags.ends_with(':');
+        if is_tags {
+            heading.tags = tags
+                .trim_matches(':')
+                .split(':')
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+            rest = title.trim_end();
+        }
+    }
+    heading.title = rest.to_string();
+    heading
+}
+
+/// Returns the ID of the epic of an epic heading: its `ID` property, or else the epic with the same
+/// title, created if there is none.
+fn resolve_epic(backlog: &mut Backlog, heading: &Heading, summary: &mut ImportSummary) -> String {
+    let existing = match heading.properties.get("ID") {
+        Some(id) => backlog.epics.iter_mut().find(|e| &e.id == id),
+        None => backlog
+            .epics
+            .iter_mut()
+            .find(|e| e.title.eq_ignore_ascii_case(&heading.title)),
+    };
+    if let Some(epic) = existing {
+        epic.title = heading.title.clone();
+        return epic.id.clone();
+    }
+    let id = match heading.properties.get("ID") {
+        Some(id) => id.clone(),
+        None => next_epic_id(backlog),
+    };
+    backlog.epics.push(Epic {
+        id: id.clone(),
+        title: heading.title.clone(),
+        ..Default::default()
+    });
+    summary.new_epics.push(id.clone());
+    id
+}
+
+/// Imports an Org document into the backlog, on a best-effort basis.
+///
+/// Headings with a TODO keyword are tasks, matched by their `ID` property, or by title within
+/// their epic when they have none: known tasks are updated and others added. Top-level headings without a keyword are
+/// epics, holding the tasks below them, and tasks nested under a task get it as parent. Keywords
+/// other than the ones `format_org` writes are mapped leniently (`DOING` is InProgress,
+/// `CANCELLED` is Done, ...), and anything else in the document is ignored. Returns an error if
+/// the resulting backlog has missing dependencies or cycles.
+pub fn import_org(
+    backlog: &mut Backlog,
+    text: &str,
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let mut summary = ImportSummary::default();
+    let mut rows: Vec<(TaskRow, Vec<String>)> = Vec::new();
+    let mut epic: Option<(usize, String)> = None;
+    let mut tasks: Vec<(usize, String)> = Vec::new();
+
+    for heading in parse_headings(text) {
+        tasks.retain(|(level, _)| *level < heading.level);
+        if epic
+            .as_ref()
+            .is_some_and(|(level, _)| *level >= heading.level)
+        {
+            epic = None;
+        }
+        let Some(state) = heading.keyword.clone() else {
+            if tasks.is_empty() && epic.is_none() {
+                let id = resolve_epic(backlog, &heading, &mut summary);
+                epic = Some((heading.level, id));
+            }
+            continue;
+        };
+
+        let property = |name: &str| heading.properties.get(name).cloned();
+        let id = property("ID").unwrap_or_default();
+        let description = heading.body.join("\n").trim().to_string();
+        let row = TaskRow {
+            id: id.clone(),
+            title: heading.title.clone(),
+            epic: epic.as_ref().map(|(_, id)| id.clone()),
+            state: Some(format!("{:?}", state)),
+            priority: property("PRIORITY")
+                .and_then(|p| p.parse().ok())
+                .or(heading.priority),
+            estimate: property("ESTIMATE").and_then(|e| e.parse().ok()),
+            assignee: property("ASSIGNEE"),
+            depends: property("DEPENDS")
+                .unwrap_or_default()
+                .split([' ', ','])
+                .filter(|d| !d.is_empty())
+                .collect::<Vec<_>>()
+                .join(", "),
+            tags: heading.tags.join(", "),
+            due: heading.deadline,
+            milestone: property("MILESTONE"),
+            sprint: property("SPRINT"),
+            parent: property("PARENT").or_else(|| {
+                tasks
+                    .last()
+                    .map(|(_, id)| id.clone())
+                    .filter(|id| !id.is_empty())
+            }),
+            description: Some(description).filter(|d| !d.is_empty()),
+        };
+        tasks.push((heading.level, id));
+        rows.push((row, heading.checklist));
+    }
+
+    let (mut rows, checklists): (Vec<TaskRow>, Vec<Vec<String>>) = rows.into_iter().unzip();
+    summary.merge(apply_rows_without_ids(backlog, &mut rows, now)?);
+    for (row, checklist) in rows.into_iter().zip(checklists) {
+        let Some(task) = backlog.find_task_mut(&row.id) else {
+            continue;
+        };
+        if task.done_when == checklist {
+            continue;
+        }
+        task.done_when = checklist;
+        task.updated_at = Some(now);
+        if !summary.added.contains(&row.id) && !summary.updated.contains(&row.id) {
+            summary.unchanged = summary.unchanged.saturating_sub(1);
+            summary.updated.push(row.id);
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_org_round_trip() {
+        let yaml = r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+            priority: 1
+            tags: [infra, ci-cd]
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-2
+                title: Endpoints
+                state: Blocked
+                description: Serve JSON.
+                depends: [T-1]
+                done_when: [Documented]
+                estimate: 3
+                priority: 5
+                due: 2024-03-01
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let org = format_org(&backlog);
+        assert!(org.contains("\n* DONE [#A] Setup :infra:ci_cd:\n"));
+        assert!(org.contains(
+            "\n** WAITING Endpoints\nDEADLINE: <2024-03-01 Fri>\n:PROPERTIES:\n:ID: T-2\n\
+             :DEPENDS: T-1\n:PRIORITY: 5\n:ESTIMATE: 3\n:END:\nServe JSON.\n- [ ] Documented\n"
+        ));
+
+        let now = Utc::now();
+        let mut copy = backlog.clone();
+        let summary = import_org(&mut copy, &org, now).unwrap();
+        assert!(summary.added.is_empty() && summary.new_epics.is_empty());
+        assert_eq!(summary.updated, ["T-1"]);
+        assert_eq!(summary.unchanged, 1);
+
+        let mut empty = Backlog::default();
+        let summary = import_org(&mut empty, &org, now).unwrap();
+        assert_eq!(summary.added, ["T-1", "T-2"]);
+        assert_eq!(empty.epics[0].title, "API");
+        let task = &empty.epics[0].tasks[0];
+        assert!(matches!(task.state, TaskState::Blocked));
+        assert_eq!(task.done_when, ["Documented"]);
+        assert_eq!(task.priority, Some(5));
+
+        let hand_written =
+            "* Frontend\n** DOING [#B] Login page :ui:\n*** TODO Form\n* NEXT Docs\n";
+        let summary = import_org(&mut empty, hand_written, now).unwrap();
+        assert_eq!(summary.new_epics, ["E-2"]);
+        assert_eq!(summary.added, ["T-3", "T-4", "T-5"]);
+        let login = &empty.epics[1].tasks[0];
+        assert_eq!(
+            (login.priority, login.tags.as_slice()),
+            (Some(2), &["ui".to_string()][..])
+        );
+        assert!(matches!(login.state, TaskState::InProgress));
+        let summary = import_org(&mut empty, hand_written, now).unwrap();
+        assert!(summary.added.is_empty() && summary.new_epics.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Groups the remaining (not Done) tasks into dependency levels.
+///
+/// Every task in level N only depends on Done tasks or on tasks from levels before N, so all the tasks
+/// of a level can be worked on in parallel once the previous levels are finished. Level 0 contains the
+/// tasks that can start right away. Dependencies on unknown tasks are ignored, as in `get_ready_tasks`.
+/// Returns an error listing the tasks involved if several tasks share an ID, or if the remaining
+/// tasks contain a dependency cycle.
+pub fn execution_levels(backlog: &Backlog) -> Result<Vec<Vec<&Task>>, String> {
+    let all_tasks = backlog.all_tasks();
+
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<&str> = Vec::new();
+    for task in &all_tasks {
+        if !seen.insert(task.id.as_str()) && !duplicates.contains(&task.id.as_str()) {
+            duplicates.push(task.id.as_str());
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(format!("Duplicate task IDs: {}", duplicates.join(", ")));
+    }
+
+    let pending: Vec<&Task> = all_tasks
+        .iter()
+        .filter(|t| !matches!(t.state, TaskState::Done))
+        .copied()
+        .collect();
+
+    let pending_ids: Vec<&str> = pending.iter().map(|t| t.id.as_str()).collect();
+    let mut levels_by_id: HashMap<&str, usize> = HashMap::new();
+    let mut levels: Vec<Vec<&Task>> = Vec::new();
+
+    while levels_by_id.len() < pending.len() {
+        let level: Vec<&Task> = pending
+            .iter()
+            .filter(|t| !levels_by_id.contains_key(t.id.as_str()))
+            .filter(|t| {
+                t.depends.iter().all(|dep| {
+                    !pending_ids.contains(&dep.as_str()) || levels_by_id.contains_key(dep.as_str())
+                })
+            })
+            .copied()
+            .collect();
+
+        if level.is_empty() {
+            let stuck: Vec<&str> = pending
+                .iter()
+                .filter(|t| !levels_by_id.contains_key(t.id.as_str()))
+                .map(|t| t.id.as_str())
+                .collect();
+            return Err(format!(
+                "Dependency cycle among tasks: {}",
+                stuck.join(", ")
+            ));
+        }
+
+        for task in &level {
+            levels_by_id.insert(task.id.as_str(), levels.len());
+        }
+        levels.push(level);
+    }
+
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_by_dependency_depth() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "Done already"
+            state: Done
+          - id: T-2
+            title: "Needs T-1"
+            depends: [T-1]
+          - id: T-3
+            title: "Independent"
+          - id: T-4
+            title: "Needs T-2 and T-3"
+            depends: [T-2, T-3]
+          - id: T-5
+            title: "Needs T-4"
+            depends: [T-4, T-1]
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let levels = execution_levels(&backlog).unwrap();
+        let ids: Vec<Vec<&str>> = levels
+            .iter()
+            .map(|level| level.iter().map(|t| t.id.as_str()).collect())
+            .collect();
+
+        assert_eq!(ids, vec![vec!["T-2", "T-3"], vec!["T-4"], vec!["T-5"]]);
+    }
+
+    #[test]
+    fn duplicate_ids_are_not_a_cycle() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "First"
+          - id: T-1
+            title: "Same ID"
+          - id: T-2
+            title: "Needs T-1"
+            depends: [T-1]
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            execution_levels(&backlog).unwrap_err(),
+            "Duplicate task IDs: T-1"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Formats the plan of the backlog as PlantUML: a dependency diagram of all the tasks, then a
+/// Gantt chart of the remaining ones.
+///
+/// The dependency diagram groups tasks into a package per epic, colored by state, with an arrow
+/// from each task to the tasks depending on it. The Gantt chart starts on `start` and schedules
+/// the tasks not Done after the remaining tasks they depend on, one day per unit of estimate and at
+/// least one day each, with a separator per epic. Dependencies on unknown tasks are left out.
+pub fn format_plantuml(backlog: &Backlog, start: NaiveDate) -> String {
+    let mut lines = vec![
+        "@startuml".to_string(),
+        format!("title Dependencies of {}", label(&backlog.project)),
+        "hide stereotype".to_string(),
+        "skinparam rectangle {".to_string(),
+        "  BackgroundColor<<Done>> #C8E6C9".to_string(),
+        "  BackgroundColor<<InProgress>> #FFF59D".to_string(),
+        "  BackgroundColor<<Blocked>> #FFCDD2".to_string(),
+        "}".to_string(),
+    ];
+    for task in &backlog.tasks {
+        lines.push(node(task, ""));
+    }
+    for epic in &backlog.epics {
+        lines.push(format!(
+            "package \"{}: {}\" as {} {{",
+            label(&epic.id),
+            label(&epic.title),
+            alias(&epic.id)
+        ));
+        for task in &epic.tasks {
+            lines.push(node(task, "  "));
+        }
+        lines.push("}".to_string());
+    }
+    let tasks = backlog.all_tasks();
+    for task in &tasks {
+        for dep in &task.depends {
+            if tasks.iter().any(|t| &t.id == dep) {
+                lines.push(format!("{} --> {}", alias(dep), alias(&task.id)));
+            }
+        }
+    }
+    lines.push("@enduml".to_string());
+    lines.push(String::new());
+
+    lines.extend([
+        "@startgantt".to_string(),
+        format!("title Plan of {}", label(&backlog.project)),
+        format!("Project starts {}", start.format("%Y-%m-%d")),
+    ]);
+    let pending = |task: &Task| !matches!(task.state, TaskState::Done);
+    let groups = std::iter::once((None, &backlog.tasks))
+        .chain(backlog.epics.iter().map(|e| (Some(e), &e.tasks)));
+    for (epic, epic_tasks) in groups {
+        let remaining: Vec<&Task> = epic_tasks.iter().filter(|t| pending(t)).collect();
+        if remaining.is_empty() {
+            continue;
+        }
+        if let Some(epic) = epic {
+            lines.push(format!("-- {} --", bar(&epic.title)));
+        }
+        for task in remaining {
+            let days = task.estimate.map_or(1.0, |e| e.ceil().max(1.0));
+            lines.push(format!(
+                "[{} {}] as [{}] requires {} day{}",
+                bar(&task.id),
+                bar(&task.title),
+                alias(&task.id),
+                days,
+                if days == 1.0 { "" } else { "s" }
+            ));
+            match task.state {
+                TaskState::InProgress => {
+                    lines.push(format!("[{}] is colored in Gold", alias(&task.id)))
+                }
+                TaskState::Blocked => {
+                    lines.push(format!("[{}] is colored in Tomato", alias(&task.id)))
+                }
+                _ => {}
+            }
+        }
+    }
+    for task in tasks.iter().filter(|t| pending(t)) {
+        for dep in &task.depends {
+            if tasks.iter().any(|t| &t.id == dep && pending(t)) {
+                lines.push(format!("[{}] -> [{}]", alias(dep), alias(&task.id)));
+            }
+        }
+    }
+    lines.push("@endgantt".to_string());
+
+    let mut uml = lines.join("\n");
+    uml.push('\n');
+    uml
+}
+
+/// Returns the node of a task in the dependency diagram.
+fn node(task: &Task, indent: &str) -> String {
+    let state = match task.state {
+        TaskState::Todo => "Todo",
+        TaskState::InProgress => "InProgress",
+        TaskState::Blocked => "Blocked",
+        TaskState::Done => "Done",
+    };
+    format!(
+        "{}rectangle \"{}\\n{}\" as {} <<{}>>",
+        indent,
+        label(&task.id),
+        label(&task.title),
+        alias(&task.id),
+        state
+    )
+}
+
+/// Turns an ID into a PlantUML alias, which only holds letters, digits and underscores.
+fn alias(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Escapes a text quoted in the dependency diagram.
+fn label(text: &str) -> String {
+    text.replace('"', "'").replace(['\n', '\r'], " ")
+}
+
+/// Escapes a text between the brackets of a Gantt chart task or the dashes of a separator.
+fn bar(text: &str) -> String {
+    text.replace('[', "(")
+        .replace(']', ")")
+        .replace("--", "-")
+        .replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_plantuml() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: Weather
+        tasks:
+          - id: T-1
+            title: Setup "repo"
+            state: Done
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-2
+                title: Fetch [forecast]
+                depends: [T-1]
+                estimate: 2.5
+                state: InProgress
+              - id: T-3
+                title: Cache
+                depends: [T-2, T-9]
+        "#,
+        )
+        .unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let uml = format_plantuml(&backlog, start);
+
+        assert!(uml.starts_with("@startuml\ntitle Dependencies of Weather\n"));
+        assert!(uml.contains("rectangle \"T-1\\nSetup 'repo'\" as T_1 <<Done>>"));
+        assert!(uml.contains("package \"E-1: API\" as E_1 {\n  rectangle \"T-2"));
+        assert!(uml.contains("T_1 --> T_2\nT_2 --> T_3\n@enduml"));
+
+        assert!(uml.contains("Project starts 2024-03-04\n-- API --\n"));
+        assert!(!uml.contains("[T-1 "));
+        assert!(uml.contains("[T-2 Fetch (forecast)] as [T_2] requires 3 days\n"));
+        assert!(uml.contains("[T_2] is colored in Gold\n"));
+        assert!(uml.contains("[T-3 Cache] as [T_3] requires 1 day\n"));
+        assert!(uml.ends_with("[T_2] -> [T_3]\n@endgantt\n"));
+    }
+}
@@ -0,0 +1,320 @@
+use serde::Serialize;
+use taskai_schema::{Backlog, ExternalRef};
+
+/// A problem found with an external reference or link of a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefIssue {
+    /// ID of the task carrying the reference.
+    pub task_id: String,
+    /// The reference as written (`system:id` for external refs, the URL for links).
+    pub reference: String,
+    /// Description of the problem.
+    pub message: String,
+}
+
+/// Checks the format of every external reference and link in the backlog.
+///
+/// GitHub references must look like `owner/repo#123`, GitLab references like `group/project#123`,
+/// Jira references like `PROJ-123`, and links must be absolute http(s) URLs. References to other systems only need a non-empty ID.
+pub fn check_ref_formats(backlog: &Backlog) -> Vec<RefIssue> {
+    let mut issues = Vec::new();
+
+    for task in backlog.all_tasks() {
+        for external_ref in &task.external_refs {
+            if let Err(message) = check_ref_format(external_ref) {
+                issues.push(RefIssue {
+                    task_id: task.id.clone(),
+                    reference: external_ref.to_string(),
+                    message,
+                });
+            }
+        }
+
+        for link in &task.links {
+            if !is_http_url(link) {
+                issues.push(RefIssue {
+                    task_id: task.id.clone(),
+                    reference: link.clone(),
+                    message: "Link is not an absolute http(s) URL".to_string(),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Checks that every GitHub/Jira reference and link in the backlog points to something that exists.
+///
+/// GitHub issues are looked up through the REST API (authenticated with `GITHUB_TOKEN` when set).
+/// Jira tickets require `JIRA_BASE_URL`, and optionally `JIRA_EMAIL` and `JIRA_API_TOKEN`.
+/// References with an invalid format are skipped; use `check_ref_formats` to report them.
+pub async fn check_refs_online(backlog: &Backlog) -> Vec<RefIssue> {
+    let client = crate::web::http_client();
+    let mut issues = Vec::new();
+
+    for task in backlog.all_tasks() {
+        for external_ref in &task.external_refs {
+            if check_ref_format(external_ref).is_err() {
+                continue;
+            }
+
+            tracing::debug!(system = %external_ref.system, id = %external_ref.id, "looking up reference");
+            let result = match external_ref.system.as_str() {
+                "github" => github_issue_exists(&client, &external_ref.id).await,
+                "jira" => jira_issue_exists(&client, &external_ref.id).await,
+                _ => continue,
+            };
+
+            if let Err(message) = result {
+                issues.push(RefIssue {
+                    task_id: task.id.clone(),
+                    reference: external_ref.to_string(),
+                    message,
+                });
+            }
+        }
+
+        for link in &task.links {
+            if !is_http_url(link) {
+                continue;
+            }
+
+            if let Err(message) = link_is_alive(&client, link).await {
+                issues.push(RefIssue {
+                    task_id: task.id.clone(),
+                    reference: link.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Checks the references and links of the backlog like [`check_refs_online`], blocking until it is
+/// done, for programs that are not async. Must not be called from within an async runtime.
+pub fn check_refs_online_blocking(backlog: &Backlog) -> Result<Vec<RefIssue>, String> {
+    crate::blocking::block_on(check_refs_online(backlog))
+}
+
+/// Validates the ID of an external reference according to its system.
+fn check_ref_format(external_ref: &ExternalRef) -> Result<(), String> {
+    let id = external_ref.id.trim();
+    if id.is_empty() {
+        return Err("Reference has an empty ID".to_string());
+    }
+
+    match external_ref.system.as_str() {
+        "github" => parse_github_ref(id)
+            .map(|_| ())
+            .ok_or_else(|| "GitHub reference must look like owner/repo#123".to_string()),
+        "gitlab" => parse_gitlab_ref(id)
+            .map(|_| ())
+            .ok_or_else(|| "GitLab reference must look like group/project#123".to_string()),
+        "jira" => {
+            if is_jira_key(id) {
+                Ok(())
+            } else {
+                Err("Jira reference must look like PROJ-123".to_string())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Splits a GitHub reference of the form `owner/repo#123` into its parts.
+pub(crate) fn parse_github_ref(id: &str) -> Option<(&str, &str, u64)> {
+    let (repo_path, number) = id.split_once('#')?;
+    let (owner, repo) = repo_path.split_once('/')?;
+    let valid_name = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    };
+
+    if !valid_name(owner) || !valid_name(repo) {
+        return None;
+    }
+
+    number.parse().ok().map(|n| (owner, repo, n))
+}
+
+/// Splits a GitLab reference of the form `group/project#123` into the project path and the issue
+/// IID. The project may be nested in subgroups, such as `group/subgroup/project#123`.
+pub(crate) fn parse_gitlab_ref(id: &str) -> Option<(&str, u64)> {
+    let (path, iid) = id.rsplit_once('#')?;
+    let valid_name = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    };
+    if !path.contains('/') || !path.split('/').all(valid_name) {
+        return None;
+    }
+    iid.parse().ok().map(|iid| (path, iid))
+}
+
+/// Returns true if the string looks like a Jira issue key (`PROJ-123`).
+fn is_jira_key(id: &str) -> bool {
+    match id.split_once('-') {
+        Some((project, number)) => {
+            project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Returns true if the string is an absolute http(s) URL with a host.
+fn is_http_url(link: &str) -> bool {
+    let rest = match link
+        .strip_prefix("https://")
+        .or_else(|| link.strip_prefix("http://"))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    !host.is_empty() && !link.contains(char::is_whitespace)
+}
+
+async fn github_issue_exists(client: &reqwest::Client, id: &str) -> Result<(), String> {
+    let (owner, repo, number) = match parse_github_ref(id) {
+        Some(parts) => parts,
+        None => return Ok(()),
+    };
+
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/issues/{}",
+        owner, repo, number
+    );
+    let mut request = client.get(&url).header("User-Agent", "taskai");
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("GitHub request failed: {}", e))?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(()),
+        404 | 410 => Err("GitHub issue does not exist".to_string()),
+        status => Err(format!("GitHub API returned status {}", status)),
+    }
+}
+
+async fn jira_issue_exists(client: &reqwest::Client, key: &str) -> Result<(), String> {
+    let base_url = std::env::var("JIRA_BASE_URL")
+        .map_err(|_| "JIRA_BASE_URL environment variable not set".to_string())?;
+
+    let url = format!(
+        "{}/rest/api/2/issue/{}?fields=key",
+        base_url.trim_end_matches('/'),
+        key
+    );
+    let mut request = client.get(&url);
+    if let (Ok(email), Ok(token)) = (std::env::var("JIRA_EMAIL"), std::env::var("JIRA_API_TOKEN")) {
+        request = request.basic_auth(email, Some(token));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Jira request failed: {}", e))?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(()),
+        404 => Err("Jira issue does not exist".to_string()),
+        status => Err(format!("Jira API returned status {}", status)),
+    }
+}
+
+async fn link_is_alive(client: &reqwest::Client, link: &str) -> Result<(), String> {
+    let response = client
+        .head(link)
+        .header("User-Agent", "taskai")
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    // Some servers reject HEAD requests; only treat missing resources as dead links.
+    if status.as_u16() == 404 || status.as_u16() == 410 {
+        Err(format!("Link returned status {}", status.as_u16()))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ref_formats() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "Task"
+            external_refs:
+              - system: github
+                id: graniet/taskai#12
+              - system: github
+                id: taskai#12
+              - system: jira
+                id: PROJ-42
+              - system: jira
+                id: proj42
+              - system: gitlab
+                id: acme/tools/app#3
+              - system: gitlab
+                id: app#3
+            links:
+              - https://example.com/spec
+              - example.com
+        "#;
+
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let issues = check_ref_formats(&backlog);
+        let references: Vec<&str> = issues.iter().map(|i| i.reference.as_str()).collect();
+
+        assert_eq!(
+            references,
+            vec![
+                "github:taskai#12",
+                "jira:proj42",
+                "gitlab:app#3",
+                "example.com"
+            ]
+        );
+    }
+
+    /// Tests the blocking online check, without references that need a lookup.
+    #[test]
+    fn refs_online_blocking() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "Task"
+            external_refs:
+              - system: jira
+                id: proj42
+            links:
+              - example.com
+        "#;
+
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        assert!(check_refs_online_blocking(&backlog).unwrap().is_empty());
+    }
+}
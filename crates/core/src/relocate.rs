@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use taskai_schema::{Backlog, Epic, Task};
+
+/// Moves a task and its subtasks to another epic, or to the standalone task list when `epic` is
+/// `None`.
+///
+/// Subtasks are found through their `parent` field, transitively. Tasks keep their IDs, so
+/// dependencies stay intact. Returns the IDs of the moved tasks, in backlog order, or an error if
+/// the task or the epic does not exist.
+pub fn move_task(
+    backlog: &mut Backlog,
+    task_id: &str,
+    epic: Option<&str>,
+) -> Result<Vec<String>, String> {
+    if backlog.find_task(task_id).is_none() {
+        return Err(format!(
+            "Task with ID '{}' not found in the backlog.",
+            task_id
+        ));
+    }
+    if let Some(epic_id) = epic {
+        if !backlog.epics.iter().any(|e| e.id == epic_id) {
+            return Err(format!(
+                "Epic with ID '{}' not found in the backlog.",
+                epic_id
+            ));
+        }
+    }
+
+    let ids = with_subtasks(backlog, vec![task_id.to_string()]);
+    let mut moved: Vec<Task> = Vec::new();
+    let mut take = |tasks: &mut Vec<Task>| {
+        let (taken, kept) = tasks.drain(..).partition(|t: &Task| ids.contains(&t.id));
+        *tasks = kept;
+        moved.extend(taken);
+    };
+    take(&mut backlog.tasks);
+    for epic in &mut backlog.epics {
+        take(&mut epic.tasks);
+    }
+
+    let moved_ids = moved.iter().map(|t| t.id.clone()).collect();
+    match epic.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+        Some(epic) => epic.tasks.extend(moved),
+        None => backlog.tasks.extend(moved),
+    }
+    Ok(moved_ids)
+}
+
+/// Adds the subtasks of the given tasks to them, transitively, following their `parent` field.
+fn with_subtasks(backlog: &Backlog, mut ids: Vec<String>) -> Vec<String> {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for task in backlog.all_tasks() {
+            let is_subtask = task.parent.as_ref().is_some_and(|p| ids.contains(p));
+            if is_subtask && !ids.contains(&task.id) {
+                ids.push(task.id.clone());
+                changed = true;
+            }
+        }
+    }
+    ids
+}
+
+/// How to split an epic: the tasks going to a new epic.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EpicSplit {
+    /// Title of the new epic.
+    pub title: String,
+    /// IDs of the tasks moving to the new epic.
+    pub tasks: Vec<String>,
+}
+
+/// Splits an epic in two, moving the given tasks (with their subtasks) to a new epic inserted right
+/// after it.
+///
+/// Task IDs and dependencies are preserved. Returns the IDs of the moved tasks, or an error if a
+/// task does not belong to the epic, the new epic ID is taken, or the split would leave either
+/// epic empty; the backlog is left unchanged on error.
+pub fn split_epic(
+    backlog: &mut Backlog,
+    epic_id: &str,
+    new_epic_id: &str,
+    split: &EpicSplit,
+) -> Result<Vec<String>, String> {
+    let Some(index) = backlog.epics.iter().position(|e| e.id == epic_id) else {
+        return Err(format!(
+            "Epic with ID '{}' not found in the backlog.",
+            epic_id
+        ));
+    };
+    if backlog.epics.iter().any(|e| e.id == new_epic_id) {
+        return Err(format!("An epic with ID '{}' already exists.", new_epic_id));
+    }
+    if split.tasks.is_empty() {
+        return Err("No tasks selected for the new epic.".to_string());
+    }
+    for id in &split.tasks {
+        if !backlog.epics[index].tasks.iter().any(|t| &t.id == id) {
+            return Err(format!("Task {} does not belong to epic {}.", id, epic_id));
+        }
+    }
+    let moving = with_subtasks(backlog, split.tasks.clone());
+    if backlog.epics[index]
+        .tasks
+        .iter()
+        .all(|t| moving.contains(&t.id))
+    {
+        return Err(format!(
+            "Splitting would move every task out of epic {}.",
+            epic_id
+        ));
+    }
+
+    backlog.epics.insert(
+        index + 1,
+        Epic {
+            id: new_epic_id.to_string(),
+            title: split.title.clone(),
+            ..Default::default()
+        },
+    );
+    let mut moved = Vec::new();
+    for id in &split.tasks {
+        if !moved.contains(id) {
+            moved.extend(move_task(backlog, id, Some(new_epic_id))?);
+        }
+    }
+    Ok(moved)
+}
+
+/// Parses an epic split suggested by an LLM, as YAML, possibly wrapped in a code block.
+pub fn parse_epic_split(response: &str) -> Result<EpicSplit, String> {
+    serde_yaml::from_str(yaml_block(response))
+        .map_err(|e| format!("Invalid epic split suggestion: {}", e))
+}
+
+/// Returns the content of the first code block of an LLM response, or the whole response when it
+/// has none.
+pub(crate) fn yaml_block(response: &str) -> &str {
+    match response.split_once("```") {
+        Some((_, rest)) => {
+            let block = rest.split("```").next().unwrap_or_default();
+            // Skip the language tag of the code block, if any.
+            match block.split_once('\n') {
+                Some((tag, body)) if !tag.contains(':') => body,
+                _ => block,
+            }
+        }
+        None => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_with_subtasks() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: First
+            tasks:
+              - id: T-1
+                title: Parent
+              - id: T-2
+                title: Subtask
+                parent: T-1
+              - id: T-3
+                title: Sub-subtask
+                parent: T-2
+              - id: T-4
+                title: Other
+                depends: [T-1]
+          - id: E-2
+            title: Second
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let moved = move_task(&mut backlog, "T-1", Some("E-2")).unwrap();
+        assert_eq!(moved, vec!["T-1", "T-2", "T-3"]);
+        assert_eq!(backlog.epics[0].tasks.len(), 1);
+        assert_eq!(backlog.epics[1].tasks.len(), 3);
+        assert!(backlog.validate().is_ok());
+
+        move_task(&mut backlog, "T-4", None).unwrap();
+        assert_eq!(backlog.tasks[0].id, "T-4");
+
+        assert!(move_task(&mut backlog, "T-4", Some("E-9")).is_err());
+        assert!(move_task(&mut backlog, "T-9", None).is_err());
+    }
+
+    #[test]
+    fn split_and_parse() {
+        let yaml = r#"
+        project: test
+        epics:
+          - id: E-1
+            title: Big
+            tasks:
+              - id: T-1
+                title: API
+              - id: T-2
+                title: UI
+              - id: T-3
+                title: UI subtask
+                parent: T-2
+                depends: [T-1]
+          - id: E-2
+            title: Other
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let split =
+            parse_epic_split("Sure:\n```yaml\ntitle: Frontend\ntasks: [T-2]\n```\n").unwrap();
+        assert_eq!(split.title, "Frontend");
+
+        let moved = split_epic(&mut backlog, "E-1", "E-3", &split).unwrap();
+        assert_eq!(moved, vec!["T-2", "T-3"]);
+        let epic_ids: Vec<&str> = backlog.epics.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(epic_ids, vec!["E-1", "E-3", "E-2"]);
+        assert!(backlog.validate().is_ok());
+
+        let everything = EpicSplit {
+            title: "All".to_string(),
+            tasks: vec!["T-1".to_string()],
+        };
+        assert!(split_epic(&mut backlog, "E-1", "E-4", &everything).is_err());
+        let epic_ids: Vec<&str> = backlog.epics.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(epic_ids, vec!["E-1", "E-3", "E-2"]);
+        assert_eq!(backlog.epics[0].tasks.len(), 1);
+    }
+}
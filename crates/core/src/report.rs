@@ -0,0 +1,207 @@
+use crate::stats::sum;
+use chrono::{Datelike, Days, NaiveDate};
+use serde::Serialize;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Unit in which work is counted by reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkUnit {
+    /// Sum of task estimates; unestimated tasks count for nothing.
+    Estimate,
+    /// Number of tasks.
+    Tasks,
+}
+
+/// Work in the backlog at the end of a day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BurndownDay {
+    pub date: NaiveDate,
+    /// Total work of the tasks existing that day.
+    pub scope: f64,
+    /// Work completed up to that day.
+    pub completed: f64,
+    /// Work left to do.
+    pub remaining: f64,
+    /// Average work completed per week over the rolling window ending that day.
+    pub velocity: f64,
+}
+
+/// Work completed during a week.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyVelocity {
+    /// Monday of the week.
+    pub week: NaiveDate,
+    pub completed: f64,
+}
+
+/// Burndown and velocity of a backlog over time.
+#[derive(Debug, Clone, Serialize)]
+pub struct Burndown {
+    pub unit: WorkUnit,
+    /// One entry per day, oldest first.
+    pub days: Vec<BurndownDay>,
+    /// One entry per week, oldest first.
+    pub weeks: Vec<WeeklyVelocity>,
+    /// Number of weeks the rolling velocity is averaged over.
+    pub window_weeks: u32,
+    /// Date the remaining work would be done at the current velocity, if there is any velocity.
+    pub projected_completion: Option<NaiveDate>,
+}
+
+/// Computes the burndown and velocity of the backlog from task creation and completion dates.
+///
+/// Work is counted in estimates when any task carries one, and in tasks otherwise. Archived tasks
+/// count as completed work. Tasks without a creation date are part of the scope from the start, and
+/// Done tasks completed before the start or without a completion date are completed from the start,
+/// without adding to the velocity. The series starts at `since`, or at the earliest creation or
+/// completion date, and ends `today`.
+pub fn burndown(
+    backlog: &Backlog,
+    since: Option<NaiveDate>,
+    today: NaiveDate,
+    window_weeks: u32,
+) -> Burndown {
+    let mut tasks = backlog.all_tasks();
+    tasks.extend(backlog.archive.iter());
+
+    let unit = if tasks.iter().any(|t| t.estimate.is_some()) {
+        WorkUnit::Estimate
+    } else {
+        WorkUnit::Tasks
+    };
+    let work = |task: &Task| match unit {
+        WorkUnit::Estimate => task.estimate.unwrap_or(0.0),
+        WorkUnit::Tasks => 1.0,
+    };
+
+    let created = |task: &Task| task.created_at.map(|at| at.date_naive());
+    let completed = |task: &Task| match task.state {
+        TaskState::Done => Some(task.completed_at.map(|at| at.date_naive())),
+        _ => None,
+    };
+
+    let start = since
+        .or_else(|| {
+            tasks
+                .iter()
+                .flat_map(|t| [created(t), completed(t).flatten()])
+                .flatten()
+                .min()
+        })
+        .unwrap_or(today)
+        .min(today);
+    let window_weeks = window_weeks.max(1);
+    let window_days = 7 * u64::from(window_weeks);
+
+    // Total work of the tasks matching the predicate
+    let work_of = |predicate: &dyn Fn(&Task) -> bool| -> f64 {
+        sum(tasks.iter().filter(|t| predicate(t)).map(|t| work(t)))
+    };
+
+    // Work completed before the series starts is done from the start, without counting as velocity
+    let mut done = work_of(&|t| completed(t).is_some_and(|d| d.is_none_or(|d| d < start)));
+    let mut days = Vec::new();
+    // Work completed each day of the series
+    let mut daily: Vec<f64> = Vec::new();
+    for date in start.iter_days().take_while(|d| *d <= today) {
+        let scope = work_of(&|t| created(t).is_none_or(|d| d <= date));
+        let today_done = work_of(&|t| completed(t) == Some(Some(date)));
+        done += today_done;
+        daily.push(today_done);
+        let window_start = daily.len().saturating_sub(window_days as usize);
+        let velocity = daily[window_start..].iter().sum::<f64>() / f64::from(window_weeks);
+
+        days.push(BurndownDay {
+            date,
+            scope,
+            completed: done,
+            remaining: (scope - done).max(0.0),
+            velocity,
+        });
+    }
+
+    let mut weeks: Vec<WeeklyVelocity> = Vec::new();
+    for (day, day_done) in days.iter().zip(daily) {
+        let week = day.date - Days::new(u64::from(day.date.weekday().num_days_from_monday()));
+        match weeks.last_mut() {
+            Some(last) if last.week == week => last.completed += day_done,
+            _ => weeks.push(WeeklyVelocity {
+                week,
+                completed: day_done,
+            }),
+        }
+    }
+
+    let projected_completion = days.last().and_then(|last| {
+        if last.remaining == 0.0 || last.velocity == 0.0 {
+            return None;
+        }
+        let days_left = (last.remaining / last.velocity * 7.0).ceil() as u64;
+        today.checked_add_days(Days::new(days_left))
+    });
+
+    Burndown {
+        unit,
+        days,
+        weeks,
+        window_weeks,
+        projected_completion,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burndown() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Done early
+            state: Done
+            estimate: 3
+            created_at: 2024-01-01T09:00:00Z
+            completed_at: 2024-01-02T09:00:00Z
+          - id: T-2
+            title: Added later
+            estimate: 2
+            created_at: 2024-01-08T09:00:00Z
+          - id: T-3
+            title: Done this week
+            state: Done
+            estimate: 1
+            created_at: 2024-01-01T09:00:00Z
+            completed_at: 2024-01-09T09:00:00Z
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+        let report = burndown(&backlog, None, today, 1);
+        assert_eq!(report.unit, WorkUnit::Estimate);
+        assert_eq!(report.days.len(), 10);
+        assert_eq!(report.days[0].scope, 4.0);
+        assert_eq!(report.days[1].remaining, 1.0);
+        let last = report.days.last().unwrap();
+        assert_eq!(
+            (last.scope, last.completed, last.remaining),
+            (6.0, 4.0, 2.0)
+        );
+        assert_eq!(last.velocity, 1.0);
+
+        // 2024-01-01 is a Monday
+        let weekly: Vec<f64> = report.weeks.iter().map(|w| w.completed).collect();
+        assert_eq!(weekly, vec![3.0, 1.0]);
+        assert_eq!(
+            report.projected_completion,
+            NaiveDate::from_ymd_opt(2024, 1, 24)
+        );
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 5);
+        let report = burndown(&backlog, since, today, 1);
+        assert_eq!(report.days[0].completed, 3.0);
+        assert_eq!(report.weeks[0].completed, 0.0);
+    }
+}
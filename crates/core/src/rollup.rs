@@ -0,0 +1,136 @@
+use serde::Serialize;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// Dimension used to group tasks when rolling up estimates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupBy {
+    /// Group by epic; standalone tasks are grouped under `(no epic)`.
+    Epic,
+    /// Group by the `milestone` field of tasks.
+    Milestone,
+    /// Group by the `sprint` field of tasks.
+    Sprint,
+}
+
+/// Estimate totals for one group of tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateRollup {
+    /// Name of the group (epic ID, milestone or sprint).
+    pub group: String,
+    /// Number of tasks in the group.
+    pub tasks: usize,
+    /// Number of tasks in the group without an estimate.
+    pub unestimated: usize,
+    /// Sum of the estimates of Done tasks.
+    pub completed: f64,
+    /// Sum of the estimates of tasks that are not Done.
+    pub remaining: f64,
+    /// Sum of all estimates in the group.
+    pub total: f64,
+}
+
+impl EstimateRollup {
+    fn new(group: String) -> Self {
+        EstimateRollup {
+            group,
+            tasks: 0,
+            unestimated: 0,
+            completed: 0.0,
+            remaining: 0.0,
+            total: 0.0,
+        }
+    }
+
+    fn add(&mut self, task: &Task) {
+        self.tasks += 1;
+        self.total += task.estimate.unwrap_or(0.0);
+        match task.estimate {
+            Some(estimate) if matches!(task.state, TaskState::Done) => self.completed += estimate,
+            Some(estimate) => self.remaining += estimate,
+            None => self.unestimated += 1,
+        }
+    }
+}
+
+/// Computes completed and remaining estimate totals per epic, milestone or sprint.
+///
+/// Groups appear in the order they are first encountered in the backlog. Tasks without a milestone or
+/// sprint are grouped under `(none)`.
+pub fn estimate_rollups(backlog: &Backlog, by: RollupBy) -> Vec<EstimateRollup> {
+    let mut rollups: Vec<EstimateRollup> = Vec::new();
+
+    let mut add = |group: &str, task: &Task| {
+        match rollups.iter_mut().find(|r| r.group == group) {
+            Some(rollup) => rollup.add(task),
+            None => {
+                let mut rollup = EstimateRollup::new(group.to_string());
+                rollup.add(task);
+                rollups.push(rollup);
+            }
+        }
+    };
+
+    match by {
+        RollupBy::Epic => {
+            for epic in &backlog.epics {
+                for task in &epic.tasks {
+                    add(&epic.id, task);
+                }
+            }
+            for task in &backlog.tasks {
+                add("(no epic)", task);
+            }
+        }
+        RollupBy::Milestone | RollupBy::Sprint => {
+            for task in backlog.all_tasks() {
+                let group = match by {
+                    RollupBy::Milestone => task.milestone.as_deref(),
+                    _ => task.sprint.as_deref(),
+                };
+                add(group.unwrap_or("(none)"), task);
+            }
+        }
+    }
+
+    rollups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollup_by_milestone() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: "One"
+            state: Done
+            estimate: 2
+            milestone: M1
+          - id: T-2
+            title: "Two"
+            estimate: 3
+            milestone: M1
+          - id: T-3
+            title: "Three"
+            milestone: M2
+          - id: T-4
+            title: "Four"
+            estimate: 5
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let rollups = estimate_rollups(&backlog, RollupBy::Milestone);
+
+        assert_eq!(rollups.len(), 3);
+        assert_eq!(rollups[0].group, "M1");
+        assert_eq!(rollups[0].completed, 2.0);
+        assert_eq!(rollups[0].remaining, 3.0);
+        assert_eq!(rollups[1].group, "M2");
+        assert_eq!(rollups[1].unestimated, 1);
+        assert_eq!(rollups[2].group, "(none)");
+        assert_eq!(rollups[2].total, 5.0);
+    }
+}
@@ -0,0 +1,415 @@
+use crate::ids::next_task_id;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::mem;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// A task flattened into a single row, for spreadsheets.
+///
+/// Lists (`depends`, `tags`) are comma-separated. Every column but `id` and `title` may be left out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskRow {
+    pub id: String,
+    pub title: String,
+    /// ID of the epic containing the task; empty for standalone tasks.
+    pub epic: Option<String>,
+    pub state: Option<String>,
+    pub priority: Option<u32>,
+    pub estimate: Option<f64>,
+    pub assignee: Option<String>,
+    pub depends: String,
+    pub tags: String,
+    pub due: Option<NaiveDate>,
+    pub milestone: Option<String>,
+    pub sprint: Option<String>,
+    pub parent: Option<String>,
+    pub description: Option<String>,
+}
+
+impl TaskRow {
+    fn new(task: &Task, epic: Option<&str>) -> Self {
+        TaskRow {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            epic: epic.map(str::to_string),
+            state: Some(format!("{:?}", task.state)),
+            priority: task.priority,
+            estimate: task.estimate,
+            assignee: task.assignee.clone(),
+            depends: task.depends.join(", "),
+            tags: task.tags.join(", "),
+            due: task.due,
+            milestone: task.milestone.clone(),
+            sprint: task.sprint.clone(),
+            parent: task.parent.clone(),
+            description: task.description.clone(),
+        }
+    }
+}
+
+/// Names of the columns of a row, as in the header of a CSV file.
+pub const TASK_ROW_COLUMNS: [&str; 14] = [
+    "id",
+    "title",
+    "epic",
+    "state",
+    "priority",
+    "estimate",
+    "assignee",
+    "depends",
+    "tags",
+    "due",
+    "milestone",
+    "sprint",
+    "parent",
+    "description",
+];
+
+/// Tasks added and updated by an import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    /// Number of rows matching their task exactly.
+    pub unchanged: usize,
+    /// Epics created because rows referenced them.
+    pub new_epics: Vec<String>,
+}
+
+impl ImportSummary {
+    /// Adds the outcome of another import to the summary.
+    pub(crate) fn merge(&mut self, other: ImportSummary) {
+        self.added.extend(other.added);
+        self.updated.extend(other.updated);
+        self.unchanged += other.unchanged;
+        self.new_epics.extend(other.new_epics);
+    }
+}
+
+/// Flattens the tasks of the backlog into rows: standalone tasks first, then the tasks of each
+/// epic.
+pub fn task_rows(backlog: &Backlog) -> Vec<TaskRow> {
+    let standalone = backlog.tasks.iter().map(|t| TaskRow::new(t, None));
+    let in_epics = backlog
+        .epics
+        .iter()
+        .flat_map(|e| e.tasks.iter().map(|t| TaskRow::new(t, Some(&e.id))));
+    standalone.chain(in_epics).collect()
+}
+
+/// Applies rows to the backlog: tasks are matched by ID and updated in place, unknown IDs are
+/// added, and tasks without a row are left untouched.
+///
+/// Only the fields of the given `columns` (among [`TASK_ROW_COLUMNS`]) are set; the others keep
+/// their values. Tasks are moved to the epic of their row, and missing epics are created. Returns
+/// an error, leaving the backlog in an unspecified state, if a row is invalid or the resulting
+/// backlog has missing dependencies or cycles.
+pub fn apply_task_rows(
+    backlog: &mut Backlog,
+    rows: &[TaskRow],
+    columns: &[&str],
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let has = |column: &str| columns.contains(&column);
+    let mut summary = ImportSummary::default();
+    let mut seen: Vec<&str> = Vec::new();
+
+    for (line, row) in rows.iter().enumerate() {
+        let row_number = line + 1;
+        if row.id.trim().is_empty() || row.title.trim().is_empty() {
+            return Err(format!("Row {} needs both an id and a title.", row_number));
+        }
+        if seen.contains(&row.id.as_str()) {
+            return Err(format!(
+                "Row {}: task {} appears more than once.",
+                row_number, row.id
+            ));
+        }
+        seen.push(&row.id);
+        let current = task_rows(backlog).into_iter().find(|r| r.id == row.id);
+        // Columns left out keep the values of the task
+        let base = current.clone().unwrap_or_default();
+        let state = match pick(has("state"), &row.state, &base.state) {
+            Some(state) => parse_state(&state)
+                .ok_or_else(|| format!("Row {}: unknown state '{}'.", row_number, state))?,
+            None => TaskState::Todo,
+        };
+        let epic = pick(has("epic"), &row.epic, &base.epic)
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty());
+
+        // Compare in the form rows are exported in, so that `done` matches a Done task
+        let normalized = TaskRow {
+            id: row.id.clone(),
+            title: row.title.clone(),
+            epic: epic.clone(),
+            state: Some(format!("{:?}", state)),
+            priority: pick(has("priority"), &row.priority, &base.priority),
+            estimate: pick(has("estimate"), &row.estimate, &base.estimate),
+            assignee: pick(has("assignee"), &row.assignee, &base.assignee),
+            depends: match has("depends") {
+                true => split_list(&row.depends).join(", "),
+                false => base.depends.clone(),
+            },
+            tags: match has("tags") {
+                true => split_list(&row.tags).join(", "),
+                false => base.tags.clone(),
+            },
+            due: pick(has("due"), &row.due, &base.due),
+            milestone: pick(has("milestone"), &row.milestone, &base.milestone),
+            sprint: pick(has("sprint"), &row.sprint, &base.sprint),
+            parent: pick(has("parent"), &row.parent, &base.parent),
+            description: pick(has("description"), &row.description, &base.description),
+        };
+        if current.as_ref() == Some(&normalized) {
+            summary.unchanged += 1;
+            continue;
+        }
+        let existing = current.is_some();
+        if !existing && backlog.has_task(&row.id) {
+            return Err(format!(
+                "Row {}: task {} is archived and cannot be updated.",
+                row_number, row.id
+            ));
+        }
+
+        let update = |task: &mut Task| {
+            task.title = normalized.title.clone();
+            if mem::discriminant(&task.state) != mem::discriminant(&state) {
+                task.set_state(state.clone(), now);
+            }
+            task.priority = normalized.priority;
+            task.estimate = normalized.estimate;
+            task.assignee = normalized.assignee.clone();
+            task.depends = split_list(&normalized.depends);
+            task.tags = split_list(&normalized.tags);
+            task.due = normalized.due;
+            task.milestone = normalized.milestone.clone();
+            task.sprint = normalized.sprint.clone();
+            task.parent = normalized.parent.clone();
+            task.description = normalized.description.clone();
+            task.updated_at = Some(now);
+        };
+
+        // Tasks staying in their epic keep their place in it
+        if existing && base.epic == epic {
+            if let Some(task) = backlog.find_task_mut(&row.id) {
+                update(task);
+            }
+            summary.updated.push(row.id.clone());
+            continue;
+        }
+        let mut task = take_task(backlog, &row.id).unwrap_or_else(|| Task {
+            id: row.id.clone(),
+            created_at: Some(now),
+            ..Default::default()
+        });
+        update(&mut task);
+
+        match epic.as_deref() {
+            Some(epic_id) => {
+                if !backlog.epics.iter().any(|e| e.id == epic_id) {
+                    backlog.epics.push(Epic {
+                        id: epic_id.to_string(),
+                        title: epic_id.to_string(),
+                        ..Default::default()
+                    });
+                    summary.new_epics.push(epic_id.to_string());
+                }
+                if let Some(e) = backlog.epics.iter_mut().find(|e| e.id == epic_id) {
+                    e.tasks.push(task);
+                }
+            }
+            None => backlog.tasks.push(task),
+        }
+
+        if existing {
+            summary.updated.push(row.id.clone());
+        } else {
+            summary.added.push(row.id.clone());
+        }
+    }
+
+    backlog.validate()?;
+    Ok(summary)
+}
+
+/// Returns the value of a column of the row if the column is present, or else the current value.
+fn pick<T: Clone>(present: bool, value: &T, current: &T) -> T {
+    if present {
+        value.clone()
+    } else {
+        current.clone()
+    }
+}
+
+/// Gives rows without an ID the ID of the task with the same title in the same epic, if any.
+pub(crate) fn match_rows_by_title(backlog: &Backlog, rows: &mut [TaskRow]) {
+    for row in rows.iter_mut().filter(|r| r.id.is_empty()) {
+        let siblings = match &row.epic {
+            Some(epic) => backlog
+                .epics
+                .iter()
+                .find(|e| &e.id == epic)
+                .map(|e| &e.tasks),
+            None => Some(&backlog.tasks),
+        };
+        if let Some(task) = siblings
+            .into_iter()
+            .flatten()
+            .find(|t| t.title.eq_ignore_ascii_case(&row.title))
+        {
+            row.id = task.id.clone();
+        }
+    }
+}
+
+/// Applies rows that may lack an ID, for formats where IDs are optional.
+///
+/// Rows without an ID take the ID of the task with the same title in the same epic, if any. Rows
+/// with an ID are then applied first, so that the new IDs given to the others never collide with
+/// them; the IDs are filled in `rows`.
+pub(crate) fn apply_rows_without_ids(
+    backlog: &mut Backlog,
+    rows: &mut [TaskRow],
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    match_rows_by_title(backlog, rows);
+    let identified: Vec<TaskRow> = rows.iter().filter(|r| !r.id.is_empty()).cloned().collect();
+    let mut summary = apply_task_rows(backlog, &identified, &TASK_ROW_COLUMNS, now)?;
+    for row in rows.iter_mut().filter(|r| r.id.is_empty()) {
+        row.id = next_task_id(backlog, row.epic.as_deref());
+        let row = std::slice::from_ref(row);
+        summary.merge(apply_task_rows(backlog, row, &TASK_ROW_COLUMNS, now)?);
+    }
+    Ok(summary)
+}
+
+/// Removes the task with the given ID from the backlog, wherever it is, and returns it.
+fn take_task(backlog: &mut Backlog, id: &str) -> Option<Task> {
+    let lists =
+        std::iter::once(&mut backlog.tasks).chain(backlog.epics.iter_mut().map(|e| &mut e.tasks));
+    for tasks in lists {
+        if let Some(index) = tasks.iter().position(|t| t.id == id) {
+            return Some(tasks.remove(index));
+        }
+    }
+    None
+}
+
+/// Parses a task state leniently: `done`, `In progress`, `in_progress` and `InProgress` are all
+/// accepted.
+fn parse_state(state: &str) -> Option<TaskState> {
+    let normalized: String = state
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    match normalized.as_str() {
+        "todo" => Some(TaskState::Todo),
+        "inprogress" => Some(TaskState::InProgress),
+        "blocked" => Some(TaskState::Blocked),
+        "done" => Some(TaskState::Done),
+        _ => None,
+    }
+}
+
+/// Splits a comma-separated list, dropping empty items.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_round_trip() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+            tags: [infra, ci]
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-2
+                title: Endpoints
+                depends: [T-1]
+                estimate: 3
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let now = Utc::now();
+
+        let mut rows = task_rows(&backlog);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].tags, "infra, ci");
+        assert_eq!(rows[1].epic.as_deref(), Some("E-1"));
+        assert_eq!(rows[1].depends, "T-1");
+
+        rows[1].state = Some("in progress".to_string());
+        rows[1].epic = Some("E-2".to_string());
+        rows.push(TaskRow {
+            id: "T-3".to_string(),
+            title: "Docs".to_string(),
+            depends: "T-1, T-2".to_string(),
+            ..Default::default()
+        });
+        let summary = apply_task_rows(&mut backlog, &rows, &TASK_ROW_COLUMNS, now).unwrap();
+        assert_eq!(summary.unchanged, 1);
+        assert_eq!(summary.updated, vec!["T-2"]);
+        assert_eq!(summary.added, vec!["T-3"]);
+        assert_eq!(summary.new_epics, vec!["E-2"]);
+
+        assert!(backlog.epics[0].tasks.is_empty());
+        let moved = &backlog.epics[1].tasks[0];
+        assert!(matches!(moved.state, TaskState::InProgress));
+        assert_eq!(backlog.tasks[1].depends, vec!["T-1", "T-2"]);
+
+        rows[0].state = Some("finished".to_string());
+        assert!(apply_task_rows(&mut backlog, &rows, &TASK_ROW_COLUMNS, now).is_err());
+    }
+
+    /// Tests that updated tasks keep their place, and fields without a column their values.
+    #[test]
+    fn test_apply_partial_rows() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Setup
+            tags: [infra]
+            estimate: 2
+          - id: T-2
+            title: Docs
+        "#;
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let rows = [TaskRow {
+            id: "T-1".to_string(),
+            title: "Setup CI".to_string(),
+            state: Some("done".to_string()),
+            ..Default::default()
+        }];
+
+        let columns = ["id", "title", "state"];
+        let summary = apply_task_rows(&mut backlog, &rows, &columns, Utc::now()).unwrap();
+        assert_eq!(summary.updated, vec!["T-1"]);
+        let task = &backlog.tasks[0];
+        assert_eq!((task.id.as_str(), task.title.as_str()), ("T-1", "Setup CI"));
+        assert!(matches!(task.state, TaskState::Done));
+        assert_eq!(task.tags, vec!["infra"]);
+        assert_eq!(task.estimate, Some(2.0));
+        assert_eq!(backlog.tasks[1].id, "T-2");
+
+        let again = apply_task_rows(&mut backlog, &rows, &columns, Utc::now()).unwrap();
+        assert_eq!(again.unchanged, 1);
+    }
+}
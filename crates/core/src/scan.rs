@@ -0,0 +1,132 @@
+use crate::verify::is_glob;
+use serde::Serialize;
+use taskai_schema::{Backlog, TaskState};
+
+/// A deliverable of a task, with the changed paths matching it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliverableChange {
+    /// The deliverable as written in the backlog (a path or a glob pattern).
+    pub pattern: String,
+    /// Changed paths matching the deliverable; empty if none did.
+    pub paths: Vec<String>,
+}
+
+/// An open task with deliverables touched by a set of changes.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanMatch {
+    pub task_id: String,
+    pub title: String,
+    pub state: TaskState,
+    pub deliverables: Vec<DeliverableChange>,
+}
+
+impl ScanMatch {
+    /// Tells whether every deliverable of the task was changed, so that it appears completed.
+    pub fn is_complete(&self) -> bool {
+        self.deliverables.iter().all(|d| !d.paths.is_empty())
+    }
+}
+
+/// Matches changed paths against the deliverables of the Todo and InProgress tasks.
+///
+/// Paths are relative to the directory deliverables are resolved against. Glob deliverables match
+/// the paths they describe (`**` spanning directories), and other deliverables match the same path
+/// or any path below it, so a directory deliverable is touched by any change inside it. Returns the
+/// tasks with at least one deliverable touched, in backlog order.
+pub fn scan_changes(backlog: &Backlog, changed: &[String]) -> Vec<ScanMatch> {
+    backlog
+        .all_tasks()
+        .into_iter()
+        .filter(|t| matches!(t.state, TaskState::Todo | TaskState::InProgress))
+        .filter_map(|task| {
+            let patterns = task.deliverable.as_ref()?.paths();
+            let deliverables: Vec<DeliverableChange> = patterns
+                .into_iter()
+                .map(|pattern| DeliverableChange {
+                    pattern: pattern.to_string(),
+                    paths: changed
+                        .iter()
+                        .filter(|path| matches_deliverable(pattern, path))
+                        .cloned()
+                        .collect(),
+                })
+                .collect();
+            deliverables
+                .iter()
+                .any(|d| !d.paths.is_empty())
+                .then(|| ScanMatch {
+                    task_id: task.id.clone(),
+                    title: task.title.clone(),
+                    state: task.state.clone(),
+                    deliverables,
+                })
+        })
+        .collect()
+}
+
+/// Returns true if the changed path is the deliverable, is below it, or matches its glob.
+fn matches_deliverable(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if is_glob(pattern) {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        return glob::Pattern::new(pattern).is_ok_and(|p| p.matches_with(path, options));
+    }
+    let pattern = pattern.trim_end_matches('/');
+    path == pattern
+        || path
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_changes() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Parser
+            deliverable: [src/parser.rs, "tests/**/*.rs"]
+          - id: T-2
+            title: Docs
+            state: InProgress
+            deliverable: docs/
+          - id: T-3
+            title: CLI
+            deliverable: ["src/*.rs"]
+          - id: T-4
+            title: Done already
+            state: Done
+            deliverable: src/parser.rs
+        "#,
+        )
+        .unwrap();
+        let changed: Vec<String> = [
+            "src/parser.rs",
+            "tests/unit/parse.rs",
+            "docs/guide/intro.md",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let matches = scan_changes(&backlog, &changed);
+        let found: Vec<(&str, bool)> = matches
+            .iter()
+            .map(|m| (m.task_id.as_str(), m.is_complete()))
+            .collect();
+        assert_eq!(found, [("T-1", true), ("T-2", true), ("T-3", true)]);
+        assert_eq!(matches[0].deliverables[1].paths, ["tests/unit/parse.rs"]);
+
+        let partial = scan_changes(&backlog, &["src/parser.rs".to_string()]);
+        assert!(!partial[0].is_complete());
+        assert!(!matches_deliverable("src/*.rs", "src/bin/main.rs"));
+        assert!(!matches_deliverable("docs", "docsite/index.md"));
+    }
+}
@@ -0,0 +1,146 @@
+use regex::{Regex, RegexBuilder};
+use taskai_schema::{Backlog, Task};
+
+/// How the query of a search is interpreted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Treats the query as a regular expression rather than plain text.
+    pub regex: bool,
+    /// Matches letter case exactly.
+    pub case_sensitive: bool,
+}
+
+/// A task matching a search.
+#[derive(Debug, Clone)]
+pub struct SearchMatch<'a> {
+    /// The matching task.
+    pub task: &'a Task,
+    /// ID of the epic containing the task, if any.
+    pub epic: Option<&'a str>,
+    /// Fields of the task the query matched, as they appear in the YAML.
+    pub fields: Vec<&'static str>,
+}
+
+/// Searches task IDs, titles, descriptions and `done_when` criteria for a query.
+///
+/// Plain-text queries match substrings; both plain-text and regex queries ignore case unless
+/// `case_sensitive` is set. Returns the matching tasks in backlog order, or an error if the regex is
+/// invalid.
+pub fn search_tasks<'a>(
+    backlog: &'a Backlog,
+    query: &str,
+    options: SearchOptions,
+) -> Result<Vec<SearchMatch<'a>>, String> {
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let pattern = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))?;
+
+    let standalone = backlog.tasks.iter().map(|t| (t, None));
+    let in_epics = backlog
+        .epics
+        .iter()
+        .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e.id.as_str()))));
+
+    Ok(standalone
+        .chain(in_epics)
+        .filter_map(|(task, epic)| {
+            let fields = matching_fields(task, &pattern);
+            (!fields.is_empty()).then_some(SearchMatch { task, epic, fields })
+        })
+        .collect())
+}
+
+/// Returns the searchable fields of the task matched by the pattern.
+fn matching_fields(task: &Task, pattern: &Regex) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if pattern.is_match(&task.id) {
+        fields.push("id");
+    }
+    if pattern.is_match(&task.title) {
+        fields.push("title");
+    }
+    if task
+        .description
+        .as_deref()
+        .is_some_and(|d| pattern.is_match(d))
+    {
+        fields.push("description");
+    }
+    if task.done_when.iter().any(|c| pattern.is_match(c)) {
+        fields.push("done_when");
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_text_and_regex() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Add OAuth login
+          - id: T-2
+            title: Session store
+            description: Keeps oauth tokens
+            done_when: ["`cargo test` passes"]
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-3
+                title: Rate limiting
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let matches = search_tasks(&backlog, "oauth", SearchOptions::default()).unwrap();
+        let found: Vec<(&str, Vec<&str>)> = matches
+            .iter()
+            .map(|m| (m.task.id.as_str(), m.fields.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("T-1", vec!["title"]), ("T-2", vec!["description"])]
+        );
+
+        let case_sensitive = SearchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            search_tasks(&backlog, "oauth", case_sensitive)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let regex = SearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let matches = search_tasks(&backlog, r"^T-[13]$", regex).unwrap();
+        assert_eq!(matches[1].epic, Some("E-1"));
+        assert_eq!(matches[1].fields, vec!["id"]);
+
+        assert_eq!(
+            search_tasks(&backlog, "cargo test", SearchOptions::default()).unwrap()[0].fields,
+            vec!["done_when"]
+        );
+        assert!(search_tasks(&backlog, "(", regex).is_err());
+        assert_eq!(
+            search_tasks(&backlog, "(", SearchOptions::default())
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::config::{NotifyEvent, SlackConfig};
+use crate::standup::{Standup, StandupTask};
+use serde_json::{json, Value};
+use taskai_schema::{Backlog, Task};
+
+/// Where a Slack message goes: a route's webhook or channel, or the configured defaults when both
+/// are unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlackDestination {
+    pub webhook: Option<String>,
+    pub channel: Option<String>,
+}
+
+/// A message to post to Slack.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlackMessage {
+    pub destination: SlackDestination,
+    /// Text of the message, in Slack `mrkdwn`.
+    pub text: String,
+}
+
+/// Client posting messages to Slack, through an incoming webhook or with a bot token.
+///
+/// The webhook and token come from the `[slack]` configuration, then `SLACK_WEBHOOK_URL` and
+/// `SLACK_BOT_TOKEN`. Bot messages are sent to `SLACK_API_URL` (`https://slack.com/api` by
+/// default).
+pub struct SlackClient {
+    client: reqwest::Client,
+    api_url: String,
+    webhook: Option<String>,
+    token: Option<String>,
+    channel: Option<String>,
+}
+
+impl SlackClient {
+    /// Creates a client from the configuration, completed by the environment.
+    pub fn new(config: &SlackConfig) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        SlackClient {
+            client: crate::web::http_client(),
+            api_url: env("SLACK_API_URL")
+                .unwrap_or_else(|| "https://slack.com/api".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            webhook: config.webhook.clone().or_else(|| env("SLACK_WEBHOOK_URL")),
+            token: config.token.clone().or_else(|| env("SLACK_BOT_TOKEN")),
+            channel: config.channel.clone(),
+        }
+    }
+
+    /// Tells whether a webhook or a bot token is set, without which nothing is posted.
+    pub fn is_configured(&self) -> bool {
+        self.webhook.is_some() || self.token.is_some()
+    }
+
+    /// Posts a message to its destination.
+    ///
+    /// A destination webhook is used first, then the bot token with the destination channel or
+    /// the default one, then the default webhook when the destination names no channel.
+    pub async fn post(&self, message: &SlackMessage) -> Result<(), String> {
+        let destination = &message.destination;
+        let channel = destination.channel.as_ref().or(self.channel.as_ref());
+        let text = json!({ "text": message.text });
+        let request = if let Some(webhook) = &destination.webhook {
+            self.client.post(webhook).json(&text)
+        } else if let (Some(token), Some(channel)) = (&self.token, channel) {
+            self.client
+                .post(format!("{}/chat.postMessage", self.api_url))
+                .bearer_auth(token)
+                .json(&json!({ "channel": channel, "text": message.text }))
+        } else if let (Some(webhook), None) = (&self.webhook, &destination.channel) {
+            self.client.post(webhook).json(&text)
+        } else if let Some(channel) = &destination.channel {
+            return Err(format!("Posting to {} needs a Slack bot token", channel));
+        } else {
+            return Err("No Slack channel to post to; set slack.channel".to_string());
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Slack request failed: {}", e))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Invalid Slack response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "Slack returned status {}: {}",
+                status.as_u16(),
+                body
+            ));
+        }
+        // The Web API answers 200 with `ok: false` on errors; webhooks answer plain `ok`
+        match serde_json::from_str::<Value>(&body) {
+            Ok(reply) if reply["ok"] == false => Err(format!(
+                "Slack API error: {}",
+                reply["error"].as_str().unwrap_or("unknown")
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Builds the messages about tasks that were marked as done or blocked, one per destination.
+///
+/// Each task goes to the first route of the configuration matching its epic or one of its tags, or
+/// to the default destination. Tasks not found in the backlog are left out.
+pub fn slack_task_messages(
+    config: &SlackConfig,
+    backlog: &Backlog,
+    event: NotifyEvent,
+    task_ids: &[String],
+) -> Vec<SlackMessage> {
+    let mut messages: Vec<SlackMessage> = Vec::new();
+    for id in task_ids {
+        let Some(task) = backlog.find_task(id) else {
+            continue;
+        };
+        let epic = backlog
+            .epics
+            .iter()
+            .find(|e| e.tasks.iter().any(|t| t.id == task.id))
+            .map(|e| e.id.as_str());
+        let destination = config
+            .routes
+            .iter()
+            .find(|route| {
+                route.epic.is_some() && route.epic.as_deref() == epic
+                    || route
+                        .tag
+                        .as_ref()
+                        .is_some_and(|tag| task.tags.contains(tag))
+            })
+            .map(|route| SlackDestination {
+                webhook: route.webhook.clone(),
+                channel: route.channel.clone(),
+            })
+            .unwrap_or_default();
+
+        let line = task_line(&backlog.project, task, event);
+        match messages.iter_mut().find(|m| m.destination == destination) {
+            Some(message) => {
+                message.text.push('\n');
+                message.text.push_str(&line);
+            }
+            None => messages.push(SlackMessage {
+                destination,
+                text: line,
+            }),
+        }
+    }
+    messages
+}
+
+/// Builds the standup message, posted to the default destination.
+pub fn slack_standup_message(standup: &Standup) -> SlackMessage {
+    let mut text = format!(
+        "*Standup for {}* since {}",
+        escape(&standup.project),
+        standup.since.format("%Y-%m-%d %H:%M UTC")
+    );
+    let sections = [
+        ("Done", &standup.done),
+        ("In progress", &standup.in_progress),
+        ("Blocked", &standup.blocked),
+    ];
+    for (heading, tasks) in sections {
+        text.push_str(&format!("\n*{}*", heading));
+        if tasks.is_empty() {
+            text.push_str("\n• _nothing_");
+        }
+        for task in tasks {
+            text.push_str(&format!("\n• {}", standup_line(task)));
+        }
+    }
+    SlackMessage {
+        destination: SlackDestination::default(),
+        text,
+    }
+}
+
+fn task_line(project: &str, task: &Task, event: NotifyEvent) -> String {
+    let name = format!(
+        "[{}] *{}* {}",
+        escape(project),
+        escape(&task.id),
+        escape(&task.title)
+    );
+    match (event, &task.blocker) {
+        (NotifyEvent::Blocked, Some(blocker)) => match &blocker.link {
+            Some(link) => format!(
+                ":no_entry: {} is blocked: {} (<{}>)",
+                name,
+                escape(&blocker.reason),
+                link
+            ),
+            None => format!(
+                ":no_entry: {} is blocked: {}",
+                name,
+                escape(&blocker.reason)
+            ),
+        },
+        (NotifyEvent::Blocked, None) => format!(":no_entry: {} is blocked", name),
+        _ => format!(":white_check_mark: {} is done", name),
+    }
+}
+
+fn standup_line(task: &StandupTask) -> String {
+    let mut line = format!("*{}* {}", escape(&task.id), escape(&task.title));
+    if let Some(assignee) = &task.assignee {
+        line.push_str(&format!(" ({})", escape(assignee)));
+    }
+    if let Some(reason) = &task.reason {
+        line.push_str(&format!(": {}", escape(reason)));
+    }
+    line
+}
+
+/// Escapes the characters Slack reads as markup in message text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SlackRoute;
+
+    #[test]
+    fn test_slack_task_messages() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: demo
+        tasks:
+          - id: T-1
+            title: Fix <script> & co
+            tags: [security]
+          - id: T-2
+            title: Docs
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: T-3
+                title: Endpoints
+                state: Blocked
+                blocker:
+                  reason: Waiting for the schema
+        "#,
+        )
+        .unwrap();
+        let config = SlackConfig {
+            routes: vec![
+                SlackRoute {
+                    epic: Some("E-1".to_string()),
+                    channel: Some("#api".to_string()),
+                    ..Default::default()
+                },
+                SlackRoute {
+                    tag: Some("security".to_string()),
+                    webhook: Some("https://hooks.slack.com/services/X".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let ids = ["T-1", "T-2", "T-9"].map(String::from);
+
+        let messages = slack_task_messages(&config, &backlog, NotifyEvent::Done, &ids);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].text,
+            ":white_check_mark: [demo] *T-1* Fix &lt;script&gt; &amp; co is done"
+        );
+        assert_eq!(messages[1].destination, SlackDestination::default());
+
+        let blocked = slack_task_messages(
+            &config,
+            &backlog,
+            NotifyEvent::Blocked,
+            &["T-3".to_string()],
+        );
+        assert_eq!(blocked[0].destination.channel.as_deref(), Some("#api"));
+        assert_eq!(
+            blocked[0].text,
+            ":no_entry: [demo] *T-3* Endpoints is blocked: Waiting for the schema"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+use taskai_schema::{Backlog, Task};
+
+/// Order in which `sort_tasks` puts the tasks of a backlog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Execution order: every task after its dependencies, otherwise in file order.
+    Dependencies,
+    /// Most important first (by priority, then due date), but never before a dependency.
+    Priority,
+}
+
+/// Reorders the tasks of the backlog so that it reads in execution order, returning the number of
+/// tasks that changed position.
+///
+/// Standalone tasks and the tasks of each epic are sorted within their own list; epics keep their
+/// order. Dependencies across lists are still taken into account, through the order of all the
+/// tasks. Tasks caught in a dependency cycle keep their relative order, after the other tasks.
+pub fn sort_tasks(backlog: &mut Backlog, order: SortOrder) -> usize {
+    let rank: HashMap<String, usize> = execution_order(&backlog.all_tasks(), order)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, id)| (id, rank))
+        .collect();
+
+    let lists =
+        std::iter::once(&mut backlog.tasks).chain(backlog.epics.iter_mut().map(|e| &mut e.tasks));
+    let mut moved = 0;
+    for tasks in lists {
+        let before: Vec<String> = tasks.iter().map(|t| t.id.clone()).collect();
+        tasks.sort_by_key(|t| rank.get(&t.id).copied().unwrap_or(usize::MAX));
+        moved += tasks
+            .iter()
+            .zip(&before)
+            .filter(|(task, id)| &task.id != *id)
+            .count();
+    }
+    moved
+}
+
+/// Returns the IDs of the tasks in execution order: at each step, the first ready task by `order`,
+/// with file order breaking ties.
+fn execution_order(tasks: &[&Task], order: SortOrder) -> Vec<String> {
+    let known: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut sorted = Vec::with_capacity(tasks.len());
+
+    while sorted.len() < tasks.len() {
+        let ready = tasks.iter().enumerate().filter(|(_, t)| {
+            !placed.contains(t.id.as_str())
+                && t.depends
+                    .iter()
+                    .all(|dep| placed.contains(dep.as_str()) || !known.contains(dep.as_str()))
+        });
+        let next = match order {
+            SortOrder::Dependencies => ready.min_by_key(|(index, _)| *index),
+            SortOrder::Priority => ready.min_by_key(|(index, t)| {
+                (
+                    t.priority.is_none(),
+                    t.priority,
+                    t.due.is_none(),
+                    t.due,
+                    *index,
+                )
+            }),
+        };
+        let Some((_, task)) = next else {
+            break;
+        };
+        placed.insert(&task.id);
+        sorted.push(task.id.clone());
+    }
+
+    // Tasks left are part of a cycle
+    sorted.extend(
+        tasks
+            .iter()
+            .filter(|t| !placed.contains(t.id.as_str()))
+            .map(|t| t.id.clone()),
+    );
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_tasks() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-3
+            title: Release
+            depends: [T-2, E-2]
+          - id: T-1
+            title: Setup
+            priority: 2
+          - id: T-2
+            title: Build
+            depends: [T-1]
+          - id: T-4
+            title: Docs
+            priority: 1
+        epics:
+          - id: E
+            title: API
+            tasks:
+              - id: E-2
+                title: Endpoints
+                depends: [E-1]
+              - id: E-1
+                title: Models
+                depends: [T-1]
+        "#;
+        let ids = |backlog: &Backlog| -> Vec<String> {
+            backlog.all_tasks().iter().map(|t| t.id.clone()).collect()
+        };
+
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(sort_tasks(&mut backlog, SortOrder::Dependencies), 6);
+        assert_eq!(ids(&backlog), ["T-1", "T-2", "T-4", "T-3", "E-1", "E-2"]);
+        assert_eq!(sort_tasks(&mut backlog, SortOrder::Dependencies), 0);
+
+        let mut backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        sort_tasks(&mut backlog, SortOrder::Priority);
+        assert_eq!(ids(&backlog), ["T-4", "T-1", "T-2", "T-3", "E-1", "E-2"]);
+    }
+}
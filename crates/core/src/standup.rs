@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// A task listed in a standup.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StandupTask {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Why the task is blocked, for blocked tasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// What was done since a point in time, what is in progress and what is blocked.
+#[derive(Debug, Clone, Serialize)]
+pub struct Standup {
+    pub project: String,
+    pub since: DateTime<Utc>,
+    pub done: Vec<StandupTask>,
+    pub in_progress: Vec<StandupTask>,
+    pub blocked: Vec<StandupTask>,
+}
+
+/// Summarizes the backlog for a standup: the tasks completed since `since` (archived ones
+/// included), oldest first, then the InProgress and Blocked tasks in backlog order.
+pub fn standup(backlog: &Backlog, since: DateTime<Utc>) -> Standup {
+    let tasks = backlog.all_tasks();
+    let mut done: Vec<&Task> = tasks
+        .iter()
+        .copied()
+        .chain(&backlog.archive)
+        .filter(|t| matches!(t.state, TaskState::Done))
+        .filter(|t| t.completed_at.is_some_and(|at| at >= since))
+        .collect();
+    done.sort_by_key(|t| t.completed_at);
+    let listed = |state: fn(&TaskState) -> bool| {
+        tasks
+            .iter()
+            .filter(|t| state(&t.state))
+            .map(|t| standup_task(t))
+            .collect()
+    };
+
+    Standup {
+        project: backlog.project.clone(),
+        since,
+        done: done.into_iter().map(standup_task).collect(),
+        in_progress: listed(|s| matches!(s, TaskState::InProgress)),
+        blocked: listed(|s| matches!(s, TaskState::Blocked)),
+    }
+}
+
+fn standup_task(task: &Task) -> StandupTask {
+    StandupTask {
+        id: task.id.clone(),
+        title: task.title.clone(),
+        assignee: task.assignee.clone(),
+        reason: task.blocker.as_ref().map(|b| b.reason.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standup() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Parser
+            state: Done
+            completed_at: 2026-10-15T16:00:00Z
+          - id: T-2
+            title: Lexer
+            state: Done
+            completed_at: 2026-10-01T16:00:00Z
+          - id: T-3
+            title: CLI
+            state: InProgress
+            assignee: alice
+          - id: T-4
+            title: Release
+            state: Blocked
+            blocker:
+              reason: Waiting for the crates.io name
+        archive:
+          - id: T-0
+            title: Setup
+            state: Done
+            completed_at: 2026-10-15T09:00:00Z
+        "#,
+        )
+        .unwrap();
+        let since = "2026-10-15T00:00:00Z".parse().unwrap();
+
+        let standup = standup(&backlog, since);
+        let ids = |tasks: &[StandupTask]| tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&standup.done), ["T-0", "T-1"]);
+        assert_eq!(ids(&standup.in_progress), ["T-3"]);
+        assert_eq!(
+            standup.blocked[0].reason.as_deref(),
+            Some("Waiting for the crates.io name")
+        );
+    }
+}
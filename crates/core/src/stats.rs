@@ -0,0 +1,186 @@
+use crate::ids::split_task_id;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, Task, TaskState};
+
+/// How `group_stats` groups the tasks of a backlog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsBy {
+    /// By assignee; unassigned tasks form their own group.
+    Assignee,
+    /// By tag; tasks with several tags count in each of their groups.
+    Tag,
+    /// By epic ID; standalone tasks form their own group.
+    Epic,
+    /// By kind of task, as given by the prefix of its ID (`API` for `API-3`).
+    Kind,
+}
+
+/// Counts, estimates and cycle time of a group of tasks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupStats {
+    pub group: String,
+    /// Tasks not done yet (Todo, InProgress or Blocked).
+    pub open: usize,
+    pub done: usize,
+    /// Sum of the estimates of all the tasks of the group.
+    pub estimate: f64,
+    /// Sum of the estimates of the open tasks.
+    pub remaining: f64,
+    /// Average number of days from start to completion of the done tasks with both dates.
+    pub average_cycle_days: Option<f64>,
+}
+
+/// Computes statistics per group of tasks, for retrospectives and load balancing.
+///
+/// Groups are sorted by name, with the group of tasks lacking the grouping field (unassigned,
+/// untagged...) last. Archived tasks are not counted.
+pub fn group_stats(backlog: &Backlog, by: StatsBy) -> Vec<GroupStats> {
+    let mut groups: BTreeMap<(bool, String), Vec<&Task>> = BTreeMap::new();
+    let lists = std::iter::once((None, &backlog.tasks)).chain(
+        backlog
+            .epics
+            .iter()
+            .map(|e| (Some(e.id.as_str()), &e.tasks)),
+    );
+    for (epic_id, tasks) in lists {
+        for task in tasks {
+            let keys: Vec<Option<String>> = match by {
+                StatsBy::Assignee => vec![task.assignee.clone()],
+                StatsBy::Tag if task.tags.is_empty() => vec![None],
+                StatsBy::Tag => task.tags.iter().cloned().map(Some).collect(),
+                StatsBy::Epic => vec![epic_id.map(str::to_string)],
+                StatsBy::Kind => {
+                    vec![split_task_id(&task.id).map(|(prefix, _)| prefix.to_string())]
+                }
+            };
+            for key in keys {
+                let key = match key {
+                    Some(key) => (false, key),
+                    None => (true, missing_group(by).to_string()),
+                };
+                groups.entry(key).or_default().push(task);
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, group), tasks)| stats(group, &tasks))
+        .collect()
+}
+
+/// Name of the group of tasks lacking the grouping field.
+fn missing_group(by: StatsBy) -> &'static str {
+    match by {
+        StatsBy::Assignee => "(unassigned)",
+        StatsBy::Tag => "(untagged)",
+        StatsBy::Epic => "(no epic)",
+        StatsBy::Kind => "(other)",
+    }
+}
+
+fn stats(group: String, tasks: &[&Task]) -> GroupStats {
+    let is_done = |t: &&&Task| matches!(t.state, TaskState::Done);
+    let cycle_days: Vec<f64> = tasks
+        .iter()
+        .filter(is_done)
+        .filter_map(|t| Some((t.completed_at? - t.started_at?).num_seconds() as f64 / 86400.0))
+        .collect();
+    GroupStats {
+        group,
+        open: tasks.iter().filter(|t| !is_done(t)).count(),
+        done: tasks.iter().filter(is_done).count(),
+        estimate: sum(tasks.iter().filter_map(|t| t.estimate)),
+        remaining: sum(tasks
+            .iter()
+            .filter(|t| !is_done(t))
+            .filter_map(|t| t.estimate)),
+        average_cycle_days: (!cycle_days.is_empty())
+            .then(|| cycle_days.iter().sum::<f64>() / cycle_days.len() as f64),
+    }
+}
+
+/// Sums hours or other amounts of work, giving 0.0 rather than -0.0 when there is nothing to sum,
+/// so that totals never print as `-0` in JSON or YAML output.
+pub(crate) fn sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    values.into_iter().fold(0.0, |sum, v| sum + v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum() {
+        let total = sum([]);
+        assert!(total == 0.0 && total.is_sign_positive());
+        assert_eq!(sum([1.5, 2.0]), 3.5);
+    }
+
+    #[test]
+    fn test_group_stats() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+            assignee: alice
+            tags: [infra, ci]
+            estimate: 2
+            started_at: 2024-01-01T00:00:00Z
+            completed_at: 2024-01-03T00:00:00Z
+          - id: T-2
+            title: Docs
+            estimate: 1
+            tags: [docs]
+        epics:
+          - id: E-1
+            title: API
+            tasks:
+              - id: API-1
+                title: Models
+                assignee: alice
+                estimate: 3
+                state: Done
+                started_at: 2024-01-01T00:00:00Z
+                completed_at: 2024-01-02T00:00:00Z
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let summary = |by: StatsBy| -> Vec<(String, usize, usize)> {
+            group_stats(&backlog, by)
+                .into_iter()
+                .map(|s| (s.group, s.open, s.done))
+                .collect()
+        };
+
+        let by_assignee = group_stats(&backlog, StatsBy::Assignee);
+        assert_eq!(by_assignee[0].group, "alice");
+        assert_eq!((by_assignee[0].done, by_assignee[0].estimate), (2, 5.0));
+        assert_eq!(by_assignee[0].average_cycle_days, Some(1.5));
+        assert_eq!(by_assignee[1].group, "(unassigned)");
+        assert_eq!(
+            (by_assignee[1].remaining, by_assignee[1].average_cycle_days),
+            (1.0, None)
+        );
+
+        assert_eq!(
+            summary(StatsBy::Tag),
+            [
+                ("ci".to_string(), 0, 1),
+                ("docs".to_string(), 1, 0),
+                ("infra".to_string(), 0, 1),
+                ("(untagged)".to_string(), 0, 1),
+            ]
+        );
+        assert_eq!(
+            summary(StatsBy::Epic),
+            [("E-1".to_string(), 0, 1), ("(no epic)".to_string(), 1, 1)]
+        );
+        assert_eq!(
+            summary(StatsBy::Kind),
+            [("API".to_string(), 0, 1), ("T".to_string(), 1, 1)]
+        );
+    }
+}
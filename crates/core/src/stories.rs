@@ -0,0 +1,712 @@
+use crate::gitlab::tracking_epic;
+use crate::ids::next_task_id;
+use crate::rows::ImportSummary;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use taskai_schema::{Backlog, Blocker, ExternalRef, Task, TaskState};
+
+/// A story of a Pivotal Tracker or Shortcut export, as imported into the backlog.
+#[derive(Debug, Clone, Default)]
+struct Story {
+    id: String,
+    title: String,
+    description: Option<String>,
+    /// Story type, such as `feature`, `bug` or `chore`.
+    kind: String,
+    labels: Vec<String>,
+    state: TaskState,
+    estimate: Option<f64>,
+    /// ID and name of the epic of the story.
+    epic: Option<(String, String)>,
+    iteration: Option<String>,
+    owner: Option<String>,
+    url: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+    due: Option<NaiveDate>,
+    /// Items of the checklist of the story.
+    checklist: Vec<String>,
+    /// IDs of the stories blocking this one.
+    blocked_by: Vec<String>,
+    /// Reason of a blocker that is not another story.
+    blocker: Option<String>,
+}
+
+/// Imports a Pivotal Tracker export into the backlog: the CSV file of a project, or the JSON array
+/// of stories returned by its API.
+///
+/// Stories become tasks, matched with the tasks already imported by their `pivotal` reference:
+/// known tasks get their title and state updated, others are added. Labels become tags, along with
+/// the type of bugs and chores, iterations become sprints, tasks of stories become `done_when`
+/// criteria, and unresolved blockers naming another story (`#123`) become dependencies; other
+/// unresolved blockers leave the task Blocked. Unscheduled, unstarted and planned stories are Todo,
+/// accepted ones Done and the others InProgress; releases are left out.
+pub fn import_pivotal(
+    backlog: &mut Backlog,
+    text: &str,
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let stories = if is_json(text) {
+        pivotal_json(text)?
+    } else {
+        pivotal_csv(text)?
+    };
+    Ok(import_stories(backlog, "pivotal", &stories, now))
+}
+
+/// Imports a Shortcut export into the backlog: the CSV file of stories, or the JSON array of
+/// stories returned by its API, alone or as `stories` next to the `epics` they belong to.
+///
+/// Stories are matched and mapped as by [`import_pivotal`], with `shortcut` references; epics of
+/// stories become epics, and `blocks` story links become dependencies. Completed stories are Done,
+/// blocked ones Blocked, started ones InProgress and the others Todo.
+pub fn import_shortcut(
+    backlog: &mut Backlog,
+    text: &str,
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let stories = if is_json(text) {
+        shortcut_json(text)?
+    } else {
+        shortcut_csv(text)?
+    };
+    Ok(import_stories(backlog, "shortcut", &stories, now))
+}
+
+/// Tells whether an export is JSON rather than CSV.
+fn is_json(text: &str) -> bool {
+    text.trim_start().starts_with(['[', '{'])
+}
+
+/// Adds the stories to the backlog, or updates the tasks already imported from them.
+fn import_stories(
+    backlog: &mut Backlog,
+    system: &str,
+    stories: &[Story],
+    now: DateTime<Utc>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let task_of = |backlog: &Backlog, id: &str| {
+        backlog
+            .all_tasks()
+            .iter()
+            .find(|t| {
+                t.external_refs
+                    .iter()
+                    .any(|r| r.system == system && r.id == id)
+            })
+            .map(|t| t.id.clone())
+    };
+
+    for story in stories {
+        let state = story_state(story);
+        if let Some(task_id) = task_of(backlog, &story.id) {
+            let Some(task) = backlog.find_task_mut(&task_id) else {
+                continue;
+            };
+            let mut changed = false;
+            if task.title != story.title {
+                task.title = story.title.clone();
+                task.updated_at = Some(now);
+                changed = true;
+            }
+            if std::mem::discriminant(&task.state) != std::mem::discriminant(&state) {
+                task.set_state(state, now);
+                changed = true;
+            }
+            if changed {
+                summary.updated.push(task_id);
+            } else {
+                summary.unchanged += 1;
+            }
+            continue;
+        }
+
+        let epic_id = story.epic.as_ref().map(|(id, name)| {
+            let epic_ref = ExternalRef {
+                system: system.to_string(),
+                id: format!("epic-{}", id),
+                url: None,
+                synced_at: None,
+            };
+            tracking_epic(backlog, name, epic_ref, &mut summary)
+        });
+        let mut tags = story.labels.clone();
+        if matches!(story.kind.as_str(), "bug" | "chore") && !tags.contains(&story.kind) {
+            tags.push(story.kind.clone());
+        }
+        let mut task = Task {
+            id: next_task_id(backlog, epic_id.as_deref()),
+            title: story.title.clone(),
+            description: story.description.clone(),
+            done_when: story.checklist.clone(),
+            tags,
+            estimate: story.estimate,
+            sprint: story.iteration.clone(),
+            due: story.due,
+            assignee: story.owner.clone(),
+            created_at: story.created_at,
+            updated_at: Some(now),
+            external_refs: vec![ExternalRef {
+                system: system.to_string(),
+                id: story.id.clone(),
+                url: story.url.clone(),
+                synced_at: Some(now),
+            }],
+            ..Default::default()
+        };
+        if !matches!(state, TaskState::Todo) {
+            let at = match state {
+                TaskState::Done => story.completed_at,
+                _ => None,
+            };
+            task.set_state(state, at.unwrap_or(now));
+            task.updated_at = Some(now);
+        }
+        if let (TaskState::Blocked, Some(reason)) = (&task.state, &story.blocker) {
+            task.blocker = Some(Blocker {
+                reason: reason.clone(),
+                link: None,
+            });
+        }
+        summary.added.push(task.id.clone());
+        match epic_id.and_then(|id| backlog.epics.iter_mut().find(|e| e.id == id)) {
+            Some(epic) => epic.tasks.push(task),
+            None => backlog.tasks.push(task),
+        }
+    }
+
+    for story in stories {
+        let Some(task_id) = task_of(backlog, &story.id) else {
+            continue;
+        };
+        let blockers: Vec<String> = story
+            .blocked_by
+            .iter()
+            .filter_map(|id| task_of(backlog, id))
+            .filter(|id| id != &task_id)
+            .collect();
+        let Some(task) = backlog.find_task_mut(&task_id) else {
+            continue;
+        };
+        let mut changed = false;
+        for blocker in blockers {
+            if !task.depends.contains(&blocker) {
+                task.depends.push(blocker);
+                changed = true;
+            }
+        }
+        if changed && !summary.added.contains(&task_id) && !summary.updated.contains(&task_id) {
+            task.updated_at = Some(now);
+            summary.updated.push(task_id);
+        }
+    }
+    summary
+}
+
+/// Returns the state of the task of a story; stories held by a blocker that is not a story are
+/// Blocked unless done.
+fn story_state(story: &Story) -> TaskState {
+    match story.state {
+        TaskState::Done => TaskState::Done,
+        _ if story.blocker.is_some() => TaskState::Blocked,
+        ref state => state.clone(),
+    }
+}
+
+/// Returns the state of the tasks in a Pivotal Tracker story state.
+fn pivotal_state(state: &str) -> TaskState {
+    match state.trim().to_lowercase().as_str() {
+        "accepted" => TaskState::Done,
+        "started" | "finished" | "delivered" | "rejected" => TaskState::InProgress,
+        _ => TaskState::Todo,
+    }
+}
+
+/// Reads a time as written by the exports: RFC 3339, or a date such as `Nov 22, 2019`.
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    parse_date(value)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc())
+}
+
+/// Reads a date such as `Nov 22, 2019`, `2019-11-22` or the date of an RFC 3339 time.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    ["%b %d, %Y", "%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .or_else(|| Some(DateTime::parse_from_rfc3339(value).ok()?.date_naive()))
+}
+
+/// Returns the non-empty, trimmed text of a field.
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Splits a list of labels or names separated by commas or semicolons.
+fn split_list(value: &str) -> Vec<String> {
+    value.split([',', ';']).filter_map(non_empty).collect()
+}
+
+/// Returns the IDs of the stories named in a blocker, as `#123`.
+fn story_ids(blocker: &str) -> Vec<String> {
+    let reference = Regex::new(r"#(\d+)").unwrap();
+    reference
+        .captures_iter(blocker)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// Sets the blockers of a story from their descriptions and whether they are resolved.
+fn add_blocker(story: &mut Story, description: &str, resolved: bool) {
+    if resolved || description.trim().is_empty() {
+        return;
+    }
+    let ids = story_ids(description);
+    if ids.is_empty() {
+        story
+            .blocker
+            .get_or_insert_with(|| description.trim().to_string());
+    }
+    story.blocked_by.extend(ids);
+}
+
+/// A CSV export, whose columns may repeat, such as the blockers and tasks of Pivotal Tracker.
+struct CsvExport {
+    headers: Vec<String>,
+    records: Vec<csv::StringRecord>,
+}
+
+impl CsvExport {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Error reading the CSV header: {}", e))?
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect();
+        let records = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Error parsing the CSV export: {}", e))?;
+        Ok(CsvExport { headers, records })
+    }
+
+    /// Returns the values of the columns named `name` in a record, empty ones included.
+    fn values<'a>(&self, record: &'a csv::StringRecord, name: &str) -> Vec<&'a str> {
+        self.headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| header.as_str() == name)
+            .map(|(i, _)| record.get(i).unwrap_or_default())
+            .collect()
+    }
+
+    /// Returns the value of the first column named `name` in a record.
+    fn value<'a>(&self, record: &'a csv::StringRecord, name: &str) -> &'a str {
+        self.values(record, name)
+            .first()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Checks that the export has the columns `names`.
+    fn require(&self, names: &[&str]) -> Result<(), String> {
+        match names.iter().find(|n| !self.headers.iter().any(|h| h == *n)) {
+            Some(name) => Err(format!("The CSV export has no `{}` column.", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads the stories of a Pivotal Tracker CSV export.
+fn pivotal_csv(text: &str) -> Result<Vec<Story>, String> {
+    let export = CsvExport::parse(text)?;
+    export.require(&["id", "title"])?;
+    let mut stories = Vec::new();
+    for record in &export.records {
+        let kind = export.value(record, "type").trim().to_lowercase();
+        if kind == "release" {
+            continue;
+        }
+        let mut story = Story {
+            id: export.value(record, "id").trim().to_string(),
+            title: export.value(record, "title").trim().to_string(),
+            description: non_empty(export.value(record, "description")),
+            labels: split_list(export.value(record, "labels")),
+            state: pivotal_state(export.value(record, "current state")),
+            estimate: export.value(record, "estimate").trim().parse().ok(),
+            iteration: non_empty(export.value(record, "iteration"))
+                .map(|n| format!("Iteration {}", n)),
+            owner: non_empty(export.value(record, "owned by")),
+            url: non_empty(export.value(record, "url")),
+            created_at: parse_time(export.value(record, "created at")),
+            completed_at: parse_time(export.value(record, "accepted at")),
+            due: parse_date(export.value(record, "deadline")),
+            kind,
+            ..Default::default()
+        };
+        story.checklist = export
+            .values(record, "task")
+            .into_iter()
+            .filter_map(non_empty)
+            .collect();
+        let blockers = export.values(record, "blocker");
+        let statuses = export.values(record, "blocker status");
+        for (i, blocker) in blockers.iter().enumerate() {
+            let resolved = statuses
+                .get(i)
+                .is_some_and(|s| s.trim().eq_ignore_ascii_case("resolved"));
+            add_blocker(&mut story, blocker, resolved);
+        }
+        if !story.id.is_empty() {
+            stories.push(story);
+        }
+    }
+    Ok(stories)
+}
+
+/// A label of a story in the JSON exports.
+#[derive(Deserialize)]
+struct JsonLabel {
+    name: String,
+}
+
+/// An item of the checklist of a story in the JSON exports.
+#[derive(Deserialize)]
+struct JsonTask {
+    description: String,
+}
+
+/// A story of Pivotal Tracker, as returned by its API.
+#[derive(Deserialize)]
+struct PivotalStory {
+    id: Value,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    story_type: Option<String>,
+    #[serde(default)]
+    current_state: Option<String>,
+    #[serde(default)]
+    estimate: Option<f64>,
+    #[serde(default)]
+    labels: Vec<JsonLabel>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    accepted_at: Option<String>,
+    #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
+    tasks: Vec<JsonTask>,
+    #[serde(default)]
+    blockers: Vec<PivotalBlocker>,
+}
+
+/// A blocker of a Pivotal Tracker story.
+#[derive(Deserialize)]
+struct PivotalBlocker {
+    description: String,
+    #[serde(default)]
+    resolved: bool,
+}
+
+/// Returns an ID of the JSON exports, a number or a string, as text.
+fn json_id(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+/// Reads the stories of a Pivotal Tracker JSON export.
+fn pivotal_json(text: &str) -> Result<Vec<Story>, String> {
+    let stories: Vec<PivotalStory> = serde_json::from_str(text)
+        .map_err(|e| format!("Error parsing the Pivotal Tracker stories: {}", e))?;
+    let mut imported = Vec::new();
+    for s in stories {
+        let kind = s.story_type.unwrap_or_default().to_lowercase();
+        if kind == "release" {
+            continue;
+        }
+        let mut story = Story {
+            id: json_id(&s.id),
+            title: s.name.trim().to_string(),
+            description: s.description.as_deref().and_then(non_empty),
+            kind,
+            labels: s.labels.into_iter().map(|l| l.name).collect(),
+            state: pivotal_state(s.current_state.as_deref().unwrap_or_default()),
+            estimate: s.estimate,
+            url: s.url,
+            created_at: s.created_at.as_deref().and_then(parse_time),
+            completed_at: s.accepted_at.as_deref().and_then(parse_time),
+            due: s.deadline.as_deref().and_then(parse_date),
+            checklist: s
+                .tasks
+                .iter()
+                .filter_map(|t| non_empty(&t.description))
+                .collect(),
+            ..Default::default()
+        };
+        for blocker in &s.blockers {
+            add_blocker(&mut story, &blocker.description, blocker.resolved);
+        }
+        imported.push(story);
+    }
+    Ok(imported)
+}
+
+/// Returns the state of the tasks of a Shortcut story.
+fn shortcut_state(completed: bool, blocked: bool, started: bool) -> TaskState {
+    if completed {
+        TaskState::Done
+    } else if blocked {
+        TaskState::Blocked
+    } else if started {
+        TaskState::InProgress
+    } else {
+        TaskState::Todo
+    }
+}
+
+/// Reads the stories of a Shortcut CSV export.
+fn shortcut_csv(text: &str) -> Result<Vec<Story>, String> {
+    let export = CsvExport::parse(text)?;
+    export.require(&["id", "name"])?;
+    let flag = |record, name| {
+        export
+            .value(record, name)
+            .trim()
+            .eq_ignore_ascii_case("true")
+    };
+    let mut stories = Vec::new();
+    for record in &export.records {
+        let state_name = export.value(record, "state").to_lowercase();
+        let started = !export.value(record, "started_at").trim().is_empty()
+            || ["progress", "review", "started"]
+                .iter()
+                .any(|s| state_name.contains(s));
+        let epic_name = non_empty(export.value(record, "epic"));
+        let epic_id = non_empty(export.value(record, "epic_id")).or_else(|| epic_name.clone());
+        let story = Story {
+            id: export.value(record, "id").trim().to_string(),
+            title: export.value(record, "name").trim().to_string(),
+            description: non_empty(export.value(record, "description")),
+            kind: export.value(record, "type").trim().to_lowercase(),
+            labels: split_list(export.value(record, "labels")),
+            state: shortcut_state(
+                flag(record, "is_completed"),
+                flag(record, "is_blocked"),
+                started,
+            ),
+            estimate: export.value(record, "estimate").trim().parse().ok(),
+            epic: epic_id.zip(epic_name),
+            iteration: non_empty(export.value(record, "iteration")),
+            owner: split_list(export.value(record, "owners"))
+                .into_iter()
+                .next(),
+            url: non_empty(export.value(record, "app_url")),
+            created_at: parse_time(export.value(record, "created_at")),
+            completed_at: parse_time(export.value(record, "completed_at")),
+            due: parse_date(export.value(record, "due_date")),
+            checklist: split_list(export.value(record, "tasks")),
+            ..Default::default()
+        };
+        if !story.id.is_empty() {
+            stories.push(story);
+        }
+    }
+    Ok(stories)
+}
+
+/// A story of Shortcut, as returned by its API.
+#[derive(Deserialize)]
+struct ShortcutStory {
+    id: Value,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    story_type: Option<String>,
+    #[serde(default)]
+    labels: Vec<JsonLabel>,
+    #[serde(default)]
+    epic_id: Option<Value>,
+    #[serde(default)]
+    estimate: Option<f64>,
+    #[serde(default)]
+    app_url: Option<String>,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    started: bool,
+    #[serde(default)]
+    blocked: bool,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
+    tasks: Vec<JsonTask>,
+    #[serde(default)]
+    story_links: Vec<ShortcutStoryLink>,
+}
+
+/// A link between Shortcut stories: `subject_id` blocks `object_id` with the `blocks` verb.
+#[derive(Deserialize)]
+struct ShortcutStoryLink {
+    verb: String,
+    subject_id: Value,
+    object_id: Value,
+}
+
+/// An epic of Shortcut, as returned by its API.
+#[derive(Deserialize)]
+struct ShortcutEpic {
+    id: Value,
+    name: String,
+}
+
+/// The stories of a Shortcut JSON export, alone or with their epics.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ShortcutExport {
+    Stories(Vec<ShortcutStory>),
+    Workspace {
+        stories: Vec<ShortcutStory>,
+        #[serde(default)]
+        epics: Vec<ShortcutEpic>,
+    },
+}
+
+/// Reads the stories of a Shortcut JSON export.
+fn shortcut_json(text: &str) -> Result<Vec<Story>, String> {
+    let export: ShortcutExport = serde_json::from_str(text)
+        .map_err(|e| format!("Error parsing the Shortcut stories: {}", e))?;
+    let (stories, epics) = match export {
+        ShortcutExport::Stories(stories) => (stories, Vec::new()),
+        ShortcutExport::Workspace { stories, epics } => (stories, epics),
+    };
+    let mut imported = Vec::new();
+    for s in &stories {
+        let id = json_id(&s.id);
+        let epic = s.epic_id.as_ref().map(|epic_id| {
+            let epic_id = json_id(epic_id);
+            let name = epics
+                .iter()
+                .find(|e| json_id(&e.id) == epic_id)
+                .map_or_else(|| format!("Epic {}", epic_id), |e| e.name.clone());
+            (epic_id, name)
+        });
+        // Links are listed on both stories; only those blocking this one matter
+        let blocked_by = s
+            .story_links
+            .iter()
+            .filter(|l| l.verb == "blocks" && json_id(&l.object_id) == id)
+            .map(|l| json_id(&l.subject_id))
+            .collect();
+        imported.push(Story {
+            title: s.name.trim().to_string(),
+            description: s.description.as_deref().and_then(non_empty),
+            kind: s.story_type.clone().unwrap_or_default().to_lowercase(),
+            labels: s.labels.iter().map(|l| l.name.clone()).collect(),
+            state: shortcut_state(s.completed, s.blocked, s.started),
+            estimate: s.estimate,
+            epic,
+            url: s.app_url.clone(),
+            created_at: s.created_at.as_deref().and_then(parse_time),
+            completed_at: s.completed_at.as_deref().and_then(parse_time),
+            due: s.deadline.as_deref().and_then(parse_date),
+            checklist: s
+                .tasks
+                .iter()
+                .filter_map(|t| non_empty(&t.description))
+                .collect(),
+            blocked_by,
+            id,
+            ..Default::default()
+        });
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the import of Pivotal Tracker CSV and Shortcut JSON exports, and that importing again
+    /// updates the tasks instead of duplicating them.
+    #[test]
+    fn test_import_stories() {
+        let now = Utc::now();
+        let mut backlog = Backlog {
+            project: "weather".into(),
+            ..Default::default()
+        };
+        let csv = "\
+Id,Title,Labels,Iteration,Type,Estimate,Current State,Created at,Accepted at,Description,URL,Owned By,Blocker,Blocker Status,Blocker,Blocker Status,Task,Task Status
+100,Fetch forecast,\"api, backend\",3,feature,2,accepted,\"Nov 20, 2019\",\"Nov 22, 2019\",Call the API,https://www.pivotaltracker.com/story/show/100,Ada,,,,,Parse JSON,completed
+101,Show forecast,ui,,bug,,started,,,,,,#100 needs the data,unresolved,Waiting for design,resolved,,
+102,Dark mode,,,feature,,unstarted,,,,,,Waiting for design,unresolved,,,,
+103,Release 1,,,release,,,,,,,,,,,,,
+";
+        let summary = import_pivotal(&mut backlog, csv, now).unwrap();
+        assert_eq!(summary.added.len(), 3);
+        let fetch = &backlog.tasks[0];
+        assert!(matches!(fetch.state, TaskState::Done));
+        assert_eq!(fetch.tags, vec!["api", "backend"]);
+        assert_eq!(fetch.sprint.as_deref(), Some("Iteration 3"));
+        assert_eq!(fetch.done_when, vec!["Parse JSON"]);
+        assert_eq!(fetch.assignee.as_deref(), Some("Ada"));
+        let show = &backlog.tasks[1];
+        assert_eq!(show.depends, vec![fetch.id.clone()]);
+        assert_eq!(show.tags, vec!["ui", "bug"]);
+        assert!(matches!(show.state, TaskState::InProgress));
+        let dark = &backlog.tasks[2];
+        assert!(matches!(dark.state, TaskState::Blocked));
+        assert_eq!(dark.blocker.as_ref().unwrap().reason, "Waiting for design");
+        let dark_id = dark.id.clone();
+
+        let csv = csv.replace("102,Dark mode", "102,Dark theme");
+        let summary = import_pivotal(&mut backlog, &csv, now).unwrap();
+        assert!(summary.added.is_empty());
+        assert_eq!(summary.updated, vec![dark_id]);
+        assert_eq!(backlog.tasks[2].title, "Dark theme");
+
+        let json = r#"{
+            "epics": [{"id": 7, "name": "Alerts"}],
+            "stories": [
+                {"id": 1, "name": "Send alerts", "epic_id": 7, "started": true,
+                 "labels": [{"name": "notify"}],
+                 "story_links": [{"verb": "blocks", "subject_id": 2, "object_id": 1}]},
+                {"id": 2, "name": "Store devices", "epic_id": 7, "completed": true,
+                 "story_type": "chore"}
+            ]
+        }"#;
+        let summary = import_shortcut(&mut backlog, json, now).unwrap();
+        assert_eq!(summary.added.len(), 2);
+        assert_eq!(summary.new_epics.len(), 1);
+        let epic = &backlog.epics[0];
+        assert_eq!(epic.title, "Alerts");
+        assert!(matches!(epic.tasks[0].state, TaskState::InProgress));
+        assert_eq!(epic.tasks[0].depends, vec![epic.tasks[1].id.clone()]);
+        assert_eq!(epic.tasks[1].tags, vec!["chore"]);
+    }
+}
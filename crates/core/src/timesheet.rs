@@ -0,0 +1,176 @@
+use crate::stats::sum;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use taskai_schema::{Backlog, Task};
+
+/// Hours logged on a task during a day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimesheetEntry {
+    pub date: NaiveDate,
+    pub task: String,
+    pub title: String,
+    pub hours: f64,
+}
+
+/// Hours logged on a task over the whole period, next to its estimate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskTime {
+    pub task: String,
+    pub title: String,
+    pub hours: f64,
+    pub estimate: Option<f64>,
+    /// Whether a timer is running on the task.
+    pub running: bool,
+}
+
+/// Time logged on the tasks of a backlog, per day and per task.
+#[derive(Debug, Clone, Serialize)]
+pub struct Timesheet {
+    /// One entry per day and task with logged time, oldest first.
+    pub entries: Vec<TimesheetEntry>,
+    /// One entry per task with logged time, in backlog order.
+    pub tasks: Vec<TaskTime>,
+    pub total_hours: f64,
+}
+
+/// Builds the timesheet of the backlog from the time logs of its tasks, archived ones included.
+///
+/// Sessions spanning midnight (UTC) are split between their days, and running sessions count up to
+/// `now`. Only days from `since` to `until`, both included, are kept when they are given.
+pub fn timesheet(
+    backlog: &Backlog,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    now: DateTime<Utc>,
+) -> Timesheet {
+    let mut tasks = backlog.all_tasks();
+    tasks.extend(backlog.archive.iter());
+
+    let in_period =
+        |date: NaiveDate| since.is_none_or(|s| date >= s) && until.is_none_or(|u| date <= u);
+
+    // Hours per day, then per task index, to sort entries by date and then by backlog order
+    let mut days: BTreeMap<NaiveDate, BTreeMap<usize, f64>> = BTreeMap::new();
+    for (index, task) in tasks.iter().enumerate() {
+        for entry in &task.time_log {
+            let end = entry.end.unwrap_or(now);
+            for (date, hours) in split_by_day(entry.start, end) {
+                if in_period(date) {
+                    *days.entry(date).or_default().entry(index).or_insert(0.0) += hours;
+                }
+            }
+        }
+    }
+
+    let mut totals: BTreeMap<usize, f64> = BTreeMap::new();
+    let entries: Vec<TimesheetEntry> = days
+        .into_iter()
+        .flat_map(|(date, hours_by_task)| {
+            hours_by_task
+                .into_iter()
+                .map(move |(index, hours)| (date, index, hours))
+        })
+        .map(|(date, index, hours)| {
+            *totals.entry(index).or_insert(0.0) += hours;
+            TimesheetEntry {
+                date,
+                task: tasks[index].id.clone(),
+                title: tasks[index].title.clone(),
+                hours,
+            }
+        })
+        .collect();
+
+    let task_times: Vec<TaskTime> = totals
+        .into_iter()
+        .map(|(index, hours)| task_time(tasks[index], hours))
+        .collect();
+    let total_hours = sum(task_times.iter().map(|t| t.hours));
+
+    Timesheet {
+        entries,
+        tasks: task_times,
+        total_hours,
+    }
+}
+
+fn task_time(task: &Task, hours: f64) -> TaskTime {
+    TaskTime {
+        task: task.id.clone(),
+        title: task.title.clone(),
+        hours,
+        estimate: task.estimate,
+        running: task.running_timer().is_some(),
+    }
+}
+
+/// Splits a session into the hours it covers on each (UTC) day.
+fn split_by_day(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(NaiveDate, f64)> {
+    let mut parts = Vec::new();
+    let mut from = start;
+    while from < end {
+        let date = from.date_naive();
+        let midnight = date
+            .checked_add_days(Days::new(1))
+            .and_then(|next| next.and_hms_opt(0, 0, 0))
+            .map(|next| next.and_utc())
+            .unwrap_or(end);
+        let to = midnight.min(end);
+        parts.push((date, (to - from).num_seconds() as f64 / 3600.0));
+        from = to;
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timesheet() {
+        let yaml = r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Setup
+            estimate: 4
+            time_log:
+              - start: 2024-01-01T22:00:00Z
+                end: 2024-01-02T01:30:00Z
+          - id: T-2
+            title: Docs
+            time_log:
+              - start: 2024-01-02T09:00:00Z
+                end: 2024-01-02T10:00:00Z
+              - start: 2024-01-03T09:00:00Z
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let now: DateTime<Utc> = "2024-01-03T09:30:00Z".parse().unwrap();
+
+        let sheet = timesheet(&backlog, None, None, now);
+        let days: Vec<(String, &str, f64)> = sheet
+            .entries
+            .iter()
+            .map(|e| (e.date.to_string(), e.task.as_str(), e.hours))
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                ("2024-01-01".to_string(), "T-1", 2.0),
+                ("2024-01-02".to_string(), "T-1", 1.5),
+                ("2024-01-02".to_string(), "T-2", 1.0),
+                ("2024-01-03".to_string(), "T-2", 0.5),
+            ]
+        );
+        assert_eq!(sheet.tasks[0].hours, 3.5);
+        assert_eq!(sheet.tasks[0].estimate, Some(4.0));
+        assert!(sheet.tasks[1].running);
+        assert_eq!(sheet.total_hours, 5.0);
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 2);
+        let sheet = timesheet(&backlog, since, since, now);
+        assert_eq!(sheet.entries.len(), 2);
+        assert_eq!(sheet.total_hours, 2.5);
+    }
+}
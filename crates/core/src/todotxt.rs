@@ -0,0 +1,281 @@
+use crate::ids::next_epic_id;
+use crate::rows::{apply_rows_without_ids, match_rows_by_title, ImportSummary, TaskRow};
+use chrono::{DateTime, NaiveDate, Utc};
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// Writes the backlog in the todo.txt format, one task per line.
+///
+/// Done tasks start with `x` and their completion date, others with their priority as `(A)` to
+/// `(Z)`, followed by the creation date. The epic of a task becomes a `+project` named after the
+/// epic title and its tags become `@contexts`; its ID, dependencies, due date, estimate, state,
+/// assignee, milestone, sprint and parent are written as `key:value` extensions. Descriptions and
+/// `done_when` criteria are left out, and spaces in values are replaced with `_`.
+pub fn format_todo_txt(backlog: &Backlog) -> String {
+    let standalone = backlog.tasks.iter().map(|t| (t, None));
+    let in_epics = backlog
+        .epics
+        .iter()
+        .flat_map(|e| e.tasks.iter().map(move |t| (t, Some(e))));
+    standalone
+        .chain(in_epics)
+        .map(|(task, epic)| format_line(task, epic) + "\n")
+        .collect()
+}
+
+fn format_line(task: &Task, epic: Option<&Epic>) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let done = matches!(task.state, TaskState::Done);
+    let cookie = task
+        .priority
+        .filter(|p| (1..=26).contains(p))
+        .map(|p| (b'A' + p as u8 - 1) as char);
+    if done {
+        words.push("x".to_string());
+        // The creation date of a done task only goes after its completion date
+        if let Some(completed) = task.completed_at {
+            words.push(completed.date_naive().to_string());
+            words.extend(task.created_at.map(|c| c.date_naive().to_string()));
+        }
+    } else {
+        words.extend(cookie.map(|c| format!("({})", c)));
+        words.extend(task.created_at.map(|c| c.date_naive().to_string()));
+    }
+    words.push(task.title.clone());
+    words.extend(epic.map(|e| format!("+{}", slug(&e.title))));
+    words.extend(task.tags.iter().map(|t| format!("@{}", slug(t))));
+
+    words.push(format!("id:{}", task.id));
+    if !task.depends.is_empty() {
+        words.push(format!("dep:{}", task.depends.join(",")));
+    }
+    let state = match task.state {
+        TaskState::InProgress => Some("in-progress"),
+        TaskState::Blocked => Some("blocked"),
+        TaskState::Todo | TaskState::Done => None,
+    };
+    let priority = match (cookie, task.priority) {
+        (Some(cookie), _) if done => Some(cookie.to_string()),
+        (None, Some(priority)) => Some(priority.to_string()),
+        _ => None,
+    };
+    let extensions = [
+        ("state", state.map(str::to_string)),
+        ("pri", priority),
+        ("due", task.due.map(|d| d.to_string())),
+        ("est", task.estimate.map(|e| e.to_string())),
+        ("assignee", task.assignee.clone()),
+        ("milestone", task.milestone.clone()),
+        ("sprint", task.sprint.clone()),
+        ("parent", task.parent.clone()),
+    ];
+    for (key, value) in extensions {
+        if let Some(value) = value {
+            words.push(format!("{}:{}", key, slug(&value)));
+        }
+    }
+    words.join(" ")
+}
+
+/// Replaces whitespace with `_`, since todo.txt words cannot contain spaces.
+fn slug(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// Reads a todo.txt word as a date, if it is one.
+fn parse_date(word: Option<&&str>) -> Option<NaiveDate> {
+    word.and_then(|w| NaiveDate::parse_from_str(w, "%Y-%m-%d").ok())
+}
+
+/// Returns the ID of the epic of a `+project`: the epic with that ID or title (with `_` for
+/// spaces), created if there is none.
+fn resolve_epic(backlog: &mut Backlog, project: &str, summary: &mut ImportSummary) -> String {
+    let existing = backlog
+        .epics
+        .iter()
+        .find(|e| e.id == project || slug(&e.title).eq_ignore_ascii_case(project));
+    if let Some(epic) = existing {
+        return epic.id.clone();
+    }
+    let id = next_epic_id(backlog);
+    backlog.epics.push(Epic {
+        id: id.clone(),
+        title: project.replace('_', " "),
+        ..Default::default()
+    });
+    summary.new_epics.push(id.clone());
+    id
+}
+
+/// Parses a todo.txt line into a row, with the `+projects` of the task.
+fn parse_line(line: &str) -> (TaskRow, Vec<String>) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut row = TaskRow::default();
+    let mut rest = &words[..];
+
+    if rest.first() == Some(&"x") {
+        row.state = Some("Done".to_string());
+        rest = &rest[1..];
+        // Completion and creation dates
+        for _ in 0..2 {
+            if parse_date(rest.first()).is_some() {
+                rest = &rest[1..];
+            }
+        }
+    } else {
+        let cookie = rest.first().and_then(|w| {
+            let letter = w.strip_prefix('(')?.strip_suffix(')')?;
+            let letter = letter.chars().next().filter(|c| c.is_ascii_uppercase())?;
+            (w.len() == 3).then_some(letter)
+        });
+        if let Some(letter) = cookie {
+            row.priority = Some(u32::from(letter as u8 - b'A') + 1);
+            rest = &rest[1..];
+        }
+        if parse_date(rest.first()).is_some() {
+            rest = &rest[1..];
+        }
+    }
+
+    let mut title = Vec::new();
+    let mut projects = Vec::new();
+    let mut tags = Vec::new();
+    for word in rest {
+        if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+            projects.push(project.to_string());
+            continue;
+        }
+        if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            tags.push(context.to_string());
+            continue;
+        }
+        let value = word
+            .split_once(':')
+            .map(|(key, value)| (key, value.to_string()));
+        match value {
+            Some(("id", value)) => row.id = value,
+            Some(("dep", value)) => row.depends = value.replace(',', ", "),
+            Some(("state", value)) if row.state.is_none() => row.state = Some(value),
+            Some(("pri", value)) => {
+                let letter = value.chars().next().filter(|c| c.is_ascii_uppercase());
+                row.priority = match letter {
+                    Some(letter) if value.len() == 1 => Some(u32::from(letter as u8 - b'A') + 1),
+                    _ => value.parse().ok().or(row.priority),
+                };
+            }
+            Some(("due", value)) => row.due = value.parse().ok(),
+            Some(("est", value)) => row.estimate = value.parse().ok(),
+            Some(("assignee", value)) => row.assignee = Some(value),
+            Some(("milestone", value)) => row.milestone = Some(value),
+            Some(("sprint", value)) => row.sprint = Some(value),
+            Some(("parent", value)) => row.parent = Some(value),
+            _ => title.push(*word),
+        }
+    }
+    row.title = title.join(" ");
+    // Projects after the first one are kept as tags
+    tags.extend(projects.iter().skip(1).cloned());
+    row.tags = tags.join(", ");
+    (row, projects)
+}
+
+/// Imports a todo.txt list into the backlog, on a best-effort basis.
+///
+/// Each non-empty line is a task, matched by its `id:` extension, or by title within its epic when
+/// it has none: known tasks are updated and others added. The first `+project` of a line is the
+/// epic of the task, matched by ID or title and created if missing; `@contexts` and other projects
+/// become tags. Unknown `key:value` extensions are kept in the title, and descriptions and
+/// `done_when` criteria of known tasks are left untouched. Returns an error if the resulting
+/// backlog has missing dependencies or cycles.
+pub fn import_todo_txt(
+    backlog: &mut Backlog,
+    text: &str,
+    now: DateTime<Utc>,
+) -> Result<ImportSummary, String> {
+    let mut summary = ImportSummary::default();
+    let mut rows = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let (mut row, projects) = parse_line(line);
+        row.epic = projects
+            .first()
+            .map(|project| resolve_epic(backlog, project, &mut summary));
+        rows.push(row);
+    }
+
+    // todo.txt has no room for descriptions, so keep those of known tasks
+    match_rows_by_title(backlog, &mut rows);
+    for row in &mut rows {
+        if let Some(task) = backlog.find_task(&row.id) {
+            row.description = task.description.clone();
+        }
+    }
+    summary.merge(apply_rows_without_ids(backlog, &mut rows, now)?);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_todo_txt_round_trip() {
+        let yaml = r#"
+        project: app
+        tasks:
+          - id: T-1
+            title: Setup
+            state: Done
+            priority: 2
+            completed_at: 2024-02-03T10:00:00Z
+            created_at: 2024-02-01T10:00:00Z
+        epics:
+          - id: E-1
+            title: User API
+            tasks:
+              - id: T-2
+                title: Endpoints
+                state: Blocked
+                description: Serve JSON.
+                depends: [T-1]
+                tags: [backend]
+                estimate: 3
+                priority: 1
+                due: 2024-03-01
+                sprint: Sprint 3
+        "#;
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+
+        let text = format_todo_txt(&backlog);
+        assert_eq!(
+            text,
+            "x 2024-02-03 2024-02-01 Setup id:T-1 pri:B\n\
+             (A) Endpoints +User_API @backend id:T-2 dep:T-1 state:blocked due:2024-03-01 est:3 \
+             sprint:Sprint_3\n"
+        );
+
+        let now = Utc::now();
+        let mut copy = backlog.clone();
+        let summary = import_todo_txt(&mut copy, &text, now).unwrap();
+        assert_eq!(summary.unchanged, 1);
+        assert_eq!(summary.updated, ["T-2"]);
+        let task = copy.find_task("T-2").unwrap();
+        assert_eq!(task.sprint.as_deref(), Some("Sprint_3"));
+        assert_eq!(task.description.as_deref(), Some("Serve JSON."));
+        assert!(matches!(task.state, TaskState::Blocked));
+
+        let mut empty = Backlog::default();
+        let list = "(B) 2024-05-01 Call Mom @phone +Family due:2024-05-02 at:10:30\n\
+                    x Pay rent +Family\n";
+        let summary = import_todo_txt(&mut empty, list, now).unwrap();
+        assert_eq!(summary.new_epics, ["E-1"]);
+        assert_eq!(empty.epics[0].title, "Family");
+        let call = &empty.epics[0].tasks[0];
+        assert_eq!(call.title, "Call Mom at:10:30");
+        assert_eq!(
+            (call.priority, call.tags.as_slice()),
+            (Some(2), &["phone".to_string()][..])
+        );
+        assert!(matches!(empty.epics[0].tasks[1].state, TaskState::Done));
+        let summary = import_todo_txt(&mut empty, list, now).unwrap();
+        assert_eq!(summary.unchanged, 2);
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Serialize;
+use std::path::Path;
+use taskai_schema::Task;
+
+/// Result of checking a single deliverable entry against the working tree.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliverableStatus {
+    /// The deliverable as written in the backlog (a path or a glob pattern).
+    pub pattern: String,
+    /// Whether at least one file matching the deliverable exists.
+    pub found: bool,
+}
+
+/// Result of checking all deliverables of a task.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskVerification {
+    /// ID of the verified task.
+    pub task_id: String,
+    /// Title of the verified task.
+    pub title: String,
+    /// Status of each deliverable declared by the task.
+    pub deliverables: Vec<DeliverableStatus>,
+}
+
+impl TaskVerification {
+    /// Returns the deliverables that could not be found.
+    pub fn missing(&self) -> Vec<&str> {
+        self.deliverables
+            .iter()
+            .filter(|d| !d.found)
+            .map(|d| d.pattern.as_str())
+            .collect()
+    }
+}
+
+/// Checks whether the deliverables of a task exist under the given root directory.
+///
+/// Deliverables containing glob metacharacters (`*`, `?`, `[`) are expanded relative to `root`
+/// and considered present if they match at least one path. Other deliverables are checked as plain paths.
+pub fn verify_task(task: &Task, root: &Path) -> TaskVerification {
+    let deliverables = task
+        .deliverable
+        .as_ref()
+        .map(|spec| spec.paths())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pattern| DeliverableStatus {
+            pattern: pattern.to_string(),
+            found: deliverable_exists(pattern, root),
+        })
+        .collect();
+
+    TaskVerification {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        deliverables,
+    }
+}
+
+/// Returns true if the deliverable path or glob pattern matches something under `root`.
+///
+/// Only the deliverable is a pattern: glob metacharacters in `root` match themselves.
+pub(crate) fn deliverable_exists(pattern: &str, root: &Path) -> bool {
+    if !is_glob(pattern) {
+        return root.join(pattern).exists();
+    }
+
+    let root = glob::Pattern::escape(&root.to_string_lossy());
+    let full_pattern = Path::new(&root).join(pattern);
+    match glob::glob(&full_pattern.to_string_lossy()) {
+        Ok(mut paths) => paths.any(|entry| entry.is_ok()),
+        Err(_) => false,
+    }
+}
+
+/// Returns true if the deliverable contains glob metacharacters.
+pub(crate) fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use taskai_schema::{DeliverableSpec, TaskState};
+
+    #[test]
+    fn verify_paths_and_globs() {
+        let dir = std::env::temp_dir().join(format!("taskai-verify-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+
+        let task = Task {
+            id: "T-1".to_string(),
+            title: "Task 1".to_string(),
+            depends: vec![],
+            state: TaskState::Done,
+            deliverable: Some(DeliverableSpec::Multiple(vec![
+                "src/main.rs".to_string(),
+                "src/*.rs".to_string(),
+                "README.md".to_string(),
+                "docs/**/*.md".to_string(),
+            ])),
+            ..Default::default()
+        };
+
+        let result = verify_task(&task, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.missing(), vec!["README.md", "docs/**/*.md"]);
+    }
+
+    #[test]
+    fn verify_globs_under_root_with_metacharacters() {
+        let dir = std::env::temp_dir().join(format!("taskai-verify-[{}]", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+
+        let found = deliverable_exists("src/*.rs", &dir);
+        let missing = deliverable_exists("docs/*.md", &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(found);
+        assert!(!missing);
+    }
+}
@@ -0,0 +1,285 @@
+use regex::Regex;
+use reqwest::Url;
+use std::time::Duration;
+
+/// Maximum number of linked pages followed when fetching a specification.
+pub const MAX_FOLLOWED_PAGES: usize = 20;
+
+/// Time after which an HTTP request is abandoned, so that an unresponsive server fails the command
+/// instead of hanging it.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the HTTP client used to fetch pages and to talk to trackers and chats, identified as
+/// taskai and giving up on requests after `HTTP_TIMEOUT`.
+pub(crate) fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(concat!("taskai/", env!("CARGO_PKG_VERSION")))
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        // Building only fails when the TLS backend cannot start, which `Client::new` panics on too
+        .unwrap_or_default()
+}
+
+/// A page fetched as a specification: its text, and the links it holds when it is HTML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebPage {
+    pub url: String,
+    /// Text of the page: HTML stripped to text, Markdown and plain text as they are.
+    pub text: String,
+    /// Links of the page to other pages under its directory, in order and without duplicates.
+    pub links: Vec<String>,
+}
+
+/// Fetches the document at `url` and, with `follow` set, up to that many of the pages it links to
+/// under its directory, as the specification of a backlog.
+///
+/// HTML pages are stripped to text, other text documents such as Markdown are passed through.
+/// Linked pages are fetched in the order of their links; those that fail or are not text are
+/// skipped. Each page starts with a `Source:` line naming its URL.
+pub async fn fetch_spec(url: &str, follow: usize) -> Result<String, String> {
+    let client = http_client();
+    let page = fetch_page(&client, url).await?;
+    let mut spec = format!("Source: {}\n\n{}", page.url, page.text);
+    let mut fetched = 0;
+    for link in &page.links {
+        if fetched >= follow.min(MAX_FOLLOWED_PAGES) {
+            break;
+        }
+        match fetch_page(&client, link).await {
+            Ok(linked) => {
+                spec.push_str(&format!(
+                    "\n\n---\n\nSource: {}\n\n{}",
+                    linked.url, linked.text
+                ));
+                fetched += 1;
+            }
+            Err(err) => tracing::warn!(%link, %err, "skipping linked page"),
+        }
+    }
+    Ok(spec)
+}
+
+/// Fetches a specification like [`fetch_spec`], blocking until it is done, for programs that are
+/// not async. Must not be called from within an async runtime.
+pub fn fetch_spec_blocking(url: &str, follow: usize) -> Result<String, String> {
+    crate::blocking::block_on(fetch_spec(url, follow))?
+}
+
+/// Fetches one page and reads its text and links.
+async fn fetch_page(client: &reqwest::Client, url: &str) -> Result<WebPage, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Unsupported URL {}: only http and https are fetched.",
+            url
+        ));
+    }
+    tracing::info!(%url, "fetching specification");
+    let response = client
+        .get(parsed)
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{} returned status {}", url, status.as_u16()));
+    }
+    // Redirects may have moved the page, and links are relative to where it ended up
+    let url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    if !is_text(&content_type) {
+        return Err(format!(
+            "{} is not a text document ({}).",
+            url, content_type
+        ));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Error reading {}: {}", url, e))?;
+    Ok(read_page(&url, &content_type, &body))
+}
+
+/// Tells whether a document of `content_type` can be read as text; an unknown type is tried.
+fn is_text(content_type: &str) -> bool {
+    content_type.is_empty()
+        || content_type.starts_with("text/")
+        || ["html", "xml", "markdown", "json", "yaml"]
+            .iter()
+            .any(|kind| content_type.contains(kind))
+}
+
+/// Reads the text and links of the document `body` served at `url` as `content_type`.
+pub fn read_page(url: &str, content_type: &str, body: &str) -> WebPage {
+    let start = body
+        .trim_start()
+        .get(..15)
+        .unwrap_or_default()
+        .to_lowercase();
+    let is_html = content_type.contains("html")
+        || start.starts_with("<!doctype html")
+        || start.starts_with("<html");
+    if !is_html {
+        return WebPage {
+            url: url.to_string(),
+            text: body.trim().to_string(),
+            links: Vec::new(),
+        };
+    }
+    WebPage {
+        url: url.to_string(),
+        text: html_to_text(body),
+        links: page_links(url, body),
+    }
+}
+
+/// Strips an HTML document to its text, keeping headings, list items and paragraphs on lines of
+/// their own, headings marked as in Markdown.
+///
+/// Scripts, styles, the head and the navigation of the page are left out.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = html.to_string();
+    for tag in [
+        "head", "script", "style", "noscript", "svg", "nav", "footer", "template",
+    ] {
+        let block = Regex::new(&format!(r"(?is)<{0}\b.*?</{0}\s*>", tag)).unwrap();
+        text = block.replace_all(&text, " ").into_owned();
+    }
+    text = Regex::new(r"(?s)<!--.*?-->")
+        .unwrap()
+        .replace_all(&text, " ")
+        .into_owned();
+    for level in 1..=6 {
+        let heading = Regex::new(&format!(r"(?i)<h{}\b[^>]*>", level)).unwrap();
+        let marker = format!("\n\n{} ", "#".repeat(level));
+        text = heading.replace_all(&text, marker.as_str()).into_owned();
+    }
+    text = Regex::new(r"(?i)<li\b[^>]*>")
+        .unwrap()
+        .replace_all(&text, "\n- ")
+        .into_owned();
+    text = Regex::new(r"(?i)<br\s*/?>")
+        .unwrap()
+        .replace_all(&text, "\n")
+        .into_owned();
+    text = Regex::new(
+        r"(?i)</?(p|div|section|article|main|ul|ol|table|tr|pre|blockquote|h[1-6])\b[^>]*>",
+    )
+    .unwrap()
+    .replace_all(&text, "\n\n")
+    .into_owned();
+    text = Regex::new(r"<[^>]*>")
+        .unwrap()
+        .replace_all(&text, " ")
+        .into_owned();
+    let text = decode_entities(&text);
+
+    // Collapse the whitespace of each line, and runs of blank lines into one
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() && lines.last().is_none_or(String::is_empty) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Decodes the named entities common in text, and numeric ones.
+fn decode_entities(text: &str) -> String {
+    let entity = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+    entity
+        .replace_all(text, |captures: &regex::Captures| {
+            let name = &captures[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => name
+                    .strip_prefix("#x")
+                    .or_else(|| name.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Returns the links of an HTML page at `url` to other pages under its directory, such as the
+/// chapters linked from the index of a document, resolved and without their fragment.
+pub fn page_links(url: &str, html: &str) -> Vec<String> {
+    let Ok(base) = Url::parse(url) else {
+        return Vec::new();
+    };
+    let directory = &base.path()[..base.path().rfind('/').map_or(0, |i| i + 1)];
+    let href = Regex::new(r#"(?i)<a\b[^>]*?\bhref\s*=\s*("([^"]*)"|'([^']*)')"#).unwrap();
+    let mut links: Vec<String> = Vec::new();
+    for captures in href.captures_iter(html) {
+        let target = captures
+            .get(2)
+            .or(captures.get(3))
+            .map_or("", |m| m.as_str());
+        let Ok(mut link) = base.join(&decode_entities(target)) else {
+            continue;
+        };
+        link.set_fragment(None);
+        let same_site = link.scheme() == base.scheme()
+            && link.host_str() == base.host_str()
+            && link.path().starts_with(directory);
+        let page = link.as_str().trim_end_matches('/') != base.as_str().trim_end_matches('/');
+        let link = link.to_string();
+        if same_site && page && !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that HTML pages are stripped to text with their links, and Markdown passed through.
+    #[test]
+    fn test_read_page() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Docs</title><style>body { color: red; }</style></head>
+<body>
+  <nav><a href="/">Home</a></nav>
+  <h1>Weather   app</h1>
+  <p>Shows the forecast &amp; alerts.<br>Runs offline.</p>
+  <ul><li>Daily view</li><li><a href="api.html#get">API</a></li></ul>
+  <script>alert("hi")</script>
+  <a href="https://example.com/docs/api.html">API again</a>
+  <a href="https://other.org/">Elsewhere</a>
+  <a href='#top'>Top</a>
+</body></html>"#;
+        let page = read_page(
+            "https://example.com/docs/",
+            "text/html; charset=utf-8",
+            html,
+        );
+        assert_eq!(
+            page.text,
+            "# Weather app\n\nShows the forecast & alerts.\nRuns offline.\n\n- Daily view\n- API\n\n\
+             API again\nElsewhere\nTop"
+        );
+        assert_eq!(page.links, vec!["https://example.com/docs/api.html"]);
+
+        let markdown = "# Weather app\n\n- Daily view\n";
+        let page = read_page("https://example.com/spec.md", "text/markdown", markdown);
+        assert_eq!(page.text, "# Weather app\n\n- Daily view");
+        assert!(page.links.is_empty());
+    }
+}
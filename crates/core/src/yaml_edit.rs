@@ -0,0 +1,525 @@
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use taskai_schema::{Backlog, Task};
+
+/// Applies the changes made to a backlog to the YAML text it was read from, so that comments, key
+/// order, quoting and every unchanged field are kept as they are.
+///
+/// `source` is parsed to find what changed. Changed, added and removed fields of tasks and epics
+/// are edited in place, and tasks added to or removed from a list are inserted or deleted. Returns
+/// `None` when the changes cannot be made in place (tasks reordered or moved to another list, epics
+/// added or removed, top-level fields changed, tasks written in flow style...) or when the edited
+/// text would not read back as `backlog`; the backlog should then be written in canonical form.
+pub fn update_yaml(source: &str, backlog: &Backlog) -> Option<String> {
+    let old: Backlog = serde_yaml::from_str(source).ok()?;
+    let mut editor = Editor::new(source);
+
+    // Top-level fields other than the lists are left to a full rewrite
+    let lists = ["epics", "tasks", "archive"];
+    if fields(&old, &lists)? != fields(backlog, &lists)? {
+        return None;
+    }
+
+    let old_epics: Vec<&str> = old.epics.iter().map(|e| e.id.as_str()).collect();
+    let new_epics: Vec<&str> = backlog.epics.iter().map(|e| e.id.as_str()).collect();
+    if old_epics != new_epics {
+        return None;
+    }
+    for (old_epic, new_epic) in old.epics.iter().zip(&backlog.epics) {
+        let (old_fields, new_fields) =
+            (fields(old_epic, &["tasks"])?, fields(new_epic, &["tasks"])?);
+        if old_fields != new_fields {
+            editor.edit_fields(&old_epic.id, &old_fields, &new_fields)?;
+        }
+    }
+
+    let old_lists = task_lists(&old);
+    let new_lists = task_lists(backlog);
+    let list_of = |lists: &[Vec<&Task>]| -> HashMap<String, usize> {
+        lists
+            .iter()
+            .enumerate()
+            .flat_map(|(index, list)| list.iter().map(move |t| (t.id.clone(), index)))
+            .collect()
+    };
+    let (old_list_of, new_list_of) = (list_of(&old_lists), list_of(&new_lists));
+
+    for (index, (old_list, new_list)) in old_lists.iter().zip(&new_lists).enumerate() {
+        // Tasks kept in the list must stay in the same list, in the same order
+        let kept_old: Vec<&str> = old_list
+            .iter()
+            .filter(|t| new_list_of.contains_key(&t.id))
+            .map(|t| t.id.as_str())
+            .collect();
+        let kept_new: Vec<&str> = new_list
+            .iter()
+            .filter(|t| old_list_of.contains_key(&t.id))
+            .map(|t| t.id.as_str())
+            .collect();
+        if kept_old != kept_new
+            || kept_new
+                .iter()
+                .any(|id| old_list_of.get(*id) != Some(&index))
+        {
+            return None;
+        }
+
+        for task in old_list.iter().filter(|t| !new_list_of.contains_key(&t.id)) {
+            editor.remove_item(&task.id)?;
+        }
+        for (position, task) in new_list.iter().enumerate() {
+            match old_list.iter().find(|t| t.id == task.id) {
+                Some(old_task) => {
+                    let (old_fields, new_fields) = (fields(*old_task, &[])?, fields(*task, &[])?);
+                    if old_fields != new_fields {
+                        editor.edit_fields(&task.id, &old_fields, &new_fields)?;
+                    }
+                }
+                None => {
+                    let kept = |t: &&&Task| old_list_of.contains_key(&t.id);
+                    match new_list[..position].iter().rev().find(kept) {
+                        Some(previous) => editor.insert_item_after(&previous.id, task)?,
+                        None => {
+                            let next = new_list[position + 1..].iter().find(kept)?;
+                            editor.insert_item_before(&next.id, task)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let text = editor.apply()?;
+    let parsed: Backlog = serde_yaml::from_str(&text).ok()?;
+    (serde_yaml::to_value(&parsed).ok()? == serde_yaml::to_value(backlog).ok()?).then_some(text)
+}
+
+/// Lists of tasks of the backlog: standalone tasks, the tasks of each epic, then archived tasks.
+fn task_lists(backlog: &Backlog) -> Vec<Vec<&Task>> {
+    let mut lists = vec![backlog.tasks.iter().collect()];
+    lists.extend(backlog.epics.iter().map(|e| e.tasks.iter().collect()));
+    lists.push(backlog.archive.iter().collect());
+    lists
+}
+
+/// Serializes the value into its fields, leaving out the `excluded` ones.
+fn fields(value: &impl Serialize, excluded: &[&str]) -> Option<Mapping> {
+    let Value::Mapping(mut mapping) = serde_yaml::to_value(value).ok()? else {
+        return None;
+    };
+    for key in excluded {
+        mapping.remove(*key);
+    }
+    Some(mapping)
+}
+
+/// A task or epic written as a block mapping in a sequence (`- id: T-1`).
+struct Item {
+    /// Line of the sequence dash.
+    start: usize,
+    /// Line after the last line of the item, trailing comments and blank lines excluded.
+    end: usize,
+    /// Column of the keys of the mapping.
+    column: usize,
+    /// Keys of the mapping, with their line.
+    keys: Vec<(String, usize)>,
+}
+
+/// A replacement of lines of the source.
+struct Edit {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+}
+
+/// Collects edits to the lines of a YAML source, then applies them all at once.
+struct Editor<'a> {
+    lines: Vec<&'a str>,
+    edits: Vec<Edit>,
+}
+
+impl<'a> Editor<'a> {
+    fn new(source: &'a str) -> Self {
+        Editor {
+            lines: source.split('\n').collect(),
+            edits: Vec::new(),
+        }
+    }
+
+    /// Edits the fields of the item with the given ID that differ between `old` and `new`.
+    fn edit_fields(&mut self, id: &str, old: &Mapping, new: &Mapping) -> Option<()> {
+        let item = self.find_item(id)?;
+        let new_keys: Vec<&str> = new.keys().filter_map(Value::as_str).collect();
+
+        for (key, value) in new {
+            let key = key.as_str()?;
+            if old.get(key) == Some(value) {
+                continue;
+            }
+            let edit = match item.keys.iter().find(|(k, _)| k == key) {
+                Some(&(_, line)) => {
+                    let end = self.value_end(&item, line);
+                    let mut rendered =
+                        self.render_field(key, value, &self.lines[line][..item.column])?;
+                    self.keep_comment(line, end, &mut rendered);
+                    Edit {
+                        start: line,
+                        end,
+                        lines: rendered,
+                    }
+                }
+                None => {
+                    // After the closest key coming before it in schema order, or after the first key
+                    let position = new_keys.iter().position(|k| *k == key)?;
+                    let previous = new_keys[..position]
+                        .iter()
+                        .rev()
+                        .find_map(|k| item.keys.iter().find(|(existing, _)| existing == k))
+                        .or(item.keys.first())?;
+                    let at = self.value_end(&item, previous.1);
+                    let indent = " ".repeat(item.column);
+                    Edit {
+                        start: at,
+                        end: at,
+                        lines: self.render_field(key, value, &indent)?,
+                    }
+                }
+            };
+            self.edits.push(edit);
+        }
+
+        for key in old.keys().filter_map(Value::as_str) {
+            if new.contains_key(key) {
+                continue;
+            }
+            if let Some(&(_, line)) = item.keys.iter().find(|(k, _)| k == key) {
+                // The first key shares its line with the sequence dash
+                if line == item.start {
+                    return None;
+                }
+                let end = self.value_end(&item, line);
+                self.edits.push(Edit {
+                    start: line,
+                    end,
+                    lines: Vec::new(),
+                });
+            }
+        }
+        Some(())
+    }
+
+    /// Deletes the item with the given ID.
+    fn remove_item(&mut self, id: &str) -> Option<()> {
+        let item = self.find_item(id)?;
+        self.edits.push(Edit {
+            start: item.start,
+            end: item.end,
+            lines: Vec::new(),
+        });
+        Some(())
+    }
+
+    /// Inserts a task after the item with the given ID, in the same sequence.
+    fn insert_item_after(&mut self, id: &str, task: &Task) -> Option<()> {
+        let item = self.find_item(id)?;
+        let lines = self.render_item(&item, task)?;
+        self.edits.push(Edit {
+            start: item.end,
+            end: item.end,
+            lines,
+        });
+        Some(())
+    }
+
+    /// Inserts a task before the item with the given ID, in the same sequence.
+    fn insert_item_before(&mut self, id: &str, task: &Task) -> Option<()> {
+        let item = self.find_item(id)?;
+        let lines = self.render_item(&item, task)?;
+        self.edits.push(Edit {
+            start: item.start,
+            end: item.start,
+            lines,
+        });
+        Some(())
+    }
+
+    /// Applies the edits, returning the edited text. Returns `None` if edits overlap.
+    fn apply(mut self) -> Option<String> {
+        // Stable sort: insertions at the same line keep the order they were made in
+        self.edits.sort_by_key(|edit| edit.start);
+        let mut output: Vec<String> = Vec::with_capacity(self.lines.len());
+        let mut next = 0;
+        for edit in &self.edits {
+            if edit.start < next {
+                return None;
+            }
+            output.extend(self.lines[next..edit.start].iter().map(|l| l.to_string()));
+            output.extend(edit.lines.iter().cloned());
+            next = next.max(edit.end);
+        }
+        output.extend(self.lines[next..].iter().map(|l| l.to_string()));
+        Some(output.join("\n"))
+    }
+
+    /// Finds the item whose `id` key has the given value. Returns `None` if there is no such item,
+    /// or more than one.
+    fn find_item(&self, id: &str) -> Option<Item> {
+        let mut found = self.lines.iter().enumerate().filter_map(|(index, line)| {
+            let column = key_column(line);
+            let (key, rest) = split_key(&line[column..])?;
+            (key == "id" && scalar(rest) == id).then_some((index, column))
+        });
+        let (line, column) = found.next()?;
+        if found.next().is_some() || column < 2 {
+            return None;
+        }
+
+        // The item starts at the line with its sequence dash, which may be above the `id` key
+        let is_start =
+            |text: &str| key_column(text) == column && text[..column].trim_end().ends_with('-');
+        let start = (0..=line).rev().find(|&index| {
+            let text = self.lines[index];
+            !is_blank(text) && (is_start(text) || indent(text) < column)
+        })?;
+        if !is_start(self.lines[start]) {
+            return None;
+        }
+
+        let mut end = line + 1;
+        for index in line + 1..self.lines.len() {
+            let text = self.lines[index];
+            if is_blank(text) {
+                continue;
+            }
+            if indent(text) < column {
+                break;
+            }
+            end = index + 1;
+        }
+
+        let keys = (start..end)
+            .filter(|&index| {
+                let text = self.lines[index];
+                (index == start || indent(text) == column)
+                    && !text[column.min(text.len())..].starts_with('-')
+            })
+            .filter_map(|index| {
+                let (key, _) = split_key(self.lines[index].get(column..)?)?;
+                Some((key.to_string(), index))
+            })
+            .collect();
+        Some(Item {
+            start,
+            end,
+            column,
+            keys,
+        })
+    }
+
+    /// Returns the line after the value of the key at `line`, trailing comments and blank lines
+    /// excluded.
+    fn value_end(&self, item: &Item, line: usize) -> usize {
+        let next_key = item
+            .keys
+            .iter()
+            .map(|&(_, index)| index)
+            .filter(|&index| index > line)
+            .min()
+            .unwrap_or(item.end);
+        (line + 1..next_key)
+            .rev()
+            .find(|&index| !is_blank(self.lines[index]))
+            .map_or(line + 1, |index| index + 1)
+    }
+
+    /// Renders `key: value` as lines starting with `prefix`, continuation lines being indented to
+    /// the width of the prefix.
+    fn render_field(&self, key: &str, value: &Value, prefix: &str) -> Option<Vec<String>> {
+        let mut mapping = Mapping::new();
+        mapping.insert(Value::String(key.to_string()), value.clone());
+        let yaml = serde_yaml::to_string(&mapping).ok()?;
+        Some(indent_lines(&yaml, prefix, &" ".repeat(prefix.len())))
+    }
+
+    /// Renders a task as an item of the same sequence as `item`.
+    fn render_item(&self, item: &Item, task: &Task) -> Option<Vec<String>> {
+        let yaml = serde_yaml::to_string(&[task]).ok()?;
+        let indent = " ".repeat(indent(self.lines[item.start]));
+        Some(indent_lines(&yaml, &indent, &indent))
+    }
+
+    /// Keeps the trailing comment of a single-line value replaced by a single line.
+    fn keep_comment(&self, line: usize, end: usize, rendered: &mut [String]) {
+        if end != line + 1 || rendered.len() != 1 {
+            return;
+        }
+        let text = self.lines[line];
+        if let Some(comment) = comment_start(text) {
+            let code = text[..comment].trim_end();
+            rendered[0].push_str(&text[code.len()..]);
+        }
+    }
+}
+
+/// Prefixes the lines of a YAML document: `first` for the first line, `rest` for the others.
+fn indent_lines(yaml: &str, first: &str, rest: &str) -> Vec<String> {
+    yaml.trim_end_matches('\n')
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => format!("{}{}", first, line),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{}{}", rest, line),
+        })
+        .collect()
+}
+
+/// Returns the number of leading spaces of the line.
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Returns the column where the content of the line starts, after a sequence dash if any.
+fn key_column(line: &str) -> usize {
+    let column = indent(line);
+    match line[column..].strip_prefix("- ") {
+        Some(rest) => line.len() - rest.trim_start_matches(' ').len(),
+        None => column,
+    }
+}
+
+/// Returns true if the line is empty or only holds a comment.
+fn is_blank(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// Splits `key: rest` into its key and the rest of the line, for plain keys only.
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let colon = text.find(':')?;
+    let (key, rest) = (&text[..colon], &text[colon + 1..]);
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    (plain && (rest.is_empty() || rest.starts_with(' '))).then_some((key, rest))
+}
+
+/// Returns the value of a single-line scalar, without comment or quotes.
+fn scalar(text: &str) -> &str {
+    let text = match comment_start(text) {
+        Some(comment) => &text[..comment],
+        None => text,
+    }
+    .trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| text.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(text)
+}
+
+/// Returns the byte offset of the comment of the line, if any, ignoring `#` within quotes.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (offset, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if previous.is_whitespace() || previous == ':' => quote = Some(c),
+            (None, '#') if previous.is_whitespace() => return Some(offset),
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use taskai_schema::TaskState;
+
+    #[test]
+    fn test_update_yaml() {
+        let source = r#"# Backlog of the web site
+project: web
+
+tasks:
+  # Infrastructure first
+  - id: T-1
+    title: 'Setup'   # keep it short
+    depends: []
+    state: Todo
+  - title: Build
+    id: "T-2"
+    depends: [T-1]
+
+  - id: T-3
+    title: Ship
+    depends: []
+epics:
+  - id: E-1
+    title: API
+    tasks:
+      - id: E-1-1
+        title: Models
+"#;
+        let mut backlog: Backlog = serde_yaml::from_str(source).unwrap();
+        let now = Utc::now();
+        backlog.tasks[0].title = "Set up".to_string();
+        backlog.tasks[1].set_state(TaskState::Done, now);
+        backlog.tasks.remove(2);
+        backlog.epics[0].title = "Public API".to_string();
+        backlog.epics[0].tasks.push(Task {
+            id: "E-1-2".to_string(),
+            title: "Endpoints".to_string(),
+            depends: vec!["E-1-1".to_string()],
+            ..Default::default()
+        });
+
+        let updated = update_yaml(source, &backlog).unwrap();
+        let stamp = serde_yaml::to_string(&now).unwrap();
+        let stamp = stamp.trim();
+        assert_eq!(
+            updated,
+            format!(
+                r#"# Backlog of the web site
+project: web
+
+tasks:
+  # Infrastructure first
+  - id: T-1
+    title: Set up   # keep it short
+    depends: []
+    state: Todo
+  - title: Build
+    id: "T-2"
+    depends: [T-1]
+    state: Done
+    started_at: {stamp}
+    completed_at: {stamp}
+    updated_at: {stamp}
+
+epics:
+  - id: E-1
+    title: Public API
+    tasks:
+      - id: E-1-1
+        title: Models
+      - id: E-1-2
+        title: Endpoints
+        depends:
+        - E-1-1
+        state: Todo
+"#
+            )
+        );
+
+        // Reordering tasks needs a full rewrite
+        backlog.tasks.swap(0, 1);
+        assert_eq!(update_yaml(source, &backlog), None);
+    }
+}
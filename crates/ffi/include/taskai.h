@@ -0,0 +1,115 @@
+/* C interface of TaskAI. Generated by cbindgen from crates/ffi/src/lib.rs; do not edit. */
+
+#ifndef TASKAI_H
+#define TASKAI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ Status returned by the functions that can fail.
+ */
+typedef enum TaskaiStatus {
+  /*
+   The call succeeded.
+   */
+  TASKAI_STATUS_OK = 0,
+  /*
+   An argument is null or not valid UTF-8.
+   */
+  TASKAI_STATUS_INVALID_ARGUMENT = 1,
+  /*
+   The backlog has no task with the given ID.
+   */
+  TASKAI_STATUS_NOT_FOUND = 2,
+} TaskaiStatus;
+
+/*
+ A parsed backlog, along with the YAML it was parsed from.
+ */
+typedef struct TaskaiBacklog TaskaiBacklog;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Parses a backlog from YAML.
+
+ Returns a handle to release with `taskai_backlog_free`, or null if `yaml` is not a backlog; the
+ reason is then stored in `*error`, unless `error` is null, to release with `taskai_string_free`.
+
+ # Safety
+
+ `yaml` must be null or a NUL-terminated string, and `error` null or a valid pointer.
+ */
+struct TaskaiBacklog *taskai_parse(const char *yaml, char **error);
+
+/*
+ Validates the YAML of a backlog, as `taskai validate` does, with unknown fields reported as
+ errors when `strict` is true.
+
+ Returns the report as JSON, `{"valid": ..., "errors": [...], "warnings": [...]}` with the line
+ of each problem, or null if `yaml` is null or not UTF-8.
+
+ # Safety
+
+ `yaml` must be null or a NUL-terminated string.
+ */
+char *taskai_validate(const char *yaml, bool strict);
+
+/*
+ Returns the ready task to work on next, as JSON, or null when no task is ready.
+
+ # Safety
+
+ `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+ */
+char *taskai_next(const struct TaskaiBacklog *backlog);
+
+/*
+ Marks the task `task_id` Done.
+
+ # Safety
+
+ `backlog` must be null or a handle returned by `taskai_parse` and not yet freed, and `task_id`
+ null or a NUL-terminated string.
+ */
+enum TaskaiStatus taskai_mark_done(struct TaskaiBacklog *backlog, const char *task_id);
+
+/*
+ Returns the YAML of the backlog, with the changes made through the handle applied to the text
+ it was parsed from, or in canonical form when they cannot be made in place.
+
+ # Safety
+
+ `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+ */
+char *taskai_to_yaml(const struct TaskaiBacklog *backlog);
+
+/*
+ Releases a backlog returned by `taskai_parse`; null is ignored.
+
+ # Safety
+
+ `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+ */
+void taskai_backlog_free(struct TaskaiBacklog *backlog);
+
+/*
+ Releases a string returned by the library; null is ignored.
+
+ # Safety
+
+ `text` must be null or a string returned by the library and not yet freed.
+ */
+void taskai_string_free(char *text);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TASKAI_H */
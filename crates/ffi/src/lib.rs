@@ -0,0 +1,254 @@
+//! C ABI for TaskAI, so that editors and tools in other languages can embed it.
+//!
+//! The interface is declared in `include/taskai.h`, generated by cbindgen and checked against the
+//! exported functions by the tests of the crate.
+//! A backlog is parsed into an opaque `TaskaiBacklog` handle, queried and updated through it, then
+//! written back as YAML, edited in place to keep the comments and formatting of the text it was
+//! parsed from. Strings cross the boundary as NUL-terminated UTF-8; those returned by the library
+//! are owned by the caller and released with `taskai_string_free`, and results other than YAML are
+//! JSON documents.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use taskai_schema::{Backlog, TaskState};
+
+/// A parsed backlog, along with the YAML it was parsed from.
+pub struct TaskaiBacklog {
+    source: String,
+    backlog: Backlog,
+}
+
+/// Status returned by the functions that can fail.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskaiStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// An argument is null or not valid UTF-8.
+    InvalidArgument = 1,
+    /// The backlog has no task with the given ID.
+    NotFound = 2,
+}
+
+/// Reads a string argument, or `None` if it is null or not UTF-8.
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// Hands a string over to the caller; strings holding a NUL byte come back as null.
+fn give_string(text: String) -> *mut c_char {
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Stores `message` in `*error` when the caller asked for errors.
+///
+/// # Safety
+///
+/// `error` must be null or point to writable storage for a pointer.
+unsafe fn set_error(error: *mut *mut c_char, message: String) {
+    if !error.is_null() {
+        *error = give_string(message);
+    }
+}
+
+/// Parses a backlog from YAML.
+///
+/// Returns a handle to release with `taskai_backlog_free`, or null if `yaml` is not a backlog; the
+/// reason is then stored in `*error`, unless `error` is null, to release with `taskai_string_free`.
+///
+/// # Safety
+///
+/// `yaml` must be null or a NUL-terminated string, and `error` null or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_parse(
+    yaml: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut TaskaiBacklog {
+    let Some(source) = read_str(yaml) else {
+        set_error(error, "The YAML is null or not UTF-8.".to_string());
+        return ptr::null_mut();
+    };
+    match serde_yaml::from_str(source) {
+        Ok(backlog) => Box::into_raw(Box::new(TaskaiBacklog {
+            source: source.to_string(),
+            backlog,
+        })),
+        Err(err) => {
+            set_error(error, format!("Error parsing backlog: {}", err));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Validates the YAML of a backlog, as `taskai validate` does, with unknown fields reported as
+/// errors when `strict` is true.
+///
+/// Returns the report as JSON, `{"valid": ..., "errors": [...], "warnings": [...]}` with the line
+/// of each problem, or null if `yaml` is null or not UTF-8.
+///
+/// # Safety
+///
+/// `yaml` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_validate(yaml: *const c_char, strict: bool) -> *mut c_char {
+    let Some(source) = read_str(yaml) else {
+        return ptr::null_mut();
+    };
+    let report = taskai_core::validate_source(source, strict);
+    serde_json::to_string(&report).map_or(ptr::null_mut(), give_string)
+}
+
+/// Returns the ready task to work on next, as JSON, or null when no task is ready.
+///
+/// # Safety
+///
+/// `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_next(backlog: *const TaskaiBacklog) -> *mut c_char {
+    let Some(handle) = backlog.as_ref() else {
+        return ptr::null_mut();
+    };
+    let ready = taskai_core::get_ready_tasks(&handle.backlog);
+    taskai_core::best_ready_task(&handle.backlog, &ready)
+        .and_then(|task| serde_json::to_string(task).ok())
+        .map_or(ptr::null_mut(), give_string)
+}
+
+/// Marks the task `task_id` Done.
+///
+/// # Safety
+///
+/// `backlog` must be null or a handle returned by `taskai_parse` and not yet freed, and `task_id`
+/// null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_mark_done(
+    backlog: *mut TaskaiBacklog,
+    task_id: *const c_char,
+) -> TaskaiStatus {
+    let (Some(handle), Some(task_id)) = (backlog.as_mut(), read_str(task_id)) else {
+        return TaskaiStatus::InvalidArgument;
+    };
+    match handle.backlog.find_task_mut(task_id) {
+        Some(task) => {
+            task.set_state(TaskState::Done, chrono::Utc::now());
+            TaskaiStatus::Ok
+        }
+        None => TaskaiStatus::NotFound,
+    }
+}
+
+/// Returns the YAML of the backlog, with the changes made through the handle applied to the text
+/// it was parsed from, or in canonical form when they cannot be made in place.
+///
+/// # Safety
+///
+/// `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_to_yaml(backlog: *const TaskaiBacklog) -> *mut c_char {
+    let Some(handle) = backlog.as_ref() else {
+        return ptr::null_mut();
+    };
+    let yaml = match taskai_core::update_yaml(&handle.source, &handle.backlog) {
+        Some(yaml) => yaml,
+        None => match taskai_core::format_backlog(&handle.backlog) {
+            Ok(yaml) => yaml,
+            Err(_) => return ptr::null_mut(),
+        },
+    };
+    give_string(yaml)
+}
+
+/// Releases a backlog returned by `taskai_parse`; null is ignored.
+///
+/// # Safety
+///
+/// `backlog` must be null or a handle returned by `taskai_parse` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_backlog_free(backlog: *mut TaskaiBacklog) {
+    if !backlog.is_null() {
+        drop(Box::from_raw(backlog));
+    }
+}
+
+/// Releases a string returned by the library; null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by the library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn taskai_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes back a string returned by the library.
+    unsafe fn take(text: *mut c_char) -> String {
+        assert!(!text.is_null());
+        let owned = CStr::from_ptr(text).to_str().unwrap().to_string();
+        taskai_string_free(text);
+        owned
+    }
+
+    /// Tests a round trip through the C interface: parse, query, mark done and write back.
+    #[test]
+    fn test_c_interface() {
+        let yaml = CString::new(
+            "project: test # demo\ntasks:\n  - id: T-1\n    title: Parser\n  - id: T-2\n    \
+             title: Interpreter\n    depends: [T-1]\n",
+        )
+        .unwrap();
+        unsafe {
+            let report = take(taskai_validate(yaml.as_ptr(), true));
+            assert!(report.contains("\"valid\":true"));
+
+            let backlog = taskai_parse(yaml.as_ptr(), ptr::null_mut());
+            assert!(take(taskai_next(backlog)).contains("\"id\":\"T-1\""));
+            let id = CString::new("T-1").unwrap();
+            assert_eq!(taskai_mark_done(backlog, id.as_ptr()), TaskaiStatus::Ok);
+            assert!(take(taskai_next(backlog)).contains("\"id\":\"T-2\""));
+            let missing = CString::new("T-9").unwrap();
+            let status = taskai_mark_done(backlog, missing.as_ptr());
+            assert_eq!(status, TaskaiStatus::NotFound);
+
+            let written = take(taskai_to_yaml(backlog));
+            assert!(written.starts_with("project: test # demo\n"));
+            assert!(written.contains("state: Done"));
+            taskai_backlog_free(backlog);
+
+            let invalid = CString::new("project: [").unwrap();
+            let mut error = ptr::null_mut();
+            assert!(taskai_parse(invalid.as_ptr(), &mut error).is_null());
+            assert!(take(error).starts_with("Error parsing backlog"));
+        }
+    }
+
+    /// Tests that `include/taskai.h` matches the exported functions. Run the tests with
+    /// `TASKAI_UPDATE_HEADER=1` to regenerate it.
+    #[test]
+    fn test_header_up_to_date() {
+        let crate_dir = env!("CARGO_MANIFEST_DIR");
+        let mut generated = Vec::new();
+        cbindgen::generate(crate_dir).unwrap().write(&mut generated);
+        let path = std::path::Path::new(crate_dir).join("include/taskai.h");
+        if std::env::var_os("TASKAI_UPDATE_HEADER").is_some() {
+            std::fs::write(&path, &generated).unwrap();
+        }
+        let committed = std::fs::read(&path).unwrap();
+        assert!(
+            committed == generated,
+            "include/taskai.h is out of date; run `TASKAI_UPDATE_HEADER=1 cargo test -p taskai-ffi`"
+        );
+    }
+}
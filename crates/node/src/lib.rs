@@ -0,0 +1,165 @@
+//! Node.js bindings for TaskAI, built with napi-rs.
+//!
+//! Backlogs cross the boundary as YAML text, and everything read from them comes back as plain
+//! JavaScript objects shaped like the backlog file, so extensions and web backends share the Rust
+//! schema, validation and readiness rules instead of reimplementing them. Functions that change a
+//! backlog return its new YAML, edited in place to keep comments and formatting. Build with
+//! `npm run build`; functions are exported in camelCase, such as `readyTasks`.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+use taskai_core::{LintOptions, ReadyFilter};
+use taskai_schema::{Backlog, TaskState};
+
+/// Filters of `readyTasks`; tasks must match all of those given.
+#[napi(object)]
+pub struct TaskFilter {
+    /// Only keep tasks of this epic.
+    pub epic: Option<String>,
+    /// Only keep tasks carrying this tag.
+    pub tag: Option<String>,
+    /// Only keep tasks assigned to this person.
+    pub assignee: Option<String>,
+}
+
+fn failure(err: impl ToString) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+fn load(yaml: &str) -> Result<Backlog> {
+    serde_yaml::from_str(yaml).map_err(|e| failure(format!("Error parsing backlog: {}", e)))
+}
+
+fn to_js(value: impl serde::Serialize) -> Result<Value> {
+    serde_json::to_value(value).map_err(failure)
+}
+
+/// Parses a backlog, returning it as an object. Unknown fields are rejected with `strict`.
+#[napi]
+pub fn parse(yaml: String, strict: Option<bool>) -> Result<Value> {
+    let backlog = if strict.unwrap_or(false) {
+        Backlog::from_yaml_strict(&yaml).map_err(failure)?
+    } else {
+        load(&yaml)?
+    };
+    to_js(backlog)
+}
+
+/// Validates a backlog, returning `{ valid, errors, warnings }` with the line of each problem.
+/// Unknown fields are errors with `strict`, warnings otherwise.
+#[napi]
+pub fn validate(yaml: String, strict: Option<bool>) -> Result<Value> {
+    to_js(taskai_core::validate_source(&yaml, strict.unwrap_or(false)))
+}
+
+/// Runs the lint rules over a backlog, returning their diagnostics.
+#[napi]
+pub fn lint(yaml: String) -> Result<Value> {
+    let backlog = load(&yaml)?;
+    to_js(taskai_core::lint_backlog(
+        &backlog,
+        &yaml,
+        &LintOptions::default(),
+    ))
+}
+
+/// Returns the tasks whose dependencies are all Done, in backlog order.
+#[napi]
+pub fn ready_tasks(yaml: String, filter: Option<TaskFilter>) -> Result<Value> {
+    let backlog = load(&yaml)?;
+    let filter = filter.map_or_else(ReadyFilter::default, |f| ReadyFilter {
+        epic: f.epic,
+        tag: f.tag,
+        assignee: f.assignee,
+    });
+    to_js(taskai_core::filter_ready_tasks(&backlog, &filter))
+}
+
+/// Returns the ready task to work on next, by priority, then due date, then the work it unblocks,
+/// or `null` when none is ready.
+#[napi]
+pub fn next_task(yaml: String) -> Result<Value> {
+    let backlog = load(&yaml)?;
+    let ready = taskai_core::get_ready_tasks(&backlog);
+    to_js(taskai_core::best_ready_task(&backlog, &ready))
+}
+
+/// Groups the remaining tasks into waves that can be worked on in parallel, each after the
+/// previous ones.
+#[napi]
+pub fn execution_levels(yaml: String) -> Result<Value> {
+    let backlog = load(&yaml)?;
+    to_js(taskai_core::execution_levels(&backlog).map_err(failure)?)
+}
+
+/// Sets the state of a task (`Todo`, `InProgress`, `Blocked` or `Done`), adding `note` to it if
+/// given, and returns the new YAML of the backlog.
+#[napi]
+pub fn set_state(yaml: String, id: String, state: String, note: Option<String>) -> Result<String> {
+    let state: TaskState = serde_json::from_value(Value::String(state.clone()))
+        .map_err(|_| failure(format!("Invalid state: {}", state)))?;
+    let mut backlog = load(&yaml)?;
+    let now = chrono::Utc::now();
+    let task = backlog
+        .find_task_mut(&id)
+        .ok_or_else(|| failure(format!("Task with ID '{}' not found in the backlog.", id)))?;
+    task.set_state(state, now);
+    if let Some(note) = &note {
+        task.add_note(note, now);
+    }
+    match taskai_core::update_yaml(&yaml, &backlog) {
+        Some(updated) => Ok(updated),
+        None => taskai_core::format_backlog(&backlog).map_err(failure),
+    }
+}
+
+/// Marks a task Done, adding `note` to it if given, and returns the new YAML of the backlog.
+#[napi]
+pub fn mark_done(yaml: String, id: String, note: Option<String>) -> Result<String> {
+    set_state(yaml, id, "Done".to_string(), note)
+}
+
+/// Compares two versions of a backlog, returning the tasks `added`, `removed` and `modified`.
+#[napi]
+pub fn diff(old_yaml: String, new_yaml: String) -> Result<Value> {
+    let old = load(&old_yaml)?;
+    let new = load(&new_yaml)?;
+    to_js(taskai_core::diff_backlogs(&old, &new))
+}
+
+/// Rewrites a backlog in canonical form.
+#[napi]
+pub fn format(yaml: String) -> Result<String> {
+    taskai_core::format_backlog(&load(&yaml)?).map_err(failure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKLOG: &str = "project: test # demo\ntasks:\n  - id: T-1\n    title: Parser\n    \
+                           priority: 2\n  - id: T-2\n    title: Interpreter\n    depends: [T-1]\n";
+
+    /// Tests the functions exported to JavaScript, without a Node.js runtime.
+    #[test]
+    fn test_bindings() {
+        let backlog = parse(BACKLOG.to_string(), Some(true)).unwrap();
+        assert_eq!(backlog["tasks"][1]["depends"][0], "T-1");
+        assert_eq!(validate(BACKLOG.to_string(), None).unwrap()["valid"], true);
+
+        let ready = ready_tasks(BACKLOG.to_string(), None).unwrap();
+        assert_eq!(ready.as_array().unwrap().len(), 1);
+        assert_eq!(next_task(BACKLOG.to_string()).unwrap()["id"], "T-1");
+
+        let done = mark_done(BACKLOG.to_string(), "T-1".into(), Some("Merged".into())).unwrap();
+        assert!(done.starts_with("project: test # demo\n"));
+        assert_eq!(next_task(done.clone()).unwrap()["id"], "T-2");
+        let changes = diff(BACKLOG.to_string(), done).unwrap();
+        assert_eq!(changes["modified"][0]["id"], "T-1");
+
+        assert!(set_state(BACKLOG.to_string(), "T-9".into(), "Done".into(), None).is_err());
+        assert!(set_state(BACKLOG.to_string(), "T-1".into(), "Finished".into(), None).is_err());
+        assert!(parse("project: [".to_string(), None).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    // Use the protoc shipped with the crate unless one is given, so builds need no system install
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+    }
+    tonic_prost_build::configure()
+        .compile_protos(&["proto/taskai/v1/backlog.proto"], &["proto"])
+        .expect("failed to compile the protocol buffers");
+}
@@ -0,0 +1,111 @@
+use crate::v1;
+use chrono::NaiveDate;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+impl From<&TaskState> for v1::TaskState {
+    fn from(state: &TaskState) -> Self {
+        match state {
+            TaskState::Todo => v1::TaskState::Todo,
+            TaskState::InProgress => v1::TaskState::InProgress,
+            TaskState::Blocked => v1::TaskState::Blocked,
+            TaskState::Done => v1::TaskState::Done,
+        }
+    }
+}
+
+/// Returns the state of a task in a message, or `None` when it is unspecified or unknown.
+pub fn task_state(state: i32) -> Option<TaskState> {
+    match v1::TaskState::try_from(state).ok()? {
+        v1::TaskState::Unspecified => None,
+        v1::TaskState::Todo => Some(TaskState::Todo),
+        v1::TaskState::InProgress => Some(TaskState::InProgress),
+        v1::TaskState::Blocked => Some(TaskState::Blocked),
+        v1::TaskState::Done => Some(TaskState::Done),
+    }
+}
+
+impl From<&Task> for v1::Task {
+    fn from(task: &Task) -> Self {
+        v1::Task {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            state: v1::TaskState::from(&task.state).into(),
+            depends: task.depends.clone(),
+            description: task.description.clone(),
+            done_when: task.done_when.clone(),
+            tags: task.tags.clone(),
+            estimate: task.estimate,
+            milestone: task.milestone.clone(),
+            sprint: task.sprint.clone(),
+            due: task.due.map(|d| d.to_string()),
+            priority: task.priority,
+            parent: task.parent.clone(),
+            assignee: task.assignee.clone(),
+            epic: None,
+        }
+    }
+}
+
+/// Converts a task message back to a task. An unspecified state reads as Todo, and a due date
+/// that is not `YYYY-MM-DD` is dropped.
+impl From<v1::Task> for Task {
+    fn from(task: v1::Task) -> Self {
+        Task {
+            state: task_state(task.state).unwrap_or_default(),
+            due: task
+                .due
+                .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+            id: task.id,
+            title: task.title,
+            depends: task.depends,
+            description: task.description,
+            done_when: task.done_when,
+            tags: task.tags,
+            estimate: task.estimate,
+            milestone: task.milestone,
+            sprint: task.sprint,
+            priority: task.priority,
+            parent: task.parent,
+            assignee: task.assignee,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Backlog> for v1::Backlog {
+    fn from(backlog: &Backlog) -> Self {
+        v1::Backlog {
+            project: backlog.project.clone(),
+            tasks: backlog.tasks.iter().map(v1::Task::from).collect(),
+            epics: backlog
+                .epics
+                .iter()
+                .map(|epic| v1::Epic {
+                    id: epic.id.clone(),
+                    title: epic.title.clone(),
+                    tasks: epic.tasks.iter().map(v1::Task::from).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<v1::Backlog> for Backlog {
+    fn from(backlog: v1::Backlog) -> Self {
+        Backlog {
+            project: backlog.project,
+            tasks: backlog.tasks.into_iter().map(Task::from).collect(),
+            epics: backlog
+                .epics
+                .into_iter()
+                .map(|epic| Epic {
+                    id: epic.id,
+                    title: epic.title,
+                    tasks: epic.tasks.into_iter().map(Task::from).collect(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Protocol buffers of a TaskAI backlog and a gRPC service over it, built with tonic.
+//!
+//! The messages and the `BacklogService` are defined in `proto/taskai/v1/backlog.proto`, for
+//! clients in other languages to generate their own stubs. Rust services can call it through
+//! [`BacklogServiceClient`], and serve it over any backlog by implementing [`BacklogSource`] and
+//! wrapping [`BacklogGrpc`] in a [`BacklogServiceServer`], as `taskai serve --grpc-port` does.
+
+mod convert;
+mod server;
+
+/// Messages, client and server generated from `proto/taskai/v1/backlog.proto`.
+pub mod v1 {
+    tonic::include_proto!("taskai.v1");
+}
+
+/// Generated client and server of the service.
+pub use v1::backlog_service_client::BacklogServiceClient;
+pub use v1::backlog_service_server::BacklogServiceServer;
+
+/// Conversion of task states from messages; tasks and backlogs convert with `From`.
+pub use convert::task_state;
+
+/// The service implementation and the backlog it serves.
+pub use server::{BacklogGrpc, BacklogSource};
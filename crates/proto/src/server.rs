@@ -0,0 +1,231 @@
+use crate::convert::task_state;
+use crate::v1;
+use crate::v1::backlog_service_server::BacklogService;
+use std::sync::Arc;
+use taskai_core::ReadyFilter;
+use taskai_schema::{Backlog, Task, TaskState};
+use tonic::{Request, Response, Status};
+
+/// Where the gRPC service reads the backlog and writes its changes, such as the backlog file or a
+/// copy of it kept in memory.
+pub trait BacklogSource: Send + Sync + 'static {
+    /// Returns the current backlog.
+    fn backlog(&self) -> Result<Backlog, String>;
+
+    /// Changes the state of the task `id`, known to be in the backlog, adds `note` to it if given,
+    /// and saves the backlog.
+    fn set_state(&self, id: &str, state: TaskState, note: Option<&str>) -> Result<(), String>;
+}
+
+/// The gRPC service over a [`BacklogSource`], served with
+/// `BacklogServiceServer::new(BacklogGrpc::new(source))`.
+pub struct BacklogGrpc<S> {
+    source: Arc<S>,
+}
+
+impl<S: BacklogSource> BacklogGrpc<S> {
+    pub fn new(source: Arc<S>) -> Self {
+        BacklogGrpc { source }
+    }
+
+    fn backlog(&self) -> Result<Backlog, Status> {
+        self.source.backlog().map_err(Status::unavailable)
+    }
+}
+
+/// Returns the message of a task listed outside of its epic, with the ID of the epic.
+fn task_message(backlog: &Backlog, task: &Task) -> v1::Task {
+    let mut message = v1::Task::from(task);
+    message.epic = backlog
+        .epics
+        .iter()
+        .find(|e| e.tasks.iter().any(|t| t.id == task.id))
+        .map(|e| e.id.clone());
+    message
+}
+
+fn not_found(id: &str) -> Status {
+    Status::not_found(format!("Task with ID '{}' not found in the backlog.", id))
+}
+
+#[tonic::async_trait]
+impl<S: BacklogSource> BacklogService for BacklogGrpc<S> {
+    async fn get_backlog(
+        &self,
+        _request: Request<v1::GetBacklogRequest>,
+    ) -> Result<Response<v1::Backlog>, Status> {
+        Ok(Response::new(v1::Backlog::from(&self.backlog()?)))
+    }
+
+    async fn list_tasks(
+        &self,
+        request: Request<v1::ListTasksRequest>,
+    ) -> Result<Response<v1::TasksResponse>, Status> {
+        let request = request.into_inner();
+        let state = request
+            .state
+            .map(|s| task_state(s).ok_or_else(|| Status::invalid_argument("Unknown task state.")))
+            .transpose()?;
+        let backlog = self.backlog()?;
+        let tasks = backlog
+            .all_tasks()
+            .into_iter()
+            .filter(|t| {
+                state
+                    .as_ref()
+                    .is_none_or(|s| std::mem::discriminant(s) == std::mem::discriminant(&t.state))
+            })
+            .filter(|t| request.tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+            .filter(|t| request.assignee.is_none() || t.assignee == request.assignee)
+            .map(|t| task_message(&backlog, t))
+            .filter(|t| request.epic.is_none() || t.epic == request.epic)
+            .collect();
+        Ok(Response::new(v1::TasksResponse { tasks }))
+    }
+
+    async fn get_task(
+        &self,
+        request: Request<v1::GetTaskRequest>,
+    ) -> Result<Response<v1::Task>, Status> {
+        let id = request.into_inner().id;
+        let backlog = self.backlog()?;
+        let task = backlog.find_task(&id).ok_or_else(|| not_found(&id))?;
+        Ok(Response::new(task_message(&backlog, task)))
+    }
+
+    async fn next_tasks(
+        &self,
+        request: Request<v1::NextTasksRequest>,
+    ) -> Result<Response<v1::TasksResponse>, Status> {
+        let request = request.into_inner();
+        let filter = ReadyFilter {
+            epic: request.epic,
+            tag: request.tag,
+            assignee: request.assignee,
+        };
+        let limit = request.limit.map_or(usize::MAX, |l| l as usize);
+        let backlog = self.backlog()?;
+        let tasks = taskai_core::filter_ready_tasks(&backlog, &filter)
+            .into_iter()
+            .take(limit)
+            .map(|t| task_message(&backlog, t))
+            .collect();
+        Ok(Response::new(v1::TasksResponse { tasks }))
+    }
+
+    async fn set_state(
+        &self,
+        request: Request<v1::SetStateRequest>,
+    ) -> Result<Response<v1::Task>, Status> {
+        let request = request.into_inner();
+        let state = task_state(request.state)
+            .ok_or_else(|| Status::invalid_argument("Pass the new state of the task."))?;
+        if self.backlog()?.find_task(&request.id).is_none() {
+            return Err(not_found(&request.id));
+        }
+        self.source
+            .set_state(&request.id, state, request.note.as_deref())
+            .map_err(Status::internal)?;
+        let backlog = self.backlog()?;
+        let task = backlog
+            .find_task(&request.id)
+            .ok_or_else(|| not_found(&request.id))?;
+        Ok(Response::new(task_message(&backlog, task)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// A backlog kept in memory.
+    struct Memory(Mutex<Backlog>);
+
+    impl BacklogSource for Memory {
+        fn backlog(&self) -> Result<Backlog, String> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        fn set_state(&self, id: &str, state: TaskState, note: Option<&str>) -> Result<(), String> {
+            let mut backlog = self.0.lock().unwrap();
+            let task = backlog.find_task_mut(id).ok_or("missing")?;
+            task.set_state(state, chrono::Utc::now());
+            if let Some(note) = note {
+                task.add_note(note, chrono::Utc::now());
+            }
+            Ok(())
+        }
+    }
+
+    /// Tests listing, readiness and state changes through the service.
+    #[tokio::test]
+    async fn test_service() {
+        let backlog: Backlog = serde_yaml::from_str(
+            r#"
+        project: test
+        tasks:
+          - id: T-1
+            title: Parser
+            tags: [core]
+        epics:
+          - id: E-1
+            title: Runtime
+            tasks:
+              - id: T-2
+                title: Interpreter
+                depends: [T-1]
+                due: 2024-05-01
+        "#,
+        )
+        .unwrap();
+        let service = BacklogGrpc::new(Arc::new(Memory(Mutex::new(backlog))));
+
+        let listed = service
+            .list_tasks(Request::new(v1::ListTasksRequest {
+                epic: Some("E-1".into()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(listed.tasks.len(), 1);
+        assert_eq!(listed.tasks[0].due.as_deref(), Some("2024-05-01"));
+        assert_eq!(listed.tasks[0].epic.as_deref(), Some("E-1"));
+
+        let next = |limit| {
+            service.next_tasks(Request::new(v1::NextTasksRequest {
+                limit: Some(limit),
+                ..Default::default()
+            }))
+        };
+        assert_eq!(next(5).await.unwrap().into_inner().tasks[0].id, "T-1");
+
+        let done = service
+            .set_state(Request::new(v1::SetStateRequest {
+                id: "T-1".into(),
+                state: v1::TaskState::Done.into(),
+                note: Some("Merged".into()),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(done.state(), v1::TaskState::Done);
+        assert_eq!(next(5).await.unwrap().into_inner().tasks[0].id, "T-2");
+
+        let missing = service
+            .get_task(Request::new(v1::GetTaskRequest { id: "T-9".into() }))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+
+        let backlog = service
+            .get_backlog(Request::new(v1::GetBacklogRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        let backlog = Backlog::from(backlog);
+        assert!(matches!(backlog.tasks[0].state, TaskState::Done));
+        assert_eq!(backlog.epics[0].tasks[0].depends, vec!["T-1"]);
+    }
+}
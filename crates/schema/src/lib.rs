@@ -1,24 +1,28 @@
+mod progress;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use progress::{BacklogProgress, EpicProgress, Progress};
+
 /// Represents the state of a task.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub enum TaskState {
     /// The task is yet to be completed.
+    #[default]
     Todo,
+    /// The task is currently being worked on.
+    InProgress,
+    /// The task cannot progress until something else happens.
+    Blocked,
     /// The task has been completed.
     Done,
 }
 
-impl Default for TaskState {
-    fn default() -> Self {
-        TaskState::Todo
-    }
-}
-
 /// Represents a single task in the backlog.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Task {
     /// Unique identifier for the task.
     pub id: String,
@@ -39,6 +43,200 @@ pub struct Task {
     /// List of criteria that define when the task is considered done.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub done_when: Vec<String>,
+    /// Free-form tags used to group and filter tasks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Optional effort estimate (e.g. story points or hours).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    /// Optional milestone the task belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Optional sprint the task is planned in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<String>,
+    /// Optional due date of the task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    /// Optional priority of the task, 1 being the most important.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    /// ID of the task this one is a subtask of, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Person the task is assigned to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Why the task is blocked, while it is Blocked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocker: Option<Blocker>,
+    /// When the task was created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When work on the task first started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the task was marked as done.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// When the task was last modified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// References to items in external trackers (GitHub issues, Jira tickets, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<ExternalRef>,
+    /// Related URLs (specs, designs, discussions).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    /// Git branch the work on the task is done in, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Timestamped notes left on the task, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+    /// Total hours of work logged on the task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_spent: Option<f64>,
+    /// Timed work sessions on the task, oldest first; the last one has no end while its timer runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_log: Vec<TimeEntry>,
+}
+
+impl Task {
+    /// Changes the state of the task and keeps its timestamps consistent.
+    ///
+    /// Sets `started_at` the first time the task leaves Todo, sets `completed_at` when it becomes Done
+    /// and clears it when it leaves Done, and clears the blocker when it leaves Blocked. A running
+    /// timer is stopped when the task leaves InProgress or becomes Done. `updated_at` is always set
+    /// to `now`.
+    pub fn set_state(&mut self, state: TaskState, now: DateTime<Utc>) {
+        if !matches!(state, TaskState::Blocked) {
+            self.blocker = None;
+        }
+        let leaves_progress =
+            matches!(self.state, TaskState::InProgress) && !matches!(state, TaskState::InProgress);
+        if leaves_progress || matches!(state, TaskState::Done) {
+            self.stop_timer(now);
+        }
+        match state {
+            TaskState::Todo => {
+                self.completed_at = None;
+            }
+            TaskState::InProgress | TaskState::Blocked => {
+                self.started_at.get_or_insert(now);
+                self.completed_at = None;
+            }
+            TaskState::Done => {
+                self.started_at.get_or_insert(now);
+                if !matches!(self.state, TaskState::Done) || self.completed_at.is_none() {
+                    self.completed_at = Some(now);
+                }
+            }
+        }
+
+        self.state = state;
+        self.updated_at = Some(now);
+    }
+
+    /// Appends a note written at `now` to the task. `updated_at` is set to `now`.
+    pub fn add_note(&mut self, text: &str, now: DateTime<Utc>) {
+        self.notes.push(Note {
+            at: now,
+            text: text.to_string(),
+        });
+        self.updated_at = Some(now);
+    }
+
+    /// Returns when the running timer of the task was started, if one is running.
+    pub fn running_timer(&self) -> Option<DateTime<Utc>> {
+        self.time_log
+            .last()
+            .filter(|entry| entry.end.is_none())
+            .map(|entry| entry.start)
+    }
+
+    /// Starts a timer on the task. Returns false, changing nothing, if one is already running.
+    pub fn start_timer(&mut self, now: DateTime<Utc>) -> bool {
+        if self.running_timer().is_some() {
+            return false;
+        }
+        self.time_log.push(TimeEntry {
+            start: now,
+            end: None,
+        });
+        self.updated_at = Some(now);
+        true
+    }
+
+    /// Stops the running timer of the task and adds the session to `time_spent`.
+    ///
+    /// Returns the length of the session in hours, or `None` if no timer was running.
+    pub fn stop_timer(&mut self, now: DateTime<Utc>) -> Option<f64> {
+        let entry = self.time_log.last_mut().filter(|e| e.end.is_none())?;
+        entry.end = Some(now);
+        let hours = entry.hours(now);
+        self.time_spent = Some(self.time_spent.unwrap_or(0.0) + hours);
+        self.updated_at = Some(now);
+        Some(hours)
+    }
+}
+
+/// A timed work session on a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TimeEntry {
+    /// When the timer was started.
+    pub start: DateTime<Utc>,
+    /// When the timer was stopped; unset while it runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Returns the length of the session in hours, counting a running session up to `now`.
+    pub fn hours(&self, now: DateTime<Utc>) -> f64 {
+        let end = self.end.unwrap_or(now);
+        (end - self.start).num_seconds().max(0) as f64 / 3600.0
+    }
+}
+
+/// What prevents a Blocked task from progressing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Blocker {
+    /// Why the task is blocked.
+    pub reason: String,
+    /// Optional URL or reference (e.g. `github:owner/repo#12`) of the item blocking the task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// A reference to an item tracked in an external system.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExternalRef {
+    /// Name of the external system (e.g. `github`, `jira`).
+    pub system: String,
+    /// Identifier of the item in that system (e.g. `owner/repo#12`, `PROJ-42`).
+    pub id: String,
+    /// Optional URL of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// When the task and the item were last synchronized, if they ever were.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+impl std::fmt::Display for ExternalRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.system, self.id)
+    }
+}
+
+/// A timestamped note left on a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Note {
+    /// When the note was written.
+    pub at: DateTime<Utc>,
+    /// Content of the note.
+    pub text: String,
 }
 
 /// Represents the deliverable(s) for a task.
@@ -51,8 +249,18 @@ pub enum DeliverableSpec {
     Multiple(Vec<String>),
 }
 
+impl DeliverableSpec {
+    /// Returns the deliverable paths as a flat list, regardless of the variant.
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            DeliverableSpec::Single(path) => vec![path.as_str()],
+            DeliverableSpec::Multiple(paths) => paths.iter().map(|p| p.as_str()).collect(),
+        }
+    }
+}
+
 /// Represents an epic, which is a collection of related tasks.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Epic {
     /// Unique identifier for the epic.
     pub id: String,
@@ -61,10 +269,13 @@ pub struct Epic {
     /// List of tasks associated with the epic.
     #[serde(default)]
     pub tasks: Vec<Task>,
+    /// References to the epic in external trackers (Jira epics, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<ExternalRef>,
 }
 
 /// Represents the entire project backlog, including tasks, epics, and metadata.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Backlog {
     /// Name of the project.
     pub project: String,
@@ -83,9 +294,44 @@ pub struct Backlog {
     /// List of standalone tasks in the backlog.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tasks: Vec<Task>,
+    /// Completed tasks moved out of the active backlog, kept for history and so that dependencies
+    /// on them still resolve.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archive: Vec<Task>,
 }
 
 impl Backlog {
+    /// Parses a backlog from YAML, rejecting unknown or misspelled fields.
+    ///
+    /// The default `serde_yaml` parsing silently drops unknown fields (e.g. `depands:`), which is
+    /// convenient for LLM output but hides typos in hand-edited files. Returns an error listing
+    /// the path of every unknown field.
+    pub fn from_yaml_strict(yaml: &str) -> Result<Backlog, String> {
+        let (backlog, unknown_fields) =
+            Self::from_yaml_with_unknown_fields(yaml).map_err(|e| e.to_string())?;
+
+        if !unknown_fields.is_empty() {
+            return Err(format!("Unknown field(s): {}", unknown_fields.join(", ")));
+        }
+
+        Ok(backlog)
+    }
+
+    /// Parses a backlog from YAML leniently, also returning the path of every ignored unknown field
+    /// (e.g. `tasks.0.depands`).
+    pub fn from_yaml_with_unknown_fields(
+        yaml: &str,
+    ) -> Result<(Backlog, Vec<String>), serde_yaml::Error> {
+        let mut unknown_fields = Vec::new();
+        let deserializer = serde_yaml::Deserializer::from_str(yaml);
+
+        let backlog: Backlog = serde_ignored::deserialize(deserializer, |path| {
+            unknown_fields.push(path.to_string());
+        })?;
+
+        Ok((backlog, unknown_fields))
+    }
+
     /// Validates the backlog for missing dependencies and cycles.
     ///
     /// Returns `Ok(())` if the backlog is valid, or an error message otherwise.
@@ -98,17 +344,71 @@ impl Backlog {
                     return Err(format!("Task {} depends on non-existent task {}", task.id, dep_id));
                 }
             }
+            if let Some(parent) = &task.parent {
+                if parent == &task.id || !task_ids.contains(parent) {
+                    return Err(format!("Task {} has invalid parent task {}", task.id, parent));
+                }
+            }
         }
         
         if let Err(cycle) = self.check_cycles() {
-            return Err(format!("Dependency cycle detected: {}", cycle));
+            return Err(format!("Dependency cycle detected: {}", cycle.join(" -> ")));
         }
         
         Ok(())
     }
     
+    /// Tells whether a task with the given ID exists, either in the active backlog or in the archive.
+    pub fn has_task(&self, id: &str) -> bool {
+        self.find_task(id).is_some() || self.archive.iter().any(|t| t.id == id)
+    }
+
+    /// Returns the task with the given ID, whether standalone or within an epic.
+    pub fn find_task(&self, id: &str) -> Option<&Task> {
+        self.all_tasks().into_iter().find(|t| t.id == id)
+    }
+
+    /// Returns a mutable reference to the task with the given ID, whether standalone or within an epic.
+    pub fn find_task_mut(&mut self, id: &str) -> Option<&mut Task> {
+        self.tasks
+            .iter_mut()
+            .chain(self.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+            .find(|t| t.id == id)
+    }
+
+    /// Renames a task and updates every dependency and parent reference to it, archived tasks
+    /// included.
+    ///
+    /// Returns false if no task has the old ID.
+    pub fn rename_task(&mut self, old_id: &str, new_id: &str) -> bool {
+        if !self.has_task(old_id) {
+            return false;
+        }
+
+        for task in self
+            .tasks
+            .iter_mut()
+            .chain(self.epics.iter_mut().flat_map(|e| e.tasks.iter_mut()))
+            .chain(self.archive.iter_mut())
+        {
+            if task.id == old_id {
+                task.id = new_id.to_string();
+            }
+            for dep in &mut task.depends {
+                if dep == old_id {
+                    *dep = new_id.to_string();
+                }
+            }
+            if task.parent.as_deref() == Some(old_id) {
+                task.parent = Some(new_id.to_string());
+            }
+        }
+
+        true
+    }
+
     /// Returns a vector of references to all tasks, including those in epics.
-    fn all_tasks(&self) -> Vec<&Task> {
+    pub fn all_tasks(&self) -> Vec<&Task> {
         let mut all_tasks = Vec::new();
         
         for task in &self.tasks {
@@ -124,15 +424,24 @@ impl Backlog {
         all_tasks
     }
     
-    /// Returns a vector of all task IDs in the backlog.
+    /// Returns the first dependency cycle found, as a list of task IDs, if any.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        self.check_cycles().err()
+    }
+
+    /// Returns a vector of all task IDs in the backlog, including archived tasks.
     fn all_task_ids(&self) -> Vec<String> {
-        self.all_tasks().iter().map(|t| t.id.clone()).collect()
+        self.all_tasks()
+            .into_iter()
+            .chain(&self.archive)
+            .map(|t| t.id.clone())
+            .collect()
     }
     
     /// Checks for cycles in the task dependency graph.
     ///
-    /// Returns `Ok(())` if no cycles are found, or an error message with the cycle path.
-    fn check_cycles(&self) -> Result<(), String> {
+    /// Returns `Ok(())` if no cycles are found, or the task IDs along the cycle path.
+    fn check_cycles(&self) -> Result<(), Vec<String>> {
         let all_tasks = self.all_tasks();
         let task_map: HashMap<String, &Task> = all_tasks.into_iter()
             .map(|t| (t.id.clone(), t))
@@ -143,7 +452,7 @@ impl Backlog {
             let mut path = Vec::new();
             
             if self.has_cycle(task, &task_map, &mut visited, &mut path) {
-                return Err(path.join(" -> "));
+                return Err(path);
             }
         }
         
@@ -191,7 +500,6 @@ impl Backlog {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_yaml;
     
     /// Tests serialization and deserialization of the Backlog struct.
     #[test]
@@ -216,6 +524,77 @@ mod tests {
         assert_eq!(backlog.tasks[0].id, deserialized.tasks[0].id);
     }
     
+    /// Tests that strict parsing rejects misspelled fields that lenient parsing ignores.
+    #[test]
+    fn strict_unknown_fields() {
+        let yaml = r#"
+        project: test-project
+        tasks:
+          - id: T-1
+            title: "Test task"
+            depands: ["T-0"]
+        "#;
+
+        assert!(serde_yaml::from_str::<Backlog>(yaml).is_ok());
+
+        let err = Backlog::from_yaml_strict(yaml).unwrap_err();
+        assert!(err.contains("tasks.0.depands"));
+    }
+
+    /// Tests that state changes keep the task timestamps consistent.
+    #[test]
+    fn set_state_timestamps() {
+        let start: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        let end: DateTime<Utc> = "2024-01-02T00:00:00Z".parse().unwrap();
+        let mut task = Task::default();
+
+        task.set_state(TaskState::InProgress, start);
+        task.set_state(TaskState::Done, end);
+        assert_eq!(task.started_at, Some(start));
+        assert_eq!(task.completed_at, Some(end));
+
+        task.set_state(TaskState::Todo, end);
+        assert_eq!(task.completed_at, None);
+        assert_eq!(task.started_at, Some(start));
+        assert_eq!(task.updated_at, Some(end));
+    }
+
+    /// Tests that stopping a timer logs the session and accumulates the time spent.
+    #[test]
+    fn timer_accumulates_time_spent() {
+        let start: DateTime<Utc> = "2024-01-01T09:00:00Z".parse().unwrap();
+        let mut task = Task::default();
+
+        assert!(task.start_timer(start));
+        assert!(!task.start_timer(start + chrono::Duration::minutes(5)));
+        assert_eq!(task.running_timer(), Some(start));
+        assert_eq!(
+            task.stop_timer(start + chrono::Duration::minutes(90)),
+            Some(1.5)
+        );
+        assert_eq!(task.stop_timer(start), None);
+
+        task.start_timer(start + chrono::Duration::hours(3));
+        task.stop_timer(start + chrono::Duration::hours(4));
+        assert_eq!(task.time_spent, Some(2.5));
+        assert_eq!(task.time_log.len(), 2);
+        assert_eq!(task.running_timer(), None);
+    }
+
+    /// Tests that finishing a task stops its running timer.
+    #[test]
+    fn done_stops_timer() {
+        let start: DateTime<Utc> = "2024-01-01T09:00:00Z".parse().unwrap();
+        let mut task = Task::default();
+        task.set_state(TaskState::InProgress, start);
+        task.start_timer(start);
+
+        task.set_state(TaskState::Done, start + chrono::Duration::hours(2));
+        assert_eq!(task.running_timer(), None);
+        assert_eq!(task.time_spent, Some(2.0));
+        assert_eq!(task.stop_timer(start + chrono::Duration::hours(5)), None);
+    }
+
     /// Tests roundtrip serialization and deserialization of task states.
     #[test]
     fn state_roundtrip() {
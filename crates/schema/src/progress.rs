@@ -0,0 +1,179 @@
+use crate::{Backlog, Task, TaskState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Task counts and estimate totals for a group of tasks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Progress {
+    /// Total number of tasks.
+    pub total: usize,
+    /// Number of Todo tasks.
+    pub todo: usize,
+    /// Number of InProgress tasks.
+    pub in_progress: usize,
+    /// Number of Blocked tasks.
+    pub blocked: usize,
+    /// Number of Done tasks.
+    pub done: usize,
+    /// Sum of the estimates of all tasks.
+    pub estimate_total: f64,
+    /// Sum of the estimates of Done tasks.
+    pub estimate_done: f64,
+}
+
+impl Progress {
+    /// Builds the progress of the given tasks.
+    pub fn from_tasks<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Self {
+        let mut progress = Progress::default();
+        for task in tasks {
+            progress.add(task);
+        }
+        progress
+    }
+
+    /// Accounts for a single task.
+    pub fn add(&mut self, task: &Task) {
+        self.total += 1;
+        let estimate = task.estimate.unwrap_or(0.0);
+        self.estimate_total += estimate;
+
+        match task.state {
+            TaskState::Todo => self.todo += 1,
+            TaskState::InProgress => self.in_progress += 1,
+            TaskState::Blocked => self.blocked += 1,
+            TaskState::Done => {
+                self.done += 1;
+                self.estimate_done += estimate;
+            }
+        }
+    }
+
+    /// Percentage of tasks that are Done.
+    pub fn percent_done(&self) -> f64 {
+        percent(self.done as f64, self.total as f64)
+    }
+
+    /// Percentage of tasks that are InProgress.
+    pub fn percent_in_progress(&self) -> f64 {
+        percent(self.in_progress as f64, self.total as f64)
+    }
+
+    /// Percentage of tasks that are Blocked.
+    pub fn percent_blocked(&self) -> f64 {
+        percent(self.blocked as f64, self.total as f64)
+    }
+
+    /// Percentage of tasks that are Todo.
+    pub fn percent_todo(&self) -> f64 {
+        percent(self.todo as f64, self.total as f64)
+    }
+
+    /// Percentage of the total estimate that is Done.
+    ///
+    /// Tasks without an estimate do not weigh in. Returns 0 when no task is estimated.
+    pub fn percent_done_weighted(&self) -> f64 {
+        percent(self.estimate_done, self.estimate_total)
+    }
+}
+
+fn percent(part: f64, total: f64) -> f64 {
+    if total <= 0.0 {
+        0.0
+    } else {
+        part * 100.0 / total
+    }
+}
+
+/// Progress of a single epic.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EpicProgress {
+    /// ID of the epic.
+    pub id: String,
+    /// Title of the epic.
+    pub title: String,
+    /// Progress of the tasks in the epic.
+    pub progress: Progress,
+}
+
+/// Progress of a whole backlog, broken down by epic and by tag.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BacklogProgress {
+    /// Progress over all tasks.
+    pub overall: Progress,
+    /// Progress of each epic, in backlog order.
+    pub by_epic: Vec<EpicProgress>,
+    /// Progress of tasks carrying each tag, sorted by tag.
+    pub by_tag: BTreeMap<String, Progress>,
+}
+
+impl Backlog {
+    /// Computes progress metrics for the backlog.
+    pub fn progress(&self) -> BacklogProgress {
+        let all_tasks = self.all_tasks();
+
+        let by_epic = self
+            .epics
+            .iter()
+            .map(|epic| EpicProgress {
+                id: epic.id.clone(),
+                title: epic.title.clone(),
+                progress: Progress::from_tasks(&epic.tasks),
+            })
+            .collect();
+
+        let mut by_tag: BTreeMap<String, Progress> = BTreeMap::new();
+        for task in &all_tasks {
+            for tag in &task.tags {
+                by_tag.entry(tag.clone()).or_default().add(task);
+            }
+        }
+
+        BacklogProgress {
+            overall: Progress::from_tasks(all_tasks),
+            by_epic,
+            by_tag,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_counts() {
+        let yaml = r#"
+        project: test-project
+        epics:
+          - id: E-1
+            title: "Epic"
+            tasks:
+              - id: T-1
+                title: "Task 1"
+                state: Done
+                estimate: 3
+                tags: [backend]
+              - id: T-2
+                title: "Task 2"
+                state: InProgress
+                estimate: 1
+                tags: [backend, api]
+        tasks:
+          - id: T-3
+            title: "Task 3"
+        "#;
+
+        let backlog: Backlog = serde_yaml::from_str(yaml).unwrap();
+        let progress = backlog.progress();
+
+        assert_eq!(progress.overall.total, 3);
+        assert_eq!(progress.overall.done, 1);
+        assert_eq!(progress.overall.in_progress, 1);
+        assert_eq!(progress.overall.todo, 1);
+        assert_eq!(progress.overall.percent_done_weighted(), 75.0);
+        assert_eq!(progress.by_epic[0].progress.total, 2);
+        assert_eq!(progress.by_tag["backend"].total, 2);
+        assert_eq!(progress.by_tag["api"].in_progress, 1);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::provider::{IssueFields, TrackerIssue, TrackerProvider};
+use async_trait::async_trait;
+use serde_json::json;
+use taskai_core::{ClickUpClient, ClickUpStatus, ClickUpTask};
+use taskai_schema::{Epic, ExternalRef, Task, TaskState};
+
+/// The tasks of a ClickUp list, as a tracker. Issue IDs are ClickUp task IDs, the parent of a task
+/// is the ClickUp task of its epic, and states map to the statuses of the list.
+pub struct ClickUpProvider {
+    client: ClickUpClient,
+    list: String,
+    statuses: Vec<ClickUpStatus>,
+}
+
+impl ClickUpProvider {
+    /// Reads the statuses of the list `list` and creates its provider.
+    pub async fn connect(client: ClickUpClient, list: &str) -> Result<Self, String> {
+        let statuses = client.get_list(list).await?.statuses;
+        Ok(ClickUpProvider {
+            client,
+            list: list.to_string(),
+            statuses,
+        })
+    }
+
+    /// Returns the status of the list matching `state`.
+    fn status(&self, state: &TaskState) -> Result<&str, String> {
+        taskai_core::clickup_status_for(state, &self.statuses)
+            .ok_or_else(|| format!("No status of list {} matches {:?}.", self.list, state))
+    }
+}
+
+fn tracker_issue(task: ClickUpTask) -> TrackerIssue {
+    TrackerIssue {
+        state: taskai_core::clickup_state(&task.status),
+        updated_at: task.updated_at().unwrap_or_default(),
+        id: task.id,
+        title: task.name,
+        url: task.url,
+    }
+}
+
+#[async_trait]
+impl TrackerProvider for ClickUpProvider {
+    fn system(&self) -> &str {
+        "clickup"
+    }
+
+    fn target(&self) -> &str {
+        &self.list
+    }
+
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+        (external_ref.system == "clickup").then(|| external_ref.id.clone())
+    }
+
+    fn map_task(&self, task: &Task, epic: Option<&Epic>) -> IssueFields {
+        let issue = taskai_core::issue_for_task(task, None);
+        IssueFields {
+            title: issue.title,
+            body: issue.body,
+            labels: task.tags.clone(),
+            state: task.state.clone(),
+            parent: epic
+                .and_then(|e| taskai_core::clickup_task_id(&e.external_refs))
+                .map(str::to_string),
+        }
+    }
+
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+        let tasks = self.client.list_tasks(&self.list).await?;
+        Ok(tasks.into_iter().map(tracker_issue).collect())
+    }
+
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let mut data = json!({
+            "name": fields.title,
+            "markdown_description": fields.body,
+            "tags": fields.labels,
+            "status": self.status(&fields.state)?,
+        });
+        if let Some(parent) = &fields.parent {
+            data["parent"] = json!(parent);
+        }
+        let task = self.client.create_task(&self.list, &data).await?;
+        Ok(tracker_issue(task))
+    }
+
+    /// Updates the name and description of the ClickUp task; tags are left as they are.
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let data = json!({
+            "name": fields.title,
+            "markdown_description": fields.body,
+        });
+        let task = self.client.update_task(id, &data).await?;
+        Ok(tracker_issue(task))
+    }
+
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+        let task = self.client.set_task_status(id, self.status(state)?).await?;
+        Ok(tracker_issue(task))
+    }
+}
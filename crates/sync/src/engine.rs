@@ -0,0 +1,409 @@
+use crate::provider::{TrackerIssue, TrackerProvider};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskai_schema::{Backlog, Epic, Task, TaskState};
+
+/// Side whose state wins when a task and its issue both changed since they were last synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncSide {
+    Backlog,
+    /// The issue tracker.
+    Tracker,
+}
+
+/// What synchronizing a task with its issue takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    /// Task and issue agree.
+    InSync,
+    /// The task is not tracked yet: an issue must be created for it.
+    Create,
+    /// The task changed: the issue must be closed, or reopened.
+    Push { close: bool },
+    /// The issue changed: the task must become Done, or be reopened.
+    Pull { done: bool },
+    /// Both sides changed since the last sync, or the task was never synced; nothing is changed.
+    Conflict,
+    /// The issue was not found in the tracker.
+    MissingIssue,
+}
+
+/// How far a sync goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Side winning when a task and its issue both changed; such pairs are left alone when `None`.
+    pub prefer: Option<SyncSide>,
+    /// Create an issue for each task not tracked yet.
+    pub create: bool,
+    /// Push the title, description and labels of the tasks changed since the last sync.
+    pub update: bool,
+}
+
+/// A task, the issue tracking it, and what synchronizing them takes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncItem {
+    pub task_id: String,
+    /// ID of the issue; `None` for an issue still to create.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    pub action: SyncAction,
+    /// Whether the fields of the issue are updated after the task.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub update: bool,
+}
+
+/// What applying a sync changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncOutcome {
+    /// Whether the backlog changed and needs saving.
+    pub changed: bool,
+    /// The error that stopped the sync, if any; the items before it were applied.
+    pub failure: Option<String>,
+}
+
+/// Works out how to reconcile each task of the backlog with the issue tracking it among `issues`,
+/// listed from `provider`: Done tasks go with closed issues, and other tasks with open ones.
+///
+/// When they disagree, the side changed since the `synced_at` time of the reference wins: the issue
+/// is closed or reopened after the task, or the task after the issue. When both changed, or the
+/// reference was never synced, the pair is a conflict, settled by `options.prefer` when given.
+/// Tasks without an issue are planned for creation when `options.create` is set.
+pub fn plan_sync(
+    provider: &dyn TrackerProvider,
+    backlog: &Backlog,
+    issues: &[TrackerIssue],
+    options: &SyncOptions,
+) -> Vec<SyncItem> {
+    let mut items = Vec::new();
+    for task in backlog.all_tasks() {
+        let Some(id) = task.external_refs.iter().find_map(|r| provider.issue_id(r)) else {
+            if options.create {
+                items.push(SyncItem {
+                    task_id: task.id.clone(),
+                    issue: None,
+                    action: SyncAction::Create,
+                    update: false,
+                });
+            }
+            continue;
+        };
+        let (action, update) = match issues.iter().find(|i| i.id == id) {
+            None => (SyncAction::MissingIssue, false),
+            Some(issue) => {
+                let synced_at = task
+                    .external_refs
+                    .iter()
+                    .filter(|r| provider.issue_id(r).as_deref() == Some(id.as_str()))
+                    .find_map(|r| r.synced_at);
+                let action = sync_action(task, synced_at, issue, options.prefer);
+                let edited = synced_at.is_some_and(|at| task.updated_at.is_some_and(|u| u > at));
+                let pushed = matches!(action, SyncAction::InSync | SyncAction::Push { .. });
+                (action, options.update && edited && pushed)
+            }
+        };
+        items.push(SyncItem {
+            task_id: task.id.clone(),
+            issue: Some(id),
+            action,
+            update,
+        });
+    }
+    items
+}
+
+/// Carries out the `items` planned by `plan_sync`: creates, closes, reopens and updates issues
+/// through `provider`, updates the state of pulled tasks, and records in the references of the
+/// tasks when they were last in sync. Created issues are filled into their items.
+///
+/// Stops at the first error, reported in the outcome along with whether the backlog changed.
+pub async fn apply_sync(
+    provider: &dyn TrackerProvider,
+    backlog: &mut Backlog,
+    issues: &[TrackerIssue],
+    items: &mut [SyncItem],
+    now: DateTime<Utc>,
+) -> SyncOutcome {
+    let mut outcome = SyncOutcome::default();
+    for item in items.iter_mut() {
+        let Some(fields) = find_task(backlog, &item.task_id).map(|(t, e)| provider.map_task(t, e))
+        else {
+            continue;
+        };
+        let found = item
+            .issue
+            .as_ref()
+            .and_then(|id| issues.iter().find(|i| &i.id == id));
+        let result = match (item.action, found) {
+            (SyncAction::Create, _) => provider
+                .create_issue(&fields)
+                .await
+                .map_err(|err| format!("Error creating the issue of {}: {}", item.task_id, err)),
+            (SyncAction::InSync, Some(issue)) => Ok(issue.clone()),
+            (SyncAction::Push { .. }, Some(issue)) => provider
+                .close_issue(&issue.id, &fields.state)
+                .await
+                .map_err(|err| format!("Error updating the issue of {}: {}", item.task_id, err)),
+            (SyncAction::Pull { .. }, Some(issue)) => {
+                if let Some(task) = backlog.find_task_mut(&item.task_id) {
+                    task.set_state(issue.state.clone(), now);
+                    outcome.changed = true;
+                }
+                Ok(issue.clone())
+            }
+            _ => continue,
+        };
+        let result = match result {
+            Ok(issue) if item.update => provider
+                .update_issue(&issue.id, &fields)
+                .await
+                .map_err(|err| format!("Error updating the issue of {}: {}", item.task_id, err)),
+            result => result,
+        };
+        let issue = match result {
+            Ok(issue) => issue,
+            Err(err) => {
+                outcome.failure = Some(err);
+                break;
+            }
+        };
+
+        // An unchanged pair keeps the time of its last change, so that it stays in sync
+        let synced_at = if item.action == SyncAction::InSync && !item.update {
+            issue.updated_at
+        } else {
+            issue.updated_at.max(now)
+        };
+        let Some(task) = backlog.find_task_mut(&item.task_id) else {
+            continue;
+        };
+        let synced_at = task.updated_at.map_or(synced_at, |at| at.max(synced_at));
+        if item.action == SyncAction::Create {
+            task.external_refs
+                .push(provider.issue_ref(&issue, synced_at));
+            item.issue = Some(issue.id);
+            outcome.changed = true;
+        } else {
+            outcome.changed |= mark_synced(provider, task, &issue.id, synced_at);
+        }
+    }
+    outcome
+}
+
+/// Works out how to reconcile the state of a task with the state of the issue tracking it, last
+/// synced at `synced_at`.
+fn sync_action(
+    task: &Task,
+    synced_at: Option<DateTime<Utc>>,
+    issue: &TrackerIssue,
+    prefer: Option<SyncSide>,
+) -> SyncAction {
+    let done = matches!(task.state, TaskState::Done);
+    if done == issue.is_closed() {
+        return SyncAction::InSync;
+    }
+    let task_changed =
+        synced_at.is_none_or(|at| task.updated_at.is_some_and(|updated| updated > at));
+    let issue_changed = synced_at.is_none_or(|at| issue.updated_at > at);
+    let winner = match (task_changed, issue_changed) {
+        (true, false) => Some(SyncSide::Backlog),
+        (false, true) => Some(SyncSide::Tracker),
+        _ => prefer,
+    };
+    match winner {
+        Some(SyncSide::Backlog) => SyncAction::Push { close: done },
+        Some(SyncSide::Tracker) => SyncAction::Pull {
+            done: issue.is_closed(),
+        },
+        None => SyncAction::Conflict,
+    }
+}
+
+/// Records that a task and the issue `id` were in sync at `at`. Returns false if that was already
+/// recorded.
+fn mark_synced(
+    provider: &dyn TrackerProvider,
+    task: &mut Task,
+    id: &str,
+    at: DateTime<Utc>,
+) -> bool {
+    let mut changed = false;
+    for external_ref in &mut task.external_refs {
+        if provider.issue_id(external_ref).as_deref() == Some(id)
+            && external_ref.synced_at != Some(at)
+        {
+            external_ref.synced_at = Some(at);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Finds a task and the epic it belongs to.
+fn find_task<'a>(backlog: &'a Backlog, id: &str) -> Option<(&'a Task, Option<&'a Epic>)> {
+    if let Some(task) = backlog.tasks.iter().find(|t| t.id == id) {
+        return Some((task, None));
+    }
+    backlog.epics.iter().find_map(|epic| {
+        let task = epic.tasks.iter().find(|t| t.id == id)?;
+        Some((task, Some(epic)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::IssueFields;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use taskai_schema::ExternalRef;
+
+    /// A tracker keeping its issues in memory.
+    struct MemoryTracker {
+        issues: Mutex<Vec<TrackerIssue>>,
+    }
+
+    #[async_trait]
+    impl TrackerProvider for MemoryTracker {
+        fn system(&self) -> &str {
+            "memory"
+        }
+
+        fn target(&self) -> &str {
+            "board"
+        }
+
+        fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+            (external_ref.system == "memory").then(|| external_ref.id.clone())
+        }
+
+        async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+            Ok(self.issues.lock().unwrap().clone())
+        }
+
+        async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+            let mut issues = self.issues.lock().unwrap();
+            let issue = TrackerIssue {
+                id: format!("#{}", issues.len() + 1),
+                title: fields.title.clone(),
+                url: None,
+                state: fields.state.clone(),
+                updated_at: "2024-01-05T00:00:00Z".parse().unwrap(),
+            };
+            issues.push(issue.clone());
+            Ok(issue)
+        }
+
+        async fn update_issue(
+            &self,
+            id: &str,
+            fields: &IssueFields,
+        ) -> Result<TrackerIssue, String> {
+            let mut issues = self.issues.lock().unwrap();
+            let issue = issues.iter_mut().find(|i| i.id == id).ok_or("not found")?;
+            issue.title = fields.title.clone();
+            Ok(issue.clone())
+        }
+
+        async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+            let mut issues = self.issues.lock().unwrap();
+            let issue = issues.iter_mut().find(|i| i.id == id).ok_or("not found")?;
+            issue.state = state.clone();
+            Ok(issue.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_and_apply_sync() {
+        let mut backlog: Backlog = serde_yaml::from_str(
+            r##"
+        project: app
+        tasks:
+          - id: T-1
+            title: Done here
+            state: Done
+            updated_at: 2024-01-03T00:00:00Z
+            external_refs:
+              - {system: memory, id: "#1", synced_at: 2024-01-02T00:00:00Z}
+          - id: T-2
+            title: Closed there
+            updated_at: 2024-01-01T00:00:00Z
+            external_refs:
+              - {system: memory, id: "#2", synced_at: 2024-01-02T00:00:00Z}
+          - id: T-3
+            title: Changed on both sides
+            state: Done
+            updated_at: 2024-01-03T00:00:00Z
+            external_refs:
+              - {system: memory, id: "#3", synced_at: 2024-01-02T00:00:00Z}
+          - id: T-4
+            title: Deleted issue
+            external_refs:
+              - {system: memory, id: "#9"}
+          - id: T-5
+            title: Not tracked
+        "##,
+        )
+        .unwrap();
+        let issue = |id: &str, state: TaskState, updated_at: &str| TrackerIssue {
+            id: id.to_string(),
+            title: String::new(),
+            url: None,
+            state,
+            updated_at: updated_at.parse().unwrap(),
+        };
+        let tracker = MemoryTracker {
+            issues: Mutex::new(vec![
+                issue("#1", TaskState::Todo, "2024-01-01T00:00:00Z"),
+                issue("#2", TaskState::Done, "2024-01-03T00:00:00Z"),
+                issue("#3", TaskState::Todo, "2024-01-03T00:00:00Z"),
+            ]),
+        };
+        let issues = tracker.list_issues().await.unwrap();
+        let actions = |options: SyncOptions| -> Vec<SyncAction> {
+            plan_sync(&tracker, &backlog, &issues, &options)
+                .into_iter()
+                .map(|item| item.action)
+                .collect()
+        };
+        assert_eq!(
+            actions(SyncOptions::default()),
+            [
+                SyncAction::Push { close: true },
+                SyncAction::Pull { done: true },
+                SyncAction::Conflict,
+                SyncAction::MissingIssue,
+            ]
+        );
+        let options = SyncOptions {
+            prefer: Some(SyncSide::Tracker),
+            create: true,
+            update: false,
+        };
+        assert_eq!(actions(options)[2], SyncAction::Pull { done: false });
+
+        let mut items = plan_sync(&tracker, &backlog, &issues, &options);
+        let now = "2024-01-04T00:00:00Z".parse().unwrap();
+        let outcome = apply_sync(&tracker, &mut backlog, &issues, &mut items, now).await;
+        assert_eq!(
+            outcome,
+            SyncOutcome {
+                changed: true,
+                failure: None
+            }
+        );
+        assert!(matches!(backlog.tasks[1].state, TaskState::Done));
+        assert!(matches!(backlog.tasks[2].state, TaskState::Todo));
+        assert_eq!(backlog.tasks[0].external_refs[0].synced_at, Some(now));
+        assert_eq!(items[4].issue.as_deref(), Some("#4"));
+        assert_eq!(backlog.tasks[4].external_refs[0].id, "#4");
+        assert!(tracker.issues.lock().unwrap()[0].is_closed());
+
+        // Everything agrees now
+        let issues = tracker.list_issues().await.unwrap();
+        let items = plan_sync(&tracker, &backlog, &issues, &SyncOptions::default());
+        assert!(items
+            .iter()
+            .all(|i| matches!(i.action, SyncAction::InSync | SyncAction::MissingIssue)));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::provider::{IssueFields, TrackerIssue, TrackerProvider};
+use async_trait::async_trait;
+use taskai_core::{GitHubClient, GitHubIssue, NewIssue};
+use taskai_schema::{ExternalRef, TaskState};
+
+/// The issues of a GitHub repository, as a tracker. Issue IDs are `owner/name#number`.
+pub struct GitHubProvider {
+    client: GitHubClient,
+    repo: String,
+}
+
+impl GitHubProvider {
+    /// Creates the provider of the issues of `repo` (`owner/name`).
+    pub fn new(client: GitHubClient, repo: &str) -> Self {
+        GitHubProvider {
+            client,
+            repo: repo.to_string(),
+        }
+    }
+
+    fn tracker_issue(&self, issue: GitHubIssue) -> TrackerIssue {
+        TrackerIssue {
+            id: format!("{}#{}", self.repo, issue.number),
+            state: if issue.is_closed() {
+                TaskState::Done
+            } else {
+                TaskState::Todo
+            },
+            title: issue.title,
+            url: Some(issue.html_url),
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+/// Returns the number of an issue from its ID, `path#number`.
+pub(crate) fn issue_number(id: &str) -> Result<u64, String> {
+    id.rsplit_once('#')
+        .and_then(|(_, number)| number.parse().ok())
+        .ok_or_else(|| format!("Invalid issue '{}'.", id))
+}
+
+fn new_issue(fields: &IssueFields) -> NewIssue {
+    NewIssue {
+        title: fields.title.clone(),
+        body: fields.body.clone(),
+        labels: fields.labels.clone(),
+    }
+}
+
+#[async_trait]
+impl TrackerProvider for GitHubProvider {
+    fn system(&self) -> &str {
+        "github"
+    }
+
+    fn target(&self) -> &str {
+        &self.repo
+    }
+
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+        let number = taskai_core::github_ref_issue(external_ref, &self.repo)?;
+        Some(format!("{}#{}", self.repo, number))
+    }
+
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+        let issues = self.client.list_issues(&self.repo).await?;
+        Ok(issues.into_iter().map(|i| self.tracker_issue(i)).collect())
+    }
+
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let issue = self
+            .client
+            .create_issue(&self.repo, &new_issue(fields))
+            .await?;
+        // Issues are created open
+        if matches!(fields.state, TaskState::Done) {
+            let issue = self
+                .client
+                .set_issue_closed(&self.repo, issue.number, true)
+                .await?;
+            return Ok(self.tracker_issue(issue));
+        }
+        Ok(self.tracker_issue(issue))
+    }
+
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let issue = self
+            .client
+            .update_issue(&self.repo, issue_number(id)?, &new_issue(fields))
+            .await?;
+        Ok(self.tracker_issue(issue))
+    }
+
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+        let closed = matches!(state, TaskState::Done);
+        let issue = self
+            .client
+            .set_issue_closed(&self.repo, issue_number(id)?, closed)
+            .await?;
+        Ok(self.tracker_issue(issue))
+    }
+}
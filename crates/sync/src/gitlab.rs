@@ -0,0 +1,117 @@
+use crate::github::issue_number;
+use crate::provider::{IssueFields, TrackerIssue, TrackerProvider};
+use async_trait::async_trait;
+use taskai_core::{GitLabClient, GitLabIssue, NewGitLabIssue};
+use taskai_schema::{Epic, ExternalRef, Task, TaskState};
+
+/// The issues of a GitLab project, as a tracker. Issue IDs are `group/name#iid`, and the parent of
+/// an issue is the milestone of the epic of its task.
+pub struct GitLabProvider {
+    client: GitLabClient,
+    project: String,
+}
+
+impl GitLabProvider {
+    /// Creates the provider of the issues of `project` (`group/name`).
+    pub fn new(client: GitLabClient, project: &str) -> Self {
+        GitLabProvider {
+            client,
+            project: project.to_string(),
+        }
+    }
+
+    fn tracker_issue(&self, issue: GitLabIssue) -> TrackerIssue {
+        TrackerIssue {
+            id: format!("{}#{}", self.project, issue.iid),
+            state: if issue.is_closed() {
+                TaskState::Done
+            } else {
+                TaskState::Todo
+            },
+            title: issue.title,
+            url: Some(issue.web_url),
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+fn new_issue(fields: &IssueFields) -> NewGitLabIssue {
+    NewGitLabIssue {
+        title: fields.title.clone(),
+        description: fields.body.clone(),
+        // Commas separate the labels of the request
+        labels: fields
+            .labels
+            .iter()
+            .map(|label| label.replace(',', " "))
+            .collect::<Vec<_>>()
+            .join(","),
+        milestone_id: fields.parent.as_deref().and_then(|id| id.parse().ok()),
+    }
+}
+
+#[async_trait]
+impl TrackerProvider for GitLabProvider {
+    fn system(&self) -> &str {
+        "gitlab"
+    }
+
+    fn target(&self) -> &str {
+        &self.project
+    }
+
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+        let iid = taskai_core::gitlab_ref_issue(external_ref, &self.project)?;
+        Some(format!("{}#{}", self.project, iid))
+    }
+
+    fn map_task(&self, task: &Task, epic: Option<&Epic>) -> IssueFields {
+        let milestone = epic.and_then(|e| taskai_core::gitlab_milestone_id(e, &self.project));
+        let issue = taskai_core::gitlab_issue_for_task(task, milestone);
+        IssueFields {
+            title: issue.title,
+            body: issue.description,
+            labels: task.tags.clone(),
+            state: task.state.clone(),
+            parent: milestone.map(|id| id.to_string()),
+        }
+    }
+
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+        let issues = self.client.list_issues(&self.project).await?;
+        Ok(issues.into_iter().map(|i| self.tracker_issue(i)).collect())
+    }
+
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let issue = self
+            .client
+            .create_issue(&self.project, &new_issue(fields))
+            .await?;
+        // Issues are created open
+        if matches!(fields.state, TaskState::Done) {
+            let issue = self
+                .client
+                .set_issue_closed(&self.project, issue.iid, true)
+                .await?;
+            return Ok(self.tracker_issue(issue));
+        }
+        Ok(self.tracker_issue(issue))
+    }
+
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let issue = self
+            .client
+            .update_issue(&self.project, issue_number(id)?, &new_issue(fields))
+            .await?;
+        Ok(self.tracker_issue(issue))
+    }
+
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+        let closed = matches!(state, TaskState::Done);
+        let issue = self
+            .client
+            .set_issue_closed(&self.project, issue_number(id)?, closed)
+            .await?;
+        Ok(self.tracker_issue(issue))
+    }
+}
@@ -0,0 +1,123 @@
+use crate::provider::{IssueFields, TrackerIssue, TrackerProvider};
+use async_trait::async_trait;
+use serde_json::json;
+use taskai_core::{JiraClient, JiraConfig, JiraIssue};
+use taskai_schema::{Epic, ExternalRef, Task, TaskState};
+
+/// The issues of a Jira project, as a tracker. Issue IDs are keys such as `PROJ-4`, the parent of
+/// an issue is the Jira epic of its task, and states map to status categories.
+pub struct JiraProvider {
+    client: JiraClient,
+    project: String,
+    config: JiraConfig,
+}
+
+impl JiraProvider {
+    /// Creates the provider of the issues of the project with the key `project`, with the issue
+    /// types and fields of `config`.
+    pub fn new(client: JiraClient, project: &str, config: JiraConfig) -> Self {
+        JiraProvider {
+            client,
+            project: project.to_string(),
+            config,
+        }
+    }
+
+    fn tracker_issue(&self, issue: JiraIssue) -> TrackerIssue {
+        TrackerIssue {
+            state: taskai_core::jira_state(&issue),
+            url: Some(self.client.browse_url(&issue.key)),
+            updated_at: issue.fields.updated.unwrap_or_default(),
+            title: issue.fields.summary,
+            id: issue.key,
+        }
+    }
+}
+
+/// Returns the status category of the tasks in `state`.
+fn category(state: &TaskState) -> &'static str {
+    match state {
+        TaskState::Todo => "new",
+        TaskState::InProgress | TaskState::Blocked => "indeterminate",
+        TaskState::Done => "done",
+    }
+}
+
+#[async_trait]
+impl TrackerProvider for JiraProvider {
+    fn system(&self) -> &str {
+        "jira"
+    }
+
+    fn target(&self) -> &str {
+        &self.project
+    }
+
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+        let prefix = format!("{}-", self.project);
+        (external_ref.system == "jira" && external_ref.id.starts_with(&prefix))
+            .then(|| external_ref.id.clone())
+    }
+
+    /// Maps a task as `export jira` does: a description in Jira markup and labels without spaces.
+    fn map_task(&self, task: &Task, epic: Option<&Epic>) -> IssueFields {
+        let epic_key = epic.and_then(|e| taskai_core::jira_key(&e.external_refs));
+        let fields = taskai_core::jira_task_fields(task, epic_key, &self.project, &self.config);
+        IssueFields {
+            title: task.title.clone(),
+            body: fields["description"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            labels: serde_json::from_value(fields["labels"].clone()).unwrap_or_default(),
+            state: task.state.clone(),
+            parent: epic_key.map(str::to_string),
+        }
+    }
+
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+        let issues = self
+            .client
+            .search_issues(&self.project, &self.config)
+            .await?;
+        Ok(issues.into_iter().map(|i| self.tracker_issue(i)).collect())
+    }
+
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let mut data = json!({
+            "project": { "key": self.project },
+            "summary": fields.title,
+            "description": fields.body,
+            "issuetype": { "name": self.config.task_type.as_deref().unwrap_or("Task") },
+            "labels": fields.labels,
+        });
+        match (&fields.parent, &self.config.fields.epic_link) {
+            (Some(key), Some(field)) => data[field] = json!(key),
+            (Some(key), None) => data["parent"] = json!({ "key": key }),
+            (None, _) => {}
+        }
+        let key = self.client.create_issue(&data).await?;
+        // Issues are created in the initial status of their workflow
+        if !matches!(fields.state, TaskState::Todo) {
+            self.client
+                .transition_to(&key, category(&fields.state))
+                .await?;
+        }
+        Ok(self.tracker_issue(self.client.get_issue(&key).await?))
+    }
+
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let data = json!({
+            "summary": fields.title,
+            "description": fields.body,
+            "labels": fields.labels,
+        });
+        self.client.update_issue(id, &data).await?;
+        Ok(self.tracker_issue(self.client.get_issue(id).await?))
+    }
+
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+        self.client.transition_to(id, category(state)).await?;
+        Ok(self.tracker_issue(self.client.get_issue(id).await?))
+    }
+}
@@ -0,0 +1,27 @@
+//! Two-way sync between a TaskAI backlog and an issue tracker.
+//!
+//! A tracker is plugged in by implementing [`TrackerProvider`] over its API: listing, creating,
+//! updating and closing issues, and mapping tasks to issue fields. [`plan_sync`] then works out
+//! what each task and its issue need, and [`apply_sync`] carries it out on both sides. GitHub,
+//! GitLab, ClickUp, Linear and Jira providers come with the crate.
+
+mod clickup;
+mod engine;
+mod github;
+mod gitlab;
+mod jira;
+mod linear;
+mod provider;
+
+/// The extension point implemented by each tracker, and the issues and fields it exchanges.
+pub use provider::{IssueFields, TrackerIssue, TrackerProvider};
+
+/// Planning and applying a sync between the backlog and a tracker.
+pub use engine::{apply_sync, plan_sync, SyncAction, SyncItem, SyncOptions, SyncOutcome, SyncSide};
+
+/// Providers of the trackers supported out of the box.
+pub use clickup::ClickUpProvider;
+pub use github::GitHubProvider;
+pub use gitlab::GitLabProvider;
+pub use jira::JiraProvider;
+pub use linear::LinearProvider;
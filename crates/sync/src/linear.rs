@@ -0,0 +1,103 @@
+use crate::provider::{IssueFields, TrackerIssue, TrackerProvider};
+use async_trait::async_trait;
+use serde_json::json;
+use taskai_core::{LinearClient, LinearIssue, LinearTeam};
+use taskai_schema::{ExternalRef, TaskState};
+
+/// The issues of a Linear team, as a tracker. Issue IDs are identifiers such as `ENG-12`, and states
+/// map to the workflow states of the team.
+pub struct LinearProvider {
+    client: LinearClient,
+    team: LinearTeam,
+    project_id: Option<String>,
+}
+
+impl LinearProvider {
+    /// Reads the team with the key `team` and creates its provider, putting new issues in the
+    /// project named `project` when given.
+    pub async fn connect(
+        client: LinearClient,
+        team: &str,
+        project: Option<&str>,
+    ) -> Result<Self, String> {
+        let team = client.team(team).await?;
+        let project_id = match project {
+            Some(name) => Some(client.project_id(&team, name).await?),
+            None => None,
+        };
+        Ok(LinearProvider {
+            client,
+            team,
+            project_id,
+        })
+    }
+
+    /// Returns the ID of the workflow state of the team matching `state`.
+    fn state_id(&self, state: &TaskState) -> Result<&str, String> {
+        taskai_core::linear_state_for(state, &self.team.states)
+            .map(|s| s.id.as_str())
+            .ok_or_else(|| {
+                format!(
+                    "No workflow state of {} matches {:?}.",
+                    self.team.key, state
+                )
+            })
+    }
+}
+
+fn tracker_issue(issue: LinearIssue) -> TrackerIssue {
+    TrackerIssue {
+        state: taskai_core::linear_task_state(&issue.state),
+        id: issue.identifier,
+        title: issue.title,
+        url: Some(issue.url),
+        updated_at: issue.updated_at,
+    }
+}
+
+#[async_trait]
+impl TrackerProvider for LinearProvider {
+    fn system(&self) -> &str {
+        "linear"
+    }
+
+    fn target(&self) -> &str {
+        &self.team.key
+    }
+
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String> {
+        let prefix = format!("{}-", self.team.key);
+        (external_ref.system == "linear" && external_ref.id.starts_with(&prefix))
+            .then(|| external_ref.id.clone())
+    }
+
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String> {
+        let issues = self.client.list_issues(&self.team).await?;
+        Ok(issues.into_iter().map(tracker_issue).collect())
+    }
+
+    /// Creates the issue in the team and project; Linear has no labels by name, so labels are left
+    /// out.
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let mut input = json!({
+            "teamId": self.team.id,
+            "title": fields.title,
+            "description": fields.body,
+            "stateId": self.state_id(&fields.state)?,
+        });
+        if let Some(project_id) = &self.project_id {
+            input["projectId"] = json!(project_id);
+        }
+        Ok(tracker_issue(self.client.create_issue(&input).await?))
+    }
+
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String> {
+        let input = json!({ "title": fields.title, "description": fields.body });
+        Ok(tracker_issue(self.client.update_issue(id, &input).await?))
+    }
+
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String> {
+        let input = json!({ "stateId": self.state_id(state)? });
+        Ok(tracker_issue(self.client.update_issue(id, &input).await?))
+    }
+}
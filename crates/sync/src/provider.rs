@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskai_schema::{Epic, ExternalRef, Task, TaskState};
+
+/// An issue of a tracker, reduced to what a sync needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackerIssue {
+    /// ID of the issue as recorded in the references of tasks, such as `acme/app#12` or `ENG-4`.
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// State of a task in the status of the issue; closed issues are Done.
+    pub state: TaskState,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TrackerIssue {
+    /// Tells whether the issue is closed.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state, TaskState::Done)
+    }
+}
+
+/// Fields of the issue tracking a task, as mapped by [`TrackerProvider::map_task`].
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueFields {
+    pub title: String,
+    /// Description of the issue, in the markup of the tracker.
+    pub body: String,
+    pub labels: Vec<String>,
+    pub state: TaskState,
+    /// ID of what tracks the epic of the task in the tracker, such as a parent issue or a
+    /// milestone, when the tracker nests issues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// An issue tracker a backlog can be synchronized with.
+///
+/// A provider wraps the API client of a tracker and the place holding the issues: a repository,
+/// project, list or team. It finds the issues tasks refer to, maps tasks to issue fields, and
+/// reads and writes issues; the sync engine decides what to change on each side. Errors are
+/// messages ready to show.
+#[async_trait]
+pub trait TrackerProvider: Send + Sync {
+    /// Name of the tracker, also the `system` of the references to its issues, such as `github`.
+    fn system(&self) -> &str;
+
+    /// Where the issues live in the tracker, such as `acme/app`, for messages.
+    fn target(&self) -> &str;
+
+    /// Returns the ID of the issue of the target a reference points to, or `None` if it points
+    /// elsewhere.
+    fn issue_id(&self, external_ref: &ExternalRef) -> Option<String>;
+
+    /// Maps a task, and the epic it belongs to, to the fields of its issue.
+    ///
+    /// By default, the issue gets the title of the task, its description and `done_when` criteria
+    /// as a Markdown body, its tags and the title of its epic as labels, and the issue of the epic
+    /// as parent.
+    fn map_task(&self, task: &Task, epic: Option<&Epic>) -> IssueFields {
+        let issue = taskai_core::issue_for_task(task, epic);
+        IssueFields {
+            title: issue.title,
+            body: issue.body,
+            labels: issue.labels,
+            state: task.state.clone(),
+            parent: epic.and_then(|e| e.external_refs.iter().find_map(|r| self.issue_id(r))),
+        }
+    }
+
+    /// Returns the reference recording that a task is tracked by `issue`, the two being in sync at
+    /// `synced_at`.
+    fn issue_ref(&self, issue: &TrackerIssue, synced_at: DateTime<Utc>) -> ExternalRef {
+        ExternalRef {
+            system: self.system().to_string(),
+            id: issue.id.clone(),
+            url: issue.url.clone(),
+            synced_at: Some(synced_at),
+        }
+    }
+
+    /// Lists the open and closed issues of the target.
+    async fn list_issues(&self) -> Result<Vec<TrackerIssue>, String>;
+
+    /// Creates an issue with the given fields, in the status of their state.
+    async fn create_issue(&self, fields: &IssueFields) -> Result<TrackerIssue, String>;
+
+    /// Replaces the title, body and labels of an issue, leaving its status alone.
+    async fn update_issue(&self, id: &str, fields: &IssueFields) -> Result<TrackerIssue, String>;
+
+    /// Closes an issue when `state` is Done and reopens it otherwise, moving it to the status of
+    /// `state` on trackers with richer workflows.
+    async fn close_issue(&self, id: &str, state: &TaskState) -> Result<TrackerIssue, String>;
+}